@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ActivityEntry = { id: number, kind: string, actor: string, source: string | null, targetId: string | null, summary: string, 
+/**
+ * Unix timestamp (ms)
+ */
+createdAt: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActivityKind } from "./ActivityKind";
+
+export type ActivityFilter = { 
+/**
+ * Only these kinds; empty = all
+ */
+kinds: Array<ActivityKind>, actor: string | null, targetId: string | null, limit: number | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ActivityKind = "asset-created" | "asset-edited" | "node-added" | "node-removed" | "agent-ran" | "imported" | "permission-decided" | "injection-suspected";
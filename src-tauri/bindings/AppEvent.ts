@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AssetUpdated } from "./AssetUpdated";
+import type { BrokenLink } from "./BrokenLink";
+import type { BudgetExceeded } from "./BudgetExceeded";
+import type { ClipImport } from "./ClipImport";
+import type { ConnectorProvider } from "./ConnectorProvider";
+import type { DownloadProgress } from "./DownloadProgress";
+import type { FiredReminder } from "./FiredReminder";
+import type { FlaggedAsset } from "./FlaggedAsset";
+import type { ImportProgress } from "./ImportProgress";
+import type { Job } from "./Job";
+import type { JobCompleted } from "./JobCompleted";
+import type { JobProgress } from "./JobProgress";
+import type { LowDiskSpace } from "./LowDiskSpace";
+import type { PermissionRequest } from "./PermissionRequest";
+import type { ProjectActive } from "./ProjectActive";
+import type { ProxyChunk } from "./ProxyChunk";
+import type { RecipeRunRequest } from "./RecipeRunRequest";
+import type { StreamUpdate } from "./StreamUpdate";
+import type { SyncStatus } from "./SyncStatus";
+import type { TaskDispatch } from "./TaskDispatch";
+
+export type AppEvent = { "event": "project:active", "payload": ProjectActive } | { "event": "project:cloud-sync", "payload": SyncStatus } | { "event": "project:sync-conflict", "payload": SyncStatus } | { "event": "assets:broken-links", "payload": Array<BrokenLink> } | { "event": "asset:updated", "payload": AssetUpdated } | { "event": "asset:streaming", "payload": StreamUpdate } | { "event": "clip:imported", "payload": Array<ClipImport> } | { "event": "import:progress", "payload": ImportProgress } | { "event": "download:progress", "payload": DownloadProgress } | { "event": "disk:low-space", "payload": LowDiskSpace } | { "event": "job:task", "payload": TaskDispatch } | { "event": "job:progress", "payload": JobProgress } | { "event": "job:completed", "payload": JobCompleted } | { "event": "job:cancelled", "payload": Job } | { "event": "budget:exceeded", "payload": BudgetExceeded } | { "event": "permission:request", "payload": PermissionRequest } | { "event": "safety:flagged", "payload": Array<FlaggedAsset> } | { "event": "connector:connected", "payload": ConnectorProvider } | { "event": "automation:run-recipe", "payload": RecipeRunRequest } | { "event": "reminder:fired", "payload": FiredReminder } | { "event": "proxy:chunk", "payload": ProxyChunk };
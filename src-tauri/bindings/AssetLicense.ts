@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * License of an asset
+ */
+export type AssetLicense = { 
+/**
+ * e.g. "CC-BY-4.0", "royalty-free", "editorial", "owned"
+ */
+licenseType: string, 
+/**
+ * Credit line required when the asset is used
+ */
+attribution: string | null, 
+/**
+ * What the license doesn't allow, e.g. "no print", "web only"
+ */
+restrictions: string | null, 
+/**
+ * When the rights end (Unix ms); none for perpetual rights
+ */
+expiresAt: number | null, 
+/**
+ * Where the license or asset was obtained
+ */
+sourceUrl: string | null, };
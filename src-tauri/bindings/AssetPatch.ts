@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AssetPatch = { 
+/**
+ * New name; `{n}` is the asset's 1-based position (zero-padded to the
+ * width of the count) and `{name}` its current name
+ */
+name: string | null, 
+/**
+ * Hand-added tags to put on every asset
+ */
+addTags: Array<string>, 
+/**
+ * Tags to take off every asset, whatever added them
+ */
+removeTags: Array<string>, 
+/**
+ * Custom field values by field ID; null or "" clears the field
+ */
+fields: Record<string, string | number | null>, };
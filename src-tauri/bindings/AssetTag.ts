@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A searchable tag on an asset
+ */
+export type AssetTag = { assetId: string, 
+/**
+ * Normalized: lowercase, single spaces
+ */
+tag: string, 
+/**
+ * Who added it: "user", "interrogate", ...
+ */
+source: string, 
+/**
+ * Model confidence (0-1) for machine tags
+ */
+confidence: number | null, createdAt: number, };
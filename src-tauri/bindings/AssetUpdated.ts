@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `asset:updated` event
+ */
+export type AssetUpdated = { assetId: string, 
+/**
+ * Every node showing the asset, original first
+ */
+nodeIds: Array<string>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AssetUsage = { assetId: string, name: string, 
+/**
+ * "image", "text" or "record"
+ */
+kind: string, 
+/**
+ * Nodes showing the asset (originals and references)
+ */
+nodes: number, 
+/**
+ * Uses as a recipe input
+ */
+recipeInputs: number, 
+/**
+ * Size of the files under `assets/` the asset points at
+ */
+bytes: number, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AssetUsage } from "./AssetUsage";
+import type { UsageBucket } from "./UsageBucket";
+
+export type AssetUsageReport = { 
+/**
+ * All assets, most referenced first
+ */
+assets: Array<AssetUsage>, 
+/**
+ * Assets no node shows and no recipe uses
+ */
+unused: Array<string>, mostReferenced: Array<string>, buckets: Array<UsageBucket>, totalBytes: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditExport = { path: string, rows: number, 
+/**
+ * Hash of the last row, to note down and check the file against later
+ */
+lastHash: string, };
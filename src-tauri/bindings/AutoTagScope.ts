@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which images to tag
+ */
+export type AutoTagScope = { "kind": "untagged" } | { "kind": "all" } | { "kind": "assets", assetIds: Array<string>, };
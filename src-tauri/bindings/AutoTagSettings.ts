@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-project auto-tag settings
+ */
+export type AutoTagSettings = { 
+/**
+ * Tags scored below this (0..1) are dropped; unscored tags are kept
+ */
+minConfidence: number, 
+/**
+ * Most tags kept per asset
+ */
+maxTags: number, };
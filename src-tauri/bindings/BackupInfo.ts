@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackupInfo = { 
+/**
+ * Path relative to the backup root; pass to `restore_backup`
+ */
+id: string, size: number, projectName: string, projectPath: string, 
+/**
+ * Unix timestamp (ms)
+ */
+createdAt: number, includesAssets: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackupManifest = { projectName: string, projectPath: string, 
+/**
+ * Unix timestamp (ms)
+ */
+createdAt: number, includesAssets: boolean, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackupSettings = { enabled: boolean, 
+/**
+ * Minimum time between backups of one project
+ */
+intervalHours: number, 
+/**
+ * Archives kept per project
+ */
+retention: number, 
+/**
+ * Also archive `assets/` (can be large)
+ */
+includeAssets: boolean, 
+/**
+ * Backup root; defaults to `<app data>/backups`
+ */
+directory: string | null, };
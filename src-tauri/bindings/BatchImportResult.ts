@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SaveImageResult } from "./SaveImageResult";
+
+/**
+ * Result for a single file in batch import
+ */
+export type BatchImportResult = { 
+/**
+ * Original file path
+ */
+sourcePath: string, 
+/**
+ * Success result (if import succeeded)
+ */
+result: SaveImageResult | null, 
+/**
+ * SHA-256 of the file (if import succeeded)
+ */
+contentHash: string | null, 
+/**
+ * Error message (if import failed)
+ */
+error: string | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BrandIssue } from "./BrandIssue";
+import type { PaletteColor } from "./PaletteColor";
+
+export type BrandComplianceReport = { assetId: string, compliant: boolean, 
+/**
+ * Share of judged image pixels within tolerance of a brand color
+ */
+colorScore: number | null, palette: Array<PaletteColor>, issues: Array<BrandIssue>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-project brand constraints, stored in project settings under `brandKit`
+ */
+export type BrandKit = { 
+/**
+ * Approved colors as hex ("#1a2b3c")
+ */
+colors: Array<string>, fonts: Array<string>, logoAssetIds: Array<string>, 
+/**
+ * Tone of voice guidelines (free text, passed to agents)
+ */
+tone: string | null, 
+/**
+ * Words and phrases that must not appear in copy
+ */
+bannedWords: Array<string>, 
+/**
+ * Max color distance (CIE76 ΔE) to count as on-brand; default 20
+ */
+colorTolerance: number | null, };
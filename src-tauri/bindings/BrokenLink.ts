@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A linked asset file whose original can't be reached
+ */
+export type BrokenLink = { 
+/**
+ * The link, relative to the project root ("assets/<id>.<ext>")
+ */
+src: string, 
+/**
+ * Where the original was when it was linked
+ */
+sourcePath: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetScope } from "./BudgetScope";
+
+/**
+ * Payload of `budget:exceeded`: a job held until confirmed or cancelled
+ */
+export type BudgetExceeded = { jobId: string, kind: string, tasks: number, estimatedCost: number, 
+/**
+ * Project spend before this job
+ */
+spent: number, limit: number, scope: BudgetScope, };
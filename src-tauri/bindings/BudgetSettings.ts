@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-project budget settings; limits are in the same unit as the task costs
+ */
+export type BudgetSettings = { 
+/**
+ * Total spend allowed for the project; none means unlimited
+ */
+projectLimit: number | null, 
+/**
+ * Spend allowed for a single run; none means unlimited
+ */
+runLimit: number | null, 
+/**
+ * Estimated cost of one task by job kind, e.g. "generation-grid"
+ */
+taskCosts: { [key in string]?: number }, 
+/**
+ * Cost of one task of a kind without its own price
+ */
+defaultTaskCost: number, };
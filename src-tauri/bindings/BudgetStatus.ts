@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetSettings } from "./BudgetSettings";
+
+/**
+ * Settings and spend of the open project
+ */
+export type BudgetStatus = { settings: BudgetSettings, spent: number, 
+/**
+ * Left of the project limit, if there is one
+ */
+remaining: number | null, };
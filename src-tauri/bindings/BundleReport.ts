@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a bundle exported or imported
+ */
+export type BundleReport = { 
+/**
+ * The bundle file
+ */
+path: string, 
+/**
+ * Names of the settings included
+ */
+settings: Array<string>, agents: number, components: number, };
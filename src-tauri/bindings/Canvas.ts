@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Viewport } from "./Viewport";
+
+/**
+ * A board within a project; all canvases share the project's assets
+ */
+export type Canvas = { id: string, name: string, sortOrder: number, viewport: Viewport, createdAt: number, };
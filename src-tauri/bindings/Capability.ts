@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What they want to do
+ */
+export type Capability = "create-nodes" | "modify-nodes" | "delete-nodes" | "import-assets" | "run-recipes";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * Result of importing one clip, sent back to the extension and to the frontend
+ */
+export type ClipImport = { asset: Asset, node: SynniaNode, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { SynniaEdge } from "./SynniaEdge";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * A saved subgraph that can be stamped into any project.
+ * Node positions are relative to the component's top-left corner.
+ */
+export type ComponentDefinition = { id: string, name: string, description: string | null, nodes: Array<SynniaNode>, edges: Array<SynniaEdge>, assets: Array<Asset>, createdAt: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { SynniaEdge } from "./SynniaEdge";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * Nodes, edges and assets created by instantiating a component
+ */
+export type ComponentInstance = { componentId: string, nodes: Array<SynniaNode>, edges: Array<SynniaEdge>, assets: Array<Asset>, };
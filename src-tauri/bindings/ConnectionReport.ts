@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of `test_ai_connection`
+ */
+export type ConnectionReport = { provider: string, ok: boolean, status: number | null, latencyMs: number, modelCount: number | null, error: string | null, };
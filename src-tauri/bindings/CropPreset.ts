@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A crop target: a ratio such as `"1:1"`, `"9:16"` or `"16:9"` (output short
+ * side 1080px), or an exact custom size.
+ */
+export type CropPreset = string | { width: number, height: number, name: string | null, };
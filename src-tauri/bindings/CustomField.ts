@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FieldKind } from "./FieldKind";
+
+export type CustomField = { id: string, name: string, kind: FieldKind, 
+/**
+ * Choices of a select field
+ */
+options: Array<string>, 
+/**
+ * Display order
+ */
+position: number, createdAt: number, };
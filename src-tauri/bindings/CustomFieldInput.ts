@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FieldKind } from "./FieldKind";
+
+/**
+ * A field to create (no `id`) or update
+ */
+export type CustomFieldInput = { id: string | null, name: string, kind: FieldKind, options: Array<string>, };
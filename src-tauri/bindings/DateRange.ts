@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Inclusive range of Unix timestamps in milliseconds; either end may be open
+ */
+export type DateRange = { from: number | null, to: number | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What `delete_project` removed, or would remove in a dry run
+ */
+export type DeleteProjectReport = { path: string, 
+/**
+ * Files in the project folder (linked originals are not touched)
+ */
+files: number, bytes: number, 
+/**
+ * The project was open and got closed
+ */
+closesActive: boolean, 
+/**
+ * Nothing was changed
+ */
+dryRun: boolean, };
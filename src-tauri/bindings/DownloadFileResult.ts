@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response from download_asset_file command
+ */
+export type DownloadFileResult = { 
+/**
+ * Relative path to the saved file (e.g., "assets/xxx.mp4")
+ */
+relativePath: string, 
+/**
+ * File size in bytes
+ */
+size: number, contentType: string | null, };
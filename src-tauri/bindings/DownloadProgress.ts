@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `download:progress` event
+ */
+export type DownloadProgress = { downloadId: string, received: number, total: number | null, attempt: number, };
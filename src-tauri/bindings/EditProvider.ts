@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Providers with an image edit API, named as in the AI config
+ */
+export type EditProvider = "openai" | "comfyui";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An asset that couldn't be sent
+ */
+export type ExportFailure = { assetId: string, error: string, };
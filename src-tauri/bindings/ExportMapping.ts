@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where each part of an asset goes, by property or column name
+ */
+export type ExportMapping = { 
+/**
+ * Receives the asset name (in Notion, usually the title property)
+ */
+title: string, 
+/**
+ * Receives the text of text assets
+ */
+text: string | null, 
+/**
+ * Receives the image of image assets (a files / attachment property)
+ */
+image: string | null, 
+/**
+ * Custom field ID → property name
+ */
+fields: { [key in string]?: string }, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ExportedFile = { 
+/**
+ * Preset label ("1x1", "9x16", custom name or "WxH")
+ */
+preset: string, 
+/**
+ * Path relative to the project root
+ */
+path: string, width: number, height: number, 
+/**
+ * The source region was smaller than the output and had to be enlarged
+ */
+upscaled: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An asset that was sent
+ */
+export type ExportedRecord = { assetId: string, 
+/**
+ * Notion page or Airtable record ID
+ */
+recordId: string, url: string | null, };
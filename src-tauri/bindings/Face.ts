@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stored face of an image file
+ */
+export type Face = { id: string, src: string, box: [number, number, number, number], confidence: number, };
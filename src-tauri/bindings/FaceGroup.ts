@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Face } from "./Face";
+
+/**
+ * Faces of one person and the assets showing them
+ */
+export type FaceGroup = { faces: Array<Face>, assetIds: Array<string>, };
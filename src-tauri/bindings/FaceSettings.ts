@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-project face settings
+ */
+export type FaceSettings = { 
+/**
+ * Detect faces in images as they are imported
+ */
+detectOnImport: boolean, 
+/**
+ * Faces whose embeddings' cosine similarity reaches this are the same person
+ */
+matchThreshold: number, 
+/**
+ * Detections below this confidence are ignored
+ */
+minConfidence: number, };
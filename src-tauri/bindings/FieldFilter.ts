@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FilterOp } from "./FilterOp";
+
+/**
+ * One condition on a custom field; filters in a search all have to match
+ */
+export type FieldFilter = { fieldId: string, op: FilterOp, value: string | number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FieldTarget } from "./FieldTarget";
+
+export type FieldValue = { fieldId: string, target: FieldTarget, targetId: string, value: string | number, updatedAt: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An earlier file of an asset
+ */
+export type FileVersion = { id: number, assetId: string, contentHash: string, 
+/**
+ * Where the file was used from before it was replaced
+ */
+src: string, 
+/**
+ * The kept copy, relative to the project root
+ */
+path: string, size: number, createdAt: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Reminder } from "./Reminder";
+
+/**
+ * Payload of the `reminder:fired` event
+ */
+export type FiredReminder = { reminder: Reminder, 
+/**
+ * Notification title: the node's title, if it still exists
+ */
+title: string, };
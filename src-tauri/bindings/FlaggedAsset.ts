@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReviewStatus } from "./ReviewStatus";
+
+/**
+ * An asset waiting for (or given) a review
+ */
+export type FlaggedAsset = { assetId: string, name: string, src: string, score: number, label: string | null, status: ReviewStatus, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FlattenReport = { historyRemoved: number, 
+/**
+ * Leftover rows of deleted assets and nodes
+ */
+trashRemoved: number, filesRemoved: Array<string>, bytesFreed: number, dbSizeBefore: number, dbSizeAfter: number, 
+/**
+ * Nothing was changed; the counts are what a real run would remove
+ */
+dryRun: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+
+export type FolderImport = { rootGroupId: string, 
+/**
+ * Group nodes created, the root included
+ */
+groups: number, 
+/**
+ * File nodes created
+ */
+nodes: number, assets: Array<Asset>, 
+/**
+ * Files that were unsupported or could not be read
+ */
+skipped: Array<string>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GeneratedProject = { path: string, 
+/**
+ * Content nodes, groups not included
+ */
+nodes: number, groups: number, edges: number, assets: number, 
+/**
+ * Database and asset files on disk
+ */
+bytes: number, };
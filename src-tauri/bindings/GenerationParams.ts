@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Parameters used to produce a generated asset (seed, model, prompt...)
+ * Provider-specific settings go into `extra`.
+ */
+export type GenerationParams = { seed: number | null, provider: string | null, model: string | null, prompt: string | null, negativePrompt: string | null, width: number | null, height: number | null, steps: number | null, guidance: number | null, extra: Record<string, any>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GenerationParams } from "./GenerationParams";
+
+/**
+ * Stored generation parameters for an asset, linked to the asset it varies
+ */
+export type GenerationRecord = { assetId: string, recipeId: string | null, parentAssetId: string | null, params: GenerationParams, createdAt: number, };
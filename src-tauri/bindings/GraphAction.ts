@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GraphAction = { "action": "create_node", "params": { type: string, label: string, description: string, } } | { "action": "message", "params": { text: string, } };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DegreeCount } from "./DegreeCount";
+import type { GraphWarning } from "./GraphWarning";
+import type { HotSpot } from "./HotSpot";
+
+export type GraphAnalysis = { nodeCount: number, edgeCount: number, 
+/**
+ * Edges / possible directed edges
+ */
+density: number, 
+/**
+ * Number of nodes per total degree, ascending by degree
+ */
+degreeDistribution: Array<DegreeCount>, 
+/**
+ * Sizes of connected components (edges taken as undirected), largest first
+ */
+componentSizes: Array<number>, 
+/**
+ * Nodes without any edge
+ */
+isolatedNodes: number, 
+/**
+ * Strongly connected node sets that contain a recipe node
+ */
+recipeCycles: Array<Array<string>>, hotSpots: Array<HotSpot>, warnings: Array<GraphWarning>, };
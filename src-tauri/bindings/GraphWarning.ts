@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Severity } from "./Severity";
+
+export type GraphWarning = { 
+/**
+ * "recipe-fan-in", "hub-node", "recipe-cycle" or "dense-area"
+ */
+kind: string, severity: Severity, message: string, nodeIds: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One dimension of the parameter matrix
+ */
+export type GridAxis = { 
+/**
+ * Recipe input key to vary (e.g. "prompt", "style")
+ */
+key: string, values: any[], };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskListProgress } from "./TaskListProgress";
+
+export type GroupProgress = { groupNodeId: string, done: number, total: number, 
+/**
+ * 0..100, `None` when the group has no tasks
+ */
+percent: number | null, lists: Array<TaskListProgress>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * History entry for frontend
+ */
+export type HistoryEntry = { id: number, assetId: string, contentHash: string, contentPreview: string, createdAt: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Time window to load (Unix ms, both ends optional)
+ */
+export type HistoryTimelineRange = { since: number | null, until: number | null, limit: number | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A crowded board cell
+ */
+export type HotSpot = { 
+/**
+ * Top-left corner of the cell in board coordinates
+ */
+x: number, y: number, size: number, nodeIds: Array<string>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Frame count and timing of an animated GIF/WebP, kept in the asset's
+ * `valueMeta.animation`
+ */
+export type ImageAnimation = { frameCount: number, 
+/**
+ * Length of one loop
+ */
+durationMs: number, 
+/**
+ * Short looping preview (e.g. "assets/preview_xxx.gif")
+ */
+preview: string | null, };
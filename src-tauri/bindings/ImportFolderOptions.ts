@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Position } from "./Position";
+
+export type ImportFolderOptions = { 
+/**
+ * Descend into subfolders
+ */
+recursive: boolean, 
+/**
+ * Deepest subfolder level to import (the picked folder is 0)
+ */
+maxDepth: number | null, 
+/**
+ * Grid columns inside each group
+ */
+columns: number, 
+/**
+ * Canvas position of the root group; defaults to right of existing content
+ */
+origin: Position | null, 
+/**
+ * Import dot-files and dot-folders
+ */
+includeHidden: boolean, 
+/**
+ * Link images where they are instead of copying them into the project
+ */
+link: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `import:progress` event
+ */
+export type ImportProgress = { current: number, total: number, file: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One connection feeding a recipe input field
+ */
+export type InputMapping = { 
+/**
+ * Input field key
+ */
+field: string, sourceNodeId: string, sourceHandle: string | null, 
+/**
+ * Handle on the recipe node the edge connects to
+ */
+targetHandle: string | null, };
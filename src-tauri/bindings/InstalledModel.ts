@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelFormat } from "./ModelFormat";
+import type { ModelKind } from "./ModelKind";
+
+/**
+ * An installed model
+ */
+export type InstalledModel = { id: string, name: string, kind: ModelKind, format: ModelFormat, 
+/**
+ * File, relative to the models folder
+ */
+file: string, size: number, sha256: string, sourceUrl: string, installedAt: number, };
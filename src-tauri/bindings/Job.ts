@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JobTask } from "./JobTask";
+
+export type Job = { id: string, 
+/**
+ * e.g. "generation-grid"
+ */
+kind: string, 
+/**
+ * Kind-specific data needed by the finalizer
+ */
+payload: any, concurrency: number, tasks: Array<JobTask>, createdAt: number, 
+/**
+ * Waiting for confirmation; no tasks are dispatched meanwhile
+ */
+held: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Job } from "./Job";
+
+/**
+ * Payload of `job:completed`
+ */
+export type JobCompleted = { job: Job, 
+/**
+ * What the job's finalizer produced, or `{ error }` if it failed
+ */
+output: any, };
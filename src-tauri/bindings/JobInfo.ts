@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Returned when a job is submitted
+ */
+export type JobInfo = { jobId: string, total: number, 
+/**
+ * Held over budget until `confirm_job_budget`
+ */
+held: boolean, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskStatus } from "./TaskStatus";
+
+export type JobTask = { index: number, params: any, status: TaskStatus, result: any, error: string | null, };
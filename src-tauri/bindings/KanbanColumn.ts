@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlanningItem } from "./PlanningItem";
+
+export type KanbanColumn = { 
+/**
+ * Status or parent node ID; `None` for items without one
+ */
+key: string | null, title: string, items: Array<PlanningItem>, };
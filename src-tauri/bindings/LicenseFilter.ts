@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LicenseStatus } from "./LicenseStatus";
+
+/**
+ * License conditions for asset searches
+ */
+export type LicenseFilter = { 
+/**
+ * License types to match (case-insensitive); empty for any
+ */
+types: Array<string>, 
+/**
+ * Rights in one of these states; empty for any
+ */
+statuses: Array<LicenseStatus>, 
+/**
+ * Window for `expiring`, in days
+ */
+expiringDays: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LicenseReportItem } from "./LicenseReportItem";
+
+export type LicenseReport = { generatedAt: number, expiringDays: number, 
+/**
+ * Unknown, expired and expiring rights first, soonest expiry first
+ */
+items: Array<LicenseReportItem>, unknown: number, expired: number, expiring: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AssetLicense } from "./AssetLicense";
+import type { LicenseStatus } from "./LicenseStatus";
+
+export type LicenseReportItem = { assetId: string, name: string, 
+/**
+ * Where the asset came from (`sys.source`)
+ */
+source: string, license: AssetLicense | null, status: LicenseStatus, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * State of an asset's rights at a given time
+ */
+export type LicenseStatus = "unknown" | "expired" | "expiring" | "valid";
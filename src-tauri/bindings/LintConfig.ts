@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LintConfig = { emptyNodes: boolean, missingAltText: boolean, 
+/**
+ * Minimum image size in pixels (shorter side); `None` disables the rule
+ */
+minImageSize: number | null, 
+/**
+ * Platform whose character limit applies to all text, unless a text
+ * asset sets its own `config.extra.platform`
+ */
+platform: string | null, 
+/**
+ * Fallback limit when no known platform applies
+ */
+maxTextLength: number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Severity } from "./Severity";
+
+export type LintFinding = { rule: string, severity: Severity, message: string, nodeId: string | null, assetId: string | null, };
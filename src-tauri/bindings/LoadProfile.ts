@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StageTiming } from "./StageTiming";
+
+export type LoadProfile = { 
+/**
+ * Stages in the order they ran
+ */
+stages: Array<StageTiming>, totalMs: number, 
+/**
+ * Database file size, WAL included
+ */
+dbBytes: number, nodes: number, edges: number, assets: number, 
+/**
+ * Image assets whose thumbnail file is missing and would be regenerated
+ */
+missingThumbnails: number, };
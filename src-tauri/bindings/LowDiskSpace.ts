@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `disk:low-space` event
+ */
+export type LowDiskSpace = { 
+/**
+ * Folder being written to
+ */
+path: string, 
+/**
+ * Bytes about to be written
+ */
+required: number, 
+/**
+ * Bytes free before the write
+ */
+available: number, };
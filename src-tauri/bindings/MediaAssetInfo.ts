@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageAnimation } from "./ImageAnimation";
+
+/**
+ * Info for a media asset (for library view)
+ */
+export type MediaAssetInfo = { id: string, assetType: string, name: string, content: string, thumbnailPath: string | null, width: number | null, height: number | null, 
+/**
+ * Frame count, duration and looping preview of animated GIF/WebP
+ */
+animation: ImageAnimation | null, createdAt: number, updatedAt: number, };
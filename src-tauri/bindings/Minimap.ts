@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Bounds } from "./Bounds";
+
+export type Minimap = { 
+/**
+ * PNG as a data URL
+ */
+image: string, width: number, height: number, 
+/**
+ * Board area shown, in board coordinates
+ */
+bounds: Bounds, 
+/**
+ * Minimap pixels per board unit
+ */
+scale: number, };
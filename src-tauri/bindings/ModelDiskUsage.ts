@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelKindUsage } from "./ModelKindUsage";
+
+/**
+ * Disk used by installed models
+ */
+export type ModelDiskUsage = { 
+/**
+ * Models folder
+ */
+path: string, total: number, 
+/**
+ * Kinds with at least one model
+ */
+kinds: Array<ModelKindUsage>, 
+/**
+ * Free space on the models' disk, if known
+ */
+available: number | null, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelKind } from "./ModelKind";
+
+/**
+ * A model to download
+ */
+export type ModelDownload = { kind: ModelKind, 
+/**
+ * Shown in the model list; defaults to the file name
+ */
+name: string | null, 
+/**
+ * `.onnx` or `.gguf` file over http(s)
+ */
+url: string, 
+/**
+ * Expected SHA-256 of the file, hex
+ */
+sha256: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A model offered by a provider
+ */
+export type ModelInfo = { id: string, name: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a model is used for
+ */
+export type ModelKind = "background-removal" | "embeddings" | "whisper" | "upscale" | "face-detection" | "depth" | "segmentation";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelKind } from "./ModelKind";
+
+/**
+ * Disk used by the models of one kind
+ */
+export type ModelKindUsage = { kind: ModelKind, count: number, size: number, };
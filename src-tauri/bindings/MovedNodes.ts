@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MovedNodes = { 
+/**
+ * Nodes moved, including children of moved groups
+ */
+nodes: number, 
+/**
+ * Edges moved along with both of their ends
+ */
+edges: number, 
+/**
+ * Edges dropped because they would have crossed canvases
+ */
+edgesRemoved: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Timeouts for outbound requests, in seconds
+ */
+export type NetworkTimeouts = { 
+/**
+ * Establishing a connection
+ */
+connectSecs: number, 
+/**
+ * A whole request with a bounded response (agent runs, proxy calls)
+ */
+requestSecs: number, 
+/**
+ * Silence between chunks of a streamed body (downloads, streams)
+ */
+idleSecs: number, };
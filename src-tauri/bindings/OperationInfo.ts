@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationKind } from "./OperationKind";
+
+/**
+ * A registered operation, as listed to the frontend
+ */
+export type OperationInfo = { id: string, kind: OperationKind, label: string, startedAt: number, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrgConfig = { 
+/**
+ * Where the layer was read from; none when there is no layer
+ */
+source: string | null, 
+/**
+ * Provider keys (as in the app settings) the studio allows; none allows all
+ */
+approvedProviders: Array<string> | null, 
+/**
+ * Hosts no request may reach, even when allowlisted. `*.example.com`
+ * matches subdomains.
+ */
+blockedDomains: Array<string>, 
+/**
+ * Workspace for users who haven't chosen one
+ */
+defaultWorkspace: string | null, };
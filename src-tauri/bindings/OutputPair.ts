@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLine } from "./DiffLine";
+import type { FieldChange } from "./FieldChange";
+import type { RunOutput } from "./RunOutput";
+
+export type OutputPair = { nodeId: string, 
+/**
+ * Missing if the node didn't finish in that run
+ */
+a: RunOutput | null, b: RunOutput | null, changed: boolean, 
+/**
+ * For text outputs
+ */
+textDiff: Array<DiffLine>, 
+/**
+ * For record outputs, top-level fields
+ */
+fieldChanges: Array<FieldChange>, };
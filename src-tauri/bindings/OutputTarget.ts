@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the recipe's output goes
+ */
+export type OutputTarget = { 
+/**
+ * "form", "selector", "gallery", "table" or "text"
+ */
+node: string, title: string | null, 
+/**
+ * Existing node to write into instead of creating one
+ */
+targetNodeId: string | null, 
+/**
+ * Node-specific config, copied to the output asset's `config.extra`
+ */
+extra: Record<string, any>, };
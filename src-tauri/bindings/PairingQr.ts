@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the desktop shows to pair a phone
+ */
+export type PairingQr = { 
+/**
+ * Address of the capture page, token included
+ */
+url: string, 
+/**
+ * QR code of `url` as an SVG document
+ */
+svg: string, };
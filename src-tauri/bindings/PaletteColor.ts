@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One dominant color of an image
+ */
+export type PaletteColor = { hex: string, 
+/**
+ * Fraction of pixels (0..1)
+ */
+share: number, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PageSize } from "./PageSize";
+
+export type PdfExportOptions = { 
+/**
+ * Nodes to include (with everything nested inside); empty for the whole board
+ */
+nodeIds: Array<string>, 
+/**
+ * Document title, defaults to the project name
+ */
+title: string | null, pageSize: PageSize, landscape: boolean, columns: number, 
+/**
+ * Include text assets as note cards
+ */
+includeNotes: boolean, 
+/**
+ * Custom field IDs to add to image captions, node values before asset ones
+ */
+fields: Array<string>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PermissionRule } from "./PermissionRule";
+
+/**
+ * Per-project permission policy; pairs without a rule use the capability's default
+ */
+export type PermissionPolicy = { rules: Array<PermissionRule>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actor } from "./Actor";
+import type { Capability } from "./Capability";
+
+/**
+ * A prompt shown to the user
+ */
+export type PermissionRequest = { id: string, actor: Actor, capability: Capability, 
+/**
+ * What will happen, e.g. "Delete 3 nodes"
+ */
+summary: string, targetIds: Array<string>, createdAt: number, };
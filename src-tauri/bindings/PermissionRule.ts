@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actor } from "./Actor";
+import type { Capability } from "./Capability";
+import type { Decision } from "./Decision";
+
+/**
+ * One policy entry
+ */
+export type PermissionRule = { actor: Actor, capability: Capability, decision: Decision, };
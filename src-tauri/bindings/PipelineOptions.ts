@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a run is scheduled
+ */
+export type PipelineOptions = { 
+/**
+ * Nodes running at once, 1 (the default) to 8
+ */
+concurrency: number | null, 
+/**
+ * Provider of each node by node ID, e.g. "gemini"
+ */
+providers: { [key in string]?: string }, 
+/**
+ * Nodes running at once per provider
+ */
+providerLimits: { [key in string]?: number }, };
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStep } from "./PipelineStep";
+import type { TaskStatus } from "./TaskStatus";
+
+export type PipelineRun = { id: string, 
+/**
+ * Derived from the steps: running while anything runs or can start
+ */
+status: TaskStatus, 
+/**
+ * In execution order
+ */
+steps: Array<PipelineStep>, concurrency: number, providerLimits: { [key in string]?: number }, 
+/**
+ * Rate-limited providers and when they may be used again
+ */
+pausedUntil: Record<string, number>, 
+/**
+ * Nodes to start now; report them as running before starting them
+ */
+readyNodeIds: Array<string>, 
+/**
+ * When paused nodes may start, if that's all that's left
+ */
+resumeAt: number | null, createdAt: number, updatedAt: number, };
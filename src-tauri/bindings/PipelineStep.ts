@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskStatus } from "./TaskStatus";
+
+export type PipelineStep = { nodeId: string, status: TaskStatus, 
+/**
+ * Nodes of the run feeding this one
+ */
+dependsOn: Array<string>, provider: string | null, 
+/**
+ * Checkpoint: the asset the node produced
+ */
+outputAssetId: string | null, error: string | null, startedAt: number | null, finishedAt: number | null, };
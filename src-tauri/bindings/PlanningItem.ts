@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PlanningItem = { nodeId: string, nodeType: string, title: string, assetId: string | null, parentId: string | null, startDate: string | null, dueDate: string | null, status: string | null, };
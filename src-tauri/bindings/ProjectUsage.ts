@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UsedNode } from "./UsedNode";
+
+export type ProjectUsage = { name: string, path: string, nodes: Array<UsedNode>, 
+/**
+ * Generations made with the recipe, or runs of the agent
+ */
+runs: number, lastUsedAt: number | null, };
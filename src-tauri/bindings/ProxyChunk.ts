@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `proxy:chunk` event emitted by `proxy_request_stream`
+ */
+export type ProxyChunk = { requestId: string, chunk: string, bodyEncoding: string, };
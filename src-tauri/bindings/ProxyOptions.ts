@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResponseMode } from "./ResponseMode";
+
+/**
+ * Optional request settings
+ */
+export type ProxyOptions = { 
+/**
+ * Total request timeout in milliseconds
+ */
+timeoutMs: number | null, 
+/**
+ * Connection timeout in milliseconds
+ */
+connectTimeoutMs: number | null, responseMode: ResponseMode, };
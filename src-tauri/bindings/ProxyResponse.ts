@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ProxyResponse = { status: number, headers: { [key in string]?: string }, body: string, 
+/**
+ * "text" or "base64"
+ */
+bodyEncoding: string, filePath: string | null, };
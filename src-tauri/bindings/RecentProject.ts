@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RecentProject = { name: string, path: string, last_opened: string, project_id: string | null, aliases: Array<string>, ambiguous_with: string | null, };
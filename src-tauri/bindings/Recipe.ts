@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InputMapping } from "./InputMapping";
+import type { OutputTarget } from "./OutputTarget";
+
+export type Recipe = { id: string, name: string, agentId: string | null, inputs: Array<InputMapping>, output: OutputTarget, createdAt: number, updatedAt: number, };
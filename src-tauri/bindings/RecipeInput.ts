@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InputMapping } from "./InputMapping";
+import type { OutputTarget } from "./OutputTarget";
+
+/**
+ * A recipe to save; without `id` a new one is created
+ */
+export type RecipeInput = { id: string | null, name: string, agentId: string | null, inputs: Array<InputMapping>, output: OutputTarget, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The prompt a recipe node would send, with rough token counts
+ */
+export type RecipePreview = { recipeId: string, agentName: string, 
+/**
+ * Resolved inputs by field; fields fed by several sources get a list
+ */
+inputs: Record<string, any>, 
+/**
+ * Fields whose source node or asset is gone
+ */
+missingInputs: Array<string>, systemInstruction: string, userMessage: string, systemTokens: number, userTokens: number, totalTokens: number, };
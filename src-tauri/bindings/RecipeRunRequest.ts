@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `automation:run-recipe`
+ */
+export type RecipeRunRequest = { recipeNodeId: string, recipeId: string, };
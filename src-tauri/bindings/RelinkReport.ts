@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BrokenLink } from "./BrokenLink";
+
+/**
+ * Outcome of `relink`
+ */
+export type RelinkReport = { 
+/**
+ * Links now pointing at the new location
+ */
+relinked: number, 
+/**
+ * Links under the old prefix whose file isn't at the new location;
+ * these are left unchanged
+ */
+missing: Array<BrokenLink>, };
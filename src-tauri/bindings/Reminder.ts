@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A scheduled desktop notification for a node
+ */
+export type Reminder = { id: string, nodeId: string, 
+/**
+ * Unix timestamp (ms)
+ */
+fireAt: number, message: string, fired: boolean, createdAt: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file or folder on a remote drive
+ */
+export type RemoteFile = { 
+/**
+ * Provider's ID, for listing a folder or importing a file
+ */
+id: string, name: string, isFolder: boolean, size: number | null, 
+/**
+ * RFC 3339
+ */
+modifiedAt: string | null, mimeType: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * Asset and node made by `import`
+ */
+export type RemoteImport = { asset: Asset, node: SynniaNode, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+
+export type RenamedAsset = { asset: Asset, 
+/**
+ * New path of the asset's file, relative to the project root, if it moved
+ */
+src: string | null, 
+/**
+ * Files moved, derivatives included
+ */
+files: number, 
+/**
+ * Nodes whose title changed with the name
+ */
+nodeIds: Array<string>, 
+/**
+ * Other assets whose values pointed at the moved files
+ */
+updatedAssetIds: Array<string>, };
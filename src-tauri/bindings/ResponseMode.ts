@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the response body is returned to the frontend
+ */
+export type ResponseMode = "text" | "base64" | "file";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of restoring a history version
+ */
+export type RestoreResult = { 
+/**
+ * The restored value
+ */
+value: any, 
+/**
+ * Hash of the content that was replaced (now in history)
+ */
+previousHash: string, 
+/**
+ * Hash of the restored content
+ */
+restoredHash: string, };
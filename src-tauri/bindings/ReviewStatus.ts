@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Review state of a checked image
+ */
+export type ReviewStatus = "clear" | "flagged" | "quarantined" | "approved" | "rejected";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputPair } from "./OutputPair";
+
+export type RunComparison = { runA: string, runB: string, 
+/**
+ * In run B's execution order, then nodes only run A has
+ */
+pairs: Array<OutputPair>, 
+/**
+ * Pairs that differ
+ */
+changed: number, };
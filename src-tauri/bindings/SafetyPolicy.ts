@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens to an image the classifier flags
+ */
+export type SafetyPolicy = "off" | "flag" | "quarantine";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SafetyPolicy } from "./SafetyPolicy";
+
+/**
+ * Per-project safety settings
+ */
+export type SafetySettings = { policy: SafetyPolicy, 
+/**
+ * Classifier scores (0..1) at or above this are flagged
+ */
+threshold: number, };
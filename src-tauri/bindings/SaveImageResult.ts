@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageAnimation } from "./ImageAnimation";
+
+/**
+ * Response from save_image_file command
+ */
+export type SaveImageResult = { 
+/**
+ * Relative path to the saved image (e.g., "assets/xxx.png")
+ */
+relativePath: string, 
+/**
+ * Relative path to the thumbnail (e.g., "assets/thumb_xxx.jpg")
+ */
+thumbnailPath: string | null, 
+/**
+ * Image width
+ */
+width: number, 
+/**
+ * Image height
+ */
+height: number, 
+/**
+ * Frame count, duration and looping preview (animated GIF/WebP only)
+ */
+animation: ImageAnimation | null, 
+/**
+ * Name of the file as the user had it, for the asset title
+ */
+originalName: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportFolderOptions } from "./ImportFolderOptions";
+import type { Position } from "./Position";
+
+/**
+ * One step of a script
+ */
+export type ScriptStep = { "command": "create-node", nodeType: string, title: string, position: Position | null, parentId: string | null, content: string | null, } | { "command": "import-file", path: string, position: Position | null, parentId: string | null, } | { "command": "import-folder", path: string, options: ImportFolderOptions | null, } | { "command": "run-agent", agentId: string, inputs: any, contextNodeId: string | null, } | { "command": "export-subgraph", nodeIds: Array<string>, dest: string, };
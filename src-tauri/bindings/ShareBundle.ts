@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ShareBundle = { id: string, title: string, 
+/**
+ * Bundle folder, relative to the project root
+ */
+path: string, 
+/**
+ * Zip of the folder, relative to the project root
+ */
+archive: string, 
+/**
+ * Items on the page
+ */
+items: number, createdAt: number, 
+/**
+ * When the bundle expires (Unix ms); none if it doesn't
+ */
+expiresAt: number | null, };
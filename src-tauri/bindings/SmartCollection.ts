@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SmartQuery } from "./SmartQuery";
+
+export type SmartCollection = { id: string, name: string, query: SmartQuery, createdAt: number, updatedAt: number, };
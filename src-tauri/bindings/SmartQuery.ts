@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DateRange } from "./DateRange";
+import type { FieldFilter } from "./FieldFilter";
+import type { LicenseFilter } from "./LicenseFilter";
+
+export type SmartQuery = { 
+/**
+ * "image", "text" or "record"; empty for any
+ */
+kinds: Array<string>, 
+/**
+ * Comma-separated tag prefixes, e.g. "hero, night"
+ */
+tags: string, fields: Array<FieldFilter>, created: DateRange | null, updated: DateRange | null, license: LicenseFilter | null, 
+/**
+ * Words that all have to appear in the asset name or text, ignoring case
+ */
+text: string, };
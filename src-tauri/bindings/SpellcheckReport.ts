@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TextIssue } from "./TextIssue";
+
+/**
+ * Result of checking one text asset
+ */
+export type SpellcheckReport = { assetId: string, lang: string, 
+/**
+ * False when no dictionary is installed for `lang` (grammar checks only)
+ */
+hasDictionary: boolean, issues: Array<TextIssue>, };
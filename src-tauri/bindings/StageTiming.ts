@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long one stage of the load took
+ */
+export type StageTiming = { 
+/**
+ * `db_open`, `meta`, `nodes`, `edges`, `assets`, `settings` or `thumbnails`
+ */
+stage: string, ms: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a step made, reported as its task result
+ */
+export type StepOutput = { nodeIds: Array<string>, assetIds: Array<string>, 
+/**
+ * Folder written outside the project (exports)
+ */
+path: string | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { AssetLicense } from "./AssetLicense";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * Asset and node made by `import`
+ */
+export type StockImport = { asset: Asset, node: SynniaNode, license: AssetLicense, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StockProvider } from "./StockProvider";
+
+/**
+ * A photo found by `search`
+ */
+export type StockResult = { 
+/**
+ * `<provider>:<photo id>`, for `import_stock_result`
+ */
+id: string, provider: StockProvider, description: string | null, width: number, height: number, thumbnailUrl: string, previewUrl: string, 
+/**
+ * Photographer
+ */
+author: string, authorUrl: string | null, 
+/**
+ * The photo's page on the provider's site
+ */
+pageUrl: string, };
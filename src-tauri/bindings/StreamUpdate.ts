@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `asset:streaming` events
+ */
+export type StreamUpdate = { assetId: string, content: string, done: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SubgraphExport = { 
+/**
+ * Root of the new project
+ */
+path: string, nodes: number, edges: number, assets: number, files: number, };
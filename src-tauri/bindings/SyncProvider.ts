@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SyncProvider = "dropbox" | "one-drive" | "i-cloud" | "google-drive" | "box";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SyncProvider } from "./SyncProvider";
+
+export type SyncStatus = { provider: SyncProvider | null, 
+/**
+ * Database opened without WAL
+ */
+safeMode: boolean, 
+/**
+ * Conflicted copies of the database found in the project folder
+ */
+conflicts: Array<string>, };
@@ -4,4 +4,4 @@ import type { Graph } from "./Graph";
 import type { ProjectMeta } from "./ProjectMeta";
 import type { Viewport } from "./Viewport";
 
-export type SynniaProject = { version: string, meta: ProjectMeta, viewport: Viewport, graph: Graph, assets: Record<string, Asset>, settings: Record<string, any>, };
+export type SynniaProject = { version: string, meta: ProjectMeta, viewport: Viewport, graph: Graph, assets: Record<string, Asset>, settings: Record<string, any>, canvasId: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A task handed to the frontend for execution (`job:task` event payload)
+ */
+export type TaskDispatch = { jobId: string, kind: string, taskIndex: number, params: any, };
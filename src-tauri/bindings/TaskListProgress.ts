@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Completion of one task list on the board
+ */
+export type TaskListProgress = { nodeId: string, assetId: string, title: string, done: number, total: number, };
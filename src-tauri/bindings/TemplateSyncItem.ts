@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TemplateKind } from "./TemplateKind";
+import type { TemplateSyncStatus } from "./TemplateSyncStatus";
+
+export type TemplateSyncItem = { kind: TemplateKind, id: string, name: string | null, version: string | null, status: TemplateSyncStatus, error: string | null, };
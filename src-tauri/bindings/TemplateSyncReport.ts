@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TemplateSyncItem } from "./TemplateSyncItem";
+
+export type TemplateSyncReport = { source: string, 
+/**
+ * Nothing was downloaded or written
+ */
+dryRun: boolean, items: Array<TemplateSyncItem>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a sync did (or, for a dry run, would do) with an entry
+ */
+export type TemplateSyncStatus = "added" | "updated" | "unchanged" | "modified" | "failed";
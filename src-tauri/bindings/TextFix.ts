@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A replacement for a UTF-16 span
+ */
+export type TextFix = { start: number, end: number, replacement: string, };
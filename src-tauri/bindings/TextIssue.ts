@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IssueKind } from "./IssueKind";
+
+/**
+ * A problem found in the text, as a UTF-16 span
+ */
+export type TextIssue = { kind: IssueKind, start: number, end: number, text: string, message: string, suggestions: Array<string>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Size and JPEG quality of generated thumbnails, configured per project
+ */
+export type ThumbnailSettings = { 
+/**
+ * Longest side in pixels
+ */
+size: number, 
+/**
+ * JPEG quality, 1..=100
+ */
+quality: number, };
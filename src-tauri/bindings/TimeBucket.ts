@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TimeBucket = { 
+/**
+ * "2025-03-14" for days and weeks (week start), "2025-03" for months
+ */
+label: string, 
+/**
+ * Unix timestamp (ms) of the bucket start
+ */
+start: number, openMs: number, focusMs: number, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimeBucket } from "./TimeBucket";
+import type { TimePeriod } from "./TimePeriod";
+
+export type TimeReport = { period: TimePeriod, buckets: Array<TimeBucket>, totalOpenMs: number, totalFocusMs: number, };
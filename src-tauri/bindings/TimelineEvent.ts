@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimelineSource } from "./TimelineSource";
+
+export type TimelineEvent = { source: TimelineSource, 
+/**
+ * Row ID within the source
+ */
+id: number, 
+/**
+ * Activity kind, or "asset-version" / "file-version"
+ */
+kind: string, actor: string | null, 
+/**
+ * Node or asset the event is about
+ */
+targetId: string | null, summary: string, 
+/**
+ * Unix timestamp (ms)
+ */
+createdAt: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TimelineRange = { 
+/**
+ * Inclusive, ISO 8601; compared at the precision given ("2025-03" = whole month)
+ */
+start: string | null, end: string | null, };
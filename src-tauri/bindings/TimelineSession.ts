@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimelineEvent } from "./TimelineEvent";
+
+/**
+ * Events of one working session, newest first
+ */
+export type TimelineSession = { 
+/**
+ * The recorded session, if the events fall into one
+ */
+sessionId: number | null, startedAt: number, endedAt: number, events: Array<TimelineEvent>, };
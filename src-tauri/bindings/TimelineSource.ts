@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where an event comes from
+ */
+export type TimelineSource = "activity" | "asset-version" | "file-version";
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportFailure } from "./ExportFailure";
+import type { ExportedRecord } from "./ExportedRecord";
+
+export type TrackerExport = { records: Array<ExportedRecord>, failed: Array<ExportFailure>, };
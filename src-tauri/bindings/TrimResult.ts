@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Asset } from "./Asset";
+import type { SynniaNode } from "./SynniaNode";
+
+/**
+ * The new clip, plus its node when the source node was given
+ */
+export type TrimResult = { asset: Asset, node: SynniaNode | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProjectUsage } from "./ProjectUsage";
+
+export type UsageReport = { 
+/**
+ * Projects using it, most recently used first
+ */
+projects: Array<ProjectUsage>, 
+/**
+ * Projects looked at
+ */
+scanned: number, 
+/**
+ * Projects that couldn't be read
+ */
+unreadable: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A named camera position on the canvas
+ */
+export type ViewportBookmark = { id: string, name: string, x: number, y: number, zoom: number, createdAt: number, };
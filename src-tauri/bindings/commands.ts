@@ -0,0 +1,348 @@
+// This file was generated by `cargo test export_bindings` (src/commands/bindings.rs). Do not edit this file manually.
+import type { ActivityEntry } from "./ActivityEntry";
+import type { ActivityFilter } from "./ActivityFilter";
+import type { Actor } from "./Actor";
+import type { AgentDefinition } from "./AgentDefinition";
+import type { Asset } from "./Asset";
+import type { AssetLicense } from "./AssetLicense";
+import type { AssetPatch } from "./AssetPatch";
+import type { AssetTag } from "./AssetTag";
+import type { AssetUsageReport } from "./AssetUsageReport";
+import type { AuditExport } from "./AuditExport";
+import type { AuditFormat } from "./AuditFormat";
+import type { AutoTagScope } from "./AutoTagScope";
+import type { AutoTagSettings } from "./AutoTagSettings";
+import type { BackupInfo } from "./BackupInfo";
+import type { BackupSettings } from "./BackupSettings";
+import type { BatchImportResult } from "./BatchImportResult";
+import type { BrandComplianceReport } from "./BrandComplianceReport";
+import type { BrandKit } from "./BrandKit";
+import type { BrokenLink } from "./BrokenLink";
+import type { BudgetSettings } from "./BudgetSettings";
+import type { BudgetStatus } from "./BudgetStatus";
+import type { BundleReport } from "./BundleReport";
+import type { Canvas } from "./Canvas";
+import type { Capability } from "./Capability";
+import type { ComponentDefinition } from "./ComponentDefinition";
+import type { ComponentInstance } from "./ComponentInstance";
+import type { ConnectionReport } from "./ConnectionReport";
+import type { ConnectorProvider } from "./ConnectorProvider";
+import type { CropPreset } from "./CropPreset";
+import type { CustomField } from "./CustomField";
+import type { CustomFieldInput } from "./CustomFieldInput";
+import type { DateRange } from "./DateRange";
+import type { DeleteProjectReport } from "./DeleteProjectReport";
+import type { DownloadFileResult } from "./DownloadFileResult";
+import type { EditMode } from "./EditMode";
+import type { EditProvider } from "./EditProvider";
+import type { ExportMapping } from "./ExportMapping";
+import type { ExportedFile } from "./ExportedFile";
+import type { FaceGroup } from "./FaceGroup";
+import type { FaceSettings } from "./FaceSettings";
+import type { FieldFilter } from "./FieldFilter";
+import type { FieldTarget } from "./FieldTarget";
+import type { FieldValue } from "./FieldValue";
+import type { FileVersion } from "./FileVersion";
+import type { FlaggedAsset } from "./FlaggedAsset";
+import type { FlattenReport } from "./FlattenReport";
+import type { FolderImport } from "./FolderImport";
+import type { GeneratedProject } from "./GeneratedProject";
+import type { GenerationParams } from "./GenerationParams";
+import type { GenerationRecord } from "./GenerationRecord";
+import type { GraphAction } from "./GraphAction";
+import type { GraphAnalysis } from "./GraphAnalysis";
+import type { GreetResponse } from "./GreetResponse";
+import type { GridAxis } from "./GridAxis";
+import type { GroupProgress } from "./GroupProgress";
+import type { HistoryEntry } from "./HistoryEntry";
+import type { HistoryTimelineRange } from "./HistoryTimelineRange";
+import type { ImportFolderOptions } from "./ImportFolderOptions";
+import type { InstalledModel } from "./InstalledModel";
+import type { Job } from "./Job";
+import type { JobInfo } from "./JobInfo";
+import type { KanbanColumn } from "./KanbanColumn";
+import type { KanbanGroupBy } from "./KanbanGroupBy";
+import type { LicenseFilter } from "./LicenseFilter";
+import type { LicenseReport } from "./LicenseReport";
+import type { LintConfig } from "./LintConfig";
+import type { LintFinding } from "./LintFinding";
+import type { LoadProfile } from "./LoadProfile";
+import type { MapKind } from "./MapKind";
+import type { MediaAssetInfo } from "./MediaAssetInfo";
+import type { Minimap } from "./Minimap";
+import type { ModelDiskUsage } from "./ModelDiskUsage";
+import type { ModelDownload } from "./ModelDownload";
+import type { ModelInfo } from "./ModelInfo";
+import type { MovedNodes } from "./MovedNodes";
+import type { NetworkTimeouts } from "./NetworkTimeouts";
+import type { OperationInfo } from "./OperationInfo";
+import type { OrgConfig } from "./OrgConfig";
+import type { PairingQr } from "./PairingQr";
+import type { PdfExport } from "./PdfExport";
+import type { PdfExportOptions } from "./PdfExportOptions";
+import type { PermissionPolicy } from "./PermissionPolicy";
+import type { PermissionRequest } from "./PermissionRequest";
+import type { PipelineOptions } from "./PipelineOptions";
+import type { PipelineRun } from "./PipelineRun";
+import type { PlanningItem } from "./PlanningItem";
+import type { Position } from "./Position";
+import type { ProxyOptions } from "./ProxyOptions";
+import type { ProxyResponse } from "./ProxyResponse";
+import type { RecentProject } from "./RecentProject";
+import type { Recipe } from "./Recipe";
+import type { RecipeInput } from "./RecipeInput";
+import type { RecipePreview } from "./RecipePreview";
+import type { RelinkReport } from "./RelinkReport";
+import type { Reminder } from "./Reminder";
+import type { RemoteFile } from "./RemoteFile";
+import type { RemoteImport } from "./RemoteImport";
+import type { RenamedAsset } from "./RenamedAsset";
+import type { RestoreResult } from "./RestoreResult";
+import type { ReviewDecision } from "./ReviewDecision";
+import type { RunComparison } from "./RunComparison";
+import type { SafetySettings } from "./SafetySettings";
+import type { SaveImageResult } from "./SaveImageResult";
+import type { ScriptStep } from "./ScriptStep";
+import type { SeedStrategy } from "./SeedStrategy";
+import type { ShareBundle } from "./ShareBundle";
+import type { SmartCollection } from "./SmartCollection";
+import type { SmartQuery } from "./SmartQuery";
+import type { SpellcheckReport } from "./SpellcheckReport";
+import type { StockImport } from "./StockImport";
+import type { StockProvider } from "./StockProvider";
+import type { StockResult } from "./StockResult";
+import type { StockSize } from "./StockSize";
+import type { SubgraphExport } from "./SubgraphExport";
+import type { SyncStatus } from "./SyncStatus";
+import type { SynniaNode } from "./SynniaNode";
+import type { SynniaProject } from "./SynniaProject";
+import type { TaskStatus } from "./TaskStatus";
+import type { TemplateSyncReport } from "./TemplateSyncReport";
+import type { TextFix } from "./TextFix";
+import type { ThumbnailSettings } from "./ThumbnailSettings";
+import type { TimePeriod } from "./TimePeriod";
+import type { TimeReport } from "./TimeReport";
+import type { TimelineRange } from "./TimelineRange";
+import type { TimelineSession } from "./TimelineSession";
+import type { TrackerExport } from "./TrackerExport";
+import type { TrimResult } from "./TrimResult";
+import type { UsageReport } from "./UsageReport";
+import type { Viewport } from "./Viewport";
+import type { ViewportBookmark } from "./ViewportBookmark";
+
+export type Invoke = <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;
+
+/** Typed command invokers over `invoke` (e.g. `apiClient.invoke`) */
+export const createCommands = (invoke: Invoke) => ({
+    ping: (args: { name: string }) => invoke<GreetResponse>("ping", args),
+    getServerPort: () => invoke<number>("get_server_port"),
+    initProject: (args: { path: string }) => invoke<string>("init_project", args),
+    getRecentProjects: () => invoke<Array<RecentProject>>("get_recent_projects"),
+    getDefaultProjectsPath: () => invoke<string>("get_default_projects_path"),
+    setDefaultProjectsPath: (args: { path: string }) => invoke<void>("set_default_projects_path", args),
+    createProject: (args: { name: string, parentPath: string }) => invoke<string>("create_project", args),
+    loadProject: (args: { path: string }) => invoke<SynniaProject>("load_project", args),
+    profileProjectLoad: (args: { path: string }) => invoke<LoadProfile>("profile_project_load", args),
+    generateTestProject: (args: { nodes: number, assets: number, size: number }) => invoke<GeneratedProject>("generate_test_project", args),
+    saveProject: (args: { project: SynniaProject, force?: boolean | null }) => invoke<void>("save_project", args),
+    saveProjectAutosave: (args: { project: SynniaProject, force?: boolean | null }) => invoke<void>("save_project_autosave", args),
+    getCurrentProjectPath: () => invoke<string>("get_current_project_path"),
+    deleteProject: (args: { path: string, dryRun?: boolean | null }) => invoke<DeleteProjectReport>("delete_project", args),
+    resetProject: () => invoke<SynniaProject>("reset_project"),
+    setThumbnail: (args: { imageRelativePath: string }) => invoke<void>("set_thumbnail", args),
+    openInBrowser: (args: { url: string }) => invoke<void>("open_in_browser", args),
+    renameProject: (args: { oldPath: string, newName: string }) => invoke<string>("rename_project", args),
+    getSyncStatus: () => invoke<SyncStatus>("get_sync_status"),
+    saveSettings: (args: { key: string, baseUrl: string, modelName: string }) => invoke<void>("save_settings", args),
+    getApiKey: () => invoke<string>("get_api_key"),
+    getBaseUrl: () => invoke<string>("get_base_url"),
+    getModelName: () => invoke<string>("get_model_name"),
+    runAgent: (args: { agentDef: AgentDefinition, inputs: any, contextNodeId?: string | null, operationId?: string | null }) => invoke<Array<GraphAction>>("run_agent", args),
+    runAgentStreaming: (args: { agentDef: AgentDefinition, inputs: any, contextNodeId?: string | null, targetAssetId: string, append?: boolean | null, flushTokens?: number | null, operationId?: string | null }) => invoke<Asset>("run_agent_streaming", args),
+    getAgents: () => invoke<Array<AgentDefinition>>("get_agents"),
+    saveAgent: (args: { agent: AgentDefinition }) => invoke<void>("save_agent", args),
+    deleteAgent: (args: { agentId: string }) => invoke<void>("delete_agent", args),
+    getAgentUsages: (args: { agentId: string }) => invoke<UsageReport>("get_agent_usages", args),
+    getTemplateUsages: (args: { templateId: string }) => invoke<UsageReport>("get_template_usages", args),
+    getAiConfig: () => invoke<string>("get_ai_config"),
+    saveAiConfig: (args: { config: string }) => invoke<void>("save_ai_config", args),
+    testAiConnection: (args: { provider: string }) => invoke<ConnectionReport>("test_ai_connection", args),
+    listModels: (args: { provider: string }) => invoke<Array<ModelInfo>>("list_models", args),
+    getMediaConfig: () => invoke<string>("get_media_config"),
+    saveMediaConfig: (args: { config: string }) => invoke<void>("save_media_config", args),
+    getAppSettings: () => invoke<string>("get_app_settings"),
+    saveAppSettings: (args: { settings: string }) => invoke<void>("save_app_settings", args),
+    exportSettingsBundle: (args: { dest?: string | null }) => invoke<BundleReport>("export_settings_bundle", args),
+    importSettingsBundle: (args: { file: string }) => invoke<BundleReport>("import_settings_bundle", args),
+    getNetworkAllowlist: () => invoke<Array<string>>("get_network_allowlist"),
+    saveNetworkAllowlist: (args: { hosts: Array<string>, strict: boolean }) => invoke<void>("save_network_allowlist", args),
+    getNetworkTimeouts: () => invoke<NetworkTimeouts>("get_network_timeouts"),
+    saveNetworkTimeouts: (args: { timeouts: NetworkTimeouts }) => invoke<void>("save_network_timeouts", args),
+    getOrgConfig: () => invoke<OrgConfig>("get_org_config"),
+    importFile: (args: { filePath: string, link?: boolean | null }) => invoke<SaveImageResult>("import_file", args),
+    saveProcessedImage: (args: { base64Data: string, filename?: string | null }) => invoke<SaveImageResult>("save_processed_image", args),
+    downloadAndSaveImage: (args: { url: string, filename?: string | null, downloadId?: string | null }) => invoke<SaveImageResult>("download_and_save_image", args),
+    batchImportImages: (args: { filePaths: Array<string>, concurrency?: number | null }) => invoke<Array<BatchImportResult>>("batch_import_images", args),
+    getMediaAssets: () => invoke<Array<MediaAssetInfo>>("get_media_assets"),
+    downloadAssetFile: (args: { url: string, downloadId: string, extension?: string | null, maxBytes?: number | null }) => invoke<DownloadFileResult>("download_asset_file", args),
+    cancelDownload: (args: { downloadId: string }) => invoke<boolean>("cancel_download", args),
+    getActiveDownloads: () => invoke<Array<string>>("get_active_downloads"),
+    getAssetUsageReport: () => invoke<AssetUsageReport>("get_asset_usage_report"),
+    getBrokenLinks: () => invoke<Array<BrokenLink>>("get_broken_links"),
+    relinkAssets: (args: { oldPrefix: string, newPrefix: string }) => invoke<RelinkReport>("relink_assets", args),
+    renameAsset: (args: { assetId: string, newName: string, renameFile?: boolean | null }) => invoke<RenamedAsset>("rename_asset", args),
+    bulkUpdateAssets: (args: { assetIds: Array<string>, patch: AssetPatch }) => invoke<Array<Asset>>("bulk_update_assets", args),
+    importFolder: (args: { path: string, options?: ImportFolderOptions | null }) => invoke<FolderImport>("import_folder", args),
+    generateVideoProxies: (args: { assetIds?: Array<string> | null, minBytes?: number | null, force?: boolean | null }) => invoke<JobInfo>("generate_video_proxies", args),
+    trimAudio: (args: { assetId: string, startMs: number, endMs: number, nodeId?: string | null }) => invoke<TrimResult>("trim_audio", args),
+    upscaleImage: (args: { assetId: string, factor: number, provider?: string | null }) => invoke<JobInfo>("upscale_image", args),
+    generateImageMaps: (args: { assetId: string, kinds: Array<MapKind>, nodeId?: string | null }) => invoke<JobInfo>("generate_image_maps", args),
+    saveAssetWithHistory: (args: { asset: Asset }) => invoke<boolean>("save_asset_with_history", args),
+    getAssetHistory: (args: { assetId: string, limit?: number | null }) => invoke<Array<HistoryEntry>>("get_asset_history", args),
+    getHistoryContent: (args: { historyId: number }) => invoke<string>("get_history_content", args),
+    restoreAssetVersion: (args: { assetId: string, historyId: number }) => invoke<RestoreResult>("restore_asset_version", args),
+    countAssetHistory: (args: { assetId: string }) => invoke<number>("count_asset_history", args),
+    getFileHistory: (args: { assetId: string }) => invoke<Array<FileVersion>>("get_file_history", args),
+    restoreFileVersion: (args: { assetId: string, versionId: number }) => invoke<Asset>("restore_file_version", args),
+    getProjectHistoryTimeline: (args: { range?: HistoryTimelineRange | null }) => invoke<Array<TimelineSession>>("get_project_history_timeline", args),
+    flattenProject: (args: { keepLastN: number, dryRun?: boolean | null }) => invoke<FlattenReport>("flatten_project", args),
+    proxyRequest: (args: { url: string, method: string, headers: { [key in string]?: string }, body?: string | null, options?: ProxyOptions | null, operationId?: string | null }) => invoke<ProxyResponse>("proxy_request", args),
+    proxyRequestStream: (args: { requestId: string, url: string, method: string, headers: { [key in string]?: string }, body?: string | null, options?: ProxyOptions | null }) => invoke<ProxyResponse>("proxy_request_stream", args),
+    runGenerationGrid: (args: { recipeNodeId: string, variations: Array<GridAxis>, concurrency?: number | null, outputNodeType?: string | null }) => invoke<JobInfo>("run_generation_grid", args),
+    completeJobTask: (args: { jobId: string, taskIndex: number, result?: any | null, error?: string | null }) => invoke<void>("complete_job_task", args),
+    cancelJob: (args: { jobId: string }) => invoke<Job>("cancel_job", args),
+    confirmJobBudget: (args: { jobId: string, approve: boolean }) => invoke<Job>("confirm_job_budget", args),
+    getJob: (args: { jobId: string }) => invoke<Job>("get_job", args),
+    getJobs: () => invoke<Array<Job>>("get_jobs"),
+    recordGeneration: (args: { assetId: string, params: GenerationParams, recipeId?: string | null, parentAssetId?: string | null }) => invoke<GenerationRecord>("record_generation", args),
+    getGenerationRecord: (args: { assetId: string }) => invoke<GenerationRecord | null>("get_generation_record", args),
+    getAssetVariations: (args: { assetId: string }) => invoke<Array<GenerationRecord>>("get_asset_variations", args),
+    regenerateVariation: (args: { assetId: string, overrides?: any | null, seedStrategy?: SeedStrategy | null }) => invoke<JobInfo>("regenerate_variation", args),
+    editImageAi: (args: { assetId: string, maskAssetId: string, prompt: string, mode?: EditMode | null, provider?: EditProvider | null }) => invoke<JobInfo>("edit_image_ai", args),
+    interrogateImage: (args: { assetId: string, provider?: string | null }) => invoke<JobInfo>("interrogate_image", args),
+    createComparison: (args: { assetIds: Array<string>, name?: string | null }) => invoke<Asset>("create_comparison", args),
+    voteComparison: (args: { comparisonId: string, candidateAssetId: string, note?: string | null }) => invoke<Asset>("vote_comparison", args),
+    addComparisonNote: (args: { comparisonId: string, candidateAssetId: string, note: string }) => invoke<Asset>("add_comparison_note", args),
+    getComponents: () => invoke<Array<ComponentDefinition>>("get_components"),
+    saveComponent: (args: { name: string, nodeIds: Array<string>, description?: string | null }) => invoke<ComponentDefinition>("save_component", args),
+    deleteComponent: (args: { componentId: string }) => invoke<void>("delete_component", args),
+    instantiateComponent: (args: { componentId: string, position: Position }) => invoke<ComponentInstance>("instantiate_component", args),
+    getSpellcheckLanguages: () => invoke<Array<string>>("get_spellcheck_languages"),
+    checkTextAsset: (args: { assetId: string, lang: string }) => invoke<SpellcheckReport>("check_text_asset", args),
+    applyFixes: (args: { assetId: string, fixes: Array<TextFix> }) => invoke<Asset>("apply_fixes", args),
+    translateAsset: (args: { assetId: string, targetLang: string, provider?: string | null }) => invoke<JobInfo>("translate_asset", args),
+    translateGroup: (args: { groupId: string, targetLang: string, provider?: string | null, concurrency?: number | null }) => invoke<JobInfo>("translate_group", args),
+    lintProject: (args: { config?: LintConfig | null }) => invoke<Array<LintFinding>>("lint_project", args),
+    getBrandKit: () => invoke<BrandKit>("get_brand_kit"),
+    saveBrandKit: (args: { kit: BrandKit }) => invoke<void>("save_brand_kit", args),
+    checkBrandCompliance: (args: { assetId: string }) => invoke<BrandComplianceReport>("check_brand_compliance", args),
+    exportCrops: (args: { assetId: string, presets: Array<CropPreset>, operationId?: string | null }) => invoke<Array<ExportedFile>>("export_crops", args),
+    exportPdf: (args: { options?: PdfExportOptions | null, operationId?: string | null }) => invoke<PdfExport>("export_pdf", args),
+    exportSubgraph: (args: { nodeIds: Array<string>, dest: string, includeHistory?: boolean | null, operationId?: string | null }) => invoke<SubgraphExport>("export_subgraph", args),
+    cancelOperation: (args: { opId: string }) => invoke<boolean>("cancel_operation", args),
+    getActiveOperations: () => invoke<Array<OperationInfo>>("get_active_operations"),
+    getTimeline: (args: { range?: TimelineRange | null }) => invoke<Array<PlanningItem>>("get_timeline", args),
+    getKanban: (args: { groupBy?: KanbanGroupBy | null }) => invoke<Array<KanbanColumn>>("get_kanban", args),
+    toggleTask: (args: { assetId: string, index: number }) => invoke<Asset>("toggle_task", args),
+    getGroupProgress: (args: { groupNodeId: string }) => invoke<GroupProgress>("get_group_progress", args),
+    scheduleReminder: (args: { nodeId: string, when: string, message: string }) => invoke<Reminder>("schedule_reminder", args),
+    getReminders: (args: { includeFired?: boolean | null }) => invoke<Array<Reminder>>("get_reminders", args),
+    cancelReminder: (args: { reminderId: string }) => invoke<void>("cancel_reminder", args),
+    getActivity: (args: { since?: number | null, filters?: ActivityFilter | null }) => invoke<Array<ActivityEntry>>("get_activity", args),
+    exportAuditLog: (args: { range?: DateRange | null, format: AuditFormat, dest?: string | null }) => invoke<AuditExport>("export_audit_log", args),
+    getTimeReport: (args: { period?: TimePeriod | null, since?: number | null, until?: number | null }) => invoke<TimeReport>("get_time_report", args),
+    getBackupSettings: () => invoke<BackupSettings>("get_backup_settings"),
+    saveBackupSettings: (args: { settings: BackupSettings }) => invoke<void>("save_backup_settings", args),
+    listBackups: () => invoke<Array<BackupInfo>>("list_backups"),
+    runBackupsNow: () => invoke<Array<BackupInfo>>("run_backups_now"),
+    restoreBackup: (args: { backupId: string, targetPath?: string | null }) => invoke<string>("restore_backup", args),
+    analyzeGraph: () => invoke<GraphAnalysis>("analyze_graph"),
+    renderMinimap: (args: { width: number, height: number }) => invoke<Minimap>("render_minimap", args),
+    setNodeLock: (args: { nodeId: string, locked?: boolean | null, pinned?: boolean | null }) => invoke<SynniaNode>("set_node_lock", args),
+    createReference: (args: { nodeId: string, position?: Position | null }) => invoke<SynniaNode>("create_reference", args),
+    listReferences: (args: { assetId: string }) => invoke<Array<SynniaNode>>("list_references", args),
+    saveViewportBookmark: (args: { name: string, viewport?: Viewport | null }) => invoke<ViewportBookmark>("save_viewport_bookmark", args),
+    listViewportBookmarks: () => invoke<Array<ViewportBookmark>>("list_viewport_bookmarks"),
+    deleteViewportBookmark: (args: { bookmarkId: string }) => invoke<void>("delete_viewport_bookmark", args),
+    createCanvas: (args: { name: string }) => invoke<Canvas>("create_canvas", args),
+    listCanvases: () => invoke<Array<Canvas>>("list_canvases"),
+    openCanvas: (args: { canvasId: string }) => invoke<SynniaProject>("open_canvas", args),
+    moveNodesToCanvas: (args: { nodeIds: Array<string>, canvasId: string }) => invoke<MovedNodes>("move_nodes_to_canvas", args),
+    getClipToken: () => invoke<string>("get_clip_token"),
+    regenerateClipToken: () => invoke<string>("regenerate_clip_token"),
+    getPairingQr: () => invoke<PairingQr>("get_pairing_qr"),
+    stopMobileCapture: () => invoke<void>("stop_mobile_capture"),
+    getThumbnailSettings: () => invoke<ThumbnailSettings>("get_thumbnail_settings"),
+    saveThumbnailSettings: (args: { settings: ThumbnailSettings }) => invoke<ThumbnailSettings>("save_thumbnail_settings", args),
+    regenerateThumbnails: (args: { force?: boolean | null, concurrency?: number | null }) => invoke<JobInfo>("regenerate_thumbnails", args),
+    getAssetTags: (args: { assetId: string }) => invoke<Array<AssetTag>>("get_asset_tags", args),
+    setAssetTags: (args: { assetId: string, tags: Array<string> }) => invoke<Array<AssetTag>>("set_asset_tags", args),
+    searchAssetsByTags: (args: { query: string, limit?: number | null, fields?: Array<FieldFilter> | null, license?: LicenseFilter | null }) => invoke<Array<string>>("search_assets_by_tags", args),
+    getAutoTagSettings: () => invoke<AutoTagSettings>("get_auto_tag_settings"),
+    saveAutoTagSettings: (args: { settings: AutoTagSettings }) => invoke<AutoTagSettings>("save_auto_tag_settings", args),
+    autoTagAssets: (args: { scope?: AutoTagScope | null, provider?: string | null, concurrency?: number | null }) => invoke<JobInfo>("auto_tag_assets", args),
+    getCustomFields: () => invoke<Array<CustomField>>("get_custom_fields"),
+    saveCustomField: (args: { field: CustomFieldInput }) => invoke<CustomField>("save_custom_field", args),
+    deleteCustomField: (args: { fieldId: string }) => invoke<boolean>("delete_custom_field", args),
+    getCustomFieldValues: (args: { targetId: string }) => invoke<Array<FieldValue>>("get_custom_field_values", args),
+    setCustomFieldValue: (args: { target: FieldTarget, targetId: string, fieldId: string, value: any }) => invoke<Array<FieldValue>>("set_custom_field_value", args),
+    searchByCustomFields: (args: { target: FieldTarget, filters: Array<FieldFilter>, limit?: number | null }) => invoke<Array<string>>("search_by_custom_fields", args),
+    createSmartCollection: (args: { name: string, query: SmartQuery }) => invoke<SmartCollection>("create_smart_collection", args),
+    listSmartCollections: () => invoke<Array<SmartCollection>>("list_smart_collections"),
+    deleteSmartCollection: (args: { collectionId: string }) => invoke<void>("delete_smart_collection", args),
+    evaluateSmartCollection: (args: { collectionId: string, limit?: number | null }) => invoke<Array<string>>("evaluate_smart_collection", args),
+    listRecipes: () => invoke<Array<Recipe>>("list_recipes"),
+    getRecipe: (args: { recipeId: string }) => invoke<Recipe>("get_recipe", args),
+    saveRecipe: (args: { recipe: RecipeInput }) => invoke<Recipe>("save_recipe", args),
+    deleteRecipe: (args: { recipeId: string }) => invoke<boolean>("delete_recipe", args),
+    previewRecipe: (args: { recipeNodeId: string }) => invoke<RecipePreview>("preview_recipe", args),
+    startPipeline: (args: { nodeIds: Array<string>, options?: PipelineOptions | null }) => invoke<PipelineRun>("start_pipeline", args),
+    recordPipelineStep: (args: { runId: string, nodeId: string, status: TaskStatus, outputAssetId?: string | null, error?: string | null, retryAfterMs?: number | null }) => invoke<PipelineRun>("record_pipeline_step", args),
+    resumePipeline: (args: { runId: string }) => invoke<PipelineRun>("resume_pipeline", args),
+    getPipelineRun: (args: { runId: string }) => invoke<PipelineRun>("get_pipeline_run", args),
+    listPipelineRuns: (args: { limit?: number | null }) => invoke<Array<PipelineRun>>("list_pipeline_runs", args),
+    compareRuns: (args: { nodeId?: string | null, runA: string, runB: string }) => invoke<RunComparison>("compare_runs", args),
+    getFaceSettings: () => invoke<FaceSettings>("get_face_settings"),
+    saveFaceSettings: (args: { settings: FaceSettings }) => invoke<FaceSettings>("save_face_settings", args),
+    detectFaces: (args: { assetIds?: Array<string> | null, force?: boolean | null }) => invoke<JobInfo>("detect_faces", args),
+    groupAssetsByFace: (args: { threshold?: number | null }) => invoke<Array<FaceGroup>>("group_assets_by_face", args),
+    getSafetySettings: () => invoke<SafetySettings>("get_safety_settings"),
+    saveSafetySettings: (args: { settings: SafetySettings }) => invoke<SafetySettings>("save_safety_settings", args),
+    scanImageSafety: (args: { assetIds?: Array<string> | null, force?: boolean | null }) => invoke<JobInfo>("scan_image_safety", args),
+    getFlaggedAssets: (args: { includeReviewed?: boolean | null }) => invoke<Array<FlaggedAsset>>("get_flagged_assets", args),
+    reviewFlaggedAssets: (args: { assetIds: Array<string>, decision: ReviewDecision }) => invoke<Array<FlaggedAsset>>("review_flagged_assets", args),
+    getPermissionPolicy: () => invoke<PermissionPolicy>("get_permission_policy"),
+    savePermissionPolicy: (args: { policy: PermissionPolicy }) => invoke<PermissionPolicy>("save_permission_policy", args),
+    getPendingPermissionRequests: () => invoke<Array<PermissionRequest>>("get_pending_permission_requests"),
+    resolvePermissionRequest: (args: { requestId: string, allow: boolean, remember?: boolean | null }) => invoke<void>("resolve_permission_request", args),
+    checkAutomationPermission: (args: { actor: Actor, capability: Capability, summary: string, targetIds?: Array<string> | null }) => invoke<void>("check_automation_permission", args),
+    getIgnoreRules: () => invoke<Array<string>>("get_ignore_rules"),
+    saveIgnoreRules: (args: { patterns: Array<string> }) => invoke<Array<string>>("save_ignore_rules", args),
+    getBudgetSettings: () => invoke<BudgetSettings>("get_budget_settings"),
+    saveBudgetSettings: (args: { settings: BudgetSettings }) => invoke<BudgetSettings>("save_budget_settings", args),
+    getBudgetStatus: () => invoke<BudgetStatus>("get_budget_status"),
+    runCommandScript: (args: { steps: Array<ScriptStep> }) => invoke<JobInfo>("run_command_script", args),
+    syncTemplatesFromUrl: (args: { url: string, dryRun?: boolean | null }) => invoke<TemplateSyncReport>("sync_templates_from_url", args),
+    setLicense: (args: { assetId: string, license?: AssetLicense | null }) => invoke<AssetLicense | null>("set_license", args),
+    getLicense: (args: { assetId: string }) => invoke<AssetLicense | null>("get_license", args),
+    licenseReport: (args: { expiringDays?: number | null, dest?: string | null }) => invoke<LicenseReport>("license_report", args),
+    searchStock: (args: { provider: StockProvider, query: string, page?: number | null }) => invoke<Array<StockResult>>("search_stock", args),
+    importStockResult: (args: { id: string, size?: StockSize | null }) => invoke<StockImport>("import_stock_result", args),
+    connectRemoteProvider: (args: { provider: ConnectorProvider }) => invoke<string>("connect_remote_provider", args),
+    disconnectRemoteProvider: (args: { provider: ConnectorProvider }) => invoke<void>("disconnect_remote_provider", args),
+    getConnectedProviders: () => invoke<Array<ConnectorProvider>>("get_connected_providers"),
+    listRemoteFiles: (args: { provider: ConnectorProvider, folder?: string | null }) => invoke<Array<RemoteFile>>("list_remote_files", args),
+    importRemoteFile: (args: { provider: ConnectorProvider, fileId: string }) => invoke<RemoteImport>("import_remote_file", args),
+    exportToNotion: (args: { assetIds: Array<string>, databaseId: string, mapping: ExportMapping }) => invoke<TrackerExport>("export_to_notion", args),
+    exportToAirtable: (args: { assetIds: Array<string>, baseId: string, table: string, mapping: ExportMapping }) => invoke<TrackerExport>("export_to_airtable", args),
+    shareToWebhook: (args: { webhookUrl: string, nodeIds: Array<string>, message?: string | null }) => invoke<JobInfo>("share_to_webhook", args),
+    createShareBundle: (args: { nodeIds: Array<string>, expiry?: number | null }) => invoke<ShareBundle>("create_share_bundle", args),
+    listShareBundles: () => invoke<Array<ShareBundle>>("list_share_bundles"),
+    deleteShareBundle: (args: { bundleId: string }) => invoke<void>("delete_share_bundle", args),
+    listInstalledModels: () => invoke<Array<InstalledModel>>("list_installed_models"),
+    downloadModel: (args: { request: ModelDownload, downloadId: string }) => invoke<InstalledModel>("download_model", args),
+    deleteModel: (args: { modelId: string }) => invoke<boolean>("delete_model", args),
+    getModelDiskUsage: () => invoke<ModelDiskUsage>("get_model_disk_usage"),
+});
+
+export type Commands = ReturnType<typeof createCommands>;
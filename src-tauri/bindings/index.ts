@@ -26,3 +26,34 @@ export type { SynniaEdge } from './SynniaEdge';
 
 // Agent Types
 export type { AgentDefinition } from './AgentDefinition';
+
+// Generation Types
+export type { GenerationParams } from './GenerationParams';
+export type { GenerationRecord } from './GenerationRecord';
+
+// Component Types
+export type { ComponentDefinition } from './ComponentDefinition';
+
+// Brand Types
+export type { BrandKit } from './BrandKit';
+
+// Reminder Types
+export type { Reminder } from './Reminder';
+
+// Viewport Bookmark Types
+export type { ViewportBookmark } from './ViewportBookmark';
+
+// Asset Tag Types
+export type { AssetTag } from './AssetTag';
+
+// Canvas Types
+export type { Canvas } from './Canvas';
+
+// Event Types
+// Every backend event, keyed by name; see src/events.rs
+export type { AppEvent } from './AppEvent';
+
+// Command Invokers
+// Generated from the command signatures in src/commands/bindings.rs
+export { createCommands } from './commands';
+export type { Commands, Invoke } from './commands';
@@ -0,0 +1,64 @@
+//! Activity feed commands: what changed in the project, and when.
+
+use tauri::{AppHandle, Manager, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{audit, database, io_sqlite, validation};
+use crate::services::activity::{self, ActivityEntry, ActivityFilter};
+use crate::services::audit::{AuditExport, AuditFormat};
+use crate::services::smart_collections::DateRange;
+
+/// Activity newest first. `since` is a Unix timestamp (ms), e.g. the end of
+/// the user's last session.
+#[tauri::command]
+pub fn get_activity(
+    since: Option<i64>,
+    filters: Option<ActivityFilter>,
+    state: State<AppState>,
+) -> Result<Vec<ActivityEntry>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    activity::get_activity(&conn, since, &filters.unwrap_or_default())
+}
+
+/// Export the activity, agent runs, permission decisions and generation
+/// records within `range` as CSV or JSON, each row with a chained integrity
+/// hash. `dest` defaults to `Documents/Synnia`.
+#[tauri::command]
+pub fn export_audit_log(
+    range: Option<DateRange>,
+    format: AuditFormat,
+    dest: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<AuditExport, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let rows = audit::collect(&conn, &range.unwrap_or_default())?;
+
+    let dest = match dest {
+        Some(dest) => validation::check_path(&dest)?,
+        None => {
+            let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
+            let project = project_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let name = format!("{} Audit {}.{}", project, chrono::Local::now().format("%Y-%m-%d"), format.extension());
+            docs_dir.join("Synnia").join(name.trim())
+        }
+    };
+    audit::write(&rows, format, &dest)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
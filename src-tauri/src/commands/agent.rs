@@ -1,13 +1,30 @@
 use tauri::{State, AppHandle, Manager};
 use std::path::PathBuf;
 use crate::error::AppError;
-use crate::models::{AgentDefinition};
-use crate::services::agent_service::{call_gemini_agent, GraphAction};
+use crate::events::{self, AppEvent};
+use crate::models::{AgentDefinition, Asset};
+use crate::services::agent_service::{call_gemini_agent, stream_gemini_text, GraphAction};
 use crate::AppState;
 use crate::config::GlobalConfig;
+use crate::services::{asset_stream, brand, database, io_sqlite, prompt_guard, provider_health, settings_bundle};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::asset_stream::AssetStream;
+use crate::services::cancellation::OperationKind;
+use crate::services::net_guard::{NetworkPolicy, NetworkTimeouts};
+use crate::services::org_config::OrgConfig;
+use crate::services::permissions::{Actor, Capability};
+use crate::services::provider_health::{ConnectionReport, ModelInfo, ProviderCredentials};
+use crate::services::settings_bundle::BundleReport;
+use super::components::get_components_dir;
+
+/// Most characters of asset content put into an agent's context
+const MAX_CONTEXT_CHARS: usize = 8000;
+
+/// Provider key of the Gemini calls agents make, for the org's approved list
+pub(crate) const AGENT_PROVIDER: &str = "google";
 
 // Helper to get agents directory
-fn get_agents_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+pub(crate) fn get_agents_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
     let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
     let agents_dir = docs_dir.join("Synnia").join("Agents");
     if !agents_dir.exists() {
@@ -75,35 +92,224 @@ pub async fn run_agent(
     agent_def: AgentDefinition, 
     inputs: serde_json::Value,
     context_node_id: Option<String>,
-    _state: State<'_, AppState>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
     app: AppHandle
 ) -> Result<Vec<GraphAction>, AppError> {
     println!("Starting run_agent: {} with inputs: {:?}", agent_def.name, inputs); 
 
     // 1. Load Config
     let config = GlobalConfig::load(&app);
+    config.org.check_provider(AGENT_PROVIDER)?;
+    let timeouts = NetworkPolicy::from_config(&config).timeouts;
     let api_key = config.gemini_api_key.ok_or(AppError::Agent("Please configure Gemini API Key in Settings".to_string()))?;
     let base_url = config.gemini_base_url.unwrap_or("https://generativelanguage.googleapis.com".to_string());
     let model_name = config.gemini_model_name.unwrap_or("gemini-1.5-flash".to_string());
     
+    let context = agent_context(&state, context_node_id.as_deref(), &agent_def.name, true);
+
+    // 2. Call Service (cancellable through `cancel_operation`)
+    let operation = state.operations.register(operation_id, OperationKind::AgentRun, &agent_def.name)?;
+    let actions = operation.token().run(async {
+        call_gemini_agent(
+            &api_key, 
+            &base_url, 
+            &model_name, 
+            &agent_def.system_prompt,
+            inputs, 
+            context,
+            &timeouts,
+        ).await.map_err(AppError::Network)
+    }).await?;
+    drop(operation);
+
+    if let Some(project_path) = state.current_project_path.lock().ok().and_then(|p| p.clone()) {
+        let mut entry = NewActivity::new(
+            ActivityKind::AgentRan,
+            "agent",
+            format!("Ran {} ({} actions)", agent_def.name, actions.len()),
+        ).source(&agent_def.name);
+        if let Some(nid) = &context_node_id {
+            entry = entry.target(nid);
+        }
+        activity::log(&PathBuf::from(project_path), entry);
+    }
+
+    // 3. Check the actions against the project's permission policy
+    let actions = authorize_actions(actions, &state, &app).await?;
+
+    // 4. Return actions to Frontend
+    Ok(actions)
+}
+
+/// Run an agent and stream its text output straight into a text asset,
+/// emitting `asset:streaming` as partial output is written. The run is saved
+/// as a single history version once it completes.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_agent_streaming(
+    agent_def: AgentDefinition,
+    inputs: serde_json::Value,
+    context_node_id: Option<String>,
+    target_asset_id: String,
+    append: Option<bool>,
+    flush_tokens: Option<usize>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle
+) -> Result<Asset, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(AGENT_PROVIDER)?;
+    let timeouts = NetworkPolicy::from_config(&config).timeouts;
+    let api_key = config.gemini_api_key.ok_or(AppError::Agent("Please configure Gemini API Key in Settings".to_string()))?;
+    let base_url = config.gemini_base_url.unwrap_or("https://generativelanguage.googleapis.com".to_string());
+    let model_name = config.gemini_model_name.unwrap_or("gemini-1.5-flash".to_string());
+
+    let project_path = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?
+        .clone()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)?;
+    let context = agent_context(&state, context_node_id.as_deref(), &agent_def.name, true);
+
+    let mut stream = AssetStream::begin(
+        &project_path,
+        &target_asset_id,
+        append.unwrap_or(false),
+        flush_tokens.unwrap_or(asset_stream::DEFAULT_FLUSH_TOKENS),
+    )?;
+    let operation = state.operations.register(operation_id, OperationKind::AgentRun, &agent_def.name)?;
+    let result = operation.token().run(async {
+        stream_gemini_text(
+            &api_key,
+            &base_url,
+            &model_name,
+            &agent_def.system_prompt,
+            inputs,
+            context,
+            &timeouts,
+            |text| {
+                if let Some(update) = stream.push(text).map_err(|e| e.to_string())? {
+                    let _ = events::emit(&app, AppEvent::AssetStreaming(update));
+                }
+                Ok(())
+            },
+        ).await.map_err(AppError::Network)
+    }).await;
+    drop(operation);
+
+    // Whatever arrived before a failure or cancel is still kept as a version
+    let (asset, update) = stream.finish()?;
+    events::emit(&app, AppEvent::AssetStreaming(update))?;
+    result?;
+
+    activity::log(&project_path, NewActivity::new(
+        ActivityKind::AgentRan,
+        "agent",
+        format!("Ran {} into {}", agent_def.name, asset.sys.name),
+    ).source(&agent_def.name).target(&asset.id));
+
+    Ok(asset)
+}
+
+/// Context handed to an agent: the focused node, the brand kit and the
+/// focused asset's (guarded) content. Removed instruction-like lines are
+/// logged to the activity feed when `log_findings` is set.
+pub(crate) fn agent_context(
+    state: &State<'_, AppState>,
+    context_node_id: Option<&str>,
+    agent_name: &str,
+    log_findings: bool,
+) -> String {
     let context = if let Some(nid) = context_node_id {
          format!("User is focusing on Node: {}", nid)
     } else {
          "No specific node selected.".to_string()
     };
+    let context = match brand_context(state) {
+        Some(kit) => format!("{}\n\n{}", context, kit),
+        None => context,
+    };
+    match context_node_id.and_then(|nid| focused_content(state, nid, agent_name, log_findings)) {
+        Some(content) => format!("{}\n\n{}", context, content),
+        None => context,
+    }
+}
 
-    // 2. Call Service
-    let actions = call_gemini_agent(
-        &api_key, 
-        &base_url, 
-        &model_name, 
-        &agent_def.system_prompt,
-        inputs, 
-        context
-    ).await.map_err(|e| AppError::Network(e))?;
-
-    // 3. Return actions to Frontend
-    Ok(actions)
+/// Drop the actions the project's policy doesn't allow, telling the user why.
+pub(crate) async fn authorize_actions(
+    actions: Vec<GraphAction>,
+    state: &State<'_, AppState>,
+    app: &AppHandle,
+) -> Result<Vec<GraphAction>, AppError> {
+    let Some(project_path) = state.current_project_path.lock().ok().and_then(|p| p.clone()) else {
+        return Ok(actions);
+    };
+    let creates = actions.iter().filter(|a| matches!(a, GraphAction::CreateNode { .. })).count();
+    if creates == 0 {
+        return Ok(actions);
+    }
+    
+    let summary = format!("Create {} node{}", creates, if creates == 1 { "" } else { "s" });
+    match state.permissions
+        .authorize(app, &PathBuf::from(project_path), Actor::Agent, Capability::CreateNodes, summary, Vec::new())
+        .await
+    {
+        Ok(()) => Ok(actions),
+        Err(AppError::Forbidden(_)) => {
+            let mut kept: Vec<GraphAction> = actions.into_iter()
+                .filter(|a| !matches!(a, GraphAction::CreateNode { .. }))
+                .collect();
+            kept.push(GraphAction::Message {
+                text: format!("Skipped {} new node(s): not allowed by this project's permission policy.", creates),
+            });
+            Ok(kept)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Content of the focused node's asset, labeled as untrusted. Lines that
+/// look like instructions to the agent are removed and logged.
+fn focused_content(state: &State<'_, AppState>, node_id: &str, agent_name: &str, log_findings: bool) -> Option<String> {
+    let project_path = PathBuf::from(state.current_project_path.lock().ok()?.clone()?);
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path)).ok()?;
+    let asset_id = io_sqlite::get_node(&conn, node_id).ok()??.data.asset_id?;
+    let asset = io_sqlite::get_asset(&conn, &asset_id).ok()??;
+    
+    let content = match &asset.value {
+        serde_json::Value::String(text) => text.clone(),
+        value => value.get("content").or_else(|| value.get("text"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| serde_json::to_string_pretty(value).unwrap_or_default()),
+    };
+    let content: String = content.chars().take(MAX_CONTEXT_CHARS).collect();
+    if content.trim().is_empty() {
+        return None;
+    }
+    
+    let guarded = prompt_guard::guard(&format!("{}: {}", asset.sys.source, asset.sys.name), &content);
+    if log_findings && !guarded.findings.is_empty() {
+        let count = guarded.findings.len();
+        activity::log(&project_path, NewActivity::new(
+            ActivityKind::InjectionSuspected,
+            "agent",
+            format!(
+                "Removed {} instruction-like line{} from {} before running {}",
+                count, if count == 1 { "" } else { "s" }, asset.sys.name, agent_name
+            ),
+        ).source(agent_name).target(&asset.id));
+    }
+    Some(format!("Focused node content:\n{}", guarded.text))
+}
+
+/// Brand kit of the open project as agent context, if one is set
+fn brand_context(state: &State<'_, AppState>) -> Option<String> {
+    let project_path = state.current_project_path.lock().ok()?.clone()?;
+    let project = io_sqlite::load_project_sqlite(&PathBuf::from(project_path)).ok()?;
+    let kit = brand::kit_from_project(&project)?;
+    Some(brand::agent_context(&kit, &project))
 }
 
 #[tauri::command]
@@ -148,6 +354,26 @@ pub fn save_ai_config(config: String, app: AppHandle) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Ping a configured provider: reachability, key and latency. Failures are
+/// reported in the result rather than as errors.
+#[tauri::command]
+pub async fn test_ai_connection(provider: String, app: AppHandle) -> Result<ConnectionReport, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(&provider)?;
+    let credentials = ProviderCredentials::from_config(&config, &provider);
+    Ok(provider_health::test_connection(&NetworkPolicy::from_config(&config), &provider, &credentials).await)
+}
+
+/// Models available with a configured provider's key, sorted by ID.
+#[tauri::command]
+pub async fn list_models(provider: String, app: AppHandle) -> Result<Vec<ModelInfo>, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(&provider)?;
+    let credentials = ProviderCredentials::from_config(&config, &provider);
+    let (models, _) = provider_health::fetch_models(&NetworkPolicy::from_config(&config), &provider, &credentials).await?;
+    Ok(models)
+}
+
 #[tauri::command]
 pub fn get_media_config(app: AppHandle) -> Result<String, AppError> {
     let config = GlobalConfig::load(&app);
@@ -162,6 +388,41 @@ pub fn save_media_config(config: String, app: AppHandle) -> Result<(), AppError>
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_network_allowlist(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let config = GlobalConfig::load(&app);
+    Ok(config.network_allowlist.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn save_network_allowlist(hosts: Vec<String>, strict: bool, app: AppHandle) -> Result<(), AppError> {
+    let mut global_config = GlobalConfig::load(&app);
+    global_config.network_allowlist = Some(hosts);
+    global_config.network_strict = Some(strict);
+    global_config.save(&app).map_err(AppError::Unknown)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_network_timeouts(app: AppHandle) -> Result<NetworkTimeouts, AppError> {
+    Ok(GlobalConfig::load(&app).network_timeouts.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn save_network_timeouts(timeouts: NetworkTimeouts, app: AppHandle) -> Result<(), AppError> {
+    let mut global_config = GlobalConfig::load(&app);
+    global_config.network_timeouts = Some(timeouts);
+    global_config.save(&app).map_err(AppError::Unknown)?;
+    Ok(())
+}
+
+/// The organization's read-only config layer, for the settings screen to
+/// show what it enforces.
+#[tauri::command]
+pub fn get_org_config(app: AppHandle) -> Result<OrgConfig, AppError> {
+    Ok(GlobalConfig::load(&app).org)
+}
+
 #[tauri::command]
 pub fn get_app_settings(app: AppHandle) -> Result<String, AppError> {
     let config = GlobalConfig::load(&app);
@@ -174,4 +435,30 @@ pub fn save_app_settings(settings: String, app: AppHandle) -> Result<(), AppErro
     global_config.app_settings = Some(settings);
     global_config.save(&app).map_err(|e| AppError::Unknown(e))?;
     Ok(())
-}
\ No newline at end of file
+}
+/// Write agents, components and settings (without secrets) to one bundle
+/// file; `dest` defaults to `Documents/Synnia`.
+#[tauri::command]
+pub fn export_settings_bundle(dest: Option<String>, app: AppHandle) -> Result<BundleReport, AppError> {
+    let dest = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => {
+            let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
+            let name = format!("Synnia Settings {}.json", chrono::Local::now().format("%Y-%m-%d"));
+            docs_dir.join("Synnia").join(name)
+        }
+    };
+    let config = GlobalConfig::load(&app);
+    settings_bundle::export_bundle(&config, &get_agents_dir(&app)?, &get_components_dir(&app)?, &dest)
+}
+
+/// Merge a settings bundle into this machine's setup. Local secrets are kept.
+#[tauri::command]
+pub fn import_settings_bundle(file: String, app: AppHandle) -> Result<BundleReport, AppError> {
+    let path = PathBuf::from(&file);
+    let bundle = settings_bundle::read_bundle(&path)?;
+    let mut config = GlobalConfig::load(&app);
+    settings_bundle::apply_bundle(&bundle, &mut config, &get_agents_dir(&app)?, &get_components_dir(&app)?)?;
+    config.save(&app).map_err(AppError::Unknown)?;
+    Ok(settings_bundle::report(&bundle, &path))
+}
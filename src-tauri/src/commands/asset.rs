@@ -2,15 +2,34 @@
 
 use tauri::{State, AppHandle};
 use crate::error::AppError;
+use crate::events::{self, AppEvent};
 use crate::AppState;
-use crate::services::{database, io_sqlite};
-use std::path::PathBuf;
-use std::io::Cursor;
-use base64::Engine;
-use image::ImageReader;
+use crate::config::GlobalConfig;
+use crate::models::Asset;
+use crate::services::{asset_rename, asset_usage, audio_trim, batch_import, bulk_edit, database, disk_space, download, faces, fs_paths, image_maps, io_sqlite, linked_files, references, safety, upscale, validation, video_proxy};
+use crate::services::linked_files::{BrokenLink, RelinkReport};
+use crate::services::asset_rename::RenamedAsset;
+use crate::services::asset_usage::AssetUsageReport;
+use crate::services::audio_trim::TrimResult;
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::batch_import::{ImportTask, ImportedImage};
+use crate::services::bulk_edit::AssetPatch;
+use crate::services::download::DownloadOptions;
+use crate::services::folder_import::{self, FolderImport, ImportFolderOptions, ImportProgress};
+use crate::services::job_queue::Job;
+use crate::services::image_maps::{MapKind, MapRunner, MapTask, MapsPayload};
+use crate::services::upscale::UpscalePayload;
+use crate::services::video_proxy::ProxyTask;
+use super::jobs::{spawn_local_job, submit_job, JobInfo};
+use super::safety::check_new_images;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::imaging::{decode_base64_image, detect_image_format, generate_animation_preview, generate_thumbnail, get_image_dimensions, ImageAnimation};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
 
 /// Info for a media asset (for library view)
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaAssetInfo {
     pub id: String,
@@ -20,12 +39,17 @@ pub struct MediaAssetInfo {
     pub thumbnail_path: Option<String>,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    /// Frame count, duration and looping preview of animated GIF/WebP
+    pub animation: Option<ImageAnimation>,
+    #[ts(type = "number")]
     pub created_at: i64,
+    #[ts(type = "number")]
     pub updated_at: i64,
 }
 
 /// Response from save_image_file command
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveImageResult {
     /// Relative path to the saved image (e.g., "assets/xxx.png")
@@ -36,77 +60,144 @@ pub struct SaveImageResult {
     pub width: u32,
     /// Image height
     pub height: u32,
+    /// Frame count, duration and looping preview (animated GIF/WebP only)
+    pub animation: Option<ImageAnimation>,
+    /// Name of the file as the user had it, for the asset title
+    pub original_name: Option<String>,
 }
 
 /// Import a file from the file system into the project assets folder.
+/// With `link`, the file stays where it is and is linked in instead of copied.
 #[tauri::command]
-pub fn import_file(file_path: String, state: State<AppState>, _app: AppHandle) -> Result<SaveImageResult, AppError> {
+pub async fn import_file(file_path: String, link: Option<bool>, state: State<'_, AppState>, app: AppHandle) -> Result<SaveImageResult, AppError> {
     let project_root = get_project_root(&state)?;
-    
-    let source_path = PathBuf::from(&file_path);
-    if !source_path.exists() {
-        return Err(AppError::NotFound(format!("File not found: {}", file_path)));
-    }
+    let source_path = validation::check_path(&file_path)?;
+    state.blocking.run(move || {
+        if !source_path.exists() {
+            return Err(AppError::NotFound(format!("File not found: {}", file_path)));
+        }
 
-    // Create assets directory if it doesn't exist
-    let assets_dir = project_root.join("assets");
-    if !assets_dir.exists() {
-        std::fs::create_dir_all(&assets_dir)?;
-    }
+        // Create assets directory if it doesn't exist
+        let assets_dir = project_root.join("assets");
+        if !assets_dir.exists() {
+            std::fs::create_dir_all(&assets_dir)?;
+        }
 
-    let ext = source_path.extension().and_then(|s| s.to_str()).unwrap_or("bin");
-    let file_id = uuid::Uuid::new_v4().to_string();
-    let new_filename = format!("{}.{}", file_id, ext);
-    let relative_path = format!("assets/{}", new_filename);
-    let target_path = project_root.join(&relative_path);
-    
-    println!("[Asset] Copying from {:?} to {:?}", source_path, target_path);
-    std::fs::copy(&source_path, &target_path)?;
+        let ext = fs_paths::extension(&source_path).unwrap_or_else(|| "bin".to_string());
+        let (file_id, relative_path) = if link.unwrap_or(false) {
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            linked_files::link_file(&conn, &project_root, &source_path)?
+        } else {
+            let file_id = uuid::Uuid::new_v4().to_string();
+            let relative_path = format!("assets/{}.{}", file_id, ext);
+            let target_path = validation::resolve_in_project(&project_root, &relative_path)?;
+            disk_space::ensure_space(&app, &assets_dir, disk_space::files_size(&[&source_path]))?;
+            
+            println!("[Asset] Copying from {:?} to {:?}", source_path, target_path);
+            std::fs::copy(fs_paths::long_path(&source_path), &target_path)?;
+            (file_id, relative_path)
+        };
+        let target_path = project_root.join(&relative_path);
+        let file_name = fs_paths::file_name(&source_path);
+        activity::log(&project_root, NewActivity::new(ActivityKind::Imported, "user", format!("Imported {}", file_name)));
 
-    // Check if it's an image and generate thumbnail
-    let is_image = matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp");
-    
-    if is_image {
-        let image_data = std::fs::read(&target_path)?;
+        // Check if it's an image and generate thumbnail
+        let is_image = matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp");
+        
+        if is_image {
+            let image_data = std::fs::read(&target_path)?;
+            let (width, height) = get_image_dimensions(&image_data)?;
+            let thumbnail_path = generate_thumbnail(&project_root, &file_id, &image_data)?;
+            let animation = generate_animation_preview(&project_root, &file_id, &image_data).ok().flatten();
+        
+            Ok(SaveImageResult {
+                relative_path,
+                thumbnail_path: Some(thumbnail_path),
+                width,
+                height,
+                animation,
+                original_name: Some(file_name),
+            })
+        } else {
+            Ok(SaveImageResult {
+                relative_path,
+                thumbnail_path: None,
+                width: 0,
+                height: 0,
+                animation: None,
+                original_name: Some(file_name),
+            })
+        }
+    }).await
+}
+
+/// Save a processed image from base64 data.
+/// This is called after image editing (crop, rotate, bg removal, etc.)
+#[tauri::command]
+pub async fn save_processed_image(
+    base64_data: String,
+    filename: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SaveImageResult, AppError> {
+    let project_root = get_project_root(&state)?;
+    validation::check_base64_size(&base64_data, validation::MAX_IMAGE_BYTES)?;
+    if let Some(name) = &filename {
+        validation::check_file_name(name)?;
+    }
+    state.blocking.run(move || {
+        // Decode base64
+        let image_data = decode_base64_image(&base64_data)?;
+        
+        // Get image dimensions
         let (width, height) = get_image_dimensions(&image_data)?;
+        
+        // Generate unique filename
+        let file_id = uuid::Uuid::new_v4().to_string();
+        let ext = detect_image_format(&image_data).unwrap_or("png");
+        let final_filename = filename.clone().unwrap_or_else(|| format!("{}.{}", file_id, ext));
+        
+        // Ensure assets directory exists
+        let assets_dir = project_root.join("assets");
+        if !assets_dir.exists() {
+            std::fs::create_dir_all(&assets_dir)?;
+        }
+        
+        // Save the image
+        let relative_path = format!("assets/{}", final_filename);
+        let target_path = validation::resolve_in_project(&project_root, &relative_path)?;
+        std::fs::write(&target_path, &image_data)?;
+        
+        // Generate thumbnail (and animated preview for GIF/WebP)
         let thumbnail_path = generate_thumbnail(&project_root, &file_id, &image_data)?;
+        let animation = generate_animation_preview(&project_root, &file_id, &image_data).ok().flatten();
         
         Ok(SaveImageResult {
             relative_path,
             thumbnail_path: Some(thumbnail_path),
             width,
             height,
+            animation,
+            original_name: filename,
         })
-    } else {
-        Ok(SaveImageResult {
-            relative_path,
-            thumbnail_path: None,
-            width: 0,
-            height: 0,
-        })
-    }
+    }).await
 }
 
-/// Save a processed image from base64 data.
-/// This is called after image editing (crop, rotate, bg removal, etc.)
+/// Download an image from a URL and save it to the assets folder.
+/// This is used for AI-generated images that are returned as HTTP URLs.
+/// Progress is reported via `download:progress` events under `download_id`.
 #[tauri::command]
-pub fn save_processed_image(
-    base64_data: String,
+pub async fn download_and_save_image(
+    url: String,
     filename: Option<String>,
-    state: State<AppState>,
+    download_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<SaveImageResult, AppError> {
     let project_root = get_project_root(&state)?;
-    
-    // Decode base64
-    let image_data = decode_base64_image(&base64_data)?;
-    
-    // Get image dimensions
-    let (width, height) = get_image_dimensions(&image_data)?;
-    
-    // Generate unique filename
-    let file_id = uuid::Uuid::new_v4().to_string();
-    let ext = detect_image_format(&image_data).unwrap_or("png");
-    let final_filename = filename.unwrap_or_else(|| format!("{}.{}", file_id, ext));
+    if let Some(name) = &filename {
+        validation::check_file_name(name)?;
+    }
     
     // Ensure assets directory exists
     let assets_dir = project_root.join("assets");
@@ -114,77 +205,303 @@ pub fn save_processed_image(
         std::fs::create_dir_all(&assets_dir)?;
     }
     
-    // Save the image
+    // Download via the manager (policy check, retry/resume, size limit)
+    let file_id = uuid::Uuid::new_v4().to_string();
+    let download_id = download_id.unwrap_or_else(|| file_id.clone());
+    let temp_path = assets_dir.join(format!("{}.download", file_id));
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    
+    state.downloads
+        .download(&app, &policy, &download_id, &url, &temp_path, &DownloadOptions::default())
+        .await?;
+    
+    let image_data = std::fs::read(&temp_path)?;
+    
+    // Get image dimensions
+    let (width, height) = match get_image_dimensions(&image_data) {
+        Ok(dims) => dims,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+    
+    // Generate unique filename
+    let ext = detect_image_format(&image_data).unwrap_or("png");
+    let final_filename = filename.clone().unwrap_or_else(|| format!("{}.{}", file_id, ext));
+    
+    // Move the image into place
     let relative_path = format!("assets/{}", final_filename);
-    let target_path = project_root.join(&relative_path);
-    std::fs::write(&target_path, &image_data)?;
+    let target_path = match validation::resolve_in_project(&project_root, &relative_path) {
+        Ok(path) => path,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&temp_path, &target_path)?;
     
-    // Generate thumbnail
+    // Generate thumbnail (and animated preview for GIF/WebP)
     let thumbnail_path = generate_thumbnail(&project_root, &file_id, &image_data)?;
+    let animation = generate_animation_preview(&project_root, &file_id, &image_data).ok().flatten();
     
     Ok(SaveImageResult {
         relative_path,
         thumbnail_path: Some(thumbnail_path),
         width,
         height,
+        animation,
+        original_name: filename,
     })
 }
 
-/// Download an image from a URL and save it to the assets folder.
-/// This is used for AI-generated images that are returned as HTTP URLs.
+/// Response from download_asset_file command
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadFileResult {
+    /// Relative path to the saved file (e.g., "assets/xxx.mp4")
+    pub relative_path: String,
+    /// File size in bytes
+    #[ts(type = "number")]
+    pub size: u64,
+    pub content_type: Option<String>,
+}
+
+/// Download any remote file (e.g. AI video output) into the assets folder.
+/// Supports resume on flaky connections, size limits and cancellation.
 #[tauri::command]
-pub async fn download_and_save_image(
+pub async fn download_asset_file(
     url: String,
-    filename: Option<String>,
+    download_id: String,
+    extension: Option<String>,
+    max_bytes: Option<u64>,
     state: State<'_, AppState>,
-) -> Result<SaveImageResult, AppError> {
+    app: AppHandle,
+) -> Result<DownloadFileResult, AppError> {
     let project_root = get_project_root(&state)?;
     
-    // Download the image
-    let response = reqwest::get(&url).await
-        .map_err(|e| AppError::Unknown(format!("Failed to download image: {}", e)))?;
-    
-    if !response.status().is_success() {
-        return Err(AppError::Unknown(format!("HTTP error: {}", response.status())));
-    }
-    
-    let image_data = response.bytes().await
-        .map_err(|e| AppError::Unknown(format!("Failed to read response: {}", e)))?;
-    
-    // Get image dimensions
-    let (width, height) = get_image_dimensions(&image_data)?;
-    
-    // Generate unique filename
-    let file_id = uuid::Uuid::new_v4().to_string();
-    let ext = detect_image_format(&image_data).unwrap_or("png");
-    let final_filename = filename.unwrap_or_else(|| format!("{}.{}", file_id, ext));
-    
-    // Ensure assets directory exists
     let assets_dir = project_root.join("assets");
     if !assets_dir.exists() {
         std::fs::create_dir_all(&assets_dir)?;
     }
     
-    // Save the image
-    let relative_path = format!("assets/{}", final_filename);
-    let target_path = project_root.join(&relative_path);
-    std::fs::write(&target_path, &image_data)?;
+    let ext = extension
+        .or_else(|| {
+            reqwest::Url::parse(&url).ok()
+                .and_then(|u| u.path_segments()?.next_back().map(|s| s.to_string()))
+                .and_then(|name| name.rsplit_once('.').map(|(_, e)| e.to_lowercase()))
+        })
+        .filter(|e| !e.is_empty() && e.len() <= 8 && e.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "bin".to_string());
+    
+    let relative_path = format!("assets/{}.{}", uuid::Uuid::new_v4(), ext);
+    let target_path = validation::resolve_in_project(&project_root, &relative_path)?;
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    let options = DownloadOptions {
+        max_bytes: max_bytes.unwrap_or(download::DEFAULT_MAX_BYTES),
+        ..Default::default()
+    };
     
-    // Generate thumbnail
-    let thumbnail_path = generate_thumbnail(&project_root, &file_id, &image_data)?;
+    let outcome = state.downloads
+        .download(&app, &policy, &download_id, &url, &target_path, &options)
+        .await?;
     
-    Ok(SaveImageResult {
+    Ok(DownloadFileResult {
         relative_path,
-        thumbnail_path: Some(thumbnail_path),
-        width,
-        height,
+        size: outcome.bytes,
+        content_type: outcome.content_type,
+    })
+}
+
+/// Cancel a running download by ID. Returns false if it was not running.
+#[tauri::command]
+pub fn cancel_download(download_id: String, state: State<AppState>) -> Result<bool, AppError> {
+    Ok(state.downloads.cancel(&download_id))
+}
+
+/// List IDs of downloads that are queued or running.
+#[tauri::command]
+pub fn get_active_downloads(state: State<AppState>) -> Result<Vec<String>, AppError> {
+    Ok(state.downloads.active_ids())
+}
+
+/// Import a folder tree as nested groups with the files laid out in grids.
+/// Progress is reported via `import:progress` events.
+#[tauri::command]
+pub async fn import_folder(
+    path: String,
+    options: Option<ImportFolderOptions>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<FolderImport, AppError> {
+    let project_root = get_project_root(&state)?;
+    let options = options.unwrap_or_default();
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = PathBuf::from(&path);
+        if !options.link {
+            disk_space::ensure_space(&app, &project_root.join("assets"), folder_import::estimated_size(&project_root, &path, &options)?)?;
+        }
+        folder_import::import_folder(&project_root, &path, &options, |progress| {
+            let _ = events::emit(&app, AppEvent::ImportProgress(progress.clone()));
+        })
     })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Folder import failed: {}", e)))?
+}
+
+/// Transcode low-bitrate proxies of large videos (or of `asset_ids`) in the
+/// background with ffmpeg. Progress and the result arrive as `job:progress`
+/// and `job:completed` events.
+#[tauri::command]
+pub fn generate_video_proxies(
+    asset_ids: Option<Vec<String>>,
+    min_bytes: Option<u64>,
+    force: Option<bool>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_root = get_project_root(&state)?;
+    let ffmpeg = video_proxy::find_ffmpeg()
+        .ok_or_else(|| AppError::NotFound("ffmpeg not found; install it or set SYNNIA_FFMPEG".to_string()))?;
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let tasks = video_proxy::pending_tasks(
+        &conn,
+        &project_root,
+        asset_ids.as_deref(),
+        min_bytes.unwrap_or(video_proxy::DEFAULT_MIN_BYTES),
+        force.unwrap_or(false),
+    )?;
+    
+    // ffmpeg is multi-threaded already; one transcode at a time
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(video_proxy::JOB_KIND, serde_json::Value::Null, params, 1);
+    let root = project_root.clone();
+    
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| {
+            let task: ProxyTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+            video_proxy::generate_proxy(&ffmpeg, &root, &task).map(serde_json::Value::from)
+        },
+        move |job| {
+            let proxies: Vec<(String, String)> = job.tasks.iter()
+                .filter_map(|t| {
+                    let task: ProxyTask = serde_json::from_value(t.params.clone()).ok()?;
+                    Some((task.asset_id, t.result.as_ref()?.as_str()?.to_string()))
+                })
+                .collect();
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            video_proxy::record_proxies(&conn, &proxies)?;
+            Ok(serde_json::json!({ "proxies": proxies.len() }))
+        },
+    ))
+}
+
+/// Cut `start_ms..end_ms` of an audio asset into a new asset (placed next
+/// to `node_id` when given).
+#[tauri::command]
+pub async fn trim_audio(
+    asset_id: String,
+    start_ms: u64,
+    end_ms: u64,
+    node_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TrimResult, AppError> {
+    let project_root = get_project_root(&state)?;
+    state.blocking.run(move || {
+        audio_trim::trim_audio(&project_root, &asset_id, start_ms, end_ms, node_id.as_deref())
+    }).await
+}
+
+/// Upscale an image asset by `factor` (2-4), saving the result as a new
+/// version of the asset. The "local" provider runs the ESRGAN model in the
+/// background; other providers are dispatched to the frontend as `job:task`.
+/// The updated asset arrives with `job:completed`.
+#[tauri::command]
+pub fn upscale_image(
+    asset_id: String,
+    factor: u32,
+    provider: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_root = get_project_root(&state)?;
+    let payload = UpscalePayload {
+        asset_id,
+        factor,
+        provider: provider.unwrap_or_else(|| upscale::LOCAL_PROVIDER.to_string()),
+    };
+    let task = upscale::build_task(&project_root, &payload)?;
+    let job = Job::new(upscale::JOB_KIND, serde_json::to_value(&payload)?, vec![task], 1);
+    
+    if payload.provider != upscale::LOCAL_PROVIDER {
+        return submit_job(&app, &state, job);
+    }
+    
+    let upscaler = upscale::find_local_upscaler().ok_or_else(|| AppError::NotFound(
+        "Local upscaler not found; set SYNNIA_UPSCALER and download an upscale model (or set SYNNIA_ESRGAN_MODEL)".to_string()
+    ))?;
+    let root = project_root.clone();
+    let handle = app.clone();
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| upscale::upscale_local(&upscaler, &root, params),
+        move |job| {
+            let output = serde_json::to_value(upscale::finalize_upscale(&project_root, job)?)?;
+            check_new_images(&handle, project_root);
+            Ok(output)
+        },
+    ))
+}
+
+/// Derive depth maps and/or segmentation masks of an image asset with the
+/// local models, in the background. Each map becomes a linked image asset
+/// (stacked beside `node_id` when given), delivered with `job:completed`.
+#[tauri::command]
+pub fn generate_image_maps(
+    asset_id: String,
+    kinds: Vec<MapKind>,
+    node_id: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_root = get_project_root(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let tasks = image_maps::build_tasks(&conn, &project_root, &asset_id, &kinds)?;
+    let runner = MapRunner::find(&kinds)?;
+    
+    // Each model already uses every core; run them one at a time
+    let payload = MapsPayload { asset_id, node_id };
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(image_maps::JOB_KIND, serde_json::to_value(&payload)?, params, 1);
+    let root = project_root.clone();
+    
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| {
+            let task: MapTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+            runner.run(&root, &task).map(serde_json::Value::from)
+        },
+        move |job| Ok(serde_json::to_value(image_maps::finalize_maps(&project_root, job)?)?),
+    ))
 }
 
 /// Get all media assets (images, videos, audio) for the asset library.
 /// Excludes text and json types.
 #[tauri::command]
-pub fn get_media_assets(state: State<AppState>) -> Result<Vec<MediaAssetInfo>, AppError> {
+pub async fn get_media_assets(state: State<'_, AppState>) -> Result<Vec<MediaAssetInfo>, AppError> {
     let project_path = {
         let path_guard = state.current_project_path.lock()
             .map_err(|_| AppError::Unknown("Path Lock Poisoned".to_string()))?;
@@ -192,86 +509,175 @@ pub fn get_media_assets(state: State<AppState>) -> Result<Vec<MediaAssetInfo>, A
     };
     
     let project_root = PathBuf::from(&project_path);
-    let db_path = io_sqlite::get_db_path(&project_root);
-    
-    let conn = database::open_db(&db_path)
-        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
-    
-    // Query all assets that are not text or record (form)
-    let mut stmt = conn.prepare(
-        "SELECT id, value_type, value_json, value_meta_json, sys_json, updated_at 
-         FROM assets 
-         WHERE value_type NOT IN ('text', 'record')
-         ORDER BY updated_at DESC"
-    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
-    
-    let assets = stmt.query_map([], |row| {
-        let id: String = row.get(0)?;
-        let asset_type: String = row.get(1)?;
-        let value_json: String = row.get(2)?;
-        let value_meta_json: Option<String> = row.get(3)?;
-        let sys_json: String = row.get(4)?;
-        let updated_at: i64 = row.get(5)?;
-        Ok((id, asset_type, value_json, value_meta_json, sys_json, updated_at))
-    }).map_err(|e| AppError::Io(format!("Failed to query assets: {}", e)))?;
-    
-    let mut result = Vec::new();
-    
-    for asset in assets {
-        let (id, asset_type, value_json, value_meta_json, sys_json, updated_at) = 
-            asset.map_err(|e| AppError::Io(format!("Failed to read asset: {}", e)))?;
+    state.blocking.run(move || {
+        let db_path = io_sqlite::get_db_path(&project_root);
         
-        // Parse value (could be string path or object with src)
-        let content: String = serde_json::from_str(&value_json)
-            .unwrap_or_else(|_| value_json.trim_matches('"').to_string());
+        let conn = database::open_db(&db_path)
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
         
-        // Parse sys metadata for name and createdAt
-        let sys: serde_json::Value = serde_json::from_str(&sys_json)
-            .unwrap_or_else(|_| serde_json::json!({}));
+        // Query all assets that are not text or record (form), leaving out
+        // quarantined and rejected images
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, value_type, value_json, value_meta_json, sys_json, updated_at 
+             FROM assets a
+             WHERE value_type NOT IN ('text', 'record')
+               AND NOT EXISTS (SELECT 1 FROM image_reviews r
+                               WHERE {} AND r.status IN ('quarantined', 'rejected'))
+             ORDER BY updated_at DESC",
+            safety::ASSET_SRC_MATCH
+        )).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
         
-        let name = sys.get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Unnamed")
-            .to_string();
+        let assets = stmt.query_map([], |row| {
+            let id: String = row.get(0)?;
+            let asset_type: String = row.get(1)?;
+            let value_json: String = row.get(2)?;
+            let value_meta_json: Option<String> = row.get(3)?;
+            let sys_json: String = row.get(4)?;
+            let updated_at: i64 = row.get(5)?;
+            Ok((id, asset_type, value_json, value_meta_json, sys_json, updated_at))
+        }).map_err(|e| AppError::Io(format!("Failed to query assets: {}", e)))?;
         
-        let created_at = sys.get("createdAt")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(updated_at);
+        let mut result = Vec::new();
         
-        // Parse valueMeta for image metadata
-        let value_meta: serde_json::Value = value_meta_json
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_else(|| serde_json::json!({}));
+        for asset in assets {
+            let (id, asset_type, value_json, value_meta_json, sys_json, updated_at) = 
+                asset.map_err(|e| AppError::Io(format!("Failed to read asset: {}", e)))?;
         
-        let thumbnail_path = value_meta
-            .get("preview")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+            // Parse value (could be string path or object with src)
+            let content: String = serde_json::from_str(&value_json)
+                .unwrap_or_else(|_| value_json.trim_matches('"').to_string());
         
-        let width = value_meta
-            .get("width")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u32);
+            // Parse sys metadata for name and createdAt
+            let sys: serde_json::Value = serde_json::from_str(&sys_json)
+                .unwrap_or_else(|_| serde_json::json!({}));
         
-        let height = value_meta
-            .get("height")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u32);
+            let name = sys.get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unnamed")
+                .to_string();
         
-        result.push(MediaAssetInfo {
-            id,
-            asset_type,
-            name,
-            content,
-            thumbnail_path,
-            width,
-            height,
-            created_at,
-            updated_at,
-        });
-    }
-    
-    Ok(result)
+            let created_at = sys.get("createdAt")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(updated_at);
+        
+            // Parse valueMeta for image metadata
+            let value_meta: serde_json::Value = value_meta_json
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+        
+            let thumbnail_path = value_meta
+                .get("preview")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+        
+            let width = value_meta
+                .get("width")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+        
+            let height = value_meta
+                .get("height")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+        
+            let animation = value_meta
+                .get("animation")
+                .and_then(|v| serde_json::from_value(v.clone()).ok());
+        
+            result.push(MediaAssetInfo {
+                id,
+                asset_type,
+                name,
+                content,
+                thumbnail_path,
+                width,
+                height,
+                animation,
+                created_at,
+                updated_at,
+            });
+        }
+        
+        Ok(result)
+    }).await
+}
+
+/// Usage of every asset (nodes, recipe inputs, disk size), for the cleanup UI.
+#[tauri::command]
+pub async fn get_asset_usage_report(state: State<'_, AppState>) -> Result<AssetUsageReport, AppError> {
+    let project_root = get_project_root(&state)?;
+    state.blocking.run(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        asset_usage::get_asset_usage_report(&conn, &project_root)
+    }).await
+}
+
+/// Linked files whose originals can't be reached (also announced on load
+/// as `assets:broken-links`).
+#[tauri::command]
+pub async fn get_broken_links(state: State<'_, AppState>) -> Result<Vec<BrokenLink>, AppError> {
+    let project_root = get_project_root(&state)?;
+    state.blocking.run(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        linked_files::broken_links(&conn, &project_root)
+    }).await
+}
+
+/// Repoint linked files from `old_prefix` to `new_prefix`, e.g. after
+/// footage moved to another drive or the NAS is mounted elsewhere.
+#[tauri::command]
+pub async fn relink_assets(
+    old_prefix: String,
+    new_prefix: String,
+    state: State<'_, AppState>,
+) -> Result<RelinkReport, AppError> {
+    let project_root = get_project_root(&state)?;
+    validation::check_path(&old_prefix)?;
+    validation::check_path(&new_prefix)?;
+    state.blocking.run(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        linked_files::relink(&conn, &project_root, &old_prefix, &new_prefix)
+    }).await
+}
+
+/// Rename an asset; with `rename_file`, also its file on disk (named after
+/// the asset plus a content hash) and everything pointing at it.
+#[tauri::command]
+pub async fn rename_asset(
+    asset_id: String,
+    new_name: String,
+    rename_file: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<RenamedAsset, AppError> {
+    let project_root = get_project_root(&state)?;
+    state.blocking.run(move || {
+        let mut conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        let renamed = asset_rename::rename_asset(&mut conn, &project_root, &asset_id, &new_name, rename_file.unwrap_or(false))?;
+        for id in &renamed.updated_asset_ids {
+            references::notify_asset_updated(&conn, id)?;
+        }
+        Ok(renamed)
+    }).await
+}
+
+/// Rename, retag and set custom fields of many assets in one transaction,
+/// recorded as a single activity entry. Returns the updated assets.
+#[tauri::command]
+pub async fn bulk_update_assets(
+    asset_ids: Vec<String>,
+    patch: AssetPatch,
+    state: State<'_, AppState>,
+) -> Result<Vec<Asset>, AppError> {
+    let project_root = get_project_root(&state)?;
+    state.blocking.run(move || {
+        let mut conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        bulk_edit::bulk_update(&mut conn, &asset_ids, &patch)
+    }).await
 }
 
 // ============================================
@@ -295,86 +701,31 @@ fn get_project_root(state: &State<AppState>) -> Result<PathBuf, AppError> {
     }
 }
 
-/// Decode base64 image data, handling data URI prefix
-fn decode_base64_image(data: &str) -> Result<Vec<u8>, AppError> {
-    let base64_str = if data.contains(",") {
-        // Data URI format: "data:image/png;base64,xxxxx"
-        data.split(",").nth(1).unwrap_or(data)
-    } else {
-        data
-    };
-    
-    base64::engine::general_purpose::STANDARD
-        .decode(base64_str)
-        .map_err(|e| AppError::Unknown(format!("Failed to decode base64: {}", e)))
-}
-
-/// Get image dimensions from raw bytes
-fn get_image_dimensions(data: &[u8]) -> Result<(u32, u32), AppError> {
-    let reader = ImageReader::new(Cursor::new(data))
-        .with_guessed_format()
-        .map_err(|e| AppError::Unknown(format!("Failed to read image: {}", e)))?;
-    
-    let dimensions = reader.into_dimensions()
-        .map_err(|e| AppError::Unknown(format!("Failed to get image dimensions: {}", e)))?;
-    
-    Ok(dimensions)
-}
-
-/// Detect image format from raw bytes
-fn detect_image_format(data: &[u8]) -> Option<&'static str> {
-    if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
-        Some("png")
-    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
-        Some("jpg")
-    } else if data.starts_with(b"GIF") {
-        Some("gif")
-    } else if data.starts_with(b"RIFF") && data.len() > 12 && &data[8..12] == b"WEBP" {
-        Some("webp")
-    } else {
-        None
-    }
-}
-
-/// Generate a thumbnail for an image
-fn generate_thumbnail(project_root: &PathBuf, file_id: &str, image_data: &[u8]) -> Result<String, AppError> {
-    const THUMBNAIL_SIZE: u32 = 200;
-    
-    let img = image::load_from_memory(image_data)
-        .map_err(|e| AppError::Unknown(format!("Failed to load image for thumbnail: {}", e)))?;
-    
-    // Resize to thumbnail (preserving aspect ratio)
-    let thumbnail = img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
-    
-    // Save thumbnail as JPEG (smaller file size)
-    let thumb_filename = format!("thumb_{}.jpg", file_id);
-    let thumb_relative = format!("assets/{}", thumb_filename);
-    let thumb_path = project_root.join(&thumb_relative);
-    
-    thumbnail.save(&thumb_path)
-        .map_err(|e| AppError::Unknown(format!("Failed to save thumbnail: {}", e)))?;
-    
-    Ok(thumb_relative)
-}
-
 /// Result for a single file in batch import
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchImportResult {
     /// Original file path
     pub source_path: String,
     /// Success result (if import succeeded)
     pub result: Option<SaveImageResult>,
+    /// SHA-256 of the file (if import succeeded)
+    pub content_hash: Option<String>,
     /// Error message (if import failed)
     pub error: Option<String>,
 }
 
 /// Import multiple files from the file system into the project assets folder.
+/// Files are processed in parallel as a `batch-import` job (cancellable via
+/// `cancel_job`); progress is reported via `import:progress` events.
 /// Returns results for each file, including any errors.
 #[tauri::command]
-pub fn batch_import_images(
+pub async fn batch_import_images(
     file_paths: Vec<String>,
-    state: State<AppState>,
+    concurrency: Option<usize>,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<Vec<BatchImportResult>, AppError> {
     let project_root = get_project_root(&state)?;
     
@@ -384,79 +735,85 @@ pub fn batch_import_images(
         std::fs::create_dir_all(&assets_dir)?;
     }
     
-    let mut results: Vec<BatchImportResult> = Vec::with_capacity(file_paths.len());
-    
-    for file_path in file_paths {
-        let source_path = PathBuf::from(&file_path);
-        
-        // Check if file exists
-        if !source_path.exists() {
-            results.push(BatchImportResult {
-                source_path: file_path,
+    disk_space::ensure_space(&app, &assets_dir, disk_space::files_size(&file_paths))?;
+    
+    let params = file_paths.iter()
+        .map(|p| serde_json::to_value(ImportTask { source_path: p.clone() }))
+        .collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(
+        batch_import::JOB_KIND,
+        serde_json::Value::Null,
+        params,
+        concurrency.unwrap_or_else(batch_import::default_concurrency),
+    );
+    let queue = state.jobs.clone();
+    let root = project_root.clone();
+    let face_detector = faces::import_detector(&project_root);
+    let classifier = safety::auto_classifier(&project_root);
+    
+    let job = tauri::async_runtime::spawn_blocking(move || {
+        let total = job.tasks.len();
+        let mut current = 0;
+        queue.run_local(
+            job,
+            |params| {
+                let task: ImportTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+                let image = batch_import::import_image(&root, Path::new(&task.source_path))?;
+                if let Some(detector) = &face_detector {
+                    // Best effort: a face scan failure doesn't fail the import
+                    let _ = detector.detect_and_store(&root, &image.relative_path);
+                }
+                if let Some(classifier) = &classifier {
+                    // Unchecked images are picked up by the next scan
+                    let _ = classifier.check(&root, &image.relative_path);
+                }
+                serde_json::to_value(image).map_err(|e| e.to_string())
+            },
+            |_, task| {
+                current += 1;
+                let file = task.params.get("sourcePath").and_then(|p| p.as_str()).unwrap_or_default().to_string();
+                let _ = events::emit(&app, AppEvent::ImportProgress(ImportProgress { current, total, file }));
+            },
+        )
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Batch import failed: {}", e)))??;
+    
+    let results: Vec<BatchImportResult> = file_paths.into_iter()
+        .zip(job.tasks)
+        .map(|(source_path, task)| match task.result.map(serde_json::from_value::<ImportedImage>) {
+            Some(Ok(image)) => BatchImportResult {
+                source_path,
+                result: Some(SaveImageResult {
+                    relative_path: image.relative_path,
+                    thumbnail_path: image.thumbnail_path,
+                    width: image.width,
+                    height: image.height,
+                    animation: image.animation,
+                    original_name: Some(image.original_name),
+                }),
+                content_hash: Some(image.content_hash),
+                error: None,
+            },
+            Some(Err(e)) => BatchImportResult {
+                source_path,
                 result: None,
-                error: Some("File not found".to_string()),
-            });
-            continue;
-        }
-        
-        // Get extension and generate new filename
-        let ext = source_path.extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("bin")
-            .to_lowercase();
-        
-        // Skip non-image files
-        if !matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp") {
-            results.push(BatchImportResult {
-                source_path: file_path,
+                content_hash: None,
+                error: Some(e.to_string()),
+            },
+            None => BatchImportResult {
+                source_path,
                 result: None,
-                error: Some(format!("Unsupported image format: {}", ext)),
-            });
-            continue;
-        }
-        
-        let file_id = uuid::Uuid::new_v4().to_string();
-        let new_filename = format!("{}.{}", file_id, ext);
-        let relative_path = format!("assets/{}", new_filename);
-        let target_path = project_root.join(&relative_path);
-        
-        // Copy file
-        match std::fs::copy(&source_path, &target_path) {
-            Ok(_) => {
-                // Read image and generate thumbnail
-                match std::fs::read(&target_path) {
-                    Ok(image_data) => {
-                        let (width, height) = get_image_dimensions(&image_data).unwrap_or((0, 0));
-                        let thumbnail_path = generate_thumbnail(&project_root, &file_id, &image_data).ok();
-                        
-                        results.push(BatchImportResult {
-                            source_path: file_path,
-                            result: Some(SaveImageResult {
-                                relative_path,
-                                thumbnail_path,
-                                width,
-                                height,
-                            }),
-                            error: None,
-                        });
-                    }
-                    Err(e) => {
-                        results.push(BatchImportResult {
-                            source_path: file_path,
-                            result: None,
-                            error: Some(format!("Failed to read image: {}", e)),
-                        });
-                    }
-                }
-            }
-            Err(e) => {
-                results.push(BatchImportResult {
-                    source_path: file_path,
-                    result: None,
-                    error: Some(format!("Failed to copy file: {}", e)),
-                });
-            }
-        }
+                content_hash: None,
+                error: task.error,
+            },
+        })
+        .collect();
+    
+    let imported = results.iter().filter(|r| r.result.is_some()).count();
+    if imported > 0 {
+        let summary = format!("Imported {} image{}", imported, if imported == 1 { "" } else { "s" });
+        activity::log(&project_root, NewActivity::new(ActivityKind::Imported, "user", summary));
     }
     
     Ok(results)
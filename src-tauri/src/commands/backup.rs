@@ -0,0 +1,87 @@
+//! Backup commands: settings, manual runs, listing and restoring archives.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::backup::{self, BackupInfo, BackupSettings};
+use crate::services::io_sqlite;
+
+#[tauri::command]
+pub fn get_backup_settings(app: AppHandle) -> Result<BackupSettings, AppError> {
+    Ok(GlobalConfig::load(&app).backup.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn save_backup_settings(settings: BackupSettings, app: AppHandle) -> Result<(), AppError> {
+    let mut config = GlobalConfig::load(&app);
+    config.backup = Some(settings);
+    config.save(&app).map_err(AppError::Unknown)
+}
+
+/// All backup archives, newest first.
+#[tauri::command]
+pub fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, AppError> {
+    let settings = GlobalConfig::load(&app).backup.unwrap_or_default();
+    backup::list_backups(&backup::backup_dir(&app, &settings)?)
+}
+
+/// Back up every recent project now, regardless of schedule.
+#[tauri::command]
+pub fn run_backups_now(app: AppHandle) -> Result<Vec<BackupInfo>, AppError> {
+    let config = GlobalConfig::load(&app);
+    let settings = config.backup.clone().unwrap_or_default();
+    let backup_root = backup::backup_dir(&app, &settings)?;
+    
+    let mut created = Vec::new();
+    for project in &config.recent_projects {
+        let root = PathBuf::from(&project.path);
+        if io_sqlite::is_sqlite_project(&root) {
+            created.push(backup::backup_project(&root, &project.name, &backup_root, &settings)?);
+        }
+    }
+    Ok(created)
+}
+
+/// Restore a backup over its project (or into `target_path`). The current
+/// state is archived first so a restore can itself be undone. If the open
+/// project is restored it is closed; reload it from the returned path.
+#[tauri::command]
+pub fn restore_backup(
+    backup_id: String,
+    target_path: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<String, AppError> {
+    let settings = GlobalConfig::load(&app).backup.unwrap_or_default();
+    let backup_root = backup::backup_dir(&app, &settings)?;
+    
+    let target = match target_path {
+        Some(path) => PathBuf::from(path),
+        None => backup::list_backups(&backup_root)?
+            .into_iter()
+            .find(|b| b.id == backup_id)
+            .map(|b| PathBuf::from(b.manifest.project_path))
+            .ok_or_else(|| AppError::NotFound(format!("Backup not found: {}", backup_id)))?,
+    };
+    
+    if io_sqlite::is_sqlite_project(&target) {
+        let name = target.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        // Keep the pre-restore archive regardless of retention
+        let keep_all = BackupSettings { retention: usize::MAX, ..settings.clone() };
+        backup::backup_project(&target, &name, &backup_root, &keep_all)?;
+    }
+    
+    {
+        let mut path_guard = state.current_project_path.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+        if path_guard.as_ref().is_some_and(|current| std::path::Path::new(current) == target) {
+            *path_guard = None;
+            state.sessions.stop();
+        }
+    }
+    
+    let restored = backup::restore_backup(&backup_root, &backup_id, Some(&target))?;
+    Ok(restored.to_string_lossy().into_owned())
+}
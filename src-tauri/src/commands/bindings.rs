@@ -0,0 +1,557 @@
+//! TypeScript invokers for every registered command.
+//!
+//! `cargo test export_bindings` writes `bindings/commands.ts` next to the
+//! ts-rs types: one typed function per command, taking the command's
+//! arguments as one object (camelCase, as Tauri expects) and resolving to its
+//! return type. The table must list the same commands as `generate_handler!`
+//! in `lib.rs`; `test_bindings_match_registered_commands` checks this.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::Write;
+use ts_rs::{Dependency, TypeVisitor, TS};
+use crate::config::RecentProject;
+use crate::models::{
+    AgentDefinition, Asset, AssetTag, BrandKit, Canvas, ComponentDefinition, GenerationParams,
+    GenerationRecord, Position, Reminder, SynniaNode, SynniaProject, Viewport, ViewportBookmark,
+};
+use crate::services::activity::{ActivityEntry, ActivityFilter};
+use crate::services::agent_service::GraphAction;
+use crate::services::asset_rename::RenamedAsset;
+use crate::services::asset_usage::AssetUsageReport;
+use crate::services::audio_trim::TrimResult;
+use crate::services::audit::{AuditExport, AuditFormat};
+use crate::services::auto_tag::{AutoTagScope, AutoTagSettings};
+use crate::services::backup::{BackupInfo, BackupSettings};
+use crate::services::brand::BrandComplianceReport;
+use crate::services::budget::{BudgetSettings, BudgetStatus};
+use crate::services::bulk_edit::AssetPatch;
+use crate::services::cancellation::OperationInfo;
+use crate::services::canvases::MovedNodes;
+use crate::services::command_script::ScriptStep;
+use crate::services::compaction::FlattenReport;
+use crate::services::connectors::{ConnectorProvider, RemoteFile, RemoteImport};
+use crate::services::custom_fields::{CustomField, CustomFieldInput, FieldFilter, FieldTarget, FieldValue};
+use crate::services::components::ComponentInstance;
+use crate::services::export::{CropPreset, ExportedFile};
+use crate::services::faces::{FaceGroup, FaceSettings};
+use crate::services::file_history::FileVersion;
+use crate::services::folder_import::{FolderImport, ImportFolderOptions};
+use crate::services::generation::SeedStrategy;
+use crate::services::generation_grid::GridAxis;
+use crate::services::graph_analysis::GraphAnalysis;
+use crate::services::history::RestoreResult;
+use crate::services::image_edit::{EditMode, EditProvider};
+use crate::services::image_maps::MapKind;
+use crate::services::imaging::ThumbnailSettings;
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::licenses::{AssetLicense, LicenseFilter, LicenseReport};
+use crate::services::lint::{LintConfig, LintFinding};
+use crate::services::linked_files::{BrokenLink, RelinkReport};
+use crate::services::load_profile::LoadProfile;
+use crate::services::minimap::Minimap;
+use crate::services::mobile_capture::PairingQr;
+use crate::services::model_manager::{InstalledModel, ModelDiskUsage, ModelDownload};
+use crate::services::net_guard::NetworkTimeouts;
+use crate::services::pdf_export::{PdfExport, PdfExportOptions};
+use crate::services::org_config::OrgConfig;
+use crate::services::permissions::{Actor, Capability, PermissionPolicy, PermissionRequest};
+use crate::services::pipelines::{PipelineOptions, PipelineRun};
+use crate::services::planning::{self, KanbanColumn, KanbanGroupBy, PlanningItem};
+use crate::services::provider_health::{ConnectionReport, ModelInfo};
+use crate::services::recipes::{Recipe, RecipeInput, RecipePreview};
+use crate::services::run_comparison::RunComparison;
+use crate::services::safety::{FlaggedAsset, ReviewDecision, SafetySettings};
+use crate::services::settings_bundle::BundleReport;
+use crate::services::share_bundle::ShareBundle;
+use crate::services::smart_collections::{DateRange, SmartCollection, SmartQuery};
+use crate::services::spellcheck::TextFix;
+use crate::services::stock::{StockImport, StockProvider, StockResult, StockSize};
+use crate::services::stress_fixtures::GeneratedProject;
+use crate::services::subgraph::SubgraphExport;
+use crate::services::tasklist::GroupProgress;
+use crate::services::template_sync::TemplateSyncReport;
+use crate::services::time_tracking::{TimePeriod, TimeReport};
+use crate::services::timeline::{self, TimelineSession};
+use crate::services::tracker_export::{ExportMapping, TrackerExport};
+use crate::services::usages::UsageReport;
+use crate::services::cloud_sync;
+use crate::GreetResponse;
+use super::asset::{BatchImportResult, DownloadFileResult, MediaAssetInfo, SaveImageResult};
+use super::history::HistoryEntry;
+use super::http_proxy::{ProxyOptions, ProxyResponse};
+use super::jobs::JobInfo;
+use super::project::DeleteProjectReport;
+use super::spellcheck::SpellcheckReport;
+
+/// `serde_json::Value` arguments, typed like the `any` fields of the models
+#[derive(TS)]
+#[ts(rename = "any")]
+struct Json;
+
+struct CommandBinding {
+    name: &'static str,
+    args: Vec<(&'static str, String)>,
+    ret: String,
+    deps: Vec<Dependency>,
+}
+
+macro_rules! command_bindings {
+    ($($name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty;)*) => {
+        [$(CommandBinding {
+            name: stringify!($name),
+            args: vec![$((stringify!($arg), <$ty as TS>::name())),*],
+            ret: <$ret as TS>::name(),
+            deps: {
+                #[allow(unused_mut)]
+                let mut deps = dependencies::<$ret>();
+                $(deps.extend(dependencies::<$ty>());)*
+                deps
+            },
+        }),*]
+    };
+}
+
+fn commands() -> Vec<CommandBinding> {
+    Vec::from(command_bindings! {
+        ping(name: String) -> GreetResponse;
+        get_server_port() -> u16;
+
+        // Project Commands
+        init_project(path: String) -> String;
+        get_recent_projects() -> Vec<RecentProject>;
+        get_default_projects_path() -> String;
+        set_default_projects_path(path: String) -> ();
+        create_project(name: String, parent_path: String) -> String;
+        load_project(path: String) -> SynniaProject;
+        profile_project_load(path: String) -> LoadProfile;
+        // Debug builds only
+        generate_test_project(nodes: usize, assets: usize, size: u32) -> GeneratedProject;
+        save_project(project: SynniaProject, force: Option<bool>) -> ();
+        save_project_autosave(project: SynniaProject, force: Option<bool>) -> ();
+        get_current_project_path() -> String;
+        delete_project(path: String, dry_run: Option<bool>) -> DeleteProjectReport;
+        reset_project() -> SynniaProject;
+        set_thumbnail(image_relative_path: String) -> ();
+        open_in_browser(url: String) -> ();
+        rename_project(old_path: String, new_name: String) -> String;
+        get_sync_status() -> cloud_sync::SyncStatus;
+
+        // Agent Commands
+        save_settings(key: String, base_url: String, model_name: String) -> ();
+        get_api_key() -> String;
+        get_base_url() -> String;
+        get_model_name() -> String;
+        run_agent(agent_def: AgentDefinition, inputs: Json, context_node_id: Option<String>, operation_id: Option<String>) -> Vec<GraphAction>;
+        run_agent_streaming(agent_def: AgentDefinition, inputs: Json, context_node_id: Option<String>, target_asset_id: String, append: Option<bool>, flush_tokens: Option<usize>, operation_id: Option<String>) -> Asset;
+        get_agents() -> Vec<AgentDefinition>;
+        save_agent(agent: AgentDefinition) -> ();
+        delete_agent(agent_id: String) -> ();
+        get_agent_usages(agent_id: String) -> UsageReport;
+        get_template_usages(template_id: String) -> UsageReport;
+        get_ai_config() -> String;
+        save_ai_config(config: String) -> ();
+        test_ai_connection(provider: String) -> ConnectionReport;
+        list_models(provider: String) -> Vec<ModelInfo>;
+        get_media_config() -> String;
+        save_media_config(config: String) -> ();
+        get_app_settings() -> String;
+        save_app_settings(settings: String) -> ();
+        export_settings_bundle(dest: Option<String>) -> BundleReport;
+        import_settings_bundle(file: String) -> BundleReport;
+        get_network_allowlist() -> Vec<String>;
+        save_network_allowlist(hosts: Vec<String>, strict: bool) -> ();
+        get_network_timeouts() -> NetworkTimeouts;
+        save_network_timeouts(timeouts: NetworkTimeouts) -> ();
+        get_org_config() -> OrgConfig;
+
+        // Asset Commands
+        import_file(file_path: String, link: Option<bool>) -> SaveImageResult;
+        save_processed_image(base64_data: String, filename: Option<String>) -> SaveImageResult;
+        download_and_save_image(url: String, filename: Option<String>, download_id: Option<String>) -> SaveImageResult;
+        batch_import_images(file_paths: Vec<String>, concurrency: Option<usize>) -> Vec<BatchImportResult>;
+        get_media_assets() -> Vec<MediaAssetInfo>;
+        download_asset_file(url: String, download_id: String, extension: Option<String>, max_bytes: Option<u64>) -> DownloadFileResult;
+        cancel_download(download_id: String) -> bool;
+        get_active_downloads() -> Vec<String>;
+        get_asset_usage_report() -> AssetUsageReport;
+        get_broken_links() -> Vec<BrokenLink>;
+        relink_assets(old_prefix: String, new_prefix: String) -> RelinkReport;
+        rename_asset(asset_id: String, new_name: String, rename_file: Option<bool>) -> RenamedAsset;
+        bulk_update_assets(asset_ids: Vec<String>, patch: AssetPatch) -> Vec<Asset>;
+        import_folder(path: String, options: Option<ImportFolderOptions>) -> FolderImport;
+        generate_video_proxies(asset_ids: Option<Vec<String>>, min_bytes: Option<u64>, force: Option<bool>) -> JobInfo;
+        trim_audio(asset_id: String, start_ms: u64, end_ms: u64, node_id: Option<String>) -> TrimResult;
+        upscale_image(asset_id: String, factor: u32, provider: Option<String>) -> JobInfo;
+        generate_image_maps(asset_id: String, kinds: Vec<MapKind>, node_id: Option<String>) -> JobInfo;
+
+        // History Commands
+        save_asset_with_history(asset: Asset) -> bool;
+        get_asset_history(asset_id: String, limit: Option<i32>) -> Vec<HistoryEntry>;
+        get_history_content(history_id: i64) -> String;
+        restore_asset_version(asset_id: String, history_id: i64) -> RestoreResult;
+        count_asset_history(asset_id: String) -> i64;
+        get_file_history(asset_id: String) -> Vec<FileVersion>;
+        restore_file_version(asset_id: String, version_id: i64) -> Asset;
+        get_project_history_timeline(range: Option<timeline::TimelineRange>) -> Vec<TimelineSession>;
+        flatten_project(keep_last_n: usize, dry_run: Option<bool>) -> FlattenReport;
+
+        // HTTP Proxy
+        proxy_request(url: String, method: String, headers: HashMap<String, String>, body: Option<String>, options: Option<ProxyOptions>, operation_id: Option<String>) -> ProxyResponse;
+        proxy_request_stream(request_id: String, url: String, method: String, headers: HashMap<String, String>, body: Option<String>, options: Option<ProxyOptions>) -> ProxyResponse;
+
+        // Job Commands
+        run_generation_grid(recipe_node_id: String, variations: Vec<GridAxis>, concurrency: Option<usize>, output_node_type: Option<String>) -> JobInfo;
+        complete_job_task(job_id: String, task_index: usize, result: Option<Json>, error: Option<String>) -> ();
+        cancel_job(job_id: String) -> Job;
+        confirm_job_budget(job_id: String, approve: bool) -> Job;
+        get_job(job_id: String) -> Job;
+        get_jobs() -> Vec<Job>;
+
+        // Generation Commands
+        record_generation(asset_id: String, params: GenerationParams, recipe_id: Option<String>, parent_asset_id: Option<String>) -> GenerationRecord;
+        get_generation_record(asset_id: String) -> Option<GenerationRecord>;
+        get_asset_variations(asset_id: String) -> Vec<GenerationRecord>;
+        regenerate_variation(asset_id: String, overrides: Option<Json>, seed_strategy: Option<SeedStrategy>) -> JobInfo;
+        edit_image_ai(asset_id: String, mask_asset_id: String, prompt: String, mode: Option<EditMode>, provider: Option<EditProvider>) -> JobInfo;
+        interrogate_image(asset_id: String, provider: Option<String>) -> JobInfo;
+
+        // Comparison Commands
+        create_comparison(asset_ids: Vec<String>, name: Option<String>) -> Asset;
+        vote_comparison(comparison_id: String, candidate_asset_id: String, note: Option<String>) -> Asset;
+        add_comparison_note(comparison_id: String, candidate_asset_id: String, note: String) -> Asset;
+
+        // Component Commands
+        get_components() -> Vec<ComponentDefinition>;
+        save_component(name: String, node_ids: Vec<String>, description: Option<String>) -> ComponentDefinition;
+        delete_component(component_id: String) -> ();
+        instantiate_component(component_id: String, position: Position) -> ComponentInstance;
+
+        // Spellcheck Commands
+        get_spellcheck_languages() -> Vec<String>;
+        check_text_asset(asset_id: String, lang: String) -> SpellcheckReport;
+        apply_fixes(asset_id: String, fixes: Vec<TextFix>) -> Asset;
+
+        // Translation Commands
+        translate_asset(asset_id: String, target_lang: String, provider: Option<String>) -> JobInfo;
+        translate_group(group_id: String, target_lang: String, provider: Option<String>, concurrency: Option<usize>) -> JobInfo;
+
+        // Lint Commands
+        lint_project(config: Option<LintConfig>) -> Vec<LintFinding>;
+
+        // Brand Commands
+        get_brand_kit() -> BrandKit;
+        save_brand_kit(kit: BrandKit) -> ();
+        check_brand_compliance(asset_id: String) -> BrandComplianceReport;
+
+        // Export Commands
+        export_crops(asset_id: String, presets: Vec<CropPreset>, operation_id: Option<String>) -> Vec<ExportedFile>;
+        export_pdf(options: Option<PdfExportOptions>, operation_id: Option<String>) -> PdfExport;
+        export_subgraph(node_ids: Vec<String>, dest: String, include_history: Option<bool>, operation_id: Option<String>) -> SubgraphExport;
+
+        // Operation Commands
+        cancel_operation(op_id: String) -> bool;
+        get_active_operations() -> Vec<OperationInfo>;
+
+        // Planning Commands
+        get_timeline(range: Option<planning::TimelineRange>) -> Vec<PlanningItem>;
+        get_kanban(group_by: Option<KanbanGroupBy>) -> Vec<KanbanColumn>;
+
+        // Task List Commands
+        toggle_task(asset_id: String, index: usize) -> Asset;
+        get_group_progress(group_node_id: String) -> GroupProgress;
+
+        // Reminder Commands
+        schedule_reminder(node_id: String, when: String, message: String) -> Reminder;
+        get_reminders(include_fired: Option<bool>) -> Vec<Reminder>;
+        cancel_reminder(reminder_id: String) -> ();
+
+        // Activity Commands
+        get_activity(since: Option<i64>, filters: Option<ActivityFilter>) -> Vec<ActivityEntry>;
+        export_audit_log(range: Option<DateRange>, format: AuditFormat, dest: Option<String>) -> AuditExport;
+
+        // Time Tracking Commands
+        get_time_report(period: Option<TimePeriod>, since: Option<i64>, until: Option<i64>) -> TimeReport;
+
+        // Backup Commands
+        get_backup_settings() -> BackupSettings;
+        save_backup_settings(settings: BackupSettings) -> ();
+        list_backups() -> Vec<BackupInfo>;
+        run_backups_now() -> Vec<BackupInfo>;
+        restore_backup(backup_id: String, target_path: Option<String>) -> String;
+
+        // Graph Analysis Commands
+        analyze_graph() -> GraphAnalysis;
+
+        // Minimap Commands
+        render_minimap(width: u32, height: u32) -> Minimap;
+
+        // Node Lock Commands
+        set_node_lock(node_id: String, locked: Option<bool>, pinned: Option<bool>) -> SynniaNode;
+
+        // Reference Commands
+        create_reference(node_id: String, position: Option<Position>) -> SynniaNode;
+        list_references(asset_id: String) -> Vec<SynniaNode>;
+
+        // Viewport Bookmark Commands
+        save_viewport_bookmark(name: String, viewport: Option<Viewport>) -> ViewportBookmark;
+        list_viewport_bookmarks() -> Vec<ViewportBookmark>;
+        delete_viewport_bookmark(bookmark_id: String) -> ();
+
+        // Canvas Commands
+        create_canvas(name: String) -> Canvas;
+        list_canvases() -> Vec<Canvas>;
+        open_canvas(canvas_id: String) -> SynniaProject;
+        move_nodes_to_canvas(node_ids: Vec<String>, canvas_id: String) -> MovedNodes;
+
+        // Clip Commands
+        get_clip_token() -> String;
+        regenerate_clip_token() -> String;
+        get_pairing_qr() -> PairingQr;
+        stop_mobile_capture() -> ();
+
+        // Thumbnail Commands
+        get_thumbnail_settings() -> ThumbnailSettings;
+        save_thumbnail_settings(settings: ThumbnailSettings) -> ThumbnailSettings;
+        regenerate_thumbnails(force: Option<bool>, concurrency: Option<usize>) -> JobInfo;
+
+        // Tag Commands
+        get_asset_tags(asset_id: String) -> Vec<AssetTag>;
+        set_asset_tags(asset_id: String, tags: Vec<String>) -> Vec<AssetTag>;
+        search_assets_by_tags(query: String, limit: Option<usize>, fields: Option<Vec<FieldFilter>>, license: Option<LicenseFilter>) -> Vec<String>;
+        get_auto_tag_settings() -> AutoTagSettings;
+        save_auto_tag_settings(settings: AutoTagSettings) -> AutoTagSettings;
+        auto_tag_assets(scope: Option<AutoTagScope>, provider: Option<String>, concurrency: Option<usize>) -> JobInfo;
+
+        // Custom Field Commands
+        get_custom_fields() -> Vec<CustomField>;
+        save_custom_field(field: CustomFieldInput) -> CustomField;
+        delete_custom_field(field_id: String) -> bool;
+        get_custom_field_values(target_id: String) -> Vec<FieldValue>;
+        set_custom_field_value(target: FieldTarget, target_id: String, field_id: String, value: Json) -> Vec<FieldValue>;
+        search_by_custom_fields(target: FieldTarget, filters: Vec<FieldFilter>, limit: Option<usize>) -> Vec<String>;
+
+        // Smart Collection Commands
+        create_smart_collection(name: String, query: SmartQuery) -> SmartCollection;
+        list_smart_collections() -> Vec<SmartCollection>;
+        delete_smart_collection(collection_id: String) -> ();
+        evaluate_smart_collection(collection_id: String, limit: Option<usize>) -> Vec<String>;
+
+        // Recipe Commands
+        list_recipes() -> Vec<Recipe>;
+        get_recipe(recipe_id: String) -> Recipe;
+        save_recipe(recipe: RecipeInput) -> Recipe;
+        delete_recipe(recipe_id: String) -> bool;
+        preview_recipe(recipe_node_id: String) -> RecipePreview;
+
+        // Pipeline Commands
+        start_pipeline(node_ids: Vec<String>, options: Option<PipelineOptions>) -> PipelineRun;
+        record_pipeline_step(run_id: String, node_id: String, status: TaskStatus, output_asset_id: Option<String>, error: Option<String>, retry_after_ms: Option<u64>) -> PipelineRun;
+        resume_pipeline(run_id: String) -> PipelineRun;
+        get_pipeline_run(run_id: String) -> PipelineRun;
+        list_pipeline_runs(limit: Option<usize>) -> Vec<PipelineRun>;
+        compare_runs(node_id: Option<String>, run_a: String, run_b: String) -> RunComparison;
+
+        // Face Commands
+        get_face_settings() -> FaceSettings;
+        save_face_settings(settings: FaceSettings) -> FaceSettings;
+        detect_faces(asset_ids: Option<Vec<String>>, force: Option<bool>) -> JobInfo;
+        group_assets_by_face(threshold: Option<f32>) -> Vec<FaceGroup>;
+
+        // Safety Commands
+        get_safety_settings() -> SafetySettings;
+        save_safety_settings(settings: SafetySettings) -> SafetySettings;
+        scan_image_safety(asset_ids: Option<Vec<String>>, force: Option<bool>) -> JobInfo;
+        get_flagged_assets(include_reviewed: Option<bool>) -> Vec<FlaggedAsset>;
+        review_flagged_assets(asset_ids: Vec<String>, decision: ReviewDecision) -> Vec<FlaggedAsset>;
+
+        // Permission Commands
+        get_permission_policy() -> PermissionPolicy;
+        save_permission_policy(policy: PermissionPolicy) -> PermissionPolicy;
+        get_pending_permission_requests() -> Vec<PermissionRequest>;
+        resolve_permission_request(request_id: String, allow: bool, remember: Option<bool>) -> ();
+        check_automation_permission(actor: Actor, capability: Capability, summary: String, target_ids: Option<Vec<String>>) -> ();
+
+        // Ignore Rule Commands
+        get_ignore_rules() -> Vec<String>;
+        save_ignore_rules(patterns: Vec<String>) -> Vec<String>;
+
+        // Budget Commands
+        get_budget_settings() -> BudgetSettings;
+        save_budget_settings(settings: BudgetSettings) -> BudgetSettings;
+        get_budget_status() -> BudgetStatus;
+
+        // Script Commands
+        run_command_script(steps: Vec<ScriptStep>) -> JobInfo;
+
+        // Template Commands
+        sync_templates_from_url(url: String, dry_run: Option<bool>) -> TemplateSyncReport;
+
+        // License Commands
+        set_license(asset_id: String, license: Option<AssetLicense>) -> Option<AssetLicense>;
+        get_license(asset_id: String) -> Option<AssetLicense>;
+        license_report(expiring_days: Option<u32>, dest: Option<String>) -> LicenseReport;
+
+        // Stock Commands
+        search_stock(provider: StockProvider, query: String, page: Option<u32>) -> Vec<StockResult>;
+        import_stock_result(id: String, size: Option<StockSize>) -> StockImport;
+
+        // Connector Commands
+        connect_remote_provider(provider: ConnectorProvider) -> String;
+        disconnect_remote_provider(provider: ConnectorProvider) -> ();
+        get_connected_providers() -> Vec<ConnectorProvider>;
+        list_remote_files(provider: ConnectorProvider, folder: Option<String>) -> Vec<RemoteFile>;
+        import_remote_file(provider: ConnectorProvider, file_id: String) -> RemoteImport;
+
+        // Tracker Export Commands
+        export_to_notion(asset_ids: Vec<String>, database_id: String, mapping: ExportMapping) -> TrackerExport;
+        export_to_airtable(asset_ids: Vec<String>, base_id: String, table: String, mapping: ExportMapping) -> TrackerExport;
+
+        // Share Commands
+        share_to_webhook(webhook_url: String, node_ids: Vec<String>, message: Option<String>) -> JobInfo;
+        create_share_bundle(node_ids: Vec<String>, expiry: Option<u32>) -> ShareBundle;
+        list_share_bundles() -> Vec<ShareBundle>;
+        delete_share_bundle(bundle_id: String) -> ();
+
+        // Model Commands
+        list_installed_models() -> Vec<InstalledModel>;
+        download_model(request: ModelDownload, download_id: String) -> InstalledModel;
+        delete_model(model_id: String) -> bool;
+        get_model_disk_usage() -> ModelDiskUsage;
+    })
+}
+
+/// Exported types `T` refers to, itself included
+fn dependencies<T: TS + 'static + ?Sized>() -> Vec<Dependency> {
+    struct Visit<'a>(&'a mut Vec<Dependency>);
+    impl TypeVisitor for Visit<'_> {
+        fn visit<T: TS + 'static + ?Sized>(&mut self) {
+            if TypeId::of::<T>() == TypeId::of::<Json>() {
+                return;
+            }
+            if let Some(dep) = Dependency::from_ty::<T>() {
+                self.0.push(dep);
+            }
+        }
+    }
+    let mut deps = Vec::new();
+    let mut visit = Visit(&mut deps);
+    visit.visit::<T>();
+    T::visit_generics(&mut visit);
+    deps
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Large integers travel as JSON numbers, as in the models' `#[ts(type = "number")]`
+fn ts_type(name: &str) -> String {
+    match name {
+        "null" => "void".to_string(),
+        _ => name.replace("bigint", "number"),
+    }
+}
+
+fn render(commands: &[CommandBinding]) -> String {
+    let mut imports: HashMap<String, String> = HashMap::new();
+    for dep in commands.iter().flat_map(|c| &c.deps) {
+        let path = dep.output_path.with_extension("");
+        imports.insert(dep.ts_name.clone(), format!("./{}", path.to_string_lossy().replace('\\', "/")));
+    }
+    let mut imports: Vec<_> = imports.into_iter().collect();
+    imports.sort();
+
+    let mut out = String::from(
+        "// This file was generated by `cargo test export_bindings` (src/commands/bindings.rs). Do not edit this file manually.\n"
+    );
+    for (name, path) in &imports {
+        let _ = writeln!(out, "import type {{ {} }} from \"{}\";", name, path);
+    }
+    out.push_str("\nexport type Invoke = <T>(cmd: string, args?: Record<string, unknown>) => Promise<T>;\n\n");
+    out.push_str("/** Typed command invokers over `invoke` (e.g. `apiClient.invoke`) */\n");
+    out.push_str("export const createCommands = (invoke: Invoke) => ({\n");
+    for command in commands {
+        let ret = ts_type(&command.ret);
+        if command.args.is_empty() {
+            let _ = writeln!(out, "    {}: () => invoke<{}>(\"{}\"),", camel_case(command.name), ret, command.name);
+            continue;
+        }
+        let args: Vec<String> = command.args.iter()
+            .map(|(arg, ty)| match ty.strip_suffix(" | null") {
+                Some(inner) => format!("{}?: {} | null", camel_case(arg), ts_type(inner)),
+                None => format!("{}: {}", camel_case(arg), ts_type(ty)),
+            })
+            .collect();
+        let _ = writeln!(
+            out,
+            "    {}: (args: {{ {} }}) => invoke<{}>(\"{}\", args),",
+            camel_case(command.name), args.join(", "), ret, command.name
+        );
+    }
+    out.push_str("});\n\nexport type Commands = ReturnType<typeof createCommands>;\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_bindings_commands() {
+        let dir = std::env::var("TS_RS_EXPORT_DIR").unwrap_or_else(|_| "./bindings".to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(std::path::Path::new(&dir).join("commands.ts"), render(&commands())).unwrap();
+    }
+
+    #[test]
+    fn test_render_command() {
+        let rendered = render(&command_bindings! {
+            restore_asset_version(asset_id: String, history_id: i64) -> RestoreResult;
+            get_jobs() -> Vec<Job>;
+            complete_job_task(job_id: String, result: Option<Json>) -> ();
+        });
+        assert!(rendered.contains("import type { RestoreResult } from \"./RestoreResult\";"));
+        assert!(rendered.contains(
+            "restoreAssetVersion: (args: { assetId: string, historyId: number }) => invoke<RestoreResult>(\"restore_asset_version\", args),"
+        ));
+        assert!(rendered.contains("getJobs: () => invoke<Array<Job>>(\"get_jobs\"),"));
+        assert!(rendered.contains(
+            "completeJobTask: (args: { jobId: string, result?: any | null }) => invoke<void>(\"complete_job_task\", args),"
+        ));
+        assert!(!rendered.contains("./any"));
+    }
+
+    #[test]
+    fn test_bindings_match_registered_commands() {
+        let lib = include_str!("../lib.rs");
+        let start = lib.find("generate_handler![").expect("generate_handler! in lib.rs");
+        let end = start + lib[start..].find("])").expect("end of generate_handler!");
+
+        let registered: std::collections::BTreeSet<&str> = lib[start..end]
+            .lines()
+            .skip(1)
+            .map(|line| line.split("//").next().unwrap_or("").trim().trim_end_matches(','))
+            .filter(|entry| !entry.is_empty() && !entry.starts_with("#["))
+            .map(|path| path.rsplit("::").next().unwrap_or(path))
+            .collect();
+        let bound: std::collections::BTreeSet<&str> = commands().iter().map(|c| c.name).collect();
+
+        let unbound: Vec<_> = registered.difference(&bound).collect();
+        let unregistered: Vec<_> = bound.difference(&registered).collect();
+        assert!(unbound.is_empty(), "commands without a binding: {:?}", unbound);
+        assert!(unregistered.is_empty(), "bindings for unregistered commands: {:?}", unregistered);
+    }
+}
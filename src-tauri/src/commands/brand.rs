@@ -0,0 +1,61 @@
+//! Brand kit commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::models::BrandKit;
+use crate::AppState;
+use crate::services::{brand, database, io_sqlite};
+use crate::services::brand::BrandComplianceReport;
+
+/// The current project's brand kit (empty if none is set)
+#[tauri::command]
+pub fn get_brand_kit(state: State<AppState>) -> Result<BrandKit, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let kit = io_sqlite::get_setting(&conn, brand::SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    Ok(kit)
+}
+
+#[tauri::command]
+pub fn save_brand_kit(kit: BrandKit, state: State<AppState>) -> Result<(), AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    io_sqlite::set_setting(&conn, brand::SETTINGS_KEY, &serde_json::to_value(&kit)?)
+}
+
+/// Compare an asset against the brand kit: image palette vs approved colors,
+/// text vs banned words, declared font vs approved fonts.
+#[tauri::command]
+pub fn check_brand_compliance(
+    asset_id: String,
+    state: State<AppState>,
+) -> Result<BrandComplianceReport, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    
+    let asset = project.assets.get(&asset_id)
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    let kit = brand::kit_from_project(&project)
+        .ok_or_else(|| AppError::Unknown("This project has no brand kit".to_string()))?;
+    
+    brand::check_asset(&project_path, asset, &kit)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,47 @@
+//! Budget commands: per-project and per-run spending limits for provider
+//! jobs, and the spend so far.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{budget, database, io_sqlite};
+use crate::services::budget::{BudgetSettings, BudgetStatus};
+
+#[tauri::command]
+pub fn get_budget_settings(state: State<AppState>) -> Result<BudgetSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    budget::get_settings(&conn)
+}
+
+/// Store the settings; negative and non-finite amounts are dropped.
+#[tauri::command]
+pub fn save_budget_settings(settings: BudgetSettings, state: State<AppState>) -> Result<BudgetSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    budget::save_settings(&conn, settings)
+}
+
+/// Settings plus what the project has spent and has left.
+#[tauri::command]
+pub fn get_budget_status(state: State<AppState>) -> Result<BudgetStatus, AppError> {
+    let conn = open_project_db(&state)?;
+    budget::get_status(&conn)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,64 @@
+//! Canvas commands: separate boards within one project.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::{Canvas, SynniaProject};
+use crate::services::{canvases, database, io_sqlite};
+use crate::services::canvases::MovedNodes;
+
+#[tauri::command]
+pub fn create_canvas(
+    name: String,
+    state: State<AppState>,
+) -> Result<Canvas, AppError> {
+    let conn = open_project_db(&state)?;
+    canvases::create(&conn, &name)
+}
+
+#[tauri::command]
+pub fn list_canvases(state: State<AppState>) -> Result<Vec<Canvas>, AppError> {
+    let conn = open_project_db(&state)?;
+    canvases::list(&conn)
+}
+
+/// Switch to a canvas and return the project with its graph and viewport.
+#[tauri::command]
+pub fn open_canvas(
+    canvas_id: String,
+    state: State<AppState>,
+) -> Result<SynniaProject, AppError> {
+    let conn = open_project_db(&state)?;
+    canvases::open(&conn, &canvas_id)?;
+    io_sqlite::load_project_sqlite(&get_project_path(&state)?)
+}
+
+/// Move nodes (with their children) to another canvas.
+#[tauri::command]
+pub fn move_nodes_to_canvas(
+    node_ids: Vec<String>,
+    canvas_id: String,
+    state: State<AppState>,
+) -> Result<MovedNodes, AppError> {
+    let mut conn = open_project_db(&state)?;
+    canvases::move_nodes(&mut conn, &node_ids, &canvas_id)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
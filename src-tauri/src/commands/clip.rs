@@ -0,0 +1,44 @@
+//! Commands for pairing the browser extension with the local clip endpoint,
+//! and phones with the capture page.
+
+use tauri::{State, AppHandle};
+use crate::error::AppError;
+use crate::config::GlobalConfig;
+use crate::AppState;
+use crate::services::mobile_capture::PairingQr;
+
+/// Get the bearer token the browser extension must send to `POST /clip`.
+#[tauri::command]
+pub fn get_clip_token(state: State<AppState>) -> Result<String, AppError> {
+    let token = state.clip_token.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    Ok(token.clone())
+}
+
+/// Rotate the clip token, unpairing any previously configured extension.
+#[tauri::command]
+pub fn regenerate_clip_token(state: State<AppState>, app: AppHandle) -> Result<String, AppError> {
+    let mut config = GlobalConfig::load(&app);
+    config.clip_token = None;
+    let (token, _) = config.ensure_clip_token();
+    config.save(&app).map_err(AppError::Unknown)?;
+    
+    let mut guard = state.clip_token.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    *guard = token.clone();
+    
+    Ok(token)
+}
+
+/// Start the phone capture page on the local network and get the QR code
+/// that opens it. Photos and notes sent from the phone land in the inbox.
+#[tauri::command]
+pub fn get_pairing_qr(state: State<AppState>, app: AppHandle) -> Result<PairingQr, AppError> {
+    state.mobile_capture.pair(state.current_project_path.clone(), state.permissions.clone(), app)
+}
+
+/// Stop the phone capture page; paired phones need a new QR code.
+#[tauri::command]
+pub fn stop_mobile_capture(state: State<AppState>) -> Result<(), AppError> {
+    state.mobile_capture.stop()
+}
@@ -0,0 +1,55 @@
+//! A/B comparison commands: create review rounds, record votes and notes.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::Asset;
+use crate::services::comparison;
+
+/// Snapshot the given assets into a new comparison asset.
+#[tauri::command]
+pub fn create_comparison(
+    asset_ids: Vec<String>,
+    name: Option<String>,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let project_path = get_project_path(&state)?;
+    comparison::create_comparison(&project_path, &asset_ids, name)
+}
+
+/// Vote for a candidate, optionally with a note. Returns the updated comparison.
+#[tauri::command]
+pub fn vote_comparison(
+    comparison_id: String,
+    candidate_asset_id: String,
+    note: Option<String>,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let project_path = get_project_path(&state)?;
+    comparison::record_feedback(&project_path, &comparison_id, &candidate_asset_id, true, note)
+}
+
+/// Add a note to a candidate without voting. Returns the updated comparison.
+#[tauri::command]
+pub fn add_comparison_note(
+    comparison_id: String,
+    candidate_asset_id: String,
+    note: String,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let project_path = get_project_path(&state)?;
+    comparison::record_feedback(&project_path, &comparison_id, &candidate_asset_id, false, Some(note))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
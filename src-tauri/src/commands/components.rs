@@ -0,0 +1,93 @@
+//! Component commands: save subgraphs to the global components store and
+//! stamp them into the current project.
+
+use tauri::{State, AppHandle, Manager};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::{ComponentDefinition, Position};
+use crate::services::{components, database, io_sqlite};
+use crate::services::components::ComponentInstance;
+
+// Helper to get the global components directory
+pub(crate) fn get_components_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
+    let components_dir = docs_dir.join("Synnia").join("Components");
+    if !components_dir.exists() {
+        std::fs::create_dir_all(&components_dir).map_err(|e| AppError::Io(e.to_string()))?;
+    }
+    Ok(components_dir)
+}
+
+#[tauri::command]
+pub fn get_components(app: AppHandle) -> Result<Vec<ComponentDefinition>, AppError> {
+    let dir = get_components_dir(&app)?;
+    Ok(components::list_components(&dir))
+}
+
+/// Save the given nodes (and their children, edges and assets) as a component.
+#[tauri::command]
+pub fn save_component(
+    name: String,
+    node_ids: Vec<String>,
+    description: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ComponentDefinition, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    
+    let component = components::extract_component(&project, &node_ids, name, description)?;
+    let dir = get_components_dir(&app)?;
+    components::store_files(&project_path, &dir, &component)?;
+    components::save_component(&dir, &component)?;
+    
+    Ok(component)
+}
+
+#[tauri::command]
+pub fn delete_component(component_id: String, app: AppHandle) -> Result<(), AppError> {
+    components::delete_component(&get_components_dir(&app)?, &component_id)
+}
+
+/// Stamp a component into the current project at `position` with fresh IDs.
+#[tauri::command]
+pub fn instantiate_component(
+    component_id: String,
+    position: Position,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ComponentInstance, AppError> {
+    let dir = get_components_dir(&app)?;
+    let component = components::load_component(&dir, &component_id)?;
+    let mut instance = components::instantiate(&component, &position);
+    
+    let project_path = get_project_path(&state)?;
+    components::place_files(&dir, &component.id, &project_path, &mut instance)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    for asset in &instance.assets {
+        io_sqlite::save_asset_with_history(&project_path, asset)?;
+    }
+    for node in &instance.nodes {
+        io_sqlite::upsert_node(&conn, node)?;
+    }
+    for edge in &instance.edges {
+        io_sqlite::upsert_edge(&conn, edge)?;
+    }
+    
+    Ok(instance)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,92 @@
+//! Connector commands: sign in to Google Drive / Dropbox, browse folders and
+//! import files into the project.
+
+use tauri::{AppHandle, Manager, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::services::{connectors, disk_space};
+use crate::services::connectors::{ConnectorProvider, RemoteFile, RemoteImport};
+use crate::services::connectors::oauth::{self, ClientCredentials};
+use crate::services::net_guard::NetworkPolicy;
+
+/// Start signing in to `provider`: the URL to open in the browser. The
+/// provider redirects back to the local server, which emits
+/// `connector:connected` once the sign-in went through.
+#[tauri::command]
+pub fn connect_remote_provider(
+    provider: ConnectorProvider,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<String, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(provider.key())?;
+    let client = ClientCredentials::from_config(&config, provider);
+    let redirect_uri = format!("http://127.0.0.1:{}{}", state.server_port, oauth::CALLBACK_PATH);
+    oauth::start(&state.logins, provider, &client, &redirect_uri)
+}
+
+/// Forget the tokens of `provider` on this machine.
+#[tauri::command]
+pub fn disconnect_remote_provider(provider: ConnectorProvider, app: AppHandle) -> Result<(), AppError> {
+    oauth::save_token(&get_config_dir(&app)?, provider, None)
+}
+
+#[tauri::command]
+pub fn get_connected_providers(app: AppHandle) -> Result<Vec<ConnectorProvider>, AppError> {
+    Ok(oauth::connected(&get_config_dir(&app)?))
+}
+
+/// Files and folders in `folder` (a folder ID from an earlier listing), or
+/// in the drive's root.
+#[tauri::command]
+pub async fn list_remote_files(
+    provider: ConnectorProvider,
+    folder: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<RemoteFile>, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(provider.key())?;
+    let client = ClientCredentials::from_config(&config, provider);
+    let policy = NetworkPolicy::from_config(&config);
+    connectors::list_files(&get_config_dir(&app)?, &policy, &client, provider, folder.as_deref()).await
+}
+
+/// Download a remote file into the project as an asset and node.
+#[tauri::command]
+pub async fn import_remote_file(
+    provider: ConnectorProvider,
+    file_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RemoteImport, AppError> {
+    let project_root = get_project_path(&state)?;
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(provider.key())?;
+    let client = ClientCredentials::from_config(&config, provider);
+    let policy = NetworkPolicy::from_config(&config);
+
+    let (name, data) = connectors::download_file(&get_config_dir(&app)?, &policy, &client, provider, &file_id).await?;
+    let assets_dir = project_root.join("assets");
+    std::fs::create_dir_all(&assets_dir)?;
+    disk_space::ensure_space(&app, &assets_dir, data.len() as u64)?;
+
+    state.blocking.run(move || connectors::import(&project_root, provider, &name, &data)).await
+}
+
+// Helper functions
+
+fn get_config_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path().app_config_dir().map_err(|e| AppError::Io(e.to_string()))
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,83 @@
+//! Custom field commands: the project's field definitions, their values on
+//! assets and nodes, and searching by them.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{custom_fields, database, io_sqlite};
+use crate::services::custom_fields::{CustomField, CustomFieldInput, FieldFilter, FieldTarget, FieldValue};
+
+/// Default max results of a field search
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+#[tauri::command]
+pub fn get_custom_fields(state: State<AppState>) -> Result<Vec<CustomField>, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::list_fields(&conn)
+}
+
+/// Create a field (without `id`) or update one. Values that no longer fit
+/// the field are cleared.
+#[tauri::command]
+pub fn save_custom_field(field: CustomFieldInput, state: State<AppState>) -> Result<CustomField, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::save_field(&conn, &field)
+}
+
+/// Delete a field and its values. Returns false if it didn't exist.
+#[tauri::command]
+pub fn delete_custom_field(field_id: String, state: State<AppState>) -> Result<bool, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::delete_field(&conn, &field_id)
+}
+
+#[tauri::command]
+pub fn get_custom_field_values(target_id: String, state: State<AppState>) -> Result<Vec<FieldValue>, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::get_values(&conn, &target_id)
+}
+
+/// Set a field on an asset or node; null or "" clears it. Returns all of
+/// the target's values.
+#[tauri::command]
+pub fn set_custom_field_value(
+    target: FieldTarget,
+    target_id: String,
+    field_id: String,
+    value: serde_json::Value,
+    state: State<AppState>,
+) -> Result<Vec<FieldValue>, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::set_value(&conn, target, &target_id, &field_id, &value)
+}
+
+/// IDs of assets or nodes matching every filter.
+#[tauri::command]
+pub fn search_by_custom_fields(
+    target: FieldTarget,
+    filters: Vec<FieldFilter>,
+    limit: Option<usize>,
+    state: State<AppState>,
+) -> Result<Vec<String>, AppError> {
+    let conn = open_project_db(&state)?;
+    custom_fields::search(&conn, target, &filters, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,104 @@
+//! Export commands.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, disk_space, io_sqlite};
+use crate::services::cancellation::OperationKind;
+use crate::services::export::{self, CropPreset, ExportedFile};
+use crate::services::pdf_export::{self, PdfExport, PdfExportOptions};
+use crate::services::subgraph::{self, SubgraphExport};
+use crate::services::watermark::Watermark;
+
+/// Write platform-sized crops of an image asset to the project's `exports/`
+/// folder. Presets are ratios (`"1:1"`, `"9:16"`, `"16:9"`) or custom
+/// `{ width, height, name? }` sizes. The project watermark, if configured,
+/// is applied to every file.
+#[tauri::command]
+pub async fn export_crops(
+    asset_id: String,
+    presets: Vec<CropPreset>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<ExportedFile>, AppError> {
+    if presets.is_empty() {
+        return Err(AppError::Unknown("No crop presets given".to_string()));
+    }
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let asset = io_sqlite::get_asset(&conn, &asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    
+    disk_space::ensure_space(&app, &project_path.join(export::EXPORT_DIR), export::estimated_size(&presets)?)?;
+    
+    let watermark = Watermark::load(&project_path)?;
+    let operation = state.operations.register(operation_id, OperationKind::Export, format!("Crops of {}", asset.sys.name))?;
+    let cancel = operation.token().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        export::export_crops(&project_path, &asset, &presets, watermark.as_ref(), &cancel)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Export failed: {}", e)))?
+}
+
+/// Lay out selected nodes (or the whole board) into a printable PDF in the
+/// project's `exports/` folder.
+#[tauri::command]
+pub async fn export_pdf(
+    options: Option<PdfExportOptions>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<PdfExport, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    let options = options.unwrap_or_default();
+    let estimate = pdf_export::estimated_size(&project_path, &project, &options)?;
+    disk_space::ensure_space(&app, &project_path.join(export::EXPORT_DIR), estimate)?;
+    let watermark = Watermark::load(&project_path)?;
+    
+    let operation = state.operations.register(operation_id, OperationKind::Export, "PDF")?;
+    let cancel = operation.token().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        pdf_export::export_pdf(&project_path, &project, &options, watermark.as_ref(), &cancel)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Export failed: {}", e)))?
+}
+
+/// Spin the given nodes (with everything nested in them) out into a new
+/// project at `dest`, copying their assets, internal edges and files.
+#[tauri::command]
+pub async fn export_subgraph(
+    node_ids: Vec<String>,
+    dest: String,
+    include_history: Option<bool>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SubgraphExport, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    
+    let operation = state.operations.register(operation_id, OperationKind::Export, format!("Project at {}", dest))?;
+    let cancel = operation.token().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        subgraph::export_subgraph(&project_path, &project, &node_ids, &PathBuf::from(dest), include_history.unwrap_or(false), &cancel)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Export failed: {}", e)))?
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<'_, AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,91 @@
+//! Face commands: local face detection and grouping reference photos by person.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, faces, io_sqlite};
+use crate::services::faces::{FaceDetector, FaceGroup, FaceSettings, FaceTask};
+use crate::services::job_queue::Job;
+use super::jobs::{spawn_local_job, JobInfo};
+
+#[tauri::command]
+pub fn get_face_settings(state: State<AppState>) -> Result<FaceSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    faces::get_settings(&conn)
+}
+
+/// Store whether to scan imports and how close faces must be to match.
+#[tauri::command]
+pub fn save_face_settings(settings: FaceSettings, state: State<AppState>) -> Result<FaceSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    faces::save_settings(&conn, &settings)
+}
+
+/// Scan image assets for faces in the background: the given ones, or every
+/// image not scanned yet. `force` rescans. The groups by person arrive with
+/// `job:completed`.
+#[tauri::command]
+pub fn detect_faces(
+    asset_ids: Option<Vec<String>>,
+    force: Option<bool>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = open_project_db(&state)?;
+    let detector = FaceDetector::find().ok_or_else(|| AppError::NotFound(
+        "Local face detector not found; set SYNNIA_FACE_RUNNER and download a face-detection model (or set SYNNIA_FACE_MODEL)".to_string()
+    ))?;
+    let tasks = faces::pending_tasks(&conn, &project_path, asset_ids.as_deref(), force.unwrap_or(false))?;
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    
+    // The model uses every core; run one image at a time
+    let job = Job::new(faces::JOB_KIND, serde_json::Value::Null, params, 1);
+    let root = project_path.clone();
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| {
+            let task: FaceTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+            detector.detect_and_store(&root, &task.src).map(serde_json::Value::from)
+        },
+        move |_| {
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            let settings = faces::get_settings(&conn)?;
+            Ok(serde_json::to_value(faces::group_by_face(&conn, settings.match_threshold)?)?)
+        },
+    ))
+}
+
+/// Group the scanned assets by the people in them, largest group first.
+/// `threshold` (cosine similarity, 0..1) overrides the project setting.
+#[tauri::command]
+pub fn group_assets_by_face(threshold: Option<f32>, state: State<AppState>) -> Result<Vec<FaceGroup>, AppError> {
+    let conn = open_project_db(&state)?;
+    let threshold = match threshold {
+        Some(t) => t,
+        None => faces::get_settings(&conn)?.match_threshold,
+    };
+    faces::group_by_face(&conn, threshold)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
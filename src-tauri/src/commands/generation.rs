@@ -0,0 +1,174 @@
+//! Generation tracking commands: stored seeds/params, variations, AI
+//! inpaint/outpaint edits and prompt-from-image.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::{GenerationParams, GenerationRecord};
+use crate::services::{database, generation, image_edit, interrogate, io_sqlite};
+use crate::services::generation::{SeedStrategy, VariationPayload};
+use crate::services::image_edit::{EditMode, EditPayload, EditProvider};
+use crate::services::interrogate::{InterrogatePayload, LocalInterrogator};
+use crate::services::job_queue::Job;
+use super::jobs::{spawn_local_job, submit_job, JobInfo};
+
+/// Record the seed/model/params used to generate an asset.
+#[tauri::command]
+pub fn record_generation(
+    asset_id: String,
+    params: GenerationParams,
+    recipe_id: Option<String>,
+    parent_asset_id: Option<String>,
+    state: State<AppState>,
+) -> Result<GenerationRecord, AppError> {
+    let conn = open_conn(&state)?;
+    let record = GenerationRecord {
+        asset_id,
+        recipe_id,
+        parent_asset_id,
+        params,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    generation::save_record(&conn, &record)?;
+    Ok(record)
+}
+
+/// Get the stored generation parameters for an asset
+#[tauri::command]
+pub fn get_generation_record(
+    asset_id: String,
+    state: State<AppState>,
+) -> Result<Option<GenerationRecord>, AppError> {
+    let conn = open_conn(&state)?;
+    generation::get_record(&conn, &asset_id)
+}
+
+/// List variations generated from an asset
+#[tauri::command]
+pub fn get_asset_variations(
+    asset_id: String,
+    state: State<AppState>,
+) -> Result<Vec<GenerationRecord>, AppError> {
+    let conn = open_conn(&state)?;
+    generation::get_variations(&conn, &asset_id)
+}
+
+/// Re-run a generated asset with its stored parameters plus `overrides`.
+/// The task is dispatched through the job queue (`job:task` with kind
+/// "variation"); on completion a new linked asset is created next to the source.
+#[tauri::command]
+pub fn regenerate_variation(
+    asset_id: String,
+    overrides: Option<serde_json::Value>,
+    seed_strategy: Option<SeedStrategy>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let conn = open_conn(&state)?;
+    let record = generation::get_record(&conn, &asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("No generation parameters stored for asset: {}", asset_id)))?;
+    
+    let overrides = overrides.unwrap_or_else(|| serde_json::json!({}));
+    let params = generation::derive_params(&record.params, &overrides, seed_strategy.unwrap_or_default())?;
+    
+    let payload = VariationPayload {
+        source_asset_id: asset_id.clone(),
+        recipe_id: record.recipe_id.clone(),
+        params: params.clone(),
+    };
+    let task = serde_json::json!({
+        "sourceAssetId": asset_id,
+        "recipeId": record.recipe_id,
+        "params": params,
+    });
+    
+    let job = Job::new(generation::VARIATION_JOB_KIND, serde_json::to_value(&payload)?, vec![task], 1);
+    submit_job(&app, &state, job)
+}
+
+/// Inpaint (or outpaint) an image asset with `prompt`, guided by a mask
+/// asset. The prepared image and mask are dispatched through the job queue
+/// (`job:task` with kind "image-edit") to the chosen provider; on completion
+/// the edit becomes a new linked asset next to the source.
+#[tauri::command]
+pub fn edit_image_ai(
+    asset_id: String,
+    mask_asset_id: String,
+    prompt: String,
+    mode: Option<EditMode>,
+    provider: Option<EditProvider>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    
+    let payload = EditPayload {
+        source_asset_id: asset_id,
+        mask_asset_id,
+        prompt,
+        mode: mode.unwrap_or_default(),
+        provider: provider.unwrap_or_default(),
+    };
+    let task = image_edit::build_task(&project_path, &payload)?;
+    
+    let job = Job::new(image_edit::JOB_KIND, serde_json::to_value(&payload)?, vec![task], 1);
+    submit_job(&app, &state, job)
+}
+
+/// Describe an image asset as a prompt and tag list, stored in its
+/// `valueMeta.prompt`/`valueMeta.tags` and the tag index. The "local"
+/// provider (default) runs the CLIP interrogator in the background; other
+/// providers are dispatched as `job:task`. The result arrives with
+/// `job:completed`.
+#[tauri::command]
+pub fn interrogate_image(
+    asset_id: String,
+    provider: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = open_conn(&state)?;
+    
+    let payload = InterrogatePayload {
+        asset_id,
+        provider: provider.unwrap_or_else(|| interrogate::LOCAL_PROVIDER.to_string()),
+    };
+    let task = interrogate::build_task(&conn, &project_path, &payload)?;
+    let job = Job::new(interrogate::JOB_KIND, serde_json::to_value(&payload)?, vec![task], 1);
+    
+    if payload.provider != interrogate::LOCAL_PROVIDER {
+        return submit_job(&app, &state, job);
+    }
+    
+    let interrogator = LocalInterrogator::find().ok_or_else(|| AppError::NotFound(
+        "Local interrogator not found; set SYNNIA_INTERROGATOR and SYNNIA_CLIP_MODEL".to_string()
+    ))?;
+    let root = project_path.clone();
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| interrogator.run(&root, params),
+        move |job| Ok(serde_json::to_value(interrogate::finalize_interrogation(&project_path, job)?)?),
+    ))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_conn(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
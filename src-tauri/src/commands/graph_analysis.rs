@@ -0,0 +1,28 @@
+//! Graph statistics commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::io_sqlite;
+use crate::services::graph_analysis::{self, GraphAnalysis};
+
+/// Statistics and complexity warnings for the current board.
+#[tauri::command]
+pub fn analyze_graph(state: State<AppState>) -> Result<GraphAnalysis, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    Ok(graph_analysis::analyze_graph(&project))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
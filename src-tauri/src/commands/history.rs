@@ -4,17 +4,25 @@ use tauri::State;
 use crate::error::AppError;
 use crate::AppState;
 use crate::models::Asset;
-use crate::services::{database, history, io_sqlite, hash};
+use crate::services::{compaction, database, file_history, history, io_sqlite, hash, timeline};
+use crate::services::file_history::FileVersion;
+use crate::services::history::RestoreResult;
+use crate::services::timeline::{TimelineRange, TimelineSession};
+use crate::services::compaction::FlattenReport;
 use std::path::PathBuf;
+use ts_rs::TS;
 
 /// History entry for frontend
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryEntry {
+    #[ts(type = "number")]
     pub id: i64,
     pub asset_id: String,
     pub content_hash: String,
     pub content_preview: String, // Truncated content for display
+    #[ts(type = "number")]
     pub created_at: i64,
 }
 
@@ -27,15 +35,17 @@ pub fn save_asset_with_history(
     let project_path = get_project_path(&state)?;
     let db_path = io_sqlite::get_db_path(&project_path);
     
-    let conn = database::open_db(&db_path)
+    let mut conn = database::open_db(&db_path)
         .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
     
     // Serialize value (was content)
     let value_json = serde_json::to_string(&asset.value)?;
     let new_hash = hash::compute_content_hash(&value_json);
     
     // Get old hash to check if changed
-    let old_hash = history::get_current_hash(&conn, &asset.id)
+    let old_hash = history::get_current_hash(&tx, &asset.id)
         .map_err(|e| AppError::Io(format!("Failed to get current hash: {}", e)))?;
     
     let hash_changed = old_hash.as_ref() != Some(&new_hash);
@@ -44,15 +54,17 @@ pub fn save_asset_with_history(
     if hash_changed {
         if let Some(ref old) = old_hash {
             // Get old value for snapshot
-            let old_value: Option<String> = conn.query_row(
+            let old_value: Option<String> = tx.query_row(
                 "SELECT value_json FROM assets WHERE id = ?1",
                 rusqlite::params![&asset.id],
                 |row| row.get(0),
             ).ok();
             
             if let Some(old_value) = old_value {
-                history::create_snapshot_if_changed(&conn, &asset.id, old, &old_value)
+                history::create_snapshot_if_changed(&tx, &asset.id, old, &old_value)
                     .map_err(|e| AppError::Io(format!("Failed to create snapshot: {}", e)))?;
+                let old_value: serde_json::Value = serde_json::from_str(&old_value)?;
+                file_history::track_replacement(&tx, &project_path, &asset.id, &old_value, &asset.value)?;
             }
         }
     }
@@ -64,7 +76,7 @@ pub fn save_asset_with_history(
     let now = chrono::Utc::now().timestamp_millis();
     let value_type_str = serde_json::to_string(&asset.value_type)?;
     
-    conn.execute(
+    tx.execute(
         "INSERT INTO assets (id, value_type, value_hash, value_json, value_meta_json, config_json, sys_json, updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
          ON CONFLICT(id) DO UPDATE SET
@@ -87,6 +99,8 @@ pub fn save_asset_with_history(
         ],
     ).map_err(|e| AppError::Io(format!("Failed to save asset: {}", e)))?;
     
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
     Ok(hash_changed)
 }
 
@@ -159,42 +173,62 @@ pub fn get_history_content(
     Ok(entry.content_json)
 }
 
-/// Restore an asset to a specific history version
+/// Restore an asset to a specific history version. The current content is
+/// snapshotted first, so the restore itself can be undone.
 #[tauri::command]
 pub fn restore_asset_version(
     asset_id: String,
     history_id: i64,
     state: State<AppState>,
-) -> Result<serde_json::Value, AppError> {
+) -> Result<RestoreResult, AppError> {
     let project_path = get_project_path(&state)?;
     let db_path = io_sqlite::get_db_path(&project_path);
     
-    let conn = database::open_db(&db_path)
+    let mut conn = database::open_db(&db_path)
         .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
     
-    // Get the history entry
-    let entry = history::get_history_entry(&conn, history_id)
-        .map_err(|e| AppError::Io(format!("Failed to get history entry: {}", e)))?
-        .ok_or_else(|| AppError::NotFound("History entry not found".to_string()))?;
-    
-    // Verify it belongs to the right asset
-    if entry.asset_id != asset_id {
-        return Err(AppError::Unknown("History entry does not belong to this asset".to_string()));
-    }
-    
-    // Parse the content
-    let content: serde_json::Value = serde_json::from_str(&entry.content_json)?;
-    
-    // Update the asset with restored content
-    let now = chrono::Utc::now().timestamp_millis();
-    let new_hash = crate::services::hash::compute_content_hash(&entry.content_json);
-    
-    conn.execute(
-        "UPDATE assets SET value_json = ?1, value_hash = ?2, updated_at = ?3 WHERE id = ?4",
-        rusqlite::params![&entry.content_json, &new_hash, now, &asset_id],
-    ).map_err(|e| AppError::Io(format!("Failed to restore asset: {}", e)))?;
-    
-    Ok(content)
+    let result = history::restore_version(&tx, &asset_id, history_id)?;
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+    Ok(result)
+}
+
+/// Earlier image files of an asset, newest first
+#[tauri::command]
+pub fn get_file_history(
+    asset_id: String,
+    state: State<AppState>,
+) -> Result<Vec<FileVersion>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    file_history::list_versions(&conn, &asset_id)
+}
+
+/// Make an earlier image file the asset's current one again
+#[tauri::command]
+pub fn restore_file_version(
+    asset_id: String,
+    version_id: i64,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let project_path = get_project_path(&state)?;
+    file_history::restore_version(&project_path, &asset_id, version_id)
+}
+
+/// Activity, asset versions and kept image files merged into one feed,
+/// grouped by working session, newest first
+#[tauri::command]
+pub fn get_project_history_timeline(
+    range: Option<TimelineRange>,
+    state: State<AppState>,
+) -> Result<Vec<TimelineSession>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    timeline::get_timeline(&conn, &range.unwrap_or_default())
 }
 
 /// Count history entries for an asset
@@ -213,6 +247,19 @@ pub fn count_asset_history(
         .map_err(|e| AppError::Io(format!("Failed to count history: {}", e)))
 }
 
+/// Compact the current project: keep the newest `keep_last_n` versions per
+/// asset, clear leftovers of deleted content and unreferenced files, and
+/// rewrite the database. With `dry_run`, only report what would be removed.
+#[tauri::command]
+pub fn flatten_project(
+    keep_last_n: usize,
+    dry_run: Option<bool>,
+    state: State<AppState>,
+) -> Result<FlattenReport, AppError> {
+    let project_path = get_project_path(&state)?;
+    compaction::flatten_project(&project_path, keep_last_n, dry_run.unwrap_or(false))
+}
+
 // Helper functions
 
 fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
@@ -1,38 +1,265 @@
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use crate::config::GlobalConfig;
 use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::AppState;
+use crate::services::cancellation::OperationKind;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::validation;
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How the response body is returned to the frontend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseMode {
+    /// Text in the Content-Type charset, UTF-8 if none (lossy for binary content)
+    #[default]
+    Text,
+    /// Base64-encoded bytes, safe for images and other binary payloads
+    Base64,
+    /// Written to a temp file; `filePath` holds its location
+    File,
+}
+
+/// Optional request settings
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyOptions {
+    /// Total request timeout in milliseconds
+    #[ts(type = "number | null")]
+    pub timeout_ms: Option<u64>,
+    /// Connection timeout in milliseconds
+    #[ts(type = "number | null")]
+    pub connect_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub response_mode: ResponseMode,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
 pub struct ProxyResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: String,
+    /// "text" or "base64"
+    pub body_encoding: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+}
+
+/// Payload of the `proxy:chunk` event emitted by `proxy_request_stream`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyChunk {
+    pub request_id: String,
+    pub chunk: String,
+    pub body_encoding: String,
 }
 
 /// Proxy an HTTP request to avoid CORS issues with local services
-/// Supports Ollama, ComfyUI, and other local AI services
+/// Supports Ollama, ComfyUI, and other local AI services.
+/// Pass `operation_id` to be able to abort it with `cancel_operation`.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn proxy_request(
     url: String,
     method: String,
     headers: HashMap<String, String>,
     body: Option<String>,
+    options: Option<ProxyOptions>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ProxyResponse, AppError> {
+    let options = options.unwrap_or_default();
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    let operation = state.operations.register(operation_id, OperationKind::ProxyRequest, format!("{} {}", method, url))?;
+
+    let (status, response_headers, bytes) = operation.token().run(async {
+        let response = send_request(&policy, &url, &method, headers, body, &options, false).await?;
+
+        // Extract response data
+        let status = response.status().as_u16();
+        let response_headers = collect_headers(&response);
+        let bytes = read_body(response, validation::MAX_RESPONSE_BODY_BYTES).await?;
+        Ok((status, response_headers, bytes))
+    }).await?;
+
+    match options.response_mode {
+        ResponseMode::Text => Ok(ProxyResponse {
+            status,
+            body: TextDecoder::new(response_headers.get("content-type").map(String::as_str)).decode(&bytes, true),
+            headers: response_headers,
+            body_encoding: "text".to_string(),
+            file_path: None,
+        }),
+        ResponseMode::Base64 => Ok(ProxyResponse {
+            status,
+            headers: response_headers,
+            body: base64::engine::general_purpose::STANDARD.encode(&bytes),
+            body_encoding: "base64".to_string(),
+            file_path: None,
+        }),
+        ResponseMode::File => {
+            let path = temp_file_path(&response_headers)?;
+            std::fs::write(&path, &bytes)?;
+            Ok(ProxyResponse {
+                status,
+                headers: response_headers,
+                body: String::new(),
+                body_encoding: "text".to_string(),
+                file_path: Some(path.to_string_lossy().to_string()),
+            })
+        }
+    }
+}
+
+/// Proxy an HTTP request and forward the body as it arrives.
+/// Each chunk is emitted as a `proxy:chunk` event tagged with `request_id`
+/// (SSE streams from Ollama, progress from ComfyUI). The returned response
+/// carries status and headers; in File mode the body is also written to disk.
+/// `request_id` doubles as the operation ID for `cancel_operation`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn proxy_request_stream(
+    request_id: String,
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    options: Option<ProxyOptions>,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<ProxyResponse, AppError> {
-    let client = reqwest::Client::new();
+    let options = options.unwrap_or_default();
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    let operation = state.operations.register(Some(request_id.clone()), OperationKind::ProxyRequest, format!("{} {}", method, url))?;
+
+    operation.token().run(async {
+        let mut response = send_request(&policy, &url, &method, headers, body, &options, true).await?;
+
+        let status = response.status().as_u16();
+        let response_headers = collect_headers(&response);
+
+        // Chunks are base64 unless the body is text; in File mode the body
+        // itself is only on disk, so the response carries none to decode
+        let mut text = TextDecoder::new(response_headers.get("content-type").map(String::as_str));
+        let (encoding, body_encoding, mut file, file_path) = match options.response_mode {
+            ResponseMode::Text => ("text", "text", None, None),
+            ResponseMode::Base64 => ("base64", "base64", None, None),
+            ResponseMode::File => {
+                let path = temp_file_path(&response_headers)?;
+                let file = std::fs::File::create(&path)?;
+                ("base64", "text", Some(file), Some(path.to_string_lossy().to_string()))
+            }
+        };
+
+        while let Some(bytes) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?
+        {
+            if let Some(file) = file.as_mut() {
+                file.write_all(&bytes)?;
+            }
+
+            let chunk = if encoding == "text" {
+                text.decode(&bytes, false)
+            } else {
+                base64::engine::general_purpose::STANDARD.encode(&bytes)
+            };
+            if chunk.is_empty() {
+                continue;
+            }
+
+            events::emit(&app, AppEvent::ProxyChunk(ProxyChunk {
+                request_id: request_id.clone(),
+                chunk,
+                body_encoding: encoding.to_string(),
+            }))?;
+        }
+
+        // A character cut off by the end of the body
+        if encoding == "text" {
+            let rest = text.decode(&[], true);
+            if !rest.is_empty() {
+                events::emit(&app, AppEvent::ProxyChunk(ProxyChunk {
+                    request_id: request_id.clone(),
+                    chunk: rest,
+                    body_encoding: encoding.to_string(),
+                }))?;
+            }
+        }
+
+        Ok(ProxyResponse {
+            status,
+            headers: response_headers,
+            body: String::new(),
+            body_encoding: body_encoding.to_string(),
+            file_path,
+        })
+    }).await
+}
+
+// Helper functions
+
+async fn send_request(
+    policy: &NetworkPolicy,
+    url: &str,
+    method: &str,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    options: &ProxyOptions,
+    streaming: bool,
+) -> Result<reqwest::Response, AppError> {
+    validation::check_url(url)?;
+    validation::check_body_size(body.as_deref(), validation::MAX_REQUEST_BODY_BYTES)?;
+    let url = policy.check_url(url).await?;
+
+    // Streams are only cut off after going silent; other calls have a total
+    // limit. Either can be overridden per request.
+    let mut client_builder = policy.client_builder();
+    if let Some(ms) = options.timeout_ms {
+        client_builder = client_builder.timeout(Duration::from_millis(ms));
+    } else if streaming {
+        client_builder = client_builder.read_timeout(policy.timeouts.idle());
+    } else {
+        client_builder = client_builder.timeout(policy.timeouts.request());
+    }
+    if let Some(ms) = options.connect_timeout_ms {
+        client_builder = client_builder.connect_timeout(Duration::from_millis(ms));
+    }
+    let client = client_builder
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
     
     // Build request
     let mut request_builder = match method.to_uppercase().as_str() {
-        "GET" => client.get(&url),
-        "POST" => client.post(&url),
-        "PUT" => client.put(&url),
-        "DELETE" => client.delete(&url),
-        "PATCH" => client.patch(&url),
-        _ => return Err(AppError::Unknown(format!("Unsupported HTTP method: {}", method))),
+        "GET" => client.get(url),
+        "POST" => client.post(url),
+        "PUT" => client.put(url),
+        "DELETE" => client.delete(url),
+        "PATCH" => client.patch(url),
+        _ => return Err(AppError::InvalidInput(format!("Unsupported HTTP method: {}", method))),
     };
 
     // Add headers
     for (key, value) in headers {
-        request_builder = request_builder.header(&key, &value);
+        let name = reqwest::header::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|_| AppError::InvalidInput(format!("Invalid header name: {}", key)))?;
+        let value = reqwest::header::HeaderValue::from_str(&value)
+            .map_err(|_| AppError::InvalidInput(format!("Invalid value for header {}", key)))?;
+        request_builder = request_builder.header(name, value);
     }
 
     // Add body if present
@@ -41,29 +268,146 @@ pub async fn proxy_request(
     }
 
     // Execute request
-    let response = request_builder
+    request_builder
         .send()
         .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+        .map_err(|e| {
+            if e.is_timeout() {
+                AppError::Network(format!("Request timed out: {}", e))
+            } else {
+                AppError::Network(e.to_string())
+            }
+        })
+}
 
-    // Extract response data
-    let status = response.status().as_u16();
-    let response_headers: HashMap<String, String> = response
+/// Read a whole response body, failing once it grows past `max_bytes`
+async fn read_body(mut response: reqwest::Response, max_bytes: usize) -> Result<Vec<u8>, AppError> {
+    let too_large = || AppError::InvalidInput(format!("Response body exceeds {} bytes", max_bytes));
+    if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+fn collect_headers(response: &reqwest::Response) -> HashMap<String, String> {
+    response
         .headers()
         .iter()
         .filter_map(|(k, v)| {
             v.to_str().ok().map(|val| (k.to_string(), val.to_string()))
         })
+        .collect()
+}
+
+/// Decodes a text body as `Response::text` would: in the Content-Type
+/// charset, UTF-8 if there is none. Fed chunk by chunk, a character split
+/// between chunks is held back until the rest of it arrives.
+struct TextDecoder {
+    decoder: encoding_rs::Decoder,
+}
+
+impl TextDecoder {
+    fn new(content_type: Option<&str>) -> Self {
+        let encoding = content_type
+            .and_then(|ct| {
+                ct.split(';').skip(1).find_map(|param| {
+                    let (name, value) = param.split_once('=')?;
+                    name.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"'))
+                })
+            })
+            .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        Self { decoder: encoding.new_decoder() }
+    }
+
+    /// Decode the next bytes; `last` flushes anything held back
+    fn decode(&mut self, bytes: &[u8], last: bool) -> String {
+        let capacity = self.decoder.max_utf8_buffer_length(bytes.len()).unwrap_or(bytes.len() * 3 + 4);
+        let mut text = String::with_capacity(capacity);
+        let _ = self.decoder.decode_to_string(bytes, &mut text, last);
+        text
+    }
+}
+
+/// Longest extension kept from a Content-Type subtype
+const MAX_EXTENSION_LEN: usize = 10;
+
+/// Temp file for File mode, with an extension guessed from Content-Type
+fn temp_file_path(headers: &HashMap<String, String>) -> Result<PathBuf, AppError> {
+    let dir = std::env::temp_dir().join("synnia-proxy");
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+    }
+
+    let ext = file_extension(headers.get("content-type").map(String::as_str));
+    validation::resolve_in_project(&dir, &format!("{}.{}", uuid::Uuid::new_v4(), ext))
+}
+
+/// Extension for a Content-Type: its subtype, kept to a few ASCII letters and
+/// digits since the header comes from the remote server
+fn file_extension(content_type: Option<&str>) -> String {
+    let ext: String = content_type
+        .and_then(|ct| ct.split(';').next())
+        .and_then(|ct| ct.split('/').nth(1))
+        .map(|sub| match sub.trim() {
+            "jpeg" => "jpg",
+            "svg+xml" => "svg",
+            "octet-stream" => "bin",
+            other => other,
+        })
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(MAX_EXTENSION_LEN)
         .collect();
 
-    let response_body = response
-        .text()
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    if ext.is_empty() { "bin".to_string() } else { ext.to_ascii_lowercase() }
+}
 
-    Ok(ProxyResponse {
-        status,
-        headers: response_headers,
-        body: response_body,
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_extension_from_content_type() {
+        assert_eq!(file_extension(Some("image/jpeg")), "jpg");
+        assert_eq!(file_extension(Some("image/svg+xml; charset=utf-8")), "svg");
+        assert_eq!(file_extension(Some("image/PNG")), "png");
+        assert_eq!(file_extension(Some("text/../../etc/passwd")), "bin");
+        assert_eq!(file_extension(Some("x/..\\..\\evil.exe")), "evilexe");
+        assert_eq!(file_extension(Some("application/aaaaaaaaaaaaaaaaaaaaaaaa")), "aaaaaaaaaa");
+        assert_eq!(file_extension(Some("garbage")), "bin");
+        assert_eq!(file_extension(None), "bin");
+    }
+
+    #[test]
+    fn test_text_decoding() {
+        // "é" split between two chunks comes out whole
+        let mut text = TextDecoder::new(Some("text/event-stream"));
+        assert_eq!(text.decode(b"caf\xC3", false), "caf");
+        assert_eq!(text.decode(b"\xA9 au lait", false), "\u{e9} au lait");
+        assert_eq!(text.decode(&[], true), "");
+        
+        // Cut off for good at the end of the body
+        let mut text = TextDecoder::new(None);
+        assert_eq!(text.decode(b"\xE2\x82", false), "");
+        assert_eq!(text.decode(&[], true), "\u{fffd}");
+        
+        let latin1 = TextDecoder::new(Some("text/plain; charset=\"ISO-8859-1\"")).decode(b"caf\xE9", true);
+        assert_eq!(latin1, "caf\u{e9}");
+        let unknown = TextDecoder::new(Some("text/plain; charset=klingon")).decode("caf\u{e9}".as_bytes(), true);
+        assert_eq!(unknown, "caf\u{e9}");
+    }
 }
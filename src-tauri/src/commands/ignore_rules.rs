@@ -0,0 +1,39 @@
+//! Per-project ignore rule commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, ignore_rules, io_sqlite};
+
+/// The project's ignore patterns (`.gitignore` syntax), or the defaults
+#[tauri::command]
+pub fn get_ignore_rules(state: State<AppState>) -> Result<Vec<String>, AppError> {
+    let conn = open_project_db(&state)?;
+    ignore_rules::get_patterns(&conn)
+}
+
+/// Replace the project's ignore patterns; returns them as stored.
+#[tauri::command]
+pub fn save_ignore_rules(patterns: Vec<String>, state: State<AppState>) -> Result<Vec<String>, AppError> {
+    let conn = open_project_db(&state)?;
+    ignore_rules::save_patterns(&conn, &patterns)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,283 @@
+//! Job queue commands: batch generation grids and task reporting.
+//!
+//! Tasks are emitted to the frontend as `job:task` events; the frontend runs
+//! them with its recipe executors and reports back via `complete_job_task`.
+//! Without a window (`--server`), workers take the same events from
+//! `/events` and report over the REST API (`rest_api`).
+//! Jobs over the project's budget are held until `confirm_job_budget`.
+
+use tauri::{State, AppHandle};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::error::AppError;
+use crate::events::{self, AppEvent, JobCompleted, JobProgress};
+use crate::AppState;
+use crate::services::{auto_tag, budget, database, generation, generation_grid, image_edit, interrogate, io_sqlite, translation, upscale};
+use crate::services::generation_grid::{GridAxis, GridPayload};
+use crate::services::job_queue::{Job, JobQueue, TaskDispatch};
+use super::safety::check_new_images;
+use ts_rs::TS;
+
+/// Returned when a job is submitted
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub job_id: String,
+    pub total: usize,
+    /// Held over budget until `confirm_job_budget`
+    pub held: bool,
+}
+
+/// Run a recipe node over a parameter matrix (e.g. 4 prompts × 3 styles).
+/// Results become an array asset plus a grid of nodes next to the recipe.
+#[tauri::command]
+pub fn run_generation_grid(
+    recipe_node_id: String,
+    variations: Vec<GridAxis>,
+    concurrency: Option<usize>,
+    output_node_type: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    submit_generation_grid(&app, &state, recipe_node_id, variations, concurrency, output_node_type)
+}
+
+/// Report the outcome of a task. Starts the next pending tasks and, once the
+/// whole job has settled, runs its finalizer and emits `job:completed`.
+#[tauri::command]
+pub fn complete_job_task(
+    job_id: String,
+    task_index: usize,
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let outcome = match error {
+        Some(e) => Err(e),
+        None => Ok(result.unwrap_or(serde_json::Value::Null)),
+    };
+    finish_task(&app, &state, &job_id, task_index, outcome)
+}
+
+/// Cancel a job; running tasks finish on the frontend but their results are dropped.
+#[tauri::command]
+pub fn cancel_job(job_id: String, state: State<AppState>, app: AppHandle) -> Result<Job, AppError> {
+    let job = state.jobs.cancel(&job_id)?;
+    settle_spend(&job);
+    events::emit(&app, AppEvent::JobCancelled(job.clone()))?;
+    Ok(job)
+}
+
+/// Answer a `budget:exceeded` hold: `approve` charges the job to the budget
+/// and starts it, otherwise it is cancelled.
+#[tauri::command]
+pub fn confirm_job_budget(job_id: String, approve: bool, state: State<AppState>, app: AppHandle) -> Result<Job, AppError> {
+    let job = state.jobs.get(&job_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))?;
+    if !job.held {
+        return Err(AppError::InvalidInput(format!("Job is not waiting for confirmation: {}", job_id)));
+    }
+    if !approve {
+        return cancel_job(job_id, state, app);
+    }
+    
+    budget::charge(&open_db(&job_root(&job)?)?, &job)?;
+    let dispatches = state.jobs.release(&job_id)?;
+    emit_dispatches(&app, &dispatches)?;
+    state.jobs.get(&job_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))
+}
+
+/// Get a running job with per-task status
+#[tauri::command]
+pub fn get_job(job_id: String, state: State<AppState>) -> Result<Job, AppError> {
+    state.jobs.get(&job_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))
+}
+
+/// List running jobs with per-task status
+#[tauri::command]
+pub fn get_jobs(state: State<AppState>) -> Result<Vec<Job>, AppError> {
+    state.jobs.list()
+}
+
+// Helper functions
+
+/// Build and submit a generation grid job (`run_generation_grid`, REST)
+pub(crate) fn submit_generation_grid(
+    app: &AppHandle,
+    state: &AppState,
+    recipe_node_id: String,
+    variations: Vec<GridAxis>,
+    concurrency: Option<usize>,
+    output_node_type: Option<String>,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let recipe = io_sqlite::get_node(&conn, &recipe_node_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Recipe node not found: {}", recipe_node_id)))?;
+    
+    let cells = generation_grid::expand_matrix(&variations)?;
+    
+    // Each task carries the recipe to run alongside its parameter overrides
+    let params = cells
+        .into_iter()
+        .map(|inputs| serde_json::json!({
+            "recipeNodeId": recipe.id,
+            "recipeId": recipe.data.recipe_id,
+            "inputs": inputs,
+        }))
+        .collect();
+    
+    let payload = GridPayload {
+        recipe_node_id,
+        axes: variations,
+        output_node_type: output_node_type.unwrap_or_else(|| "image-node".to_string()),
+    };
+    let job = Job::new(generation_grid::JOB_KIND, serde_json::to_value(&payload)?, params, concurrency.unwrap_or(2));
+    submit_job(app, state, job)
+}
+
+/// Record a task's outcome (`complete_job_task`, REST). Starts the next
+/// pending tasks and, once the job has settled, finalizes it.
+pub(crate) fn finish_task(
+    app: &AppHandle,
+    state: &AppState,
+    job_id: &str,
+    task_index: usize,
+    outcome: Result<serde_json::Value, String>,
+) -> Result<(), AppError> {
+    let update = state.jobs.complete_task(job_id, task_index, outcome)?;
+    emit_dispatches(app, &update.dispatches)?;
+    
+    if let Some(job) = update.finished {
+        settle_spend(&job);
+        let output = match job.kind.as_str() {
+            generation_grid::JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(generation_grid::finalize_grid(&project_path, &job)?)?
+            }
+            generation::VARIATION_JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(generation::finalize_variation(&project_path, &job)?)?
+            }
+            image_edit::JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(image_edit::finalize_edit(&project_path, &job)?)?
+            }
+            auto_tag::JOB_KIND => {
+                let conn = open_db(&job_root(&job)?)?;
+                serde_json::to_value(auto_tag::finalize_auto_tag(&conn, &job)?)?
+            }
+            interrogate::JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(interrogate::finalize_interrogation(&project_path, &job)?)?
+            }
+            translation::TRANSLATION_JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(translation::finalize_translation(&project_path, &job)?)?
+            }
+            upscale::JOB_KIND => {
+                let project_path = job_root(&job)?;
+                serde_json::to_value(upscale::finalize_upscale(&project_path, &job)?)?
+            }
+            _ => serde_json::Value::Null,
+        };
+        
+        // Newly generated images go through the project's safety policy
+        if matches!(job.kind.as_str(), generation_grid::JOB_KIND | generation::VARIATION_JOB_KIND | image_edit::JOB_KIND | upscale::JOB_KIND) {
+            check_new_images(app, job_root(&job)?);
+        }
+        
+        events::emit(app, AppEvent::JobCompleted(JobCompleted { job, output }))?;
+    }
+    
+    Ok(())
+}
+
+/// Run a backend job on the local worker pool in the background. Emits
+/// `job:progress` as tasks settle and `job:completed` with the finalizer's
+/// output (cancelled jobs were already reported by `cancel_job`).
+pub(crate) fn spawn_local_job<W, F>(app: AppHandle, queue: Arc<JobQueue>, job: Job, worker: W, finalize: F) -> JobInfo
+where
+    W: Fn(&serde_json::Value) -> Result<serde_json::Value, String> + Send + Sync + 'static,
+    F: FnOnce(&Job) -> Result<serde_json::Value, AppError> + Send + 'static,
+{
+    let info = JobInfo { job_id: job.id.clone(), total: job.tasks.len(), held: false };
+    let total = info.total;
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut current = 0;
+        let result = queue.run_local(job, worker, |job, _| {
+            current += 1;
+            let _ = events::emit(&app, AppEvent::JobProgress(JobProgress { job_id: job.id.clone(), current, total }));
+        });
+        let Ok(job) = result else { return };
+        
+        let output = finalize(&job).unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }));
+        let _ = events::emit(&app, AppEvent::JobCompleted(JobCompleted { job, output }));
+    });
+    
+    info
+}
+
+/// Submit a job whose tasks run on the frontend (provider calls). A job
+/// that would exceed the project's budget is held and announced as
+/// `budget:exceeded`; anything else is charged and dispatched right away.
+pub(crate) fn submit_job(app: &AppHandle, state: &AppState, mut job: Job) -> Result<JobInfo, AppError> {
+    let project_root = get_project_path(state)?;
+    let conn = open_db(&project_root)?;
+    job.project_root = Some(project_root);
+    let exceeded = budget::check(&conn, &job)?;
+    job.held = exceeded.is_some();
+    if !job.held {
+        budget::charge(&conn, &job)?;
+    }
+    
+    let info = JobInfo { job_id: job.id.clone(), total: job.tasks.len(), held: job.held };
+    let dispatches = state.jobs.submit(job)?;
+    match exceeded {
+        Some(exceeded) => events::emit(app, AppEvent::BudgetExceeded(exceeded))?,
+        None => emit_dispatches(app, &dispatches)?,
+    }
+    Ok(info)
+}
+
+fn emit_dispatches(app: &AppHandle, dispatches: &[TaskDispatch]) -> Result<(), AppError> {
+    for dispatch in dispatches {
+        events::emit(app, AppEvent::JobTask(dispatch.clone()))?;
+    }
+    Ok(())
+}
+
+/// Settle a finished or cancelled job's charge; jobs outside a project
+/// were never charged.
+fn settle_spend(job: &Job) {
+    if let Some(conn) = job.project_root.as_deref().and_then(|root| open_db(root).ok()) {
+        let _ = budget::settle(&conn, job);
+    }
+}
+
+/// The project a job was submitted in
+fn job_root(job: &Job) -> Result<PathBuf, AppError> {
+    job.project_root.clone().ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_db(project_root: &Path) -> Result<rusqlite::Connection, AppError> {
+    database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
+
+fn get_project_path(state: &AppState) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
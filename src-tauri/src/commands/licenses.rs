@@ -0,0 +1,69 @@
+//! License commands: usage rights of assets and the rights report.
+
+use tauri::State;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite, licenses, validation};
+use crate::services::licenses::{AssetLicense, LicenseReport};
+
+/// Record an asset's license; `None` clears it.
+#[tauri::command]
+pub fn set_license(
+    asset_id: String,
+    license: Option<AssetLicense>,
+    state: State<AppState>,
+) -> Result<Option<AssetLicense>, AppError> {
+    let conn = open_project_db(&state)?;
+    if io_sqlite::get_asset(&conn, &asset_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Asset not found: {}", asset_id)));
+    }
+    licenses::set_license(&conn, &asset_id, license.as_ref())
+}
+
+#[tauri::command]
+pub fn get_license(asset_id: String, state: State<AppState>) -> Result<Option<AssetLicense>, AppError> {
+    let conn = open_project_db(&state)?;
+    licenses::get_license(&conn, &asset_id)
+}
+
+/// Rights of every asset from outside the app, flagging unknown, expired and
+/// expiring ones (within `expiring_days`, default 30). With `dest`, the
+/// report is also written there as JSON.
+#[tauri::command]
+pub fn license_report(
+    expiring_days: Option<u32>,
+    dest: Option<String>,
+    state: State<AppState>,
+) -> Result<LicenseReport, AppError> {
+    let conn = open_project_db(&state)?;
+    let report = licenses::report(&conn, expiring_days.unwrap_or(licenses::DEFAULT_EXPIRING_DAYS))?;
+
+    if let Some(dest) = dest {
+        let dest = validation::check_path(&dest)?;
+        if let Some(dir) = dest.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&dest, serde_json::to_string_pretty(&report)?)?;
+    }
+    Ok(report)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
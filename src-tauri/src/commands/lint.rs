@@ -0,0 +1,34 @@
+//! Board lint commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::io_sqlite;
+use crate::services::lint::{self, LintConfig, LintFinding};
+
+/// Check the current board for issues. Uses the project's `lint` settings
+/// unless `config` is given.
+#[tauri::command]
+pub fn lint_project(
+    config: Option<LintConfig>,
+    state: State<AppState>,
+) -> Result<Vec<LintFinding>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    
+    let config = config.unwrap_or_else(|| LintConfig::from_project(&project));
+    Ok(lint::lint_project(&project, &config))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
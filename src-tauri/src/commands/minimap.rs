@@ -0,0 +1,32 @@
+//! Minimap commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::io_sqlite;
+use crate::services::minimap::{self, Minimap};
+
+/// Render the current board as a `width` x `height` PNG (cached until the graph changes).
+#[tauri::command]
+pub fn render_minimap(
+    width: u32,
+    height: u32,
+    state: State<AppState>,
+) -> Result<Minimap, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    minimap::render_minimap(&project_path, &project, width, height)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -3,4 +3,49 @@ pub mod agent;
 pub mod asset;
 pub mod history;
 pub mod http_proxy;
+pub mod clip;
+pub mod jobs;
+pub mod generation;
+pub mod comparison;
+pub mod components;
+pub mod spellcheck;
+pub mod translation;
+pub mod lint;
+pub mod brand;
+pub mod export;
+pub mod planning;
+pub mod tasklist;
+pub mod reminders;
+pub mod activity;
+pub mod time_tracking;
+pub mod backup;
+pub mod node_locks;
+pub mod graph_analysis;
+pub mod minimap;
+pub mod references;
+pub mod viewport_bookmarks;
+pub mod canvases;
+pub mod thumbnails;
+pub mod tags;
+pub mod custom_fields;
+pub mod smart_collections;
+pub mod recipes;
+pub mod pipelines;
+pub mod faces;
+pub mod safety;
+pub mod permissions;
+pub mod ignore_rules;
+pub mod operations;
+pub mod usages;
+pub mod budget;
+pub mod scripts;
+pub mod templates;
+pub mod licenses;
+pub mod stock;
+pub mod connectors;
+pub mod trackers;
+pub mod share;
+pub mod models;
+#[cfg(test)]
+mod bindings;
 // pub mod graph; // Removed
\ No newline at end of file
@@ -0,0 +1,59 @@
+//! Local model commands: download, list and delete the ONNX/GGUF models
+//! used by on-device features.
+
+use tauri::{AppHandle, State};
+use crate::error::AppError;
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::services::download::DownloadOptions;
+use crate::services::model_manager::{self, InstalledModel, ModelDiskUsage, ModelDownload};
+use crate::services::net_guard::NetworkPolicy;
+
+/// Installed models, newest first.
+#[tauri::command]
+pub fn list_installed_models(app: AppHandle) -> Result<Vec<InstalledModel>, AppError> {
+    model_manager::list(&model_manager::models_dir(&app)?)
+}
+
+/// Download a model and install it once its SHA-256 matches. Progress is
+/// reported as `download:progress` under `download_id`, which
+/// `cancel_download` takes too. A model with the same checksum that is
+/// already installed is returned as is.
+#[tauri::command]
+pub async fn download_model(
+    request: ModelDownload,
+    download_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<InstalledModel, AppError> {
+    let root = model_manager::models_dir(&app)?;
+    if let Some(model) = model_manager::find_existing(&root, &request)? {
+        return Ok(model);
+    }
+    let prepared = model_manager::prepare(&root, &request)?;
+
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    // Models are large; a retry of the same model picks up where this left off
+    let options = DownloadOptions { max_bytes: model_manager::MAX_MODEL_BYTES, keep_partial: true, ..Default::default() };
+    state.downloads
+        .download(&app, &policy, &download_id, &request.url, &prepared.dest, &options)
+        .await?;
+
+    let source_name = reqwest::Url::parse(&request.url).ok()
+        .and_then(|u| u.path_segments()?.next_back().map(|s| s.to_string()))
+        .unwrap_or_else(|| request.kind.as_str().to_string());
+    // Hashing a multi-gigabyte file takes a while
+    state.blocking.run(move || model_manager::install(&root, &request, &prepared, &source_name)).await
+}
+
+/// Delete a model and its file. Returns false if it wasn't installed.
+#[tauri::command]
+pub fn delete_model(model_id: String, app: AppHandle) -> Result<bool, AppError> {
+    model_manager::delete(&model_manager::models_dir(&app)?, &model_id)
+}
+
+/// Disk used by installed models, per kind, and the space left.
+#[tauri::command]
+pub fn get_model_disk_usage(app: AppHandle) -> Result<ModelDiskUsage, AppError> {
+    model_manager::disk_usage(&model_manager::models_dir(&app)?)
+}
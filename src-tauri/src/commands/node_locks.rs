@@ -0,0 +1,34 @@
+//! Node locking and pinning commands.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::SynniaNode;
+use crate::services::{database, io_sqlite, node_locks};
+
+/// Lock/unlock and pin/unpin a node; omitted flags are left unchanged.
+#[tauri::command]
+pub fn set_node_lock(
+    node_id: String,
+    locked: Option<bool>,
+    pinned: Option<bool>,
+    state: State<AppState>,
+) -> Result<SynniaNode, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    node_locks::set_node_flags(&conn, &node_id, locked, pinned)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,21 @@
+//! Cancellation of in-flight operations: agent runs, proxy requests, exports
+//! and downloads.
+
+use tauri::State;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::cancellation::OperationInfo;
+
+/// Cancel an operation by the ID it was started with. Returns false if
+/// nothing with that ID is running.
+#[tauri::command]
+pub fn cancel_operation(op_id: String, state: State<AppState>) -> Result<bool, AppError> {
+    Ok(state.operations.cancel(&op_id) || state.downloads.cancel(&op_id))
+}
+
+/// Cancellable operations currently running, oldest first (downloads are
+/// listed by `get_active_downloads`).
+#[tauri::command]
+pub fn get_active_operations(state: State<AppState>) -> Result<Vec<OperationInfo>, AppError> {
+    Ok(state.operations.active())
+}
@@ -0,0 +1,87 @@
+//! Permission commands: the per-project policy for agent and automation
+//! actions and answers to its prompts.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite, permissions};
+use crate::services::permissions::{Actor, Capability, Decision, PermissionPolicy, PermissionRequest, PermissionRule};
+
+#[tauri::command]
+pub fn get_permission_policy(state: State<AppState>) -> Result<PermissionPolicy, AppError> {
+    let conn = open_project_db(&state)?;
+    permissions::get_policy(&conn)
+}
+
+#[tauri::command]
+pub fn save_permission_policy(policy: PermissionPolicy, state: State<AppState>) -> Result<PermissionPolicy, AppError> {
+    let conn = open_project_db(&state)?;
+    permissions::save_policy(&conn, &policy)
+}
+
+/// Prompts still waiting for an answer (e.g. after reloading the window).
+#[tauri::command]
+pub fn get_pending_permission_requests(state: State<AppState>) -> Result<Vec<PermissionRequest>, AppError> {
+    state.permissions.pending()
+}
+
+/// Answer a `permission:request` prompt. With `remember`, the answer becomes
+/// the project's rule for that actor and capability.
+#[tauri::command]
+pub fn resolve_permission_request(
+    request_id: String,
+    allow: bool,
+    remember: Option<bool>,
+    state: State<AppState>,
+) -> Result<(), AppError> {
+    let request = state.permissions.resolve(&request_id, allow)?;
+    
+    if remember.unwrap_or(false) {
+        let conn = open_project_db(&state)?;
+        let mut policy = permissions::get_policy(&conn)?;
+        policy.set(PermissionRule {
+            actor: request.actor,
+            capability: request.capability,
+            decision: if allow { Decision::Allow } else { Decision::Deny },
+        });
+        permissions::save_policy(&conn, &policy)?;
+    }
+    Ok(())
+}
+
+/// Check an action the frontend is about to apply on behalf of an agent or
+/// automation (e.g. deleting nodes). Resolves once allowed; errors with
+/// `Forbidden` otherwise.
+#[tauri::command]
+pub async fn check_automation_permission(
+    actor: Actor,
+    capability: Capability,
+    summary: String,
+    target_ids: Option<Vec<String>>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let project_path = get_project_path(&state)?;
+    state.permissions
+        .authorize(&app, &project_path, actor, capability, summary, target_ids.unwrap_or_default())
+        .await
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
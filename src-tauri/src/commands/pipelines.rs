@@ -0,0 +1,99 @@
+//! Pipeline commands: checkpointed runs over graphs of recipe nodes.
+//!
+//! Each returned run lists the nodes ready to start. The frontend runs them
+//! with its recipe executors and reports them via `record_pipeline_step`;
+//! `resume_pipeline` picks a failed or cancelled run up after its usable
+//! checkpoints.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite, pipelines, run_comparison};
+use crate::services::job_queue::TaskStatus;
+use crate::services::pipelines::{PipelineOptions, PipelineRun};
+use crate::services::run_comparison::RunComparison;
+
+/// Default number of runs listed
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+/// Plan a run over the given recipe nodes, in dependency order, running
+/// independent branches side by side as `options` allow.
+#[tauri::command]
+pub fn start_pipeline(
+    node_ids: Vec<String>,
+    options: Option<PipelineOptions>,
+    state: State<AppState>,
+) -> Result<PipelineRun, AppError> {
+    let conn = open_project_db(&state)?;
+    pipelines::start(&conn, &node_ids, &options.unwrap_or_default())
+}
+
+/// Save a node's state; finished nodes need the asset they produced. Report
+/// a rate-limited node as pending with the provider's `retry_after_ms`.
+#[tauri::command]
+pub fn record_pipeline_step(
+    run_id: String,
+    node_id: String,
+    status: TaskStatus,
+    output_asset_id: Option<String>,
+    error: Option<String>,
+    retry_after_ms: Option<u64>,
+    state: State<AppState>,
+) -> Result<PipelineRun, AppError> {
+    let conn = open_project_db(&state)?;
+    pipelines::record_step(&conn, &run_id, &node_id, status, output_asset_id.as_deref(), error.as_deref(), retry_after_ms)
+}
+
+/// Reset every node without a usable checkpoint, and everything downstream
+/// of it, to pending, so the run continues from there.
+#[tauri::command]
+pub fn resume_pipeline(run_id: String, state: State<AppState>) -> Result<PipelineRun, AppError> {
+    let conn = open_project_db(&state)?;
+    pipelines::resume(&conn, &run_id)
+}
+
+#[tauri::command]
+pub fn get_pipeline_run(run_id: String, state: State<AppState>) -> Result<PipelineRun, AppError> {
+    let conn = open_project_db(&state)?;
+    pipelines::get_run(&conn, &run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))
+}
+
+/// Most recently updated runs first.
+#[tauri::command]
+pub fn list_pipeline_runs(limit: Option<usize>, state: State<AppState>) -> Result<Vec<PipelineRun>, AppError> {
+    let conn = open_project_db(&state)?;
+    pipelines::list_runs(&conn, limit.unwrap_or(DEFAULT_LIST_LIMIT))
+}
+
+/// Pair what two runs produced, for one node or (without `node_id`) all of
+/// them, with text and field diffs.
+#[tauri::command]
+pub fn compare_runs(
+    node_id: Option<String>,
+    run_a: String,
+    run_b: String,
+    state: State<AppState>,
+) -> Result<RunComparison, AppError> {
+    let conn = open_project_db(&state)?;
+    run_comparison::compare_runs(&conn, node_id.as_deref(), &run_a, &run_b)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,46 @@
+//! Planning view commands: timeline/calendar and kanban queries.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite};
+use crate::services::planning::{self, KanbanColumn, KanbanGroupBy, PlanningItem, TimelineRange};
+
+/// Dated nodes overlapping `range` (all dated nodes if omitted).
+#[tauri::command]
+pub fn get_timeline(
+    range: Option<TimelineRange>,
+    state: State<AppState>,
+) -> Result<Vec<PlanningItem>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    planning::get_timeline(&conn, &range.unwrap_or_default())
+}
+
+/// Planning nodes grouped into columns by `status` (default) or `parent`.
+#[tauri::command]
+pub fn get_kanban(
+    group_by: Option<KanbanGroupBy>,
+    state: State<AppState>,
+) -> Result<Vec<KanbanColumn>, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    planning::get_kanban(&conn, group_by.unwrap_or_default())
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -1,15 +1,22 @@
-use tauri::{State, AppHandle, Emitter};
+use tauri::{State, AppHandle};
 use tauri::Manager;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::error::AppError;
+use crate::events::{self, AppEvent, ProjectActive};
 use crate::config::{GlobalConfig, RecentProject};
 use crate::models::SynniaProject;
-use crate::services::io_sqlite;
+use crate::services::{backup, cloud_sync, database, disk_space, io_sqlite, linked_files, load_profile, node_locks, validation};
+use crate::services::load_profile::LoadProfile;
 use crate::AppState; 
+use ts_rs::TS;
 
 #[tauri::command]
 pub fn get_recent_projects(app: AppHandle) -> Result<Vec<RecentProject>, AppError> {
-    let config = GlobalConfig::load(&app);
+    let mut config = GlobalConfig::load(&app);
+    // Entries saved before duplicates were merged on add
+    if config.merge_recent_duplicates() {
+        config.save(&app).map_err(AppError::Unknown)?;
+    }
     Ok(config.recent_projects)
 }
 
@@ -17,7 +24,7 @@ pub fn get_recent_projects(app: AppHandle) -> Result<Vec<RecentProject>, AppErro
 pub fn get_default_projects_path(app: AppHandle) -> Result<String, AppError> {
     let config = GlobalConfig::load(&app);
     
-    if let Some(ws) = config.default_workspace {
+    if let Some(ws) = config.workspace() {
         return Ok(ws);
     }
 
@@ -68,70 +75,120 @@ pub fn init_project(path: String, state: State<AppState>, app: AppHandle) -> Res
         .unwrap_or("Untitled Project");
     
     // Initialize project with SQLite
-    io_sqlite::init_project_sqlite(&project_path, name)?;
+    let project = io_sqlite::init_project_sqlite(&project_path, name)?;
     
     // Update AppState
     let mut path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Path Lock Poisoned".to_string()))?;
     *path_guard = Some(path.clone());
+    state.sessions.project_opened(&project_path);
 
     // Update Global Config
     let mut config = GlobalConfig::load(&app);
-    config.add_recent(name.to_string(), path.clone());
+    config.add_recent(name.to_string(), path.clone(), Some(project.meta.id));
     if let Err(e) = config.save(&app) {
         println!("Failed to save global config: {}", e);
     }
     
     // Signal project active
-    app.emit("project:active", serde_json::json!({ "name": name })).map_err(|e| AppError::Unknown(e.to_string()))?;
+    events::emit(&app, AppEvent::ProjectActive(ProjectActive { name: name.to_string() }))?;
+    announce_sync_status(&app, &project_path);
 
     Ok(format!("Project initialized at {}", path))
 }
 
 #[tauri::command]
-pub fn load_project(path: String, state: State<AppState>, app: AppHandle) -> Result<SynniaProject, AppError> {
+pub async fn load_project(path: String, state: State<'_, AppState>, app: AppHandle) -> Result<SynniaProject, AppError> {
     let project_path = PathBuf::from(&path);
     if !project_path.exists() {
         return Err(AppError::NotFound(format!("Project path not found: {}", path)));
     }
 
-    // Load SQLite project
-    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    // Load SQLite project; linked files are checked while we're off the IPC thread
+    let root = project_path.clone();
+    let (project, broken_links) = state.blocking.run(move || {
+        let project = io_sqlite::load_project_sqlite(&root)?;
+        let conn = database::open_db(&io_sqlite::get_db_path(&root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        Ok((project, linked_files::broken_links(&conn, &root)?))
+    }).await?;
 
     // Update AppState
     let mut path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Path Lock Poisoned".to_string()))?;
     *path_guard = Some(path.clone());
+    state.sessions.project_opened(&project_path);
 
     // Update Recent Projects
     let mut config = GlobalConfig::load(&app);
-    config.add_recent(project.meta.name.clone(), path.clone());
+    config.add_recent(project.meta.name.clone(), path.clone(), Some(project.meta.id.clone()));
     config.save(&app).map_err(|e| AppError::Unknown(e))?;
 
-    app.emit("project:active", serde_json::json!({ "name": project.meta.name })).map_err(|e| AppError::Unknown(e.to_string()))?;
+    events::emit(&app, AppEvent::ProjectActive(ProjectActive { name: project.meta.name.clone() }))?;
+    announce_sync_status(&app, &project_path);
+    if !broken_links.is_empty() {
+        let _ = events::emit(&app, AppEvent::BrokenLinks(broken_links));
+    }
 
     Ok(project)
 }
 
+/// Time opening the project at `path`, stage by stage, without making it
+/// the current project
 #[tauri::command]
-pub fn save_project_autosave(project: SynniaProject, state: State<AppState>) -> Result<(), AppError> {
+pub async fn profile_project_load(path: String, state: State<'_, AppState>) -> Result<LoadProfile, AppError> {
+    let project_path = validation::check_path(&path)?;
+    state.blocking.run(move || load_profile::profile_load(&project_path)).await
+}
+
+/// Write a synthetic project with `nodes` nodes over `assets` assets
+/// (images `size` pixels square) to the default projects folder, for
+/// performance testing. Debug builds only.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub async fn generate_test_project(
+    nodes: usize,
+    assets: usize,
+    size: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<crate::services::stress_fixtures::GeneratedProject, AppError> {
+    let name = format!("Stress Test {}n {}a {}", nodes, assets, chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    let project_path = PathBuf::from(get_default_projects_path(app)?).join(name);
+    state.blocking.run(move || {
+        crate::services::stress_fixtures::generate(&project_path, nodes, assets, size)
+    }).await
+}
+
+#[tauri::command]
+pub async fn save_project_autosave(project: SynniaProject, force: Option<bool>, state: State<'_, AppState>, app: AppHandle) -> Result<(), AppError> {
     let project_path_str = {
         let path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
         path_guard.clone().ok_or(AppError::ProjectNotLoaded)?
     };
     
     let project_path = PathBuf::from(project_path_str);
-    io_sqlite::save_project_sqlite(&project_path, &project)?;
+    let root = project_path.clone();
+    state.blocking.run_serialized(&project_path, move || {
+        check_locked_nodes(&root, &project, force)?;
+        io_sqlite::save_project_sqlite(&root, &project)
+    }).await?;
+    check_sync_conflicts(&app, &project_path);
     Ok(())
 }
 
 #[tauri::command]
-pub fn save_project(project: SynniaProject, state: State<AppState>) -> Result<(), AppError> {
+pub async fn save_project(project: SynniaProject, force: Option<bool>, state: State<'_, AppState>, app: AppHandle) -> Result<(), AppError> {
     let project_path_str = {
         let path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
         path_guard.clone().ok_or(AppError::ProjectNotLoaded)?
     };
     
     let project_path = PathBuf::from(project_path_str);
-    io_sqlite::save_project_sqlite(&project_path, &project)?;
+    let root = project_path.clone();
+    state.blocking.run_serialized(&project_path, move || {
+        check_locked_nodes(&root, &project, force)?;
+        io_sqlite::save_project_sqlite(&root, &project)
+    }).await?;
+    check_sync_conflicts(&app, &project_path);
     Ok(())
 }
 
@@ -141,8 +198,25 @@ pub fn get_current_project_path(state: State<AppState>) -> Result<String, AppErr
     path_guard.clone().ok_or(AppError::ProjectNotLoaded)
 }
 
+/// What `delete_project` removed, or would remove in a dry run
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteProjectReport {
+    pub path: String,
+    /// Files in the project folder (linked originals are not touched)
+    pub files: usize,
+    #[ts(type = "number")]
+    pub bytes: u64,
+    /// The project was open and got closed
+    pub closes_active: bool,
+    /// Nothing was changed
+    pub dry_run: bool,
+}
+
+/// Delete a project folder. With `dry_run`, only report what would go.
 #[tauri::command]
-pub fn delete_project(path: String, state: State<AppState>, app: AppHandle) -> Result<(), AppError> {
+pub fn delete_project(path: String, dry_run: Option<bool>, state: State<AppState>, app: AppHandle) -> Result<DeleteProjectReport, AppError> {
     let path_buf = PathBuf::from(&path);
     
     if !path_buf.exists() {
@@ -159,17 +233,30 @@ pub fn delete_project(path: String, state: State<AppState>, app: AppHandle) -> R
         )));
     }
 
+    let files = backup::walk_files(&path_buf)?;
+    let mut report = DeleteProjectReport {
+        path: path.clone(),
+        files: files.len(),
+        bytes: disk_space::files_size(&files),
+        closes_active: false,
+        dry_run: dry_run.unwrap_or(false),
+    };
+
     // Check if this is the active project and close it if so
     {
         let mut path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
-        let should_close = if let Some(current) = &*path_guard {
+        report.closes_active = if let Some(current) = &*path_guard {
             PathBuf::from(current) == path_buf
         } else {
             false
         };
+        if report.dry_run {
+            return Ok(report);
+        }
 
-        if should_close {
+        if report.closes_active {
             *path_guard = None;
+            state.sessions.stop();
         }
     }
 
@@ -181,7 +268,7 @@ pub fn delete_project(path: String, state: State<AppState>, app: AppHandle) -> R
     config.recent_projects.retain(|p| p.path != path);
     config.save(&app).map_err(|e| AppError::Unknown(e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
@@ -210,6 +297,7 @@ pub fn rename_project(old_path: String, new_name: String, state: State<AppState>
 
         if should_close {
             *path_guard = None;
+            state.sessions.stop();
         }
     }
 
@@ -272,7 +360,7 @@ pub fn set_thumbnail(image_relative_path: String, state: State<AppState>) -> Res
     };
 
     // 2. Copy File
-    let src = PathBuf::from(&project_path).join(&image_relative_path);
+    let src = validation::resolve_in_project(Path::new(&project_path), &image_relative_path)?;
     let dest = PathBuf::from(&project_path).join("thumbnail.png");
     
     if src.exists() {
@@ -287,4 +375,45 @@ pub fn set_thumbnail(image_relative_path: String, state: State<AppState>) -> Res
     // So this command is purely optional or utility.
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Cloud-drive status of the current project: provider, safe mode, conflicted copies.
+#[tauri::command]
+pub fn get_sync_status(state: State<AppState>) -> Result<cloud_sync::SyncStatus, AppError> {
+    let path_guard = state.current_project_path.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    let project_path = path_guard.clone().ok_or(AppError::ProjectNotLoaded)?;
+    Ok(cloud_sync::sync_status(&PathBuf::from(project_path)))
+}
+
+/// Warn the frontend when a project lives in a synced folder
+fn announce_sync_status(app: &AppHandle, project_path: &std::path::Path) {
+    let status = cloud_sync::sync_status(project_path);
+    if status.provider.is_some() {
+        let _ = events::emit(app, AppEvent::CloudSync(status.clone()));
+    }
+    if !status.conflicts.is_empty() {
+        let _ = events::emit(app, AppEvent::SyncConflict(status));
+    }
+}
+
+/// Conflicted copies appear after another machine saved the same project
+fn check_sync_conflicts(app: &AppHandle, project_path: &std::path::Path) {
+    if cloud_sync::detect_provider(project_path).is_none() {
+        return;
+    }
+    let status = cloud_sync::sync_status(project_path);
+    if !status.conflicts.is_empty() {
+        let _ = events::emit(app, AppEvent::SyncConflict(status));
+    }
+}
+
+/// Locked and pinned nodes may only change on a forced save
+fn check_locked_nodes(project_path: &std::path::Path, project: &SynniaProject, force: Option<bool>) -> Result<(), AppError> {
+    let db_path = io_sqlite::get_db_path(project_path);
+    if force == Some(true) || !db_path.exists() {
+        return Ok(());
+    }
+    let conn = database::open_db(&db_path)
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    node_locks::check_graph_save(&conn, io_sqlite::require_canvas(project)?, &project.graph.nodes)
+}
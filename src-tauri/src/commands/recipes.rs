@@ -0,0 +1,74 @@
+//! Recipe commands: the recipe definitions stored in the project.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite, recipes};
+use crate::services::recipes::{Recipe, RecipeInput, RecipePreview};
+
+#[tauri::command]
+pub fn list_recipes(state: State<AppState>) -> Result<Vec<Recipe>, AppError> {
+    let conn = open_project_db(&state)?;
+    recipes::list(&conn)
+}
+
+#[tauri::command]
+pub fn get_recipe(recipe_id: String, state: State<AppState>) -> Result<Recipe, AppError> {
+    let conn = open_project_db(&state)?;
+    recipes::get(&conn, &recipe_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Recipe not found: {}", recipe_id)))
+}
+
+/// Create a recipe, or replace the definition of the one with `recipe.id`.
+#[tauri::command]
+pub fn save_recipe(recipe: RecipeInput, state: State<AppState>) -> Result<Recipe, AppError> {
+    let conn = open_project_db(&state)?;
+    recipes::save(&conn, &recipe)
+}
+
+/// Delete a recipe definition. Returns false if it didn't exist.
+#[tauri::command]
+pub fn delete_recipe(recipe_id: String, state: State<AppState>) -> Result<bool, AppError> {
+    let conn = open_project_db(&state)?;
+    recipes::delete(&conn, &recipe_id)
+}
+
+/// Resolve a recipe node's inputs and render the prompt its agent would be
+/// sent, without calling the provider.
+#[tauri::command]
+pub fn preview_recipe(
+    recipe_node_id: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<RecipePreview, AppError> {
+    let conn = open_project_db(&state)?;
+    let recipe = recipes::for_node(&conn, &recipe_node_id)?;
+    let agent_id = recipe.agent_id.as_deref()
+        .ok_or_else(|| AppError::InvalidInput(format!("Recipe {} has no agent", recipe.name)))?;
+    let agent = super::agent::get_agents(app)?
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| AppError::NotFound(format!("Agent not found: {}", agent_id)))?;
+    // Guard findings are only logged for real runs
+    let context = super::agent::agent_context(&state, Some(&recipe_node_id), &agent.name, false);
+    recipes::preview(&conn, &recipe, &agent, &context)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
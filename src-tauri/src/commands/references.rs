@@ -0,0 +1,47 @@
+//! Reference node commands: alias nodes that share one asset.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::{Position, SynniaNode};
+use crate::services::{database, io_sqlite, references};
+
+/// Create a reference to `node_id`'s asset, next to it unless `position` is given.
+#[tauri::command]
+pub fn create_reference(
+    node_id: String,
+    position: Option<Position>,
+    state: State<AppState>,
+) -> Result<SynniaNode, AppError> {
+    let conn = open_project_db(&state)?;
+    references::create_reference(&conn, &node_id, position)
+}
+
+/// Reference nodes showing an asset (the original node is not included).
+#[tauri::command]
+pub fn list_references(
+    asset_id: String,
+    state: State<AppState>,
+) -> Result<Vec<SynniaNode>, AppError> {
+    let conn = open_project_db(&state)?;
+    references::list_references(&conn, &asset_id)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
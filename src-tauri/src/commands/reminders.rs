@@ -0,0 +1,67 @@
+//! Reminder commands: schedule desktop reminders for nodes.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::Reminder;
+use crate::services::{database, io_sqlite, reminders};
+
+/// Schedule a reminder for a node. `when` is an RFC 3339 timestamp.
+#[tauri::command]
+pub fn schedule_reminder(
+    node_id: String,
+    when: String,
+    message: String,
+    state: State<AppState>,
+) -> Result<Reminder, AppError> {
+    let fire_at = chrono::DateTime::parse_from_rfc3339(&when)
+        .map_err(|e| AppError::Unknown(format!("Invalid reminder time '{}': {}", when, e)))?
+        .timestamp_millis();
+    
+    let conn = open_project_db(&state)?;
+    if io_sqlite::get_node(&conn, &node_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Node not found: {}", node_id)));
+    }
+    reminders::schedule(&conn, &node_id, fire_at, &message)
+}
+
+/// Reminders of the current project, pending ones only unless `include_fired`.
+#[tauri::command]
+pub fn get_reminders(
+    include_fired: Option<bool>,
+    state: State<AppState>,
+) -> Result<Vec<Reminder>, AppError> {
+    let conn = open_project_db(&state)?;
+    reminders::list(&conn, include_fired.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn cancel_reminder(
+    reminder_id: String,
+    state: State<AppState>,
+) -> Result<(), AppError> {
+    let conn = open_project_db(&state)?;
+    if !reminders::cancel(&conn, &reminder_id)? {
+        return Err(AppError::NotFound(format!("Reminder not found: {}", reminder_id)));
+    }
+    Ok(())
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,120 @@
+//! Content safety commands: policy settings, scans and the review queue.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::AppState;
+use crate::services::{database, io_sqlite, safety};
+use crate::services::job_queue::Job;
+use crate::services::safety::{FlaggedAsset, ReviewDecision, SafetyClassifier, SafetySettings, SafetyTask};
+use super::jobs::{spawn_local_job, JobInfo};
+
+#[tauri::command]
+pub fn get_safety_settings(state: State<AppState>) -> Result<SafetySettings, AppError> {
+    let conn = open_project_db(&state)?;
+    safety::get_settings(&conn)
+}
+
+/// Store the policy and threshold; applies to images checked afterwards.
+#[tauri::command]
+pub fn save_safety_settings(settings: SafetySettings, state: State<AppState>) -> Result<SafetySettings, AppError> {
+    let conn = open_project_db(&state)?;
+    safety::save_settings(&conn, &settings)
+}
+
+/// Check image assets in the background: the given ones, or every image
+/// not checked yet. `force` re-checks. The review queue arrives with
+/// `job:completed`.
+#[tauri::command]
+pub fn scan_image_safety(
+    asset_ids: Option<Vec<String>>,
+    force: Option<bool>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = open_project_db(&state)?;
+    let classifier = SafetyClassifier::find().ok_or_else(|| AppError::NotFound(
+        "Local safety classifier not found; set SYNNIA_SAFETY_RUNNER and SYNNIA_SAFETY_MODEL".to_string()
+    ))?;
+    let tasks = safety::pending_tasks(&conn, &project_path, asset_ids.as_deref(), force.unwrap_or(false))?;
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    
+    let job = Job::new(safety::JOB_KIND, serde_json::Value::Null, params, 2);
+    let root = project_path.clone();
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| {
+            let task: SafetyTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+            let status = classifier.check(&root, &task.src)?;
+            serde_json::to_value(status).map_err(|e| e.to_string())
+        },
+        move |_| {
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            Ok(serde_json::to_value(safety::flagged_assets(&conn, false)?)?)
+        },
+    ))
+}
+
+/// Assets waiting for review (with `include_reviewed`, also decided ones).
+#[tauri::command]
+pub fn get_flagged_assets(include_reviewed: Option<bool>, state: State<AppState>) -> Result<Vec<FlaggedAsset>, AppError> {
+    let conn = open_project_db(&state)?;
+    safety::flagged_assets(&conn, include_reviewed.unwrap_or(false))
+}
+
+/// Approve flagged assets (back in the media library) or reject them (kept
+/// hidden). Returns the review queue left.
+#[tauri::command]
+pub fn review_flagged_assets(
+    asset_ids: Vec<String>,
+    decision: ReviewDecision,
+    state: State<AppState>,
+) -> Result<Vec<FlaggedAsset>, AppError> {
+    let conn = open_project_db(&state)?;
+    safety::review(&conn, &asset_ids, decision)?;
+    safety::flagged_assets(&conn, false)
+}
+
+// Helper functions
+
+/// Check images not checked yet in the background, if the project has a
+/// safety policy. Emits `safety:flagged` with the review queue when new
+/// images were flagged.
+pub(crate) fn check_new_images(app: &AppHandle, project_root: PathBuf) {
+    let Some(classifier) = safety::auto_classifier(&project_root) else { return };
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let Ok(conn) = database::open_db(&io_sqlite::get_db_path(&project_root)) else { return };
+        let Ok(tasks) = safety::pending_tasks(&conn, &project_root, None, false) else { return };
+        let flagged = tasks.iter()
+            .filter_map(|task| classifier.check(&project_root, &task.src).ok())
+            .filter(|status| *status != safety::ReviewStatus::Clear)
+            .count();
+        if flagged > 0 {
+            if let Ok(queue) = safety::flagged_assets(&conn, false) {
+                let _ = events::emit(&app, AppEvent::SafetyFlagged(queue));
+            }
+        }
+    });
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,183 @@
+//! Command scripts: run a validated list of steps as one job.
+//!
+//! Each step is a task of a `command-script` job, so scripts show up in the
+//! job list, report `job:progress` and can be cancelled with `cancel_job`.
+//! A failing or cancelled script is rolled back (see `command_script`).
+
+use tauri::{AppHandle, Manager, State};
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
+use crate::events::{self, AppEvent, JobCompleted, JobProgress};
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::models::AgentDefinition;
+use crate::services::agent_service::{call_gemini_agent, GraphAction};
+use crate::services::command_script::{self, ScriptCheckpoint, ScriptStep, StepOutput};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::job_queue::Job;
+use crate::services::net_guard::NetworkPolicy;
+use super::agent::{agent_context, authorize_actions, get_agents, AGENT_PROVIDER};
+use super::jobs::JobInfo;
+
+/// Validate `steps` and run them in order in the background. Later steps may
+/// refer to nodes made by earlier ones as `"$<step>"`. Emits `job:completed`
+/// with each step's output, or with the failing step once the project has
+/// been rolled back.
+#[tauri::command]
+pub fn run_command_script(
+    steps: Vec<ScriptStep>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    command_script::validate(&steps)?;
+
+    // Agents are resolved up front so a typo fails before anything changes
+    let known = get_agents(app.clone())?;
+    let mut agents = Vec::new();
+    for step in &steps {
+        if let ScriptStep::RunAgent { agent_id, .. } = step {
+            let agent = known.iter().find(|a| &a.id == agent_id)
+                .ok_or_else(|| AppError::NotFound(format!("Agent not found: {}", agent_id)))?;
+            agents.push(agent.clone());
+        }
+    }
+
+    let params = steps.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(command_script::JOB_KIND, serde_json::json!({ "steps": steps.len() }), params, 1);
+    let info = JobInfo { job_id: job.id.clone(), total: job.tasks.len(), held: false };
+    state.jobs.submit(job)?;
+
+    let job_id = info.job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        run_script(app, project_path, job_id, steps, agents).await;
+    });
+
+    Ok(info)
+}
+
+// Helper functions
+
+async fn run_script(
+    app: AppHandle,
+    project_path: PathBuf,
+    job_id: String,
+    steps: Vec<ScriptStep>,
+    agents: Vec<AgentDefinition>,
+) {
+    let state = app.state::<AppState>();
+    let total = steps.len();
+    let mut agents = agents.into_iter();
+    let mut outputs: Vec<StepOutput> = Vec::new();
+    let mut checkpoint = ScriptCheckpoint::new();
+
+    for (index, step) in steps.iter().enumerate() {
+        let result = match step {
+            ScriptStep::RunAgent { inputs, context_node_id, .. } => {
+                let agent = agents.next().expect("agents were resolved for every agent step");
+                run_agent_step(&app, &state, &project_path, &agent, inputs.clone(), context_node_id.as_deref(), &outputs).await
+            }
+            _ => {
+                let (root, step, done) = (project_path.clone(), step.clone(), outputs.clone());
+                state.blocking.run(move || command_script::run_step(&root, &step, &done)).await
+            }
+        };
+        if let Ok(output) = &result {
+            checkpoint.record(output);
+        }
+
+        let reported = result.as_ref()
+            .map_err(|e| e.to_string())
+            .and_then(|output| serde_json::to_value(output).map_err(|e| e.to_string()));
+        let update = match state.jobs.complete_task(&job_id, index, reported) {
+            Ok(update) => update,
+            // Cancelled with `cancel_job`, which already reported it
+            Err(_) => {
+                let _ = checkpoint.rollback(&project_path);
+                return;
+            }
+        };
+
+        match result {
+            Ok(output) => outputs.push(output),
+            Err(e) => {
+                let rolled_back = checkpoint.rollback(&project_path).is_ok();
+                let Ok(job) = state.jobs.cancel(&job_id) else { return };
+                let output = serde_json::json!({
+                    "error": e.to_string(),
+                    "failedStep": index,
+                    "rolledBack": rolled_back,
+                });
+                let _ = events::emit(&app, AppEvent::JobCompleted(JobCompleted { job, output }));
+                return;
+            }
+        }
+        let _ = events::emit(&app, AppEvent::JobProgress(JobProgress { job_id: job_id.clone(), current: index + 1, total }));
+
+        if let Some(job) = update.finished {
+            activity::log(&project_path, NewActivity::new(
+                ActivityKind::NodeAdded,
+                "script",
+                format!("Ran a script of {} step{}", total, if total == 1 { "" } else { "s" }),
+            ));
+            let output = serde_json::json!({ "steps": outputs });
+            let _ = events::emit(&app, AppEvent::JobCompleted(JobCompleted { job, output }));
+            return;
+        }
+    }
+}
+
+/// Run an agent and turn the nodes it proposes (if the project's policy
+/// allows them) into text nodes.
+async fn run_agent_step(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    project_path: &Path,
+    agent: &AgentDefinition,
+    inputs: serde_json::Value,
+    context_node_id: Option<&str>,
+    outputs: &[StepOutput],
+) -> Result<StepOutput, AppError> {
+    let config = GlobalConfig::load(app);
+    config.org.check_provider(AGENT_PROVIDER)?;
+    let timeouts = NetworkPolicy::from_config(&config).timeouts;
+    let api_key = config.gemini_api_key.ok_or(AppError::Agent("Please configure Gemini API Key in Settings".to_string()))?;
+    let base_url = config.gemini_base_url.unwrap_or("https://generativelanguage.googleapis.com".to_string());
+    let model_name = config.gemini_model_name.unwrap_or("gemini-1.5-flash".to_string());
+
+    let context_node_id = context_node_id.map(|id| command_script::resolve(id, outputs)).transpose()?;
+    let context = agent_context(state, context_node_id.as_deref(), &agent.name, true);
+    let actions = call_gemini_agent(
+        &api_key,
+        &base_url,
+        &model_name,
+        &agent.system_prompt,
+        inputs,
+        context,
+        &timeouts,
+    ).await.map_err(AppError::Network)?;
+    let actions = authorize_actions(actions, state, app).await?;
+
+    let root = project_path.to_path_buf();
+    state.blocking.run(move || {
+        let mut output = StepOutput::default();
+        for action in actions {
+            if let GraphAction::CreateNode { label, description, .. } = action {
+                let created = command_script::create_node(&root, "text-node", &label, Some(&description), None, None)?;
+                output.node_ids.extend(created.node_ids);
+                output.asset_ids.extend(created.asset_ids);
+            }
+        }
+        Ok(output)
+    }).await
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,152 @@
+//! Share commands: post selected nodes to a Slack or Discord webhook, or
+//! package them as a read-only HTML bundle.
+//!
+//! Each webhook message is a task of a `webhook-share` job, so posts show up
+//! in the job list, report `job:progress` and can be cancelled with
+//! `cancel_job`.
+
+use tauri::{AppHandle, Manager, State};
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
+use crate::events::{self, AppEvent, JobCompleted, JobProgress};
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::services::{database, disk_space, io_sqlite, share_bundle, webhook_share};
+use crate::services::export::EXPORT_DIR;
+use crate::services::job_queue::Job;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::share_bundle::ShareBundle;
+use crate::services::webhook_share::{SharePost, WebhookKind};
+use super::jobs::JobInfo;
+
+/// Post the images and captions of `node_ids` to `webhook_url` in the
+/// background, with `message` above the captions. Emits `job:completed`
+/// once every message went out, or with the first one that didn't.
+#[tauri::command]
+pub fn share_to_webhook(
+    webhook_url: String,
+    node_ids: Vec<String>,
+    message: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    let kind = WebhookKind::detect(&webhook_url)?;
+
+    let conn = open_project_db(&project_path)?;
+    let items = webhook_share::collect(&project_path, &conn, &node_ids)?;
+    let posts = webhook_share::plan(kind, &items, message.as_deref());
+    if posts.iter().all(|p| p.text.is_empty() && p.attachments.is_empty()) {
+        return Err(AppError::InvalidInput("The selected nodes have no images or captions to share".to_string()));
+    }
+
+    let params = posts.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(webhook_share::JOB_KIND, serde_json::json!({ "service": kind, "nodes": node_ids.len() }), params, 1);
+    let info = JobInfo { job_id: job.id.clone(), total: job.tasks.len(), held: false };
+    state.jobs.submit(job)?;
+
+    let job_id = info.job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        run_share(app, job_id, webhook_url, kind, posts).await;
+    });
+
+    Ok(info)
+}
+
+/// Write a read-only HTML page of `node_ids` with their files to
+/// `exports/share/`, plus a zip of it, expiring after `expiry` days (or
+/// never).
+#[tauri::command]
+pub async fn create_share_bundle(
+    node_ids: Vec<String>,
+    expiry: Option<u32>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ShareBundle, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = open_project_db(&project_path)?;
+    let estimate = share_bundle::estimated_size(&project_path, &conn, &node_ids)?;
+    disk_space::ensure_space(&app, &project_path.join(EXPORT_DIR), estimate)?;
+    drop(conn);
+
+    state.blocking.run(move || {
+        let conn = open_project_db(&project_path)?;
+        share_bundle::create(&project_path, &conn, &node_ids, expiry)
+    }).await
+}
+
+/// Share bundles of the project, newest first. Expired ones are deleted.
+#[tauri::command]
+pub fn list_share_bundles(state: State<AppState>) -> Result<Vec<ShareBundle>, AppError> {
+    let project_path = get_project_path(&state)?;
+    share_bundle::list(&project_path, &open_project_db(&project_path)?)
+}
+
+#[tauri::command]
+pub fn delete_share_bundle(bundle_id: String, state: State<AppState>) -> Result<(), AppError> {
+    let project_path = get_project_path(&state)?;
+    share_bundle::delete(&project_path, &open_project_db(&project_path)?, &bundle_id)
+}
+
+// Helper functions
+
+async fn run_share(app: AppHandle, job_id: String, webhook_url: String, kind: WebhookKind, posts: Vec<SharePost>) {
+    let state = app.state::<AppState>();
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    let total = posts.len();
+
+    for (index, post) in posts.into_iter().enumerate() {
+        let rendered = post.clone();
+        let result = match state.blocking.run(move || webhook_share::render(&rendered)).await {
+            Ok(files) => {
+                let attached = files.len();
+                webhook_share::send(&policy, &webhook_url, kind, &post, files).await
+                    .map(|_| serde_json::json!({ "attachments": attached }))
+            }
+            Err(e) => Err(e),
+        };
+
+        let failed = result.as_ref().err().map(|e| e.to_string());
+        let update = match state.jobs.complete_task(&job_id, index, result.map_err(|e| e.to_string())) {
+            Ok(update) => update,
+            // Cancelled with `cancel_job`, which already reported it
+            Err(_) => return,
+        };
+
+        // Later messages would be out of context without this one
+        if let Some(error) = failed {
+            let job = match update.finished {
+                Some(job) => job,
+                None => match state.jobs.cancel(&job_id) {
+                    Ok(job) => job,
+                    Err(_) => return,
+                },
+            };
+            let output = serde_json::json!({ "error": error, "failedPost": index });
+            let _ = events::emit(&app, AppEvent::JobCompleted(JobCompleted { job, output }));
+            return;
+        }
+        let _ = events::emit(&app, AppEvent::JobProgress(JobProgress { job_id: job_id.clone(), current: index + 1, total }));
+
+        if let Some(job) = update.finished {
+            let output = serde_json::json!({ "service": kind, "posts": total });
+            let _ = events::emit(&app, AppEvent::JobCompleted(JobCompleted { job, output }));
+            return;
+        }
+    }
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(project_path: &Path) -> Result<rusqlite::Connection, AppError> {
+    database::open_db(&io_sqlite::get_db_path(project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
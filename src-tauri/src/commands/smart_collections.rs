@@ -0,0 +1,69 @@
+//! Smart collection commands: saved asset queries the library evaluates on
+//! demand.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite, smart_collections};
+use crate::services::smart_collections::{SmartCollection, SmartQuery};
+
+/// Default max results of a collection
+const DEFAULT_LIMIT: usize = 500;
+
+#[tauri::command]
+pub fn create_smart_collection(
+    name: String,
+    query: SmartQuery,
+    state: State<AppState>,
+) -> Result<SmartCollection, AppError> {
+    let conn = open_project_db(&state)?;
+    smart_collections::create(&conn, &name, &query)
+}
+
+#[tauri::command]
+pub fn list_smart_collections(state: State<AppState>) -> Result<Vec<SmartCollection>, AppError> {
+    let conn = open_project_db(&state)?;
+    smart_collections::list(&conn)
+}
+
+#[tauri::command]
+pub fn delete_smart_collection(
+    collection_id: String,
+    state: State<AppState>,
+) -> Result<(), AppError> {
+    let conn = open_project_db(&state)?;
+    if !smart_collections::delete(&conn, &collection_id)? {
+        return Err(AppError::NotFound(format!("Collection not found: {}", collection_id)));
+    }
+    Ok(())
+}
+
+/// IDs of the assets currently in the collection, most recently updated first.
+#[tauri::command]
+pub fn evaluate_smart_collection(
+    collection_id: String,
+    limit: Option<usize>,
+    state: State<AppState>,
+) -> Result<Vec<String>, AppError> {
+    let conn = open_project_db(&state)?;
+    smart_collections::evaluate(&conn, &collection_id, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
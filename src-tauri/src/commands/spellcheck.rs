@@ -0,0 +1,101 @@
+//! Spelling/grammar commands for text assets.
+
+use tauri::{State, AppHandle, Manager};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::Asset;
+use crate::services::{database, io_sqlite, spellcheck, text_asset};
+use crate::services::spellcheck::{TextFix, TextIssue};
+use ts_rs::TS;
+
+/// Result of checking one text asset
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckReport {
+    pub asset_id: String,
+    pub lang: String,
+    /// False when no dictionary is installed for `lang` (grammar checks only)
+    pub has_dictionary: bool,
+    pub issues: Vec<TextIssue>,
+}
+
+// Helper to get the global dictionaries directory
+fn get_dictionaries_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
+    let dictionaries_dir = docs_dir.join("Synnia").join("Dictionaries");
+    if !dictionaries_dir.exists() {
+        std::fs::create_dir_all(&dictionaries_dir).map_err(|e| AppError::Io(e.to_string()))?;
+    }
+    Ok(dictionaries_dir)
+}
+
+/// Languages with an installed dictionary (`<lang>.dic` or `<lang>.txt`)
+#[tauri::command]
+pub fn get_spellcheck_languages(app: AppHandle) -> Result<Vec<String>, AppError> {
+    Ok(spellcheck::available_languages(&get_dictionaries_dir(&app)?))
+}
+
+#[tauri::command]
+pub fn check_text_asset(
+    asset_id: String,
+    lang: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<SpellcheckReport, AppError> {
+    let asset = load_text_asset(&state, &asset_id)?;
+    let text = text_asset::text_content(&asset).unwrap_or_default();
+    
+    let dictionary = spellcheck::load_dictionary(&get_dictionaries_dir(&app)?, &lang);
+    let issues = spellcheck::check_text(text, &lang, dictionary.as_ref());
+    
+    Ok(SpellcheckReport {
+        asset_id,
+        lang,
+        has_dictionary: dictionary.is_some(),
+        issues,
+    })
+}
+
+/// Apply fixes (UTF-16 spans from `check_text_asset`) and save the asset.
+#[tauri::command]
+pub fn apply_fixes(
+    asset_id: String,
+    fixes: Vec<TextFix>,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let mut asset = load_text_asset(&state, &asset_id)?;
+    let text = text_asset::text_content(&asset).unwrap_or_default();
+    
+    let fixed = spellcheck::apply_fixes(text, &fixes)?;
+    text_asset::set_text_content(&mut asset, fixed);
+    
+    io_sqlite::save_asset_with_history(&get_project_path(&state)?, &asset)?;
+    Ok(asset)
+}
+
+// Helper functions
+
+fn load_text_asset(state: &State<AppState>, asset_id: &str) -> Result<Asset, AppError> {
+    let project_path = get_project_path(state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let asset = io_sqlite::get_asset(&conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    if text_asset::text_content(&asset).is_none() {
+        return Err(AppError::Unknown(format!("Asset {} is not a text asset", asset_id)));
+    }
+    Ok(asset)
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
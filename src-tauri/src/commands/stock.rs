@@ -0,0 +1,75 @@
+//! Stock photo commands: search Unsplash/Pexels and import a result with
+//! its license.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::services::stock::{self, StockImport, StockProvider, StockResult, StockSize};
+use crate::services::download::DownloadOptions;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::provider_health::ProviderCredentials;
+
+/// One page (from 1) of photos matching `query`, using the provider's key
+/// from the app settings.
+#[tauri::command]
+pub async fn search_stock(
+    provider: StockProvider,
+    query: String,
+    page: Option<u32>,
+    app: AppHandle,
+) -> Result<Vec<StockResult>, AppError> {
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(provider.key())?;
+    let credentials = ProviderCredentials::from_config(&config, provider.key());
+    let policy = NetworkPolicy::from_config(&config);
+    stock::search(&policy, provider, &credentials, &query, page.unwrap_or(1)).await
+}
+
+/// Download a search result (`size` defaults to medium) into the project as
+/// an image node, with the provider's license and the photographer's credit.
+#[tauri::command]
+pub async fn import_stock_result(
+    id: String,
+    size: Option<StockSize>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<StockImport, AppError> {
+    let project_root = get_project_path(&state)?;
+    let provider = id.split(':').next().unwrap_or_default();
+    let config = GlobalConfig::load(&app);
+    config.org.check_provider(provider)?;
+    let credentials = ProviderCredentials::from_config(&config, provider);
+    let policy = NetworkPolicy::from_config(&config);
+
+    let photo = stock::fetch_photo(&policy, &credentials, &id).await?;
+    let assets_dir = project_root.join("assets");
+    std::fs::create_dir_all(&assets_dir)?;
+    let download_id = uuid::Uuid::new_v4().to_string();
+    let temp_path = assets_dir.join(format!("{}.download", download_id));
+    let url = photo.url(size.unwrap_or_default()).to_string();
+    state.downloads
+        .download(&app, &policy, &download_id, &url, &temp_path, &DownloadOptions::default())
+        .await?;
+    // Only a courtesy to the provider; the photo is already here
+    let _ = stock::track_download(&policy, &credentials, &photo).await;
+
+    state.blocking.run(move || {
+        let data = std::fs::read(&temp_path);
+        let _ = std::fs::remove_file(&temp_path);
+        stock::import(&project_root, &photo, &data?)
+    }).await
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,125 @@
+//! Asset tag commands: hand-added tags, tag search and library auto-tagging.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::AssetTag;
+use crate::services::{auto_tag, database, interrogate, io_sqlite, tags};
+use crate::services::auto_tag::{AutoTagScope, AutoTagSettings};
+use crate::services::custom_fields::FieldFilter;
+use crate::services::licenses::LicenseFilter;
+use crate::services::interrogate::LocalInterrogator;
+use crate::services::job_queue::Job;
+use crate::services::tags::ScoredTag;
+use super::jobs::{spawn_local_job, submit_job, JobInfo};
+
+/// Default max results of a tag search
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+#[tauri::command]
+pub fn get_asset_tags(asset_id: String, state: State<AppState>) -> Result<Vec<AssetTag>, AppError> {
+    let conn = open_project_db(&state)?;
+    tags::get_tags(&conn, &asset_id)
+}
+
+/// Replace the hand-added tags of an asset; machine tags are kept.
+#[tauri::command]
+pub fn set_asset_tags(
+    asset_id: String,
+    tags: Vec<String>,
+    state: State<AppState>,
+) -> Result<Vec<AssetTag>, AppError> {
+    let conn = open_project_db(&state)?;
+    if io_sqlite::get_asset(&conn, &asset_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Asset not found: {}", asset_id)));
+    }
+    let tags: Vec<ScoredTag> = tags.into_iter().map(|t| (t, None)).collect();
+    tags::set_tags(&conn, &asset_id, tags::USER_SOURCE, &tags)
+}
+
+/// IDs of assets matching every comma-separated tag prefix in `query`,
+/// every custom field filter in `fields` and the `license` filter.
+#[tauri::command]
+pub fn search_assets_by_tags(
+    query: String,
+    limit: Option<usize>,
+    fields: Option<Vec<FieldFilter>>,
+    license: Option<LicenseFilter>,
+    state: State<AppState>,
+) -> Result<Vec<String>, AppError> {
+    let conn = open_project_db(&state)?;
+    tags::search(&conn, &query, &fields.unwrap_or_default(), license.as_ref(), limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+}
+
+#[tauri::command]
+pub fn get_auto_tag_settings(state: State<AppState>) -> Result<AutoTagSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    auto_tag::get_settings(&conn)
+}
+
+/// Store the confidence threshold and tag limit; applies to later runs.
+#[tauri::command]
+pub fn save_auto_tag_settings(settings: AutoTagSettings, state: State<AppState>) -> Result<AutoTagSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    auto_tag::save_settings(&conn, &settings)
+}
+
+/// Tag the images in `scope` (untagged ones by default) in the background.
+/// The "local" provider (default) runs the local tagger; other providers
+/// are dispatched as `job:task`. Counts arrive with `job:completed`.
+#[tauri::command]
+pub fn auto_tag_assets(
+    scope: Option<AutoTagScope>,
+    provider: Option<String>,
+    concurrency: Option<usize>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = open_project_db(&state)?;
+    let provider = provider.unwrap_or_else(|| interrogate::LOCAL_PROVIDER.to_string());
+    let tasks = auto_tag::pending_tasks(&conn, &project_path, &scope.unwrap_or_default(), &provider)?;
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    
+    if provider != interrogate::LOCAL_PROVIDER {
+        let job = Job::new(auto_tag::JOB_KIND, serde_json::Value::Null, params, concurrency.unwrap_or(3));
+        return submit_job(&app, &state, job);
+    }
+    
+    let tagger = LocalInterrogator::find().ok_or_else(|| AppError::NotFound(
+        "Local tagger not found; set SYNNIA_INTERROGATOR and SYNNIA_CLIP_MODEL".to_string()
+    ))?;
+    // The model uses every core; more workers only add memory
+    let job = Job::new(auto_tag::JOB_KIND, serde_json::Value::Null, params, concurrency.unwrap_or(1));
+    let root = project_path.clone();
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| tagger.run(&root, params),
+        move |job| {
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            Ok(serde_json::to_value(auto_tag::finalize_auto_tag(&conn, job)?)?)
+        },
+    ))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
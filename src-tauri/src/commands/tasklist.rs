@@ -0,0 +1,43 @@
+//! Task list commands: check off tasks and roll up group progress.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::Asset;
+use crate::services::io_sqlite;
+use crate::services::tasklist::{self, GroupProgress};
+
+/// Toggle one task's `done` flag. Returns the updated task list.
+#[tauri::command]
+pub fn toggle_task(
+    asset_id: String,
+    index: usize,
+    state: State<AppState>,
+) -> Result<Asset, AppError> {
+    let project_path = get_project_path(&state)?;
+    tasklist::toggle_task(&project_path, &asset_id, index)
+}
+
+/// Task completion across all task lists inside a group.
+#[tauri::command]
+pub fn get_group_progress(
+    group_node_id: String,
+    state: State<AppState>,
+) -> Result<GroupProgress, AppError> {
+    let project_path = get_project_path(&state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    tasklist::group_progress(&project, &group_node_id)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
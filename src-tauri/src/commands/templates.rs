@@ -0,0 +1,39 @@
+//! Template gallery commands: sync the shared starting kit from a remote index.
+
+use tauri::{AppHandle, Manager};
+use std::path::PathBuf;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::template_sync::{self, TemplateStores, TemplateSyncReport};
+use super::agent::get_agents_dir;
+use super::components::get_components_dir;
+
+// Helper to get the global project templates directory
+pub(crate) fn get_templates_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let docs_dir = app.path().document_dir().map_err(|_| AppError::Unknown("No documents directory found".into()))?;
+    let templates_dir = docs_dir.join("Synnia").join("Templates");
+    if !templates_dir.exists() {
+        std::fs::create_dir_all(&templates_dir).map_err(|e| AppError::Io(e.to_string()))?;
+    }
+    Ok(templates_dir)
+}
+
+/// Install new and updated templates, components and agents listed by the
+/// index at `url`, verifying each download's checksum. Entries edited
+/// locally are kept. With `dry_run` nothing is downloaded besides the index.
+#[tauri::command]
+pub async fn sync_templates_from_url(url: String, dry_run: Option<bool>, app: AppHandle) -> Result<TemplateSyncReport, AppError> {
+    let policy = NetworkPolicy::from_config(&GlobalConfig::load(&app));
+    let templates = get_templates_dir(&app)?;
+    let manifest = templates.parent()
+        .map(|synnia_dir| synnia_dir.join("template-sync.json"))
+        .ok_or_else(|| AppError::Unknown("No Synnia directory found".into()))?;
+    let stores = TemplateStores {
+        templates,
+        components: get_components_dir(&app)?,
+        agents: get_agents_dir(&app)?,
+        manifest,
+    };
+    template_sync::sync(&policy, &url, &stores, dry_run.unwrap_or(false)).await
+}
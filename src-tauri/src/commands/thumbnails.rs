@@ -0,0 +1,96 @@
+//! Thumbnail commands: per-project size/quality and background regeneration.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{batch_import, database, io_sqlite, thumbnails};
+use crate::services::imaging::ThumbnailSettings;
+use crate::services::job_queue::Job;
+use crate::services::thumbnails::ThumbnailTask;
+use super::jobs::{spawn_local_job, JobInfo};
+
+#[tauri::command]
+pub fn get_thumbnail_settings(state: State<AppState>) -> Result<ThumbnailSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    thumbnails::get_settings(&conn)
+}
+
+/// Store thumbnail settings; existing thumbnails keep their old size until
+/// `regenerate_thumbnails(force: true)` runs.
+#[tauri::command]
+pub fn save_thumbnail_settings(settings: ThumbnailSettings, state: State<AppState>) -> Result<ThumbnailSettings, AppError> {
+    let conn = open_project_db(&state)?;
+    thumbnails::save_settings(&conn, &settings)
+}
+
+/// Regenerate missing thumbnails (all of them with `force`) in the
+/// background. Progress is reported via `job:progress` events and the
+/// result via `job:completed`; cancel with `cancel_job`.
+#[tauri::command]
+pub async fn regenerate_thumbnails(
+    force: Option<bool>,
+    concurrency: Option<usize>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(&state)?;
+    // Finding pending thumbnails checks every image asset on disk
+    let root = project_path.clone();
+    let (settings, tasks) = state.blocking.run(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        let settings = thumbnails::get_settings(&conn)?;
+        let tasks = thumbnails::pending_tasks(&conn, &root, force.unwrap_or(false))?;
+        Ok((settings, tasks))
+    }).await?;
+    
+    let params = tasks.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    let job = Job::new(
+        thumbnails::JOB_KIND,
+        serde_json::Value::Null,
+        params,
+        concurrency.unwrap_or_else(batch_import::default_concurrency),
+    );
+    let root = project_path.clone();
+    
+    Ok(spawn_local_job(
+        app,
+        state.jobs.clone(),
+        job,
+        move |params| {
+            let task: ThumbnailTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+            thumbnails::regenerate(&root, &task, &settings).map(serde_json::Value::from)
+        },
+        move |job| {
+            let previews: Vec<(String, String)> = job.tasks.iter()
+                .filter_map(|t| {
+                    let task: ThumbnailTask = serde_json::from_value(t.params.clone()).ok()?;
+                    Some((task.asset_id, t.result.as_ref()?.as_str()?.to_string()))
+                })
+                .collect();
+            let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+                .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+            let updated = thumbnails::record_previews(&conn, &previews)?;
+            Ok(serde_json::json!({ "regenerated": previews.len(), "assetsUpdated": updated }))
+        },
+    ))
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -0,0 +1,34 @@
+//! Time tracking commands: open/focus time reports for the current project.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{database, io_sqlite};
+use crate::services::time_tracking::{self, TimePeriod, TimeReport};
+
+/// Time spent per day, week or month. `since` / `until` are Unix timestamps (ms).
+#[tauri::command]
+pub fn get_time_report(
+    period: Option<TimePeriod>,
+    since: Option<i64>,
+    until: Option<i64>,
+    state: State<AppState>,
+) -> Result<TimeReport, AppError> {
+    let project_path = get_project_path(&state)?;
+    let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    time_tracking::get_time_report(&conn, period.unwrap_or_default(), since, until)
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,76 @@
+//! Tracker export commands: push selected assets to a Notion database or an
+//! Airtable table.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::config::GlobalConfig;
+use crate::services::{database, io_sqlite, tracker_export};
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::provider_health::ProviderCredentials;
+use crate::services::tracker_export::{ExportItem, ExportMapping, TrackerExport};
+
+/// Create a page per asset in the Notion database `database_id`, filling the
+/// properties named in `mapping`.
+#[tauri::command]
+pub async fn export_to_notion(
+    asset_ids: Vec<String>,
+    database_id: String,
+    mapping: ExportMapping,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<TrackerExport, AppError> {
+    let (policy, token) = service_access(&app, "notion", "Notion")?;
+    let items = collect_items(&state, asset_ids, &mapping).await?;
+    tracker_export::to_notion(&policy, &token, &database_id, &mapping, items).await
+}
+
+/// Create a record per asset in `table` (name or ID) of the Airtable base
+/// `base_id`, filling the fields named in `mapping`.
+#[tauri::command]
+pub async fn export_to_airtable(
+    asset_ids: Vec<String>,
+    base_id: String,
+    table: String,
+    mapping: ExportMapping,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<TrackerExport, AppError> {
+    let (policy, token) = service_access(&app, "airtable", "Airtable")?;
+    let items = collect_items(&state, asset_ids, &mapping).await?;
+    tracker_export::to_airtable(&policy, &token, &base_id, &table, &mapping, items).await
+}
+
+// Helper functions
+
+/// Network policy and API token of a service, if the studio allows it
+fn service_access(app: &AppHandle, key: &str, label: &str) -> Result<(NetworkPolicy, String), AppError> {
+    let config = GlobalConfig::load(app);
+    config.org.check_provider(key)?;
+    let token = ProviderCredentials::from_config(&config, key).api_key
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("Add a {} API token in Settings", label)))?;
+    Ok((NetworkPolicy::from_config(&config), token))
+}
+
+async fn collect_items(state: &State<'_, AppState>, asset_ids: Vec<String>, mapping: &ExportMapping) -> Result<Vec<ExportItem>, AppError> {
+    let project_path = get_project_path(state)?;
+    let mapping = mapping.clone();
+    state.blocking.run(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&project_path))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        tracker_export::collect(&project_path, &conn, &asset_ids, &mapping)
+    }).await
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
@@ -0,0 +1,68 @@
+//! Translation commands: translate a text asset or every text in a group.
+//!
+//! Texts are dispatched through the job queue (`job:task` with kind
+//! "translation"); results become linked text assets when the job settles.
+
+use tauri::{State, AppHandle};
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::{io_sqlite, translation};
+use crate::services::job_queue::Job;
+use crate::services::translation::{TranslationPayload, TranslationSource};
+use super::jobs::{submit_job, JobInfo};
+
+/// Translate one text asset into `target_lang`.
+#[tauri::command]
+pub fn translate_asset(
+    asset_id: String,
+    target_lang: String,
+    provider: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    submit_translation(TranslationSource::Asset(asset_id), target_lang, provider, None, &state, &app)
+}
+
+/// Translate every text asset nested in a group (batch mode).
+#[tauri::command]
+pub fn translate_group(
+    group_id: String,
+    target_lang: String,
+    provider: Option<String>,
+    concurrency: Option<usize>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<JobInfo, AppError> {
+    submit_translation(TranslationSource::Group(group_id), target_lang, provider, concurrency, &state, &app)
+}
+
+// Helper functions
+
+fn submit_translation(
+    source: TranslationSource,
+    target_lang: String,
+    provider: Option<String>,
+    concurrency: Option<usize>,
+    state: &State<AppState>,
+    app: &AppHandle,
+) -> Result<JobInfo, AppError> {
+    let project_path = get_project_path(state)?;
+    let project = io_sqlite::load_project_sqlite(&project_path)?;
+    
+    let payload = TranslationPayload { target_lang, provider };
+    let tasks = translation::build_tasks(&project, source, &payload)?;
+    
+    let job = Job::new(translation::TRANSLATION_JOB_KIND, serde_json::to_value(&payload)?, tasks, concurrency.unwrap_or(3));
+    submit_job(app, state, job)
+}
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
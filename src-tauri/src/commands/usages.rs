@@ -0,0 +1,53 @@
+//! Where-used commands: which projects and nodes depend on a shared recipe
+//! or agent.
+
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::AppState;
+use crate::services::usages::{self, Subject, UsageReport};
+
+/// Projects and nodes using the recipe (template) `template_id`, across the
+/// open project and the recent projects.
+#[tauri::command]
+pub async fn get_template_usages(
+    template_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<UsageReport, AppError> {
+    let projects = workspace_projects(&state, &app);
+    state.blocking.run(move || Ok(usages::scan(&projects, Subject::Recipe(&template_id)))).await
+}
+
+/// Projects where the agent ran and the nodes it ran on, across the open
+/// project and the recent projects.
+#[tauri::command]
+pub async fn get_agent_usages(
+    agent_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<UsageReport, AppError> {
+    let agent = super::agent::get_agents(app.clone())?
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| AppError::NotFound(format!("Agent not found: {}", agent_id)))?;
+    let projects = workspace_projects(&state, &app);
+    state.blocking.run(move || Ok(usages::scan(&projects, Subject::Agent(&agent.name)))).await
+}
+
+// Helper functions
+
+/// The open project first, then the recent ones
+fn workspace_projects(state: &State<'_, AppState>, app: &AppHandle) -> Vec<(String, PathBuf)> {
+    let mut projects = Vec::new();
+    if let Some(path) = state.current_project_path.lock().ok().and_then(|p| p.clone()) {
+        let path = PathBuf::from(path);
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        projects.push((name, path));
+    }
+    projects.extend(GlobalConfig::load(app).recent_projects
+        .into_iter()
+        .map(|p| (p.name, PathBuf::from(p.path))));
+    projects
+}
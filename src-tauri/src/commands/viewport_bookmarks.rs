@@ -0,0 +1,60 @@
+//! Viewport bookmark commands: named camera positions on the canvas.
+
+use tauri::State;
+use std::path::PathBuf;
+use crate::error::AppError;
+use crate::AppState;
+use crate::models::{Viewport, ViewportBookmark};
+use crate::services::{canvases, database, io_sqlite, viewport_bookmarks};
+
+/// Bookmark `viewport`, or the last saved viewport of the active canvas if
+/// none is given.
+#[tauri::command]
+pub fn save_viewport_bookmark(
+    name: String,
+    viewport: Option<Viewport>,
+    state: State<AppState>,
+) -> Result<ViewportBookmark, AppError> {
+    let conn = open_project_db(&state)?;
+    let viewport = match viewport {
+        Some(viewport) => viewport,
+        None => io_sqlite::load_viewport(&conn, &canvases::active_canvas(&conn)?)?,
+    };
+    viewport_bookmarks::save(&conn, &name, &viewport)
+}
+
+#[tauri::command]
+pub fn list_viewport_bookmarks(state: State<AppState>) -> Result<Vec<ViewportBookmark>, AppError> {
+    let conn = open_project_db(&state)?;
+    viewport_bookmarks::list(&conn)
+}
+
+#[tauri::command]
+pub fn delete_viewport_bookmark(
+    bookmark_id: String,
+    state: State<AppState>,
+) -> Result<(), AppError> {
+    let conn = open_project_db(&state)?;
+    if !viewport_bookmarks::delete(&conn, &bookmark_id)? {
+        return Err(AppError::NotFound(format!("Bookmark not found: {}", bookmark_id)));
+    }
+    Ok(())
+}
+
+// Helper functions
+
+fn get_project_path(state: &State<AppState>) -> Result<PathBuf, AppError> {
+    let path_guard = state.current_project_path.lock()
+        .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    
+    path_guard
+        .as_ref()
+        .map(PathBuf::from)
+        .ok_or(AppError::ProjectNotLoaded)
+}
+
+fn open_project_db(state: &State<AppState>) -> Result<rusqlite::Connection, AppError> {
+    let project_path = get_project_path(state)?;
+    database::open_db(&io_sqlite::get_db_path(&project_path))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri::Manager;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
+use crate::services::backup::BackupSettings;
+use crate::services::net_guard::NetworkTimeouts;
+use crate::services::org_config::{self, OrgConfig};
+use ts_rs::TS;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GlobalConfig {
@@ -24,13 +28,38 @@ pub struct GlobalConfig {
     
     // Unified app settings (JSON string) - new simplified format
     pub app_settings: Option<String>,
+    
+    // Bearer token for the local server's /clip endpoint (browser extension)
+    pub clip_token: Option<String>,
+    
+    // Outbound network policy: user-approved hosts and strict mode
+    pub network_allowlist: Option<Vec<String>>,
+    pub network_strict: Option<bool>,
+    pub network_timeouts: Option<NetworkTimeouts>,
+    
+    // Scheduled project backups
+    pub backup: Option<BackupSettings>,
+    
+    // Read-only organization layer, loaded alongside and never saved
+    #[serde(skip)]
+    pub org: OrgConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
 pub struct RecentProject {
     pub name: String,
     pub path: String,
     pub last_opened: String, // ISO Date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    // Other paths this same project was opened through (symlinks, mounts)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    // Another entry with the same project ID that can't be proven to be the
+    // same folder (a copy, or a volume that isn't mounted right now)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambiguous_with: Option<String>,
 }
 
 impl GlobalConfig {
@@ -45,12 +74,19 @@ impl GlobalConfig {
         
         let config_path = config_dir.join("config.json");
         
-        if config_path.exists() {
+        let mut config: GlobalConfig = if config_path.exists() {
             let content = fs::read_to_string(&config_path).unwrap_or_default();
             serde_json::from_str(&content).unwrap_or_default()
         } else {
             GlobalConfig::default()
-        }
+        };
+        config.org = org_config::load(&config_dir);
+        config
+    }
+
+    /// The user's workspace, or the organization's default
+    pub fn workspace(&self) -> Option<String> {
+        self.default_workspace.clone().or_else(|| self.org.default_workspace.clone())
     }
 
     pub fn save(&self, app: &AppHandle) -> Result<(), String> {
@@ -63,16 +99,17 @@ impl GlobalConfig {
         fs::write(config_path, json).map_err(|e| e.to_string())
     }
 
-    pub fn add_recent(&mut self, name: String, path: String) {
-        // Remove existing entry if present (deduplication)
-        self.recent_projects.retain(|p| p.path != path);
-        
-        // Add to top (MRU)
+    pub fn add_recent(&mut self, name: String, path: String, project_id: Option<String>) {
+        // Add to top (MRU); older entries for the same folder merge into it
         self.recent_projects.insert(0, RecentProject {
             name,
-            path,
+            path: normalize_project_path(&path),
             last_opened: chrono::Utc::now().to_rfc3339(),
+            project_id,
+            aliases: Vec::new(),
+            ambiguous_with: None,
         });
+        self.merge_recent_duplicates();
 
         // Limit to 10 items
         if self.recent_projects.len() > 10 {
@@ -80,7 +117,97 @@ impl GlobalConfig {
         }
     }
 
+    /// Merge recent entries that reach the same project folder (same
+    /// resolved path or same database file), keeping the most recent, and
+    /// flag entries sharing a project ID that can't be told apart.
+    /// Returns whether anything changed.
+    pub fn merge_recent_duplicates(&mut self) -> bool {
+        let before = self.recent_projects.clone();
+        let mut merged: Vec<(RecentProject, Option<FileIdentity>)> = Vec::new();
+
+        for entry in std::mem::take(&mut self.recent_projects) {
+            let path = normalize_project_path(&entry.path);
+            let identity = project_file_identity(Path::new(&path));
+            let same = merged.iter_mut().find(|(kept, kept_identity)| {
+                normalize_project_path(&kept.path) == path || (identity.is_some() && *kept_identity == identity)
+            });
+            match same {
+                Some((kept, _)) => {
+                    for alias in std::iter::once(entry.path).chain(entry.aliases) {
+                        if alias != kept.path && !kept.aliases.contains(&alias) {
+                            kept.aliases.push(alias);
+                        }
+                    }
+                    if kept.project_id.is_none() {
+                        kept.project_id = entry.project_id;
+                    }
+                }
+                None => merged.push((entry, identity)),
+            }
+        }
+
+        self.recent_projects = merged.into_iter().map(|(entry, _)| entry).collect();
+        let ids: Vec<(Option<String>, String)> = self.recent_projects.iter()
+            .map(|p| (p.project_id.clone(), p.path.clone()))
+            .collect();
+        for project in &mut self.recent_projects {
+            project.ambiguous_with = ids.iter()
+                .find(|(id, path)| id.is_some() && *id == project.project_id && *path != project.path)
+                .map(|(_, path)| path.clone());
+        }
+
+        self.recent_projects != before
+    }
+
     pub fn set_workspace(&mut self, path: String) {
         self.default_workspace = Some(path);
     }
-}
\ No newline at end of file
+
+    /// Get the clip token, generating one on first use.
+    /// Returns the token and whether the config changed.
+    pub fn ensure_clip_token(&mut self) -> (String, bool) {
+        match &self.clip_token {
+            Some(token) if !token.is_empty() => (token.clone(), false),
+            _ => {
+                let token = uuid::Uuid::new_v4().simple().to_string();
+                self.clip_token = Some(token.clone());
+                (token, true)
+            }
+        }
+    }
+}
+
+/// Device and inode of a project's database, to spot one folder reached
+/// through two paths
+type FileIdentity = (u64, u64);
+
+#[cfg(unix)]
+fn project_file_identity(path: &Path) -> Option<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(path.join("synnia.db")).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn project_file_identity(_path: &Path) -> Option<FileIdentity> {
+    None
+}
+
+/// Resolve symlinks and `..` when the folder exists; otherwise just drop
+/// trailing separators.
+fn normalize_project_path(path: &str) -> String {
+    match fs::canonicalize(path) {
+        Ok(resolved) => {
+            let resolved = resolved.to_string_lossy().to_string();
+            // Keep Windows paths in their usual form, not the verbatim `\\?\` one
+            match resolved.strip_prefix(r"\\?\") {
+                Some(rest) if !rest.starts_with("UNC") => rest.to_string(),
+                _ => resolved,
+            }
+        }
+        Err(_) => {
+            let trimmed = path.trim_end_matches(['/', '\\']);
+            if trimmed.is_empty() { path.to_string() } else { trimmed.to_string() }
+        }
+    }
+}
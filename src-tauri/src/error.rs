@@ -9,6 +9,9 @@ pub enum AppError {
     Agent(String),
     ProjectNotLoaded,
     NotFound(String),
+    Forbidden(String),
+    InvalidInput(String),
+    InsufficientSpace(String),
     Unknown(String),
     Serialization(String),
 }
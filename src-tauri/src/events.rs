@@ -0,0 +1,198 @@
+//! Every event the backend sends to the frontend.
+//!
+//! Events are variants of `AppEvent`, each carrying its payload type; the
+//! serde rename is the event name the frontend listens for. Emitting goes
+//! through `emit`, so a name and its payload can't drift apart, and the
+//! exported `AppEvent` union gives the frontend the payload type for each
+//! name.
+//!
+//! Events are also mirrored, as `{ event, payload }` JSON, to subscribers
+//! of `subscribe` — the local server's `/events` WebSocket — so tools
+//! outside the app can follow the project live.
+
+use std::sync::OnceLock;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+use crate::commands::http_proxy::ProxyChunk;
+use crate::error::AppError;
+use crate::services::asset_stream::StreamUpdate;
+use crate::services::budget::BudgetExceeded;
+use crate::services::clip::ClipImport;
+use crate::services::cloud_sync::SyncStatus;
+use crate::services::connectors::ConnectorProvider;
+use crate::services::disk_space::LowDiskSpace;
+use crate::services::download::DownloadProgress;
+use crate::services::folder_import::ImportProgress;
+use crate::services::job_queue::{Job, TaskDispatch};
+use crate::services::linked_files::BrokenLink;
+use crate::services::permissions::PermissionRequest;
+use crate::services::references::AssetUpdated;
+use crate::services::reminders::FiredReminder;
+use crate::services::safety::FlaggedAsset;
+use crate::services::url_scheme::RecipeRunRequest;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "event", content = "payload")]
+pub enum AppEvent {
+    /// A project was opened or created
+    #[serde(rename = "project:active")]
+    ProjectActive(ProjectActive),
+    /// The open project lives in a cloud-synced folder
+    #[serde(rename = "project:cloud-sync")]
+    CloudSync(SyncStatus),
+    /// The sync client left conflicted copies of the project database
+    #[serde(rename = "project:sync-conflict")]
+    SyncConflict(SyncStatus),
+    /// Linked imports whose originals can't be reached, found on load
+    #[serde(rename = "assets:broken-links")]
+    BrokenLinks(Vec<BrokenLink>),
+    /// An asset changed outside the canvas (e.g. a reference was edited)
+    #[serde(rename = "asset:updated")]
+    AssetUpdated(AssetUpdated),
+    /// Text streamed into an asset by an agent
+    #[serde(rename = "asset:streaming")]
+    AssetStreaming(StreamUpdate),
+    /// Clippings arrived from the browser extension
+    #[serde(rename = "clip:imported")]
+    ClipImported(Vec<ClipImport>),
+    #[serde(rename = "import:progress")]
+    ImportProgress(ImportProgress),
+    #[serde(rename = "download:progress")]
+    DownloadProgress(DownloadProgress),
+    #[serde(rename = "disk:low-space")]
+    LowDiskSpace(LowDiskSpace),
+    /// A task for the frontend to run
+    #[serde(rename = "job:task")]
+    JobTask(TaskDispatch),
+    /// A task of a backend-run job settled
+    #[serde(rename = "job:progress")]
+    JobProgress(JobProgress),
+    #[serde(rename = "job:completed")]
+    JobCompleted(JobCompleted),
+    #[serde(rename = "job:cancelled")]
+    JobCancelled(Job),
+    /// A job was held because it would exceed the budget
+    #[serde(rename = "budget:exceeded")]
+    BudgetExceeded(BudgetExceeded),
+    /// An action needs the user's approval
+    #[serde(rename = "permission:request")]
+    PermissionRequest(PermissionRequest),
+    /// The review queue after new images were flagged
+    #[serde(rename = "safety:flagged")]
+    SafetyFlagged(Vec<FlaggedAsset>),
+    /// A connector sign-in finished
+    #[serde(rename = "connector:connected")]
+    ConnectorConnected(ConnectorProvider),
+    /// A `synnia://run-recipe` link asked to run a recipe node
+    #[serde(rename = "automation:run-recipe")]
+    RunRecipe(RecipeRunRequest),
+    #[serde(rename = "reminder:fired")]
+    ReminderFired(FiredReminder),
+    /// Part of a streamed proxy response
+    #[serde(rename = "proxy:chunk")]
+    ProxyChunk(ProxyChunk),
+}
+
+/// Payload of `project:active`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectActive {
+    pub name: String,
+}
+
+/// Payload of `job:progress`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    pub job_id: String,
+    pub current: usize,
+    pub total: usize,
+}
+
+/// Payload of `job:completed`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JobCompleted {
+    pub job: Job,
+    /// What the job's finalizer produced, or `{ error }` if it failed
+    #[ts(type = "any")]
+    pub output: serde_json::Value,
+}
+
+/// Mirrored events a subscriber may fall behind by before it skips ahead
+const MIRROR_CAPACITY: usize = 256;
+
+/// Send `event` to every window and to mirror subscribers
+pub fn emit(app: &AppHandle, event: AppEvent) -> Result<(), AppError> {
+    let mirrored = event.is_mirrored();
+    let mut value = serde_json::to_value(event)?;
+    let sender = mirror();
+    if mirrored && sender.receiver_count() > 0 {
+        let _ = sender.send(value.to_string());
+    }
+    let (name, payload) = into_parts(&mut value);
+    app.emit(&name, payload).map_err(|e| AppError::Unknown(e.to_string()))
+}
+
+/// Receive mirrored events as `{ "event": ..., "payload": ... }` JSON
+pub fn subscribe() -> broadcast::Receiver<String> {
+    mirror().subscribe()
+}
+
+impl AppEvent {
+    /// Proxied responses are for the webview that asked; everything else
+    /// goes to mirror subscribers as well
+    fn is_mirrored(&self) -> bool {
+        !matches!(self, AppEvent::ProxyChunk(_))
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn mirror() -> &'static broadcast::Sender<String> {
+    static MIRROR: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+    MIRROR.get_or_init(|| broadcast::channel(MIRROR_CAPACITY).0)
+}
+
+/// Event name and payload of a serialized `AppEvent`, as the frontend
+/// receives them
+fn into_parts(value: &mut serde_json::Value) -> (String, serde_json::Value) {
+    let name = value.get("event").and_then(|e| e.as_str()).unwrap_or_default().to_string();
+    let payload = value.get_mut("payload").map(serde_json::Value::take).unwrap_or_default();
+    (name, payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parts(event: AppEvent) -> (String, serde_json::Value) {
+        into_parts(&mut serde_json::to_value(event).unwrap())
+    }
+
+    #[test]
+    fn test_event_parts() {
+        let (name, payload) = parts(AppEvent::JobProgress(JobProgress { job_id: "j1".to_string(), current: 2, total: 5 }));
+        assert_eq!(name, "job:progress");
+        assert_eq!(payload, json!({ "jobId": "j1", "current": 2, "total": 5 }));
+
+        let (name, payload) = parts(AppEvent::BrokenLinks(Vec::new()));
+        assert_eq!((name.as_str(), payload), ("assets:broken-links", json!([])));
+    }
+
+    #[test]
+    fn test_mirrored_events() {
+        let chunk = ProxyChunk { request_id: "r".to_string(), chunk: String::new(), body_encoding: "text".to_string() };
+        assert!(!AppEvent::ProxyChunk(chunk).is_mirrored());
+        assert!(AppEvent::ProjectActive(ProjectActive { name: "Moodboard".to_string() }).is_mirrored());
+    }
+}
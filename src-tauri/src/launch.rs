@@ -0,0 +1,117 @@
+//! Command-line launch options.
+//!
+//! `synnia --server` starts the services layer without a window: the local
+//! server (assets, REST, `/events`, `/clip`), the job queue, schedulers and
+//! recipe engine keep running so a studio machine can host batch work while
+//! artists connect from their desktop clients with the clip token. The app
+//! still links the webview runtime, so on Linux it needs a display server
+//! (`xvfb-run synnia --server` on a machine without one).
+//!
+//! Jobs whose tasks are provider calls (generation, translation, recipes)
+//! have no frontend to run them on: workers submit and report them over the
+//! REST API (see `services::rest_api`).
+//!
+//! ```text
+//! synnia --server [--host 0.0.0.0] [--port 7420] [--project <path>] [--print-token]
+//! ```
+//!
+//! The token stays in the app config; `--print-token` shows it on start.
+
+use std::path::PathBuf;
+
+/// Host the local server binds to unless told otherwise
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Run headless
+    pub server: bool,
+    pub host: String,
+    /// Fixed port; a free one is picked when unset
+    pub port: Option<u16>,
+    /// Project to open on start
+    pub project: Option<PathBuf>,
+    /// Show the clip token on start
+    pub print_token: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self { server: false, host: DEFAULT_HOST.to_string(), port: None, project: None, print_token: false }
+    }
+}
+
+impl LaunchOptions {
+    /// Parse the process arguments (without the program name). Unknown
+    /// arguments are left to the platform (e.g. macOS's `-psn_*`).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = |name: &str| inline.clone().or_else(|| args.next())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| format!("{} needs a value", name));
+            match flag.as_str() {
+                "--server" => options.server = true,
+                "--host" => options.host = value("--host")?,
+                "--port" => {
+                    let port = value("--port")?;
+                    options.port = Some(port.parse().map_err(|_| format!("Invalid port: {}", port))?);
+                }
+                "--project" => options.project = Some(PathBuf::from(value("--project")?)),
+                "--print-token" => options.print_token = true,
+                _ => {}
+            }
+        }
+        if !options.server && (options.host != DEFAULT_HOST || options.project.is_some() || options.print_token) {
+            return Err("--host, --project and --print-token only apply with --server".to_string());
+        }
+        Ok(options)
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Whether the server is reachable from other machines
+    pub fn is_public(&self) -> bool {
+        !is_loopback_host(&self.host)
+    }
+}
+
+/// Whether binding `host` keeps a server to this machine
+pub fn is_loopback_host(host: &str) -> bool {
+    matches!(host, "127.0.0.1" | "localhost" | "::1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+        assert_eq!(parse(&["-psn_0_12345"]).unwrap(), LaunchOptions::default());
+
+        let options = parse(&["--server", "--host", "0.0.0.0", "--port=7420", "--project", "/srv/shots"]).unwrap();
+        assert!(options.server);
+        assert!(options.is_public());
+        assert_eq!(options.port, Some(7420));
+        assert_eq!(options.project, Some(PathBuf::from("/srv/shots")));
+
+        assert!(parse(&["--server", "--port", "web"]).is_err());
+        assert!(parse(&["--server", "--host"]).is_err());
+        assert!(parse(&["--host", "0.0.0.0"]).is_err());
+        assert!(parse(&["--print-token"]).is_err());
+        assert!(parse(&["--server", "--print-token"]).unwrap().print_token);
+        assert!(!parse(&["--server"]).unwrap().is_public());
+    }
+}
@@ -1,4 +1,5 @@
 use tauri::{Manager, State};
+use config::GlobalConfig;
 use serde::{Serialize, Deserialize};
 use ts_rs::TS;
 use std::sync::{Mutex, Arc};
@@ -9,6 +10,8 @@ mod models;
 mod services;
 mod error;
 mod config;
+mod events;
+mod launch;
 mod state; 
 
 use state::AppState; 
@@ -35,18 +38,97 @@ fn get_server_port(state: State<AppState>) -> u16 {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let options = match launch::LaunchOptions::from_env() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("synnia: {}", e);
+            std::process::exit(2);
+        }
+    };
+    let headless = options.server;
+
     // Shared State for Project Path (between Tauri Commands and Actix)
     let current_project_path = Arc::new(Mutex::new(None));
 
-    // Start Local File Server
-    let server_port = services::file_server::init(current_project_path.clone());
+    let mut builder = tauri::Builder::default();
+    // Opening a `synnia://` link launches the app again; the running one
+    // takes the link instead. Separate `--server` instances may run side by side.
+    if !headless {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }));
+    }
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
+            // Clip token is persisted so the browser extension stays paired
+            let mut config = GlobalConfig::load(app.handle());
+            let (token, changed) = config.ensure_clip_token();
+            if changed {
+                if let Err(e) = config.save(app.handle()) {
+                    println!("Failed to save global config: {}", e);
+                }
+            }
+            let clip_token = Arc::new(Mutex::new(token));
+
+            // An organization config served from a URL is refreshed on every start
+            let org_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let policy = services::net_guard::NetworkPolicy::from_config(&GlobalConfig::load(&org_app));
+                let Ok(config_dir) = org_app.path().app_config_dir() else { return };
+                if let Err(e) = services::org_config::refresh(&config_dir, &policy).await {
+                    println!("Failed to refresh org config: {}", e);
+                }
+            });
+            let permissions = Arc::new(services::permissions::PermissionGate::new());
+            let logins = Arc::new(services::connectors::oauth::PendingLogins::new());
+
+            // Start Local File Server
+            let server_port = match services::file_server::init(
+                &options.host,
+                options.port,
+                current_project_path.clone(),
+                clip_token.clone(),
+                permissions.clone(),
+                logins.clone(),
+                app.handle().clone(),
+            ) {
+                Ok(port) => port,
+                // Headless, the server is all there is: say why and quit
+                Err(e) if options.server => {
+                    eprintln!("synnia: {}", e);
+                    std::process::exit(1);
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            // Fire due reminders while the app runs
+            services::reminders::start_scheduler(app.handle().clone(), current_project_path.clone());
+            services::backup::start_scheduler(app.handle().clone());
+            services::references::init(app.handle().clone());
+
+            app.manage(AppState {
+                current_project_path,
+                server_port,
+                clip_token,
+                downloads: Arc::new(services::download::DownloadManager::new()),
+                jobs: Arc::new(services::job_queue::JobQueue::new()),
+                sessions: Arc::new(services::time_tracking::SessionTracker::new()),
+                permissions,
+                logins,
+                operations: Arc::new(services::cancellation::CancellationRegistry::new()),
+                blocking: Arc::new(services::blocking::BlockingPool::new()),
+                mobile_capture: Arc::new(services::mobile_capture::MobileCapture::new()),
+            });
+
+            services::url_scheme::init(app.handle());
+            services::model_manager::init(app.handle());
 
-    tauri::Builder::default()
-        .manage(AppState {
-            current_project_path,
-            server_port,
-        })
-        .setup(|app| {
             app.handle().plugin(tauri_plugin_dialog::init())?; // Init dialog plugin
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -56,6 +138,15 @@ pub fn run() {
                 )?;
             }
 
+            if options.server {
+                start_headless(app.handle(), &options)?;
+                return Ok(());
+            }
+
+            // The main window isn't created from the config, so `--server` runs without one
+            if let Some(config) = app.config().app.windows.iter().find(|w| w.label == "main") {
+                tauri::WebviewWindowBuilder::from_config(app.handle(), config)?.build()?;
+            }
             if let Some(window) = app.get_webview_window("main") {
                 // Windows: Manual borderless
                 #[cfg(target_os = "windows")]
@@ -81,6 +172,9 @@ pub fn run() {
             commands::project::set_default_projects_path,
             commands::project::create_project,
             commands::project::load_project, // New
+            commands::project::profile_project_load,
+            #[cfg(debug_assertions)]
+            commands::project::generate_test_project,
             commands::project::save_project, // New
             commands::project::save_project_autosave, // New
             commands::project::get_current_project_path,
@@ -89,6 +183,7 @@ pub fn run() {
             commands::project::set_thumbnail,
             commands::project::open_in_browser,
             commands::project::rename_project,
+            commands::project::get_sync_status,
 
             // Graph Commands REMOVED
 
@@ -98,15 +193,27 @@ pub fn run() {
             commands::agent::get_base_url,
             commands::agent::get_model_name,
             commands::agent::run_agent,
+            commands::agent::run_agent_streaming,
             commands::agent::get_agents,
             commands::agent::save_agent,
             commands::agent::delete_agent,
+            commands::usages::get_agent_usages,
+            commands::usages::get_template_usages,
             commands::agent::get_ai_config,
             commands::agent::save_ai_config,
+            commands::agent::test_ai_connection,
+            commands::agent::list_models,
             commands::agent::get_media_config,
             commands::agent::save_media_config,
             commands::agent::get_app_settings,
             commands::agent::save_app_settings,
+            commands::agent::export_settings_bundle,
+            commands::agent::import_settings_bundle,
+            commands::agent::get_network_allowlist,
+            commands::agent::save_network_allowlist,
+            commands::agent::get_network_timeouts,
+            commands::agent::save_network_timeouts,
+            commands::agent::get_org_config,
 
             // Asset Commands
             commands::asset::import_file,
@@ -114,6 +221,19 @@ pub fn run() {
             commands::asset::download_and_save_image,
             commands::asset::batch_import_images,
             commands::asset::get_media_assets,
+            commands::asset::download_asset_file,
+            commands::asset::cancel_download,
+            commands::asset::get_active_downloads,
+            commands::asset::get_asset_usage_report,
+            commands::asset::get_broken_links,
+            commands::asset::relink_assets,
+            commands::asset::rename_asset,
+            commands::asset::bulk_update_assets,
+            commands::asset::import_folder,
+            commands::asset::generate_video_proxies,
+            commands::asset::trim_audio,
+            commands::asset::upscale_image,
+            commands::asset::generate_image_maps,
 
             // History Commands
             commands::history::save_asset_with_history,
@@ -121,17 +241,267 @@ pub fn run() {
             commands::history::get_history_content,
             commands::history::restore_asset_version,
             commands::history::count_asset_history,
+            commands::history::get_file_history,
+            commands::history::restore_file_version,
+            commands::history::get_project_history_timeline,
+            commands::history::flatten_project,
 
             // HTTP Proxy
             commands::http_proxy::proxy_request,
+            commands::http_proxy::proxy_request_stream,
+
+            // Job Commands
+            commands::jobs::run_generation_grid,
+            commands::jobs::complete_job_task,
+            commands::jobs::cancel_job,
+            commands::jobs::confirm_job_budget,
+            commands::jobs::get_job,
+            commands::jobs::get_jobs,
+
+            // Generation Commands
+            commands::generation::record_generation,
+            commands::generation::get_generation_record,
+            commands::generation::get_asset_variations,
+            commands::generation::regenerate_variation,
+            commands::generation::edit_image_ai,
+            commands::generation::interrogate_image,
+
+            // Comparison Commands
+            commands::comparison::create_comparison,
+            commands::comparison::vote_comparison,
+            commands::comparison::add_comparison_note,
+
+            // Component Commands
+            commands::components::get_components,
+            commands::components::save_component,
+            commands::components::delete_component,
+            commands::components::instantiate_component,
+
+            // Spellcheck Commands
+            commands::spellcheck::get_spellcheck_languages,
+            commands::spellcheck::check_text_asset,
+            commands::spellcheck::apply_fixes,
+
+            // Translation Commands
+            commands::translation::translate_asset,
+            commands::translation::translate_group,
+
+            // Lint Commands
+            commands::lint::lint_project,
+
+            // Brand Commands
+            commands::brand::get_brand_kit,
+            commands::brand::save_brand_kit,
+            commands::brand::check_brand_compliance,
+
+            // Export Commands
+            commands::export::export_crops,
+            commands::export::export_pdf,
+            commands::export::export_subgraph,
+            // Operation Commands
+            commands::operations::cancel_operation,
+            commands::operations::get_active_operations,
+
+            // Planning Commands
+            commands::planning::get_timeline,
+            commands::planning::get_kanban,
+
+            // Task List Commands
+            commands::tasklist::toggle_task,
+            commands::tasklist::get_group_progress,
+
+            // Reminder Commands
+            commands::reminders::schedule_reminder,
+            commands::reminders::get_reminders,
+            commands::reminders::cancel_reminder,
+
+            // Activity Commands
+            commands::activity::get_activity,
+            commands::activity::export_audit_log,
+
+            // Time Tracking Commands
+            commands::time_tracking::get_time_report,
+
+            // Backup Commands
+            commands::backup::get_backup_settings,
+            commands::backup::save_backup_settings,
+            commands::backup::list_backups,
+            commands::backup::run_backups_now,
+            commands::backup::restore_backup,
+
+            // Graph Analysis Commands
+            commands::graph_analysis::analyze_graph,
+
+            // Minimap Commands
+            commands::minimap::render_minimap,
+
+            // Node Lock Commands
+            commands::node_locks::set_node_lock,
+
+            // Reference Commands
+            commands::references::create_reference,
+            commands::references::list_references,
+
+            // Viewport Bookmark Commands
+            commands::viewport_bookmarks::save_viewport_bookmark,
+            commands::viewport_bookmarks::list_viewport_bookmarks,
+            commands::viewport_bookmarks::delete_viewport_bookmark,
+
+            // Canvas Commands
+            commands::canvases::create_canvas,
+            commands::canvases::list_canvases,
+            commands::canvases::open_canvas,
+            commands::canvases::move_nodes_to_canvas,
+
+            // Clip Commands
+            commands::clip::get_clip_token,
+            commands::clip::regenerate_clip_token,
+            commands::clip::get_pairing_qr,
+            commands::clip::stop_mobile_capture,
+
+            // Thumbnail Commands
+            commands::thumbnails::get_thumbnail_settings,
+            commands::thumbnails::save_thumbnail_settings,
+            commands::thumbnails::regenerate_thumbnails,
+
+            // Tag Commands
+            commands::tags::get_asset_tags,
+            commands::tags::set_asset_tags,
+            commands::tags::search_assets_by_tags,
+            commands::tags::get_auto_tag_settings,
+            commands::tags::save_auto_tag_settings,
+            commands::tags::auto_tag_assets,
+            // Custom Field Commands
+            commands::custom_fields::get_custom_fields,
+            commands::custom_fields::save_custom_field,
+            commands::custom_fields::delete_custom_field,
+            commands::custom_fields::get_custom_field_values,
+            commands::custom_fields::set_custom_field_value,
+            commands::custom_fields::search_by_custom_fields,
+            // Smart Collection Commands
+            commands::smart_collections::create_smart_collection,
+            commands::smart_collections::list_smart_collections,
+            commands::smart_collections::delete_smart_collection,
+            commands::smart_collections::evaluate_smart_collection,
+
+            // Recipe Commands
+            commands::recipes::list_recipes,
+            commands::recipes::get_recipe,
+            commands::recipes::save_recipe,
+            commands::recipes::delete_recipe,
+            commands::recipes::preview_recipe,
+
+            // Pipeline Commands
+            commands::pipelines::start_pipeline,
+            commands::pipelines::record_pipeline_step,
+            commands::pipelines::resume_pipeline,
+            commands::pipelines::get_pipeline_run,
+            commands::pipelines::list_pipeline_runs,
+            commands::pipelines::compare_runs,
+            // Face Commands
+            commands::faces::get_face_settings,
+            commands::faces::save_face_settings,
+            commands::faces::detect_faces,
+            commands::faces::group_assets_by_face,
+            // Safety Commands
+            commands::safety::get_safety_settings,
+            commands::safety::save_safety_settings,
+            commands::safety::scan_image_safety,
+            commands::safety::get_flagged_assets,
+            commands::safety::review_flagged_assets,
+            // Permission Commands
+            commands::permissions::get_permission_policy,
+            commands::permissions::save_permission_policy,
+            commands::permissions::get_pending_permission_requests,
+            commands::permissions::resolve_permission_request,
+            commands::permissions::check_automation_permission,
+            // Ignore Rule Commands
+            commands::ignore_rules::get_ignore_rules,
+            commands::ignore_rules::save_ignore_rules,
+            // Budget Commands
+            commands::budget::get_budget_settings,
+            commands::budget::save_budget_settings,
+            commands::budget::get_budget_status,
+            // Script Commands
+            commands::scripts::run_command_script,
+            // Template Commands
+            commands::templates::sync_templates_from_url,
+            // License Commands
+            commands::licenses::set_license,
+            commands::licenses::get_license,
+            commands::licenses::license_report,
+            // Stock Commands
+            commands::stock::search_stock,
+            commands::stock::import_stock_result,
+            // Connector Commands
+            commands::connectors::connect_remote_provider,
+            commands::connectors::disconnect_remote_provider,
+            commands::connectors::get_connected_providers,
+            commands::connectors::list_remote_files,
+            commands::connectors::import_remote_file,
+            // Tracker Export Commands
+            commands::trackers::export_to_notion,
+            commands::trackers::export_to_airtable,
+            // Share Commands
+            commands::share::share_to_webhook,
+            commands::share::create_share_bundle,
+            commands::share::list_share_bundles,
+            commands::share::delete_share_bundle,
+
+            // Model Commands
+            commands::models::list_installed_models,
+            commands::models::download_model,
+            commands::models::delete_model,
+            commands::models::get_model_disk_usage,
         ])
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                if window.label() == "main" {
+            match event {
+                tauri::WindowEvent::CloseRequested { .. } if window.label() == "main" => {
+                    window.state::<AppState>().sessions.stop();
                     window.app_handle().exit(0);
                 }
+                tauri::WindowEvent::Focused(focused) if window.label() == "main" => {
+                    window.state::<AppState>().sessions.set_focused(*focused);
+                }
+                _ => {}
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(move |_app, event| {
+            // Headless, there's no window whose closing ends the app
+            if let tauri::RunEvent::ExitRequested { api, code: None, .. } = event {
+                if headless {
+                    api.prevent_exit();
+                }
+            }
+        });
+}
+
+/// `--server`: open the requested project and say where clients connect.
+fn start_headless(app: &tauri::AppHandle, options: &launch::LaunchOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let state = app.state::<AppState>();
+    if let Some(project) = &options.project {
+        if !services::io_sqlite::is_sqlite_project(project) {
+            return Err(format!("Not a Synnia project: {}", project.display()).into());
+        }
+        let path = project.to_string_lossy().to_string();
+        *state.current_project_path.lock().map_err(|_| "Path lock poisoned")? = Some(path);
+    }
+
+    println!("Synnia server listening on http://{}:{}", options.host, state.server_port);
+    println!("  REST API: /api/v1   events: /events");
+    // Logs of a headless server tend to be kept and shared; the token only
+    // shows when asked for
+    if options.print_token {
+        let token = state.clip_token.lock().map(|t| t.clone()).unwrap_or_default();
+        println!("  token: {}", token);
+    } else {
+        let config_dir = app.path().app_config_dir()?;
+        println!("  token: \"clip_token\" in {} (or run with --print-token)", config_dir.join("config.json").display());
+    }
+    if options.is_public() {
+        println!("  Reachable from other machines; asset requests from them need the token too");
+    }
+    Ok(())
 }
\ No newline at end of file
@@ -22,6 +22,10 @@ pub struct SynniaProject {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[ts(type = "Record<string, any>")]
     pub settings: Option<HashMap<String, serde_json::Value>>,
+    
+    // Canvas (board) the graph and viewport belong to; required to save
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canvas_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -126,7 +130,7 @@ pub struct ArrayAssetConfig {
     #[ts(type = "any[]")]
     pub options: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub mode: Option<String>, // "single" | "multi"
+    pub mode: Option<String>, // "single" | "multi" | "grid" | "comparison" | "tasklist"
 }
 
 /// Unified Asset structure (Form-Centric Model)
@@ -205,7 +209,7 @@ pub struct Position {
     pub y: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct SynniaNodeData {
@@ -239,6 +243,20 @@ pub struct SynniaNodeData {
     // Product Node: Has Output Edge connection point
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_product_handle: Option<bool>,
+
+    // Planning: ISO 8601 dates and a free-form status ("todo", "in-progress", "done"...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    // Locking: locked nodes can't be moved, resized or deleted; pinned nodes can't be moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -275,6 +293,179 @@ pub struct AgentDefinition {
     pub is_system: bool,
 }
 
+// ========================================== 
+// Generation Tracking
+// ========================================== 
+
+/// Parameters used to produce a generated asset (seed, model, prompt...)
+/// Provider-specific settings go into `extra`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(type = "number | null")]
+    pub seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guidance: Option<f64>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    #[ts(type = "Record<string, any>")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Stored generation parameters for an asset, linked to the asset it varies
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationRecord {
+    pub asset_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_asset_id: Option<String>,
+    pub params: GenerationParams,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+// ========================================== 
+// Components (Reusable Subgraphs)
+// ========================================== 
+
+/// A saved subgraph that can be stamped into any project.
+/// Node positions are relative to the component's top-left corner.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub nodes: Vec<SynniaNode>,
+    pub edges: Vec<SynniaEdge>,
+    pub assets: Vec<Asset>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+// ========================================== 
+// Brand Kit
+// ========================================== 
+
+/// Per-project brand constraints, stored in project settings under `brandKit`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BrandKit {
+    /// Approved colors as hex ("#1a2b3c")
+    #[serde(default)]
+    pub colors: Vec<String>,
+    #[serde(default)]
+    pub fonts: Vec<String>,
+    #[serde(default)]
+    pub logo_asset_ids: Vec<String>,
+    /// Tone of voice guidelines (free text, passed to agents)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone: Option<String>,
+    /// Words and phrases that must not appear in copy
+    #[serde(default)]
+    pub banned_words: Vec<String>,
+    /// Max color distance (CIE76 ΔE) to count as on-brand; default 20
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_tolerance: Option<f64>,
+}
+
+// ========================================== 
+// Reminders
+// ========================================== 
+
+/// A scheduled desktop notification for a node
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub id: String,
+    pub node_id: String,
+    /// Unix timestamp (ms)
+    #[ts(type = "number")]
+    pub fire_at: i64,
+    pub message: String,
+    pub fired: bool,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+// ========================================== 
+// Viewport Bookmarks
+// ========================================== 
+
+/// A named camera position on the canvas
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportBookmark {
+    pub id: String,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub zoom: f64,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+// ========================================== 
+// Asset Tags
+// ========================================== 
+
+/// A searchable tag on an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTag {
+    pub asset_id: String,
+    /// Normalized: lowercase, single spaces
+    pub tag: String,
+    /// Who added it: "user", "interrogate", ...
+    pub source: String,
+    /// Model confidence (0-1) for machine tags
+    pub confidence: Option<f64>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+// ========================================== 
+// Canvases
+// ========================================== 
+
+/// A board within a project; all canvases share the project's assets
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Canvas {
+    pub id: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub sort_order: i64,
+    pub viewport: Viewport,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
 // ========================================== 
 // Tests & Binding Generation
 // ========================================== 
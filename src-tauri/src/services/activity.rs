@@ -0,0 +1,326 @@
+//! Per-project activity feed: a changelog of high-level events (assets
+//! created/edited, nodes added/removed, agent runs, imports) so returning
+//! users can see what changed since their last session.
+//!
+//! Repeated edits of the same asset by the same actor are coalesced into one
+//! entry within `EDIT_COALESCE_MS`, so autosave doesn't flood the feed.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{Asset, SynniaProject};
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Edits of one asset closer together than this share an entry
+pub const EDIT_COALESCE_MS: i64 = 10 * 60 * 1000;
+
+/// Default page size for `get_activity`
+const DEFAULT_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum ActivityKind {
+    AssetCreated,
+    AssetEdited,
+    NodeAdded,
+    NodeRemoved,
+    AgentRan,
+    Imported,
+    PermissionDecided,
+    InjectionSuspected,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::AssetCreated => "asset-created",
+            ActivityKind::AssetEdited => "asset-edited",
+            ActivityKind::NodeAdded => "node-added",
+            ActivityKind::NodeRemoved => "node-removed",
+            ActivityKind::AgentRan => "agent-ran",
+            ActivityKind::Imported => "imported",
+            ActivityKind::PermissionDecided => "permission-decided",
+            ActivityKind::InjectionSuspected => "injection-suspected",
+        }
+    }
+}
+
+/// An event to record
+#[derive(Debug, Clone)]
+pub struct NewActivity {
+    pub kind: ActivityKind,
+    /// Who caused it: "user", "ai", "agent", "clip", ...
+    pub actor: String,
+    /// Finer origin, e.g. the agent name
+    pub source: Option<String>,
+    /// Node or asset the event is about
+    pub target_id: Option<String>,
+    pub summary: String,
+}
+
+impl NewActivity {
+    pub fn new(kind: ActivityKind, actor: &str, summary: impl Into<String>) -> Self {
+        Self { kind, actor: actor.to_string(), source: None, target_id: None, summary: summary.into() }
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn target(mut self, target_id: impl Into<String>) -> Self {
+        self.target_id = Some(target_id.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub kind: String,
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    pub summary: String,
+    /// Unix timestamp (ms)
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityFilter {
+    /// Only these kinds; empty = all
+    #[serde(default)]
+    pub kinds: Vec<ActivityKind>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub target_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Node IDs and asset hashes before a project save, to diff against after
+#[derive(Debug, Clone, Default)]
+pub struct GraphSnapshot {
+    node_ids: HashSet<String>,
+    asset_hashes: HashMap<String, String>,
+}
+
+impl GraphSnapshot {
+    pub fn capture(conn: &Connection) -> Result<Self, AppError> {
+        let node_ids = query_pairs(conn, "SELECT id, id FROM nodes")?.into_keys().collect();
+        let asset_hashes = query_pairs(conn, "SELECT id, value_hash FROM assets")?;
+        Ok(Self { node_ids, asset_hashes })
+    }
+}
+
+/// Append an event, coalescing repeated asset edits.
+pub fn record(conn: &Connection, activity: &NewActivity) -> Result<(), AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    
+    if activity.kind == ActivityKind::AssetEdited {
+        let updated = conn.execute(
+            "UPDATE activity SET created_at = ?1, summary = ?2
+             WHERE id = (SELECT id FROM activity
+                         WHERE kind = ?3 AND actor = ?4 AND target_id IS ?5 AND created_at >= ?6
+                         ORDER BY created_at DESC LIMIT 1)",
+            params![now, activity.summary, activity.kind.as_str(), activity.actor, activity.target_id, now - EDIT_COALESCE_MS],
+        ).map_err(|e| AppError::Io(format!("Failed to update activity: {}", e)))?;
+        if updated > 0 {
+            return Ok(());
+        }
+    }
+    
+    conn.execute(
+        "INSERT INTO activity (kind, actor, source, target_id, summary, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![activity.kind.as_str(), activity.actor, activity.source, activity.target_id, activity.summary, now],
+    ).map_err(|e| AppError::Io(format!("Failed to record activity: {}", e)))?;
+    
+    Ok(())
+}
+
+/// Record an event in the project at `project_root`. Failures are logged, not
+/// returned, so the feed never breaks the operation it describes.
+pub fn log(project_root: &Path, activity: NewActivity) {
+    let result = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+        .and_then(|conn| record(&conn, &activity));
+    if let Err(e) = result {
+        println!("Failed to record activity: {}", e);
+    }
+}
+
+/// Record node and asset changes made by a project save.
+pub fn record_project_changes(conn: &Connection, before: &GraphSnapshot, project: &SynniaProject) -> Result<(), AppError> {
+    let after = GraphSnapshot::capture(conn)?;
+    
+    for node in &project.graph.nodes {
+        if !before.node_ids.contains(&node.id) {
+            let title = display_name(&node.data.title, &node.type_);
+            record(conn, &NewActivity::new(ActivityKind::NodeAdded, "user", format!("Added {}", title)).target(&node.id))?;
+        }
+    }
+    let mut removed: Vec<&String> = before.node_ids.difference(&after.node_ids).collect();
+    removed.sort();
+    for node_id in removed {
+        record(conn, &NewActivity::new(ActivityKind::NodeRemoved, "user", "Removed node").target(node_id))?;
+    }
+    
+    let mut asset_ids: Vec<&String> = project.assets.keys().collect();
+    asset_ids.sort();
+    for asset_id in asset_ids {
+        let created = match (before.asset_hashes.get(asset_id), after.asset_hashes.get(asset_id)) {
+            (None, _) => true,
+            (Some(old), Some(new)) if old != new => false,
+            _ => continue,
+        };
+        record(conn, &asset_change(&project.assets[asset_id], created))?;
+    }
+    
+    Ok(())
+}
+
+/// Asset created/edited event, attributed to the asset's `sys.source`.
+pub fn asset_change(asset: &Asset, created: bool) -> NewActivity {
+    let name = display_name(&asset.sys.name, "asset");
+    let (kind, summary) = if created {
+        (ActivityKind::AssetCreated, format!("Created {}", name))
+    } else {
+        (ActivityKind::AssetEdited, format!("Edited {}", name))
+    };
+    NewActivity::new(kind, &asset.sys.source, summary).target(&asset.id)
+}
+
+/// Events newest first, optionally only those after `since` (ms).
+pub fn get_activity(conn: &Connection, since: Option<i64>, filter: &ActivityFilter) -> Result<Vec<ActivityEntry>, AppError> {
+    let kinds = serde_json::to_string(&filter.kinds.iter().map(|k| k.as_str()).collect::<Vec<_>>())?;
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT) as i64;
+    
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, actor, source, target_id, summary, created_at FROM activity
+         WHERE (?1 IS NULL OR created_at > ?1)
+           AND (?2 = '[]' OR kind IN (SELECT value FROM json_each(?2)))
+           AND (?3 IS NULL OR actor = ?3)
+           AND (?4 IS NULL OR target_id = ?4)
+         ORDER BY created_at DESC, id DESC
+         LIMIT ?5"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params![since, kinds, filter.actor, filter.target_id, limit], |row| {
+        Ok(ActivityEntry {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            actor: row.get(2)?,
+            source: row.get(3)?,
+            target_id: row.get(4)?,
+            summary: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query activity: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load activity: {}", e)))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn query_pairs(conn: &Connection, sql: &str) -> Result<HashMap<String, String>, AppError> {
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| AppError::Io(format!("Failed to query snapshot: {}", e)))?;
+    
+    rows.collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load snapshot: {}", e)))
+}
+
+fn display_name(name: &str, fallback: &str) -> String {
+    match name.trim() {
+        "" => fallback.to_string(),
+        name => format!("\"{}\"", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+    use crate::services::io_sqlite::{init_project_sqlite, save_project_sqlite};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn text_asset(id: &str, text: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "content": text }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: "Script".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    fn text_node(id: &str, asset_id: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "text-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: "Script".to_string(),
+                asset_id: Some(asset_id.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_project_save_records_changes() {
+        let dir = tempdir().unwrap();
+        let mut project = init_project_sqlite(dir.path(), "Feed").unwrap();
+        project.assets.insert("a".to_string(), text_asset("a", "draft"));
+        project.graph.nodes.push(text_node("n", "a"));
+        save_project_sqlite(dir.path(), &project).unwrap();
+        
+        // Two edits coalesce into one entry
+        for text in ["second draft", "final"] {
+            project.assets.insert("a".to_string(), text_asset("a", text));
+            save_project_sqlite(dir.path(), &project).unwrap();
+        }
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let kinds: Vec<String> = get_activity(&conn, None, &ActivityFilter::default()).unwrap()
+            .into_iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec!["asset-edited", "asset-created", "node-added"]);
+        
+        let filter = ActivityFilter { kinds: vec![ActivityKind::NodeAdded], ..Default::default() };
+        let added = get_activity(&conn, None, &filter).unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].summary, "Added \"Script\"");
+        assert!(get_activity(&conn, Some(added[0].created_at + EDIT_COALESCE_MS), &filter).unwrap().is_empty());
+    }
+}
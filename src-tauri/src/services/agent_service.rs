@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use crate::services::prompt_guard;
+use crate::services::net_guard::NetworkTimeouts;
+use ts_rs::TS;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
 #[serde(tag = "action", content = "params")]
 pub enum GraphAction {
     #[serde(rename = "create_node")]
@@ -53,21 +57,10 @@ fn render_template(template: &str, inputs: &Value) -> String {
     result
 }
 
-/// Call Gemini with dynamic agent configuration
-pub async fn call_gemini_agent(
-    api_key: &str, 
-    base_url: &str,
-    model_name: &str,
-    agent_system_prompt: &str, 
-    inputs: Value,             
-    context_nodes: String      
-) -> Result<Vec<GraphAction>, String> {
-    
-    // 1. Render the Agent's Prompt
-    let rendered_persona = render_template(agent_system_prompt, &inputs);
-
-    // 2. Construct the MASTER System Instruction
-    let master_system_instruction = format!(r#" 
+/// System instruction of an agent run that answers with graph actions
+pub fn actions_instruction(agent_system_prompt: &str, inputs: &Value) -> String {
+    let rendered_persona = render_template(agent_system_prompt, inputs);
+    format!(r#" 
     You are an AI Agent within the Synnia creative environment.
     
     YOUR CORE INSTRUCTION (PERSONA):
@@ -81,15 +74,51 @@ pub async fn call_gemini_agent(
     OUTPUT RULES:
     - OUTPUT ONLY RAW JSON. No markdown blocks. No prose before/after.
     - STRICTLY follow the action schema.
+    - {}
     
     Example Output:
     [
       {{ "action": "message", "params": {{ "text": "Here are three concepts based on your request." }} }},
       {{ "action": "create_node", "params": {{ "type": "Text", "label": "Concept A", "description": "..." }} }}
     ]
-    "#, rendered_persona);
+    "#, rendered_persona, prompt_guard::SYSTEM_RULE)
+}
+
+/// System instruction of an agent run that streams plain text
+pub fn text_instruction(agent_system_prompt: &str, inputs: &Value) -> String {
+    let rendered_persona = render_template(agent_system_prompt, inputs);
+    format!(r#"
+    You are an AI Agent within the Synnia creative environment.
+
+    YOUR CORE INSTRUCTION (PERSONA):
+    {}
+
+    OUTPUT RULES:
+    - Respond with the content only. No JSON, no preamble, no closing remarks.
+    - {}
+    "#, rendered_persona, prompt_guard::SYSTEM_RULE)
+}
 
-    // 3. Clean base url
+/// User message of an agent run around its context
+pub fn user_message(context_nodes: &str) -> String {
+    format!("Context:\n{}\n\nExecute your task.", context_nodes)
+}
+
+/// Call Gemini with dynamic agent configuration
+pub async fn call_gemini_agent(
+    api_key: &str, 
+    base_url: &str,
+    model_name: &str,
+    agent_system_prompt: &str, 
+    inputs: Value,             
+    context_nodes: String,
+    timeouts: &NetworkTimeouts,
+) -> Result<Vec<GraphAction>, String> {
+    
+    // 1. Render the Agent's Prompt into the MASTER System Instruction
+    let master_system_instruction = actions_instruction(agent_system_prompt, &inputs);
+
+    // 2. Clean base url
     let clean_base = base_url.trim_end_matches('/');
     let url = format!(
         "{}/v1beta/models/{}:generateContent?key={}",
@@ -98,8 +127,8 @@ pub async fn call_gemini_agent(
         api_key
     );
 
-    // 4. Construct Body
-    let full_user_message = format!("Context:\n{}\n\nExecute your task.", context_nodes);
+    // 3. Construct Body
+    let full_user_message = user_message(&context_nodes);
 
     // JSON macro uses standard JSON syntax, NO escaping needed for braces unless inside string literals
     let payload = json!({
@@ -116,13 +145,21 @@ pub async fn call_gemini_agent(
         }
     });
 
-    // 5. Network Call
-    let client = reqwest::Client::new();
+    // 4. Network Call
+    let client = reqwest::Client::builder()
+        .connect_timeout(timeouts.connect())
+        .timeout(timeouts.request())
+        .build()
+        .map_err(|e| format!("Network error: {}", e))?;
     let res = client.post(url)
         .json(&payload)
         .send()
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(|e| if e.is_timeout() {
+            format!("Request timed out after {}s", timeouts.request().as_secs())
+        } else {
+            format!("Network error: {}", e)
+        })?;
 
     if !res.status().is_success() {
         return Err(format!("API Error: {}", res.text().await.unwrap_or_default()));
@@ -146,3 +183,94 @@ pub async fn call_gemini_agent(
 
     Ok(actions)
 }
+
+/// Call Gemini with the agent's persona and stream plain text back through
+/// `on_text` as it is generated. Returns the full text.
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_gemini_text(
+    api_key: &str,
+    base_url: &str,
+    model_name: &str,
+    agent_system_prompt: &str,
+    inputs: Value,
+    context_nodes: String,
+    timeouts: &NetworkTimeouts,
+    mut on_text: impl FnMut(&str) -> Result<(), String>,
+) -> Result<String, String> {
+    let system_instruction = text_instruction(agent_system_prompt, &inputs);
+
+    let clean_base = base_url.trim_end_matches('/');
+    let url = format!(
+        "{}/v1beta/models/{}:streamGenerateContent?alt=sse&key={}",
+        clean_base,
+        model_name,
+        api_key
+    );
+
+    let full_user_message = user_message(&context_nodes);
+    let payload = json!({
+        "contents": [{
+            "role": "user",
+            "parts": [{ "text": full_user_message }]
+        }],
+        "systemInstruction": {
+            "parts": [{ "text": system_instruction }]
+        },
+        "generationConfig": {
+            "temperature": 0.7
+        }
+    });
+
+    // No total timeout: a long answer keeps streaming, silence does not
+    let client = reqwest::Client::builder()
+        .connect_timeout(timeouts.connect())
+        .read_timeout(timeouts.idle())
+        .build()
+        .map_err(|e| format!("Network error: {}", e))?;
+    let mut res = client.post(url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !res.status().is_success() {
+        return Err(format!("API Error: {}", res.text().await.unwrap_or_default()));
+    }
+
+    // Server-sent events: one `data: {json}` line per chunk
+    let mut full_text = String::new();
+    let mut pending = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| format!("Network error: {}", e))? {
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let text = sse_text(&String::from_utf8_lossy(&line));
+            if !text.is_empty() {
+                on_text(&text)?;
+                full_text.push_str(&text);
+            }
+        }
+    }
+    let text = sse_text(&String::from_utf8_lossy(&pending));
+    if !text.is_empty() {
+        on_text(&text)?;
+        full_text.push_str(&text);
+    }
+
+    if full_text.trim().is_empty() {
+        return Err("No content generated".to_string());
+    }
+    Ok(full_text)
+}
+
+/// Text carried by one server-sent event line (empty for anything else)
+fn sse_text(line: &str) -> String {
+    let Some(data) = line.trim().strip_prefix("data:") else {
+        return String::new();
+    };
+    serde_json::from_str::<GeminiResponse>(data.trim()).ok()
+        .and_then(|r| r.candidates)
+        .and_then(|c| c.into_iter().next())
+        .map(|c| c.content.parts.into_iter().map(|p| p.text).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,357 @@
+//! Asset renaming, optionally carried through to the file on disk.
+//!
+//! Renaming the file gives it a readable name from the asset name plus a
+//! short content hash (`hero-shot-3f9a12c4.png`), so equal names never
+//! collide. Thumbnails, medium tiers, proxies and animated previews follow
+//! their original, and every stored path is rewritten: asset values and
+//! metadata (also of other assets pointing at the file), value history,
+//! file history, faces, safety reviews and links. Node titles that showed
+//! the old name take the new one.
+//!
+//! Files are moved first and the database is updated in one transaction;
+//! if that fails the files are moved back.
+
+use std::fs;
+use std::path::Path;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::hash::{compute_content_hash, compute_file_hash};
+use crate::services::io_sqlite;
+use ts_rs::TS;
+
+const MAX_NAME_LEN: usize = 200;
+/// Longest file stem taken from the name, before the hash
+const MAX_STEM_LEN: usize = 60;
+const HASH_SUFFIX_LEN: usize = 8;
+/// Derived files named `<prefix><stem>.<ext>` next to the original
+const DERIVED_PREFIXES: &[&str] = &["thumb_", "medium_", "proxy_", "preview_"];
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedAsset {
+    pub asset: Asset,
+    /// New path of the asset's file, relative to the project root, if it moved
+    pub src: Option<String>,
+    /// Files moved, derivatives included
+    pub files: usize,
+    /// Nodes whose title changed with the name
+    pub node_ids: Vec<String>,
+    /// Other assets whose values pointed at the moved files
+    pub updated_asset_ids: Vec<String>,
+}
+
+/// Rename an asset and, with `rename_file`, its file and derivatives.
+pub fn rename_asset(
+    conn: &mut Connection,
+    project_root: &Path,
+    asset_id: &str,
+    new_name: &str,
+    rename_file: bool,
+) -> Result<RenamedAsset, AppError> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::InvalidInput(format!("Names need 1 to {} characters", MAX_NAME_LEN)));
+    }
+    let mut asset = io_sqlite::get_asset(conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+
+    let moves = if rename_file { plan_moves(project_root, &asset, new_name)? } else { Vec::new() };
+    let mut moved: Vec<&(String, String)> = Vec::with_capacity(moves.len());
+    for pair in &moves {
+        if let Err(e) = fs::rename(project_root.join(&pair.0), project_root.join(&pair.1)) {
+            undo_moves(project_root, &moved);
+            return Err(AppError::Io(format!("Failed to rename {}: {}", pair.0, e)));
+        }
+        moved.push(pair);
+    }
+
+    let old_name = std::mem::replace(&mut asset.sys.name, new_name.to_string());
+    asset.sys.updated_at = chrono::Utc::now().timestamp_millis();
+    match update_database(conn, &mut asset, &old_name, &moves) {
+        Ok((node_ids, updated_asset_ids)) => Ok(RenamedAsset {
+            asset,
+            src: moves.first().map(|(_, to)| to.clone()),
+            files: moves.len(),
+            node_ids,
+            updated_asset_ids,
+        }),
+        Err(e) => {
+            undo_moves(project_root, &moved);
+            Err(e)
+        }
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// `(from, to)` paths relative to the project root, the asset's file first.
+/// Empty if the file already has the name.
+fn plan_moves(project_root: &Path, asset: &Asset, new_name: &str) -> Result<Vec<(String, String)>, AppError> {
+    let src = asset.value.get("src").or(Some(&asset.value))
+        .and_then(|s| s.as_str())
+        .filter(|s| s.starts_with("assets/"))
+        .ok_or_else(|| AppError::InvalidInput("Asset has no file in the project to rename".to_string()))?;
+    let path = project_root.join(src);
+    let hash = compute_file_hash(&path)
+        .map_err(|e| AppError::NotFound(format!("Failed to read {}: {}", src, e)))?;
+
+    let (dir, filename) = src.rsplit_once('/').unwrap_or(("assets", src));
+    let (old_stem, ext) = match filename.rsplit_once('.') {
+        Some((stem, ext)) => (stem, format!(".{}", ext)),
+        None => (filename, String::new()),
+    };
+    let base = format!("{}-{}", slug(new_name), &hash[..HASH_SUFFIX_LEN]);
+    if old_stem == base {
+        return Ok(Vec::new());
+    }
+    let mut new_stem = base.clone();
+    let mut n = 2;
+    while project_root.join(format!("{}/{}{}", dir, new_stem, ext)).exists() {
+        new_stem = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let mut moves = vec![(src.to_string(), format!("{}/{}{}", dir, new_stem, ext))];
+    if let Ok(entries) = fs::read_dir(project_root.join(dir)) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            for prefix in DERIVED_PREFIXES {
+                let Some(rest) = name.strip_prefix(prefix).and_then(|r| r.strip_prefix(old_stem)) else { continue };
+                if rest.starts_with('.') {
+                    moves.push((format!("{}/{}", dir, name), format!("{}/{}{}{}", dir, prefix, new_stem, rest)));
+                }
+            }
+        }
+    }
+    Ok(moves)
+}
+
+fn undo_moves(project_root: &Path, moved: &[&(String, String)]) {
+    for (from, to) in moved.iter().rev() {
+        if let Err(e) = fs::rename(project_root.join(to), project_root.join(from)) {
+            println!("[AssetRename] Failed to move {} back: {}", to, e);
+        }
+    }
+}
+
+/// Rename the asset and rewrite moved paths everywhere, in one transaction.
+/// Returns the retitled nodes and the other assets that were rewritten.
+fn update_database(
+    conn: &mut Connection,
+    asset: &mut Asset,
+    old_name: &str,
+    moves: &[(String, String)],
+) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
+
+    let mut updated_asset_ids = Vec::new();
+    if !moves.is_empty() {
+        for id in assets_mentioning(&tx, moves)? {
+            let (value_json, value_meta_json): (String, Option<String>) = tx.query_row(
+                "SELECT value_json, value_meta_json FROM assets WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).map_err(|e| AppError::Io(format!("Failed to load asset: {}", e)))?;
+            let value_json = replace_paths(&value_json, moves);
+            tx.execute(
+                "UPDATE assets SET value_json = ?1, value_hash = ?2, value_meta_json = ?3 WHERE id = ?4",
+                params![
+                    value_json,
+                    compute_content_hash(&value_json),
+                    value_meta_json.map(|m| replace_paths(&m, moves)),
+                    id,
+                ],
+            ).map_err(|e| AppError::Io(format!("Failed to update asset paths: {}", e)))?;
+            if id == asset.id {
+                asset.value = serde_json::from_str(&value_json)?;
+            } else {
+                updated_asset_ids.push(id);
+            }
+        }
+        if let Some(meta) = &asset.value_meta {
+            asset.value_meta = Some(serde_json::from_str(&replace_paths(&meta.to_string(), moves))?);
+        }
+
+        // Older versions keep pointing at the file under its new name; versions
+        // that only differed in the path collapse into one
+        let mut stmt = tx.prepare("SELECT id, content_json FROM asset_history")
+            .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+        let history: Vec<(i64, String)> = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| AppError::Io(format!("Failed to load history: {}", e)))?;
+        drop(stmt);
+        for (id, content_json) in history {
+            let replaced = replace_paths(&content_json, moves);
+            if replaced != content_json {
+                tx.execute(
+                    "UPDATE OR REPLACE asset_history SET content_json = ?1, content_hash = ?2 WHERE id = ?3",
+                    params![replaced, compute_content_hash(&replaced), id],
+                ).map_err(|e| AppError::Io(format!("Failed to update history: {}", e)))?;
+            }
+        }
+
+        for (from, to) in moves {
+            for sql in [
+                "UPDATE file_history SET src = ?2 WHERE src = ?1",
+                "UPDATE image_faces SET src = ?2 WHERE src = ?1",
+                "UPDATE image_reviews SET src = ?2 WHERE src = ?1",
+                "UPDATE linked_files SET src = ?2 WHERE src = ?1",
+            ] {
+                tx.execute(sql, params![from, to])
+                    .map_err(|e| AppError::Io(format!("Failed to update file records: {}", e)))?;
+            }
+        }
+    }
+
+    tx.execute(
+        "UPDATE assets SET sys_json = ?1, updated_at = ?2 WHERE id = ?3",
+        params![serde_json::to_string(&asset.sys)?, asset.sys.updated_at, asset.id],
+    ).map_err(|e| AppError::Io(format!("Failed to rename asset: {}", e)))?;
+
+    let mut node_ids = Vec::new();
+    for mut node in io_sqlite::get_nodes_by_asset(&tx, &asset.id)? {
+        if node.data.title == old_name {
+            node.data.title = asset.sys.name.clone();
+            io_sqlite::upsert_node(&tx, &node)?;
+            node_ids.push(node.id);
+        }
+    }
+
+    activity::record(&tx, &NewActivity::new(
+        ActivityKind::AssetEdited,
+        "user",
+        format!("Renamed {} to {}", old_name, asset.sys.name),
+    ).target(&asset.id))?;
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+    Ok((node_ids, updated_asset_ids))
+}
+
+/// IDs of assets whose value or metadata mention any moved path
+fn assets_mentioning(conn: &Connection, moves: &[(String, String)]) -> Result<Vec<String>, AppError> {
+    let paths = serde_json::to_string(&moves.iter().map(|(from, _)| from).collect::<Vec<_>>())?;
+    let mut stmt = conn.prepare(
+        "SELECT a.id FROM assets a WHERE EXISTS (
+             SELECT 1 FROM json_each(?1) p
+             WHERE instr(a.value_json, p.value) > 0 OR instr(COALESCE(a.value_meta_json, ''), p.value) > 0
+         ) ORDER BY a.id"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![paths], |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to find asset paths: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to find asset paths: {}", e)))
+}
+
+fn replace_paths(text: &str, moves: &[(String, String)]) -> String {
+    moves.iter().fold(text.to_string(), |text, (from, to)| text.replace(from.as_str(), to))
+}
+
+/// "Hero Shot (v2)!" -> "hero-shot-v2"
+fn slug(name: &str) -> String {
+    let slug = name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(MAX_STEM_LEN).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "asset".to_string() } else { slug.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn node(id: &str, title: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: title.to_string(),
+                asset_id: Some("img".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_rename_with_file() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("assets")).unwrap();
+        for name in ["1b2c.png", "thumb_1b2c.jpg", "medium_1b2c.png", "thumb_1b2c0.jpg"] {
+            fs::write(root.join("assets").join(name), name).unwrap();
+        }
+        let mut conn = database::init_db(&io_sqlite::get_db_path(root)).unwrap();
+        let sys = |name: &str| serde_json::json!({ "name": name, "createdAt": 0, "updatedAt": 0, "source": "user" }).to_string();
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, value_meta_json, sys_json, updated_at)
+             VALUES ('img', '\"record\"', 'h', '{\"src\":\"assets/1b2c.png\"}', '{\"preview\":\"assets/thumb_1b2c.jpg\"}', ?1, 0),
+                    ('note', '\"record\"', 'h', '{\"content\":\"![](assets/1b2c.png)\"}', NULL, ?2, 0)",
+            params![sys("IMG_0042"), sys("Notes")],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO asset_history (asset_id, content_hash, content_json, created_at)
+             VALUES ('img', 'old', '{\"src\":\"assets/1b2c.png\",\"width\":1}', 0)",
+            [],
+        ).unwrap();
+        conn.execute("INSERT INTO image_reviews (src, score, status, created_at) VALUES ('assets/1b2c.png', 0.1, 'ok', 0)", []).unwrap();
+        io_sqlite::upsert_node(&conn, &node("original", "IMG_0042")).unwrap();
+        io_sqlite::upsert_node(&conn, &node("captioned", "My caption")).unwrap();
+
+        let renamed = rename_asset(&mut conn, root, "img", " Hero Shot (v2) ", true).unwrap();
+        let hash = &compute_file_hash(&root.join(renamed.src.as_ref().unwrap())).unwrap()[..HASH_SUFFIX_LEN];
+        let src = format!("assets/hero-shot-v2-{}.png", hash);
+        assert_eq!(renamed.src.as_deref(), Some(src.as_str()));
+        assert_eq!(renamed.files, 3, "the original, its thumbnail and medium tier");
+        assert_eq!(renamed.asset.sys.name, "Hero Shot (v2)");
+        assert_eq!(renamed.asset.value["src"], src.as_str());
+        assert_eq!(renamed.node_ids, ["original"]);
+        assert_eq!(renamed.updated_asset_ids, ["note"]);
+        assert!(root.join(format!("assets/thumb_hero-shot-v2-{}.jpg", hash)).exists());
+        assert!(root.join("assets/thumb_1b2c0.jpg").exists(), "other files with a similar stem stay");
+        assert!(!root.join("assets/1b2c.png").exists());
+
+        let stored = io_sqlite::get_asset(&conn, "img").unwrap().unwrap();
+        assert_eq!(stored.value, renamed.asset.value);
+        let history: String = conn.query_row("SELECT content_json FROM asset_history", [], |row| row.get(0)).unwrap();
+        assert!(history.contains(&src));
+        let review: String = conn.query_row("SELECT src FROM image_reviews", [], |row| row.get(0)).unwrap();
+        assert_eq!(review, src);
+        let note = io_sqlite::get_asset(&conn, "note").unwrap().unwrap();
+        assert_eq!(note.value["content"], format!("![]({})", src));
+        assert_eq!(io_sqlite::get_node(&conn, "captioned").unwrap().unwrap().data.title, "My caption");
+
+        // Renaming to the same name keeps the file where it is
+        let again = rename_asset(&mut conn, root, "img", "Hero Shot (v2)", true).unwrap();
+        assert_eq!((again.src, again.files), (None, 0));
+
+        // Name only
+        let name_only = rename_asset(&mut conn, root, "note", "Shot list", false).unwrap();
+        assert_eq!(name_only.asset.sys.name, "Shot list");
+        assert!(matches!(rename_asset(&mut conn, root, "note", "Shot list", true), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Hero Shot (v2)!"), "hero-shot-v2");
+        assert_eq!(slug("Ünïcode  café"), "ünïcode-café");
+        assert_eq!(slug("???"), "asset");
+    }
+}
@@ -0,0 +1,166 @@
+//! Streaming generated text into a text asset while it is produced.
+//!
+//! Partial output is written to the asset every `flush_tokens` (estimated)
+//! tokens without touching history; `finish` puts the original value back
+//! for a moment and saves the final text through the normal history path,
+//! so the whole run is one undoable version.
+
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Default tokens between partial writes
+pub const DEFAULT_FLUSH_TOKENS: usize = 32;
+
+/// Payload of `asset:streaming` events
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamUpdate {
+    pub asset_id: String,
+    pub content: String,
+    pub done: bool,
+}
+
+/// An in-progress stream into a text asset
+pub struct AssetStream {
+    project_root: PathBuf,
+    conn: Connection,
+    asset: Asset,
+    original: Value,
+    /// Text kept in front of the output (append mode)
+    prefix: String,
+    output: String,
+    unflushed_tokens: usize,
+    flush_tokens: usize,
+}
+
+impl AssetStream {
+    /// Start streaming into the text asset `asset_id`, replacing its text or
+    /// appending to it.
+    pub fn begin(project_root: &Path, asset_id: &str, append: bool, flush_tokens: usize) -> Result<Self, AppError> {
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        let asset = io_sqlite::get_asset(&conn, asset_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+        let Value::String(text) = &asset.value else {
+            return Err(AppError::Unknown(format!("Asset {} is not a text asset", asset_id)));
+        };
+        let prefix = match text.trim_end() {
+            existing if append && !existing.is_empty() => format!("{}\n\n", existing),
+            _ => String::new(),
+        };
+        
+        Ok(Self {
+            project_root: project_root.to_path_buf(),
+            conn,
+            original: asset.value.clone(),
+            asset,
+            prefix,
+            output: String::new(),
+            unflushed_tokens: 0,
+            flush_tokens: flush_tokens.max(1),
+        })
+    }
+
+    /// Add generated text. Returns an update when enough tokens piled up
+    /// and were written to the asset.
+    pub fn push(&mut self, text: &str) -> Result<Option<StreamUpdate>, AppError> {
+        if text.is_empty() {
+            return Ok(None);
+        }
+        self.output.push_str(text);
+        self.unflushed_tokens += estimate_tokens(text);
+        if self.unflushed_tokens < self.flush_tokens {
+            return Ok(None);
+        }
+        self.unflushed_tokens = 0;
+        
+        let content = self.content();
+        self.write_value(&Value::String(content.clone()))?;
+        Ok(Some(StreamUpdate { asset_id: self.asset.id.clone(), content, done: false }))
+    }
+
+    /// Save what was generated as one history version. Without any output
+    /// the asset is left as it was.
+    pub fn finish(mut self) -> Result<(Asset, StreamUpdate), AppError> {
+        self.write_value(&self.original.clone())?;
+        if !self.output.trim().is_empty() {
+            self.asset.value = Value::String(self.content());
+            self.asset.sys.updated_at = chrono::Utc::now().timestamp_millis();
+            io_sqlite::save_asset_with_history(&self.project_root, &self.asset)?;
+        }
+        let update = StreamUpdate {
+            asset_id: self.asset.id.clone(),
+            content: self.asset.value.as_str().unwrap_or_default().to_string(),
+            done: true,
+        };
+        Ok((self.asset, update))
+    }
+
+    fn content(&self) -> String {
+        format!("{}{}", self.prefix, self.output)
+    }
+
+    /// Overwrite the stored value in place (no history, hash untouched)
+    fn write_value(&self, value: &Value) -> Result<(), AppError> {
+        self.conn.execute(
+            "UPDATE assets SET value_json = ?1, updated_at = ?2 WHERE id = ?3",
+            params![serde_json::to_string(value)?, chrono::Utc::now().timestamp_millis(), self.asset.id],
+        ).map_err(|e| AppError::Io(format!("Failed to write streamed text: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Rough token count (~4 characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use crate::services::history;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_stream_into_asset() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Notes").unwrap();
+        project.assets.insert("note".to_string(), Asset {
+            id: "note".to_string(),
+            value_type: ValueType::Record,
+            value: Value::String("Draft".to_string()),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Note".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        });
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let mut stream = AssetStream::begin(root, "note", true, 4).unwrap();
+        assert!(stream.push("Once ").unwrap().is_none());
+        let update = stream.push("upon a time, ").unwrap().unwrap();
+        assert_eq!(update.content, "Draft\n\nOnce upon a time, ");
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let stored = io_sqlite::get_asset(&conn, "note").unwrap().unwrap();
+        assert_eq!(stored.value, Value::String("Draft\n\nOnce upon a time, ".to_string()));
+        
+        stream.push("the end.").unwrap();
+        let (asset, update) = stream.finish().unwrap();
+        assert!(update.done);
+        assert_eq!(asset.value, Value::String("Draft\n\nOnce upon a time, the end.".to_string()));
+        
+        // One snapshot: the text from before the run
+        let versions = history::get_asset_history(&conn, "note", Some(10)).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].content_json, "\"Draft\"");
+    }
+}
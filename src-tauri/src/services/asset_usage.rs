@@ -0,0 +1,249 @@
+//! Asset usage report for the cleanup UI.
+//!
+//! Cross-references every asset against the nodes showing it (on any
+//! canvas) and its uses as a recipe input (edges into recipe nodes and
+//! generation records naming it as parent), and sums the size of the files
+//! it keeps in `assets/`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use rusqlite::Connection;
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::{compaction, io_sqlite, text_asset};
+use ts_rs::TS;
+
+/// Number of assets listed in `most_referenced`
+const TOP_N: usize = 10;
+
+/// Reference count buckets: (label, min, max inclusive)
+const BUCKETS: &[(&str, usize, usize)] = &[
+    ("unused", 0, 0),
+    ("1", 1, 1),
+    ("2-4", 2, 4),
+    ("5+", 5, usize::MAX),
+];
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetUsageReport {
+    /// All assets, most referenced first
+    pub assets: Vec<AssetUsage>,
+    /// Assets no node shows and no recipe uses
+    pub unused: Vec<String>,
+    pub most_referenced: Vec<String>,
+    pub buckets: Vec<UsageBucket>,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetUsage {
+    pub asset_id: String,
+    pub name: String,
+    /// "image", "text" or "record"
+    pub kind: String,
+    /// Nodes showing the asset (originals and references)
+    pub nodes: usize,
+    /// Uses as a recipe input
+    pub recipe_inputs: usize,
+    /// Size of the files under `assets/` the asset points at
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+impl AssetUsage {
+    pub fn references(&self) -> usize {
+        self.nodes + self.recipe_inputs
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    pub label: String,
+    pub assets: usize,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+pub fn get_asset_usage_report(conn: &Connection, project_root: &Path) -> Result<AssetUsageReport, AppError> {
+    let node_counts = count_by_asset(
+        conn,
+        "SELECT json_extract(data_json, '$.assetId'), COUNT(*) FROM nodes
+         WHERE json_extract(data_json, '$.assetId') IS NOT NULL GROUP BY 1",
+    )?;
+    let edge_inputs = count_by_asset(
+        conn,
+        "SELECT json_extract(s.data_json, '$.assetId'), COUNT(*) FROM edges e
+         JOIN nodes s ON s.id = e.source
+         JOIN nodes t ON t.id = e.target
+         WHERE json_extract(s.data_json, '$.assetId') IS NOT NULL
+           AND (t.type = 'recipe-node' OR json_extract(t.data_json, '$.recipeId') IS NOT NULL)
+         GROUP BY 1",
+    )?;
+    let generation_inputs = count_by_asset(
+        conn,
+        "SELECT parent_asset_id, COUNT(*) FROM generation_records WHERE parent_asset_id IS NOT NULL GROUP BY 1",
+    )?;
+    
+    let assets_dir = project_root.join("assets");
+    let mut assets: Vec<AssetUsage> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .map(|asset| AssetUsage {
+            nodes: node_counts.get(&asset.id).copied().unwrap_or(0),
+            recipe_inputs: edge_inputs.get(&asset.id).copied().unwrap_or(0)
+                + generation_inputs.get(&asset.id).copied().unwrap_or(0),
+            bytes: file_size(&assets_dir, &asset),
+            kind: kind(&asset).to_string(),
+            name: asset.sys.name.clone(),
+            asset_id: asset.id,
+        })
+        .collect();
+    assets.sort_by(|a, b| b.references().cmp(&a.references()).then_with(|| a.name.cmp(&b.name)));
+    
+    let buckets = BUCKETS.iter()
+        .map(|(label, min, max)| {
+            let members = assets.iter().filter(|a| (*min..=*max).contains(&a.references()));
+            let (count, bytes) = members.fold((0, 0), |(n, b), a| (n + 1, b + a.bytes));
+            UsageBucket { label: label.to_string(), assets: count, bytes }
+        })
+        .collect();
+    
+    Ok(AssetUsageReport {
+        unused: assets.iter().filter(|a| a.references() == 0).map(|a| a.asset_id.clone()).collect(),
+        most_referenced: assets.iter().filter(|a| a.references() > 0).take(TOP_N).map(|a| a.asset_id.clone()).collect(),
+        total_bytes: assets.iter().map(|a| a.bytes).sum(),
+        buckets,
+        assets,
+    })
+}
+
+/// "image", "text" or "record"
+pub fn kind(asset: &Asset) -> &'static str {
+    if asset.value.get("src").is_some() {
+        "image"
+    } else if text_asset::text_content(asset).is_some() {
+        "text"
+    } else {
+        "record"
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn count_by_asset(conn: &Connection, sql: &str) -> Result<HashMap<String, usize>, AppError> {
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))
+        .map_err(|e| AppError::Io(format!("Failed to count asset usage: {}", e)))?;
+    rows.collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to count asset usage: {}", e)))
+}
+
+
+/// Files the asset points at, with their thumbnails
+fn file_size(assets_dir: &Path, asset: &Asset) -> u64 {
+    let mut referenced = HashSet::new();
+    compaction::collect_asset_paths(&asset.value.to_string(), &mut referenced);
+    if let Some(meta) = &asset.value_meta {
+        compaction::collect_asset_paths(&meta.to_string(), &mut referenced);
+    }
+    if referenced.is_empty() {
+        return 0;
+    }
+    
+    let Ok(entries) = fs::read_dir(assets_dir) else { return 0 };
+    entries.flatten()
+        .filter(|e| compaction::is_referenced(&e.file_name().to_string_lossy(), &referenced))
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, Position, SynniaEdge, SynniaNode, SynniaNodeData, ValueType};
+    use crate::services::database;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn image(id: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": format!("assets/{}.png", id) }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        }
+    }
+
+    fn node(id: &str, type_: &str, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: type_.to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_asset_usage_report() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Board").unwrap();
+        for id in ["hero", "spare"] {
+            fs::write(root.join(format!("assets/{}.png", id)), b"png!").unwrap();
+            project.assets.insert(id.to_string(), image(id));
+        }
+        fs::write(root.join("assets/thumb_hero.jpg"), b"jpg").unwrap();
+        project.graph.nodes = vec![
+            node("hero-1", "image-node", Some("hero")),
+            node("hero-2", "image-node", Some("hero")),
+            node("upscale", "recipe-node", None),
+        ];
+        project.graph.edges = vec![SynniaEdge {
+            id: "e1".to_string(),
+            source: "hero-1".to_string(),
+            target: "upscale".to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }];
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let report = get_asset_usage_report(&conn, root).unwrap();
+        let hero = &report.assets[0];
+        assert_eq!((hero.asset_id.as_str(), hero.nodes, hero.recipe_inputs, hero.bytes), ("hero", 2, 1, 7));
+        assert_eq!(report.unused, vec!["spare"]);
+        assert_eq!(report.most_referenced, vec!["hero"]);
+        assert_eq!(report.total_bytes, 11);
+        
+        let bucket = |label: &str| report.buckets.iter().find(|b| b.label == label).unwrap().clone();
+        assert_eq!((bucket("unused").assets, bucket("unused").bytes), (1, 4));
+        assert_eq!(bucket("2-4").assets, 1);
+    }
+}
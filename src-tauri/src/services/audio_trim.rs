@@ -0,0 +1,295 @@
+//! Audio trimming: cut a clip out of an audio asset into a new asset, with
+//! `config.extra.provenance` linking back to the source.
+//!
+//! WAV files are cut sample-accurately in process; other formats go
+//! through ffmpeg (see `video_proxy::find_ffmpeg`).
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use serde::Serialize;
+use serde_json::json;
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{database, imaging, io_sqlite, video_proxy};
+use ts_rs::TS;
+
+const NODE_GAP: f64 = 40.0;
+
+/// The new clip, plus its node when the source node was given
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimResult {
+    pub asset: Asset,
+    pub node: Option<SynniaNode>,
+}
+
+/// Cut `start_ms..end_ms` of an audio asset into a new asset. With
+/// `node_id`, the clip also gets a node next to that one.
+pub fn trim_audio(
+    project_root: &Path,
+    asset_id: &str,
+    start_ms: u64,
+    end_ms: u64,
+    node_id: Option<&str>,
+) -> Result<TrimResult, AppError> {
+    if end_ms <= start_ms {
+        return Err(AppError::Unknown("Clip end must be after its start".to_string()));
+    }
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let source = io_sqlite::get_asset(&conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    let source_path = source.value.get("src").or(Some(&source.value)).and_then(|v| v.as_str())
+        .and_then(|src| imaging::resolve_image_source(project_root, src))
+        .filter(|p| p.is_file())
+        .ok_or_else(|| AppError::NotFound(format!("Audio file of {} not found", asset_id)))?;
+    let ext = source_path.extension().and_then(|e| e.to_str()).unwrap_or("wav").to_lowercase();
+    
+    let relative_path = format!("assets/{}.{}", uuid::Uuid::new_v4(), ext);
+    let target_path = project_root.join(&relative_path);
+    let duration_ms = if ext == "wav" {
+        let data = std::fs::read(&source_path)?;
+        let (clip, duration_ms) = trim_wav(&data, start_ms, end_ms)?;
+        std::fs::write(&target_path, clip)?;
+        duration_ms
+    } else {
+        trim_with_ffmpeg(&source_path, &target_path, start_ms, end_ms)?;
+        end_ms - start_ms
+    };
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let asset = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Record,
+        value: json!({ "src": relative_path }),
+        value_meta: Some(json!({ "durationMs": duration_ms })),
+        config: Some(json!({
+            "schema": [],
+            "extra": {
+                "provenance": {
+                    "kind": "trim",
+                    "sourceAssetId": source.id,
+                    "startMs": start_ms,
+                    "endMs": start_ms + duration_ms,
+                    "generatedAt": now,
+                }
+            }
+        })),
+        sys: AssetSysMetadata {
+            name: format!("{} ({}–{})", source.sys.name, format_time(start_ms), format_time(start_ms + duration_ms)),
+            created_at: now,
+            updated_at: now,
+            source: "user".to_string(),
+        },
+    };
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    
+    let source_node = match node_id {
+        Some(node_id) => Some(io_sqlite::get_node(&conn, node_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", node_id)))?),
+        None => None,
+    };
+    let node = source_node.map(|source_node| SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: source_node.type_.clone(),
+        position: Position {
+            x: source_node.position.x + source_node.width.unwrap_or(300.0) + NODE_GAP,
+            y: source_node.position.y,
+        },
+        width: source_node.width,
+        height: source_node.height,
+        parent_id: source_node.parent_id.clone(),
+        extent: source_node.extent.clone(),
+        style: None,
+        data: SynniaNodeData {
+            title: asset.sys.name.clone(),
+            asset_id: Some(asset.id.clone()),
+            ..Default::default()
+        },
+    });
+    if let Some(node) = &node {
+        io_sqlite::upsert_node(&conn, node)?;
+    }
+    
+    Ok(TrimResult { asset, node })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Cut a RIFF/WAVE file at whole sample frames; the end is clamped to the
+/// audio's length. Returns the new file and its duration.
+fn trim_wav(data: &[u8], start_ms: u64, end_ms: u64) -> Result<(Vec<u8>, u64), AppError> {
+    let invalid = |msg: &str| AppError::Unknown(format!("Invalid WAV file: {}", msg));
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+    
+    let mut fmt: Option<&[u8]> = None;
+    let mut samples: Option<&[u8]> = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let len = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+        let body_start = pos + 8;
+        // Streamed files may leave the data size unset; take what is there
+        let body_end = body_start.saturating_add(len).min(data.len());
+        match id {
+            b"fmt " => fmt = Some(&data[body_start..body_end]),
+            b"data" => samples = Some(&data[body_start..body_end]),
+            _ => {}
+        }
+        pos = body_start.saturating_add(len).saturating_add(len & 1);
+    }
+    let fmt = fmt.filter(|f| f.len() >= 16).ok_or_else(|| invalid("missing fmt chunk"))?;
+    let samples = samples.ok_or_else(|| invalid("missing data chunk"))?;
+    
+    let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]) as u64;
+    let block_align = u16::from_le_bytes([fmt[12], fmt[13]]) as u64;
+    if sample_rate == 0 || block_align == 0 {
+        return Err(invalid("bad sample format"));
+    }
+    
+    let total_frames = samples.len() as u64 / block_align;
+    let start_frame = start_ms * sample_rate / 1000;
+    let end_frame = (end_ms * sample_rate / 1000).min(total_frames);
+    if start_frame >= end_frame {
+        return Err(AppError::Unknown("Clip starts after the end of the audio".to_string()));
+    }
+    let clip = &samples[(start_frame * block_align) as usize..(end_frame * block_align) as usize];
+    
+    let fmt_padded = fmt.len() + (fmt.len() & 1);
+    let clip_padded = clip.len() + (clip.len() & 1);
+    let mut out = Vec::with_capacity(12 + 8 + fmt_padded + 8 + clip_padded);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&((4 + 8 + fmt_padded + 8 + clip_padded) as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    for (id, body) in [(b"fmt ", fmt), (b"data", clip)] {
+        out.extend_from_slice(id);
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(body);
+        if body.len() & 1 == 1 {
+            out.push(0);
+        }
+    }
+    
+    Ok((out, (end_frame - start_frame) * 1000 / sample_rate))
+}
+
+fn trim_with_ffmpeg(source: &Path, target: &Path, start_ms: u64, end_ms: u64) -> Result<(), AppError> {
+    let ffmpeg = video_proxy::find_ffmpeg()
+        .ok_or_else(|| AppError::NotFound("ffmpeg not found; only WAV files can be trimmed without it".to_string()))?;
+    
+    // Seeking after -i decodes from the start but cuts exactly
+    let output = Command::new(ffmpeg)
+        .args(["-y", "-v", "error", "-i"])
+        .arg(source)
+        .args(["-ss", &format_seconds(start_ms), "-to", &format_seconds(end_ms), "-vn"])
+        .arg(target)
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|e| AppError::Io(format!("Failed to run ffmpeg: {}", e)))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(target);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::Unknown(format!("ffmpeg failed: {}", stderr.lines().last().unwrap_or("unknown error"))));
+    }
+    Ok(())
+}
+
+fn format_seconds(ms: u64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// "1:05.250" style label for clip names
+fn format_time(ms: u64) -> String {
+    let seconds = ms / 1000;
+    match ms % 1000 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        frac => format!("{}:{:02}.{:03}", seconds / 60, seconds % 60, frac),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// 16-bit mono WAV whose samples count up from 0
+    fn wav(sample_rate: u32, frames: u32) -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        fmt.extend_from_slice(&1u16.to_le_bytes()); // mono
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        let samples: Vec<u8> = (0..frames).flat_map(|i| (i as u16).to_le_bytes()).collect();
+        
+        let mut out = b"RIFF".to_vec();
+        out.extend_from_slice(&(4 + 8 + 16 + 8 + samples.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&fmt);
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        out.extend_from_slice(&samples);
+        out
+    }
+
+    #[test]
+    fn test_trim_wav() {
+        let (clip, duration) = trim_wav(&wav(1000, 3000), 500, 1500).unwrap();
+        assert_eq!(duration, 1000);
+        assert_eq!(clip.len(), 44 + 2000);
+        assert_eq!(u16::from_le_bytes([clip[44], clip[45]]), 500);
+        
+        // End is clamped; a start past the end is an error
+        assert_eq!(trim_wav(&wav(1000, 3000), 2500, 9000).unwrap().1, 500);
+        assert!(trim_wav(&wav(1000, 3000), 4000, 5000).is_err());
+        assert!(trim_wav(b"not a wav", 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_trim_audio() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Mix").unwrap();
+        std::fs::write(root.join("assets/voice.wav"), wav(8000, 80000)).unwrap();
+        project.assets.insert("voice".to_string(), Asset {
+            id: "voice".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/voice.wav" }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Voice".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        });
+        project.graph.nodes.push(SynniaNode {
+            id: "n1".to_string(),
+            type_: "audio-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: Some(300.0),
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData { title: "Voice".to_string(), asset_id: Some("voice".to_string()), ..Default::default() },
+        });
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let result = trim_audio(root, "voice", 2000, 5250, Some("n1")).unwrap();
+        assert_eq!(result.asset.sys.name, "Voice (0:02–0:05.250)");
+        assert_eq!(result.asset.value_meta.as_ref().unwrap()["durationMs"], 3250);
+        assert_eq!(result.asset.config.as_ref().unwrap()["extra"]["provenance"]["sourceAssetId"], "voice");
+        let node = result.node.unwrap();
+        assert_eq!((node.position.x, node.type_.as_str()), (340.0, "audio-node"));
+        
+        let src = result.asset.value["src"].as_str().unwrap();
+        assert_eq!(std::fs::metadata(root.join(src)).unwrap().len(), 44 + 3250 * 8 * 2);
+        assert!(trim_audio(root, "voice", 5000, 5000, None).is_err());
+    }
+}
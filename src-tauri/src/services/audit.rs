@@ -0,0 +1,302 @@
+//! Audit export: a project's record of who (or which AI) did what, for
+//! studios that have to retain it.
+//!
+//! Rows come from the activity feed (agent runs and permission decisions get
+//! their own section) and from the generation records, which tie each
+//! generated asset to its recipe and parameters. Every row carries a
+//! SHA-256 over its fields and the previous row's hash, so any edit,
+//! removal or reordering after export breaks the chain from that row on.
+
+use std::fs;
+use std::path::Path;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::hash::compute_content_hash;
+use crate::services::smart_collections::DateRange;
+use ts_rs::TS;
+
+/// Hash the chain starts from
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditFormat {
+    Csv,
+    Json,
+}
+
+impl AuditFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AuditFormat::Csv => "csv",
+            AuditFormat::Json => "json",
+        }
+    }
+}
+
+/// One exported record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRow {
+    /// "activity", "agent-run", "permission" or "generation"
+    pub section: String,
+    /// Row ID in its source table
+    pub id: String,
+    /// Unix timestamp (ms)
+    pub created_at: i64,
+    pub kind: String,
+    pub actor: String,
+    pub source: Option<String>,
+    pub target_id: Option<String>,
+    pub summary: String,
+    /// Extra fields as JSON (generation parameters)
+    pub detail: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditExport {
+    pub path: String,
+    pub rows: usize,
+    /// Hash of the last row, to note down and check the file against later
+    pub last_hash: String,
+}
+
+const CSV_HEADER: &[&str] = &[
+    "section", "id", "createdAt", "kind", "actor", "source", "targetId", "summary", "detail", "prevHash", "hash",
+];
+
+/// Audit rows created within `range`, oldest first, with their hash chain.
+pub fn collect(conn: &Connection, range: &DateRange) -> Result<Vec<AuditRow>, AppError> {
+    let from = range.from.unwrap_or(i64::MIN);
+    let to = range.to.unwrap_or(i64::MAX);
+    let io = |e: rusqlite::Error| AppError::Io(format!("Failed to read audit records: {}", e));
+
+    let mut rows: Vec<AuditRow> = conn
+        .prepare(
+            "SELECT id, kind, actor, source, target_id, summary, created_at FROM activity
+             WHERE created_at BETWEEN ?1 AND ?2",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![from, to], |row| {
+                let kind: String = row.get(1)?;
+                let section = match kind.as_str() {
+                    "agent-ran" => "agent-run",
+                    "permission-decided" => "permission",
+                    _ => "activity",
+                };
+                Ok(AuditRow {
+                    section: section.to_string(),
+                    id: row.get::<_, i64>(0)?.to_string(),
+                    created_at: row.get(6)?,
+                    kind,
+                    actor: row.get(2)?,
+                    source: row.get(3)?,
+                    target_id: row.get(4)?,
+                    summary: row.get(5)?,
+                    detail: None,
+                    prev_hash: String::new(),
+                    hash: String::new(),
+                })
+            })?
+            .collect()
+        })
+        .map_err(io)?;
+
+    let generations: Vec<AuditRow> = conn
+        .prepare(
+            "SELECT asset_id, recipe_id, parent_asset_id, params_json, created_at FROM generation_records
+             WHERE created_at BETWEEN ?1 AND ?2",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![from, to], |row| {
+                let asset_id: String = row.get(0)?;
+                let parent: Option<String> = row.get(2)?;
+                Ok(AuditRow {
+                    section: "generation".to_string(),
+                    id: asset_id.clone(),
+                    created_at: row.get(4)?,
+                    kind: "generated".to_string(),
+                    actor: "ai".to_string(),
+                    source: row.get(1)?,
+                    summary: match &parent {
+                        Some(parent) => format!("Generated {} from {}", asset_id, parent),
+                        None => format!("Generated {}", asset_id),
+                    },
+                    target_id: Some(asset_id),
+                    detail: Some(row.get(3)?),
+                    prev_hash: String::new(),
+                    hash: String::new(),
+                })
+            })?
+            .collect()
+        })
+        .map_err(io)?;
+    rows.extend(generations);
+
+    rows.sort_by(|a, b| (a.created_at, &a.section, &a.id).cmp(&(b.created_at, &b.section, &b.id)));
+    chain(&mut rows);
+    Ok(rows)
+}
+
+/// Fill in each row's previous and own hash.
+pub fn chain(rows: &mut [AuditRow]) {
+    let mut prev = GENESIS_HASH.to_string();
+    for row in rows {
+        row.prev_hash = prev;
+        row.hash = row_hash(row);
+        prev = row.hash.clone();
+    }
+}
+
+/// Index of the first row whose hash doesn't match its fields or chain, if any
+#[cfg(test)]
+pub fn verify(rows: &[AuditRow]) -> Option<usize> {
+    let mut prev = GENESIS_HASH;
+    for (index, row) in rows.iter().enumerate() {
+        if row.prev_hash != prev || row.hash != row_hash(row) {
+            return Some(index);
+        }
+        prev = &row.hash;
+    }
+    None
+}
+
+/// Write `rows` to `dest` as CSV or JSON.
+pub fn write(rows: &[AuditRow], format: AuditFormat, dest: &Path) -> Result<AuditExport, AppError> {
+    let content = match format {
+        AuditFormat::Json => serde_json::to_string_pretty(rows)?,
+        AuditFormat::Csv => {
+            let mut csv = CSV_HEADER.join(",");
+            csv.push_str("\r\n");
+            for row in rows {
+                let created_at = row.created_at.to_string();
+                let fields = [
+                    row.section.as_str(),
+                    &row.id,
+                    &created_at,
+                    &row.kind,
+                    &row.actor,
+                    row.source.as_deref().unwrap_or(""),
+                    row.target_id.as_deref().unwrap_or(""),
+                    &row.summary,
+                    row.detail.as_deref().unwrap_or(""),
+                    &row.prev_hash,
+                    &row.hash,
+                ];
+                csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+                csv.push_str("\r\n");
+            }
+            csv
+        }
+    };
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(dest, content)?;
+
+    Ok(AuditExport {
+        path: dest.to_string_lossy().to_string(),
+        rows: rows.len(),
+        last_hash: rows.last().map_or_else(|| GENESIS_HASH.to_string(), |r| r.hash.clone()),
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Hash of a row's fields (unit-separated, so no field can bleed into the
+/// next) and the previous hash
+fn row_hash(row: &AuditRow) -> String {
+    let fields = [
+        row.prev_hash.as_str(),
+        &row.section,
+        &row.id,
+        &row.created_at.to_string(),
+        &row.kind,
+        &row.actor,
+        row.source.as_deref().unwrap_or(""),
+        row.target_id.as_deref().unwrap_or(""),
+        &row.summary,
+        row.detail.as_deref().unwrap_or(""),
+    ];
+    compute_content_hash(&fields.join("\u{1f}"))
+}
+
+/// Quote a CSV field when needed. Fields that spreadsheets would run as
+/// formulas get a leading apostrophe.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) { format!("'{}", value) } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::activity::{self, ActivityKind, NewActivity};
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_sections_and_chain() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        activity::record(&conn, &NewActivity::new(ActivityKind::AgentRan, "agent", "Ran Writer").source("Writer")).unwrap();
+        activity::record(&conn, &NewActivity::new(ActivityKind::PermissionDecided, "agent", "Denied: Create 2 nodes")).unwrap();
+        conn.execute(
+            "INSERT INTO generation_records (asset_id, recipe_id, parent_asset_id, params_json, created_at)
+             VALUES ('a1', 'r1', NULL, '{\"seed\":7}', 1)",
+            [],
+        ).unwrap();
+
+        let mut rows = collect(&conn, &DateRange::default()).unwrap();
+        let sections: Vec<&str> = rows.iter().map(|r| r.section.as_str()).collect();
+        assert_eq!(sections, ["generation", "agent-run", "permission"]);
+        assert_eq!(rows[0].prev_hash, GENESIS_HASH);
+        assert_eq!(rows[1].prev_hash, rows[0].hash);
+        assert_eq!(verify(&rows), None);
+
+        rows[1].summary = "Ran Editor".to_string();
+        assert_eq!(verify(&rows), Some(1));
+
+        let recent = collect(&conn, &DateRange { from: Some(2), to: None }).unwrap();
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn test_write_csv_escapes_fields() {
+        let dir = tempdir().unwrap();
+        let mut rows = vec![AuditRow {
+            section: "activity".to_string(),
+            id: "1".to_string(),
+            created_at: 5,
+            kind: "imported".to_string(),
+            actor: "user".to_string(),
+            source: None,
+            target_id: None,
+            summary: "=HYPERLINK(\"x\"), and more".to_string(),
+            detail: None,
+            prev_hash: String::new(),
+            hash: String::new(),
+        }];
+        chain(&mut rows);
+
+        let dest = dir.path().join("audit.csv");
+        let export = write(&rows, AuditFormat::Csv, &dest).unwrap();
+        assert_eq!(export.rows, 1);
+        assert_eq!(export.last_hash, rows[0].hash);
+        let csv = fs::read_to_string(&dest).unwrap();
+        assert!(csv.starts_with("section,id,createdAt,"));
+        assert!(csv.contains("\"'=HYPERLINK(\"\"x\"\"), and more\""));
+    }
+}
@@ -0,0 +1,236 @@
+//! Auto-tagging of the media library: classify/caption image assets in a
+//! background job and write the confident tags into the tag index.
+//!
+//! Tasks run through the same runner as interrogation (locally or on the
+//! frontend with a provider) and report the same `{ prompt, tags }` shape.
+//! Tags below the project's confidence threshold are dropped when the job
+//! settles.
+
+use std::path::Path;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{imaging, interrogate, io_sqlite, tags};
+use crate::services::batch_import::IMAGE_EXTENSIONS;
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "auto-tag";
+
+/// Tag source in the tag index
+pub const TAG_SOURCE: &str = "auto";
+
+/// Settings key of the auto-tag settings
+pub const SETTINGS_KEY: &str = "autoTag";
+
+/// Per-project auto-tag settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoTagSettings {
+    /// Tags scored below this (0..1) are dropped; unscored tags are kept
+    pub min_confidence: f64,
+    /// Most tags kept per asset
+    pub max_tags: usize,
+}
+
+impl Default for AutoTagSettings {
+    fn default() -> Self {
+        Self { min_confidence: 0.35, max_tags: 20 }
+    }
+}
+
+impl AutoTagSettings {
+    /// Clamp to usable values
+    pub fn normalized(self) -> Self {
+        let min_confidence = if self.min_confidence.is_finite() { self.min_confidence.clamp(0.0, 1.0) } else { 0.0 };
+        Self { min_confidence, max_tags: self.max_tags.clamp(1, 100) }
+    }
+}
+
+/// Which images to tag
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum AutoTagScope {
+    /// Images without any tag yet
+    #[default]
+    Untagged,
+    /// Every image; earlier auto tags are replaced
+    All,
+    /// Only these assets
+    #[serde(rename_all = "camelCase")]
+    Assets { asset_ids: Vec<String> },
+}
+
+/// Parameters of one tagging task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoTagTask {
+    pub asset_id: String,
+    /// Image file relative to the project root
+    pub src: String,
+    pub provider: String,
+}
+
+/// Counts of a finished auto-tag job
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoTagResult {
+    pub job_id: String,
+    pub tagged: usize,
+    pub tags: usize,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<AutoTagSettings, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Store auto-tag settings (clamped to usable values). Returns what was stored.
+pub fn save_settings(conn: &Connection, settings: &AutoTagSettings) -> Result<AutoTagSettings, AppError> {
+    let settings = settings.normalized();
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(settings)?)?;
+    Ok(settings)
+}
+
+/// Image assets in `scope`, in path order.
+pub fn pending_tasks(
+    conn: &Connection,
+    project_root: &Path,
+    scope: &AutoTagScope,
+    provider: &str,
+) -> Result<Vec<AutoTagTask>, AppError> {
+    let tagged: Vec<String> = match scope {
+        AutoTagScope::Untagged => {
+            let mut stmt = conn.prepare("SELECT DISTINCT asset_id FROM asset_tags")
+                .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+            let rows = stmt.query_map([], |row| row.get(0))
+                .map_err(|e| AppError::Io(format!("Failed to query tags: {}", e)))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Io(format!("Failed to load tags: {}", e)))?
+        }
+        _ => Vec::new(),
+    };
+    
+    let mut tasks: Vec<AutoTagTask> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .filter(|asset| match scope {
+            AutoTagScope::Untagged => !tagged.contains(&asset.id),
+            AutoTagScope::All => true,
+            AutoTagScope::Assets { asset_ids } => asset_ids.contains(&asset.id),
+        })
+        .filter_map(|asset| {
+            let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+            let path = imaging::resolve_image_source(project_root, src).filter(|p| p.is_file())?;
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+                return None;
+            }
+            let src = path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/");
+            Some(AutoTagTask { asset_id: asset.id, src, provider: provider.to_string() })
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.src.cmp(&b.src));
+    Ok(tasks)
+}
+
+/// Write the confident tags of every successful task into the tag index.
+pub fn finalize_auto_tag(conn: &Connection, job: &Job) -> Result<AutoTagResult, AppError> {
+    let settings = get_settings(conn)?;
+    let mut tagged = 0;
+    let mut total = 0;
+    
+    for task in &job.tasks {
+        if task.status != TaskStatus::Success {
+            continue;
+        }
+        let Ok(params) = serde_json::from_value::<AutoTagTask>(task.params.clone()) else { continue };
+        let Some((_, mut found)) = task.result.as_ref().and_then(interrogate::parse_result) else { continue };
+        
+        found.retain(|(_, confidence)| confidence.map_or(true, |c| c >= settings.min_confidence));
+        found.sort_by(|a, b| b.1.unwrap_or(1.0).total_cmp(&a.1.unwrap_or(1.0)));
+        found.truncate(settings.max_tags);
+        
+        let stored = tags::set_tags(conn, &params.asset_id, TAG_SOURCE, &found)?;
+        let added = stored.iter().filter(|t| t.source == TAG_SOURCE).count();
+        if added > 0 {
+            tagged += 1;
+            total += added;
+        }
+    }
+    
+    Ok(AutoTagResult { job_id: job.id.clone(), tagged, tags: total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use crate::services::database;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_auto_tag() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Library").unwrap();
+        for id in ["a", "b", "c"] {
+            image::RgbImage::new(4, 4).save(root.join(format!("assets/{}.png", id))).unwrap();
+            project.assets.insert(id.to_string(), Asset {
+                id: id.to_string(),
+                value_type: ValueType::Record,
+                value: json!({ "src": format!("assets/{}.png", id) }),
+                value_meta: None,
+                config: None,
+                sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "import".to_string() },
+            });
+        }
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        tags::set_tags(&conn, "c", tags::USER_SOURCE, &[("logo".to_string(), None)]).unwrap();
+        save_settings(&conn, &AutoTagSettings { min_confidence: 0.5, max_tags: 2 }).unwrap();
+        
+        let tasks = pending_tasks(&conn, root, &AutoTagScope::Untagged, "local").unwrap();
+        assert_eq!(tasks.iter().map(|t| t.asset_id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(pending_tasks(&conn, root, &AutoTagScope::All, "local").unwrap().len(), 3);
+        
+        let params = tasks.iter().map(|t| serde_json::to_value(t).unwrap()).collect();
+        let mut job = Job::new(JOB_KIND, serde_json::Value::Null, params, 2);
+        job.tasks[0].status = TaskStatus::Success;
+        job.tasks[0].result = Some(json!({ "tags": [
+            { "tag": "cat", "confidence": 0.9 },
+            { "tag": "dog", "confidence": 0.2 },
+            { "tag": "sofa", "confidence": 0.6 },
+            { "tag": "indoor", "confidence": 0.55 },
+        ] }));
+        job.tasks[1].status = TaskStatus::Error;
+        
+        let result = finalize_auto_tag(&conn, &job).unwrap();
+        assert_eq!((result.tagged, result.tags), (1, 2));
+        let a: Vec<String> = tags::get_tags(&conn, "a").unwrap().into_iter().map(|t| t.tag).collect();
+        assert_eq!(a, vec!["cat", "sofa"]);
+        
+        let left = pending_tasks(&conn, root, &AutoTagScope::Untagged, "local").unwrap();
+        assert_eq!(left.len(), 1);
+    }
+
+    #[test]
+    fn test_settings_survive_project_save() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Tags").unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let saved = save_settings(&conn, &AutoTagSettings { min_confidence: 0.6, max_tags: 5 }).unwrap();
+
+        // The frontend's autosave sends no settings
+        project.settings = Some(std::collections::HashMap::new());
+        io_sqlite::save_project_sqlite(dir.path(), &project).unwrap();
+
+        assert_eq!(get_settings(&conn).unwrap(), saved);
+        assert_ne!(saved, Default::default());
+    }
+}
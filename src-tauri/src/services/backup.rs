@@ -0,0 +1,657 @@
+//! Workspace-wide backups of project databases.
+//!
+//! A background task zips each known project's `synnia.db` (and its
+//! `assets/` folder when configured) into
+//! `<backup dir>/<project folder>/<timestamp>.zip`, keeping the newest
+//! `retention` archives per project. Each archive carries a
+//! `backup.json` manifest describing where it came from.
+//!
+//! Linked imports aren't copied: `links.json` records where each link in
+//! `assets/` pointed, and restoring puts the links back.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::services::{database, export, hash, io_sqlite, linked_files};
+use ts_rs::TS;
+
+/// How often the scheduler checks whether backups are due
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const MANIFEST_NAME: &str = "backup.json";
+const DB_ENTRY: &str = "synnia.db";
+const LINKS_ENTRY: &str = "links.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    /// Minimum time between backups of one project
+    pub interval_hours: u32,
+    /// Archives kept per project
+    pub retention: usize,
+    /// Also archive `assets/` (can be large)
+    pub include_assets: bool,
+    /// Backup root; defaults to `<app data>/backups`
+    pub directory: Option<String>,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            retention: 7,
+            include_assets: false,
+            directory: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub project_name: String,
+    pub project_path: String,
+    /// Unix timestamp (ms)
+    #[ts(type = "number")]
+    pub created_at: i64,
+    pub includes_assets: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// Path relative to the backup root; pass to `restore_backup`
+    pub id: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[serde(flatten)]
+    pub manifest: BackupManifest,
+}
+
+/// Backup root for the given settings
+pub fn backup_dir(app: &AppHandle, settings: &BackupSettings) -> Result<PathBuf, AppError> {
+    match &settings.directory {
+        Some(dir) if !dir.trim().is_empty() => Ok(PathBuf::from(dir)),
+        _ => app.path().app_data_dir()
+            .map(|dir| dir.join("backups"))
+            .map_err(|e| AppError::Io(format!("Failed to resolve app data dir: {}", e))),
+    }
+}
+
+/// Archive one project and prune its old archives.
+pub fn backup_project(
+    project_root: &Path,
+    project_name: &str,
+    backup_root: &Path,
+    settings: &BackupSettings,
+) -> Result<BackupInfo, AppError> {
+    if !io_sqlite::is_sqlite_project(project_root) {
+        return Err(AppError::NotFound(format!("Not a Synnia project: {}", project_root.display())));
+    }
+    
+    let project_dir = backup_root.join(project_folder(project_root));
+    fs::create_dir_all(&project_dir)?;
+    
+    let now = chrono::Utc::now();
+    let stem = now.format("%Y%m%d-%H%M%S").to_string();
+    let archive_path = project_dir.join(export::unique_filename(&project_dir, &stem, "zip"));
+    
+    // VACUUM INTO gives a consistent copy even while the project is open (WAL)
+    let snapshot = project_dir.join(format!(".{}.db", stem));
+    let _ = fs::remove_file(&snapshot);
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    conn.execute("VACUUM INTO ?1", [snapshot.to_string_lossy()])
+        .map_err(|e| AppError::Io(format!("Failed to snapshot database: {}", e)))?;
+    drop(conn);
+    
+    let manifest = BackupManifest {
+        project_name: project_name.to_string(),
+        project_path: project_root.to_string_lossy().into_owned(),
+        created_at: now.timestamp_millis(),
+        includes_assets: settings.include_assets,
+    };
+    
+    let result = (|| {
+        let mut zip = ZipWriter::new(fs::File::create(&archive_path)?);
+        zip.add(MANIFEST_NAME, &serde_json::to_vec_pretty(&manifest)?)?;
+        zip.add(DB_ENTRY, &fs::read(&snapshot)?)?;
+        if settings.include_assets {
+            let assets_dir = project_root.join("assets");
+            if assets_dir.is_dir() {
+                for file in walk_files(&assets_dir)? {
+                    zip.add(&entry_name(project_root, &file), &fs::read(&file)?)?;
+                }
+                let links: BTreeMap<String, String> = walk_links(&assets_dir)?
+                    .into_iter()
+                    .map(|(link, target)| (entry_name(project_root, &link), target.to_string_lossy().into_owned()))
+                    .collect();
+                if !links.is_empty() {
+                    zip.add(LINKS_ENTRY, &serde_json::to_vec_pretty(&links)?)?;
+                }
+            }
+        }
+        zip.finish()
+    })();
+    let _ = fs::remove_file(&snapshot);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+    
+    prune(&project_dir, settings.retention.max(1))?;
+    
+    let size = fs::metadata(&archive_path)?.len();
+    Ok(BackupInfo { id: relative_id(backup_root, &archive_path), size, manifest })
+}
+
+/// All archives under the backup root, newest first.
+pub fn list_backups(backup_root: &Path) -> Result<Vec<BackupInfo>, AppError> {
+    let mut backups = Vec::new();
+    if !backup_root.is_dir() {
+        return Ok(backups);
+    }
+    
+    for dir in fs::read_dir(backup_root)?.flatten().filter(|e| e.path().is_dir()) {
+        for archive in list_archives(&dir.path())? {
+            let manifest = match read_manifest(&archive) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    println!("Skipping unreadable backup {:?}: {}", archive, e);
+                    continue;
+                }
+            };
+            backups.push(BackupInfo {
+                id: relative_id(backup_root, &archive),
+                size: fs::metadata(&archive)?.len(),
+                manifest,
+            });
+        }
+    }
+    
+    backups.sort_by_key(|b| std::cmp::Reverse(b.manifest.created_at));
+    Ok(backups)
+}
+
+/// Restore an archive into `target` (default: the project it came from).
+/// Returns the restored project path.
+pub fn restore_backup(backup_root: &Path, backup_id: &str, target: Option<&Path>) -> Result<PathBuf, AppError> {
+    let archive = resolve_archive(backup_root, backup_id)?;
+    let manifest = read_manifest(&archive)?;
+    let target = target.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(&manifest.project_path));
+    
+    let zip = ZipReader::open(&archive)?;
+    let db = zip.read(DB_ENTRY)?
+        .ok_or_else(|| AppError::Io(format!("Backup {} has no database", backup_id)))?;
+    
+    fs::create_dir_all(&target)?;
+    let db_path = io_sqlite::get_db_path(&target);
+    // Stale WAL files would be replayed over the restored database
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(db_path.with_file_name(format!("{}{}", DB_ENTRY, suffix)));
+    }
+    fs::write(&db_path, db)?;
+    
+    for name in zip.names().into_iter().filter(|n| n.starts_with("assets/")) {
+        let Some(relative) = safe_relative(&name) else { continue };
+        if let Some(data) = zip.read(&name)? {
+            let path = target.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, data)?;
+        }
+    }
+    
+    if let Some(data) = zip.read(LINKS_ENTRY)? {
+        let links: BTreeMap<String, String> = serde_json::from_slice(&data)?;
+        for (name, original) in links {
+            let Some(relative) = safe_relative(&name).filter(|_| name.starts_with("assets/")) else { continue };
+            let path = target.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if path.symlink_metadata().is_ok() {
+                fs::remove_file(&path)?;
+            }
+            if let Err(e) = linked_files::create_link(Path::new(&original), &path) {
+                println!("Failed to restore link {}: {}", name, e);
+            }
+        }
+    }
+    
+    Ok(target)
+}
+
+/// Back up every recent project whose newest archive is older than the interval.
+pub fn run_due_backups(app: &AppHandle) -> Result<Vec<BackupInfo>, AppError> {
+    let config = GlobalConfig::load(app);
+    let settings = config.backup.clone().unwrap_or_default();
+    if !settings.enabled {
+        return Ok(Vec::new());
+    }
+    
+    let backup_root = backup_dir(app, &settings)?;
+    let interval_ms = settings.interval_hours.max(1) as i64 * 3_600_000;
+    let now = chrono::Utc::now().timestamp_millis();
+    
+    let mut created = Vec::new();
+    for project in &config.recent_projects {
+        let root = PathBuf::from(&project.path);
+        if !io_sqlite::is_sqlite_project(&root) {
+            continue;
+        }
+        let last = newest_backup_time(&backup_root.join(project_folder(&root)));
+        if last.is_some_and(|t| now - t < interval_ms) {
+            continue;
+        }
+        match backup_project(&root, &project.name, &backup_root, &settings) {
+            Ok(info) => created.push(info),
+            Err(e) => println!("Failed to back up {}: {}", project.path, e),
+        }
+    }
+    
+    Ok(created)
+}
+
+/// Start the background backup task.
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            
+            let app = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || run_due_backups(&app)).await;
+            if let Ok(Err(e)) = result {
+                println!("Backup run failed: {}", e);
+            }
+        }
+    });
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Folder name for a project's archives: its directory name plus a short
+/// path hash, so same-named projects in different places don't collide
+fn project_folder(project_root: &Path) -> String {
+    let name = project_root.file_name()
+        .map(|n| export::sanitize(&n.to_string_lossy()))
+        .unwrap_or_else(|| "project".to_string());
+    let digest = hash::compute_content_hash(&project_root.to_string_lossy());
+    format!("{}-{}", name, &digest[..8.min(digest.len())])
+}
+
+fn relative_id(backup_root: &Path, archive: &Path) -> String {
+    archive.strip_prefix(backup_root).unwrap_or(archive).to_string_lossy().replace('\\', "/")
+}
+
+fn resolve_archive(backup_root: &Path, backup_id: &str) -> Result<PathBuf, AppError> {
+    let relative = safe_relative(backup_id)
+        .filter(|_| backup_id.ends_with(".zip"))
+        .ok_or_else(|| AppError::NotFound(format!("Invalid backup id: {}", backup_id)))?;
+    let path = backup_root.join(relative);
+    if !path.is_file() {
+        return Err(AppError::NotFound(format!("Backup not found: {}", backup_id)));
+    }
+    Ok(path)
+}
+
+/// Reject absolute paths and `..` so archive names can't escape their root
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    path.components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+fn list_archives(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "zip"))
+        .collect();
+    // Timestamped names sort chronologically
+    archives.sort();
+    Ok(archives)
+}
+
+fn newest_backup_time(dir: &Path) -> Option<i64> {
+    let archives = list_archives(dir).ok()?;
+    archives.iter().rev().find_map(|a| read_manifest(a).ok()).map(|m| m.created_at)
+}
+
+fn prune(dir: &Path, keep: usize) -> Result<(), AppError> {
+    let archives = list_archives(dir)?;
+    let excess = archives.len().saturating_sub(keep);
+    for archive in &archives[..excess] {
+        fs::remove_file(archive)?;
+    }
+    Ok(())
+}
+
+fn read_manifest(archive: &Path) -> Result<BackupManifest, AppError> {
+    let data = ZipReader::open(archive)?.read(MANIFEST_NAME)?
+        .ok_or_else(|| AppError::Io(format!("Missing {} in {:?}", MANIFEST_NAME, archive)))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+/// Archive entry name of a file under the project root
+fn entry_name(project_root: &Path, file: &Path) -> String {
+    file.strip_prefix(project_root).unwrap_or(file).to_string_lossy().replace('\\', "/")
+}
+
+/// Symlinks under `dir` and where they point, sorted
+fn walk_links(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>, AppError> {
+    let mut links = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        let path = entry.path();
+        if file_type.is_symlink() {
+            let target = fs::read_link(&path)?;
+            links.push((path, target));
+        } else if file_type.is_dir() {
+            links.extend(walk_links(&path)?);
+        }
+    }
+    links.sort();
+    Ok(links)
+}
+
+/// Regular files under `dir`, sorted; symlinks are not followed
+pub(crate) fn walk_files(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        let path = entry.path();
+        if file_type.is_symlink() {
+            // Linked imports stay where they are (see `walk_links`)
+            continue;
+        } else if file_type.is_dir() {
+            files.extend(walk_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Minimal ZIP (deflate, no ZIP64) — enough for our own archives
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_SIG: u32 = 0x0605_4b50;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+struct ZipEntry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+pub(crate) struct ZipWriter<W: Write> {
+    out: W,
+    written: u64,
+    entries: Vec<ZipEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub(crate) fn new(out: W) -> Self {
+        Self { out, written: 0, entries: Vec::new() }
+    }
+
+    pub(crate) fn add(&mut self, name: &str, data: &[u8]) -> Result<(), AppError> {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(data);
+        let entry = ZipEntry {
+            name: name.to_string(),
+            method: METHOD_DEFLATE,
+            crc: crc.finalize(),
+            compressed_size: zip32(compressed.len() as u64)?,
+            size: zip32(data.len() as u64)?,
+            offset: zip32(self.written)?,
+        };
+        
+        let mut header = Vec::with_capacity(30 + name.len());
+        put32(&mut header, LOCAL_HEADER_SIG);
+        put16(&mut header, 20); // version needed
+        put16(&mut header, 0x0800); // UTF-8 names
+        put16(&mut header, entry.method);
+        put16(&mut header, 0); // mod time
+        put16(&mut header, 0x21); // mod date (1980-01-01)
+        put32(&mut header, entry.crc);
+        put32(&mut header, entry.compressed_size);
+        put32(&mut header, entry.size);
+        put16(&mut header, name.len() as u16);
+        put16(&mut header, 0); // extra length
+        header.extend_from_slice(name.as_bytes());
+        
+        self.write(&header)?;
+        self.write(&compressed)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<(), AppError> {
+        let directory_offset = zip32(self.written)?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            put32(&mut directory, CENTRAL_HEADER_SIG);
+            put16(&mut directory, 20); // version made by
+            put16(&mut directory, 20); // version needed
+            put16(&mut directory, 0x0800);
+            put16(&mut directory, entry.method);
+            put16(&mut directory, 0);
+            put16(&mut directory, 0x21);
+            put32(&mut directory, entry.crc);
+            put32(&mut directory, entry.compressed_size);
+            put32(&mut directory, entry.size);
+            put16(&mut directory, entry.name.len() as u16);
+            put16(&mut directory, 0); // extra length
+            put16(&mut directory, 0); // comment length
+            put16(&mut directory, 0); // disk number
+            put16(&mut directory, 0); // internal attributes
+            put32(&mut directory, 0); // external attributes
+            put32(&mut directory, entry.offset);
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        
+        let count = u16::try_from(self.entries.len())
+            .map_err(|_| AppError::Io("Too many files for a backup archive".to_string()))?;
+        let mut end = Vec::with_capacity(22);
+        put32(&mut end, END_OF_CENTRAL_SIG);
+        put16(&mut end, 0);
+        put16(&mut end, 0);
+        put16(&mut end, count);
+        put16(&mut end, count);
+        put32(&mut end, zip32(directory.len() as u64)?);
+        put32(&mut end, directory_offset);
+        put16(&mut end, 0);
+        
+        self.write(&directory)?;
+        self.write(&end)?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), AppError> {
+        self.out.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+struct ZipReader {
+    data: Vec<u8>,
+    entries: Vec<ZipEntry>,
+}
+
+impl ZipReader {
+    fn open(path: &Path) -> Result<Self, AppError> {
+        let data = fs::read(path)?;
+        let invalid = || AppError::Io(format!("Invalid backup archive: {:?}", path));
+        
+        let end = (0..data.len().saturating_sub(21)).rev()
+            .find(|&i| get32(&data, i) == Some(END_OF_CENTRAL_SIG))
+            .ok_or_else(invalid)?;
+        let count = get16(&data, end + 10).ok_or_else(invalid)? as usize;
+        let mut pos = get32(&data, end + 16).ok_or_else(invalid)? as usize;
+        
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            if get32(&data, pos) != Some(CENTRAL_HEADER_SIG) {
+                return Err(invalid());
+            }
+            let field16 = |at: usize| get16(&data, pos + at).ok_or_else(invalid);
+            let field32 = |at: usize| get32(&data, pos + at).ok_or_else(invalid);
+            let name_len = field16(28)? as usize;
+            let skip = name_len + field16(30)? as usize + field16(32)? as usize;
+            let name = data.get(pos + 46..pos + 46 + name_len).ok_or_else(invalid)?;
+            entries.push(ZipEntry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                method: field16(10)?,
+                crc: field32(16)?,
+                compressed_size: field32(20)?,
+                size: field32(24)?,
+                offset: field32(42)?,
+            });
+            pos += 46 + skip;
+        }
+        
+        Ok(Self { data, entries })
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.entries.iter().map(|e| e.name.clone()).collect()
+    }
+
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let Some(entry) = self.entries.iter().find(|e| e.name == name) else { return Ok(None) };
+        let invalid = || AppError::Io(format!("Corrupt entry in backup archive: {}", name));
+        
+        let offset = entry.offset as usize;
+        if get32(&self.data, offset) != Some(LOCAL_HEADER_SIG) {
+            return Err(invalid());
+        }
+        let name_len = get16(&self.data, offset + 26).ok_or_else(invalid)? as usize;
+        let extra_len = get16(&self.data, offset + 28).ok_or_else(invalid)? as usize;
+        let start = offset + 30 + name_len + extra_len;
+        let raw = self.data.get(start..start + entry.compressed_size as usize).ok_or_else(invalid)?;
+        
+        let bytes = match entry.method {
+            METHOD_STORED => raw.to_vec(),
+            METHOD_DEFLATE => {
+                let mut out = Vec::with_capacity(entry.size as usize);
+                flate2::read::DeflateDecoder::new(raw).read_to_end(&mut out)?;
+                out
+            }
+            other => return Err(AppError::Io(format!("Unsupported compression method {} in backup", other))),
+        };
+        
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(&bytes);
+        if crc.finalize() != entry.crc {
+            return Err(invalid());
+        }
+        Ok(Some(bytes))
+    }
+}
+
+fn zip32(value: u64) -> Result<u32, AppError> {
+    u32::try_from(value).map_err(|_| AppError::Io("Backup archive exceeds 4 GB".to_string()))
+}
+
+fn put16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn get16(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn get32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::io_sqlite::{init_project_sqlite, load_project_sqlite};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_backup_restore_and_retention() {
+        let workspace = tempdir().unwrap();
+        let project_root = workspace.path().join("Moodboard");
+        fs::create_dir_all(project_root.join("assets")).unwrap();
+        fs::write(project_root.join("assets/a.txt"), b"asset").unwrap();
+        init_project_sqlite(&project_root, "Moodboard").unwrap();
+        
+        let backups = workspace.path().join("backups");
+        let settings = BackupSettings { retention: 2, include_assets: true, ..Default::default() };
+        let first = backup_project(&project_root, "Moodboard", &backups, &settings).unwrap();
+        for _ in 0..2 {
+            backup_project(&project_root, "Moodboard", &backups, &settings).unwrap();
+        }
+        
+        let listed = list_backups(&backups).unwrap();
+        assert_eq!(listed.len(), 2, "retention keeps the newest archives");
+        assert!(listed.iter().all(|b| b.id != first.id));
+        assert!(listed[0].manifest.includes_assets);
+        
+        let target = workspace.path().join("Restored");
+        let restored = restore_backup(&backups, &listed[0].id, Some(&target)).unwrap();
+        assert_eq!(load_project_sqlite(&restored).unwrap().meta.name, "Moodboard");
+        assert_eq!(fs::read(target.join("assets/a.txt")).unwrap(), b"asset");
+        
+        assert!(restore_backup(&backups, "../outside.zip", None).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_backup_keeps_links() {
+        let workspace = tempdir().unwrap();
+        let project_root = workspace.path().join("Footage");
+        fs::create_dir_all(project_root.join("assets")).unwrap();
+        init_project_sqlite(&project_root, "Footage").unwrap();
+        let original = workspace.path().join("nas/shot.mov");
+        fs::create_dir_all(original.parent().unwrap()).unwrap();
+        fs::write(&original, b"footage").unwrap();
+        std::os::unix::fs::symlink(&original, project_root.join("assets/l1.mov")).unwrap();
+
+        let backups = workspace.path().join("backups");
+        let settings = BackupSettings { include_assets: true, ..Default::default() };
+        let info = backup_project(&project_root, "Footage", &backups, &settings).unwrap();
+
+        let target = workspace.path().join("Restored");
+        restore_backup(&backups, &info.id, Some(&target)).unwrap();
+        assert_eq!(fs::read_link(target.join("assets/l1.mov")).unwrap(), original);
+        assert_eq!(fs::read(target.join("assets/l1.mov")).unwrap(), b"footage");
+    }
+}
@@ -0,0 +1,167 @@
+//! Batch image import, run as a local job so files are copied, hashed,
+//! probed and thumbnailed (with animated previews for GIF/WebP) in parallel.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::services::{fs_paths, hash, imaging};
+use crate::services::imaging::ImageAnimation;
+
+pub const JOB_KIND: &str = "batch-import";
+
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+/// Parameters of one import task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTask {
+    pub source_path: String,
+}
+
+/// Result of one import task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedImage {
+    pub relative_path: String,
+    pub thumbnail_path: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub animation: Option<ImageAnimation>,
+    /// SHA-256 of the file, for spotting duplicates
+    pub content_hash: String,
+    /// Source file name (lossy if it isn't valid UTF-8)
+    #[serde(default)]
+    pub original_name: String,
+}
+
+/// Worker count for a batch when the caller doesn't choose
+pub fn default_concurrency() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+/// Copy one image into `assets/` and thumbnail it. The error is the
+/// message shown for the file.
+pub fn import_image(project_root: &Path, source_path: &Path) -> Result<ImportedImage, String> {
+    let source = fs_paths::long_path(source_path);
+    if !source.exists() {
+        return Err("File not found".to_string());
+    }
+    
+    let ext = fs_paths::extension(source_path).unwrap_or_else(|| "bin".to_string());
+    if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!("Unsupported image format: {}", ext));
+    }
+    
+    // Read once; the copy, hash, probe and thumbnail all work from memory
+    let image_data = std::fs::read(&source).map_err(|e| format!("Failed to read image: {}", e))?;
+    
+    let file_id = uuid::Uuid::new_v4().to_string();
+    let relative_path = format!("assets/{}.{}", file_id, ext);
+    std::fs::write(project_root.join(&relative_path), &image_data)
+        .map_err(|e| format!("Failed to copy file: {}", e))?;
+    
+    let (width, height) = imaging::get_image_dimensions(&image_data).unwrap_or((0, 0));
+    let thumbnail_path = imaging::generate_thumbnail(project_root, &file_id, &image_data).ok();
+    let animation = imaging::generate_animation_preview(project_root, &file_id, &image_data).ok().flatten();
+    
+    Ok(ImportedImage {
+        relative_path,
+        thumbnail_path,
+        width,
+        height,
+        animation,
+        content_hash: hash::compute_binary_hash(&image_data),
+        original_name: fs_paths::file_name(source_path),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::job_queue::{Job, JobQueue, TaskStatus};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_batch_import_job() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        
+        let mut png = Vec::new();
+        image::RgbImage::new(8, 6)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let mut paths = Vec::new();
+        for i in 0..6 {
+            let path = root.join(format!("{}.png", i));
+            std::fs::write(&path, &png).unwrap();
+            paths.push(path.to_string_lossy().to_string());
+        }
+        paths.push(root.join("notes.txt").to_string_lossy().to_string());
+        
+        let params = paths.iter()
+            .map(|p| serde_json::to_value(ImportTask { source_path: p.clone() }).unwrap())
+            .collect();
+        let job = Job::new(JOB_KIND, serde_json::Value::Null, params, 3);
+        let mut progress = 0;
+        let job = JobQueue::new().run_local(
+            job,
+            |params| {
+                let task: ImportTask = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+                let image = import_image(root, Path::new(&task.source_path))?;
+                serde_json::to_value(image).map_err(|e| e.to_string())
+            },
+            |_, _| progress += 1,
+        ).unwrap();
+        
+        assert_eq!(progress, 7);
+        assert_eq!(job.tasks[6].error.as_deref(), Some("File not found"));
+        let images: Vec<ImportedImage> = job.tasks[..6].iter()
+            .map(|t| serde_json::from_value(t.result.clone().unwrap()).unwrap())
+            .collect();
+        assert!(images.iter().all(|i| (i.width, i.height) == (8, 6) && root.join(&i.relative_path).exists()));
+        assert!(images.iter().all(|i| i.content_hash == images[0].content_hash));
+        assert!(job.tasks.iter().all(|t| t.status != TaskStatus::Running));
+    }
+
+    #[test]
+    fn test_import_unicode_names() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        let mut png = Vec::new();
+        image::RgbImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        
+        let source = root.join("参考 资料").join("Ünïcödé 🎨");
+        std::fs::create_dir_all(&source).unwrap();
+        for name in ["猫 🐱.PNG", "мудборд.png", "🌅.jpg"] {
+            std::fs::write(source.join(name), &png).unwrap();
+            let image = import_image(root, &source.join(name)).unwrap();
+            assert_eq!(image.original_name, name);
+            assert!(image.relative_path.ends_with(&format!(".{}", fs_paths::extension(Path::new(name)).unwrap())));
+            assert!(root.join(&image.relative_path).is_file());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_import_non_utf8_name() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        let mut png = Vec::new();
+        image::RgbImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        
+        let path = root.join(OsStr::from_bytes(b"caf\xe9.png"));
+        std::fs::write(&path, &png).unwrap();
+        let image = import_image(root, &path).unwrap();
+        assert_eq!(image.original_name, "caf\u{fffd}.png");
+        assert_eq!((image.width, image.height), (2, 2));
+    }
+}
@@ -0,0 +1,116 @@
+//! Blocking file and database work for async commands.
+//!
+//! Heavy commands (project load/save, imports, thumbnail scans) are `async`
+//! and hand their work to `BlockingPool::run`, which moves it onto Tauri's
+//! blocking threads so the IPC thread stays responsive. A semaphore caps how
+//! many run at once, so a burst of imports can't starve a save.
+//!
+//! Project saves go through `run_serialized`: saves of one project queue on
+//! that project's lane and commit in the order they were requested, so an
+//! older autosave can't land over a newer save.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use crate::error::AppError;
+
+/// Blocking commands running at the same time
+const MAX_CONCURRENT_BLOCKING: usize = 4;
+
+/// Runs closures on the blocking thread pool, a limited number at a time
+pub struct BlockingPool {
+    permits: Semaphore,
+    /// One FIFO lock per project root
+    lanes: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl Default for BlockingPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockingPool {
+    pub fn new() -> Self {
+        Self::with_limit(MAX_CONCURRENT_BLOCKING)
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Self { permits: Semaphore::new(limit.max(1)), lanes: Mutex::new(HashMap::new()) }
+    }
+
+    /// Run `work` on a blocking thread once a slot is free.
+    pub async fn run<T, F>(&self, work: F) -> Result<T, AppError>
+    where
+        F: FnOnce() -> Result<T, AppError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let _permit = self.permits.acquire().await
+            .map_err(|_| AppError::Unknown("Blocking pool closed".to_string()))?;
+        tauri::async_runtime::spawn_blocking(work)
+            .await
+            .map_err(|e| AppError::Unknown(format!("Blocking task failed: {}", e)))?
+    }
+
+    /// Run `work` like `run`, after the earlier work on the same `lane`
+    /// (a project root) finished.
+    pub async fn run_serialized<T, F>(&self, lane: &Path, work: F) -> Result<T, AppError>
+    where
+        F: FnOnce() -> Result<T, AppError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let lane = self.lanes.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?
+            .entry(lane.to_path_buf())
+            .or_default()
+            .clone();
+        // Tokio's mutex is fair, so waiters get their turn in arrival order
+        let _turn = lane.lock().await;
+        self.run(work).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_returns_result() {
+        let pool = BlockingPool::with_limit(1);
+        let value = tauri::async_runtime::block_on(pool.run(|| Ok(21 * 2))).unwrap();
+        assert_eq!(value, 42);
+
+        let failed = tauri::async_runtime::block_on(pool.run(|| Err::<(), _>(AppError::NotFound("x".to_string()))));
+        assert!(matches!(failed, Err(AppError::NotFound(_))));
+
+        // The permit is released after each run
+        assert_eq!(pool.permits.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_run_serialized_excludes() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let pool = Arc::new(BlockingPool::with_limit(4));
+        let busy = Arc::new(AtomicBool::new(false));
+        let runs: Vec<_> = (0..4).map(|_| {
+            let (pool, busy) = (pool.clone(), busy.clone());
+            tauri::async_runtime::spawn(async move {
+                pool.run_serialized(Path::new("/projects/a"), move || {
+                    // Never two saves of the project at once
+                    assert!(!busy.swap(true, Ordering::SeqCst));
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    busy.store(false, Ordering::SeqCst);
+                    Ok(())
+                }).await
+            })
+        }).collect();
+        tauri::async_runtime::block_on(async {
+            for run in runs {
+                run.await.unwrap().unwrap();
+            }
+        });
+        assert_eq!(pool.lanes.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,292 @@
+//! Brand kit compliance: compare image palettes and copy against the
+//! project's brand kit, and render the kit as agent context.
+
+use std::path::Path;
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::{Asset, BrandKit, SynniaProject};
+use crate::services::imaging::{self, PaletteColor};
+use crate::services::text_asset;
+use ts_rs::TS;
+
+/// Project settings key holding the brand kit
+pub const SETTINGS_KEY: &str = "brandKit";
+
+const DEFAULT_COLOR_TOLERANCE: f64 = 20.0;
+
+/// Palette colors below this share are too small to judge
+const MIN_JUDGED_SHARE: f64 = 0.05;
+
+/// Colors this close to gray are treated as neutral and always allowed
+const NEUTRAL_CHROMA: f64 = 10.0;
+
+const PALETTE_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BrandIssue {
+    /// "color" | "font" | "word"
+    pub kind: String,
+    pub value: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BrandComplianceReport {
+    pub asset_id: String,
+    pub compliant: bool,
+    /// Share of judged image pixels within tolerance of a brand color
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_score: Option<f64>,
+    pub palette: Vec<PaletteColor>,
+    pub issues: Vec<BrandIssue>,
+}
+
+/// Brand kit stored in project settings, if any
+pub fn kit_from_project(project: &SynniaProject) -> Option<BrandKit> {
+    project.settings.as_ref()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+/// Check one asset: image palette against brand colors, copy against banned
+/// words, declared fonts against brand fonts.
+pub fn check_asset(project_root: &Path, asset: &Asset, kit: &BrandKit) -> Result<BrandComplianceReport, AppError> {
+    let mut issues = Vec::new();
+    let mut palette = Vec::new();
+    let mut color_score = None;
+    
+    if let Some(src) = asset.value.get("src").and_then(|s| s.as_str()) {
+        let path = imaging::resolve_image_source(project_root, src)
+            .ok_or_else(|| AppError::Unknown(format!("Image is not stored in the project: {}", src)))?;
+        let img = image::open(&path)
+            .map_err(|e| AppError::Unknown(format!("Failed to load image: {}", e)))?;
+        palette = imaging::extract_palette(&img, PALETTE_SIZE);
+        
+        if !kit.colors.is_empty() {
+            let (score, color_issues) = check_palette(&palette, kit);
+            color_score = score;
+            issues.extend(color_issues);
+        }
+    }
+    
+    if let Some(text) = text_asset::text_content(asset) {
+        issues.extend(check_words(text, &kit.banned_words));
+    }
+    
+    let font = asset.config.as_ref()
+        .and_then(|c| c.get("extra"))
+        .and_then(|e| e.get("fontFamily"))
+        .and_then(|f| f.as_str());
+    if let Some(font) = font {
+        if !kit.fonts.is_empty() && !kit.fonts.iter().any(|f| f.eq_ignore_ascii_case(font)) {
+            issues.push(BrandIssue {
+                kind: "font".to_string(),
+                value: font.to_string(),
+                message: format!("Font \"{}\" is not in the brand kit", font),
+            });
+        }
+    }
+    
+    Ok(BrandComplianceReport {
+        asset_id: asset.id.clone(),
+        compliant: issues.is_empty(),
+        color_score,
+        palette,
+        issues,
+    })
+}
+
+/// Brand kit as a prompt section for agents
+pub fn agent_context(kit: &BrandKit, project: &SynniaProject) -> String {
+    let mut lines = vec!["BRAND KIT (follow these constraints):".to_string()];
+    if !kit.colors.is_empty() {
+        lines.push(format!("- Approved colors: {}", kit.colors.join(", ")));
+    }
+    if !kit.fonts.is_empty() {
+        lines.push(format!("- Approved fonts: {}", kit.fonts.join(", ")));
+    }
+    if let Some(tone) = kit.tone.as_deref().filter(|t| !t.trim().is_empty()) {
+        lines.push(format!("- Tone of voice: {}", tone.trim()));
+    }
+    if !kit.banned_words.is_empty() {
+        lines.push(format!("- Never use: {}", kit.banned_words.join(", ")));
+    }
+    let logos: Vec<&str> = kit.logo_asset_ids.iter()
+        .filter_map(|id| project.assets.get(id))
+        .map(|a| a.sys.name.as_str())
+        .collect();
+    if !logos.is_empty() {
+        lines.push(format!("- Logo assets: {}", logos.join(", ")));
+    }
+    lines.join("\n")
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn check_palette(palette: &[PaletteColor], kit: &BrandKit) -> (Option<f64>, Vec<BrandIssue>) {
+    let tolerance = kit.color_tolerance.unwrap_or(DEFAULT_COLOR_TOLERANCE);
+    let brand: Vec<[f64; 3]> = kit.colors.iter().filter_map(|c| parse_hex(c)).map(to_lab).collect();
+    
+    let mut judged = 0.0;
+    let mut on_brand = 0.0;
+    let mut issues = Vec::new();
+    
+    for color in palette.iter().filter(|c| c.share >= MIN_JUDGED_SHARE) {
+        let Some(rgb) = parse_hex(&color.hex) else { continue };
+        let lab = to_lab(rgb);
+        if chroma(lab) < NEUTRAL_CHROMA {
+            continue;
+        }
+        judged += color.share;
+        
+        let nearest = brand.iter().map(|b| delta_e(lab, *b)).fold(f64::MAX, f64::min);
+        if nearest <= tolerance {
+            on_brand += color.share;
+        } else {
+            issues.push(BrandIssue {
+                kind: "color".to_string(),
+                value: color.hex.clone(),
+                message: format!("{} covers {:.0}% of the image and is not a brand color", color.hex, color.share * 100.0),
+            });
+        }
+    }
+    
+    let score = if judged > 0.0 { Some(on_brand / judged) } else { None };
+    (score, issues)
+}
+
+fn check_words(text: &str, banned: &[String]) -> Vec<BrandIssue> {
+    let lower = text.to_lowercase();
+    banned.iter()
+        .filter(|w| !w.trim().is_empty())
+        .filter(|w| contains_phrase(&lower, &w.trim().to_lowercase()))
+        .map(|w| BrandIssue {
+            kind: "word".to_string(),
+            value: w.clone(),
+            message: format!("\"{}\" is on the banned words list", w),
+        })
+        .collect()
+}
+
+/// Whole-word/phrase match
+fn contains_phrase(haystack: &str, phrase: &str) -> bool {
+    haystack.match_indices(phrase).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let value = u32::from_str_radix(&hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+/// sRGB → CIE L*a*b* (D65)
+fn to_lab(rgb: [u8; 3]) -> [f64; 3] {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    let (r, g, b) = (linear(rgb[0]), linear(rgb[1]), linear(rgb[2]));
+    
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    
+    let f = |t: f64| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn delta_e(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+fn chroma(lab: [f64; 3]) -> f64 {
+    (lab[1].powi(2) + lab[2].powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use image::{Rgba, RgbaImage};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn kit() -> BrandKit {
+        BrandKit {
+            colors: vec!["#e30613".to_string(), "#fff".to_string()],
+            fonts: vec!["Inter".to_string()],
+            banned_words: vec!["cheap".to_string(), "best ever".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn asset(value: serde_json::Value, config: Option<serde_json::Value>) -> Asset {
+        Asset {
+            id: "a".to_string(),
+            value_type: ValueType::Record,
+            value,
+            value_meta: None,
+            config,
+            sys: AssetSysMetadata {
+                name: "A".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_check_image_palette() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        
+        // 60% brand red, 20% white (neutral), 20% green
+        let img = RgbaImage::from_fn(10, 10, |x, _| match x {
+            0..=5 => Rgba([0xe0, 0x08, 0x14, 255]),
+            6..=7 => Rgba([255, 255, 255, 255]),
+            _ => Rgba([0, 160, 0, 255]),
+        });
+        img.save(dir.path().join("assets/ad.png")).unwrap();
+        
+        let report = check_asset(dir.path(), &asset(json!({ "src": "assets/ad.png" }), None), &kit()).unwrap();
+        assert!(!report.compliant);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, "color");
+        assert!((report.color_score.unwrap() - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_check_text_and_font() {
+        let text = asset(
+            json!({ "content": "Our best ever sale, not cheapest", "format": "plain" }),
+            Some(json!({ "extra": { "fontFamily": "Comic Sans" } })),
+        );
+        let report = check_asset(Path::new("/unused"), &text, &kit()).unwrap();
+        let values: Vec<&str> = report.issues.iter().map(|i| i.value.as_str()).collect();
+        assert_eq!(values, vec!["best ever", "Comic Sans"]);
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("#fff"), Some([255, 255, 255]));
+        assert_eq!(parse_hex("e30613"), Some([0xe3, 0x06, 0x13]));
+        assert_eq!(parse_hex("#12"), None);
+    }
+}
@@ -0,0 +1,256 @@
+//! Spending limits for provider calls dispatched through the job queue.
+//!
+//! Each project prices one task of each job kind and may cap a single run
+//! (one job) and the project as a whole. Before a job's tasks go out its
+//! cost is estimated; a job that would cross either limit is held in the
+//! queue and announced as `budget:exceeded` until someone confirms or
+//! cancels it. Dispatched jobs are charged to the project's spend ledger by
+//! their estimate and settled by the tasks that actually ran.
+
+use std::collections::HashMap;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::io_sqlite;
+use crate::services::job_queue::{Job, TaskStatus};
+use ts_rs::TS;
+
+/// Settings key of the budget settings
+pub const SETTINGS_KEY: &str = "budget";
+
+/// Per-project budget settings; limits are in the same unit as the task costs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BudgetSettings {
+    /// Total spend allowed for the project; none means unlimited
+    pub project_limit: Option<f64>,
+    /// Spend allowed for a single run; none means unlimited
+    pub run_limit: Option<f64>,
+    /// Estimated cost of one task by job kind, e.g. "generation-grid"
+    pub task_costs: HashMap<String, f64>,
+    /// Cost of one task of a kind without its own price
+    pub default_task_cost: f64,
+}
+
+impl BudgetSettings {
+    /// Drop negative and non-finite amounts
+    pub fn normalized(self) -> Self {
+        let amount = |v: f64| v.is_finite().then(|| v.max(0.0));
+        Self {
+            project_limit: self.project_limit.and_then(amount),
+            run_limit: self.run_limit.and_then(amount),
+            task_costs: self.task_costs.into_iter()
+                .filter_map(|(kind, cost)| amount(cost).map(|cost| (kind, cost)))
+                .collect(),
+            default_task_cost: amount(self.default_task_cost).unwrap_or(0.0),
+        }
+    }
+
+    pub fn task_cost(&self, kind: &str) -> f64 {
+        self.task_costs.get(kind).copied().unwrap_or(self.default_task_cost)
+    }
+
+    /// Estimated cost of running every task of `job`
+    pub fn estimate(&self, job: &Job) -> f64 {
+        self.task_cost(&job.kind) * job.tasks.len() as f64
+    }
+}
+
+/// Which limit a job would cross
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetScope {
+    Run,
+    Project,
+}
+
+/// Payload of `budget:exceeded`: a job held until confirmed or cancelled
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetExceeded {
+    pub job_id: String,
+    pub kind: String,
+    pub tasks: usize,
+    pub estimated_cost: f64,
+    /// Project spend before this job
+    pub spent: f64,
+    pub limit: f64,
+    pub scope: BudgetScope,
+}
+
+/// Settings and spend of the open project
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub settings: BudgetSettings,
+    pub spent: f64,
+    /// Left of the project limit, if there is one
+    pub remaining: Option<f64>,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<BudgetSettings, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_settings(conn: &Connection, settings: BudgetSettings) -> Result<BudgetSettings, AppError> {
+    let settings = settings.normalized();
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(&settings)?)?;
+    Ok(settings)
+}
+
+pub fn get_status(conn: &Connection) -> Result<BudgetStatus, AppError> {
+    let settings = get_settings(conn)?;
+    let spent = spent(conn)?;
+    let remaining = settings.project_limit.map(|limit| (limit - spent).max(0.0));
+    Ok(BudgetStatus { settings, spent, remaining })
+}
+
+/// Total charged to the project so far
+pub fn spent(conn: &Connection) -> Result<f64, AppError> {
+    conn.query_row("SELECT COALESCE(SUM(cost), 0) FROM budget_spend", [], |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to read spend: {}", e)))
+}
+
+/// The limit `job` would cross if it ran, checking the run limit first.
+pub fn check(conn: &Connection, job: &Job) -> Result<Option<BudgetExceeded>, AppError> {
+    let settings = get_settings(conn)?;
+    let estimated_cost = settings.estimate(job);
+    if estimated_cost <= 0.0 {
+        return Ok(None);
+    }
+    let spent = spent(conn)?;
+
+    let crossed = match (settings.run_limit, settings.project_limit) {
+        (Some(limit), _) if estimated_cost > limit => Some((limit, BudgetScope::Run)),
+        (_, Some(limit)) if spent + estimated_cost > limit => Some((limit, BudgetScope::Project)),
+        _ => None,
+    };
+    Ok(crossed.map(|(limit, scope)| BudgetExceeded {
+        job_id: job.id.clone(),
+        kind: job.kind.clone(),
+        tasks: job.tasks.len(),
+        estimated_cost,
+        spent,
+        limit,
+        scope,
+    }))
+}
+
+/// Charge a job's estimated cost to the ledger as it is dispatched.
+pub fn charge(conn: &Connection, job: &Job) -> Result<f64, AppError> {
+    let unit_cost = get_settings(conn)?.task_cost(&job.kind);
+    let cost = unit_cost * job.tasks.len() as f64;
+    conn.execute(
+        "INSERT OR REPLACE INTO budget_spend (job_id, kind, unit_cost, tasks, cost, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![job.id, job.kind, unit_cost, job.tasks.len() as i64, cost, chrono::Utc::now().timestamp_millis()],
+    ).map_err(|e| AppError::Io(format!("Failed to record spend: {}", e)))?;
+    Ok(cost)
+}
+
+/// Replace a settled job's estimate with the cost of the tasks that ran
+/// (cancelled tasks are refunded). Does nothing for uncharged jobs.
+pub fn settle(conn: &Connection, job: &Job) -> Result<(), AppError> {
+    let ran = job.tasks.iter()
+        .filter(|t| matches!(t.status, TaskStatus::Success | TaskStatus::Error))
+        .count() as i64;
+    conn.execute(
+        "UPDATE budget_spend SET tasks = ?2, cost = unit_cost * ?2 WHERE job_id = ?1",
+        params![job.id, ran],
+    ).map_err(|e| AppError::Io(format!("Failed to settle spend: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn job(kind: &str, tasks: usize) -> Job {
+        Job::new(kind, json!({}), (0..tasks).map(|i| json!(i)).collect(), 2)
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        assert!(check(&conn, &job("generation-grid", 64)).unwrap().is_none(), "no prices, no limits");
+
+        save_settings(&conn, BudgetSettings {
+            project_limit: Some(1.0),
+            run_limit: Some(0.5),
+            task_costs: HashMap::from([("generation-grid".to_string(), 0.04)]),
+            default_task_cost: 0.01,
+        }).unwrap();
+
+        let over = check(&conn, &job("generation-grid", 16)).unwrap().expect("0.64 exceeds the run limit");
+        assert_eq!(over.scope, BudgetScope::Run);
+        assert!((over.estimated_cost - 0.64).abs() < 1e-9);
+        assert!(check(&conn, &job("translation", 40)).unwrap().is_none());
+
+        for _ in 0..2 {
+            charge(&conn, &job("generation-grid", 12)).unwrap();
+        }
+        let over = check(&conn, &job("generation-grid", 6)).unwrap().expect("0.96 + 0.24 exceeds the project limit");
+        assert_eq!(over.scope, BudgetScope::Project);
+        assert!((over.spent - 0.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_settle_refunds_cancelled_tasks() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        save_settings(&conn, BudgetSettings { default_task_cost: 0.5, ..Default::default() }).unwrap();
+
+        let mut job = job("generation-grid", 4);
+        charge(&conn, &job).unwrap();
+        assert_eq!(spent(&conn).unwrap(), 2.0);
+
+        job.tasks[0].status = TaskStatus::Success;
+        job.tasks[1].status = TaskStatus::Error;
+        job.tasks[2].status = TaskStatus::Cancelled;
+        job.tasks[3].status = TaskStatus::Cancelled;
+        settle(&conn, &job).unwrap();
+        assert_eq!(spent(&conn).unwrap(), 1.0);
+
+        let status = get_status(&conn).unwrap();
+        assert_eq!(status.remaining, None);
+    }
+
+    #[test]
+    fn test_normalized() {
+        let settings = BudgetSettings {
+            project_limit: Some(f64::NAN),
+            run_limit: Some(-3.0),
+            task_costs: HashMap::from([("a".to_string(), f64::INFINITY), ("b".to_string(), 0.2)]),
+            default_task_cost: -1.0,
+        }.normalized();
+        assert_eq!(settings.project_limit, None);
+        assert_eq!(settings.run_limit, Some(0.0));
+        assert_eq!(settings.task_costs.len(), 1);
+        assert_eq!(settings.default_task_cost, 0.0);
+    }
+
+    #[test]
+    fn test_settings_survive_project_save() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Budget").unwrap();
+        let conn = crate::services::database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let saved = save_settings(&conn, BudgetSettings { project_limit: Some(50.0), run_limit: Some(5.0), default_task_cost: 0.1, ..Default::default() }).unwrap();
+
+        // The frontend's autosave sends no settings
+        project.settings = Some(HashMap::new());
+        io_sqlite::save_project_sqlite(dir.path(), &project).unwrap();
+
+        assert_eq!(get_settings(&conn).unwrap(), saved);
+        assert_ne!(saved, Default::default());
+    }
+}
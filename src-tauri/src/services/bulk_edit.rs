@@ -0,0 +1,211 @@
+//! Bulk metadata edits: one patch of name, tag and custom field changes
+//! applied to many assets at once.
+//!
+//! The whole patch runs in one transaction and is recorded as a single
+//! activity entry. Names, tags and fields live outside the versioned asset
+//! value, so no history snapshots are taken and existing history stays as
+//! it is. Name templates number the assets in the order given, e.g.
+//! `"Shot {n}"` gives "Shot 01" ... "Shot 80".
+
+use std::collections::HashMap;
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use serde_json::Value;
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::custom_fields::{self, FieldTarget};
+use crate::services::{io_sqlite, tags};
+use ts_rs::TS;
+
+/// Upper bound of assets per patch
+pub const MAX_ASSETS: usize = 5000;
+const MAX_NAME_LEN: usize = 200;
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetPatch {
+    /// New name; `{n}` is the asset's 1-based position (zero-padded to the
+    /// width of the count) and `{name}` its current name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Hand-added tags to put on every asset
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    /// Tags to take off every asset, whatever added them
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    /// Custom field values by field ID; null or "" clears the field
+    #[serde(default)]
+    #[ts(type = "Record<string, string | number | null>")]
+    pub fields: HashMap<String, Value>,
+}
+
+impl AssetPatch {
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.add_tags.is_empty() && self.remove_tags.is_empty() && self.fields.is_empty()
+    }
+}
+
+/// Apply `patch` to every asset in `asset_ids`, all or nothing. Returns the
+/// assets as they are afterwards.
+pub fn bulk_update(conn: &mut Connection, asset_ids: &[String], patch: &AssetPatch) -> Result<Vec<Asset>, AppError> {
+    if asset_ids.is_empty() || patch.is_empty() {
+        return Err(AppError::InvalidInput("Nothing to update".to_string()));
+    }
+    if asset_ids.len() > MAX_ASSETS {
+        return Err(AppError::InvalidInput(format!("At most {} assets per update", MAX_ASSETS)));
+    }
+    if let Some(name) = &patch.name {
+        if name.trim().is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(AppError::InvalidInput(format!("Names need 1 to {} characters", MAX_NAME_LEN)));
+        }
+    }
+    let add_tags: Vec<String> = patch.add_tags.iter().filter_map(|t| tags::normalize_tag(t)).collect();
+    let remove_tags: Vec<String> = patch.remove_tags.iter().filter_map(|t| tags::normalize_tag(t)).collect();
+
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let width = asset_ids.len().to_string().len();
+    let mut updated = Vec::with_capacity(asset_ids.len());
+    for (index, asset_id) in asset_ids.iter().enumerate() {
+        let mut asset = io_sqlite::get_asset(&tx, asset_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+
+        if let Some(template) = &patch.name {
+            let name = template
+                .replace("{name}", &asset.sys.name)
+                .replace("{n}", &format!("{:0width$}", index + 1, width = width))
+                .trim()
+                .to_string();
+            if name != asset.sys.name {
+                asset.sys.name = name;
+                asset.sys.updated_at = now;
+                tx.execute(
+                    "UPDATE assets SET sys_json = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![serde_json::to_string(&asset.sys)?, now, asset_id],
+                ).map_err(|e| AppError::Io(format!("Failed to rename asset: {}", e)))?;
+            }
+        }
+        for tag in &add_tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO asset_tags (asset_id, tag, source, confidence, created_at)
+                 VALUES (?1, ?2, ?3, NULL, ?4)",
+                params![asset_id, tag, tags::USER_SOURCE, now],
+            ).map_err(|e| AppError::Io(format!("Failed to save tag: {}", e)))?;
+        }
+        for tag in &remove_tags {
+            tx.execute("DELETE FROM asset_tags WHERE asset_id = ?1 AND tag = ?2", params![asset_id, tag])
+                .map_err(|e| AppError::Io(format!("Failed to remove tag: {}", e)))?;
+        }
+        for (field_id, value) in &patch.fields {
+            custom_fields::set_value(&tx, FieldTarget::Asset, asset_id, field_id, value)?;
+        }
+        updated.push(asset);
+    }
+
+    activity::record(&tx, &summary(&updated, patch))?;
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+    Ok(updated)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// "Renamed and tagged 80 assets", targeting the asset if there's only one
+fn summary(assets: &[Asset], patch: &AssetPatch) -> NewActivity {
+    let mut changes = Vec::new();
+    if patch.name.is_some() {
+        changes.push("renamed");
+    }
+    if !patch.add_tags.is_empty() || !patch.remove_tags.is_empty() {
+        changes.push("tagged");
+    }
+    if !patch.fields.is_empty() {
+        changes.push("set fields of");
+    }
+    let verbs = match changes.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => "edited".to_string(),
+    };
+    // Verbs are ASCII
+    let verbs = verbs[..1].to_uppercase() + &verbs[1..];
+
+    match assets {
+        [asset] => NewActivity::new(ActivityKind::AssetEdited, "user", format!("{} {}", verbs, asset.sys.name))
+            .target(&asset.id),
+        _ => NewActivity::new(ActivityKind::AssetEdited, "user", format!("{} {} assets", verbs, assets.len()))
+            .source("bulk"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::activity::ActivityFilter;
+    use crate::services::custom_fields::{CustomFieldInput, FieldKind};
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn setup(count: usize) -> (tempfile::TempDir, Connection, Vec<String>) {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        let ids: Vec<String> = (1..=count).map(|i| format!("frame-{}", i)).collect();
+        for id in &ids {
+            let sys = serde_json::json!({ "name": format!("IMG_{}", id), "createdAt": 0, "updatedAt": 0, "source": "import" });
+            conn.execute(
+                "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+                 VALUES (?1, '\"record\"', 'h', '{\"src\":\"assets/a.png\"}', ?2, 0)",
+                params![id, sys.to_string()],
+            ).unwrap();
+        }
+        (dir, conn, ids)
+    }
+
+    #[test]
+    fn test_bulk_update() {
+        let (_dir, mut conn, ids) = setup(12);
+        tags::set_tags(&conn, &ids[0], "auto", &[("blurry".to_string(), Some(0.4))]).unwrap();
+        let status = custom_fields::save_field(&conn, &CustomFieldInput {
+            id: None,
+            name: "Status".to_string(),
+            kind: FieldKind::Select,
+            options: vec!["approved".to_string()],
+        }).unwrap();
+
+        let patch = AssetPatch {
+            name: Some("Shot {n}".to_string()),
+            add_tags: vec!["Storyboard".to_string()],
+            remove_tags: vec!["blurry".to_string()],
+            fields: HashMap::from([(status.id.clone(), serde_json::json!("approved"))]),
+        };
+        let updated = bulk_update(&mut conn, &ids, &patch).unwrap();
+        assert_eq!(updated[0].sys.name, "Shot 01");
+        assert_eq!(io_sqlite::get_asset(&conn, &ids[11]).unwrap().unwrap().sys.name, "Shot 12");
+        let first_tags: Vec<String> = tags::get_tags(&conn, &ids[0]).unwrap().into_iter().map(|t| t.tag).collect();
+        assert_eq!(first_tags, ["storyboard"]);
+        assert_eq!(custom_fields::get_values(&conn, &ids[5]).unwrap()[0].value, serde_json::json!("approved"));
+        assert_eq!(history_count(&conn), 0, "metadata edits don't snapshot values");
+
+        let feed = activity::get_activity(&conn, None, &ActivityFilter::default()).unwrap();
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].summary, "Renamed, tagged and set fields of 12 assets");
+
+        // All or nothing: an unknown asset rolls the whole patch back
+        let rename = AssetPatch { name: Some("{name} final".to_string()), ..Default::default() };
+        let missing = [ids[0].clone(), "missing".to_string()];
+        assert!(matches!(bulk_update(&mut conn, &missing, &rename), Err(AppError::NotFound(_))));
+        assert_eq!(io_sqlite::get_asset(&conn, &ids[0]).unwrap().unwrap().sys.name, "Shot 01");
+
+        assert!(matches!(bulk_update(&mut conn, &ids, &AssetPatch::default()), Err(AppError::InvalidInput(_))));
+    }
+
+    fn history_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM asset_history", [], |row| row.get(0)).unwrap()
+    }
+}
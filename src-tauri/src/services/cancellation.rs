@@ -0,0 +1,211 @@
+//! Registry of in-flight operations that can be cancelled by ID.
+//!
+//! Long-running commands (agent runs, proxy requests, exports) register under
+//! an ID the frontend picks and get a `CancellationToken`. Async work is raced
+//! against the token with `run`; blocking work polls `check` between steps.
+//! Downloads keep their own flags in the `DownloadManager`; `cancel_operation`
+//! falls back to it for IDs not registered here.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use serde::Serialize;
+use tokio::sync::Notify;
+use crate::error::AppError;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum OperationKind {
+    AgentRun,
+    ProxyRequest,
+    Export,
+}
+
+/// A registered operation, as listed to the frontend
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub label: String,
+    #[ts(type = "number")]
+    pub started_at: i64,
+}
+
+/// Shared flag telling an operation to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// For blocking work: errors once the operation was cancelled.
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(cancelled_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // Registered before the check so a cancel in between isn't missed
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `operation` until it finishes or the token is cancelled, in which
+    /// case it is dropped (aborting its in-flight requests).
+    pub async fn run<T>(&self, operation: impl Future<Output = Result<T, AppError>>) -> Result<T, AppError> {
+        let mut operation = pin!(operation);
+        let mut cancelled = pin!(self.cancelled());
+        std::future::poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(cancelled_error()));
+            }
+            operation.as_mut().poll(cx)
+        }).await
+    }
+}
+
+/// Registered operations by ID, with their tokens
+type Operations = HashMap<String, (OperationInfo, CancellationToken)>;
+
+/// Operations currently registered
+#[derive(Default)]
+pub struct CancellationRegistry {
+    operations: Mutex<Operations>,
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an operation under `id` (a new ID when none is given). It
+    /// stays registered until the returned guard is dropped.
+    pub fn register(&self, id: Option<String>, kind: OperationKind, label: impl Into<String>) -> Result<OperationGuard<'_>, AppError> {
+        let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let token = CancellationToken::new();
+        let mut operations = self.lock()?;
+        if operations.contains_key(&id) {
+            return Err(AppError::Unknown(format!("Operation already running: {}", id)));
+        }
+        let info = OperationInfo {
+            id: id.clone(),
+            kind,
+            label: label.into(),
+            started_at: chrono::Utc::now().timestamp_millis(),
+        };
+        operations.insert(id.clone(), (info, token.clone()));
+        Ok(OperationGuard { registry: self, id, token })
+    }
+
+    /// Cancel an operation. Returns false if none with that ID is registered.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.lock().ok().and_then(|ops| ops.get(id).map(|(_, token)| token.clone())) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Registered operations, oldest first
+    pub fn active(&self) -> Vec<OperationInfo> {
+        let mut infos: Vec<OperationInfo> = self.lock()
+            .map(|ops| ops.values().map(|(info, _)| info.clone()).collect())
+            .unwrap_or_default();
+        infos.sort_by_key(|info| info.started_at);
+        infos
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Operations>, AppError> {
+        self.operations.lock().map_err(|_| AppError::Unknown("Operation registry lock poisoned".to_string()))
+    }
+}
+
+/// Keeps an operation registered; unregisters it when dropped
+pub struct OperationGuard<'a> {
+    registry: &'a CancellationRegistry,
+    id: String,
+    token: CancellationToken,
+}
+
+impl OperationGuard<'_> {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut operations) = self.registry.operations.lock() {
+            operations.remove(&self.id);
+        }
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn cancelled_error() -> AppError {
+    AppError::Unknown("Operation cancelled".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_cancel() {
+        let registry = CancellationRegistry::new();
+        let guard = registry.register(Some("export-1".to_string()), OperationKind::Export, "PDF").unwrap();
+        assert!(registry.register(Some("export-1".to_string()), OperationKind::Export, "PDF").is_err());
+        assert_eq!(registry.active().len(), 1);
+        
+        assert!(guard.token().check().is_ok());
+        assert!(registry.cancel("export-1"));
+        assert!(guard.token().check().is_err());
+        
+        drop(guard);
+        assert!(registry.active().is_empty());
+        assert!(!registry.cancel("export-1"));
+    }
+
+    #[test]
+    fn test_run_stops_on_cancel() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = tauri::async_runtime::block_on(token.run(std::future::pending::<Result<(), AppError>>()));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Canvases: separate boards (research / moodboard / storyboard ...) inside
+//! one project, sharing its asset pool.
+//!
+//! Nodes and edges carry a `canvas_id` and each canvas keeps its own
+//! viewport. The most recently opened canvas is the active one: project
+//! loads and saves use it unless told otherwise, and nodes created by
+//! backend features land on it.
+
+use std::collections::HashSet;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::{Canvas, Position, Viewport};
+use crate::services::{io_sqlite, node_locks};
+use ts_rs::TS;
+
+/// Canvas of projects created before canvases existed
+pub const DEFAULT_CANVAS_ID: &str = "main";
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedNodes {
+    /// Nodes moved, including children of moved groups
+    pub nodes: usize,
+    /// Edges moved along with both of their ends
+    pub edges: usize,
+    /// Edges dropped because they would have crossed canvases
+    pub edges_removed: usize,
+}
+
+/// The most recently opened canvas.
+pub fn active_canvas(conn: &Connection) -> Result<String, AppError> {
+    let id = conn.query_row(
+        "SELECT id FROM canvases ORDER BY opened_at DESC, sort_order LIMIT 1",
+        [],
+        |row| row.get(0),
+    ).optional().map_err(|e| AppError::Io(format!("Failed to query canvases: {}", e)))?;
+    Ok(id.unwrap_or_else(|| DEFAULT_CANVAS_ID.to_string()))
+}
+
+pub fn get(conn: &Connection, canvas_id: &str) -> Result<Option<Canvas>, AppError> {
+    conn.query_row(
+        "SELECT id, name, sort_order, x, y, zoom, created_at FROM canvases WHERE id = ?1",
+        params![canvas_id],
+        row_to_canvas,
+    ).optional().map_err(|e| AppError::Io(format!("Failed to load canvas: {}", e)))
+}
+
+/// Canvases in tab order.
+pub fn list(conn: &Connection) -> Result<Vec<Canvas>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, sort_order, x, y, zoom, created_at FROM canvases ORDER BY sort_order, created_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map([], row_to_canvas)
+        .map_err(|e| AppError::Io(format!("Failed to query canvases: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load canvases: {}", e)))
+}
+
+/// Add an empty canvas after the existing ones.
+pub fn create(conn: &Connection, name: &str) -> Result<Canvas, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Unknown("Canvas name is empty".to_string()));
+    }
+    
+    let sort_order: i64 = conn.query_row("SELECT COALESCE(MAX(sort_order) + 1, 0) FROM canvases", [], |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to query canvases: {}", e)))?;
+    let canvas = Canvas {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        sort_order,
+        viewport: Viewport { x: 0.0, y: 0.0, zoom: 1.0 },
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    
+    conn.execute(
+        "INSERT INTO canvases (id, name, sort_order, x, y, zoom, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![canvas.id, canvas.name, canvas.sort_order, canvas.viewport.x, canvas.viewport.y, canvas.viewport.zoom, canvas.created_at],
+    ).map_err(|e| AppError::Io(format!("Failed to create canvas: {}", e)))?;
+    
+    Ok(canvas)
+}
+
+/// Make a canvas the active one.
+pub fn open(conn: &Connection, canvas_id: &str) -> Result<(), AppError> {
+    let updated = conn.execute(
+        "UPDATE canvases SET opened_at = ?1 WHERE id = ?2",
+        params![chrono::Utc::now().timestamp_millis(), canvas_id],
+    ).map_err(|e| AppError::Io(format!("Failed to open canvas: {}", e)))?;
+    if updated == 0 {
+        return Err(AppError::NotFound(format!("Canvas not found: {}", canvas_id)));
+    }
+    Ok(())
+}
+
+/// Move nodes (and everything nested inside them) to another canvas. Nodes
+/// whose parent stays behind are detached at their absolute position; edges
+/// follow when both ends move and are removed when only one does. Locked and
+/// pinned nodes can't change canvas.
+pub fn move_nodes(conn: &mut Connection, node_ids: &[String], canvas_id: &str) -> Result<MovedNodes, AppError> {
+    if get(conn, canvas_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Canvas not found: {}", canvas_id)));
+    }
+    if let Some(missing) = node_ids.iter().find(|id| io_sqlite::get_node(conn, id).ok().flatten().is_none()) {
+        return Err(AppError::NotFound(format!("Node not found: {}", missing)));
+    }
+    
+    let ids = serde_json::to_string(node_ids)?;
+    let moved: HashSet<String> = conn.prepare(
+        "WITH RECURSIVE moved(id) AS (
+             SELECT value FROM json_each(?1)
+             UNION
+             SELECT n.id FROM nodes n JOIN moved m ON n.parent_id = m.id
+         )
+         SELECT id FROM moved"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?
+        .query_map(params![ids], |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))?;
+    // Children travel with their parent, so a locked child holds it back too
+    node_locks::check_canvas_move(conn, &moved)?;
+    
+    // Dropping the savepoint on an early return rolls it back
+    let sp = conn.savepoint()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
+    for id in &moved {
+        let Some(mut node) = io_sqlite::get_node(&sp, id)? else { continue };
+        if node.parent_id.as_ref().is_some_and(|p| !moved.contains(p)) {
+            node.position = absolute_position(&sp, &node.position, node.parent_id.as_deref())?;
+            node.parent_id = None;
+            node.extent = None;
+            io_sqlite::upsert_node(&sp, &node)?;
+        }
+    }
+    
+    let moved_ids = serde_json::to_string(&moved)?;
+    let nodes = sp.execute(
+        "UPDATE nodes SET canvas_id = ?1 WHERE id IN (SELECT value FROM json_each(?2))",
+        params![canvas_id, moved_ids],
+    ).map_err(|e| AppError::Io(format!("Failed to move nodes: {}", e)))?;
+    let edges = sp.execute(
+        "UPDATE edges SET canvas_id = ?1
+         WHERE source IN (SELECT value FROM json_each(?2)) AND target IN (SELECT value FROM json_each(?2))",
+        params![canvas_id, moved_ids],
+    ).map_err(|e| AppError::Io(format!("Failed to move edges: {}", e)))?;
+    let edges_removed = sp.execute(
+        "DELETE FROM edges
+         WHERE (source IN (SELECT value FROM json_each(?1))) != (target IN (SELECT value FROM json_each(?1)))",
+        params![moved_ids],
+    ).map_err(|e| AppError::Io(format!("Failed to remove edges: {}", e)))?;
+    
+    sp.commit().map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+    Ok(MovedNodes { nodes, edges, edges_removed })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn row_to_canvas(row: &rusqlite::Row) -> rusqlite::Result<Canvas> {
+    Ok(Canvas {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        sort_order: row.get(2)?,
+        viewport: Viewport { x: row.get(3)?, y: row.get(4)?, zoom: row.get(5)? },
+        created_at: row.get(6)?,
+    })
+}
+
+/// Child positions are relative to their parent; sum up the chain
+fn absolute_position(conn: &Connection, position: &Position, parent_id: Option<&str>) -> Result<Position, AppError> {
+    let mut position = position.clone();
+    let mut parent = parent_id.map(String::from);
+    let mut seen = HashSet::new();
+    while let Some(id) = parent {
+        // Guard against parent cycles in corrupt data
+        if !seen.insert(id.clone()) {
+            break;
+        }
+        let Some(node) = io_sqlite::get_node(conn, &id)? else { break };
+        position.x += node.position.x;
+        position.y += node.position.y;
+        parent = node.parent_id;
+    }
+    Ok(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SynniaEdge, SynniaNode, SynniaNodeData};
+    use tempfile::tempdir;
+
+    fn node(id: &str, parent: Option<&str>, x: f64) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "text-node".to_string(),
+            position: Position { x, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> SynniaEdge {
+        SynniaEdge {
+            id: format!("{}-{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }
+    }
+
+    #[test]
+    fn test_canvases() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Film").unwrap();
+        project.graph.nodes = vec![
+            node("research", None, 100.0),
+            node("scene", Some("research"), 20.0),
+            node("shot", Some("scene"), 5.0),
+            node("note", None, 0.0),
+        ];
+        project.graph.edges = vec![edge("scene", "shot"), edge("note", "shot")];
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let mut conn = crate::services::database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let storyboard = create(&conn, "Storyboard").unwrap();
+        assert_eq!(list(&conn).unwrap().iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["Main", "Storyboard"]);
+        
+        let moved = move_nodes(&mut conn, &["scene".to_string()], &storyboard.id).unwrap();
+        assert_eq!(moved, MovedNodes { nodes: 2, edges: 1, edges_removed: 1 });
+        
+        // Main is still active; its graph no longer has the moved nodes
+        let main = io_sqlite::load_project_sqlite(root).unwrap();
+        assert_eq!(main.canvas_id.as_deref(), Some(DEFAULT_CANVAS_ID));
+        assert_eq!(main.graph.nodes.len(), 2);
+        assert!(main.graph.edges.is_empty());
+        
+        open(&conn, &storyboard.id).unwrap();
+        let board = io_sqlite::load_project_sqlite(root).unwrap();
+        let scene = board.graph.nodes.iter().find(|n| n.id == "scene").unwrap();
+        assert_eq!((scene.parent_id.as_deref(), scene.position.x), (None, 120.0));
+        assert_eq!(board.graph.edges.len(), 1);
+        
+        // Saving one canvas leaves the other alone
+        io_sqlite::save_project_sqlite(root, &board).unwrap();
+        open(&conn, DEFAULT_CANVAS_ID).unwrap();
+        assert_eq!(io_sqlite::load_project_sqlite(root).unwrap().graph.nodes.len(), 2);
+        
+        // A save of Main that lands after switching to the storyboard still
+        // goes to Main; one without a canvas is refused
+        let mut late = io_sqlite::load_project_sqlite(root).unwrap();
+        open(&conn, &storyboard.id).unwrap();
+        late.graph.nodes.retain(|n| n.id != "note");
+        io_sqlite::save_project_sqlite(root, &late).unwrap();
+        assert_eq!(io_sqlite::load_nodes(&conn, DEFAULT_CANVAS_ID).unwrap().len(), 1);
+        assert_eq!(io_sqlite::load_nodes(&conn, &storyboard.id).unwrap().len(), 2);
+        late.canvas_id = None;
+        assert!(matches!(io_sqlite::save_project_sqlite(root, &late), Err(AppError::InvalidInput(_))));
+        open(&conn, DEFAULT_CANVAS_ID).unwrap();
+        
+        // Locked or pinned nodes, or their parents, stay where they are
+        node_locks::set_node_flags(&conn, "shot", None, Some(true)).unwrap();
+        let refused = move_nodes(&mut conn, &["scene".to_string()], DEFAULT_CANVAS_ID);
+        assert!(matches!(refused, Err(AppError::Forbidden(_))));
+        assert_eq!(io_sqlite::load_nodes(&conn, &storyboard.id).unwrap().len(), 2);
+        
+        assert!(open(&conn, "missing").is_err());
+    }
+}
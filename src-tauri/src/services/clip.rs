@@ -0,0 +1,388 @@
+//! Clipping import for the companion browser extension.
+//!
+//! Payloads arrive on the local server's `POST /clip` endpoint either as
+//! `multipart/form-data` (files plus optional text fields) or as JSON
+//! (`url`, `text`, `html`). Each item becomes an asset plus a node inside
+//! the project's "Inbox" group.
+
+use std::path::Path;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{database, imaging, io_sqlite};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use ts_rs::TS;
+
+/// Stable ID of the inbox group node
+pub const INBOX_GROUP_ID: &str = "synnia-inbox";
+
+/// Max accepted request body for clippings
+pub const MAX_CLIP_SIZE: usize = 50 * 1024 * 1024;
+
+/// JSON clipping payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipRequest {
+    pub url: Option<String>,
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub title: Option<String>,
+}
+
+/// A single item to import
+#[derive(Debug, Clone)]
+pub enum ClipItem {
+    File { filename: String, data: Vec<u8> },
+    Url { url: String, title: Option<String> },
+    Text { text: String, title: Option<String> },
+    Html { html: String, title: Option<String> },
+}
+
+/// Result of importing one clip, sent back to the extension and to the frontend
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipImport {
+    pub asset: Asset,
+    pub node: SynniaNode,
+}
+
+impl ClipRequest {
+    /// Expand the JSON payload into import items (a request may carry several fields).
+    pub fn into_items(self) -> Vec<ClipItem> {
+        let mut items = Vec::new();
+        if let Some(url) = self.url.filter(|s| !s.is_empty()) {
+            items.push(ClipItem::Url { url, title: self.title.clone() });
+        }
+        if let Some(text) = self.text.filter(|s| !s.is_empty()) {
+            items.push(ClipItem::Text { text, title: self.title.clone() });
+        }
+        if let Some(html) = self.html.filter(|s| !s.is_empty()) {
+            items.push(ClipItem::Html { html, title: self.title });
+        }
+        items
+    }
+}
+
+/// Parse a `multipart/form-data` body into import items.
+/// Parts with a filename are files; `url`, `text`, `html` and `title`
+/// fields are treated like the JSON payload.
+pub fn parse_multipart(content_type: &str, body: &[u8]) -> Result<Vec<ClipItem>, AppError> {
+    let boundary = content_type
+        .split(';')
+        .map(|p| p.trim())
+        .find_map(|p| p.strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"').to_string())
+        .ok_or_else(|| AppError::Unknown("Missing multipart boundary".to_string()))?;
+    
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut items = Vec::new();
+    let mut fields = ClipRequest { url: None, text: None, html: None, title: None };
+    
+    for part in split_bytes(body, &delimiter).into_iter().skip(1) {
+        // Closing delimiter is "--boundary--"
+        if part.starts_with(b"--") {
+            break;
+        }
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        
+        let header_end = find_bytes(part, b"\r\n\r\n")
+            .ok_or_else(|| AppError::Unknown("Malformed multipart part".to_string()))?;
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let data = &part[header_end + 4..];
+        
+        let disposition = headers
+            .lines()
+            .find(|l| l.to_ascii_lowercase().starts_with("content-disposition"))
+            .unwrap_or("");
+        let name = disposition_param(disposition, "name");
+        let filename = disposition_param(disposition, "filename");
+        
+        if let Some(filename) = filename {
+            items.push(ClipItem::File { filename, data: data.to_vec() });
+            continue;
+        }
+        
+        let value = String::from_utf8_lossy(data).to_string();
+        match name.as_deref() {
+            Some("url") => fields.url = Some(value),
+            Some("text") => fields.text = Some(value),
+            Some("html") => fields.html = Some(value),
+            Some("title") => fields.title = Some(value),
+            _ => {}
+        }
+    }
+    
+    items.extend(fields.into_items());
+    Ok(items)
+}
+
+/// Import clip items into the project at `project_root`.
+pub fn import_clips(project_root: &Path, items: Vec<ClipItem>) -> Result<Vec<ClipImport>, AppError> {
+    let db_path = io_sqlite::get_db_path(project_root);
+    let conn = database::open_db(&db_path)
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    ensure_inbox_group(&conn)?;
+    
+    let mut imported = Vec::with_capacity(items.len());
+    for item in items {
+        let index = count_inbox_children(&conn)?;
+        let (asset, node_type) = build_asset(project_root, item)?;
+        let node = build_node(&asset, node_type, index);
+        
+        io_sqlite::save_asset_with_history(project_root, &asset)?;
+        io_sqlite::upsert_node(&conn, &node)?;
+        
+        imported.push(ClipImport { asset, node });
+    }
+    
+    if !imported.is_empty() {
+        let summary = format!("Imported {} clip{}", imported.len(), if imported.len() == 1 { "" } else { "s" });
+        activity::record(&conn, &NewActivity::new(ActivityKind::Imported, "clip", summary).target(INBOX_GROUP_ID))?;
+    }
+    
+    Ok(imported)
+}
+
+/// Write an imported file to `assets/` and build the asset value for it:
+/// an image (with thumbnail) if it decodes as one, otherwise a file link.
+/// Returns the value, value meta and node type to show it with.
+pub(crate) fn store_file(
+    project_root: &Path,
+    filename: &str,
+    data: &[u8],
+) -> Result<(serde_json::Value, Option<serde_json::Value>, &'static str), AppError> {
+    let file_id = uuid::Uuid::new_v4().to_string();
+    let ext = imaging::detect_image_format(data)
+        .map(|e| e.to_string())
+        .or_else(|| Path::new(filename).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()))
+        .unwrap_or_else(|| "bin".to_string());
+    
+    let assets_dir = project_root.join("assets");
+    if !assets_dir.exists() {
+        std::fs::create_dir_all(&assets_dir)?;
+    }
+    let relative_path = format!("assets/{}.{}", file_id, ext);
+    std::fs::write(project_root.join(&relative_path), data)?;
+    
+    file_value(project_root, &file_id, &relative_path, data)
+}
+
+/// Asset value for a file already under `assets/` (written or linked),
+/// as returned by `store_file`.
+pub(crate) fn file_value(
+    project_root: &Path,
+    file_id: &str,
+    relative_path: &str,
+    data: &[u8],
+) -> Result<(serde_json::Value, Option<serde_json::Value>, &'static str), AppError> {
+    Ok(match imaging::get_image_dimensions(data) {
+        Ok((width, height)) => {
+            let preview = imaging::generate_thumbnail(project_root, file_id, data).ok();
+            let mut meta = serde_json::json!({ "width": width, "height": height, "preview": preview });
+            if let Some(animation) = imaging::generate_animation_preview(project_root, file_id, data).ok().flatten() {
+                meta["animation"] = serde_json::to_value(animation)?;
+            }
+            (
+                serde_json::json!({ "src": relative_path, "width": width, "height": height }),
+                Some(meta),
+                "image-node",
+            )
+        }
+        Err(_) => (
+            serde_json::json!({ "content": relative_path, "format": "file" }),
+            Some(serde_json::json!({ "length": data.len() })),
+            "text-node",
+        ),
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn build_asset(project_root: &Path, item: ClipItem) -> Result<(Asset, &'static str), AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let id = uuid::Uuid::new_v4().to_string();
+    
+    let (name, value, value_meta, node_type) = match item {
+        ClipItem::File { filename, data } => {
+            let (value, value_meta, node_type) = store_file(project_root, &filename, &data)?;
+            (filename, value, value_meta, node_type)
+        }
+        ClipItem::Url { url, title } => (
+            title.unwrap_or_else(|| url.clone()),
+            serde_json::json!({ "content": url, "format": "url" }),
+            None,
+            "text-node",
+        ),
+        ClipItem::Text { text, title } => (
+            title.unwrap_or_else(|| "Clipped Text".to_string()),
+            serde_json::json!({ "content": text, "format": "plain" }),
+            Some(serde_json::json!({ "length": text.chars().count() })),
+            "text-node",
+        ),
+        ClipItem::Html { html, title } => (
+            title.unwrap_or_else(|| "Clipped HTML".to_string()),
+            serde_json::json!({ "content": html, "format": "html" }),
+            Some(serde_json::json!({ "length": html.chars().count() })),
+            "text-node",
+        ),
+    };
+    
+    let asset = Asset {
+        id,
+        value_type: ValueType::Record,
+        value,
+        value_meta,
+        config: Some(serde_json::json!({ "schema": [] })),
+        sys: AssetSysMetadata {
+            name,
+            created_at: now,
+            updated_at: now,
+            source: "clip".to_string(),
+        },
+    };
+    
+    Ok((asset, node_type))
+}
+
+fn build_node(asset: &Asset, node_type: &str, index: i64) -> SynniaNode {
+    // Lay clippings out in a 4-column grid inside the inbox
+    let column = (index % 4) as f64;
+    let row = (index / 4) as f64;
+    
+    SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: node_type.to_string(),
+        position: Position { x: 20.0 + column * 320.0, y: 60.0 + row * 340.0 },
+        width: Some(300.0),
+        height: None,
+        parent_id: Some(INBOX_GROUP_ID.to_string()),
+        extent: Some("parent".to_string()),
+        style: None,
+        data: SynniaNodeData {
+            title: asset.sys.name.clone(),
+            asset_id: Some(asset.id.clone()),
+            ..Default::default()
+        },
+    }
+}
+
+fn ensure_inbox_group(conn: &Connection) -> Result<(), AppError> {
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) FROM nodes WHERE id = ?1",
+        params![INBOX_GROUP_ID],
+        |row| row.get::<_, i64>(0),
+    ).map(|c| c > 0)
+    .map_err(|e| AppError::Io(format!("Failed to query inbox: {}", e)))?;
+    
+    if exists {
+        return Ok(());
+    }
+    
+    let inbox = SynniaNode {
+        id: INBOX_GROUP_ID.to_string(),
+        type_: "group".to_string(),
+        position: Position { x: -1400.0, y: 0.0 },
+        width: Some(1300.0),
+        height: Some(720.0),
+        parent_id: None,
+        extent: None,
+        style: None,
+        data: SynniaNodeData {
+            title: "Inbox".to_string(),
+            layout_mode: Some("grid".to_string()),
+            ..Default::default()
+        },
+    };
+    io_sqlite::upsert_node(conn, &inbox)
+}
+
+fn count_inbox_children(conn: &Connection) -> Result<i64, AppError> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM nodes WHERE parent_id = ?1",
+        params![INBOX_GROUP_ID],
+        |row| row.get(0),
+    ).map_err(|e| AppError::Io(format!("Failed to count inbox nodes: {}", e)))
+}
+
+fn disposition_param(disposition: &str, key: &str) -> Option<String> {
+    disposition
+        .split(';')
+        .map(|p| p.trim())
+        .find_map(|p| p.strip_prefix(&format!("{}=", key)).map(|v| v.trim_matches('"').to_string()))
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn split_bytes<'a>(data: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut rest = data;
+    while let Some(pos) = find_bytes(rest, delimiter) {
+        parts.push(&rest[..pos]);
+        rest = &rest[pos + delimiter.len()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::io_sqlite::{init_project_sqlite, load_project_sqlite};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_multipart() {
+        let body = b"--XyZ\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+Content-Type: text/plain\r\n\r\n\
+hello\r\n\
+--XyZ\r\n\
+Content-Disposition: form-data; name=\"url\"\r\n\r\n\
+https://example.com\r\n\
+--XyZ--\r\n";
+        
+        let items = parse_multipart("multipart/form-data; boundary=XyZ", body).unwrap();
+        assert_eq!(items.len(), 2);
+        match &items[0] {
+            ClipItem::File { filename, data } => {
+                assert_eq!(filename, "a.txt");
+                assert_eq!(data, b"hello");
+            }
+            other => panic!("expected file, got {:?}", other),
+        }
+        assert!(matches!(&items[1], ClipItem::Url { url, .. } if url == "https://example.com"));
+    }
+
+    #[test]
+    fn test_import_clips_into_inbox() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        init_project_sqlite(project_root, "Clip Project").unwrap();
+        
+        let request = ClipRequest {
+            url: Some("https://example.com".to_string()),
+            text: Some("A quote".to_string()),
+            html: None,
+            title: Some("Example".to_string()),
+        };
+        let imported = import_clips(project_root, request.into_items()).unwrap();
+        assert_eq!(imported.len(), 2);
+        
+        let project = load_project_sqlite(project_root).unwrap();
+        assert_eq!(project.assets.len(), 2);
+        // Inbox group + two clipped nodes
+        assert_eq!(project.graph.nodes.len(), 3);
+        assert!(project.graph.nodes.iter().any(|n| n.id == INBOX_GROUP_ID));
+        assert_eq!(
+            project.graph.nodes.iter().filter(|n| n.parent_id.as_deref() == Some(INBOX_GROUP_ID)).count(),
+            2
+        );
+    }
+}
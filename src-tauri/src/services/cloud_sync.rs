@@ -0,0 +1,151 @@
+//! Cloud-drive safe mode.
+//!
+//! Sync clients (Dropbox, OneDrive, iCloud, ...) upload `synnia.db` and its
+//! `-wal` / `-shm` files independently, which corrupts WAL databases. Projects
+//! inside a synced folder are opened with a rollback journal instead, and the
+//! project folder is checked for the "conflicted copy" files these clients
+//! leave behind when two machines edit the same project.
+
+use std::path::Path;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Journal settings for projects outside synced folders
+pub const DEFAULT_PRAGMAS: &str = "PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;";
+/// Single-file journal, fully synced on commit, for synced folders
+pub const SAFE_MODE_PRAGMAS: &str = "PRAGMA journal_mode=DELETE; PRAGMA synchronous=FULL;";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncProvider {
+    Dropbox,
+    OneDrive,
+    ICloud,
+    GoogleDrive,
+    Box,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<SyncProvider>,
+    /// Database opened without WAL
+    pub safe_mode: bool,
+    /// Conflicted copies of the database found in the project folder
+    pub conflicts: Vec<String>,
+}
+
+/// Sync provider whose folder contains `path`, if any.
+pub fn detect_provider(path: &Path) -> Option<SyncProvider> {
+    for dir in path.ancestors() {
+        let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_lowercase()) else { continue };
+        let provider = match name.as_str() {
+            "dropbox" => Some(SyncProvider::Dropbox),
+            "icloud drive" | "mobile documents" | "com~apple~clouddocs" => Some(SyncProvider::ICloud),
+            "google drive" | "googledrive" | "my drive" => Some(SyncProvider::GoogleDrive),
+            "box" | "box sync" => Some(SyncProvider::Box),
+            // "OneDrive", "OneDrive - Contoso"
+            n if n == "onedrive" || n.starts_with("onedrive - ") => Some(SyncProvider::OneDrive),
+            // "Dropbox (Personal)", "Dropbox (Contoso)"
+            n if n.starts_with("dropbox (") => Some(SyncProvider::Dropbox),
+            _ => None,
+        };
+        if provider.is_some() {
+            return provider;
+        }
+        if dir.join(".dropbox").exists() {
+            return Some(SyncProvider::Dropbox);
+        }
+    }
+    None
+}
+
+/// Journal pragmas for a database file at `db_path`.
+pub fn pragmas_for(db_path: &Path) -> &'static str {
+    if detect_provider(db_path).is_some() {
+        SAFE_MODE_PRAGMAS
+    } else {
+        DEFAULT_PRAGMAS
+    }
+}
+
+/// Conflicted copies of `synnia.db` left by sync clients, e.g.
+/// "synnia (conflicted copy 2025-03-01).db" (Dropbox),
+/// "synnia-DESKTOP-1A2B.db" (OneDrive) or "synnia 2.db" (iCloud).
+pub fn find_conflicts(project_root: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(project_root) else { return Vec::new() };
+    let mut conflicts: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| is_conflict_copy(name))
+        .collect();
+    conflicts.sort();
+    conflicts
+}
+
+pub fn sync_status(project_root: &Path) -> SyncStatus {
+    let provider = detect_provider(project_root);
+    SyncStatus {
+        provider,
+        safe_mode: provider.is_some(),
+        conflicts: find_conflicts(project_root),
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn is_conflict_copy(name: &str) -> bool {
+    const OWN_FILES: &[&str] = &["synnia.db", "synnia.db-wal", "synnia.db-shm", "synnia.db-journal"];
+    let lower = name.to_lowercase();
+    lower.starts_with("synnia")
+        && lower.contains(".db")
+        && !OWN_FILES.contains(&lower.as_str())
+        // Syncthing: "synnia.sync-conflict-20250301-101500-ABC.db"
+        && (lower.contains("conflict") || !lower.starts_with("synnia.db"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_provider() {
+        let cases = [
+            ("/Users/ana/Dropbox/Synnia/Moodboard", Some(SyncProvider::Dropbox)),
+            ("C:\\Users\\ana\\OneDrive - Contoso\\Projects\\Moodboard", Some(SyncProvider::OneDrive)),
+            ("/Users/ana/Library/Mobile Documents/com~apple~CloudDocs/Moodboard", Some(SyncProvider::ICloud)),
+            ("/Users/ana/Documents/Synnia/Moodboard", None),
+        ];
+        for (path, expected) in cases {
+            let path = PathBuf::from(path.replace('\\', std::path::MAIN_SEPARATOR_STR));
+            assert_eq!(detect_provider(&path), expected, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let dir = tempdir().unwrap();
+        for name in [
+            "synnia.db",
+            "synnia.db-wal",
+            "synnia (conflicted copy 2025-03-01).db",
+            "synnia-DESKTOP-1A2B.db",
+            "synnia.sync-conflict-20250301-101500-ABC.db",
+            "notes.db",
+        ] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        assert_eq!(find_conflicts(dir.path()), vec![
+            "synnia (conflicted copy 2025-03-01).db",
+            "synnia-DESKTOP-1A2B.db",
+            "synnia.sync-conflict-20250301-101500-ABC.db",
+        ]);
+    }
+}
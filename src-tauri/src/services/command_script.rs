@@ -0,0 +1,520 @@
+//! Command scripts: a validated list of backend steps run as one job.
+//!
+//! A script creates nodes, imports files or folders, runs agents and exports
+//! subgraphs in order. Later steps can point at what earlier ones made:
+//! `"$2"` in a node ID stands for the first node step 2 created. The whole
+//! script is checked before anything runs, and a checkpoint records the
+//! nodes, assets and exports each step makes, so a failing or cancelled
+//! script takes back exactly those — files included — and keeps whatever
+//! else changed in the project meanwhile.
+//!
+//! Agent steps need the app's config and permission gate, so the command
+//! layer runs those; everything else runs here.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{clip, compaction, database, folder_import, fs_paths, io_sqlite, subgraph, validation};
+use crate::services::cancellation::CancellationToken;
+use crate::services::folder_import::ImportFolderOptions;
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "command-script";
+
+/// Upper bound of steps per script
+pub const MAX_STEPS: usize = 50;
+
+const MAX_TITLE_LEN: usize = 200;
+const NODE_WIDTH: f64 = 300.0;
+const NODE_GAP: f64 = 40.0;
+
+/// One step of a script
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum ScriptStep {
+    /// A node, with a text asset when `content` is given
+    #[serde(rename_all = "camelCase")]
+    CreateNode {
+        node_type: String,
+        title: String,
+        #[serde(default)]
+        position: Option<Position>,
+        #[serde(default)]
+        parent_id: Option<String>,
+        #[serde(default)]
+        content: Option<String>,
+    },
+    /// Copy a file into `assets/` and place it on the canvas
+    #[serde(rename_all = "camelCase")]
+    ImportFile {
+        path: String,
+        #[serde(default)]
+        position: Option<Position>,
+        #[serde(default)]
+        parent_id: Option<String>,
+    },
+    /// Import a folder as groups (see `folder_import`)
+    #[serde(rename_all = "camelCase")]
+    ImportFolder {
+        path: String,
+        #[serde(default)]
+        options: Option<ImportFolderOptions>,
+    },
+    /// Run an agent; the nodes it proposes become text nodes
+    #[serde(rename_all = "camelCase")]
+    RunAgent {
+        agent_id: String,
+        #[serde(default)]
+        #[ts(type = "any")]
+        inputs: Value,
+        #[serde(default)]
+        context_node_id: Option<String>,
+    },
+    /// Spin nodes out into a new project at `dest`
+    #[serde(rename_all = "camelCase")]
+    ExportSubgraph {
+        node_ids: Vec<String>,
+        dest: String,
+    },
+}
+
+impl ScriptStep {
+    /// Whether the step leaves nodes later steps can refer to
+    fn creates_nodes(&self) -> bool {
+        !matches!(self, ScriptStep::ExportSubgraph { .. })
+    }
+
+    /// Node IDs the step takes, which may be `$<step>` references
+    fn node_refs(&self) -> Vec<&str> {
+        match self {
+            ScriptStep::CreateNode { parent_id, .. } | ScriptStep::ImportFile { parent_id, .. } => {
+                parent_id.iter().map(String::as_str).collect()
+            }
+            ScriptStep::RunAgent { context_node_id, .. } => context_node_id.iter().map(String::as_str).collect(),
+            ScriptStep::ExportSubgraph { node_ids, .. } => node_ids.iter().map(String::as_str).collect(),
+            ScriptStep::ImportFolder { .. } => Vec::new(),
+        }
+    }
+}
+
+/// What a step made, reported as its task result
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StepOutput {
+    pub node_ids: Vec<String>,
+    pub asset_ids: Vec<String>,
+    /// Folder written outside the project (exports)
+    pub path: Option<String>,
+}
+
+/// Check a script before it runs: the step count, titles, paths and that
+/// every `$<step>` reference points at an earlier step that creates nodes.
+pub fn validate(steps: &[ScriptStep]) -> Result<(), AppError> {
+    if steps.is_empty() {
+        return Err(AppError::InvalidInput("The script has no steps".to_string()));
+    }
+    if steps.len() > MAX_STEPS {
+        return Err(AppError::InvalidInput(format!("At most {} steps per script", MAX_STEPS)));
+    }
+
+    for (index, step) in steps.iter().enumerate() {
+        let invalid = |message: String| AppError::InvalidInput(format!("Step {}: {}", index, message));
+        match step {
+            ScriptStep::CreateNode { node_type, title, .. } => {
+                if node_type.trim().is_empty() {
+                    return Err(invalid("node type is required".to_string()));
+                }
+                if title.chars().count() > MAX_TITLE_LEN {
+                    return Err(invalid(format!("titles are at most {} characters", MAX_TITLE_LEN)));
+                }
+            }
+            ScriptStep::ImportFile { path, .. } => {
+                if !fs_paths::long_path(&validation::check_path(path)?).is_file() {
+                    return Err(invalid(format!("file not found: {}", path)));
+                }
+            }
+            ScriptStep::ImportFolder { path, .. } => {
+                if !fs_paths::long_path(&validation::check_path(path)?).is_dir() {
+                    return Err(invalid(format!("folder not found: {}", path)));
+                }
+            }
+            ScriptStep::RunAgent { agent_id, .. } => {
+                if agent_id.trim().is_empty() {
+                    return Err(invalid("agent ID is required".to_string()));
+                }
+            }
+            ScriptStep::ExportSubgraph { node_ids, dest } => {
+                if node_ids.is_empty() {
+                    return Err(invalid("no nodes to export".to_string()));
+                }
+                let dest = validation::check_path(dest)?;
+                if dest.exists() && fs::read_dir(&dest)?.next().is_some() {
+                    return Err(invalid(format!("destination is not empty: {}", dest.display())));
+                }
+            }
+        }
+
+        for reference in step.node_refs() {
+            let Some(target) = reference.strip_prefix('$') else { continue };
+            let target: usize = target.parse()
+                .map_err(|_| invalid(format!("bad step reference: {}", reference)))?;
+            if target >= index || !steps[target].creates_nodes() {
+                return Err(invalid(format!("{} does not refer to an earlier step that creates nodes", reference)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replace a `$<step>` reference with the first node that step created.
+pub fn resolve(id: &str, outputs: &[StepOutput]) -> Result<String, AppError> {
+    let Some(step) = id.strip_prefix('$') else {
+        return Ok(id.to_string());
+    };
+    step.parse::<usize>().ok()
+        .and_then(|step| outputs.get(step))
+        .and_then(|output| output.node_ids.first().cloned())
+        .ok_or_else(|| AppError::NotFound(format!("Step {} created no nodes", step)))
+}
+
+/// Run a step other than `RunAgent` against the project at `project_root`.
+pub fn run_step(project_root: &Path, step: &ScriptStep, outputs: &[StepOutput]) -> Result<StepOutput, AppError> {
+    let resolve_opt = |id: &Option<String>| id.as_deref().map(|id| resolve(id, outputs)).transpose();
+    match step {
+        ScriptStep::CreateNode { node_type, title, position, parent_id, content } => {
+            let parent_id = resolve_opt(parent_id)?;
+            create_node(project_root, node_type, title, content.as_deref(), position.clone(), parent_id)
+        }
+        ScriptStep::ImportFile { path, position, parent_id } => {
+            let parent_id = resolve_opt(parent_id)?;
+            import_file(project_root, &validation::check_path(path)?, position.clone(), parent_id)
+        }
+        ScriptStep::ImportFolder { path, options } => {
+            let options = options.clone().unwrap_or_default();
+            let import = folder_import::import_folder(project_root, &validation::check_path(path)?, &options, |_| {})?;
+            // The root group first, so `$<step>` refers to it
+            let nested = nested_nodes(&open_conn(project_root)?, std::slice::from_ref(&import.root_group_id))?;
+            Ok(StepOutput {
+                node_ids: std::iter::once(import.root_group_id.clone())
+                    .chain(nested.into_iter().filter(|id| *id != import.root_group_id))
+                    .collect(),
+                asset_ids: import.assets.into_iter().map(|a| a.id).collect(),
+                path: None,
+            })
+        }
+        ScriptStep::ExportSubgraph { node_ids, dest } => {
+            let node_ids = node_ids.iter().map(|id| resolve(id, outputs)).collect::<Result<Vec<_>, _>>()?;
+            let project = io_sqlite::load_project_sqlite(project_root)?;
+            let dest = validation::check_path(dest)?;
+            let export = subgraph::export_subgraph(project_root, &project, &node_ids, &dest, false, &CancellationToken::new())?;
+            Ok(StepOutput { path: Some(export.path), ..Default::default() })
+        }
+        ScriptStep::RunAgent { .. } => Err(AppError::Unknown("Agent steps run in the command layer".to_string())),
+    }
+}
+
+/// Create a node (with a plain-text asset when `content` is given). Without
+/// a position it goes to the right of everything on the active canvas.
+pub fn create_node(
+    project_root: &Path,
+    node_type: &str,
+    title: &str,
+    content: Option<&str>,
+    position: Option<Position>,
+    parent_id: Option<String>,
+) -> Result<StepOutput, AppError> {
+    let conn = open_conn(project_root)?;
+    let asset = content.map(|content| {
+        new_asset(
+            title,
+            serde_json::json!({ "content": content, "format": "plain" }),
+            Some(serde_json::json!({ "length": content.chars().count() })),
+        )
+    });
+    if let Some(asset) = &asset {
+        io_sqlite::save_asset_with_history(project_root, asset)?;
+    }
+    let node = new_node(&conn, node_type, title, asset.as_ref(), position, parent_id)?;
+    io_sqlite::upsert_node(&conn, &node)?;
+
+    Ok(StepOutput {
+        node_ids: vec![node.id],
+        asset_ids: asset.into_iter().map(|a| a.id).collect(),
+        path: None,
+    })
+}
+
+/// What a script has made so far
+#[derive(Debug, Default)]
+pub struct ScriptCheckpoint {
+    node_ids: Vec<String>,
+    asset_ids: Vec<String>,
+    outputs: Vec<PathBuf>,
+}
+
+impl ScriptCheckpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember what a step made
+    pub fn record(&mut self, output: &StepOutput) {
+        self.node_ids.extend(output.node_ids.iter().cloned());
+        self.asset_ids.extend(output.asset_ids.iter().cloned());
+        if let Some(path) = &output.path {
+            self.outputs.push(PathBuf::from(path));
+        }
+    }
+
+    /// Delete the nodes and assets steps made, the asset files nothing else
+    /// uses and the folders steps exported to. Nodes the user moved into a
+    /// created group stay, detached from it.
+    pub fn rollback(self, project_root: &Path) -> Result<(), AppError> {
+        for output in &self.outputs {
+            let _ = fs::remove_dir_all(output);
+        }
+        let mut conn = open_conn(project_root)?;
+        let files = remove_records(&mut conn, &self.node_ids, &self.asset_ids)?;
+        remove_unused_files(&conn, project_root, &files)
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn open_conn(project_root: &Path) -> Result<Connection, AppError> {
+    database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+}
+
+fn import_file(
+    project_root: &Path,
+    source: &Path,
+    position: Option<Position>,
+    parent_id: Option<String>,
+) -> Result<StepOutput, AppError> {
+    let data = fs::read(fs_paths::long_path(source))?;
+    let name = fs_paths::file_name(source);
+    let (value, value_meta, node_type) = clip::store_file(project_root, &name, &data)?;
+    let asset = new_asset(&name, value, value_meta);
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+
+    let conn = open_conn(project_root)?;
+    let node = new_node(&conn, node_type, &name, Some(&asset), position, parent_id)?;
+    io_sqlite::upsert_node(&conn, &node)?;
+    Ok(StepOutput { node_ids: vec![node.id], asset_ids: vec![asset.id], path: None })
+}
+
+fn new_asset(name: &str, value: Value, value_meta: Option<Value>) -> Asset {
+    let now = chrono::Utc::now().timestamp_millis();
+    Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Record,
+        value,
+        value_meta,
+        config: Some(serde_json::json!({ "schema": [] })),
+        sys: AssetSysMetadata {
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+            source: "script".to_string(),
+        },
+    }
+}
+
+fn new_node(
+    conn: &Connection,
+    node_type: &str,
+    title: &str,
+    asset: Option<&Asset>,
+    position: Option<Position>,
+    parent_id: Option<String>,
+) -> Result<SynniaNode, AppError> {
+    let position = match position {
+        Some(position) => position,
+        None if parent_id.is_some() => Position { x: NODE_GAP, y: NODE_GAP },
+        None => {
+            let mut position = folder_import::free_position(conn)?;
+            position.x += NODE_GAP;
+            position
+        }
+    };
+    Ok(SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: node_type.to_string(),
+        position,
+        width: Some(NODE_WIDTH),
+        height: None,
+        extent: parent_id.as_ref().map(|_| "parent".to_string()),
+        parent_id,
+        style: None,
+        data: SynniaNodeData {
+            title: title.to_string(),
+            asset_id: asset.map(|a| a.id.clone()),
+            ..Default::default()
+        },
+    })
+}
+
+/// `ids` and every node nested in them, at any depth
+fn nested_nodes(conn: &Connection, ids: &[String]) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE nested(id) AS (
+             SELECT value FROM json_each(?1)
+             UNION
+             SELECT n.id FROM nodes n JOIN nested m ON n.parent_id = m.id
+         )
+         SELECT id FROM nested"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let nested: Vec<String> = stmt.query_map(params![serde_json::to_string(ids)?], |row| row.get(0))
+        .and_then(|rows| rows.collect())
+        .map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    Ok(nested)
+}
+
+/// Delete the given nodes (with their edges) and assets (with their history,
+/// tags and links). Returns the `assets/` files the deleted assets used.
+fn remove_records(conn: &mut Connection, node_ids: &[String], asset_ids: &[String]) -> Result<HashSet<String>, AppError> {
+    let io = |e: rusqlite::Error| AppError::Io(format!("Failed to roll back: {}", e));
+    let nodes = serde_json::to_string(node_ids)?;
+    let assets = serde_json::to_string(asset_ids)?;
+
+    // Dropping the transaction on an early return rolls it back
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(io)?;
+    let mut files = HashSet::new();
+    {
+        let mut stmt = tx.prepare(
+            "SELECT value_json FROM assets WHERE id IN (SELECT value FROM json_each(?1))
+             UNION ALL
+             SELECT content_json FROM asset_history WHERE asset_id IN (SELECT value FROM json_each(?1))"
+        ).map_err(io)?;
+        let texts: Vec<String> = stmt.query_map(params![assets], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(io)?;
+        for text in texts {
+            compaction::collect_asset_paths(&text, &mut files);
+        }
+    }
+
+    tx.execute(
+        "UPDATE nodes SET parent_id = NULL, extent = NULL
+         WHERE parent_id IN (SELECT value FROM json_each(?1)) AND id NOT IN (SELECT value FROM json_each(?1))",
+        params![nodes],
+    ).map_err(io)?;
+    tx.execute(
+        "DELETE FROM edges WHERE source IN (SELECT value FROM json_each(?1)) OR target IN (SELECT value FROM json_each(?1))",
+        params![nodes],
+    ).map_err(io)?;
+    tx.execute("DELETE FROM nodes WHERE id IN (SELECT value FROM json_each(?1))", params![nodes]).map_err(io)?;
+    for table in ["asset_history", "asset_tags"] {
+        tx.execute(
+            &format!("DELETE FROM {} WHERE asset_id IN (SELECT value FROM json_each(?1))", table),
+            params![assets],
+        ).map_err(io)?;
+    }
+    tx.execute("DELETE FROM assets WHERE id IN (SELECT value FROM json_each(?1))", params![assets]).map_err(io)?;
+    for file in &files {
+        tx.execute("DELETE FROM linked_files WHERE src = ?1", [format!("assets/{}", file)]).map_err(io)?;
+    }
+    tx.commit().map_err(io)?;
+    Ok(files)
+}
+
+/// Delete `files` from `assets/`, with their thumbnails and other tiers,
+/// unless something still in the project refers to them
+fn remove_unused_files(conn: &Connection, project_root: &Path, files: &HashSet<String>) -> Result<(), AppError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let referenced = compaction::referenced_files(conn)?;
+    let Ok(entries) = fs::read_dir(project_root.join("assets")) else { return Ok(()) };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if compaction::is_referenced(&name, files) && !compaction::is_referenced(&name, &referenced) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::canvases;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn steps(value: Value) -> Vec<ScriptStep> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&[]).is_err());
+
+        let ok = steps(json!([
+            { "command": "create-node", "nodeType": "group", "title": "Batch" },
+            { "command": "create-node", "nodeType": "text-node", "title": "Brief", "content": "Hi", "parentId": "$0" },
+            { "command": "run-agent", "agentId": "writer", "contextNodeId": "$1" },
+        ]));
+        validate(&ok).unwrap();
+
+        let forward = steps(json!([
+            { "command": "create-node", "nodeType": "text-node", "title": "A", "parentId": "$1" },
+            { "command": "create-node", "nodeType": "group", "title": "B" },
+        ]));
+        assert!(matches!(validate(&forward), Err(AppError::InvalidInput(_))));
+
+        let missing = steps(json!([{ "command": "import-file", "path": "/definitely/not/here.png" }]));
+        assert!(validate(&missing).is_err());
+    }
+
+    #[test]
+    fn test_run_steps_and_rollback() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        io_sqlite::init_project_sqlite(root, "Script").unwrap();
+        let source = tempdir().unwrap();
+        let file = source.path().join("notes.txt");
+        fs::write(&file, "draft").unwrap();
+
+        let script = steps(json!([
+            { "command": "create-node", "nodeType": "group", "title": "Batch" },
+            { "command": "import-file", "path": file.to_string_lossy(), "parentId": "$0" },
+        ]));
+        validate(&script).unwrap();
+
+        let mut checkpoint = ScriptCheckpoint::new();
+        let mut outputs = Vec::new();
+        for step in &script {
+            let output = run_step(root, step, &outputs).unwrap();
+            checkpoint.record(&output);
+            outputs.push(output);
+        }
+        // The user keeps working while the script runs
+        let mine = create_node(root, "text-node", "Mine", Some("keep"), None, None).unwrap();
+        let asset_files = || fs::read_dir(root.join("assets")).unwrap().count();
+
+        let conn = open_conn(root).unwrap();
+        let nodes = io_sqlite::load_nodes(&conn, canvases::DEFAULT_CANVAS_ID).unwrap();
+        assert_eq!(nodes.len(), 3);
+        let imported = nodes.iter().find(|n| n.id == outputs[1].node_ids[0]).unwrap();
+        assert_eq!(imported.parent_id.as_deref(), Some(outputs[0].node_ids[0].as_str()));
+        assert_eq!(asset_files(), 1);
+
+        checkpoint.rollback(root).unwrap();
+        let nodes = io_sqlite::load_nodes(&conn, canvases::DEFAULT_CANVAS_ID).unwrap();
+        assert_eq!(nodes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), vec![mine.node_ids[0].as_str()]);
+        assert_eq!(io_sqlite::load_assets(&conn).unwrap().into_keys().collect::<Vec<_>>(), mine.asset_ids);
+        assert_eq!(asset_files(), 0);
+    }
+}
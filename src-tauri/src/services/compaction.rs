@@ -0,0 +1,227 @@
+//! Project compaction ("flatten history") to shrink a project before
+//! archiving or sharing.
+//!
+//! Collapses asset history to the newest N versions, clears leftovers of
+//! deleted content (history of removed assets, reminders for removed nodes,
+//! generation records of removed assets), deletes files in `assets/` that
+//! nothing references any more (unless the project's ignore rules match
+//! them), and rewrites the database with `VACUUM`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use rusqlite::Connection;
+use serde::Serialize;
+use crate::error::AppError;
+use crate::services::{database, history, ignore_rules, io_sqlite};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenReport {
+    pub history_removed: usize,
+    /// Leftover rows of deleted assets and nodes
+    pub trash_removed: usize,
+    pub files_removed: Vec<String>,
+    #[ts(type = "number")]
+    pub bytes_freed: u64,
+    #[ts(type = "number")]
+    pub db_size_before: u64,
+    #[ts(type = "number")]
+    pub db_size_after: u64,
+    /// Nothing was changed; the counts are what a real run would remove
+    pub dry_run: bool,
+}
+
+/// Compact the project at `project_root`, keeping `keep_last_n` history
+/// versions per asset. A `dry_run` reports the same removals but rolls the
+/// database back and leaves files alone; `db_size_after` then stays at the
+/// current size.
+pub fn flatten_project(project_root: &Path, keep_last_n: usize, dry_run: bool) -> Result<FlattenReport, AppError> {
+    let db_path = io_sqlite::get_db_path(project_root);
+    let mut conn = database::open_db(&db_path)
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let mut report = FlattenReport {
+        db_size_before: db_size(&db_path),
+        dry_run,
+        ..Default::default()
+    };
+    
+    // Files are judged after the trim, so ones only old versions used go too
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to start transaction: {}", e)))?;
+    report.history_removed = history::trim_history(&tx, keep_last_n)
+        .map_err(|e| AppError::Io(format!("Failed to trim history: {}", e)))?;
+    report.trash_removed = clear_trash(&tx)?;
+    
+    let referenced = referenced_files(&tx)?;
+    let ignored = ignore_rules::load(&tx)?;
+    let assets_dir = project_root.join("assets");
+    if assets_dir.is_dir() {
+        for entry in fs::read_dir(&assets_dir)?.flatten() {
+            let path = entry.path();
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else { continue };
+            let src = format!("assets/{}", name);
+            if !path.is_file() || is_referenced(&name, &referenced) || ignored.is_ignored(&src, false) {
+                continue;
+            }
+            report.bytes_freed += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if !dry_run {
+                fs::remove_file(&path)?;
+                // Only the link goes for linked imports; the original stays put
+                tx.execute("DELETE FROM linked_files WHERE src = ?1", [&src])
+                    .map_err(|e| AppError::Io(format!("Failed to remove link: {}", e)))?;
+            }
+            report.files_removed.push(src);
+        }
+    }
+    report.files_removed.sort();
+    
+    if dry_run {
+        drop(tx);
+        report.db_size_after = report.db_size_before;
+        return Ok(report);
+    }
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit compaction: {}", e)))?;
+    conn.execute_batch("VACUUM;")
+        .map_err(|e| AppError::Io(format!("Failed to vacuum database: {}", e)))?;
+    // In WAL mode the rewritten pages sit in the WAL until checkpointed
+    let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+    drop(conn);
+    report.db_size_after = db_size(&db_path);
+    
+    Ok(report)
+}
+
+/// Collect `x` from every `assets/x` in `text`, up to a quote or whitespace
+pub(crate) fn collect_asset_paths(text: &str, out: &mut HashSet<String>) {
+    for (i, _) in text.match_indices("assets/") {
+        let rest = &text[i + "assets/".len()..];
+        let end = rest.find(|c: char| c == '"' || c == '\'' || c == '?' || c == '#' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        if end > 0 {
+            out.insert(rest[..end].to_string());
+        }
+    }
+}
+
+/// Thumbnails (`thumb_<id>.jpg`), medium tiers (`medium_<id>.jpg`), video
+/// proxies (`proxy_<id>.mp4`) and animated previews (`preview_<id>.gif`) live
+/// as long as their original does
+pub(crate) fn is_referenced(name: &str, referenced: &HashSet<String>) -> bool {
+    if referenced.contains(name) {
+        return true;
+    }
+    let Some(stem) = ["thumb_", "medium_", "proxy_", "preview_"].iter().find_map(|p| name.strip_prefix(p)).and_then(|n| n.rsplit_once('.')).map(|(s, _)| s) else {
+        return false;
+    };
+    referenced.iter().any(|r| r.rsplit_once('.').map_or(r.as_str(), |(s, _)| s) == stem)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn clear_trash(conn: &Connection) -> Result<usize, AppError> {
+    let mut removed = history::remove_orphaned_history(conn)
+        .map_err(|e| AppError::Io(format!("Failed to clear history: {}", e)))?;
+    
+    for sql in [
+        "DELETE FROM generation_records WHERE asset_id NOT IN (SELECT id FROM assets)",
+        "DELETE FROM asset_licenses WHERE asset_id NOT IN (SELECT id FROM assets)",
+        "DELETE FROM reminders WHERE node_id NOT IN (SELECT id FROM nodes)",
+        "DELETE FROM custom_field_values WHERE target = 'asset' AND target_id NOT IN (SELECT id FROM assets)",
+        "DELETE FROM custom_field_values WHERE target = 'node' AND target_id NOT IN (SELECT id FROM nodes)",
+    ] {
+        removed += conn.execute(sql, [])
+            .map_err(|e| AppError::Io(format!("Failed to clear trash: {}", e)))?;
+    }
+    Ok(removed)
+}
+
+/// File names under `assets/` mentioned anywhere in the project data
+pub(crate) fn referenced_files(conn: &Connection) -> Result<HashSet<String>, AppError> {
+    let mut referenced = HashSet::new();
+    for sql in [
+        "SELECT value_json FROM assets",
+        "SELECT value_meta_json FROM assets",
+        "SELECT config_json FROM assets",
+        "SELECT content_json FROM asset_history",
+        "SELECT thumbnail FROM project_meta",
+        "SELECT value_json FROM settings",
+    ] {
+        let mut stmt = conn.prepare(sql)
+            .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+        let rows = stmt.query_map([], |row| row.get::<_, Option<String>>(0))
+            .map_err(|e| AppError::Io(format!("Failed to scan references: {}", e)))?;
+        for text in rows.flatten().flatten() {
+            collect_asset_paths(&text, &mut referenced);
+        }
+    }
+    Ok(referenced)
+}
+
+/// Size of the database file plus its WAL
+pub(crate) fn db_size(db_path: &Path) -> u64 {
+    ["", "-wal"].iter()
+        .filter_map(|suffix| fs::metadata(format!("{}{}", db_path.display(), suffix)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn image(id: &str, src: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": src }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_flatten_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        io_sqlite::init_project_sqlite(root, "Big").unwrap();
+        fs::create_dir_all(root.join("assets")).unwrap();
+        for name in ["v1.png", "v2.png", "v3.png", "thumb_v3.jpg", "stray.png", ".DS_Store", "clip.mp4.part"] {
+            fs::write(root.join("assets").join(name), b"data").unwrap();
+        }
+        
+        for version in ["v1", "v2", "v3"] {
+            io_sqlite::save_asset_with_history(root, &image("photo", &format!("assets/{}.png", version))).unwrap();
+        }
+        
+        let preview = flatten_project(root, 1, true).unwrap();
+        assert!(root.join("assets/stray.png").exists());
+        assert_eq!(history::count_history(&database::open_db(&io_sqlite::get_db_path(root)).unwrap(), "photo").unwrap(), 2);
+        
+        let report = flatten_project(root, 1, false).unwrap();
+        assert_eq!((preview.history_removed, &preview.files_removed, preview.bytes_freed),
+            (report.history_removed, &report.files_removed, report.bytes_freed));
+        assert_eq!(report.history_removed, 1, "v1 snapshot dropped, v2 kept");
+        assert_eq!(report.files_removed, vec!["assets/stray.png", "assets/v1.png"]);
+        assert_eq!(report.bytes_freed, 8);
+        assert!(root.join("assets/thumb_v3.jpg").exists());
+        assert!(root.join("assets/v2.png").exists());
+        // Matched by the default ignore rules
+        assert!(root.join("assets/.DS_Store").exists() && root.join("assets/clip.mp4.part").exists());
+    }
+}
@@ -0,0 +1,252 @@
+//! A/B comparison assets for structured creative review rounds.
+//!
+//! A comparison is an array asset with `config.mode = "comparison"`. Each
+//! item snapshots one candidate asset at creation time (so later edits to
+//! the candidate don't change what was reviewed) and collects votes and
+//! notes. The current winner is recomputed on every change and stored in
+//! `valueMeta.winner`.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, ValueType};
+use crate::services::{database, io_sqlite};
+
+/// Array asset mode for comparisons
+pub const COMPARISON_MODE: &str = "comparison";
+
+/// Max candidates in a single comparison
+pub const MAX_CANDIDATES: usize = 8;
+
+/// One reviewer note on a candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonNote {
+    pub text: String,
+    pub created_at: i64,
+}
+
+/// One candidate in a comparison (an item of the asset value)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonCandidate {
+    pub asset_id: String,
+    pub name: String,
+    /// Candidate asset as it was when the comparison was created
+    pub snapshot: Value,
+    #[serde(default)]
+    pub votes: u32,
+    #[serde(default)]
+    pub notes: Vec<ComparisonNote>,
+}
+
+/// Build a comparison asset from candidate assets (in review order).
+pub fn create_comparison(
+    project_root: &Path,
+    asset_ids: &[String],
+    name: Option<String>,
+) -> Result<Asset, AppError> {
+    let mut ids: Vec<&String> = Vec::new();
+    for id in asset_ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.len() < 2 {
+        return Err(AppError::Unknown("A comparison needs at least two different assets".to_string()));
+    }
+    if ids.len() > MAX_CANDIDATES {
+        return Err(AppError::Unknown(format!(
+            "Comparison has {} candidates, the maximum is {}", ids.len(), MAX_CANDIDATES
+        )));
+    }
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let mut candidates = Vec::with_capacity(ids.len());
+    for id in ids {
+        let asset = io_sqlite::get_asset(&conn, id)?
+            .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", id)))?;
+        candidates.push(ComparisonCandidate {
+            asset_id: asset.id.clone(),
+            name: asset.sys.name.clone(),
+            snapshot: json!({
+                "valueType": asset.value_type,
+                "value": asset.value,
+                "config": asset.config,
+            }),
+            votes: 0,
+            notes: Vec::new(),
+        });
+    }
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut asset = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Array,
+        value: serde_json::to_value(&candidates)?,
+        value_meta: None,
+        config: Some(json!({ "mode": COMPARISON_MODE })),
+        sys: AssetSysMetadata {
+            name: name.unwrap_or_else(|| "Comparison".to_string()),
+            created_at: now,
+            updated_at: now,
+            source: "user".to_string(),
+        },
+    };
+    asset.value_meta = Some(summarize(&candidates));
+    
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    Ok(asset)
+}
+
+/// Add a vote and/or a note to one candidate and recompute the winner.
+pub fn record_feedback(
+    project_root: &Path,
+    comparison_id: &str,
+    candidate_asset_id: &str,
+    vote: bool,
+    note: Option<String>,
+) -> Result<Asset, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let mut asset = io_sqlite::get_asset(&conn, comparison_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Comparison not found: {}", comparison_id)))?;
+    if !is_comparison(&asset) {
+        return Err(AppError::Unknown(format!("Asset {} is not a comparison", comparison_id)));
+    }
+    
+    let mut candidates: Vec<ComparisonCandidate> = serde_json::from_value(asset.value.clone())?;
+    let candidate = candidates.iter_mut()
+        .find(|c| c.asset_id == candidate_asset_id)
+        .ok_or_else(|| AppError::NotFound(format!("Candidate not in comparison: {}", candidate_asset_id)))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    if vote {
+        candidate.votes += 1;
+    }
+    if let Some(text) = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+        candidate.notes.push(ComparisonNote { text, created_at: now });
+    }
+    
+    asset.value = serde_json::to_value(&candidates)?;
+    asset.value_meta = Some(summarize(&candidates));
+    asset.sys.updated_at = now;
+    
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    Ok(asset)
+}
+
+/// Candidate with the most votes; `None` while nobody voted or on a tie.
+pub fn compute_winner(candidates: &[ComparisonCandidate]) -> Option<&ComparisonCandidate> {
+    let top = candidates.iter().map(|c| c.votes).max().filter(|v| *v > 0)?;
+    let mut leaders = candidates.iter().filter(|c| c.votes == top);
+    let winner = leaders.next();
+    if leaders.next().is_some() {
+        return None;
+    }
+    winner
+}
+
+/// Whether an asset is an array asset in comparison mode
+pub fn is_comparison(asset: &Asset) -> bool {
+    asset.value_type == ValueType::Array
+        && asset.config.as_ref()
+            .and_then(|c| c.get("mode"))
+            .and_then(|m| m.as_str())
+            == Some(COMPARISON_MODE)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn summarize(candidates: &[ComparisonCandidate]) -> Value {
+    json!({
+        "length": candidates.len(),
+        "totalVotes": candidates.iter().map(|c| c.votes).sum::<u32>(),
+        "winner": compute_winner(candidates).map(|c| c.asset_id.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::io_sqlite::init_project_sqlite;
+    use tempfile::tempdir;
+
+    fn candidate(id: &str, votes: u32) -> ComparisonCandidate {
+        ComparisonCandidate {
+            asset_id: id.to_string(),
+            name: id.to_string(),
+            snapshot: Value::Null,
+            votes,
+            notes: Vec::new(),
+        }
+    }
+
+    fn save_image(project_root: &Path, id: &str, src: &str) {
+        let asset = Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": src }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        };
+        io_sqlite::save_asset_with_history(project_root, &asset).unwrap();
+    }
+
+    #[test]
+    fn test_compute_winner() {
+        assert!(compute_winner(&[candidate("a", 0), candidate("b", 0)]).is_none());
+        assert!(compute_winner(&[candidate("a", 2), candidate("b", 2)]).is_none());
+        assert_eq!(compute_winner(&[candidate("a", 1), candidate("b", 3)]).unwrap().asset_id, "b");
+    }
+
+    #[test]
+    fn test_create_comparison_and_vote() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        init_project_sqlite(project_root, "Review").unwrap();
+        save_image(project_root, "img-a", "assets/a.png");
+        save_image(project_root, "img-b", "assets/b.png");
+        
+        let ids = vec!["img-a".to_string(), "img-b".to_string(), "img-a".to_string()];
+        let comparison = create_comparison(project_root, &ids, None).unwrap();
+        assert!(is_comparison(&comparison));
+        assert_eq!(comparison.value.as_array().unwrap().len(), 2);
+        
+        // Snapshot is unaffected by later edits to the candidate
+        save_image(project_root, "img-a", "assets/a2.png");
+        
+        let updated = record_feedback(project_root, &comparison.id, "img-b", true, Some("sharper".to_string())).unwrap();
+        let meta = updated.value_meta.unwrap();
+        assert_eq!(meta["winner"], "img-b");
+        assert_eq!(meta["totalVotes"], 1);
+        
+        let candidates: Vec<ComparisonCandidate> = serde_json::from_value(updated.value).unwrap();
+        assert_eq!(candidates[0].snapshot["value"]["src"], "assets/a.png");
+        assert_eq!(candidates[1].notes[0].text, "sharper");
+    }
+
+    #[test]
+    fn test_create_comparison_validation() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        init_project_sqlite(project_root, "Review").unwrap();
+        save_image(project_root, "img-a", "assets/a.png");
+        
+        assert!(create_comparison(project_root, &["img-a".to_string()], None).is_err());
+        assert!(create_comparison(project_root, &["img-a".to_string(), "missing".to_string()], None).is_err());
+        assert!(record_feedback(project_root, "img-a", "img-a", true, None).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Reusable components ("smart blocks"): subgraphs saved to a global store
+//! and stamped into any project with fresh IDs, like symbols in design tools.
+//!
+//! Components live as JSON files in the global components directory, one
+//! file per component, next to the agent definitions. The files behind their
+//! assets' `src` are copied next to it, into `<component id>/assets/`, so a
+//! component still works once the project it came from is gone; instances
+//! get their own copies in the target project.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
+use crate::models::{Asset, ComponentDefinition, Position, SynniaEdge, SynniaNode, SynniaProject};
+use ts_rs::TS;
+
+/// Nodes, edges and assets created by instantiating a component
+#[derive(Debug, Clone, serde::Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentInstance {
+    pub component_id: String,
+    pub nodes: Vec<SynniaNode>,
+    pub edges: Vec<SynniaEdge>,
+    pub assets: Vec<Asset>,
+}
+
+/// Extract the given nodes (plus everything nested inside them), the edges
+/// between them and copies of their assets into a component definition.
+pub fn extract_component(
+    project: &SynniaProject,
+    node_ids: &[String],
+    name: String,
+    description: Option<String>,
+) -> Result<ComponentDefinition, AppError> {
+    let mut selected: HashSet<&str> = HashSet::new();
+    for id in node_ids {
+        if !project.graph.nodes.iter().any(|n| &n.id == id) {
+            return Err(AppError::NotFound(format!("Node not found: {}", id)));
+        }
+        selected.insert(id.as_str());
+    }
+    if selected.is_empty() {
+        return Err(AppError::Unknown("A component needs at least one node".to_string()));
+    }
+    
+    // Pull in children of selected groups/racks, however deeply nested
+    loop {
+        let before = selected.len();
+        for node in &project.graph.nodes {
+            if let Some(parent) = &node.parent_id {
+                if selected.contains(parent.as_str()) {
+                    selected.insert(node.id.as_str());
+                }
+            }
+        }
+        if selected.len() == before {
+            break;
+        }
+    }
+    
+    let mut nodes: Vec<SynniaNode> = project.graph.nodes.iter()
+        .filter(|n| selected.contains(n.id.as_str()))
+        .cloned()
+        .collect();
+    
+    // Detach from parents outside the selection
+    for node in &mut nodes {
+        if node.parent_id.as_deref().is_some_and(|p| !selected.contains(p)) {
+            node.parent_id = None;
+            node.extent = None;
+        }
+        if node.data.docked_to.as_deref().is_some_and(|d| !selected.contains(d)) {
+            node.data.docked_to = None;
+        }
+    }
+    
+    // Make top-level positions relative to the component's top-left corner
+    let (min_x, min_y) = nodes.iter()
+        .filter(|n| n.parent_id.is_none())
+        .fold((f64::MAX, f64::MAX), |(x, y), n| (x.min(n.position.x), y.min(n.position.y)));
+    for node in nodes.iter_mut().filter(|n| n.parent_id.is_none()) {
+        node.position.x -= min_x;
+        node.position.y -= min_y;
+    }
+    
+    let edges: Vec<SynniaEdge> = project.graph.edges.iter()
+        .filter(|e| selected.contains(e.source.as_str()) && selected.contains(e.target.as_str()))
+        .cloned()
+        .collect();
+    
+    let mut assets: Vec<Asset> = Vec::new();
+    for asset_id in nodes.iter().filter_map(|n| n.data.asset_id.as_ref()) {
+        if assets.iter().any(|a| &a.id == asset_id) {
+            continue;
+        }
+        if let Some(asset) = project.assets.get(asset_id) {
+            assets.push(asset.clone());
+        }
+    }
+    
+    Ok(ComponentDefinition {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description,
+        nodes,
+        edges,
+        assets,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// Copy a component with fresh node/edge/asset IDs, top-level nodes offset to `position`.
+pub fn instantiate(component: &ComponentDefinition, position: &Position) -> ComponentInstance {
+    let node_ids: HashMap<&str, String> = component.nodes.iter()
+        .map(|n| (n.id.as_str(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+    let asset_ids: HashMap<&str, String> = component.assets.iter()
+        .map(|a| (a.id.as_str(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+    let now = chrono::Utc::now().timestamp_millis();
+    
+    let nodes = component.nodes.iter().map(|node| {
+        let mut node = node.clone();
+        node.id = node_ids[node.id.as_str()].clone();
+        match node.parent_id.as_deref().and_then(|p| node_ids.get(p)) {
+            Some(parent) => node.parent_id = Some(parent.clone()),
+            None => {
+                node.parent_id = None;
+                node.position.x += position.x;
+                node.position.y += position.y;
+            }
+        }
+        node.data.docked_to = node.data.docked_to.as_deref()
+            .and_then(|d| node_ids.get(d))
+            .cloned();
+        node.data.asset_id = node.data.asset_id.as_deref()
+            .map(|a| asset_ids.get(a).cloned().unwrap_or_else(|| a.to_string()));
+        node.data.state = None;
+        node
+    }).collect();
+    
+    let edges = component.edges.iter().filter_map(|edge| {
+        let mut edge = edge.clone();
+        edge.id = uuid::Uuid::new_v4().to_string();
+        edge.source = node_ids.get(edge.source.as_str())?.clone();
+        edge.target = node_ids.get(edge.target.as_str())?.clone();
+        Some(edge)
+    }).collect();
+    
+    let assets = component.assets.iter().map(|asset| {
+        let mut asset = asset.clone();
+        asset.id = asset_ids[asset.id.as_str()].clone();
+        asset.sys.created_at = now;
+        asset.sys.updated_at = now;
+        asset
+    }).collect();
+    
+    ComponentInstance {
+        component_id: component.id.clone(),
+        nodes,
+        edges,
+        assets,
+    }
+}
+
+/// Copy the files behind the component's asset `src`s from the project into
+/// the store. Files that are missing are skipped. Returns the number copied.
+pub fn store_files(project_root: &Path, dir: &Path, component: &ComponentDefinition) -> Result<usize, AppError> {
+    let files_dir = files_dir(dir, &component.id);
+    let mut copied = 0;
+    for asset in &component.assets {
+        let Some(name) = src_file_name(&asset.value) else { continue };
+        let source = project_root.join("assets").join(name);
+        if !source.is_file() {
+            continue;
+        }
+        fs::create_dir_all(&files_dir)?;
+        fs::copy(&source, files_dir.join(name))?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Copy a stored component's files into the project under the instance's
+/// asset IDs and point each `src` at its copy. Assets whose file was never
+/// stored keep their `src`. Returns the number of files copied.
+pub fn place_files(dir: &Path, component_id: &str, project_root: &Path, instance: &mut ComponentInstance) -> Result<usize, AppError> {
+    let files_dir = files_dir(dir, component_id);
+    let mut copied = 0;
+    for asset in &mut instance.assets {
+        let Some(name) = src_file_name(&asset.value) else { continue };
+        let source = files_dir.join(name);
+        if !source.is_file() {
+            continue;
+        }
+        let file_name = match Path::new(name).extension().and_then(|e| e.to_str()) {
+            Some(ext) => format!("{}.{}", asset.id, ext),
+            None => asset.id.clone(),
+        };
+        let assets_dir = project_root.join("assets");
+        fs::create_dir_all(&assets_dir)?;
+        fs::copy(&source, assets_dir.join(&file_name))?;
+        
+        let src = serde_json::Value::String(format!("assets/{}", file_name));
+        match asset.value.get_mut("src") {
+            Some(value) => *value = src,
+            None => asset.value = src,
+        }
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Read all components in the store, sorted by name.
+pub fn list_components(dir: &Path) -> Vec<ComponentDefinition> {
+    let mut components = Vec::new();
+    
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            match fs::read_to_string(&path).map(|c| serde_json::from_str::<ComponentDefinition>(&c)) {
+                Ok(Ok(component)) => components.push(component),
+                _ => println!("Failed to parse component file: {:?}", path),
+            }
+        }
+    }
+    
+    components.sort_by_key(|c| c.name.to_lowercase());
+    components
+}
+
+pub fn load_component(dir: &Path, id: &str) -> Result<ComponentDefinition, AppError> {
+    let path = component_path(dir, id);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("Component not found: {}", id)));
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_component(dir: &Path, component: &ComponentDefinition) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(component)?;
+    fs::write(component_path(dir, &component.id), json)?;
+    Ok(())
+}
+
+pub fn delete_component(dir: &Path, id: &str) -> Result<(), AppError> {
+    let path = component_path(dir, id);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    let files = dir.join(safe_id(id));
+    if files.exists() {
+        fs::remove_dir_all(files)?;
+    }
+    Ok(())
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn component_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", safe_id(id)))
+}
+
+fn files_dir(dir: &Path, id: &str) -> PathBuf {
+    dir.join(safe_id(id)).join("assets")
+}
+
+fn safe_id(id: &str) -> String {
+    id.chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-').collect()
+}
+
+/// File name of an asset's `src` (or of a bare path value) under `assets/`
+fn src_file_name(value: &serde_json::Value) -> Option<&str> {
+    let src = value.get("src").or(Some(value)).and_then(|v| v.as_str())?;
+    let name = src.strip_prefix("assets/")?;
+    let is_plain = !name.is_empty() && !name.contains(['/', '\\']) && name != "." && name != "..";
+    is_plain.then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, SynniaNodeData, ValueType};
+    use crate::services::io_sqlite::init_project_sqlite;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn node(id: &str, x: f64, y: f64, parent: Option<&str>, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "asset-node".to_string(),
+            position: Position { x, y },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: parent.map(|_| "parent".to_string()),
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn edge(id: &str, source: &str, target: &str) -> SynniaEdge {
+        SynniaEdge {
+            id: id.to_string(),
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }
+    }
+
+    fn project() -> SynniaProject {
+        let dir = tempdir().unwrap();
+        let mut project = init_project_sqlite(dir.path(), "Components").unwrap();
+        project.graph.nodes = vec![
+            node("group", 100.0, 200.0, None, None),
+            node("child", 10.0, 10.0, Some("group"), Some("asset-1")),
+            node("text", 500.0, 250.0, None, Some("asset-1")),
+            node("outside", 900.0, 900.0, None, None),
+        ];
+        project.graph.edges = vec![
+            edge("e1", "child", "text"),
+            edge("e2", "text", "outside"),
+        ];
+        project.assets.insert("asset-1".to_string(), Asset {
+            id: "asset-1".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "content": "hello" }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: "Hello".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        });
+        project
+    }
+
+    #[test]
+    fn test_extract_component() {
+        let ids = vec!["group".to_string(), "text".to_string()];
+        let component = extract_component(&project(), &ids, "Block".to_string(), None).unwrap();
+        
+        assert_eq!(component.nodes.len(), 3, "child of the group is included");
+        assert_eq!(component.edges.len(), 1, "edge to an outside node is dropped");
+        assert_eq!(component.assets.len(), 1, "shared asset is copied once");
+        
+        let group = component.nodes.iter().find(|n| n.id == "group").unwrap();
+        assert_eq!((group.position.x, group.position.y), (0.0, 0.0));
+        let child = component.nodes.iter().find(|n| n.id == "child").unwrap();
+        assert_eq!((child.position.x, child.position.y), (10.0, 10.0), "nested positions stay parent-relative");
+        
+        assert!(extract_component(&project(), &["missing".to_string()], "Bad".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_instantiate_uses_fresh_ids() {
+        let ids = vec!["group".to_string(), "text".to_string()];
+        let component = extract_component(&project(), &ids, "Block".to_string(), None).unwrap();
+        let instance = instantiate(&component, &Position { x: 1000.0, y: 50.0 });
+        
+        assert!(instance.nodes.iter().all(|n| !["group", "child", "text"].contains(&n.id.as_str())));
+        
+        let group = &instance.nodes[0];
+        let child = &instance.nodes[1];
+        assert_eq!((group.position.x, group.position.y), (1000.0, 50.0));
+        assert_eq!(child.parent_id.as_ref(), Some(&group.id));
+        
+        let asset_id = &instance.assets[0].id;
+        assert_ne!(asset_id, "asset-1");
+        assert_eq!(child.data.asset_id.as_ref(), Some(asset_id));
+        assert_eq!(instance.edges[0].source, child.id);
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let dir = tempdir().unwrap();
+        let component = extract_component(&project(), &["text".to_string()], "Text".to_string(), None).unwrap();
+        
+        save_component(dir.path(), &component).unwrap();
+        assert_eq!(list_components(dir.path()).len(), 1);
+        assert_eq!(load_component(dir.path(), &component.id).unwrap().name, "Text");
+        
+        delete_component(dir.path(), &component.id).unwrap();
+        assert!(load_component(dir.path(), &component.id).is_err());
+    }
+
+    #[test]
+    fn test_files_travel_between_projects() {
+        let store = tempdir().unwrap();
+        let source = tempdir().unwrap();
+        let target = tempdir().unwrap();
+        
+        let mut project = init_project_sqlite(source.path(), "Source").unwrap();
+        fs::create_dir_all(source.path().join("assets")).unwrap();
+        fs::write(source.path().join("assets/hero.png"), b"hero pixels").unwrap();
+        project.graph.nodes = vec![node("hero", 0.0, 0.0, None, Some("hero-asset"))];
+        project.assets.insert("hero-asset".to_string(), Asset {
+            id: "hero-asset".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/hero.png", "width": 64 }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: "Hero".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        });
+        
+        let component = extract_component(&project, &["hero".to_string()], "Hero".to_string(), None).unwrap();
+        assert_eq!(store_files(source.path(), store.path(), &component).unwrap(), 1);
+        save_component(store.path(), &component).unwrap();
+        
+        // The source project can go away
+        drop(source);
+        let component = load_component(store.path(), &component.id).unwrap();
+        let mut instance = instantiate(&component, &Position { x: 0.0, y: 0.0 });
+        assert_eq!(place_files(store.path(), &component.id, target.path(), &mut instance).unwrap(), 1);
+        
+        let asset = &instance.assets[0];
+        let src = asset.value["src"].as_str().unwrap();
+        assert_eq!(src, format!("assets/{}.png", asset.id));
+        assert_eq!(asset.value["width"], 64);
+        assert_eq!(fs::read(target.path().join(src)).unwrap(), b"hero pixels");
+        
+        delete_component(store.path(), &component.id).unwrap();
+        assert!(!store.path().join(&component.id).exists());
+        assert_eq!(src_file_name(&json!("assets/../secret")), None);
+    }
+}
@@ -0,0 +1,89 @@
+//! Dropbox (API v2). Folders and files are addressed by their `id:` IDs.
+
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use super::{client, read_file, send, ConnectorProvider, RemoteFile, MAX_FILES};
+
+const API_URL: &str = "https://api.dropboxapi.com/2/files";
+const CONTENT_URL: &str = "https://content.dropboxapi.com/2/files/download";
+
+pub(super) async fn list(policy: &NetworkPolicy, token: &str, folder: Option<&str>) -> Result<Vec<RemoteFile>, AppError> {
+    let client = client(policy, false)?;
+    let mut files = Vec::new();
+    let mut request = ("list_folder", json!({ "path": folder.unwrap_or(""), "limit": 500 }));
+    loop {
+        let url = policy.check_url(&format!("{}/{}", API_URL, request.0)).await?;
+        let body: Value = send(client.post(url).bearer_auth(token).json(&request.1), ConnectorProvider::Dropbox).await?
+            .json().await
+            .map_err(|e| AppError::Network(format!("Unexpected response from Dropbox: {}", e)))?;
+        files.extend(parse_entries(&body));
+
+        let cursor = body.get("cursor").and_then(Value::as_str);
+        match cursor {
+            Some(cursor) if body.get("has_more").and_then(Value::as_bool) == Some(true) && files.len() < MAX_FILES => {
+                request = ("list_folder/continue", json!({ "cursor": cursor }));
+            }
+            _ => break,
+        }
+    }
+    files.truncate(MAX_FILES);
+    Ok(files)
+}
+
+pub(super) async fn download(policy: &NetworkPolicy, token: &str, file_id: &str) -> Result<(String, Vec<u8>), AppError> {
+    let url = policy.check_url(CONTENT_URL).await?;
+    let request = client(policy, true)?.post(url)
+        .bearer_auth(token)
+        .header("Dropbox-API-Arg", json!({ "path": file_id }).to_string());
+    let response = send(request, ConnectorProvider::Dropbox).await?;
+    let name = response.headers().get("Dropbox-API-Result")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| serde_json::from_str::<Value>(v).ok())
+        .and_then(|meta| meta.get("name")?.as_str().map(String::from))
+        .unwrap_or_else(|| file_id.trim_start_matches("id:").to_string());
+    Ok((name, read_file(response).await?))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn parse_entries(body: &Value) -> Vec<RemoteFile> {
+    body.get("entries").and_then(Value::as_array).into_iter().flatten()
+        .filter_map(|entry| {
+            let is_folder = match entry.get(".tag")?.as_str()? {
+                "folder" => true,
+                "file" => false,
+                _ => return None,
+            };
+            Some(RemoteFile {
+                id: entry.get("id")?.as_str()?.to_string(),
+                name: entry.get("name")?.as_str()?.to_string(),
+                is_folder,
+                size: entry.get("size").and_then(Value::as_u64),
+                modified_at: entry.get("server_modified").and_then(Value::as_str).map(String::from),
+                mime_type: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let body = json!({ "entries": [
+            { ".tag": "folder", "id": "id:f1", "name": "Moodboards" },
+            { ".tag": "file", "id": "id:a1", "name": "ref.jpg", "size": 1024, "server_modified": "2024-05-01T10:00:00Z" },
+            { ".tag": "deleted", "name": "gone.jpg" },
+        ], "has_more": false });
+        let files = parse_entries(&body);
+        assert_eq!(files.len(), 2);
+        assert!(files[0].is_folder);
+        assert_eq!(files[1].size, Some(1024));
+        assert_eq!(files[1].id, "id:a1");
+    }
+}
@@ -0,0 +1,127 @@
+//! Google Drive (API v3), including shared drives.
+
+use serde_json::Value;
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use super::{client, read_file, send, ConnectorProvider, RemoteFile, MAX_FILES};
+
+const API_URL: &str = "https://www.googleapis.com/drive/v3/files";
+const FOLDER_TYPE: &str = "application/vnd.google-apps.folder";
+const FILE_FIELDS: &str = "id,name,mimeType,size,modifiedTime";
+
+pub(super) async fn list(policy: &NetworkPolicy, token: &str, folder: Option<&str>) -> Result<Vec<RemoteFile>, AppError> {
+    let query = format!("'{}' in parents and trashed = false", folder.unwrap_or("root"));
+    let fields = format!("nextPageToken,files({})", FILE_FIELDS);
+    let client = client(policy, false)?;
+
+    let mut files = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut params = vec![
+            ("q", query.as_str()),
+            ("fields", fields.as_str()),
+            ("pageSize", "200"),
+            ("supportsAllDrives", "true"),
+            ("includeItemsFromAllDrives", "true"),
+        ];
+        if let Some(page_token) = &page_token {
+            params.push(("pageToken", page_token.as_str()));
+        }
+        let url = policy.check_url(API_URL).await?;
+        let body: Value = send(client.get(url).bearer_auth(token).query(&params), ConnectorProvider::GoogleDrive).await?
+            .json().await
+            .map_err(|e| AppError::Network(format!("Unexpected response from Google Drive: {}", e)))?;
+        files.extend(parse_files(&body));
+
+        page_token = body.get("nextPageToken").and_then(Value::as_str).map(String::from);
+        if page_token.is_none() || files.len() >= MAX_FILES {
+            break;
+        }
+    }
+    files.truncate(MAX_FILES);
+    Ok(files)
+}
+
+pub(super) async fn download(policy: &NetworkPolicy, token: &str, file_id: &str) -> Result<(String, Vec<u8>), AppError> {
+    let provider = ConnectorProvider::GoogleDrive;
+    let file_url = format!("{}/{}", API_URL, file_id);
+    let url = policy.check_url(&file_url).await?;
+    let meta: Value = send(client(policy, false)?.get(url).bearer_auth(token)
+        .query(&[("fields", FILE_FIELDS), ("supportsAllDrives", "true")]), provider).await?
+        .json().await
+        .map_err(|e| AppError::Network(format!("Unexpected response from Google Drive: {}", e)))?;
+    let file = parse_file(&meta)
+        .ok_or_else(|| AppError::Network("Unexpected response from Google Drive".to_string()))?;
+    if file.is_folder {
+        return Err(AppError::InvalidInput(format!("{} is a folder", file.name)));
+    }
+
+    // Docs, Sheets and Slides have no file of their own and are exported
+    let (request, name) = match file.mime_type.as_deref().and_then(export_type) {
+        Some((mime_type, ext)) => {
+            let url = policy.check_url(&format!("{}/export", file_url)).await?;
+            (client(policy, true)?.get(url).query(&[("mimeType", mime_type)]), format!("{}.{}", file.name, ext))
+        }
+        None => {
+            let url = policy.check_url(&file_url).await?;
+            (client(policy, true)?.get(url).query(&[("alt", "media"), ("supportsAllDrives", "true")]), file.name)
+        }
+    };
+    let data = read_file(send(request.bearer_auth(token), provider).await?).await?;
+    Ok((name, data))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn parse_files(body: &Value) -> Vec<RemoteFile> {
+    body.get("files").and_then(Value::as_array).into_iter().flatten()
+        .filter_map(parse_file)
+        .collect()
+}
+
+fn parse_file(file: &Value) -> Option<RemoteFile> {
+    let mime_type = file.get("mimeType").and_then(Value::as_str).map(String::from);
+    Some(RemoteFile {
+        id: file.get("id")?.as_str()?.to_string(),
+        name: file.get("name")?.as_str()?.to_string(),
+        is_folder: mime_type.as_deref() == Some(FOLDER_TYPE),
+        // Sizes come as strings (int64)
+        size: file.get("size").and_then(Value::as_str).and_then(|s| s.parse().ok()),
+        modified_at: file.get("modifiedTime").and_then(Value::as_str).map(String::from),
+        mime_type,
+    })
+}
+
+/// Export type and extension of a Google document type
+fn export_type(mime_type: &str) -> Option<(&'static str, &'static str)> {
+    match mime_type {
+        "application/vnd.google-apps.drawing" => Some(("image/png", "png")),
+        t if t.starts_with("application/vnd.google-apps.") && t != FOLDER_TYPE => Some(("application/pdf", "pdf")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_files() {
+        let body = json!({ "files": [
+            { "id": "f1", "name": "Refs", "mimeType": FOLDER_TYPE },
+            { "id": "i1", "name": "hero.png", "mimeType": "image/png", "size": "2048", "modifiedTime": "2024-05-01T10:00:00Z" },
+            { "name": "no id" },
+        ] });
+        let files = parse_files(&body);
+        assert_eq!(files.len(), 2);
+        assert!(files[0].is_folder);
+        assert_eq!(files[1].size, Some(2048));
+
+        assert_eq!(export_type("application/vnd.google-apps.document"), Some(("application/pdf", "pdf")));
+        assert_eq!(export_type(FOLDER_TYPE), None);
+        assert_eq!(export_type("image/png"), None);
+    }
+}
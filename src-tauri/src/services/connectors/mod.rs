@@ -0,0 +1,232 @@
+//! Connectors to the drives teams keep reference material on: Google Drive
+//! and Dropbox.
+//!
+//! Each provider signs in with OAuth (see `oauth`) and can list a folder and
+//! download a file. Imported files become assets from source
+//! `google-drive` / `dropbox`, so the license report flags them until their
+//! rights are recorded.
+
+mod dropbox;
+mod google_drive;
+pub mod oauth;
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{clip, database, folder_import, io_sqlite};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::net_guard::NetworkPolicy;
+use oauth::ClientCredentials;
+use ts_rs::TS;
+
+/// Most entries listed per folder
+pub const MAX_FILES: usize = 1000;
+
+/// Largest file accepted for import
+pub const MAX_FILE_BYTES: u64 = 500 * 1024 * 1024;
+
+const NODE_WIDTH: f64 = 300.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConnectorProvider {
+    GoogleDrive,
+    Dropbox,
+}
+
+impl ConnectorProvider {
+    pub const ALL: [ConnectorProvider; 2] = [ConnectorProvider::GoogleDrive, ConnectorProvider::Dropbox];
+
+    /// Key of the provider in the app settings and token store
+    pub fn key(&self) -> &'static str {
+        match self {
+            ConnectorProvider::GoogleDrive => "google-drive",
+            ConnectorProvider::Dropbox => "dropbox",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectorProvider::GoogleDrive => "Google Drive",
+            ConnectorProvider::Dropbox => "Dropbox",
+        }
+    }
+}
+
+/// A file or folder on a remote drive
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFile {
+    /// Provider's ID, for listing a folder or importing a file
+    pub id: String,
+    pub name: String,
+    pub is_folder: bool,
+    #[ts(type = "number | null")]
+    pub size: Option<u64>,
+    /// RFC 3339
+    pub modified_at: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+/// Asset and node made by `import`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteImport {
+    pub asset: Asset,
+    pub node: SynniaNode,
+}
+
+/// Entries of `folder` (the drive's root when none), folders first.
+pub async fn list_files(
+    config_dir: &Path,
+    policy: &NetworkPolicy,
+    client: &ClientCredentials,
+    provider: ConnectorProvider,
+    folder: Option<&str>,
+) -> Result<Vec<RemoteFile>, AppError> {
+    if let Some(folder) = folder {
+        check_id(folder)?;
+    }
+    let token = oauth::access_token(config_dir, policy, provider, client).await?;
+    let mut files = match provider {
+        ConnectorProvider::GoogleDrive => google_drive::list(policy, &token, folder).await?,
+        ConnectorProvider::Dropbox => dropbox::list(policy, &token, folder).await?,
+    };
+    files.sort_by(|a, b| b.is_folder.cmp(&a.is_folder).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(files)
+}
+
+/// Name and content of a remote file. Google documents come as PDF.
+pub async fn download_file(
+    config_dir: &Path,
+    policy: &NetworkPolicy,
+    client: &ClientCredentials,
+    provider: ConnectorProvider,
+    file_id: &str,
+) -> Result<(String, Vec<u8>), AppError> {
+    check_id(file_id)?;
+    let token = oauth::access_token(config_dir, policy, provider, client).await?;
+    match provider {
+        ConnectorProvider::GoogleDrive => google_drive::download(policy, &token, file_id).await,
+        ConnectorProvider::Dropbox => dropbox::download(policy, &token, file_id).await,
+    }
+}
+
+/// Add a downloaded file to the project as an asset with a node next to
+/// everything on the canvas.
+pub fn import(project_root: &Path, provider: ConnectorProvider, name: &str, data: &[u8]) -> Result<RemoteImport, AppError> {
+    let (value, value_meta, node_type) = clip::store_file(project_root, name, data)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let asset = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Record,
+        value,
+        value_meta,
+        config: Some(serde_json::json!({ "schema": [] })),
+        sys: AssetSysMetadata {
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+            source: provider.key().to_string(),
+        },
+    };
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let node = SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: node_type.to_string(),
+        position: folder_import::free_position(&conn)?,
+        width: Some(NODE_WIDTH),
+        height: None,
+        parent_id: None,
+        extent: None,
+        style: None,
+        data: SynniaNodeData {
+            title: name.to_string(),
+            asset_id: Some(asset.id.clone()),
+            ..Default::default()
+        },
+    };
+    io_sqlite::upsert_node(&conn, &node)?;
+
+    let summary = format!("Imported {} from {}", name, provider.label());
+    activity::record(&conn, &NewActivity::new(ActivityKind::Imported, "user", summary).source(provider.key()).target(&asset.id))?;
+    Ok(RemoteImport { asset, node })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Provider IDs are opaque, but never need more than these characters
+fn check_id(id: &str) -> Result<(), AppError> {
+    if id.is_empty() || id.len() > 256 || !id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':')) {
+        return Err(AppError::InvalidInput(format!("Invalid remote file ID: {}", id)));
+    }
+    Ok(())
+}
+
+fn client(policy: &NetworkPolicy, streaming: bool) -> Result<reqwest::Client, AppError> {
+    let builder = policy.client_builder();
+    let builder = if streaming { builder.read_timeout(policy.timeouts.idle()) } else { builder.timeout(policy.timeouts.request()) };
+    builder.build().map_err(|e| AppError::Network(e.to_string()))
+}
+
+/// Send a request, turning failures into messages about the provider
+async fn send(request: reqwest::RequestBuilder, provider: ConnectorProvider) -> Result<reqwest::Response, AppError> {
+    let response = request.send().await
+        .map_err(|e| AppError::Network(format!("Could not reach {}: {}", provider.label(), e)))?;
+    let status = response.status().as_u16();
+    match status {
+        200..=299 => Ok(response),
+        401 => Err(AppError::Forbidden(format!("The {} sign-in expired; connect it again", provider.label()))),
+        403 => Err(AppError::Forbidden(format!("{} denied access to this file", provider.label()))),
+        404 | 409 => Err(AppError::NotFound(format!("Not found on {}", provider.label()))),
+        429 => Err(AppError::Network(format!("HTTP 429: {} rate limited; try again later", provider.label()))),
+        _ => Err(AppError::Network(format!("HTTP {}: unexpected response from {}", status, provider.label()))),
+    }
+}
+
+/// Read a file body, refusing files over `MAX_FILE_BYTES`
+async fn read_file(mut response: reqwest::Response) -> Result<Vec<u8>, AppError> {
+    let too_large = || AppError::InvalidInput(format!("Files over {} MB can't be imported", MAX_FILE_BYTES / (1024 * 1024)));
+    if response.content_length().is_some_and(|len| len > MAX_FILE_BYTES) {
+        return Err(too_large());
+    }
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| AppError::Network(e.to_string()))? {
+        data.extend_from_slice(&chunk);
+        if data.len() as u64 > MAX_FILE_BYTES {
+            return Err(too_large());
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_import_and_ids() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let import = import(dir.path(), ConnectorProvider::Dropbox, "brief.txt", b"moodboard notes").unwrap();
+        assert_eq!(import.asset.sys.source, "dropbox");
+        assert_eq!(import.node.data.asset_id.as_deref(), Some(import.asset.id.as_str()));
+        assert!(io_sqlite::get_asset(&conn, &import.asset.id).unwrap().is_some());
+
+        assert!(check_id("id:a4ayc_80_OEAAAAAAAAAXw").is_ok());
+        assert!(check_id("1AbC-dEf_23").is_ok());
+        assert!(check_id("x' or '1'='1").is_err());
+        assert!(check_id("").is_err());
+    }
+}
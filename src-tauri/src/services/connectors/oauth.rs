@@ -0,0 +1,313 @@
+//! OAuth sign-in for connectors: authorization code flow with PKCE.
+//!
+//! `start` returns the provider's sign-in URL, which redirects back to the
+//! local server's `/oauth/callback`; `finish` trades the code for tokens
+//! there. Tokens are kept in `connectors.json` in the app config directory,
+//! outside the global config, so settings bundles never carry them.
+//! Connector apps are registered by the studio; their client ID (and, for
+//! Google, client secret) go in the app settings as `providers.<key>`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use super::ConnectorProvider;
+
+/// Connector tokens, in the app config directory
+pub const TOKENS_FILE: &str = "connectors.json";
+
+/// Path of the local server's redirect endpoint
+pub const CALLBACK_PATH: &str = "/oauth/callback";
+
+/// Sign-ins not finished within this long are dropped
+const LOGIN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Refresh access tokens this long before they expire
+const EXPIRY_MARGIN_MS: i64 = 60 * 1000;
+
+/// Connector app registered with the provider
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClientCredentials {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+impl ClientCredentials {
+    /// Connector app of `provider` in the app settings; empty if not configured.
+    pub fn from_config(config: &GlobalConfig, provider: ConnectorProvider) -> Self {
+        config.app_settings.as_deref()
+            .and_then(|s| serde_json::from_str::<Value>(s).ok())
+            .and_then(|settings| settings.get("providers")?.get(provider.key()).cloned())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    fn client_id(&self, provider: ConnectorProvider) -> Result<&str, AppError> {
+        self.client_id.as_deref().map(str::trim).filter(|id| !id.is_empty())
+            .ok_or_else(|| AppError::InvalidInput(format!("Add a {} client ID in Settings", provider.label())))
+    }
+}
+
+/// Tokens of a connected provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix ms; none if the token doesn't expire
+    pub expires_at: Option<i64>,
+}
+
+/// A sign-in waiting for its redirect
+#[derive(Debug, Clone)]
+pub struct PendingLogin {
+    pub provider: ConnectorProvider,
+    pub verifier: String,
+    pub redirect_uri: String,
+    started: Instant,
+}
+
+/// Sign-ins in progress, keyed by their `state` parameter. Shared with the
+/// local server, which receives the redirects.
+#[derive(Default)]
+pub struct PendingLogins {
+    pending: Mutex<HashMap<String, PendingLogin>>,
+}
+
+impl PendingLogins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The sign-in `state` belongs to, removing it. Each state works once.
+    pub fn take(&self, state: &str) -> Option<PendingLogin> {
+        let mut pending = self.pending.lock().ok()?;
+        pending.retain(|_, login| login.started.elapsed() < LOGIN_TTL);
+        pending.remove(state)
+    }
+
+    fn insert(&self, state: String, login: PendingLogin) -> Result<(), AppError> {
+        self.pending.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?
+            .insert(state, login);
+        Ok(())
+    }
+}
+
+/// Begin a sign-in: the URL to open in the browser.
+pub fn start(
+    logins: &PendingLogins,
+    provider: ConnectorProvider,
+    client: &ClientCredentials,
+    redirect_uri: &str,
+) -> Result<String, AppError> {
+    let client_id = client.client_id(provider)?;
+    let verifier = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    let mut params = vec![
+        ("client_id", client_id),
+        ("redirect_uri", redirect_uri),
+        ("response_type", "code"),
+        ("state", state.as_str()),
+        ("code_challenge", challenge.as_str()),
+        ("code_challenge_method", "S256"),
+    ];
+    match provider {
+        ConnectorProvider::GoogleDrive => params.extend([
+            ("scope", "https://www.googleapis.com/auth/drive.readonly"),
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ]),
+        ConnectorProvider::Dropbox => params.push(("token_access_type", "offline")),
+    }
+    let url = reqwest::Url::parse_with_params(auth_url(provider), &params)
+        .map_err(|e| AppError::Unknown(e.to_string()))?;
+
+    logins.insert(state.clone(), PendingLogin {
+        provider,
+        verifier,
+        redirect_uri: redirect_uri.to_string(),
+        started: Instant::now(),
+    })?;
+    Ok(url.to_string())
+}
+
+/// Trade the redirect's code for tokens and store them.
+pub async fn finish(
+    config_dir: &Path,
+    policy: &NetworkPolicy,
+    client: &ClientCredentials,
+    login: &PendingLogin,
+    code: &str,
+) -> Result<(), AppError> {
+    let client_id = client.client_id(login.provider)?;
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("client_id", client_id),
+        ("code_verifier", login.verifier.as_str()),
+        ("redirect_uri", login.redirect_uri.as_str()),
+    ];
+    if let Some(secret) = client.client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+    let token = request_token(policy, login.provider, &form, None).await?;
+    save_token(config_dir, login.provider, Some(&token))
+}
+
+/// A usable access token for `provider`, refreshed if it has expired.
+pub async fn access_token(
+    config_dir: &Path,
+    policy: &NetworkPolicy,
+    provider: ConnectorProvider,
+    client: &ClientCredentials,
+) -> Result<String, AppError> {
+    let token = load_tokens(config_dir).remove(provider.key())
+        .ok_or_else(|| AppError::Forbidden(format!("Connect {} first", provider.label())))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    if !token.expires_at.is_some_and(|at| at - EXPIRY_MARGIN_MS <= now) {
+        return Ok(token.access_token);
+    }
+
+    let refresh_token = token.refresh_token.as_deref()
+        .ok_or_else(|| AppError::Forbidden(format!("The {} sign-in expired; connect it again", provider.label())))?;
+    let client_id = client.client_id(provider)?;
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client_id),
+    ];
+    if let Some(secret) = client.client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+    let refreshed = request_token(policy, provider, &form, token.refresh_token.clone()).await?;
+    save_token(config_dir, provider, Some(&refreshed))?;
+    Ok(refreshed.access_token)
+}
+
+/// Providers with stored tokens
+pub fn connected(config_dir: &Path) -> Vec<ConnectorProvider> {
+    let tokens = load_tokens(config_dir);
+    ConnectorProvider::ALL.into_iter().filter(|p| tokens.contains_key(p.key())).collect()
+}
+
+/// Store (or with `None`, forget) the tokens of `provider`.
+pub fn save_token(config_dir: &Path, provider: ConnectorProvider, token: Option<&OAuthToken>) -> Result<(), AppError> {
+    let mut tokens = load_tokens(config_dir);
+    match token {
+        Some(token) => tokens.insert(provider.key().to_string(), token.clone()),
+        None => tokens.remove(provider.key()),
+    };
+    fs::create_dir_all(config_dir)?;
+    let path = config_dir.join(TOKENS_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&tokens)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn load_tokens(config_dir: &Path) -> HashMap<String, OAuthToken> {
+    fs::read(config_dir.join(TOKENS_FILE)).ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn auth_url(provider: ConnectorProvider) -> &'static str {
+    match provider {
+        ConnectorProvider::GoogleDrive => "https://accounts.google.com/o/oauth2/v2/auth",
+        ConnectorProvider::Dropbox => "https://www.dropbox.com/oauth2/authorize",
+    }
+}
+
+fn token_url(provider: ConnectorProvider) -> &'static str {
+    match provider {
+        ConnectorProvider::GoogleDrive => "https://oauth2.googleapis.com/token",
+        ConnectorProvider::Dropbox => "https://api.dropboxapi.com/oauth2/token",
+    }
+}
+
+/// POST to the token endpoint. Refreshes may leave out the refresh token,
+/// which then stays `keep_refresh`.
+async fn request_token(
+    policy: &NetworkPolicy,
+    provider: ConnectorProvider,
+    form: &[(&str, &str)],
+    keep_refresh: Option<String>,
+) -> Result<OAuthToken, AppError> {
+    let url = policy.check_url(token_url(provider)).await?;
+    let client = policy.client_builder()
+        .timeout(policy.timeouts.request())
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let response = client.post(url).form(form).send().await
+        .map_err(|e| AppError::Network(format!("Could not reach {}: {}", provider.label(), e)))?;
+    let status = response.status();
+    let body: Value = response.json().await
+        .map_err(|e| AppError::Network(format!("Unexpected response from {}: {}", provider.label(), e)))?;
+    if !status.is_success() {
+        let reason = body.get("error_description").or_else(|| body.get("error"))
+            .and_then(Value::as_str)
+            .unwrap_or("unexpected response");
+        return Err(AppError::Forbidden(format!("{} sign-in failed: {}", provider.label(), reason)));
+    }
+    parse_token(&body, keep_refresh)
+        .ok_or_else(|| AppError::Network(format!("Unexpected response from {}", provider.label())))
+}
+
+fn parse_token(body: &Value, keep_refresh: Option<String>) -> Option<OAuthToken> {
+    let now = chrono::Utc::now().timestamp_millis();
+    Some(OAuthToken {
+        access_token: body.get("access_token")?.as_str()?.to_string(),
+        refresh_token: body.get("refresh_token").and_then(Value::as_str).map(String::from).or(keep_refresh),
+        expires_at: body.get("expires_in").and_then(Value::as_i64).map(|secs| now + secs * 1000),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_start_and_store_tokens() {
+        let logins = PendingLogins::new();
+        let client = ClientCredentials { client_id: Some("app-id".to_string()), client_secret: None };
+        let url = start(&logins, ConnectorProvider::Dropbox, &client, "http://127.0.0.1:4000/oauth/callback").unwrap();
+        let url = reqwest::Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["token_access_type"], "offline");
+
+        let login = logins.take(&params["state"]).unwrap();
+        let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(login.verifier.as_bytes()));
+        assert_eq!(params["code_challenge"], challenge);
+        assert!(logins.take(&params["state"]).is_none(), "a state works once");
+        assert!(start(&logins, ConnectorProvider::GoogleDrive, &ClientCredentials::default(), "x").is_err());
+
+        let dir = tempdir().unwrap();
+        let token = parse_token(&serde_json::json!({ "access_token": "a", "expires_in": 3600 }), Some("r".to_string())).unwrap();
+        assert_eq!(token.refresh_token.as_deref(), Some("r"));
+        save_token(dir.path(), ConnectorProvider::Dropbox, Some(&token)).unwrap();
+        assert_eq!(connected(dir.path()), [ConnectorProvider::Dropbox]);
+        save_token(dir.path(), ConnectorProvider::Dropbox, None).unwrap();
+        assert!(connected(dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Content-Type detection for served assets.
+//!
+//! Sniffs magic bytes first and falls back to the file extension, so assets
+//! saved with a wrong or missing extension are still served correctly.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of leading bytes inspected when sniffing.
+const SNIFF_LEN: usize = 512;
+
+/// Detect the MIME type of raw bytes from their magic signature.
+/// Returns None if the signature is not recognized.
+pub fn sniff_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.starts_with(b"RIFF") && data.len() >= 12 && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(b"RIFF") && data.len() >= 12 && &data[8..12] == b"WAVE" {
+        Some("audio/wav")
+    } else if data.starts_with(b"BM") && data.len() >= 14 {
+        Some("image/bmp")
+    } else if data.starts_with(&[0x49, 0x49, 0x2A, 0x00]) || data.starts_with(&[0x4D, 0x4D, 0x00, 0x2A]) {
+        Some("image/tiff")
+    } else if data.starts_with(&[0x00, 0x00, 0x01, 0x00]) {
+        Some("image/x-icon")
+    } else if data.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("video/webm")
+    } else if data.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if data.starts_with(b"fLaC") {
+        Some("audio/flac")
+    } else if data.starts_with(b"ID3") || (data.len() >= 2 && data[0] == 0xFF && (data[1] & 0xE0) == 0xE0) {
+        Some("audio/mpeg")
+    } else if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        // ISO base media: the major brand tells the flavour
+        match &data[8..12] {
+            b"avif" | b"avis" => Some("image/avif"),
+            b"heic" | b"heix" | b"mif1" => Some("image/heic"),
+            b"qt  " => Some("video/quicktime"),
+            b"M4A " => Some("audio/mp4"),
+            _ => Some("video/mp4"),
+        }
+    } else if looks_like_svg(data) {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Detect the MIME type of a file, sniffing its header first and
+/// falling back to the extension.
+pub fn detect_file_mime(path: &Path) -> String {
+    let mut header = Vec::with_capacity(SNIFF_LEN);
+    if let Ok(file) = File::open(path) {
+        let _ = file.take(SNIFF_LEN as u64).read_to_end(&mut header);
+    }
+
+    if let Some(mime) = sniff_mime(&header) {
+        return mime.to_string();
+    }
+
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    actix_files::file_extension_to_mime(ext).to_string()
+}
+
+fn looks_like_svg(data: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(data);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    trimmed.starts_with("<svg") || (trimmed.starts_with("<?xml") && trimmed.contains("<svg"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A]), Some("image/png"));
+        assert_eq!(sniff_mime(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("image/jpeg"));
+        assert_eq!(sniff_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime(b"\0\0\0\x18ftypisom"), Some("video/mp4"));
+        assert_eq!(sniff_mime(b"<?xml version=\"1.0\"?><svg></svg>"), Some("image/svg+xml"));
+        assert_eq!(sniff_mime(b"plain text"), None);
+    }
+
+    #[test]
+    fn test_detect_file_mime_prefers_magic_bytes() {
+        let dir = tempdir().unwrap();
+
+        // PNG bytes saved with a misleading extension
+        let misnamed = dir.path().join("image.jpg");
+        std::fs::write(&misnamed, [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]).unwrap();
+        assert_eq!(detect_file_mime(&misnamed), "image/png");
+
+        // Unknown bytes fall back to the extension
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "hello").unwrap();
+        assert!(detect_file_mime(&text).starts_with("text/plain"));
+    }
+}
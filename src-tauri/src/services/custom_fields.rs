@@ -0,0 +1,542 @@
+//! Custom fields: project-defined metadata ("status", "client", "shot
+//! number") on assets and nodes.
+//!
+//! A field has a kind (text, number, select or date) that every value is
+//! checked against: select values must be one of the field's options, dates
+//! are stored as `YYYY-MM-DD`. Values live in `custom_field_values`, keyed by
+//! field and target; setting a value to null or an empty string clears it.
+//! Filters over values plug into asset search (`tags::search`) and node
+//! search, and PDF captions can show chosen fields.
+
+use std::collections::HashMap;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::AppError;
+use crate::services::io_sqlite;
+use ts_rs::TS;
+
+const MAX_NAME_LEN: usize = 64;
+const MAX_TEXT_LEN: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Text,
+    Number,
+    /// One of the field's options
+    Select,
+    /// `YYYY-MM-DD`
+    Date,
+}
+
+/// What a value is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldTarget {
+    Asset,
+    Node,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    pub id: String,
+    pub name: String,
+    pub kind: FieldKind,
+    /// Choices of a select field
+    pub options: Vec<String>,
+    /// Display order
+    pub position: usize,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// A field to create (no `id`) or update
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: FieldKind,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldValue {
+    pub field_id: String,
+    pub target: FieldTarget,
+    pub target_id: String,
+    #[ts(type = "string | number")]
+    pub value: Value,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOp {
+    Equals,
+    /// Text contains `value`, ignoring ASCII case
+    Contains,
+    /// Number or date at least `value`
+    AtLeast,
+    /// Number or date at most `value`
+    AtMost,
+    /// Has any value
+    IsSet,
+}
+
+/// One condition on a custom field; filters in a search all have to match
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldFilter {
+    pub field_id: String,
+    pub op: FilterOp,
+    #[serde(default)]
+    #[ts(type = "string | number | null")]
+    pub value: Value,
+}
+
+/// All fields in display order.
+pub fn list_fields(conn: &Connection) -> Result<Vec<CustomField>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, kind, options_json, position, created_at FROM custom_fields ORDER BY position, created_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], row_to_field)
+        .map_err(|e| AppError::Io(format!("Failed to query custom fields: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load custom fields: {}", e)))
+}
+
+pub fn get_field(conn: &Connection, field_id: &str) -> Result<Option<CustomField>, AppError> {
+    conn.query_row(
+        "SELECT id, name, kind, options_json, position, created_at FROM custom_fields WHERE id = ?1",
+        params![field_id],
+        row_to_field,
+    )
+    .optional()
+    .map_err(|e| AppError::Io(format!("Failed to query custom field: {}", e)))
+}
+
+/// Create a field, or update the one with `input.id`. Values that no longer
+/// fit (after a kind change or a removed option) are cleared.
+pub fn save_field(conn: &Connection, input: &CustomFieldInput) -> Result<CustomField, AppError> {
+    let name = input.name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::InvalidInput(format!("Field names must be 1 to {} characters", MAX_NAME_LEN)));
+    }
+    let mut options: Vec<String> = Vec::new();
+    if input.kind == FieldKind::Select {
+        for option in input.options.iter().map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            if !options.iter().any(|o| o == option) {
+                options.push(option.to_string());
+            }
+        }
+        if options.is_empty() {
+            return Err(AppError::InvalidInput("Select fields need at least one option".to_string()));
+        }
+    }
+    if let Some(other) = list_fields(conn)?.into_iter()
+        .find(|f| f.name.eq_ignore_ascii_case(&name) && Some(&f.id) != input.id.as_ref())
+    {
+        return Err(AppError::InvalidInput(format!("A field named \"{}\" already exists", other.name)));
+    }
+    let options_json = serde_json::to_string(&options)?;
+
+    let field = match &input.id {
+        Some(id) => {
+            let existing = get_field(conn, id)?
+                .ok_or_else(|| AppError::NotFound(format!("Custom field not found: {}", id)))?;
+            conn.execute(
+                "UPDATE custom_fields SET name = ?1, kind = ?2, options_json = ?3 WHERE id = ?4",
+                params![name, kind_name(input.kind), options_json, id],
+            ).map_err(|e| AppError::Io(format!("Failed to update custom field: {}", e)))?;
+            let field = CustomField { name, kind: input.kind, options, ..existing.clone() };
+            if existing.kind != field.kind {
+                clear_values(conn, &field.id)?;
+            } else if field.kind == FieldKind::Select {
+                conn.execute(
+                    "DELETE FROM custom_field_values WHERE field_id = ?1
+                     AND json_extract(value_json, '$') NOT IN (SELECT value FROM json_each(?2))",
+                    params![field.id, options_json],
+                ).map_err(|e| AppError::Io(format!("Failed to clear values: {}", e)))?;
+            }
+            field
+        }
+        None => {
+            let position: usize = conn.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM custom_fields", [], |row| row.get(0),
+            ).map_err(|e| AppError::Io(format!("Failed to query custom fields: {}", e)))?;
+            let field = CustomField {
+                id: uuid::Uuid::new_v4().to_string(),
+                name,
+                kind: input.kind,
+                options,
+                position,
+                created_at: chrono::Utc::now().timestamp_millis(),
+            };
+            conn.execute(
+                "INSERT INTO custom_fields (id, name, kind, options_json, position, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![field.id, field.name, kind_name(field.kind), options_json, field.position, field.created_at],
+            ).map_err(|e| AppError::Io(format!("Failed to save custom field: {}", e)))?;
+            field
+        }
+    };
+    Ok(field)
+}
+
+/// Delete a field and all its values. Returns false if it didn't exist.
+pub fn delete_field(conn: &Connection, field_id: &str) -> Result<bool, AppError> {
+    clear_values(conn, field_id)?;
+    let deleted = conn.execute("DELETE FROM custom_fields WHERE id = ?1", params![field_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete custom field: {}", e)))?;
+    Ok(deleted > 0)
+}
+
+/// Set (or, with null or an empty string, clear) a field on an asset or
+/// node. Returns the target's values.
+pub fn set_value(
+    conn: &Connection,
+    target: FieldTarget,
+    target_id: &str,
+    field_id: &str,
+    value: &Value,
+) -> Result<Vec<FieldValue>, AppError> {
+    let field = get_field(conn, field_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Custom field not found: {}", field_id)))?;
+    let exists = match target {
+        FieldTarget::Asset => io_sqlite::get_asset(conn, target_id)?.is_some(),
+        FieldTarget::Node => io_sqlite::get_node(conn, target_id)?.is_some(),
+    };
+    if !exists {
+        return Err(AppError::NotFound(format!("{} not found: {}", target_name(target), target_id)));
+    }
+
+    if value.is_null() || value.as_str().is_some_and(|s| s.trim().is_empty()) {
+        conn.execute(
+            "DELETE FROM custom_field_values WHERE field_id = ?1 AND target_id = ?2",
+            params![field_id, target_id],
+        ).map_err(|e| AppError::Io(format!("Failed to clear value: {}", e)))?;
+    } else {
+        let value = normalize_value(&field, value)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO custom_field_values (field_id, target, target_id, value_json, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![field_id, target_name(target), target_id, value.to_string(), chrono::Utc::now().timestamp_millis()],
+        ).map_err(|e| AppError::Io(format!("Failed to save value: {}", e)))?;
+    }
+    get_values(conn, target_id)
+}
+
+/// Values of an asset or node, in field order.
+pub fn get_values(conn: &Connection, target_id: &str) -> Result<Vec<FieldValue>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT v.field_id, v.target, v.target_id, v.value_json, v.updated_at
+         FROM custom_field_values v JOIN custom_fields f ON f.id = v.field_id
+         WHERE v.target_id = ?1 ORDER BY f.position, f.created_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![target_id], |row| {
+        Ok(FieldValue {
+            field_id: row.get(0)?,
+            target: if row.get::<_, String>(1)? == "node" { FieldTarget::Node } else { FieldTarget::Asset },
+            target_id: row.get(2)?,
+            value: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or(Value::Null),
+            updated_at: row.get(4)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query values: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load values: {}", e)))
+}
+
+/// `Name: value` lines of the given fields for every asset and node that has
+/// one of them, keyed by target ID and in the order of `field_ids`.
+pub fn labels(conn: &Connection, field_ids: &[String]) -> Result<HashMap<String, Vec<String>>, AppError> {
+    let fields: HashMap<String, CustomField> = list_fields(conn)?.into_iter().map(|f| (f.id.clone(), f)).collect();
+    let mut stmt = conn.prepare(
+        "SELECT target_id, field_id, value_json FROM custom_field_values
+         WHERE field_id IN (SELECT value FROM json_each(?1))"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![serde_json::to_string(field_ids)?], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    }).map_err(|e| AppError::Io(format!("Failed to query values: {}", e)))?;
+
+    let mut by_target: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+    for (target_id, field_id, value_json) in rows.flatten() {
+        let (Some(field), Some(order)) = (fields.get(&field_id), field_ids.iter().position(|id| *id == field_id)) else {
+            continue;
+        };
+        let value = match serde_json::from_str::<Value>(&value_json) {
+            Ok(Value::String(text)) => text,
+            Ok(other) => other.to_string(),
+            Err(_) => continue,
+        };
+        by_target.entry(target_id).or_default().push((order, format!("{}: {}", field.name, value)));
+    }
+    Ok(by_target.into_iter()
+        .map(|(target_id, mut labels)| {
+            labels.sort();
+            (target_id, labels.into_iter().map(|(_, label)| label).collect())
+        })
+        .collect())
+}
+
+/// SQL condition for `filter` on the row whose ID is `id_column`, with its
+/// parameters appended to `params` (numbered after the ones already there).
+pub fn filter_condition(conn: &Connection, filter: &FieldFilter, id_column: &str, params: &mut Vec<SqlValue>) -> Result<String, AppError> {
+    let field = get_field(conn, &filter.field_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Custom field not found: {}", filter.field_id)))?;
+    params.push(SqlValue::Text(field.id.clone()));
+    let field_param = params.len();
+    let comparison = match filter.op {
+        FilterOp::IsSet => String::new(),
+        FilterOp::Contains => {
+            let Some(text) = filter.value.as_str() else {
+                return Err(AppError::InvalidInput("`contains` needs a text value".to_string()));
+            };
+            let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            params.push(SqlValue::Text(format!("%{}%", escaped)));
+            format!(" AND json_extract(v.value_json, '$') LIKE ?{} ESCAPE '\\'", params.len())
+        }
+        FilterOp::Equals | FilterOp::AtLeast | FilterOp::AtMost => {
+            let operator = match filter.op {
+                FilterOp::AtLeast => ">=",
+                FilterOp::AtMost => "<=",
+                _ => "=",
+            };
+            params.push(sql_value(&normalize_value(&field, &filter.value)?));
+            format!(" AND json_extract(v.value_json, '$') {} ?{}", operator, params.len())
+        }
+    };
+    Ok(format!(
+        "EXISTS (SELECT 1 FROM custom_field_values v WHERE v.target_id = {} AND v.field_id = ?{}{})",
+        id_column, field_param, comparison
+    ))
+}
+
+/// IDs of assets (most recently updated first) or nodes (board order)
+/// matching every filter.
+pub fn search(conn: &Connection, target: FieldTarget, filters: &[FieldFilter], limit: usize) -> Result<Vec<String>, AppError> {
+    if filters.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut params = Vec::new();
+    let conditions = filters.iter()
+        .map(|filter| filter_condition(conn, filter, "t.id", &mut params))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(" AND ");
+    let sql = match target {
+        FieldTarget::Asset => format!("SELECT t.id FROM assets t WHERE {} ORDER BY t.updated_at DESC LIMIT {}", conditions, limit),
+        FieldTarget::Node => format!("SELECT t.id FROM nodes t WHERE {} ORDER BY t.rowid LIMIT {}", conditions, limit),
+    };
+
+    let mut stmt = conn.prepare(&sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params_from_iter(params.iter()), |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to search custom fields: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load search results: {}", e)))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn row_to_field(row: &rusqlite::Row) -> rusqlite::Result<CustomField> {
+    let kind: String = row.get(2)?;
+    let options: String = row.get(3)?;
+    Ok(CustomField {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: serde_json::from_value(Value::String(kind)).unwrap_or(FieldKind::Text),
+        options: serde_json::from_str(&options).unwrap_or_default(),
+        position: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn kind_name(kind: FieldKind) -> &'static str {
+    match kind {
+        FieldKind::Text => "text",
+        FieldKind::Number => "number",
+        FieldKind::Select => "select",
+        FieldKind::Date => "date",
+    }
+}
+
+fn target_name(target: FieldTarget) -> &'static str {
+    match target {
+        FieldTarget::Asset => "asset",
+        FieldTarget::Node => "node",
+    }
+}
+
+fn clear_values(conn: &Connection, field_id: &str) -> Result<(), AppError> {
+    conn.execute("DELETE FROM custom_field_values WHERE field_id = ?1", params![field_id])
+        .map_err(|e| AppError::Io(format!("Failed to clear values: {}", e)))?;
+    Ok(())
+}
+
+/// Check `value` against the field's kind and bring it to its stored form
+fn normalize_value(field: &CustomField, value: &Value) -> Result<Value, AppError> {
+    let invalid = |expected: &str| AppError::InvalidInput(format!("\"{}\" expects {}", field.name, expected));
+    match field.kind {
+        FieldKind::Text => {
+            let text = value.as_str().map(str::trim).ok_or_else(|| invalid("text"))?;
+            if text.chars().count() > MAX_TEXT_LEN {
+                return Err(invalid(&format!("at most {} characters", MAX_TEXT_LEN)));
+            }
+            Ok(Value::String(text.to_string()))
+        }
+        FieldKind::Number => {
+            let number = match value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse::<f64>().ok(),
+                _ => None,
+            }.filter(|n| n.is_finite()).ok_or_else(|| invalid("a number"))?;
+            // Whole numbers stay integers, so they read "12" rather than "12.0"
+            Ok(if number.fract() == 0.0 && number.abs() < 1e15 {
+                serde_json::json!(number as i64)
+            } else {
+                serde_json::json!(number)
+            })
+        }
+        FieldKind::Select => {
+            let choice = value.as_str().map(str::trim).ok_or_else(|| invalid("one of its options"))?;
+            field.options.iter()
+                .find(|o| o.as_str() == choice)
+                .map(|o| Value::String(o.clone()))
+                .ok_or_else(|| invalid(&format!("one of: {}", field.options.join(", "))))
+        }
+        FieldKind::Date => {
+            let text = value.as_str().map(str::trim).ok_or_else(|| invalid("a date"))?;
+            let date = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .or_else(|_| chrono::DateTime::parse_from_rfc3339(text).map(|d| d.date_naive()))
+                .map_err(|_| invalid("a date (YYYY-MM-DD)"))?;
+            Ok(Value::String(date.format("%Y-%m-%d").to_string()))
+        }
+    }
+}
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::String(text) => SqlValue::Text(text.clone()),
+        Value::Number(n) => n.as_f64().map_or(SqlValue::Null, SqlValue::Real),
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Null => SqlValue::Null,
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::database;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for (id, updated) in [("hero", 1), ("logo", 2)] {
+            conn.execute(
+                "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+                 VALUES (?1, 'record', '', '{}', '{}', ?2)",
+                params![id, updated],
+            ).unwrap();
+        }
+        io_sqlite::upsert_node(&conn, &SynniaNode {
+            id: "shot-1".to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData { title: "Shot 1".to_string(), ..Default::default() },
+        }).unwrap();
+        (dir, conn)
+    }
+
+    fn field(conn: &Connection, name: &str, kind: FieldKind, options: &[&str]) -> CustomField {
+        save_field(conn, &CustomFieldInput {
+            id: None,
+            name: name.to_string(),
+            kind,
+            options: options.iter().map(|o| o.to_string()).collect(),
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_fields_and_values() {
+        let (_dir, conn) = setup();
+        let status = field(&conn, "Status", FieldKind::Select, &["draft", "approved", "draft"]);
+        let shot = field(&conn, "Shot number", FieldKind::Number, &[]);
+        let due = field(&conn, "Due", FieldKind::Date, &[]);
+        assert_eq!(status.options, ["draft", "approved"]);
+        assert_eq!(list_fields(&conn).unwrap().iter().map(|f| f.position).collect::<Vec<_>>(), [0, 1, 2]);
+
+        // Names are unique regardless of case; select fields need options
+        let duplicate = CustomFieldInput { id: None, name: "status".to_string(), kind: FieldKind::Text, options: vec![] };
+        assert!(matches!(save_field(&conn, &duplicate), Err(AppError::InvalidInput(_))));
+        let empty = CustomFieldInput { id: None, name: "Client".to_string(), kind: FieldKind::Select, options: vec![] };
+        assert!(matches!(save_field(&conn, &empty), Err(AppError::InvalidInput(_))));
+
+        set_value(&conn, FieldTarget::Asset, "hero", &status.id, &json!("approved")).unwrap();
+        set_value(&conn, FieldTarget::Asset, "hero", &shot.id, &json!("12")).unwrap();
+        let values = set_value(&conn, FieldTarget::Asset, "hero", &due.id, &json!("2026-03-01T10:00:00Z")).unwrap();
+        assert_eq!(values.iter().map(|v| v.value.clone()).collect::<Vec<_>>(), [json!("approved"), json!(12), json!("2026-03-01")]);
+
+        assert!(matches!(set_value(&conn, FieldTarget::Asset, "hero", &status.id, &json!("final")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(set_value(&conn, FieldTarget::Asset, "hero", &due.id, &json!("soon")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(set_value(&conn, FieldTarget::Node, "missing", &status.id, &json!("draft")), Err(AppError::NotFound(_))));
+
+        // Clearing, and removing an option drops the values that used it
+        assert_eq!(set_value(&conn, FieldTarget::Asset, "hero", &shot.id, &json!("")).unwrap().len(), 2);
+        save_field(&conn, &CustomFieldInput { id: Some(status.id.clone()), name: "Status".to_string(), kind: FieldKind::Select, options: vec!["draft".to_string()] }).unwrap();
+        assert_eq!(get_values(&conn, "hero").unwrap().len(), 1);
+
+        assert!(delete_field(&conn, &due.id).unwrap());
+        assert!(get_values(&conn, "hero").unwrap().is_empty());
+        assert!(!delete_field(&conn, &due.id).unwrap());
+    }
+
+    #[test]
+    fn test_search_and_labels() {
+        let (_dir, conn) = setup();
+        let client = field(&conn, "Client", FieldKind::Text, &[]);
+        let shot = field(&conn, "Shot", FieldKind::Number, &[]);
+        set_value(&conn, FieldTarget::Asset, "hero", &client.id, &json!("Acme Studios")).unwrap();
+        set_value(&conn, FieldTarget::Asset, "logo", &client.id, &json!("Globex")).unwrap();
+        set_value(&conn, FieldTarget::Asset, "hero", &shot.id, &json!(12)).unwrap();
+        set_value(&conn, FieldTarget::Node, "shot-1", &shot.id, &json!(3)).unwrap();
+
+        let filter = |field: &CustomField, op, value| FieldFilter { field_id: field.id.clone(), op, value };
+        assert_eq!(search(&conn, FieldTarget::Asset, &[filter(&client, FilterOp::IsSet, Value::Null)], 10).unwrap(), ["logo", "hero"]);
+        assert_eq!(search(&conn, FieldTarget::Asset, &[filter(&client, FilterOp::Contains, json!("acme"))], 10).unwrap(), ["hero"]);
+        assert_eq!(search(&conn, FieldTarget::Asset, &[filter(&shot, FilterOp::Equals, json!(12))], 10).unwrap(), ["hero"]);
+        assert_eq!(search(&conn, FieldTarget::Node, &[filter(&shot, FilterOp::AtMost, json!(5))], 10).unwrap(), ["shot-1"]);
+        assert!(search(&conn, FieldTarget::Asset, &[
+            filter(&client, FilterOp::Equals, json!("Globex")),
+            filter(&shot, FilterOp::AtLeast, json!(1)),
+        ], 10).unwrap().is_empty());
+
+        let labels = labels(&conn, &[shot.id.clone(), client.id.clone()]).unwrap();
+        assert_eq!(labels["hero"], ["Shot: 12", "Client: Acme Studios"]);
+        assert_eq!(labels["shot-1"], ["Shot: 3"]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Free-space checks before large writes.
+//!
+//! Imports, exports and downloads estimate how much they're about to write
+//! and check it against the free space on the target volume first. Not
+//! enough room fails with `AppError::InsufficientSpace` before anything is
+//! copied; enough room but little left over emits `disk:low-space` so the
+//! user can clean up before the next write fails.
+//!
+//! When free space can't be read (unsupported filesystem, permissions) the
+//! write goes ahead as it did before.
+
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use tauri::AppHandle;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::services::fs_paths;
+use ts_rs::TS;
+
+/// Free space below which a write still goes ahead but triggers a warning
+pub const LOW_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Payload of the `disk:low-space` event
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LowDiskSpace {
+    /// Folder being written to
+    pub path: String,
+    /// Bytes about to be written
+    #[ts(type = "number")]
+    pub required: u64,
+    /// Bytes free before the write
+    #[ts(type = "number")]
+    pub available: u64,
+}
+
+/// Bytes free for the current user on the volume holding `path`. The path
+/// doesn't have to exist yet; its nearest existing folder is used.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let mut existing = fs_paths::long_path(path);
+    while !existing.exists() {
+        if !existing.pop() {
+            return None;
+        }
+    }
+    free_bytes(&existing)
+}
+
+/// Check that `required` bytes fit under `target`. Returns a warning when
+/// they fit but leave less than `LOW_SPACE_BYTES` free.
+pub fn check_space(target: &Path, required: u64) -> Result<Option<LowDiskSpace>, AppError> {
+    match available_space(target) {
+        Some(available) => compare_space(target, required, available),
+        None => Ok(None),
+    }
+}
+
+/// `check_space`, emitting `disk:low-space` for a warning
+pub fn ensure_space(app: &AppHandle, target: &Path, required: u64) -> Result<(), AppError> {
+    if let Some(warning) = check_space(target, required)? {
+        println!("[DiskSpace] Low on space in {}: {} free", warning.path, format_bytes(warning.available));
+        let _ = events::emit(app, AppEvent::LowDiskSpace(warning));
+    }
+    Ok(())
+}
+
+/// Total size of the given files; missing ones count as empty
+pub fn files_size<P: AsRef<Path>>(paths: &[P]) -> u64 {
+    paths.iter()
+        .filter_map(|p| fs::metadata(fs_paths::long_path(p.as_ref())).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// `check_space` for a volume with `available` bytes free
+fn compare_space(target: &Path, required: u64, available: u64) -> Result<Option<LowDiskSpace>, AppError> {
+    if required > available {
+        return Err(AppError::InsufficientSpace(format!(
+            "Not enough disk space in {}: {} needed, {} available",
+            target.display(), format_bytes(required), format_bytes(available)
+        )));
+    }
+    Ok((available - required < LOW_SPACE_BYTES).then(|| LowDiskSpace {
+        path: target.to_string_lossy().into_owned(),
+        required,
+        available,
+    }))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(unix)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `c_path` is NUL-terminated and `stat` is only read after a successful call
+    if unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let stat = unsafe { stat.assume_init() };
+    #[allow(clippy::useless_conversion)]
+    Some(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+}
+
+#[cfg(windows)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated; the other outputs may be null
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_available_space() {
+        let dir = tempdir().unwrap();
+        let available = available_space(dir.path()).unwrap();
+        assert!(available > 0);
+        // A folder that doesn't exist yet reports its parent's volume
+        assert!(available_space(&dir.path().join("exports/new")).is_some());
+    }
+
+    #[test]
+    fn test_check_space() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(check_space(dir.path(), u64::MAX), Err(AppError::InsufficientSpace(_))));
+
+        let target = Path::new("/exports");
+        let available = 10 * LOW_SPACE_BYTES;
+        assert!(matches!(compare_space(target, available + 1, available), Err(AppError::InsufficientSpace(_))));
+        assert_eq!(compare_space(target, 1024, available).unwrap(), None);
+
+        // Fits, but leaves less than LOW_SPACE_BYTES free
+        let warning = compare_space(target, available - 1024 * 1024, available).unwrap().unwrap();
+        assert_eq!(warning, LowDiskSpace { path: "/exports".to_string(), required: available - 1024 * 1024, available });
+        assert!(compare_space(target, available, available).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_files_size() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.bin"), vec![0u8; 300]).unwrap();
+        fs::write(dir.path().join("b.bin"), vec![0u8; 200]).unwrap();
+        let paths = [dir.path().join("a.bin"), dir.path().join("b.bin"), dir.path().join("missing")];
+        assert_eq!(files_size(&paths), 500);
+        assert_eq!(format_bytes(500), "500 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+    }
+}
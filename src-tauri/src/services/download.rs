@@ -0,0 +1,476 @@
+//! Download manager for remote assets (AI image/video outputs).
+//!
+//! Provides:
+//! - Concurrency limiting across all downloads
+//! - `download:progress` events
+//! - Retry with HTTP Range resume from a `.part` file, removed on failure
+//!   unless the caller wants to resume later
+//! - Max-size limits and cancellation by download ID
+//! - A free-space check once the size is known
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::sync::Semaphore;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::services::disk_space;
+use crate::services::net_guard::NetworkPolicy;
+use ts_rs::TS;
+
+/// Max downloads running at the same time
+const MAX_CONCURRENT_DOWNLOADS: usize = 3;
+
+/// Default size cap (2 GiB) for a single download
+pub const DEFAULT_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Emit a progress event at most every this many bytes
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+/// Options for a single download
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub max_bytes: u64,
+    pub max_retries: u32,
+    pub timeout: Option<Duration>,
+    /// Keep `dest.part` after a failure so that a later download to the same
+    /// `dest` resumes it; a cancelled download never keeps it
+    pub keep_partial: bool,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_retries: 3,
+            timeout: None,
+            keep_partial: false,
+        }
+    }
+}
+
+/// Payload of the `download:progress` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub download_id: String,
+    #[ts(type = "number")]
+    pub received: u64,
+    #[ts(type = "number | null")]
+    pub total: Option<u64>,
+    pub attempt: u32,
+}
+
+/// Final result of a download
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadOutcome {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub content_type: Option<String>,
+}
+
+/// Receives a download's progress and low-space events
+type Reporter<'a> = dyn Fn(AppEvent) + Sync + 'a;
+
+/// Tracks running downloads and their cancellation flags
+pub struct DownloadManager {
+    active: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl Default for DownloadManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashMap::new()),
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS)),
+        }
+    }
+
+    /// Request cancellation of a running download.
+    /// Returns false if no download with that ID is active.
+    pub fn cancel(&self, download_id: &str) -> bool {
+        let active = match self.active.lock() {
+            Ok(active) => active,
+            Err(_) => return false,
+        };
+        match active.get(download_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// IDs of downloads currently queued or running
+    pub fn active_ids(&self) -> Vec<String> {
+        self.active.lock()
+            .map(|a| a.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Download `url` to `dest`, resuming from `dest.part` on retry.
+    pub async fn download(
+        &self,
+        app: &AppHandle,
+        policy: &NetworkPolicy,
+        download_id: &str,
+        url: &str,
+        dest: &Path,
+        options: &DownloadOptions,
+    ) -> Result<DownloadOutcome, AppError> {
+        let report = |event: AppEvent| {
+            let _ = events::emit(app, event);
+        };
+        self.fetch(policy, download_id, url, dest, options, &report).await
+    }
+
+    /// `download`, with its events going to `report`
+    async fn fetch(
+        &self,
+        policy: &NetworkPolicy,
+        download_id: &str,
+        url: &str,
+        dest: &Path,
+        options: &DownloadOptions,
+        report: &Reporter<'_>,
+    ) -> Result<DownloadOutcome, AppError> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        {
+            let mut active = self.active.lock()
+                .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+            if active.contains_key(download_id) {
+                return Err(AppError::Unknown(format!("Download already running: {}", download_id)));
+            }
+            active.insert(download_id.to_string(), cancelled.clone());
+        }
+
+        let result = self.run(policy, download_id, url, dest, options, &cancelled, report).await;
+
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(download_id);
+        }
+        if result.is_err() && (!options.keep_partial || cancelled.load(Ordering::SeqCst)) {
+            let _ = std::fs::remove_file(part_path(dest));
+        }
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run(
+        &self,
+        policy: &NetworkPolicy,
+        download_id: &str,
+        url: &str,
+        dest: &Path,
+        options: &DownloadOptions,
+        cancelled: &AtomicBool,
+        report: &Reporter<'_>,
+    ) -> Result<DownloadOutcome, AppError> {
+        let _permit = self.permits.acquire().await
+            .map_err(|e| AppError::Unknown(e.to_string()))?;
+        // Cancelled while waiting for a slot
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(cancelled_error());
+        }
+
+        let url = policy.check_url(url).await?;
+        // Large files may take long; only a stalled transfer times out
+        let mut client_builder = policy.client_builder().read_timeout(policy.timeouts.idle());
+        if let Some(timeout) = options.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+        let client = client_builder.build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let part = part_path(dest);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match fetch_attempt(&client, url.clone(), &part, download_id, attempt, options, cancelled, report).await {
+                Ok(content_type) => {
+                    std::fs::rename(&part, dest)?;
+                    let bytes = std::fs::metadata(dest)?.len();
+                    return Ok(DownloadOutcome { path: dest.to_path_buf(), bytes, content_type });
+                }
+                Err(AppError::Network(msg)) if attempt <= options.max_retries && !cancelled.load(Ordering::SeqCst) => {
+                    println!("[Download] {} attempt {} failed: {}", download_id, attempt, msg);
+                    tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn fetch_attempt(
+    client: &reqwest::Client,
+    url: reqwest::Url,
+    part: &Path,
+    download_id: &str,
+    attempt: u32,
+    options: &DownloadOptions,
+    cancelled: &AtomicBool,
+    report: &Reporter<'_>,
+) -> Result<Option<String>, AppError> {
+    let resume_from = std::fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(RANGE, format!("bytes={}-", resume_from));
+    }
+
+    let mut response = request.send().await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 {
+        // Partial file is already complete (or stale); start over next attempt
+        std::fs::remove_file(part)?;
+        return Err(AppError::Network("Range not satisfiable".to_string()));
+    }
+    if !status.is_success() {
+        let err = format!("HTTP error: {}", status);
+        return if status.is_server_error() {
+            Err(AppError::Network(err))
+        } else {
+            Err(AppError::Unknown(err))
+        };
+    }
+
+    // Server may ignore Range and send the whole body
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    let mut received = if resumed { resume_from } else { 0 };
+
+    let total = if resumed {
+        response.headers().get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|v| v.parse::<u64>().ok())
+    } else {
+        response.headers().get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+    if let Some(total) = total {
+        if total > options.max_bytes {
+            return Err(AppError::Unknown(format!(
+                "Download exceeds size limit ({} > {} bytes)", total, options.max_bytes
+            )));
+        }
+        if let Some(dir) = part.parent() {
+            if let Some(warning) = disk_space::check_space(dir, total.saturating_sub(received))? {
+                report(AppEvent::LowDiskSpace(warning));
+            }
+        }
+    }
+
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)?;
+
+    let mut last_emit = received;
+    loop {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(cancelled_error());
+        }
+
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                file.flush()?;
+                return Err(AppError::Network(e.to_string()));
+            }
+        };
+
+        received += chunk.len() as u64;
+        if received > options.max_bytes {
+            drop(file);
+            let _ = std::fs::remove_file(part);
+            return Err(AppError::Unknown(format!(
+                "Download exceeds size limit ({} bytes)", options.max_bytes
+            )));
+        }
+        file.write_all(&chunk)?;
+
+        if received - last_emit >= PROGRESS_STEP {
+            last_emit = received;
+            report(AppEvent::DownloadProgress(DownloadProgress {
+                download_id: download_id.to_string(),
+                received,
+                total,
+                attempt,
+            }));
+        }
+    }
+    file.flush()?;
+
+    report(AppEvent::DownloadProgress(DownloadProgress {
+        download_id: download_id.to_string(),
+        received,
+        total: total.or(Some(received)),
+        attempt,
+    }));
+
+    Ok(content_type)
+}
+
+fn cancelled_error() -> AppError {
+    AppError::Unknown("Download cancelled".to_string())
+}
+
+/// Temp path used while a download is in flight
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use tempfile::tempdir;
+
+    /// Answer one connection per canned response, then close it. Returns the
+    /// URL and the request heads received, lowercased.
+    fn serve(responses: Vec<Vec<u8>>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/clip.bin", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for (response, stream) in responses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buf[..n]);
+                }
+                seen.lock().unwrap().push(String::from_utf8_lossy(&head).to_lowercase());
+                stream.write_all(&response).unwrap();
+                stream.flush().unwrap();
+                // Let the client read what was sent before the connection drops
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+        (url, requests)
+    }
+
+    fn fetch(manager: &DownloadManager, url: &str, dest: &Path, options: &DownloadOptions) -> Result<DownloadOutcome, AppError> {
+        let report = |_: AppEvent| {};
+        tauri::async_runtime::block_on(manager.fetch(&NetworkPolicy::default(), "test", url, dest, options, &report))
+    }
+
+    #[test]
+    fn test_part_path() {
+        let dest = Path::new("/tmp/project/assets/video.mp4");
+        assert_eq!(part_path(dest), PathBuf::from("/tmp/project/assets/video.mp4.part"));
+    }
+
+    #[test]
+    fn test_cancel_unknown_download() {
+        let manager = DownloadManager::new();
+        assert!(!manager.cancel("missing"));
+        assert!(manager.active_ids().is_empty());
+    }
+
+    #[test]
+    fn test_resume_with_range() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("clip.bin");
+        let (url, requests) = serve(vec![
+            // Dropped after the first four bytes
+            b"HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nhell".to_vec(),
+            b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 4-11/12\r\nContent-Length: 8\r\n\r\no world!".to_vec(),
+        ]);
+        
+        let outcome = fetch(&DownloadManager::new(), &url, &dest, &DownloadOptions::default()).unwrap();
+        assert_eq!(outcome.bytes, 12);
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello world!");
+        assert!(!part_path(&dest).exists());
+        
+        let requests = requests.lock().unwrap();
+        assert!(!requests[0].contains("range:"));
+        assert!(requests[1].contains("range: bytes=4-"));
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("clip.bin");
+        let options = DownloadOptions { max_bytes: 100, ..Default::default() };
+        let mut streamed = b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".to_vec();
+        streamed.extend_from_slice(&[b'x'; 300]);
+        let (url, _) = serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 300\r\n\r\n".to_vec(),
+            streamed,
+        ]);
+        
+        // Announced too large, then found too large while streaming
+        for _ in 0..2 {
+            let result = fetch(&DownloadManager::new(), &url, &dest, &options);
+            assert!(matches!(result, Err(AppError::Unknown(msg)) if msg.contains("size limit")));
+            assert!(!dest.exists());
+            assert!(!part_path(&dest).exists(), "partial file is removed");
+        }
+    }
+
+    #[test]
+    fn test_cancel_while_queued() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("clip.bin");
+        let (url, requests) = serve(vec![b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_vec()]);
+        
+        // Every slot is taken, so the download waits for one
+        let manager = Arc::new(DownloadManager::new());
+        let slots = tauri::async_runtime::block_on(manager.permits.clone().acquire_many_owned(MAX_CONCURRENT_DOWNLOADS as u32)).unwrap();
+        let task = tauri::async_runtime::spawn({
+            let manager = manager.clone();
+            let dest = dest.clone();
+            async move {
+                let report = |_: AppEvent| {};
+                manager.fetch(&NetworkPolicy::default(), "queued", &url, &dest, &DownloadOptions::default(), &report).await
+            }
+        });
+        while manager.active_ids().is_empty() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(manager.cancel("queued"));
+        drop(slots);
+        
+        let result = tauri::async_runtime::block_on(task).unwrap();
+        assert!(matches!(result, Err(AppError::Unknown(msg)) if msg.contains("cancelled")));
+        assert!(requests.lock().unwrap().is_empty(), "nothing is requested once cancelled");
+        assert!(!dest.exists());
+    }
+}
@@ -0,0 +1,323 @@
+//! Image export: social-media-ready crops written to the project's
+//! `exports/` folder.
+//!
+//! Crops are placed by saliency: the largest window with the target aspect
+//! ratio slides along the free axis to the position holding the most edge
+//! energy (a cheap stand-in for faces and subjects), ties going to the
+//! center.
+//!
+//! The project watermark, when configured, is stamped on every exported file.
+
+use std::path::Path;
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::imaging;
+use crate::services::cancellation::CancellationToken;
+use crate::services::watermark::Watermark;
+use ts_rs::TS;
+
+/// Folder (relative to the project root) receiving exported files
+pub const EXPORT_DIR: &str = "exports";
+
+/// Short side of the output for ratio presets, matching common platform sizes
+const DEFAULT_SHORT_SIDE: u32 = 1080;
+
+/// Long side of the copy the saliency map is computed on
+const SALIENCY_SAMPLE_SIZE: u32 = 128;
+
+/// A crop target: a ratio such as `"1:1"`, `"9:16"` or `"16:9"` (output short
+/// side 1080px), or an exact custom size.
+#[derive(Debug, Clone, PartialEq, Deserialize, TS)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum CropPreset {
+    Ratio(String),
+    Custom {
+        width: u32,
+        height: u32,
+        #[serde(default)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFile {
+    /// Preset label ("1x1", "9x16", custom name or "WxH")
+    pub preset: String,
+    /// Path relative to the project root
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// The source region was smaller than the output and had to be enlarged
+    pub upscaled: bool,
+}
+
+impl CropPreset {
+    /// Label used in file names
+    pub fn label(&self) -> String {
+        match self {
+            CropPreset::Ratio(ratio) => ratio.replace(':', "x"),
+            CropPreset::Custom { name: Some(name), .. } if !name.trim().is_empty() => sanitize(name),
+            CropPreset::Custom { width, height, .. } => format!("{}x{}", width, height),
+        }
+    }
+
+    /// Output size in pixels
+    pub fn output_size(&self) -> Result<(u32, u32), AppError> {
+        match self {
+            CropPreset::Ratio(ratio) => {
+                let (w, h) = parse_ratio(ratio)
+                    .ok_or_else(|| AppError::Unknown(format!("Invalid crop ratio: {}", ratio)))?;
+                let scale = DEFAULT_SHORT_SIDE as f64 / w.min(h) as f64;
+                Ok(((w as f64 * scale).round() as u32, (h as f64 * scale).round() as u32))
+            }
+            CropPreset::Custom { width, height, .. } => {
+                if *width == 0 || *height == 0 {
+                    return Err(AppError::Unknown("Crop size must be positive".to_string()));
+                }
+                Ok((*width, *height))
+            }
+        }
+    }
+}
+
+/// Export one crop per preset of an image asset, watermarked if given.
+/// Returns the written files; stops between files when cancelled.
+pub fn export_crops(
+    project_root: &Path,
+    asset: &Asset,
+    presets: &[CropPreset],
+    watermark: Option<&Watermark>,
+    cancel: &CancellationToken,
+) -> Result<Vec<ExportedFile>, AppError> {
+    let src = asset.value.get("src").and_then(|s| s.as_str())
+        .ok_or_else(|| AppError::Unknown(format!("Asset is not an image: {}", asset.id)))?;
+    let path = imaging::resolve_image_source(project_root, src)
+        .ok_or_else(|| AppError::Unknown(format!("Image is not stored in the project: {}", src)))?;
+    let img = image::open(&path)
+        .map_err(|e| AppError::Unknown(format!("Failed to load image: {}", e)))?;
+    
+    let ext = match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "jpg",
+        _ => "png",
+    };
+    let export_dir = project_root.join(EXPORT_DIR);
+    std::fs::create_dir_all(&export_dir)?;
+    let base = sanitize(&asset.sys.name);
+    
+    let mut files = Vec::with_capacity(presets.len());
+    for preset in presets {
+        cancel.check()?;
+        let (width, height) = preset.output_size()?;
+        let (x, y, crop_w, crop_h) = smart_crop(&img, width, height);
+        let output = img.crop_imm(x, y, crop_w, crop_h)
+            .resize_exact(width, height, FilterType::Lanczos3);
+        let output = match watermark {
+            Some(watermark) => watermark.apply(output),
+            None => output,
+        };
+        
+        let filename = unique_filename(&export_dir, &format!("{}_{}", base, preset.label()), ext);
+        let output = if ext == "jpg" { DynamicImage::ImageRgb8(output.to_rgb8()) } else { output };
+        output.save(export_dir.join(&filename))
+            .map_err(|e| AppError::Unknown(format!("Failed to save export: {}", e)))?;
+        
+        files.push(ExportedFile {
+            preset: preset.label(),
+            path: format!("{}/{}", EXPORT_DIR, filename),
+            width,
+            height,
+            upscaled: crop_w < width || crop_h < height,
+        });
+    }
+    
+    Ok(files)
+}
+
+/// Upper bound on the bytes `export_crops` writes for `presets`: the
+/// uncompressed RGBA size of each output
+pub fn estimated_size(presets: &[CropPreset]) -> Result<u64, AppError> {
+    presets.iter()
+        .map(|preset| preset.output_size().map(|(w, h)| w as u64 * h as u64 * 4))
+        .sum()
+}
+
+/// Largest region of `img` with the aspect ratio of `width`×`height`,
+/// positioned on the most salient part. Returns `(x, y, w, h)`.
+pub fn smart_crop(img: &DynamicImage, width: u32, height: u32) -> (u32, u32, u32, u32) {
+    let (src_w, src_h) = img.dimensions();
+    let target = width as f64 / height as f64;
+    
+    if (src_w as f64 / src_h as f64) > target {
+        // Wider than the target: slide horizontally
+        let crop_w = ((src_h as f64 * target).round() as u32).clamp(1, src_w);
+        let x = best_offset(&axis_energy(img, true), src_w, crop_w);
+        (x, 0, crop_w, src_h)
+    } else {
+        let crop_h = ((src_w as f64 / target).round() as u32).clamp(1, src_h);
+        let y = best_offset(&axis_energy(img, false), src_h, crop_h);
+        (0, y, src_w, crop_h)
+    }
+}
+
+/// File-name-safe version of a name
+pub(crate) fn sanitize(name: &str) -> String {
+    let safe: String = name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if safe.is_empty() { "export".to_string() } else { safe }
+}
+
+/// `stem.ext`, or `stem_2.ext`, `stem_3.ext`... if taken
+pub(crate) fn unique_filename(dir: &Path, stem: &str, ext: &str) -> String {
+    let mut filename = format!("{}.{}", stem, ext);
+    let mut n = 2;
+    while dir.join(&filename).exists() {
+        filename = format!("{}_{}.{}", stem, n, ext);
+        n += 1;
+    }
+    filename
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn parse_ratio(ratio: &str) -> Option<(u32, u32)> {
+    let (w, h) = ratio.split_once(':')?;
+    let (w, h) = (w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?);
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+/// Edge energy (gradient magnitude) summed per column (`horizontal`) or per
+/// row, on a downscaled grayscale copy.
+fn axis_energy(img: &DynamicImage, horizontal: bool) -> Vec<f64> {
+    let gray = img.thumbnail(SALIENCY_SAMPLE_SIZE, SALIENCY_SAMPLE_SIZE).to_luma8();
+    let (w, h) = gray.dimensions();
+    let len = if horizontal { w } else { h };
+    let mut energy = vec![0.0; len as usize];
+    
+    for y in 0..h {
+        for x in 0..w {
+            let at = |x: u32, y: u32| gray.get_pixel(x, y).0[0] as f64;
+            let dx = if x + 1 < w { (at(x + 1, y) - at(x, y)).abs() } else { 0.0 };
+            let dy = if y + 1 < h { (at(x, y + 1) - at(x, y)).abs() } else { 0.0 };
+            energy[if horizontal { x } else { y } as usize] += dx + dy;
+        }
+    }
+    energy
+}
+
+/// Start of the `window`-sized span (in source pixels) covering the most
+/// energy; `energy` is sampled over `total` source pixels.
+fn best_offset(energy: &[f64], total: u32, window: u32) -> u32 {
+    let max_offset = total - window;
+    if max_offset == 0 || energy.is_empty() {
+        return 0;
+    }
+    
+    let scale = energy.len() as f64 / total as f64;
+    let span = ((window as f64 * scale).round() as usize).clamp(1, energy.len());
+    let center = (energy.len() - span) as f64 / 2.0;
+    
+    let mut best = (f64::MIN, f64::MAX, 0usize);
+    let mut sum: f64 = energy[..span].iter().sum();
+    for start in 0..=energy.len() - span {
+        if start > 0 {
+            sum += energy[start + span - 1] - energy[start - 1];
+        }
+        let distance = (start as f64 - center).abs();
+        if sum > best.0 + 1e-6 || ((sum - best.0).abs() <= 1e-6 && distance < best.1) {
+            best = (sum, distance, start);
+        }
+    }
+    
+    ((best.2 as f64 / scale).round() as u32).min(max_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use image::{Rgba, RgbaImage};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    /// Flat image with a checkerboard "subject" in the right third
+    fn subject_right(w: u32, h: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(w, h, |x, y| {
+            if x > w * 2 / 3 && (x / 4 + y / 4) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([200, 200, 200, 255])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_preset_sizes() {
+        let presets: Vec<CropPreset> = serde_json::from_value(json!([
+            "1:1", "9:16", "16:9", { "width": 1200, "height": 628, "name": "OG image" }
+        ])).unwrap();
+        let sizes: Vec<(u32, u32)> = presets.iter().map(|p| p.output_size().unwrap()).collect();
+        assert_eq!(sizes, vec![(1080, 1080), (1080, 1920), (1920, 1080), (1200, 628)]);
+        assert_eq!(presets[1].label(), "9x16");
+        assert_eq!(presets[3].label(), "OG_image");
+        assert!(CropPreset::Ratio("wide".to_string()).output_size().is_err());
+    }
+
+    #[test]
+    fn test_smart_crop_follows_subject() {
+        let img = subject_right(300, 100);
+        let (x, y, w, h) = smart_crop(&img, 1080, 1080);
+        assert_eq!((y, w, h), (0, 100, 100));
+        assert!(x >= 190, "crop should move onto the subject, got x={}", x);
+        
+        // No detail at all: centered
+        let flat = DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 300, Rgba([9, 9, 9, 255])));
+        assert_eq!(smart_crop(&flat, 16, 9), (0, 122, 100, 56));
+    }
+
+    #[test]
+    fn test_export_crops() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        subject_right(400, 200).save(dir.path().join("assets/photo.png")).unwrap();
+        
+        let asset = Asset {
+            id: "a".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/photo.png", "width": 400, "height": 200 }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: "Launch photo".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        };
+        let presets = vec![
+            CropPreset::Ratio("1:1".to_string()),
+            CropPreset::Custom { width: 100, height: 50, name: None },
+        ];
+        
+        assert_eq!(estimated_size(&presets).unwrap(), (1080 * 1080 + 100 * 50) * 4);
+        let files = export_crops(dir.path(), &asset, &presets, None, &CancellationToken::new()).unwrap();
+        assert_eq!(files[0].path, "exports/Launch_photo_1x1.png");
+        assert!(files[0].upscaled);
+        assert!(!files[1].upscaled);
+        
+        let written = image::open(dir.path().join(&files[1].path)).unwrap();
+        assert_eq!(written.dimensions(), (100, 50));
+        
+        // Exporting again does not overwrite
+        let again = export_crops(dir.path(), &asset, &presets[..1], None, &CancellationToken::new()).unwrap();
+        assert_eq!(again[0].path, "exports/Launch_photo_1x1_2.png");
+    }
+}
@@ -0,0 +1,401 @@
+//! Face detection and grouping of reference photos, fully local.
+//!
+//! A local model runner (`SYNNIA_FACE_RUNNER`, model in `SYNNIA_FACE_MODEL`
+//! or else the installed face-detection model) prints the faces of an image as JSON:
+//! `[{ "box": [x, y, width, height], "confidence": 0.98, "embedding": [...] }]`
+//! with the box normalized to 0..1. Faces are stored per image file
+//! (`image_faces.src`), so they can be found at import time before the
+//! frontend creates the asset, and grouped by person by clustering the
+//! embeddings.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{database, imaging, io_sqlite, model_manager};
+use crate::services::batch_import::IMAGE_EXTENSIONS;
+use crate::services::model_manager::ModelKind;
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "detect-faces";
+
+/// Settings key of the face settings
+pub const SETTINGS_KEY: &str = "faces";
+
+/// Per-project face settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FaceSettings {
+    /// Detect faces in images as they are imported
+    pub detect_on_import: bool,
+    /// Faces whose embeddings' cosine similarity reaches this are the same person
+    pub match_threshold: f32,
+    /// Detections below this confidence are ignored
+    pub min_confidence: f32,
+}
+
+impl Default for FaceSettings {
+    fn default() -> Self {
+        Self { detect_on_import: false, match_threshold: 0.5, min_confidence: 0.6 }
+    }
+}
+
+impl FaceSettings {
+    /// Clamp to usable values
+    pub fn normalized(self) -> Self {
+        let unit = |v: f32, default: f32| if v.is_finite() { v.clamp(0.0, 1.0) } else { default };
+        Self {
+            detect_on_import: self.detect_on_import,
+            match_threshold: unit(self.match_threshold, 0.5),
+            min_confidence: unit(self.min_confidence, 0.6),
+        }
+    }
+}
+
+/// A face found by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedFace {
+    /// x, y, width, height relative to the image size
+    #[serde(rename = "box")]
+    pub bbox: [f32; 4],
+    pub confidence: f32,
+    #[serde(default, skip_serializing)]
+    pub embedding: Vec<f32>,
+}
+
+/// A stored face of an image file
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Face {
+    pub id: String,
+    pub src: String,
+    #[serde(rename = "box")]
+    pub bbox: [f32; 4],
+    pub confidence: f32,
+}
+
+/// Faces of one person and the assets showing them
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceGroup {
+    pub faces: Vec<Face>,
+    pub asset_ids: Vec<String>,
+}
+
+/// Parameters of one detection task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceTask {
+    /// Image file relative to the project root
+    pub src: String,
+}
+
+/// Runner and model of the local face detector
+#[derive(Debug, Clone)]
+pub struct FaceDetector {
+    runner: PathBuf,
+    model: PathBuf,
+}
+
+impl FaceDetector {
+    /// The local face detector, if a runner and model are configured.
+    pub fn find() -> Option<Self> {
+        let runner = PathBuf::from(std::env::var_os("SYNNIA_FACE_RUNNER")?);
+        let model = std::env::var_os("SYNNIA_FACE_MODEL")
+            .map(PathBuf::from)
+            .or_else(|| model_manager::installed(ModelKind::FaceDetection))?;
+        (runner.is_file() && model.is_file()).then_some(FaceDetector { runner, model })
+    }
+
+    /// Faces in the image at `path`. The error is the message shown for it.
+    pub fn detect(&self, path: &Path) -> Result<Vec<DetectedFace>, String> {
+        let output = Command::new(&self.runner)
+            .arg("-i").arg(path)
+            .arg("-m").arg(&self.model)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run face detector: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Face detector failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Unreadable face detector output: {}", e))
+    }
+
+    /// Detect and store the faces of `src` (relative to the project root).
+    /// Returns the number of faces kept.
+    pub fn detect_and_store(&self, project_root: &Path, src: &str) -> Result<usize, String> {
+        let faces = self.detect(&project_root.join(src))?;
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        let settings = get_settings(&conn).map_err(|e| e.to_string())?;
+        let faces: Vec<DetectedFace> = faces.into_iter()
+            .filter(|f| f.confidence >= settings.min_confidence)
+            .collect();
+        save_faces(&conn, src, &faces).map_err(|e| e.to_string())?;
+        Ok(faces.len())
+    }
+}
+
+pub fn get_settings(conn: &Connection) -> Result<FaceSettings, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Store face settings (clamped to usable values). Returns what was stored.
+pub fn save_settings(conn: &Connection, settings: &FaceSettings) -> Result<FaceSettings, AppError> {
+    let settings = settings.normalized();
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(settings)?)?;
+    Ok(settings)
+}
+
+/// The detector to run on import, if the project opted in and one is installed.
+pub fn import_detector(project_root: &Path) -> Option<FaceDetector> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root)).ok()?;
+    get_settings(&conn).ok()?.detect_on_import.then(FaceDetector::find).flatten()
+}
+
+/// Replace the stored faces of an image file. An empty list records that
+/// the file was scanned, so it isn't scanned again.
+pub fn save_faces(conn: &Connection, src: &str, faces: &[DetectedFace]) -> Result<(), AppError> {
+    conn.execute("DELETE FROM image_faces WHERE src = ?1", params![src])
+        .map_err(|e| AppError::Io(format!("Failed to clear faces: {}", e)))?;
+    if faces.is_empty() {
+        return mark_scanned(conn, src);
+    }
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    for face in faces {
+        let embedding: Vec<u8> = face.embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT INTO image_faces (id, src, x, y, width, height, confidence, embedding, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                src,
+                face.bbox[0],
+                face.bbox[1],
+                face.bbox[2],
+                face.bbox[3],
+                face.confidence,
+                embedding,
+                now,
+            ],
+        ).map_err(|e| AppError::Io(format!("Failed to save face: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Image files of the project's assets that were not scanned yet (all of
+/// them with `force`). `asset_ids` limits the search.
+pub fn pending_tasks(
+    conn: &Connection,
+    project_root: &Path,
+    asset_ids: Option<&[String]>,
+    force: bool,
+) -> Result<Vec<FaceTask>, AppError> {
+    let scanned = if force { Vec::new() } else { scanned_sources(conn)? };
+    let mut sources: Vec<String> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .filter(|asset| asset_ids.map_or(true, |ids| ids.contains(&asset.id)))
+        .filter_map(|asset| {
+            let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+            let path = imaging::resolve_image_source(project_root, src).filter(|p| p.is_file())?;
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            IMAGE_EXTENSIONS.contains(&ext.as_str()).then_some(())?;
+            Some(path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/"))
+        })
+        .filter(|src| !scanned.contains(src))
+        .collect();
+    sources.sort();
+    sources.dedup();
+    Ok(sources.into_iter().map(|src| FaceTask { src }).collect())
+}
+
+/// Cluster the stored faces by person (faces linked by a chain of matches
+/// above `threshold` form one group), largest group first.
+pub fn group_by_face(conn: &Connection, threshold: f32) -> Result<Vec<FaceGroup>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, src, x, y, width, height, confidence, embedding FROM image_faces
+         WHERE embedding IS NOT NULL ORDER BY src, x"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| {
+        let embedding: Vec<u8> = row.get(7)?;
+        Ok((
+            Face {
+                id: row.get(0)?,
+                src: row.get(1)?,
+                bbox: [row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?],
+                confidence: row.get(6)?,
+            },
+            unit_vector(&embedding),
+        ))
+    }).map_err(|e| AppError::Io(format!("Failed to query faces: {}", e)))?;
+    let faces: Vec<(Face, Vec<f32>)> = rows.collect::<Result<_, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load faces: {}", e)))?;
+    
+    // Single-linkage clustering with union-find
+    let mut parent: Vec<usize> = (0..faces.len()).collect();
+    for i in 0..faces.len() {
+        for j in (i + 1)..faces.len() {
+            let (a, b) = (&faces[i].1, &faces[j].1);
+            if a.len() == b.len() && !a.is_empty() && dot(a, b) >= threshold {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[rj] = ri;
+            }
+        }
+    }
+    
+    let assets_by_src = assets_by_src(conn)?;
+    let mut clusters: HashMap<usize, Vec<Face>> = HashMap::new();
+    for (i, (face, _)) in faces.into_iter().enumerate() {
+        clusters.entry(root(&mut parent, i)).or_default().push(face);
+    }
+    
+    let mut groups: Vec<FaceGroup> = clusters.into_values()
+        .map(|faces| {
+            let mut asset_ids: Vec<String> = faces.iter()
+                .flat_map(|f| assets_by_src.get(&f.src).cloned().unwrap_or_default())
+                .collect();
+            asset_ids.sort();
+            asset_ids.dedup();
+            FaceGroup { faces, asset_ids }
+        })
+        .filter(|g| !g.asset_ids.is_empty())
+        .collect();
+    groups.sort_by(|a, b| b.faces.len().cmp(&a.faces.len()).then_with(|| a.faces[0].src.cmp(&b.faces[0].src)));
+    Ok(groups)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn mark_scanned(conn: &Connection, src: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO image_faces (id, src, x, y, width, height, confidence, embedding, created_at)
+         SELECT ?1, ?2, 0, 0, 0, 0, 0, NULL, ?3
+         WHERE NOT EXISTS (SELECT 1 FROM image_faces WHERE src = ?2)",
+        params![uuid::Uuid::new_v4().to_string(), src, chrono::Utc::now().timestamp_millis()],
+    ).map_err(|e| AppError::Io(format!("Failed to save face scan: {}", e)))?;
+    Ok(())
+}
+
+fn scanned_sources(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare("SELECT DISTINCT src FROM image_faces")
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to query faces: {}", e)))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load faces: {}", e)))
+}
+
+/// Asset IDs per image file they show
+fn assets_by_src(conn: &Connection) -> Result<HashMap<String, Vec<String>>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, COALESCE(json_extract(value_json, '$.src'), json_extract(value_json, '$')) FROM assets"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1).unwrap_or(None))))
+        .map_err(|e| AppError::Io(format!("Failed to query assets: {}", e)))?;
+    
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (id, src) = row.map_err(|e| AppError::Io(format!("Failed to load asset: {}", e)))?;
+        let Some(src) = src else { continue };
+        let src = src.split(['?', '#']).next().unwrap_or(&src).trim_start_matches('/').to_string();
+        map.entry(src).or_default().push(id);
+    }
+    Ok(map)
+}
+
+fn unit_vector(bytes: &[u8]) -> Vec<f32> {
+    let v: Vec<f32> = bytes.chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    let norm = dot(&v, &v).sqrt();
+    if norm > 0.0 { v.into_iter().map(|x| x / norm).collect() } else { Vec::new() }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn face(embedding: &[f32]) -> DetectedFace {
+        DetectedFace { bbox: [0.1, 0.1, 0.3, 0.4], confidence: 0.9, embedding: embedding.to_vec() }
+    }
+
+    #[test]
+    fn test_group_by_face() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Cast").unwrap();
+        for id in ["alice1", "alice2", "bob", "empty"] {
+            image::RgbImage::new(4, 4).save(root.join(format!("assets/{}.png", id))).unwrap();
+            project.assets.insert(id.to_string(), Asset {
+                id: id.to_string(),
+                value_type: ValueType::Record,
+                value: json!({ "src": format!("assets/{}.png", id) }),
+                value_meta: None,
+                config: None,
+                sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "import".to_string() },
+            });
+        }
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        
+        save_faces(&conn, "assets/alice1.png", &[face(&[1.0, 0.1, 0.0])]).unwrap();
+        save_faces(&conn, "assets/alice2.png", &[face(&[0.9, 0.2, 0.0]), face(&[0.0, 0.0, 1.0])]).unwrap();
+        save_faces(&conn, "assets/bob.png", &[face(&[0.0, 0.1, 1.0])]).unwrap();
+        save_faces(&conn, "assets/empty.png", &[]).unwrap();
+        
+        let pending = pending_tasks(&conn, root, None, false).unwrap();
+        assert!(pending.is_empty(), "every image was scanned");
+        assert_eq!(pending_tasks(&conn, root, None, true).unwrap().len(), 4);
+        
+        let groups = group_by_face(&conn, 0.9).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].asset_ids, vec!["alice1", "alice2"]);
+        assert_eq!(groups[1].asset_ids, vec!["alice2", "bob"]);
+        
+        // A loose threshold merges everyone into one group
+        assert_eq!(group_by_face(&conn, -1.0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_settings_survive_project_save() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Faces").unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let saved = save_settings(&conn, &FaceSettings { detect_on_import: true, match_threshold: 0.7, min_confidence: 0.8 }).unwrap();
+
+        // The frontend's autosave sends no settings
+        project.settings = Some(HashMap::new());
+        io_sqlite::save_project_sqlite(dir.path(), &project).unwrap();
+
+        assert_eq!(get_settings(&conn).unwrap(), saved);
+        assert_ne!(saved, Default::default());
+    }
+}
@@ -0,0 +1,257 @@
+//! Earlier files of image assets.
+//!
+//! Asset history only covers `value_json`; when an image asset's `src` is
+//! swapped for a new file (upscale, replace), the previous file is copied to
+//! `assets/versions/<content hash>.<ext>` and listed in `file_history`, so it
+//! survives compaction and can be restored.
+
+use std::fs;
+use std::path::Path;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::{database, hash, imaging, io_sqlite};
+use ts_rs::TS;
+
+/// Where earlier files are kept, relative to the project root
+pub const VERSIONS_DIR: &str = "assets/versions";
+
+/// Maximum number of file versions to keep per asset
+const MAX_VERSIONS_PER_ASSET: i64 = 20;
+
+/// An earlier file of an asset
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersion {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub asset_id: String,
+    pub content_hash: String,
+    /// Where the file was used from before it was replaced
+    pub src: String,
+    /// The kept copy, relative to the project root
+    pub path: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// Keep the file behind `old_value` if `new_value` points somewhere else.
+/// Anything that isn't an image file in the project is ignored.
+pub fn track_replacement(
+    conn: &Connection,
+    project_root: &Path,
+    asset_id: &str,
+    old_value: &Value,
+    new_value: &Value,
+) -> Result<Option<FileVersion>, AppError> {
+    let Some(old_src) = image_src(old_value) else {
+        return Ok(None);
+    };
+    if image_src(new_value) == Some(old_src) {
+        return Ok(None);
+    }
+    keep_file(conn, project_root, asset_id, old_src)
+}
+
+/// File versions of an asset, newest first.
+pub fn list_versions(conn: &Connection, asset_id: &str) -> Result<Vec<FileVersion>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, asset_id, content_hash, src, path, size, created_at
+         FROM file_history
+         WHERE asset_id = ?1
+         ORDER BY created_at DESC, id DESC"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let versions = stmt.query_map(params![asset_id], read_version)
+        .map_err(|e| AppError::Io(format!("Failed to query file history: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to read file history: {}", e)))?;
+    Ok(versions)
+}
+
+/// Put a kept file back as the asset's image. The copy gets a fresh name
+/// under `assets/`, and the file it replaces becomes a version in turn.
+pub fn restore_version(project_root: &Path, asset_id: &str, version_id: i64) -> Result<Asset, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let version = conn.query_row(
+        "SELECT id, asset_id, content_hash, src, path, size, created_at FROM file_history WHERE id = ?1",
+        params![version_id],
+        read_version,
+    ).optional()
+        .map_err(|e| AppError::Io(format!("Failed to query file history: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("File version not found: {}", version_id)))?;
+    if version.asset_id != asset_id {
+        return Err(AppError::Unknown("File version does not belong to this asset".to_string()));
+    }
+    let mut asset = io_sqlite::get_asset(&conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    
+    let data = fs::read(project_root.join(&version.path))
+        .map_err(|e| AppError::Io(format!("Failed to read file version: {}", e)))?;
+    let ext = Path::new(&version.path).extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "png".to_string());
+    let file_id = uuid::Uuid::new_v4().to_string();
+    let relative_path = format!("assets/{}.{}", file_id, ext);
+    fs::write(project_root.join(&relative_path), &data)?;
+    let (width, height) = imaging::get_image_dimensions(&data)?;
+    let preview = imaging::generate_thumbnail(project_root, &file_id, &data).ok();
+    
+    match asset.value.as_object_mut() {
+        Some(value) => {
+            value.insert("src".to_string(), json!(relative_path));
+            value.insert("width".to_string(), json!(width));
+            value.insert("height".to_string(), json!(height));
+        }
+        None => asset.value = json!({ "src": relative_path, "width": width, "height": height }),
+    }
+    asset.value_meta = Some(json!({ "width": width, "height": height, "preview": preview }));
+    asset.sys.updated_at = chrono::Utc::now().timestamp_millis();
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    Ok(asset)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// `src` of an image value (`{ src }` or a bare path)
+fn image_src(value: &Value) -> Option<&str> {
+    value.get("src").or(Some(value)).and_then(|v| v.as_str())
+}
+
+fn keep_file(conn: &Connection, project_root: &Path, asset_id: &str, src: &str) -> Result<Option<FileVersion>, AppError> {
+    let Some(path) = imaging::resolve_image_source(project_root, src).filter(|p| p.is_file()) else {
+        return Ok(None);
+    };
+    let data = fs::read(&path)?;
+    let Some(ext) = imaging::detect_image_format(&data) else {
+        return Ok(None);
+    };
+    
+    let content_hash = hash::compute_binary_hash(&data);
+    let stored = format!("{}/{}.{}", VERSIONS_DIR, content_hash, ext);
+    let target = project_root.join(&stored);
+    if !target.is_file() {
+        fs::create_dir_all(project_root.join(VERSIONS_DIR))?;
+        fs::write(&target, &data)?;
+    }
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "INSERT INTO file_history (asset_id, content_hash, src, path, size, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(asset_id, content_hash) DO UPDATE SET src = excluded.src, created_at = excluded.created_at",
+        params![asset_id, content_hash, src, stored, data.len() as i64, now],
+    ).map_err(|e| AppError::Io(format!("Failed to record file version: {}", e)))?;
+    prune(conn, project_root, asset_id)?;
+    
+    conn.query_row(
+        "SELECT id, asset_id, content_hash, src, path, size, created_at
+         FROM file_history WHERE asset_id = ?1 AND content_hash = ?2",
+        params![asset_id, content_hash],
+        read_version,
+    ).optional()
+        .map_err(|e| AppError::Io(format!("Failed to query file history: {}", e)))
+}
+
+/// Drop versions past the per-asset limit, deleting files no other version uses
+fn prune(conn: &Connection, project_root: &Path, asset_id: &str) -> Result<(), AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, path FROM file_history
+         WHERE asset_id = ?1
+         ORDER BY created_at DESC, id DESC
+         LIMIT -1 OFFSET ?2"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let stale = stmt.query_map(params![asset_id, MAX_VERSIONS_PER_ASSET], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| AppError::Io(format!("Failed to query file history: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to read file history: {}", e)))?;
+    
+    for (id, path) in stale {
+        conn.execute("DELETE FROM file_history WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Io(format!("Failed to prune file history: {}", e)))?;
+        let still_used: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM file_history WHERE path = ?1)",
+            params![path],
+            |row| row.get(0),
+        ).map_err(|e| AppError::Io(format!("Failed to query file history: {}", e)))?;
+        if !still_used {
+            let _ = fs::remove_file(project_root.join(&path));
+        }
+    }
+    Ok(())
+}
+
+fn read_version(row: &rusqlite::Row) -> rusqlite::Result<FileVersion> {
+    Ok(FileVersion {
+        id: row.get(0)?,
+        asset_id: row.get(1)?,
+        content_hash: row.get(2)?,
+        src: row.get(3)?,
+        path: row.get(4)?,
+        size: row.get::<_, i64>(5)? as u64,
+        created_at: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use tempfile::tempdir;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::RgbImage::new(width, height)
+            .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn image(src: &str) -> Asset {
+        Asset {
+            id: "photo".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": src }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Photo".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        }
+    }
+
+    #[test]
+    fn test_replace_and_restore() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        io_sqlite::init_project_sqlite(root, "Photos").unwrap();
+        fs::write(root.join("assets/small.png"), png(4, 4)).unwrap();
+        fs::write(root.join("assets/large.png"), png(8, 8)).unwrap();
+        
+        io_sqlite::save_asset_with_history(root, &image("assets/small.png")).unwrap();
+        io_sqlite::save_asset_with_history(root, &image("assets/large.png")).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let versions = list_versions(&conn, "photo").unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].src, "assets/small.png");
+        assert!(versions[0].path.starts_with("assets/versions/"));
+        
+        // The kept copy outlives the original
+        fs::remove_file(root.join("assets/small.png")).unwrap();
+        let restored = restore_version(root, "photo", versions[0].id).unwrap();
+        assert_eq!(restored.value["width"], json!(4));
+        let src = restored.value["src"].as_str().unwrap();
+        assert!(root.join(src).is_file());
+        
+        // The upscaled file became a version in turn
+        let versions = list_versions(&conn, "photo").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].src, "assets/large.png");
+    }
+}
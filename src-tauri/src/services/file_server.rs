@@ -1,82 +1,517 @@
-use actix_web::{get, web, App, HttpServer, HttpRequest, Error, middleware};
+use actix_web::{get, post, web, App, HttpServer, HttpRequest, HttpResponse, Error, middleware};
+use actix_web::http::header::{self, HeaderValue};
 use actix_files::NamedFile;
 use actix_cors::Cors;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::net::TcpListener;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::config::GlobalConfig;
+use crate::launch;
+use crate::services::{clip, content_type, hash, imaging, linked_files, rest_api, validation};
+use crate::services::connectors::oauth::{self, ClientCredentials, PendingLogins};
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::permissions::{Actor, Capability, PermissionGate};
+use crate::services::imaging::ImageTier;
 
 // Shared state for Actix
 pub struct ServerState {
     pub current_project_path: Arc<Mutex<Option<String>>>,
+    pub clip_token: Arc<Mutex<String>>,
+    pub permissions: Arc<PermissionGate>,
+    // Shared with Tauri commands: connector sign-ins awaiting their redirect
+    pub logins: Arc<PendingLogins>,
+    pub app: AppHandle,
+    // Bound to an address other machines reach (`--server --host`)
+    pub public: bool,
+    // Content hashes keyed by path, invalidated when mtime or size changes
+    pub etag_cache: Mutex<HashMap<PathBuf, CachedEtag>>,
+    // Links confirmed in `linked_files`, with the original they pointed at
+    pub linked_cache: Mutex<HashMap<PathBuf, PathBuf>>,
+}
+
+/// Cached validator for a served file
+#[derive(Debug, Clone)]
+pub struct CachedEtag {
+    pub modified: SystemTime,
+    pub len: u64,
+    pub etag: String,
+    pub content_type: String,
+}
+
+/// Query of asset requests: `?tier=preview|medium|full`, plus the clip
+/// token for clients on other machines (see `--server`)
+#[derive(Debug, serde::Deserialize)]
+struct AssetQuery {
+    #[serde(default)]
+    tier: ImageTier,
+    token: Option<String>,
 }
 
 #[get("/assets/{filename:.*}")]
 async fn serve_asset(
-    _req: HttpRequest,
+    req: HttpRequest,
     filename: web::Path<String>,
+    query: web::Query<AssetQuery>,
     data: web::Data<ServerState>,
-) -> Result<NamedFile, Error> {
-    let project_path_opt = {
-        let guard = data.current_project_path.lock().unwrap();
-        guard.clone()
-    };
-
-    if let Some(project_path_str) = project_path_opt {
-        let project_path = PathBuf::from(project_path_str);
-        
-        // Resolve Project Root (Handle .json file case)
-        let project_root = if project_path.extension().is_some() {
-            project_path.parent().unwrap_or(&project_path).to_path_buf()
-        } else {
-            project_path
-        };
+) -> Result<HttpResponse, Error> {
+    // The webview on this machine loads assets without a token. A public
+    // server always wants one: a reverse proxy in front of it connects from
+    // loopback too.
+    let is_local = !data.public && req.peer_addr().is_some_and(|addr| addr.ip().is_loopback());
+    if !is_local && !is_authorized(&data, bearer_token(&req).or(query.token.as_deref()).unwrap_or("")) {
+        return Err(actix_web::error::ErrorUnauthorized("Invalid clip token"));
+    }
 
+    if let Some(project_root) = resolve_project_root(&data) {
         let assets_dir = project_root.join("assets");
         
         // Decode URL components (e.g. %20 -> space) is handled by actix path? 
         // filename is decoded.
         
-        let file_path = assets_dir.join(filename.into_inner());
+        // Never serve anything outside the assets folder, except the
+        // originals of linked imports
+        let filename = filename.into_inner();
+        let resolve = |name: &str| match validation::resolve_in_project(&assets_dir, name) {
+            Err(AppError::Forbidden(_)) if is_linked_asset(&data, &project_root, name) => {
+                Ok(assets_dir.join(name))
+            }
+            resolved => resolved,
+        };
+        if let Err(AppError::Forbidden(_)) = resolve(&filename) {
+            return Err(actix_web::error::ErrorForbidden("Path is outside the assets folder"));
+        }
+
+        // Serve the smallest available derivative at or above the requested tier
+        let file_path = imaging::tier_candidates(&filename, query.tier)
+            .into_iter()
+            .filter_map(|candidate| resolve(&candidate).ok())
+            .find(|path| path.is_file())
+            .ok_or_else(|| actix_web::error::ErrorNotFound("File not found"))?;
 
         // println!("[FileServer] Request: {:?}", file_path);
 
-        match NamedFile::open(file_path) {
-            Ok(file) => Ok(file),
-            Err(_) => Err(actix_web::error::ErrorNotFound("File not found")),
+        let file = match NamedFile::open(&file_path) {
+            Ok(file) => file,
+            Err(_) => return Err(actix_web::error::ErrorNotFound("File not found")),
+        };
+
+        let validator = lookup_validator(&data, &file_path, &file).await?;
+
+        // Conditional request: the webview already has this exact content
+        if if_none_match(&req, &validator.etag) {
+            return Ok(HttpResponse::NotModified()
+                .insert_header((header::ETAG, validator.etag))
+                .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+                .finish());
+        }
+
+        // Last-Modified / If-Modified-Since are handled by NamedFile itself
+        let mut response = file.use_etag(false).into_response(&req);
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&validator.content_type) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&validator.etag) {
+            headers.insert(header::ETAG, value);
         }
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+
+        Ok(response)
     } else {
         Err(actix_web::error::ErrorNotFound("No project loaded"))
     }
 }
 
-pub fn init(current_project_path: Arc<Mutex<Option<String>>>) -> u16 {
+/// Whether `assets/<name>` is a linked import. A confirmed link is only
+/// looked up in the project database again once it points somewhere else.
+fn is_linked_asset(data: &ServerState, project_root: &Path, name: &str) -> bool {
+    let path = project_root.join("assets").join(name);
+    let Ok(target) = std::fs::read_link(&path) else { return false };
+    if data.linked_cache.lock().is_ok_and(|cache| cache.get(&path) == Some(&target)) {
+        return true;
+    }
+
+    let linked = linked_files::is_linked(project_root, &format!("assets/{}", name));
+    if linked {
+        if let Ok(mut cache) = data.linked_cache.lock() {
+            cache.insert(path, target);
+        }
+    }
+    linked
+}
+
+/// Receive clippings from the browser extension and import them into the
+/// active project's inbox group. Requires `Authorization: Bearer <token>`.
+#[post("/clip")]
+async fn receive_clip(
+    req: HttpRequest,
+    body: web::Bytes,
+    data: web::Data<ServerState>,
+) -> Result<HttpResponse, Error> {
+    if !is_authorized(&data, bearer_token(&req).unwrap_or("")) {
+        return Err(actix_web::error::ErrorUnauthorized("Invalid clip token"));
+    }
+
+    let project_root = resolve_project_root(&data)
+        .ok_or_else(|| actix_web::error::ErrorConflict("No project loaded"))?;
+
+    let content_type = req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    let items = if content_type.starts_with("multipart/form-data") {
+        clip::parse_multipart(&content_type, &body)
+            .map_err(|e| actix_web::error::ErrorBadRequest(e.to_string()))?
+    } else {
+        serde_json::from_slice::<clip::ClipRequest>(&body)
+            .map_err(|e| actix_web::error::ErrorBadRequest(e.to_string()))?
+            .into_items()
+    };
+
+    if items.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("Nothing to clip"));
+    }
+
+    let summary = format!("Import {} clipping{}", items.len(), if items.len() == 1 { "" } else { "s" });
+    data.permissions
+        .authorize(&data.app, &project_root, Actor::Clip, Capability::ImportAssets, summary, Vec::new())
+        .await
+        .map_err(|e| actix_web::error::ErrorForbidden(e.to_string()))?;
+
+    // Decoding images and writing the database stay off the event loop
+    let imported = web::block(move || clip::import_clips(&project_root, items))
+        .await?
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // Let the open canvas merge the new nodes before its next autosave
+    let _ = events::emit(&data.app, AppEvent::ClipImported(imported.clone()));
+
+    Ok(HttpResponse::Ok().json(imported))
+}
+
+/// Query of the OAuth redirect
+#[derive(Debug, serde::Deserialize)]
+struct OAuthCallback {
+    state: Option<String>,
+    code: Option<String>,
+    error: Option<String>,
+}
+
+/// Redirect target of connector sign-ins: trade the code for tokens and
+/// tell the user they can return to the app.
+#[get("/oauth/callback")]
+async fn oauth_callback(
+    query: web::Query<OAuthCallback>,
+    data: web::Data<ServerState>,
+) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    let login = query.state.as_deref()
+        .and_then(|state| data.logins.take(state))
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("Unknown or expired sign-in"))?;
+
+    let result = match (query.code, query.error) {
+        (Some(code), None) => {
+            let config = GlobalConfig::load(&data.app);
+            let client = ClientCredentials::from_config(&config, login.provider);
+            let policy = NetworkPolicy::from_config(&config);
+            match data.app.path().app_config_dir() {
+                Ok(config_dir) => oauth::finish(&config_dir, &policy, &client, &login, &code).await,
+                Err(e) => Err(AppError::Io(e.to_string())),
+            }
+        }
+        (_, error) => Err(AppError::Forbidden(error.unwrap_or_else(|| "no code received".to_string()))),
+    };
+
+    let message = match result {
+        Ok(()) => {
+            let _ = events::emit(&data.app, AppEvent::ConnectorConnected(login.provider));
+            format!("{} is connected. You can close this window and return to Synnia.", login.provider.label())
+        }
+        Err(e) => format!("Connecting {} failed: {}", login.provider.label(), e),
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(format!("<!doctype html><title>Synnia</title><p>{}</p>", html_escape(&message))))
+}
+
+/// Resolve the active project's root directory (handles the .json file case).
+pub(crate) fn resolve_project_root(data: &ServerState) -> Option<PathBuf> {
+    let project_path_str = {
+        let guard = data.current_project_path.lock().ok()?;
+        guard.clone()?
+    };
+    Some(project_root_of(PathBuf::from(project_path_str)))
+}
+
+/// Root directory of a project path as stored in the shared state
+pub(crate) fn project_root_of(project_path: PathBuf) -> PathBuf {
+    if project_path.extension().is_some() {
+        project_path.parent().unwrap_or(&project_path).to_path_buf()
+    } else {
+        project_path
+    }
+}
+
+/// Assets may be overwritten in place, so clients must revalidate (cheap 304s)
+const CACHE_CONTROL: &str = "private, no-cache";
+
+/// Get the ETag and sniffed Content-Type for a file, reusing the cached
+/// hash while the file's mtime and size are unchanged.
+async fn lookup_validator(data: &ServerState, path: &Path, file: &NamedFile) -> Result<CachedEtag, Error> {
+    let metadata = file.metadata();
+    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+    let len = metadata.len();
+
+    if let Ok(cache) = data.etag_cache.lock() {
+        if let Some(cached) = cache.get(path) {
+            if cached.modified == modified && cached.len == len {
+                return Ok(cached.clone());
+            }
+        }
+    }
+
+    // Hashing a large file (or sniffing its type) would hold up every other
+    // request on this worker
+    let file_path = path.to_path_buf();
+    let (content_hash, content_type) = web::block(move || {
+        hash::compute_file_hash(&file_path).map(|hash| (hash, content_type::detect_file_mime(&file_path)))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mtime_secs = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let validator = CachedEtag {
+        modified,
+        len,
+        etag: format!("\"{}-{:x}\"", &content_hash[..16], mtime_secs),
+        content_type,
+    };
+
+    if let Ok(mut cache) = data.etag_cache.lock() {
+        cache.insert(path.to_path_buf(), validator.clone());
+    }
+
+    Ok(validator)
+}
+
+/// Check whether the request's If-None-Match header matches the ETag.
+fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    req.headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*" || tag.trim_start_matches("W/") == etag
+        }))
+        .unwrap_or(false)
+}
+
+/// Query of the event stream; browsers can't set headers on a WebSocket
+#[derive(Debug, serde::Deserialize)]
+struct EventsQuery {
+    token: Option<String>,
+}
+
+/// WebSocket mirroring backend events as `{ "event", "payload" }` text
+/// messages, for the browser extension, presenter screens and CLI watchers.
+/// Takes the clip token as `Authorization: Bearer <token>` or `?token=`.
+/// A client too slow to keep up skips the events it missed.
+#[get("/events")]
+async fn event_stream(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<EventsQuery>,
+    data: web::Data<ServerState>,
+) -> Result<HttpResponse, Error> {
+    let provided = bearer_token(&req).or(query.token.as_deref()).unwrap_or("");
+    if !is_authorized(&data, provided) {
+        return Err(actix_web::error::ErrorUnauthorized("Invalid clip token"));
+    }
+
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+
+    let mut sender = session.clone();
+    let forward = actix_web::rt::spawn(async move {
+        let mut rx = events::subscribe();
+        loop {
+            match rx.recv().await {
+                Ok(text) => {
+                    if sender.text(text).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+        let _ = sender.close(None).await;
+    });
+
+    // Answer pings and the closing handshake; forwarding stops with the client
+    actix_web::rt::spawn(async move {
+        while let Some(Ok(message)) = messages.recv().await {
+            match message {
+                actix_ws::Message::Ping(bytes) if session.pong(&bytes).await.is_err() => break,
+                actix_ws::Message::Close(reason) => {
+                    let _ = session.close(reason).await;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        forward.abort();
+    });
+
+    Ok(response)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub(crate) fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+pub(crate) fn is_authorized(data: &ServerState, provided: &str) -> bool {
+    data.clip_token.lock()
+        .map(|token| !token.is_empty() && constant_time_eq(token.as_bytes(), provided.as_bytes()))
+        .unwrap_or(false)
+}
+
+/// Compare without stopping at the first differing byte, so response times
+/// don't reveal how much of a guessed token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Origins a public server answers cross-origin requests from: the app's
+/// own webview (desktop clients, `tauri dev`) and browser extensions, which
+/// still need the token. Scripts and workers send no `Origin` at all.
+fn is_allowed_origin(origin: &str) -> bool {
+    const APP_ORIGINS: &[&str] = &[
+        "tauri://localhost",
+        "http://tauri.localhost",
+        "https://tauri.localhost",
+        "http://localhost:1420",
+    ];
+    const EXTENSION_SCHEMES: &[&str] = &["chrome-extension://", "moz-extension://", "safari-web-extension://"];
+    
+    APP_ORIGINS.contains(&origin) || EXTENSION_SCHEMES.iter().any(|scheme| origin.starts_with(scheme))
+}
+
+fn cors(public: bool) -> Cors {
+    if !public {
+        return Cors::permissive();
+    }
+    Cors::default()
+        .allowed_origin_fn(|origin, _| origin.to_str().map(is_allowed_origin).unwrap_or(false))
+        .allow_any_method()
+        .allow_any_header()
+        .max_age(3600)
+}
+
+/// REST routes, plus GraphQL when built with the `graphql` feature
+fn configure_api(cfg: &mut web::ServiceConfig) {
+    rest_api::configure(cfg);
+    #[cfg(feature = "graphql")]
+    crate::services::graphql::configure(cfg);
+}
+
+/// Start the server on `host` (loopback unless running with `--server`) and
+/// `port`, or a free port; the port it listens on. Fails when `host` isn't
+/// an address to listen on or the port is taken.
+pub fn init(
+    host: &str,
+    port: Option<u16>,
+    current_project_path: Arc<Mutex<Option<String>>>,
+    clip_token: Arc<Mutex<String>>,
+    permissions: Arc<PermissionGate>,
+    logins: Arc<PendingLogins>,
+    app: AppHandle,
+) -> Result<u16, AppError> {
+    let bind_error = |e: std::io::Error| AppError::Io(format!("Failed to listen on {}: {}", host, e));
+    
     // 1. Find a free port
-    let port = {
-        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
-        listener.local_addr().unwrap().port()
-    }; 
+    let port = match port {
+        Some(port) => port,
+        None => TcpListener::bind((host, 0)).and_then(|listener| listener.local_addr()).map_err(bind_error)?.port(),
+    };
     // listener drops here, releasing port. 
     // Race condition exists but is rare on localhost.
+    let public = !launch::is_loopback_host(host);
 
     let server_state = web::Data::new(ServerState {
         current_project_path,
+        clip_token,
+        permissions,
+        logins,
+        app,
+        public,
+        etag_cache: Mutex::new(HashMap::new()),
+        linked_cache: Mutex::new(HashMap::new()),
     });
 
     // 2. Start Actix Server in a separate thread
     let server = HttpServer::new(move || {
         App::new()
-            .wrap(Cors::permissive()) 
+            .wrap(cors(public))
             .wrap(middleware::DefaultHeaders::new().add(("Cross-Origin-Resource-Policy", "cross-origin")))
             .app_data(server_state.clone())
+            .app_data(web::PayloadConfig::new(clip::MAX_CLIP_SIZE))
             .service(serve_asset)
+            .service(receive_clip)
+            .service(event_stream)
+            .service(oauth_callback)
+            .configure(configure_api)
     })
-    .bind(("127.0.0.1", port))
-    .expect("Failed to bind Actix server")
+    .bind((host, port))
+    .map_err(bind_error)?
     .run();
 
     // Tauri async runtime spawn (Tokio)
     tauri::async_runtime::spawn(server);
 
-    println!("[FileServer] Started on http://127.0.0.1:{}/assets/", port);
-    port
+    println!("[FileServer] Started on http://{}:{}/assets/", host, port);
+    Ok(port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"s3cret-token", b"s3cret-token"));
+        assert!(!constant_time_eq(b"s3cret-token", b"s3cret-tokeN"));
+        assert!(!constant_time_eq(b"s3cret-token", b"s3cret"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn test_allowed_origins() {
+        assert!(is_allowed_origin("tauri://localhost"));
+        assert!(is_allowed_origin("http://tauri.localhost"));
+        assert!(is_allowed_origin("chrome-extension://abcdefghijklmnop"));
+        assert!(!is_allowed_origin("https://example.com"));
+        assert!(!is_allowed_origin("http://localhost:8080"));
+        assert!(!is_allowed_origin("null"));
+    }
 }
@@ -0,0 +1,512 @@
+//! Folder import: bring a whole directory of reference material onto the
+//! canvas in one go.
+//!
+//! The tree is walked up front, then every folder becomes a group node (the
+//! root one included) with its files laid out in a grid and its subfolders
+//! stacked below them. Images become image nodes; `.txt` and `.md` files
+//! become text nodes. Everything else is skipped, as are files and folders
+//! the project's ignore rules match (see `ignore_rules`). With `link` set,
+//! images stay where they are and are linked in (see `linked_files`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{canvases, clip, database, disk_space, fs_paths, ignore_rules, io_sqlite, linked_files};
+use crate::services::ignore_rules::IgnoreRules;
+use crate::services::batch_import::IMAGE_EXTENSIONS;
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use ts_rs::TS;
+
+/// Grid cell and node size inside a group, matching the clip inbox
+const CELL_WIDTH: f64 = 320.0;
+const CELL_HEIGHT: f64 = 340.0;
+const NODE_WIDTH: f64 = 300.0;
+/// Offset of the first cell from the group's corner (room for the header)
+const PADDING_X: f64 = 20.0;
+const PADDING_TOP: f64 = 60.0;
+/// Gap between stacked subgroups
+const GROUP_GAP: f64 = 40.0;
+/// Gap between existing content and an import placed next to it
+const CANVAS_GAP: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImportFolderOptions {
+    /// Descend into subfolders
+    pub recursive: bool,
+    /// Deepest subfolder level to import (the picked folder is 0)
+    pub max_depth: Option<usize>,
+    /// Grid columns inside each group
+    pub columns: usize,
+    /// Canvas position of the root group; defaults to right of existing content
+    pub origin: Option<Position>,
+    /// Import dot-files and dot-folders
+    pub include_hidden: bool,
+    /// Link images where they are instead of copying them into the project
+    pub link: bool,
+}
+
+impl Default for ImportFolderOptions {
+    fn default() -> Self {
+        Self { recursive: true, max_depth: None, columns: 4, origin: None, include_hidden: false, link: false }
+    }
+}
+
+/// Payload of the `import:progress` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub current: usize,
+    pub total: usize,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImport {
+    pub root_group_id: String,
+    /// Group nodes created, the root included
+    pub groups: usize,
+    /// File nodes created
+    pub nodes: usize,
+    pub assets: Vec<Asset>,
+    /// Files that were unsupported or could not be read
+    pub skipped: Vec<String>,
+}
+
+/// Import the folder at `path` into the project at `project_root`, calling
+/// `on_progress` after each file.
+pub fn import_folder(
+    project_root: &Path,
+    path: &Path,
+    options: &ImportFolderOptions,
+    mut on_progress: impl FnMut(&ImportProgress),
+) -> Result<FolderImport, AppError> {
+    // Entries found under a long-path root keep the prefix, so nested files open too
+    let path = &fs_paths::long_path(path);
+    if !path.is_dir() {
+        return Err(AppError::NotFound(format!("Folder not found: {}", path.display())));
+    }
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let rules = ignore_rules::load(&conn)?;
+    
+    let mut skipped = Vec::new();
+    let mut tree = scan(path, "", 0, options, &rules, &mut skipped)?
+        .ok_or_else(|| AppError::NotFound(format!("No supported files in {}", path.display())))?;
+    layout(&mut tree, options.columns.max(1));
+    
+    let origin = match &options.origin {
+        Some(origin) => origin.clone(),
+        None => free_position(&conn)?,
+    };
+    
+    let mut import = FolderImport {
+        root_group_id: String::new(),
+        groups: 0,
+        nodes: 0,
+        assets: Vec::new(),
+        skipped,
+    };
+    let total = tree.file_count();
+    let mut writer = Writer { project_root, conn: &conn, columns: options.columns.max(1), link: options.link, total, current: 0 };
+    import.root_group_id = writer.write_folder(&tree, None, origin, &mut import, &mut on_progress)?;
+    
+    let summary = format!(
+        "Imported {} file{} from {}",
+        import.nodes,
+        if import.nodes == 1 { "" } else { "s" },
+        tree.name,
+    );
+    activity::record(&conn, &NewActivity::new(ActivityKind::Imported, "folder", summary).target(&import.root_group_id))?;
+    
+    Ok(import)
+}
+
+/// Bytes `import_folder` would copy into the project: the supported files
+/// it would pick up into the project at `project_root`, without walking
+/// into skipped folders
+pub fn estimated_size(project_root: &Path, path: &Path, options: &ImportFolderOptions) -> Result<u64, AppError> {
+    let path = fs_paths::long_path(path);
+    if !path.is_dir() {
+        return Err(AppError::NotFound(format!("Folder not found: {}", path.display())));
+    }
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let rules = ignore_rules::load(&conn)?;
+    Ok(scan(&path, "", 0, options, &rules, &mut Vec::new())?.map_or(0, |tree| tree.byte_size()))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+struct Folder {
+    name: String,
+    files: Vec<PathBuf>,
+    subfolders: Vec<Folder>,
+    /// Group size, filled in by `layout`
+    width: f64,
+    height: f64,
+}
+
+impl Folder {
+    fn file_count(&self) -> usize {
+        self.files.len() + self.subfolders.iter().map(Folder::file_count).sum::<usize>()
+    }
+    
+    fn byte_size(&self) -> u64 {
+        disk_space::files_size(&self.files) + self.subfolders.iter().map(Folder::byte_size).sum::<u64>()
+    }
+}
+
+/// Walk a folder; None if nothing in it (or below it) can be imported
+/// Walk `path`, found at `relative` (`/`-separated) under the picked folder
+fn scan(
+    path: &Path,
+    relative: &str,
+    depth: usize,
+    options: &ImportFolderOptions,
+    rules: &IgnoreRules,
+    skipped: &mut Vec<String>,
+) -> Result<Option<Folder>, AppError> {
+    let mut entries: Vec<_> = fs::read_dir(path)
+        .map_err(|e| AppError::Io(format!("Failed to read folder {}: {}", path.display(), e)))?
+        .flatten()
+        .filter(|e| options.include_hidden || !e.file_name().to_string_lossy().starts_with('.'))
+        .collect();
+    entries.sort_by_key(|e| e.file_name().to_string_lossy().to_lowercase());
+    
+    let descend = options.recursive && options.max_depth.map_or(true, |max| depth < max);
+    let mut folder = Folder {
+        name: Some(fs_paths::file_name(path)).filter(|n| !n.is_empty()).unwrap_or_else(|| path.display().to_string()),
+        files: Vec::new(),
+        subfolders: Vec::new(),
+        width: 0.0,
+        height: 0.0,
+    };
+    for entry in entries {
+        // file_type() doesn't follow symlinks, so linked folders can't loop
+        let Ok(file_type) = entry.file_type() else { continue };
+        let entry_path = entry.path();
+        let entry_relative = format!("{}{}", relative, entry.file_name().to_string_lossy());
+        if rules.is_ignored(&entry_relative, file_type.is_dir()) {
+            continue;
+        }
+        if file_type.is_dir() {
+            if descend {
+                if let Some(sub) = scan(&entry_path, &format!("{}/", entry_relative), depth + 1, options, rules, skipped)? {
+                    folder.subfolders.push(sub);
+                }
+            }
+        } else if file_type.is_file() {
+            if file_kind(&entry_path).is_some() {
+                folder.files.push(entry_path);
+            } else {
+                skipped.push(entry_path.display().to_string());
+            }
+        }
+    }
+    
+    Ok((!folder.files.is_empty() || !folder.subfolders.is_empty()).then_some(folder))
+}
+
+enum FileKind {
+    Image,
+    Text(&'static str),
+}
+
+fn file_kind(path: &Path) -> Option<FileKind> {
+    let ext = fs_paths::extension(path)?;
+    match ext.as_str() {
+        "md" | "markdown" => Some(FileKind::Text("markdown")),
+        "txt" => Some(FileKind::Text("plain")),
+        e if IMAGE_EXTENSIONS.contains(&e) => Some(FileKind::Image),
+        _ => None,
+    }
+}
+
+/// Size groups bottom-up: the file grid, then subgroups stacked below it
+fn layout(folder: &mut Folder, columns: usize) {
+    for sub in &mut folder.subfolders {
+        layout(sub, columns);
+    }
+    
+    let used_columns = folder.files.len().min(columns);
+    let rows = folder.files.len().div_ceil(columns);
+    let mut width = PADDING_X + used_columns as f64 * CELL_WIDTH;
+    let mut height = PADDING_TOP + rows as f64 * CELL_HEIGHT;
+    for sub in &folder.subfolders {
+        width = width.max(PADDING_X * 2.0 + sub.width);
+        height += sub.height + GROUP_GAP;
+    }
+    folder.width = width.max(CELL_WIDTH + PADDING_X);
+    folder.height = height.max(PADDING_TOP + CELL_HEIGHT / 2.0);
+}
+
+fn subfolder_offset(folder: &Folder, index: usize, columns: usize) -> Position {
+    let rows = folder.files.len().div_ceil(columns);
+    let above: f64 = folder.subfolders[..index].iter().map(|s| s.height + GROUP_GAP).sum();
+    Position { x: PADDING_X, y: PADDING_TOP + rows as f64 * CELL_HEIGHT + above }
+}
+
+/// Top-level spot to the right of everything on the active canvas
+pub(crate) fn free_position(conn: &Connection) -> Result<Position, AppError> {
+    let canvas_id = canvases::active_canvas(conn)?;
+    let right: Option<f64> = conn.query_row(
+        "SELECT MAX(x + COALESCE(width, ?1)) FROM nodes WHERE parent_id IS NULL AND canvas_id = ?2",
+        params![NODE_WIDTH, canvas_id],
+        |row| row.get(0),
+    ).map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    Ok(Position { x: right.map_or(0.0, |r| r + CANVAS_GAP), y: 0.0 })
+}
+
+struct Writer<'a> {
+    project_root: &'a Path,
+    conn: &'a Connection,
+    columns: usize,
+    link: bool,
+    total: usize,
+    current: usize,
+}
+
+impl Writer<'_> {
+    /// Write a folder's group, then its files, then its subfolders. Parents
+    /// go first so children land on their canvas.
+    fn write_folder(
+        &mut self,
+        folder: &Folder,
+        parent_id: Option<&str>,
+        position: Position,
+        import: &mut FolderImport,
+        on_progress: &mut impl FnMut(&ImportProgress),
+    ) -> Result<String, AppError> {
+        let group = SynniaNode {
+            id: uuid::Uuid::new_v4().to_string(),
+            type_: "group".to_string(),
+            position,
+            width: Some(folder.width),
+            height: Some(folder.height),
+            parent_id: parent_id.map(String::from),
+            extent: parent_id.map(|_| "parent".to_string()),
+            style: None,
+            data: SynniaNodeData {
+                title: folder.name.clone(),
+                layout_mode: Some("grid".to_string()),
+                ..Default::default()
+            },
+        };
+        io_sqlite::upsert_node(self.conn, &group)?;
+        import.groups += 1;
+        
+        let mut index = 0;
+        for path in &folder.files {
+            self.current += 1;
+            on_progress(&ImportProgress { current: self.current, total: self.total, file: path.display().to_string() });
+            
+            let (asset, node_type) = match self.store(path) {
+                Ok(stored) => stored,
+                Err(_) => {
+                    import.skipped.push(path.display().to_string());
+                    continue;
+                }
+            };
+            let column = (index % self.columns) as f64;
+            let row = (index / self.columns) as f64;
+            index += 1;
+            
+            let node = SynniaNode {
+                id: uuid::Uuid::new_v4().to_string(),
+                type_: node_type.to_string(),
+                position: Position { x: PADDING_X + column * CELL_WIDTH, y: PADDING_TOP + row * CELL_HEIGHT },
+                width: Some(NODE_WIDTH),
+                height: None,
+                parent_id: Some(group.id.clone()),
+                extent: Some("parent".to_string()),
+                style: None,
+                data: SynniaNodeData {
+                    title: asset.sys.name.clone(),
+                    asset_id: Some(asset.id.clone()),
+                    ..Default::default()
+                },
+            };
+            io_sqlite::upsert_node(self.conn, &node)?;
+            import.nodes += 1;
+            import.assets.push(asset);
+        }
+        
+        for (i, sub) in folder.subfolders.iter().enumerate() {
+            let offset = subfolder_offset(folder, i, self.columns);
+            self.write_folder(sub, Some(&group.id), offset, import, on_progress)?;
+        }
+        
+        Ok(group.id)
+    }
+
+    fn store(&self, path: &Path) -> Result<(Asset, &'static str), AppError> {
+        let name = fs_paths::file_name(path);
+        let (value, value_meta, node_type) = match file_kind(path) {
+            Some(FileKind::Text(format)) => {
+                let text = fs::read_to_string(path)?;
+                (
+                    serde_json::json!({ "content": text, "format": format }),
+                    Some(serde_json::json!({ "length": text.chars().count() })),
+                    "text-node",
+                )
+            }
+            _ if self.link => {
+                let (file_id, src) = linked_files::link_file(self.conn, self.project_root, path)?;
+                clip::file_value(self.project_root, &file_id, &src, &fs::read(path)?)?
+            }
+            _ => clip::store_file(self.project_root, &name, &fs::read(path)?)?,
+        };
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let asset = Asset {
+            id: uuid::Uuid::new_v4().to_string(),
+            value_type: ValueType::Record,
+            value,
+            value_meta,
+            config: Some(serde_json::json!({ "schema": [] })),
+            sys: AssetSysMetadata {
+                name,
+                created_at: now,
+                updated_at: now,
+                source: "import".to_string(),
+            },
+        };
+        io_sqlite::save_asset_with_history(self.project_root, &asset)?;
+        Ok((asset, node_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn png() -> Vec<u8> {
+        let mut data = Vec::new();
+        image::RgbImage::new(4, 4)
+            .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let project = tempdir().unwrap();
+        let root = project.path();
+        io_sqlite::init_project_sqlite(root, "Refs").unwrap();
+        
+        let source = tempdir().unwrap();
+        let refs = source.path().join("refs");
+        fs::create_dir_all(refs.join("faces/old")).unwrap();
+        fs::create_dir_all(refs.join("empty")).unwrap();
+        for i in 0..5 {
+            fs::write(refs.join(format!("{}.png", i)), png()).unwrap();
+        }
+        fs::write(refs.join("notes.md"), "# Mood").unwrap();
+        fs::write(refs.join("clip.mov"), b"mov").unwrap();
+        fs::write(refs.join(".DS_Store"), b"x").unwrap();
+        fs::write(refs.join("faces/a.jpg"), png()).unwrap();
+        fs::write(refs.join("faces/old/b.png"), png()).unwrap();
+        
+        // Skipped files (.mov, dot-files) don't count towards the copy
+        let png_len = png().len() as u64;
+        assert_eq!(estimated_size(root, &refs, &ImportFolderOptions::default()).unwrap(), 7 * png_len + 6);
+        
+        let mut progress = Vec::new();
+        let import = import_folder(root, &refs, &ImportFolderOptions::default(), |p| progress.push(p.current)).unwrap();
+        assert_eq!((import.groups, import.nodes), (3, 8));
+        assert_eq!(progress, (1..=8).collect::<Vec<_>>());
+        assert_eq!(import.skipped.len(), 1);
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let nodes = io_sqlite::load_nodes(&conn, canvases::DEFAULT_CANVAS_ID).unwrap();
+        let group = |title: &str| nodes.iter().find(|n| n.type_ == "group" && n.data.title == title).unwrap();
+        let root_group = group("refs");
+        assert_eq!(root_group.id, import.root_group_id);
+        assert_eq!(root_group.width, Some(20.0 + 4.0 * 320.0));
+        
+        // Six files fill two grid rows; the subfolder sits below them
+        let faces = group("faces");
+        assert_eq!(faces.parent_id.as_deref(), Some(root_group.id.as_str()));
+        assert_eq!((faces.position.x, faces.position.y), (20.0, 60.0 + 2.0 * 340.0));
+        assert_eq!(group("old").parent_id.as_deref(), Some(faces.id.as_str()));
+        
+        let notes = nodes.iter().find(|n| n.data.title == "notes.md").unwrap();
+        assert_eq!(notes.type_, "text-node");
+        assert_eq!(nodes.iter().filter(|n| n.type_ == "image-node").count(), 7);
+        
+        // Without recursion only the top folder comes in
+        let flat = ImportFolderOptions { recursive: false, ..Default::default() };
+        assert_eq!(import_folder(root, &refs, &flat, |_| {}).unwrap().groups, 1);
+        
+        // Ignored files and folders are left out, not reported as skipped
+        ignore_rules::save_patterns(&conn, &["faces/old/".to_string(), "*.md".to_string(), "*.mov".to_string()]).unwrap();
+        let ignored = import_folder(root, &refs, &ImportFolderOptions::default(), |_| {}).unwrap();
+        assert_eq!((ignored.groups, ignored.nodes), (2, 6));
+        assert!(ignored.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_import_unicode_names() {
+        let project = tempdir().unwrap();
+        let root = project.path();
+        io_sqlite::init_project_sqlite(root, "Refs").unwrap();
+        
+        // Deep enough to pass 260 characters, with names in several scripts
+        let source = tempdir().unwrap();
+        let mut deep = source.path().join("参考 🎨");
+        for i in 0..12 {
+            deep = deep.join(format!("Ébauche-{}-дизайн-スケッチ", i));
+        }
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("猫 🐱.PNG"), png()).unwrap();
+        fs::write(deep.join("заметки.md"), "# Идея").unwrap();
+        assert!(deep.as_os_str().len() > 260);
+        
+        let import = import_folder(root, &source.path().join("参考 🎨"), &ImportFolderOptions::default(), |_| {}).unwrap();
+        assert_eq!((import.groups, import.nodes), (13, 2));
+        assert!(import.skipped.is_empty());
+        
+        let names: Vec<_> = import.assets.iter().map(|a| a.sys.name.as_str()).collect();
+        assert!(names.contains(&"猫 🐱.PNG") && names.contains(&"заметки.md"));
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let nodes = io_sqlite::load_nodes(&conn, canvases::DEFAULT_CANVAS_ID).unwrap();
+        assert!(nodes.iter().any(|n| n.type_ == "group" && n.data.title == "参考 🎨"));
+        assert!(nodes.iter().any(|n| n.type_ == "group" && n.data.title == "Ébauche-11-дизайн-スケッチ"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_import_folder_linked() {
+        let project = tempdir().unwrap();
+        let root = project.path();
+        io_sqlite::init_project_sqlite(root, "Refs").unwrap();
+        
+        let source = tempdir().unwrap();
+        fs::write(source.path().join("a.png"), png()).unwrap();
+        fs::write(source.path().join("notes.txt"), "keep").unwrap();
+        
+        let options = ImportFolderOptions { link: true, ..Default::default() };
+        let import = import_folder(root, source.path(), &options, |_| {}).unwrap();
+        assert_eq!(import.nodes, 2);
+        
+        let image = import.assets.iter().find(|a| a.sys.name == "a.png").unwrap();
+        let src = image.value["src"].as_str().unwrap();
+        assert!(root.join(src).symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(linked_files::is_linked(root, src));
+        assert!(image.value_meta.as_ref().unwrap()["preview"].is_string());
+        let notes = import.assets.iter().find(|a| a.sys.name == "notes.txt").unwrap();
+        assert_eq!(notes.value["content"], "keep");
+    }
+}
@@ -0,0 +1,132 @@
+//! Helpers for files the user picks from anywhere on disk.
+//!
+//! Sources can sit in deeply nested folders, have names in any script or
+//! names that aren't valid UTF-8 at all. Names and extensions are read
+//! lossily instead of being dropped, and on Windows long paths get the
+//! `\\?\` prefix so they aren't cut off at 260 characters.
+
+use std::path::{Path, PathBuf};
+
+/// Length from which Windows needs the `\\?\` form (directories hit the
+/// limit 12 characters before files do)
+#[cfg(windows)]
+const MAX_SHORT_PATH: usize = 248;
+
+/// File name of `path` for display and asset metadata. Bytes that aren't
+/// valid UTF-8 become U+FFFD rather than losing the whole name.
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Lowercase extension of `path`, if it has one
+pub fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .filter(|e| !e.is_empty())
+}
+
+/// `path` in a form the OS accepts at any length. On Windows, long absolute
+/// paths are rewritten to `\\?\C:\...` or `\\?\UNC\server\share\...`;
+/// elsewhere the path is returned as is.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> PathBuf {
+    use std::ffi::OsString;
+    use std::path::{Component, Prefix};
+
+    if path.as_os_str().len() < MAX_SHORT_PATH || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    let mut components = path.components();
+    let mut prefixed = match components.next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(_) => {
+                let mut s = OsString::from(r"\\?\");
+                s.push(prefix.as_os_str());
+                s
+            }
+            Prefix::UNC(server, share) => {
+                let mut s = OsString::from(r"\\?\UNC\");
+                s.push(server);
+                s.push(r"\");
+                s.push(share);
+                s
+            }
+            // Already verbatim, or a device path
+            _ => return path.to_path_buf(),
+        },
+        _ => return path.to_path_buf(),
+    };
+
+    // Verbatim paths skip normalization, so resolve `.` and `..` here
+    let mut parts = Vec::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::ParentDir => {
+                parts.pop();
+            }
+            _ => {}
+        }
+    }
+    for part in parts {
+        prefixed.push(r"\");
+        prefixed.push(part);
+    }
+    PathBuf::from(prefixed)
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_extensions() {
+        assert_eq!(file_name(Path::new("/refs/猫 🐱.PNG")), "猫 🐱.PNG");
+        assert_eq!(extension(Path::new("/refs/猫 🐱.PNG")).as_deref(), Some("png"));
+        assert_eq!(extension(Path::new("/refs/Makefile")), None);
+        assert_eq!(extension(Path::new("/refs/trailing.")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new("/refs").join(OsStr::from_bytes(b"caf\xe9.JPG"));
+        assert_eq!(file_name(&path), "caf\u{fffd}.JPG");
+        assert_eq!(extension(&path).as_deref(), Some("jpg"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_long_path_is_unchanged() {
+        let deep = format!("/refs/{}/a.png", "nested/".repeat(60));
+        assert_eq!(long_path(Path::new(&deep)), PathBuf::from(&deep));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_path_prefix() {
+        let short = Path::new(r"C:\refs\a.png");
+        assert_eq!(long_path(short), short);
+
+        let nested = "nested\\".repeat(40);
+        assert_eq!(
+            long_path(Path::new(&format!(r"C:\refs\{}..\a.png", nested))),
+            PathBuf::from(format!(r"\\?\C:\refs\{}a.png", "nested\\".repeat(39))),
+        );
+        assert_eq!(
+            long_path(Path::new(&format!(r"\\nas\footage\{}a.png", nested))),
+            PathBuf::from(format!(r"\\?\UNC\nas\footage\{}a.png", nested)),
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Generation tracking: seed/model/params per generated asset and
+//! reproducible variations.
+//!
+//! Provides:
+//! - Persisting `GenerationParams` per asset in `generation_records`
+//! - Deriving variation params with controlled overrides and seed strategy
+//! - Finalizing a variation job into a new asset linked to its parent
+
+use std::path::Path;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::{Asset, GenerationParams, GenerationRecord, Position, SynniaNode, SynniaNodeData};
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const VARIATION_JOB_KIND: &str = "variation";
+
+/// How the seed changes when deriving a variation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SeedStrategy {
+    /// Reuse the stored seed (exact reproduction unless other params change)
+    #[default]
+    Keep,
+    /// Stored seed + 1, for a nearby variation
+    Increment,
+    /// Fresh random seed
+    Random,
+}
+
+/// Job payload for a variation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariationPayload {
+    pub source_asset_id: String,
+    pub recipe_id: Option<String>,
+    pub params: GenerationParams,
+}
+
+/// Asset and node produced by a finished variation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariationResult {
+    pub asset: Asset,
+    pub node: Option<SynniaNode>,
+    pub record: GenerationRecord,
+}
+
+/// Store (or replace) the generation parameters for an asset.
+pub fn save_record(conn: &Connection, record: &GenerationRecord) -> Result<(), AppError> {
+    let params_json = serde_json::to_string(&record.params)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO generation_records (asset_id, recipe_id, parent_asset_id, params_json, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            &record.asset_id,
+            &record.recipe_id,
+            &record.parent_asset_id,
+            &params_json,
+            record.created_at
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to save generation record: {}", e)))?;
+    Ok(())
+}
+
+/// Load the generation parameters for an asset.
+pub fn get_record(conn: &Connection, asset_id: &str) -> Result<Option<GenerationRecord>, AppError> {
+    conn.query_row(
+        "SELECT asset_id, recipe_id, parent_asset_id, params_json, created_at
+         FROM generation_records WHERE asset_id = ?1",
+        params![asset_id],
+        row_to_record,
+    )
+    .optional()
+    .map_err(|e| AppError::Io(format!("Failed to load generation record: {}", e)))
+}
+
+/// List variations derived from an asset, newest first.
+pub fn get_variations(conn: &Connection, parent_asset_id: &str) -> Result<Vec<GenerationRecord>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT asset_id, recipe_id, parent_asset_id, params_json, created_at
+         FROM generation_records WHERE parent_asset_id = ?1
+         ORDER BY created_at DESC"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params![parent_asset_id], row_to_record)
+        .map_err(|e| AppError::Io(format!("Failed to query variations: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load variations: {}", e)))
+}
+
+/// Apply overrides (a partial `GenerationParams` object; `null` clears a
+/// field) and the seed strategy to stored params.
+pub fn derive_params(
+    base: &GenerationParams,
+    overrides: &Value,
+    strategy: SeedStrategy,
+) -> Result<GenerationParams, AppError> {
+    let mut merged = serde_json::to_value(base)?;
+    
+    if let (Some(target), Some(changes)) = (merged.as_object_mut(), overrides.as_object()) {
+        for (key, value) in changes {
+            if key == "extra" {
+                let extra = target.entry("extra").or_insert_with(|| json!({}));
+                if let (Some(extra), Some(changes)) = (extra.as_object_mut(), value.as_object()) {
+                    for (k, v) in changes {
+                        extra.insert(k.clone(), v.clone());
+                    }
+                }
+            } else if value.is_null() {
+                target.remove(key);
+            } else {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    
+    let mut params: GenerationParams = serde_json::from_value(merged)?;
+    
+    // An explicit seed override wins over the strategy
+    let seed_overridden = overrides.get("seed").map(|s| !s.is_null()).unwrap_or(false);
+    if !seed_overridden {
+        params.seed = match strategy {
+            SeedStrategy::Keep => params.seed,
+            SeedStrategy::Increment => params.seed.map(|s| s.wrapping_add(1)).or(Some(random_seed())),
+            SeedStrategy::Random => Some(random_seed()),
+        };
+    }
+    
+    Ok(params)
+}
+
+/// Persist the output of a finished variation job as a new asset next to its source.
+pub fn finalize_variation(project_root: &Path, job: &Job) -> Result<Option<VariationResult>, AppError> {
+    let payload: VariationPayload = serde_json::from_value(job.payload.clone())?;
+    
+    let task = match job.tasks.first() {
+        Some(task) if task.status == TaskStatus::Success => task,
+        _ => return Ok(None),
+    };
+    let output = task.result.clone().unwrap_or(Value::Null);
+    
+    let project = io_sqlite::load_project_sqlite(project_root)?;
+    let source = project.assets.get(&payload.source_asset_id)
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.source_asset_id)))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut asset = source.clone();
+    asset.id = uuid::Uuid::new_v4().to_string();
+    asset.value = output;
+    asset.value_meta = None;
+    asset.sys.name = format!("{} (variation)", source.sys.name);
+    asset.sys.created_at = now;
+    asset.sys.updated_at = now;
+    asset.sys.source = "ai".to_string();
+    
+    // Place the new node to the right of the first node showing the source
+    let node = project.graph.nodes.iter()
+        .find(|n| n.data.asset_id.as_deref() == Some(payload.source_asset_id.as_str()))
+        .map(|source_node| SynniaNode {
+            id: uuid::Uuid::new_v4().to_string(),
+            type_: source_node.type_.clone(),
+            position: Position {
+                x: source_node.position.x + source_node.width.unwrap_or(300.0) + 40.0,
+                y: source_node.position.y,
+            },
+            width: source_node.width,
+            height: source_node.height,
+            parent_id: source_node.parent_id.clone(),
+            extent: source_node.extent.clone(),
+            style: None,
+            data: SynniaNodeData {
+                title: asset.sys.name.clone(),
+                asset_id: Some(asset.id.clone()),
+                ..Default::default()
+            },
+        });
+    
+    let record = GenerationRecord {
+        asset_id: asset.id.clone(),
+        recipe_id: payload.recipe_id,
+        parent_asset_id: Some(payload.source_asset_id),
+        params: payload.params,
+        created_at: now,
+    };
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    if let Some(node) = &node {
+        io_sqlite::upsert_node(&conn, node)?;
+    }
+    save_record(&conn, &record)?;
+    
+    Ok(Some(VariationResult { asset, node, record }))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<GenerationRecord> {
+    let params_json: String = row.get(3)?;
+    Ok(GenerationRecord {
+        asset_id: row.get(0)?,
+        recipe_id: row.get(1)?,
+        parent_asset_id: row.get(2)?,
+        params: serde_json::from_str(&params_json).unwrap_or_default(),
+        created_at: row.get(4)?,
+    })
+}
+
+/// Random seed in the 32-bit range most diffusion backends accept
+fn random_seed() -> i64 {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    fn base_params() -> GenerationParams {
+        GenerationParams {
+            seed: Some(42),
+            model: Some("sdxl".to_string()),
+            prompt: Some("a red fox".to_string()),
+            steps: Some(30),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_derive_params_keeps_seed() {
+        let params = derive_params(&base_params(), &json!({ "prompt": "a blue fox" }), SeedStrategy::Keep).unwrap();
+        assert_eq!(params.seed, Some(42));
+        assert_eq!(params.prompt.as_deref(), Some("a blue fox"));
+        assert_eq!(params.model.as_deref(), Some("sdxl"));
+    }
+
+    #[test]
+    fn test_derive_params_seed_strategies() {
+        let inc = derive_params(&base_params(), &json!({}), SeedStrategy::Increment).unwrap();
+        assert_eq!(inc.seed, Some(43));
+        
+        let explicit = derive_params(&base_params(), &json!({ "seed": 7 }), SeedStrategy::Random).unwrap();
+        assert_eq!(explicit.seed, Some(7), "explicit seed wins over strategy");
+        
+        let cleared = derive_params(&base_params(), &json!({ "steps": null }), SeedStrategy::Keep).unwrap();
+        assert_eq!(cleared.steps, None);
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        
+        let record = GenerationRecord {
+            asset_id: "child".to_string(),
+            recipe_id: Some("txt2img".to_string()),
+            parent_asset_id: Some("parent".to_string()),
+            params: base_params(),
+            created_at: 1,
+        };
+        save_record(&conn, &record).unwrap();
+        
+        let loaded = get_record(&conn, "child").unwrap().unwrap();
+        assert_eq!(loaded.params.seed, Some(42));
+        assert_eq!(get_variations(&conn, "parent").unwrap().len(), 1);
+        assert!(get_record(&conn, "missing").unwrap().is_none());
+    }
+}
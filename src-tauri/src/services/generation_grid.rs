@@ -0,0 +1,326 @@
+//! Batch generation grids: parameter matrix expansion and result layout.
+//!
+//! A grid run takes a recipe node and a list of axes (e.g. 4 prompts ×
+//! 3 styles), expands them into one job task per cell, and once the job
+//! queue reports every cell settled, writes an array asset of results plus
+//! one node per successful cell laid out as a grid next to the recipe node.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, Position, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "generation-grid";
+
+/// Max cells in a single grid run
+pub const MAX_GRID_CELLS: usize = 64;
+
+const CELL_WIDTH: f64 = 300.0;
+const CELL_HEIGHT: f64 = 300.0;
+const CELL_GAP: f64 = 40.0;
+
+/// One dimension of the parameter matrix
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GridAxis {
+    /// Recipe input key to vary (e.g. "prompt", "style")
+    pub key: String,
+    #[ts(type = "any[]")]
+    pub values: Vec<Value>,
+}
+
+/// Data the finalizer needs, stored as the job payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridPayload {
+    pub recipe_node_id: String,
+    pub axes: Vec<GridAxis>,
+    pub output_node_type: String,
+}
+
+/// Assets and nodes produced by a finished grid
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridResult {
+    pub job_id: String,
+    pub collection: Asset,
+    pub assets: Vec<Asset>,
+    pub nodes: Vec<SynniaNode>,
+}
+
+/// Expand axes into the cartesian product of parameter sets.
+/// The first axis varies slowest (rows), the last fastest (columns).
+pub fn expand_matrix(axes: &[GridAxis]) -> Result<Vec<Value>, AppError> {
+    if axes.is_empty() {
+        return Err(AppError::Unknown("At least one variation axis is required".to_string()));
+    }
+    if let Some(axis) = axes.iter().find(|a| a.values.is_empty()) {
+        return Err(AppError::Unknown(format!("Variation axis '{}' has no values", axis.key)));
+    }
+    
+    let total: usize = axes.iter().map(|a| a.values.len()).product();
+    if total > MAX_GRID_CELLS {
+        return Err(AppError::Unknown(format!(
+            "Grid has {} cells, the maximum is {}", total, MAX_GRID_CELLS
+        )));
+    }
+    
+    let mut cells: Vec<Map<String, Value>> = vec![Map::new()];
+    for axis in axes {
+        let mut next = Vec::with_capacity(cells.len() * axis.values.len());
+        for cell in &cells {
+            for value in &axis.values {
+                let mut cell = cell.clone();
+                cell.insert(axis.key.clone(), value.clone());
+                next.push(cell);
+            }
+        }
+        cells = next;
+    }
+    
+    Ok(cells.into_iter().map(Value::Object).collect())
+}
+
+/// Number of grid columns: product of all axes but the first.
+pub fn column_count(axes: &[GridAxis]) -> usize {
+    axes.iter().skip(1).map(|a| a.values.len()).product::<usize>().max(1)
+}
+
+/// Persist the results of a finished grid job into the project.
+pub fn finalize_grid(project_root: &Path, job: &Job) -> Result<GridResult, AppError> {
+    let payload: GridPayload = serde_json::from_value(job.payload.clone())?;
+    let columns = column_count(&payload.axes);
+    
+    let db_path = io_sqlite::get_db_path(project_root);
+    let conn = database::open_db(&db_path)
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let recipe = io_sqlite::get_node(&conn, &payload.recipe_node_id)?;
+    let origin = recipe
+        .as_ref()
+        .map(|n| Position {
+            x: n.position.x + n.width.unwrap_or(CELL_WIDTH) + CELL_GAP * 2.0,
+            y: n.position.y,
+        })
+        .unwrap_or(Position { x: 0.0, y: 0.0 });
+    let parent_id = recipe.as_ref().and_then(|n| n.parent_id.clone());
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut assets = Vec::new();
+    let mut nodes = Vec::new();
+    let mut items = Vec::new();
+    
+    for task in &job.tasks {
+        let row = (task.index / columns) as f64;
+        let col = (task.index % columns) as f64;
+        let caption = describe_params(&task.params);
+        
+        let mut item = json!({
+            "index": task.index,
+            "params": task.params,
+            "status": task.status,
+            "caption": caption,
+        });
+        
+        match (&task.status, &task.result) {
+            (TaskStatus::Success, Some(result)) => {
+                let asset = Asset {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    value_type: ValueType::Record,
+                    value: result.clone(),
+                    value_meta: None,
+                    config: Some(json!({ "schema": [] })),
+                    sys: AssetSysMetadata {
+                        name: caption.clone(),
+                        created_at: now,
+                        updated_at: now,
+                        source: "ai".to_string(),
+                    },
+                };
+                
+                let node = SynniaNode {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    type_: payload.output_node_type.clone(),
+                    position: Position {
+                        x: origin.x + col * (CELL_WIDTH + CELL_GAP),
+                        y: origin.y + row * (CELL_HEIGHT + CELL_GAP),
+                    },
+                    width: Some(CELL_WIDTH),
+                    height: Some(CELL_HEIGHT),
+                    parent_id: parent_id.clone(),
+                    extent: None,
+                    style: None,
+                    data: SynniaNodeData {
+                        title: caption,
+                        asset_id: Some(asset.id.clone()),
+                        ..Default::default()
+                    },
+                };
+                
+                item["assetId"] = json!(asset.id);
+                if let Some(src) = result.get("src") {
+                    item["src"] = src.clone();
+                }
+                item["result"] = result.clone();
+                
+                io_sqlite::save_asset_with_history(project_root, &asset)?;
+                io_sqlite::upsert_node(&conn, &node)?;
+                assets.push(asset);
+                nodes.push(node);
+            }
+            _ => {
+                if let Some(error) = &task.error {
+                    item["error"] = json!(error);
+                }
+            }
+        }
+        
+        items.push(item);
+    }
+    
+    // Collection asset holding the whole matrix, placed below the grid
+    let rows = job.tasks.len().div_ceil(columns) as f64;
+    let collection = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Array,
+        value: Value::Array(items),
+        value_meta: Some(json!({ "length": job.tasks.len() })),
+        config: Some(json!({
+            "mode": "grid",
+            "columns": columns,
+            "axes": payload.axes,
+        })),
+        sys: AssetSysMetadata {
+            name: "Generation Grid".to_string(),
+            created_at: now,
+            updated_at: now,
+            source: "ai".to_string(),
+        },
+    };
+    let collection_node = SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: if payload.output_node_type == "image-node" { "gallery-node" } else { "table-node" }.to_string(),
+        position: Position {
+            x: origin.x,
+            y: origin.y + rows * (CELL_HEIGHT + CELL_GAP),
+        },
+        width: Some(CELL_WIDTH * 2.0),
+        height: None,
+        parent_id,
+        extent: None,
+        style: None,
+        data: SynniaNodeData {
+            title: "Generation Grid".to_string(),
+            asset_id: Some(collection.id.clone()),
+            ..Default::default()
+        },
+    };
+    
+    io_sqlite::save_asset_with_history(project_root, &collection)?;
+    io_sqlite::upsert_node(&conn, &collection_node)?;
+    nodes.push(collection_node);
+    
+    Ok(GridResult {
+        job_id: job.id.clone(),
+        collection,
+        assets,
+        nodes,
+    })
+}
+
+/// Short human-readable label for a parameter set, e.g. "cat · watercolor"
+fn describe_params(params: &Value) -> String {
+    let label = params
+        .as_object()
+        .map(|map| {
+            map.values()
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" · ")
+        })
+        .unwrap_or_default();
+    
+    let mut chars = label.chars();
+    let truncated: String = chars.by_ref().take(50).collect();
+    if chars.next().is_some() {
+        format!("{}...", truncated)
+    } else {
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::io_sqlite::{init_project_sqlite, load_project_sqlite};
+    use tempfile::tempdir;
+
+    fn axes() -> Vec<GridAxis> {
+        vec![
+            GridAxis { key: "prompt".to_string(), values: vec![json!("cat"), json!("dog")] },
+            GridAxis { key: "style".to_string(), values: vec![json!("ink"), json!("oil"), json!("pixel")] },
+        ]
+    }
+
+    #[test]
+    fn test_expand_matrix() {
+        let cells = expand_matrix(&axes()).unwrap();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], json!({ "prompt": "cat", "style": "ink" }));
+        assert_eq!(cells[5], json!({ "prompt": "dog", "style": "pixel" }));
+        assert_eq!(column_count(&axes()), 3);
+    }
+
+    #[test]
+    fn test_expand_matrix_limits() {
+        let empty = vec![GridAxis { key: "prompt".to_string(), values: vec![] }];
+        assert!(expand_matrix(&empty).is_err());
+        
+        let huge = vec![
+            GridAxis { key: "a".to_string(), values: (0..10).map(|i| json!(i)).collect() },
+            GridAxis { key: "b".to_string(), values: (0..10).map(|i| json!(i)).collect() },
+        ];
+        assert!(expand_matrix(&huge).is_err());
+    }
+
+    #[test]
+    fn test_finalize_grid() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        init_project_sqlite(project_root, "Grid Project").unwrap();
+        
+        let payload = GridPayload {
+            recipe_node_id: "recipe-1".to_string(),
+            axes: axes(),
+            output_node_type: "image-node".to_string(),
+        };
+        let mut job = Job::new(JOB_KIND, serde_json::to_value(&payload).unwrap(), expand_matrix(&axes()).unwrap(), 2);
+        for task in job.tasks.iter_mut() {
+            if task.index == 4 {
+                task.status = TaskStatus::Error;
+                task.error = Some("failed".to_string());
+            } else {
+                task.status = TaskStatus::Success;
+                task.result = Some(json!({ "src": format!("assets/{}.png", task.index) }));
+            }
+        }
+        
+        let result = finalize_grid(project_root, &job).unwrap();
+        assert_eq!(result.assets.len(), 5);
+        assert_eq!(result.nodes.len(), 6, "five cells plus the collection node");
+        assert_eq!(result.collection.value.as_array().unwrap().len(), 6);
+        
+        let project = load_project_sqlite(project_root).unwrap();
+        assert_eq!(project.assets.len(), 6);
+        assert_eq!(project.graph.nodes.len(), 6);
+    }
+}
@@ -0,0 +1,366 @@
+//! Graph statistics and complexity warnings.
+//!
+//! Computes degree distribution, connected components, cycles through recipe
+//! nodes and crowded board areas in one pass over the graph, so the frontend
+//! doesn't have to walk large boards itself. Findings worth surfacing are
+//! collected as `warnings`.
+
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+use crate::models::{SynniaNode, SynniaProject};
+use crate::services::lint::Severity;
+use ts_rs::TS;
+
+/// Recipe nodes with more inputs than this get a warning
+const MAX_RECIPE_INPUTS: usize = 20;
+/// Nodes with more connections than this are flagged as hubs
+const MAX_DEGREE: usize = 50;
+/// Side of the square board cells used to find crowded areas (px)
+const HOT_SPOT_CELL: f64 = 1000.0;
+/// Minimum number of nodes in a cell to count as a hot spot
+const HOT_SPOT_MIN_NODES: usize = 40;
+
+const RECIPE_NODE_TYPE: &str = "recipe-node";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphAnalysis {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Edges / possible directed edges
+    pub density: f64,
+    /// Number of nodes per total degree, ascending by degree
+    pub degree_distribution: Vec<DegreeCount>,
+    /// Sizes of connected components (edges taken as undirected), largest first
+    pub component_sizes: Vec<usize>,
+    /// Nodes without any edge
+    pub isolated_nodes: usize,
+    /// Strongly connected node sets that contain a recipe node
+    pub recipe_cycles: Vec<Vec<String>>,
+    pub hot_spots: Vec<HotSpot>,
+    pub warnings: Vec<GraphWarning>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DegreeCount {
+    pub degree: usize,
+    pub nodes: usize,
+}
+
+/// A crowded board cell
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct HotSpot {
+    /// Top-left corner of the cell in board coordinates
+    pub x: f64,
+    pub y: f64,
+    pub size: f64,
+    pub node_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphWarning {
+    /// "recipe-fan-in", "hub-node", "recipe-cycle" or "dense-area"
+    pub kind: String,
+    pub severity: Severity,
+    pub message: String,
+    pub node_ids: Vec<String>,
+}
+
+pub fn analyze_graph(project: &SynniaProject) -> GraphAnalysis {
+    let nodes = &project.graph.nodes;
+    let index: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+    // Edges to nodes that no longer exist are ignored
+    let edges: Vec<(usize, usize)> = project.graph.edges.iter()
+        .filter_map(|e| Some((*index.get(e.source.as_str())?, *index.get(e.target.as_str())?)))
+        .collect();
+    
+    let mut in_degree = vec![0usize; nodes.len()];
+    let mut out_degree = vec![0usize; nodes.len()];
+    for &(source, target) in &edges {
+        out_degree[source] += 1;
+        in_degree[target] += 1;
+    }
+    
+    let mut distribution: BTreeMap<usize, usize> = BTreeMap::new();
+    for i in 0..nodes.len() {
+        *distribution.entry(in_degree[i] + out_degree[i]).or_default() += 1;
+    }
+    
+    let component_sizes = component_sizes(nodes.len(), &edges);
+    let recipe_cycles: Vec<Vec<String>> = strongly_connected(nodes.len(), &edges)
+        .into_iter()
+        .filter(|scc| scc.len() > 1 || edges.contains(&(scc[0], scc[0])))
+        .filter(|scc| scc.iter().any(|&i| is_recipe(&nodes[i])))
+        .map(|scc| scc.into_iter().map(|i| nodes[i].id.clone()).collect())
+        .collect();
+    let hot_spots = hot_spots(nodes);
+    
+    let mut warnings = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if is_recipe(node) && in_degree[i] > MAX_RECIPE_INPUTS {
+            warnings.push(GraphWarning {
+                kind: "recipe-fan-in".to_string(),
+                severity: Severity::Warning,
+                message: format!("Recipe \"{}\" has {} inputs (more than {})", node.data.title, in_degree[i], MAX_RECIPE_INPUTS),
+                node_ids: vec![node.id.clone()],
+            });
+        } else if in_degree[i] + out_degree[i] > MAX_DEGREE {
+            warnings.push(GraphWarning {
+                kind: "hub-node".to_string(),
+                severity: Severity::Info,
+                message: format!("\"{}\" has {} connections", node.data.title, in_degree[i] + out_degree[i]),
+                node_ids: vec![node.id.clone()],
+            });
+        }
+    }
+    for cycle in &recipe_cycles {
+        warnings.push(GraphWarning {
+            kind: "recipe-cycle".to_string(),
+            severity: Severity::Error,
+            message: format!("{} nodes form a cycle through a recipe", cycle.len()),
+            node_ids: cycle.clone(),
+        });
+    }
+    for spot in &hot_spots {
+        warnings.push(GraphWarning {
+            kind: "dense-area".to_string(),
+            severity: Severity::Info,
+            message: format!("{} nodes crowded around ({:.0}, {:.0})", spot.node_ids.len(), spot.x, spot.y),
+            node_ids: spot.node_ids.clone(),
+        });
+    }
+    
+    let possible = nodes.len().saturating_mul(nodes.len().saturating_sub(1));
+    GraphAnalysis {
+        node_count: nodes.len(),
+        edge_count: edges.len(),
+        density: if possible == 0 { 0.0 } else { edges.len() as f64 / possible as f64 },
+        degree_distribution: distribution.into_iter().map(|(degree, nodes)| DegreeCount { degree, nodes }).collect(),
+        isolated_nodes: (0..nodes.len()).filter(|&i| in_degree[i] + out_degree[i] == 0).count(),
+        component_sizes,
+        recipe_cycles,
+        hot_spots,
+        warnings,
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn is_recipe(node: &SynniaNode) -> bool {
+    node.type_ == RECIPE_NODE_TYPE || node.data.recipe_id.is_some()
+}
+
+/// Union-find over undirected edges
+fn component_sizes(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    
+    let mut parent: Vec<usize> = (0..count).collect();
+    for &(a, b) in edges {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        if ra != rb {
+            parent[ra] = rb;
+        }
+    }
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for i in 0..count {
+        *sizes.entry(find(&mut parent, i)).or_default() += 1;
+    }
+    let mut sizes: Vec<usize> = sizes.into_values().collect();
+    sizes.sort_by_key(|&s| std::cmp::Reverse(s));
+    sizes
+}
+
+/// Tarjan's algorithm, iterative so deep chains can't overflow the stack
+fn strongly_connected(count: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); count];
+    for &(source, target) in edges {
+        adjacency[source].push(target);
+    }
+    
+    let mut index = vec![usize::MAX; count];
+    let mut low = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+    
+    for root in 0..count {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // (node, next neighbour to visit)
+        let mut work = vec![(root, 0)];
+        while let Some(&mut (v, ref mut next)) = work.last_mut() {
+            if *next == 0 && index[v] == usize::MAX {
+                index[v] = next_index;
+                low[v] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = adjacency[v].get(*next) {
+                *next += 1;
+                if index[w] == usize::MAX {
+                    work.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Bucket nodes into board cells by absolute position
+fn hot_spots(nodes: &[SynniaNode]) -> Vec<HotSpot> {
+    let by_id: HashMap<&str, &SynniaNode> = nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut cells: BTreeMap<(i64, i64), Vec<String>> = BTreeMap::new();
+    for node in nodes {
+        let (mut x, mut y) = (node.position.x, node.position.y);
+        let mut parent = node.parent_id.as_deref();
+        let mut depth = 0;
+        while let Some(p) = parent.and_then(|id| by_id.get(id)) {
+            x += p.position.x;
+            y += p.position.y;
+            parent = p.parent_id.as_deref();
+            depth += 1;
+            // Guard against parent cycles in corrupt data
+            if depth > nodes.len() {
+                break;
+            }
+        }
+        let cell = ((x / HOT_SPOT_CELL).floor() as i64, (y / HOT_SPOT_CELL).floor() as i64);
+        cells.entry(cell).or_default().push(node.id.clone());
+    }
+    
+    let mut spots: Vec<HotSpot> = cells.into_iter()
+        .filter(|(_, ids)| ids.len() >= HOT_SPOT_MIN_NODES)
+        .map(|((cx, cy), node_ids)| HotSpot {
+            x: cx as f64 * HOT_SPOT_CELL,
+            y: cy as f64 * HOT_SPOT_CELL,
+            size: HOT_SPOT_CELL,
+            node_ids,
+        })
+        .collect();
+    spots.sort_by_key(|s| std::cmp::Reverse(s.node_ids.len()));
+    spots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::models::{Graph, Position, ProjectMeta, SynniaEdge, SynniaNodeData, Viewport};
+
+    fn node(id: &str, type_: &str, x: f64) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: type_.to_string(),
+            position: Position { x, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> SynniaEdge {
+        SynniaEdge {
+            id: format!("{}-{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }
+    }
+
+    #[test]
+    fn test_analyze_graph() {
+        let mut project = SynniaProject {
+            version: "2".to_string(),
+            meta: ProjectMeta {
+                id: "p".to_string(),
+                name: "Board".to_string(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                thumbnail: None,
+                description: None,
+                author: None,
+            },
+            viewport: Viewport { x: 0.0, y: 0.0, zoom: 1.0 },
+            graph: Graph { nodes: vec![], edges: vec![] },
+            assets: HashMap::new(),
+            settings: None,
+            canvas_id: None,
+        };
+        project.graph.nodes = vec![
+            node("prompt", "text-node", 0.0),
+            node("recipe", RECIPE_NODE_TYPE, 300.0),
+            node("output", "image-node", 600.0),
+            node("lonely", "text-node", 5000.0),
+        ];
+        for i in 0..=MAX_RECIPE_INPUTS {
+            project.graph.nodes.push(node(&format!("input-{}", i), "text-node", 100.0));
+        }
+        project.graph.edges = vec![
+            edge("prompt", "recipe"),
+            edge("recipe", "output"),
+            edge("output", "prompt"),
+            edge("output", "missing"),
+        ];
+        for i in 0..=MAX_RECIPE_INPUTS {
+            project.graph.edges.push(edge(&format!("input-{}", i), "recipe"));
+        }
+        
+        let analysis = analyze_graph(&project);
+        assert_eq!(analysis.edge_count, 3 + MAX_RECIPE_INPUTS + 1, "dangling edge ignored");
+        assert_eq!(analysis.component_sizes, vec![3 + MAX_RECIPE_INPUTS + 1, 1]);
+        assert_eq!(analysis.isolated_nodes, 1);
+        assert_eq!(analysis.recipe_cycles, vec![vec!["prompt", "recipe", "output"]]);
+        assert_eq!(analysis.hot_spots.len(), 0);
+        
+        let kinds: Vec<&str> = analysis.warnings.iter().map(|w| w.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["recipe-fan-in", "recipe-cycle"]);
+    }
+}
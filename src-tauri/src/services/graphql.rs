@@ -0,0 +1,354 @@
+//! GraphQL endpoint over the open project: nodes, edges, assets, their tags
+//! and version history, in one query where the REST routes would need many.
+//!
+//! Built only with the `graphql` feature. `POST /graphql` takes a standard
+//! GraphQL request (`{ query, variables, operationName }`) and the same
+//! bearer token as the REST API. The schema is read-only, like the REST API,
+//! and queries are capped in depth and complexity.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use actix_web::{post, web, Error, HttpRequest, HttpResponse};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Json, Object, Schema, SimpleObject};
+use rusqlite::Connection;
+use crate::error::AppError;
+use crate::models::{Asset, SynniaEdge, SynniaNode};
+use crate::services::{canvases, database, history, io_sqlite, tags};
+use super::file_server::{self, ServerState};
+
+pub type ProjectSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+const MAX_DEPTH: usize = 12;
+const MAX_COMPLEXITY: usize = 5000;
+const MAX_LIMIT: usize = 500;
+const DEFAULT_LIMIT: usize = 100;
+
+/// Mount `POST /graphql` on the local server.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::Data::new(schema())).service(graphql);
+}
+
+pub fn schema() -> ProjectSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+#[post("/graphql")]
+async fn graphql(
+    req: HttpRequest,
+    body: web::Json<async_graphql::Request>,
+    schema: web::Data<ProjectSchema>,
+    data: web::Data<ServerState>,
+) -> Result<HttpResponse, Error> {
+    if !file_server::is_authorized(&data, file_server::bearer_token(&req).unwrap_or("")) {
+        return Err(actix_web::error::ErrorUnauthorized("Invalid clip token"));
+    }
+    let project_root = file_server::resolve_project_root(&data).ok_or(AppError::ProjectNotLoaded)?;
+    let db = web::block(move || Db::open(&project_root)).await??;
+    let response = schema.execute(body.into_inner().data(db)).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// The project database, for the duration of one request
+pub struct Db(Arc<Mutex<Connection>>);
+
+impl Db {
+    pub fn open(project_root: &Path) -> Result<Self, AppError> {
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        Ok(Self(Arc::new(Mutex::new(conn))))
+    }
+
+    /// Run `work` on the blocking pool, like the REST routes' queries
+    async fn with<T, F>(ctx: &Context<'_>, work: F) -> async_graphql::Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T, AppError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let conn = ctx.data::<Db>()?.0.clone();
+        let result = web::block(move || {
+            let conn = conn.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+            work(&conn)
+        })
+        .await
+        .map_err(|e| AppError::Unknown(e.to_string()))?;
+        Ok(result?)
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn project(&self, ctx: &Context<'_>) -> async_graphql::Result<ProjectInfo> {
+        Db::with(ctx, |conn| {
+            let meta = io_sqlite::load_project_meta(conn)?;
+            Ok(ProjectInfo {
+                id: meta.id,
+                name: meta.name,
+                description: meta.description,
+                created_at: meta.created_at,
+                updated_at: meta.updated_at,
+            })
+        }).await
+    }
+
+    async fn canvases(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<CanvasInfo>> {
+        Db::with(ctx, |conn| {
+            Ok(canvases::list(conn)?.into_iter()
+                .map(|c| CanvasInfo { id: c.id, name: c.name })
+                .collect())
+        }).await
+    }
+
+    /// Nodes of `canvas`, by default the most recently opened one
+    async fn nodes(&self, ctx: &Context<'_>, canvas: Option<String>) -> async_graphql::Result<Vec<Node>> {
+        Db::with(ctx, move |conn| {
+            let canvas = canvas.map_or_else(|| canvases::active_canvas(conn), Ok)?;
+            Ok(io_sqlite::load_nodes(conn, &canvas)?.into_iter().map(Node).collect())
+        }).await
+    }
+
+    async fn node(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Node>> {
+        Db::with(ctx, move |conn| Ok(io_sqlite::get_node(conn, &id)?.map(Node))).await
+    }
+
+    /// Edges of `canvas`, by default the most recently opened one
+    async fn edges(&self, ctx: &Context<'_>, canvas: Option<String>) -> async_graphql::Result<Vec<Edge>> {
+        Db::with(ctx, move |conn| {
+            let canvas = canvas.map_or_else(|| canvases::active_canvas(conn), Ok)?;
+            Ok(io_sqlite::load_edges(conn, &canvas)?.into_iter().map(Edge).collect())
+        }).await
+    }
+
+    /// Assets, most recently updated first
+    async fn assets(&self, ctx: &Context<'_>, limit: Option<usize>, offset: Option<usize>) -> async_graphql::Result<Vec<AssetObject>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        Db::with(ctx, move |conn| {
+            let mut assets: Vec<Asset> = io_sqlite::load_assets(conn)?.into_values().collect();
+            assets.sort_by(|a, b| b.sys.updated_at.cmp(&a.sys.updated_at).then_with(|| a.id.cmp(&b.id)));
+            Ok(assets.into_iter().skip(offset.unwrap_or(0)).take(limit).map(AssetObject).collect())
+        }).await
+    }
+
+    async fn asset(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<AssetObject>> {
+        Db::with(ctx, move |conn| Ok(io_sqlite::get_asset(conn, &id)?.map(AssetObject))).await
+    }
+
+    /// Assets with every tag of `tags` (comma-separated), as in
+    /// `search_assets_by_tags`
+    async fn search(&self, ctx: &Context<'_>, tags: String, limit: Option<usize>) -> async_graphql::Result<Vec<AssetObject>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        Db::with(ctx, move |conn| {
+            tags::search(conn, &tags, &[], None, limit)?.iter()
+                .filter_map(|id| io_sqlite::get_asset(conn, id).transpose())
+                .map(|asset| asset.map(AssetObject))
+                .collect()
+        }).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ProjectInfo {
+    id: String,
+    name: String,
+    description: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CanvasInfo {
+    id: String,
+    name: String,
+}
+
+pub struct Node(SynniaNode);
+
+#[Object]
+impl Node {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    #[graphql(name = "type")]
+    async fn node_type(&self) -> &str {
+        &self.0.type_
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.data.title
+    }
+
+    async fn x(&self) -> f64 {
+        self.0.position.x
+    }
+
+    async fn y(&self) -> f64 {
+        self.0.position.y
+    }
+
+    async fn width(&self) -> Option<f64> {
+        self.0.width
+    }
+
+    async fn height(&self) -> Option<f64> {
+        self.0.height
+    }
+
+    async fn parent_id(&self) -> Option<&str> {
+        self.0.parent_id.as_deref()
+    }
+
+    /// The node's full `data`, as the frontend sees it
+    async fn data(&self) -> Json<serde_json::Value> {
+        Json(serde_json::to_value(&self.0.data).unwrap_or_default())
+    }
+
+    async fn asset(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<AssetObject>> {
+        let Some(asset_id) = self.0.data.asset_id.clone() else { return Ok(None) };
+        Db::with(ctx, move |conn| Ok(io_sqlite::get_asset(conn, &asset_id)?.map(AssetObject))).await
+    }
+}
+
+pub struct Edge(SynniaEdge);
+
+#[Object]
+impl Edge {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    async fn target(&self) -> &str {
+        &self.0.target
+    }
+
+    #[graphql(name = "type")]
+    async fn edge_type(&self) -> Option<&str> {
+        self.0.type_.as_deref()
+    }
+
+    async fn label(&self) -> Option<&str> {
+        self.0.label.as_deref()
+    }
+}
+
+pub struct AssetObject(Asset);
+
+#[Object(name = "Asset")]
+impl AssetObject {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.sys.name
+    }
+
+    /// Where the asset came from: "user", "ai", "import", ...
+    async fn source(&self) -> &str {
+        &self.0.sys.source
+    }
+
+    async fn value(&self) -> Json<serde_json::Value> {
+        Json(self.0.value.clone())
+    }
+
+    /// Unix ms
+    async fn created_at(&self) -> i64 {
+        self.0.sys.created_at
+    }
+
+    /// Unix ms
+    async fn updated_at(&self) -> i64 {
+        self.0.sys.updated_at
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Tag>> {
+        let id = self.0.id.clone();
+        Db::with(ctx, move |conn| {
+            Ok(tags::get_tags(conn, &id)?.into_iter()
+                .map(|t| Tag { tag: t.tag, source: t.source, confidence: t.confidence })
+                .collect())
+        }).await
+    }
+
+    /// Earlier versions, newest first
+    async fn history(&self, ctx: &Context<'_>, limit: Option<i32>) -> async_graphql::Result<Vec<HistoryEntry>> {
+        let id = self.0.id.clone();
+        Db::with(ctx, move |conn| {
+            let entries = history::get_asset_history(conn, &id, limit.map(|l| l.clamp(1, 50)))
+                .map_err(|e| AppError::Io(format!("Failed to load history: {}", e)))?;
+            Ok(entries.into_iter()
+                .map(|e| HistoryEntry {
+                    id: e.id,
+                    content_hash: e.content_hash,
+                    content: Json(serde_json::from_str(&e.content_json).unwrap_or_default()),
+                    created_at: e.created_at,
+                })
+                .collect())
+        }).await
+    }
+
+    /// Nodes showing this asset, on any canvas
+    async fn nodes(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Node>> {
+        let id = self.0.id.clone();
+        Db::with(ctx, move |conn| Ok(io_sqlite::get_nodes_by_asset(conn, &id)?.into_iter().map(Node).collect())).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Tag {
+    tag: String,
+    source: String,
+    confidence: Option<f64>,
+}
+
+#[derive(SimpleObject)]
+pub struct HistoryEntry {
+    id: i64,
+    content_hash: String,
+    content: Json<serde_json::Value>,
+    /// Unix ms
+    created_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_query() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let sys = json!({ "name": "Hero", "createdAt": 0, "updatedAt": 0, "source": "user" });
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at) VALUES ('a1', 'record', 'h', '{}', ?1, 0)",
+            [sys.to_string()],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO nodes (id, type, x, y, data_json) VALUES ('n1', 'asset-node', 0, 0, ?1)",
+            [json!({ "title": "Hero shot", "assetId": "a1" }).to_string()],
+        ).unwrap();
+        tags::set_tags(&conn, "a1", tags::USER_SOURCE, &[("red car".to_string(), None)]).unwrap();
+
+        let query = "{ node(id: \"n1\") { title asset { name tags { tag } nodes { id } } } }";
+        let response = tauri::async_runtime::block_on(
+            schema().execute(async_graphql::Request::new(query).data(Db::open(dir.path()).unwrap()))
+        );
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["node"]["title"], "Hero shot");
+        assert_eq!(data["node"]["asset"]["tags"][0]["tag"], "red car");
+        assert_eq!(data["node"]["asset"]["nodes"][0]["id"], "n1");
+    }
+}
@@ -4,9 +4,15 @@
 //! - Automatic snapshot creation on content change
 //! - History retrieval with pagination
 //! - Version restoration
+//!
+//! Snapshots are taken from `assets.value_json` / `value_hash` and stored in
+//! `asset_history.content_json` / `content_hash`.
 
-use rusqlite::{Connection, Result as SqliteResult, params};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult, params};
 use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{hash, references};
+use ts_rs::TS;
 
 /// Maximum number of history entries to keep per asset
 const MAX_HISTORY_PER_ASSET: i32 = 50;
@@ -21,6 +27,20 @@ pub struct AssetHistoryEntry {
     pub created_at: i64,
 }
 
+/// Result of restoring a history version
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    /// The restored value
+    #[ts(type = "any")]
+    pub value: serde_json::Value,
+    /// Hash of the content that was replaced (now in history)
+    pub previous_hash: String,
+    /// Hash of the restored content
+    pub restored_hash: String,
+}
+
 /// Create a history snapshot if the content hash has changed.
 /// Uses INSERT OR IGNORE to deduplicate by (asset_id, content_hash).
 ///
@@ -100,6 +120,39 @@ pub fn get_history_entry(conn: &Connection, history_id: i64) -> SqliteResult<Opt
     }
 }
 
+/// Make a history entry the asset's current value (`assets.value_json` /
+/// `value_hash`). The current value is snapshotted first, so the restore
+/// itself can be undone.
+pub fn restore_version(conn: &Connection, asset_id: &str, history_id: i64) -> Result<RestoreResult, AppError> {
+    let entry = get_history_entry(conn, history_id)
+        .map_err(|e| AppError::Io(format!("Failed to get history entry: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("History entry not found".to_string()))?;
+    if entry.asset_id != asset_id {
+        return Err(AppError::Unknown("History entry does not belong to this asset".to_string()));
+    }
+    let value: serde_json::Value = serde_json::from_str(&entry.content_json)?;
+    
+    let (previous_hash, current_json): (String, String) = conn.query_row(
+        "SELECT value_hash, value_json FROM assets WHERE id = ?1",
+        params![asset_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()
+        .map_err(|e| AppError::Io(format!("Failed to read asset: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    create_snapshot_if_changed(conn, asset_id, &previous_hash, &current_json)
+        .map_err(|e| AppError::Io(format!("Failed to create snapshot: {}", e)))?;
+    
+    let restored_hash = hash::compute_content_hash(&entry.content_json);
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "UPDATE assets SET value_json = ?1, value_hash = ?2, updated_at = ?3 WHERE id = ?4",
+        params![entry.content_json, restored_hash, now, asset_id],
+    ).map_err(|e| AppError::Io(format!("Failed to restore asset: {}", e)))?;
+    references::notify_asset_updated(conn, asset_id)?;
+    
+    Ok(RestoreResult { value, previous_hash, restored_hash })
+}
+
 /// Remove old history entries beyond MAX_HISTORY_PER_ASSET.
 fn cleanup_old_history(conn: &Connection, asset_id: &str) -> SqliteResult<()> {
     // Delete entries that are older than the Nth newest entry
@@ -118,6 +171,29 @@ fn cleanup_old_history(conn: &Connection, asset_id: &str) -> SqliteResult<()> {
     Ok(())
 }
 
+/// Keep only the newest `keep` history entries of every asset.
+/// Returns the number of entries removed.
+pub fn trim_history(conn: &Connection, keep: usize) -> SqliteResult<usize> {
+    conn.execute(
+        "DELETE FROM asset_history
+         WHERE id IN (
+             SELECT id FROM (
+                 SELECT id, ROW_NUMBER() OVER (PARTITION BY asset_id ORDER BY created_at DESC, id DESC) AS n
+                 FROM asset_history
+             ) WHERE n > ?1
+         )",
+        params![keep as i64],
+    )
+}
+
+/// Remove history of assets that no longer exist. Returns the number removed.
+pub fn remove_orphaned_history(conn: &Connection) -> SqliteResult<usize> {
+    conn.execute(
+        "DELETE FROM asset_history WHERE asset_id NOT IN (SELECT id FROM assets)",
+        [],
+    )
+}
+
 /// Get the current content hash for an asset.
 pub fn get_current_hash(conn: &Connection, asset_id: &str) -> SqliteResult<Option<String>> {
     let mut stmt = conn.prepare(
@@ -145,7 +221,10 @@ pub fn count_history(conn: &Connection, asset_id: &str) -> SqliteResult<i64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use crate::services::{database, io_sqlite};
     use crate::services::database::init_db;
+    use serde_json::json;
     use tempfile::tempdir;
 
     fn setup_test_db() -> Connection {
@@ -232,4 +311,78 @@ mod tests {
         // Should be capped at MAX_HISTORY_PER_ASSET
         assert!(count <= MAX_HISTORY_PER_ASSET as i64);
     }
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        stmt.query_map([], |row| row.get(1)).unwrap().map(|c| c.unwrap()).collect()
+    }
+
+    fn text_asset(value: &str) -> Asset {
+        Asset {
+            id: "note".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "text": value }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Note".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        }
+    }
+
+    #[test]
+    fn test_schema_has_history_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(database::SCHEMA_SQL).unwrap();
+        
+        let assets = columns(&conn, "assets");
+        for column in ["value_json", "value_hash", "updated_at"] {
+            assert!(assets.iter().any(|c| c == column), "assets.{} missing", column);
+        }
+        let history = columns(&conn, "asset_history");
+        for column in ["asset_id", "content_hash", "content_json", "created_at"] {
+            assert!(history.iter().any(|c| c == column), "asset_history.{} missing", column);
+        }
+    }
+
+    #[test]
+    fn test_save_history_restore() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        io_sqlite::init_project_sqlite(root, "History").unwrap();
+        io_sqlite::save_asset_with_history(root, &text_asset("first")).unwrap();
+        io_sqlite::save_asset_with_history(root, &text_asset("second")).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let history = get_asset_history(&conn, "note", None).unwrap();
+        assert_eq!(history.len(), 1);
+        let second_hash = get_current_hash(&conn, "note").unwrap().unwrap();
+        
+        let restored = restore_version(&conn, "note", history[0].id).unwrap();
+        assert_eq!(restored.value, json!({ "text": "first" }));
+        assert_eq!(restored.previous_hash, second_hash);
+        assert_eq!(restored.restored_hash, history[0].content_hash);
+        
+        // The asset row holds the restored value, the replaced one is in history
+        let asset = io_sqlite::get_asset(&conn, "note").unwrap().unwrap();
+        assert_eq!(asset.value, json!({ "text": "first" }));
+        assert_eq!(get_current_hash(&conn, "note").unwrap(), Some(restored.restored_hash.clone()));
+        let history = get_asset_history(&conn, "note", None).unwrap();
+        let replaced = history.iter().find(|h| h.content_hash == second_hash).unwrap();
+        
+        // ...so the restore can be undone
+        let undone = restore_version(&conn, "note", replaced.id).unwrap();
+        assert_eq!(undone.value, json!({ "text": "second" }));
+        assert_eq!(count_history(&conn, "note").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_restore_rejects_other_asset() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        create_snapshot_if_changed(&conn, "asset-1", "hash-1", "{}").unwrap();
+        let entry = &get_asset_history(&conn, "asset-1", None).unwrap()[0];
+        
+        assert!(restore_version(&conn, "asset-2", entry.id).is_err());
+        // The entry's own asset no longer exists
+        assert!(matches!(restore_version(&conn, "asset-1", entry.id), Err(AppError::NotFound(_))));
+    }
 }
@@ -0,0 +1,191 @@
+//! Per-project ignore rules: files Synnia should leave alone when scanning
+//! folders.
+//!
+//! Rules use `.gitignore` syntax and are stored in the project's settings.
+//! A pattern without a slash matches a name at any depth; one with a slash
+//! matches from the scanned root; `*`, `?` and `**` are wildcards; a trailing
+//! `/` matches folders only, and everything inside an ignored folder is
+//! ignored; `!` re-includes what an earlier rule ignored; `#` starts a
+//! comment. Folder import skips ignored files and folders, and compaction
+//! never deletes ignored files from `assets/`. Projects without rules get
+//! `DEFAULT_PATTERNS`.
+
+use rusqlite::Connection;
+use crate::error::AppError;
+use crate::services::io_sqlite;
+
+/// Settings key of the ignore patterns
+pub const SETTINGS_KEY: &str = "ignoreRules";
+
+/// OS litter, archive leftovers, caches and in-flight downloads
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    "__MACOSX/",
+    ".cache/",
+    "*.tmp",
+    "*.part",
+];
+
+/// Parsed ignore patterns
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    glob: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    /// Matched against the whole relative path instead of the name
+    anchored: bool,
+}
+
+impl IgnoreRules {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self { rules: patterns.iter().filter_map(|p| Rule::parse(p.as_ref())).collect() }
+    }
+
+    /// Whether `path` (relative to the scanned root, `/`-separated) is
+    /// ignored, itself or through one of its folders
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_matches('/');
+        let in_ignored_folder = path.char_indices()
+            .any(|(i, c)| c == '/' && self.matches(&path[..i], true));
+        in_ignored_folder || self.matches(path, is_dir)
+    }
+
+    /// The last rule matching `path` decides
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let path: Vec<char> = path.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        self.rules.iter()
+            .rev()
+            .find(|rule| (!rule.dir_only || is_dir) && glob_match(&rule.glob, if rule.anchored { &path } else { &name }))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
+
+/// The project's patterns, or the defaults if none were saved
+pub fn get_patterns(conn: &Connection) -> Result<Vec<String>, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_else(|| DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect()))
+}
+
+/// Store the project's patterns, trimmed and without blank lines
+pub fn save_patterns(conn: &Connection, patterns: &[String]) -> Result<Vec<String>, AppError> {
+    let patterns: Vec<String> = patterns.iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if let Some(bad) = patterns.iter().find(|p| p.split('/').any(|part| part == "..")) {
+        return Err(AppError::InvalidInput(format!("Ignore patterns can't point outside the folder: {}", bad)));
+    }
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(&patterns)?)?;
+    Ok(patterns)
+}
+
+/// The project's rules, ready for matching
+pub fn load(conn: &Connection) -> Result<IgnoreRules, AppError> {
+    Ok(IgnoreRules::new(&get_patterns(conn)?))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        (!line.is_empty()).then(|| Rule { glob: line.chars().collect(), negated, dir_only, anchored })
+    }
+}
+
+/// `*` and `?` stay within one path segment; `**` crosses segments
+fn glob_match(glob: &[char], text: &[char]) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            glob_match(rest, text)
+                || text.iter().enumerate().any(|(i, c)| *c == '/' && glob_match(rest, &text[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let segment = text.iter().position(|c| *c == '/').unwrap_or(text.len());
+            (0..=segment).any(|i| glob_match(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => matches!(text.first(), Some(c) if *c != '/') && glob_match(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_ignore_patterns() {
+        let rules = IgnoreRules::new(&[
+            "# OS litter",
+            ".DS_Store",
+            "*.tmp",
+            "cache/",
+            "/raw/**/*.cr2",
+            "renders/*.png",
+            "!renders/final.png",
+        ]);
+
+        assert!(rules.is_ignored(".DS_Store", false));
+        assert!(rules.is_ignored("moodboard/.DS_Store", false));
+        assert!(rules.is_ignored("a/b/scratch.tmp", false));
+        assert!(!rules.is_ignored("a/b/scratch.tmp.png", false));
+
+        // Folder-only patterns take everything inside
+        assert!(rules.is_ignored("shots/cache", true));
+        assert!(rules.is_ignored("shots/cache/frame.png", false));
+        assert!(!rules.is_ignored("shots/cache", false));
+
+        // Anchored patterns match from the root, `**` across folders
+        assert!(rules.is_ignored("raw/day1/IMG_001.cr2", false));
+        assert!(rules.is_ignored("raw/IMG_002.cr2", false));
+        assert!(!rules.is_ignored("best/raw/IMG_003.cr2", false));
+
+        assert!(rules.is_ignored("renders/draft.png", false));
+        assert!(!rules.is_ignored("renders/final.png", false));
+        assert!(!rules.is_ignored("renders/sub/draft.png", false), "`*` stays within one folder");
+    }
+
+    #[test]
+    fn test_patterns_setting() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        assert_eq!(get_patterns(&conn).unwrap().len(), DEFAULT_PATTERNS.len());
+        assert!(load(&conn).unwrap().is_ignored("assets/video.mp4.part", false));
+
+        let saved = save_patterns(&conn, &[" *.psd ".to_string(), String::new()]).unwrap();
+        assert_eq!(saved, ["*.psd"]);
+        assert_eq!(get_patterns(&conn).unwrap(), saved);
+        assert!(!load(&conn).unwrap().is_ignored(".DS_Store", false));
+
+        assert!(matches!(save_patterns(&conn, &["../secrets".to_string()]), Err(AppError::InvalidInput(_))));
+    }
+}
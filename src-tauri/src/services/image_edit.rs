@@ -0,0 +1,355 @@
+//! AI inpainting/outpainting of image assets through the job queue.
+//!
+//! The backend prepares the source image and the mask in the encoding the
+//! provider expects; the frontend sends the request (ComfyUI or the OpenAI
+//! images edit endpoint) and reports `{ data }` (base64 or data URL) or
+//! `{ src }`. The edited image becomes a new asset next to the source, with
+//! `config.extra.provenance` linking source, mask and prompt.
+
+use std::io::Cursor;
+use std::path::Path;
+use base64::Engine;
+use image::{DynamicImage, GrayImage, ImageFormat, Luma, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::{Asset, GenerationParams, GenerationRecord, Position, SynniaNode, SynniaNodeData};
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{clip, database, generation, imaging, io_sqlite};
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "image-edit";
+
+const NODE_GAP: f64 = 40.0;
+
+/// What the prompt fills in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum EditMode {
+    /// Repaint the masked area of the image
+    #[default]
+    Inpaint,
+    /// Extend the image to the mask's size; the source sits centered and
+    /// the border (plus any masked area) is generated
+    Outpaint,
+}
+
+/// Providers with an image edit API, named as in the AI config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum EditProvider {
+    /// Images edit endpoint: the mask's transparent pixels are edited
+    #[default]
+    OpenAI,
+    /// Inpaint workflows: white mask pixels are edited
+    ComfyUI,
+}
+
+/// Job payload for an edit run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditPayload {
+    pub source_asset_id: String,
+    pub mask_asset_id: String,
+    pub prompt: String,
+    pub mode: EditMode,
+    pub provider: EditProvider,
+}
+
+/// Asset and node produced by a finished edit
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditResult {
+    pub asset: Asset,
+    pub node: Option<SynniaNode>,
+}
+
+/// Build the single task of an edit job: the prompt plus the source image
+/// and mask as PNG data URLs, sized and encoded for the provider.
+pub fn build_task(project_root: &Path, payload: &EditPayload) -> Result<Value, AppError> {
+    if payload.prompt.trim().is_empty() {
+        return Err(AppError::Unknown("Edit prompt is empty".to_string()));
+    }
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let source = load_image(project_root, &conn, &payload.source_asset_id)?;
+    let mask = load_image(project_root, &conn, &payload.mask_asset_id)?;
+    
+    let (image, edit_area) = prepare(&source, &mask, payload.mode)?;
+    let encoded_mask = encode_mask(&edit_area, image.dimensions(), payload.provider);
+    
+    Ok(json!({
+        "sourceAssetId": payload.source_asset_id,
+        "maskAssetId": payload.mask_asset_id,
+        "prompt": payload.prompt,
+        "mode": payload.mode,
+        "provider": payload.provider,
+        "width": image.width(),
+        "height": image.height(),
+        "image": png_data_url(&DynamicImage::ImageRgba8(image))?,
+        "mask": png_data_url(&encoded_mask)?,
+    }))
+}
+
+/// Persist the output of a finished edit job as a new asset next to its source.
+pub fn finalize_edit(project_root: &Path, job: &Job) -> Result<Option<EditResult>, AppError> {
+    let payload: EditPayload = serde_json::from_value(job.payload.clone())?;
+    
+    let result = match job.tasks.first() {
+        Some(task) if task.status == TaskStatus::Success => task.result.clone().unwrap_or(Value::Null),
+        _ => return Ok(None),
+    };
+    let data = reported_image(project_root, &result)?;
+    
+    let project = io_sqlite::load_project_sqlite(project_root)?;
+    let source = project.assets.get(&payload.source_asset_id)
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.source_asset_id)))?;
+    let (value, value_meta, _) = clip::store_file(project_root, "edit.png", &data)?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut asset = source.clone();
+    asset.id = uuid::Uuid::new_v4().to_string();
+    asset.value = value;
+    asset.value_meta = value_meta;
+    asset.sys.name = format!("{} ({})", source.sys.name, match payload.mode {
+        EditMode::Inpaint => "inpaint",
+        EditMode::Outpaint => "outpaint",
+    });
+    asset.sys.created_at = now;
+    asset.sys.updated_at = now;
+    asset.sys.source = "ai".to_string();
+    
+    let mut config = asset.config.take().unwrap_or_else(|| json!({ "schema": [] }));
+    if let Some(config) = config.as_object_mut() {
+        let extra = config.entry("extra").or_insert_with(|| json!({}));
+        if let Some(extra) = extra.as_object_mut() {
+            extra.insert("provenance".to_string(), json!({
+                "kind": "edit",
+                "mode": payload.mode,
+                "sourceAssetId": payload.source_asset_id,
+                "maskAssetId": payload.mask_asset_id,
+                "prompt": payload.prompt,
+                "provider": payload.provider,
+                "generatedAt": now,
+            }));
+        }
+    }
+    asset.config = Some(config);
+    
+    let node = project.graph.nodes.iter()
+        .find(|n| n.data.asset_id.as_deref() == Some(payload.source_asset_id.as_str()))
+        .map(|source_node| SynniaNode {
+            id: uuid::Uuid::new_v4().to_string(),
+            type_: source_node.type_.clone(),
+            position: Position {
+                x: source_node.position.x + source_node.width.unwrap_or(300.0) + NODE_GAP,
+                y: source_node.position.y,
+            },
+            width: source_node.width,
+            height: source_node.height,
+            parent_id: source_node.parent_id.clone(),
+            extent: source_node.extent.clone(),
+            style: None,
+            data: SynniaNodeData {
+                title: asset.sys.name.clone(),
+                asset_id: Some(asset.id.clone()),
+                ..Default::default()
+            },
+        });
+    
+    // Also list the edit among the source's variations
+    let mut extra = serde_json::Map::new();
+    extra.insert("mode".to_string(), json!(payload.mode));
+    extra.insert("maskAssetId".to_string(), json!(payload.mask_asset_id));
+    let record = GenerationRecord {
+        asset_id: asset.id.clone(),
+        recipe_id: None,
+        parent_asset_id: Some(payload.source_asset_id.clone()),
+        params: GenerationParams {
+            provider: serde_json::to_value(payload.provider)?.as_str().map(String::from),
+            prompt: Some(payload.prompt.clone()),
+            extra,
+            ..Default::default()
+        },
+        created_at: now,
+    };
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    if let Some(node) = &node {
+        io_sqlite::upsert_node(&conn, node)?;
+    }
+    generation::save_record(&conn, &record)?;
+    
+    Ok(Some(EditResult { asset, node }))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn load_image(project_root: &Path, conn: &rusqlite::Connection, asset_id: &str) -> Result<DynamicImage, AppError> {
+    let asset = io_sqlite::get_asset(conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    let path = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())
+        .and_then(|src| imaging::resolve_image_source(project_root, src))
+        .filter(|p| p.is_file())
+        .ok_or_else(|| AppError::NotFound(format!("Image file of {} not found", asset_id)))?;
+    let data = std::fs::read(&path)?;
+    image::load_from_memory(&data)
+        .map_err(|e| AppError::Unknown(format!("Failed to decode image {}: {}", asset_id, e)))
+}
+
+/// The image to send and the area to edit (`true` = generate). A mask pixel
+/// marks the area when it is light and opaque, so both white-on-black and
+/// white-on-transparent brush masks work.
+fn prepare(source: &DynamicImage, mask: &DynamicImage, mode: EditMode) -> Result<(RgbaImage, Vec<bool>), AppError> {
+    let (width, height) = match mode {
+        EditMode::Inpaint => (source.width(), source.height()),
+        EditMode::Outpaint => (mask.width(), mask.height()),
+    };
+    if width < source.width() || height < source.height() {
+        return Err(AppError::Unknown(format!(
+            "Outpaint mask ({}x{}) must be at least the image size ({}x{})",
+            width, height, source.width(), source.height()
+        )));
+    }
+    
+    let mask = image::imageops::resize(&mask.to_rgba8(), width, height, image::imageops::FilterType::Nearest);
+    let offset_x = (width - source.width()) / 2;
+    let offset_y = (height - source.height()) / 2;
+    
+    let mut image = RgbaImage::new(width, height);
+    image::imageops::overlay(&mut image, &source.to_rgba8(), offset_x as i64, offset_y as i64);
+    
+    let edit_area = mask.enumerate_pixels()
+        .map(|(x, y, Rgba([r, g, b, a]))| {
+            let luma = (*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000;
+            let outside = x < offset_x || y < offset_y
+                || x >= offset_x + source.width() || y >= offset_y + source.height();
+            outside || (luma >= 128 && *a >= 128)
+        })
+        .collect();
+    
+    Ok((image, edit_area))
+}
+
+fn encode_mask(edit_area: &[bool], width_height: (u32, u32), provider: EditProvider) -> DynamicImage {
+    let (width, height) = width_height;
+    match provider {
+        EditProvider::OpenAI => DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            let edit = edit_area[(y * width + x) as usize];
+            Rgba([0, 0, 0, if edit { 0 } else { 255 }])
+        })),
+        EditProvider::ComfyUI => DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+            Luma([if edit_area[(y * width + x) as usize] { 255 } else { 0 }])
+        })),
+    }
+}
+
+fn png_data_url(image: &DynamicImage) -> Result<String, AppError> {
+    let mut data = Vec::new();
+    image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .map_err(|e| AppError::Unknown(format!("Failed to encode PNG: {}", e)))?;
+    Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(data)))
+}
+
+/// Accept `{ src }`, `{ data }` or a plain base64 string from the executor
+fn reported_image(project_root: &Path, result: &Value) -> Result<Vec<u8>, AppError> {
+    if let Some(path) = result.get("src").and_then(|v| v.as_str())
+        .and_then(|src| imaging::resolve_image_source(project_root, src))
+    {
+        return std::fs::read(&path).map_err(|e| AppError::Io(format!("Failed to read edited image: {}", e)));
+    }
+    let encoded = result.get("data").and_then(|v| v.as_str()).or(result.as_str())
+        .ok_or_else(|| AppError::Unknown("Provider returned no image".to_string()))?;
+    imaging::decode_base64_image(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use tempfile::tempdir;
+
+    fn image_asset(id: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": format!("assets/{}.png", id) }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        }
+    }
+
+    fn decode(data_url: &Value) -> DynamicImage {
+        image::load_from_memory(&imaging::decode_base64_image(data_url.as_str().unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_prepare_masks() {
+        let source = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, Rgba([200, 0, 0, 255])));
+        // Left half painted white on transparent, at twice the image size
+        let mask = DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 8, |x, _| {
+            if x < 4 { Rgba([255, 255, 255, 255]) } else { Rgba([0, 0, 0, 0]) }
+        }));
+        
+        let (image, area) = prepare(&source, &mask, EditMode::Inpaint).unwrap();
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(area.iter().filter(|e| **e).count(), 8);
+        assert!(area[0] && !area[3]);
+        
+        let (image, area) = prepare(&source, &mask, EditMode::Outpaint).unwrap();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(image.get_pixel(0, 0)[3], 0, "border is transparent");
+        assert_eq!(image.get_pixel(2, 2)[0], 200, "source is centered");
+        assert!(!area[8 * 2 + 5] && area[8 * 2 + 6], "masked inside, generated outside");
+        
+        let small = DynamicImage::ImageRgba8(RgbaImage::new(2, 2));
+        assert!(prepare(&source, &small, EditMode::Outpaint).is_err());
+    }
+
+    #[test]
+    fn test_edit_job() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Edits").unwrap();
+        for id in ["photo", "mask"] {
+            RgbaImage::from_pixel(6, 4, Rgba([255, 255, 255, 255]))
+                .save(root.join(format!("assets/{}.png", id)))
+                .unwrap();
+            project.assets.insert(id.to_string(), image_asset(id));
+        }
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let payload = EditPayload {
+            source_asset_id: "photo".to_string(),
+            mask_asset_id: "mask".to_string(),
+            prompt: "a hat".to_string(),
+            mode: EditMode::Inpaint,
+            provider: EditProvider::OpenAI,
+        };
+        let task = build_task(root, &payload).unwrap();
+        assert_eq!((task["width"].as_u64(), task["height"].as_u64()), (Some(6), Some(4)));
+        assert_eq!(decode(&task["mask"]).to_rgba8().get_pixel(0, 0)[3], 0, "OpenAI edits transparent pixels");
+        
+        let mut job = Job::new(JOB_KIND, serde_json::to_value(&payload).unwrap(), vec![task.clone()], 1);
+        job.tasks[0].status = TaskStatus::Success;
+        job.tasks[0].result = Some(json!({ "data": task["image"] }));
+        
+        let result = finalize_edit(root, &job).unwrap().unwrap();
+        let provenance = &result.asset.config.as_ref().unwrap()["extra"]["provenance"];
+        assert_eq!(provenance["maskAssetId"], "mask");
+        assert_eq!(provenance["prompt"], "a hat");
+        assert_eq!(result.asset.value["width"], 6);
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        assert_eq!(generation::get_variations(&conn, "photo").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,313 @@
+//! Depth maps and segmentation masks of image assets, for ControlNet-style
+//! conditioning downstream.
+//!
+//! Maps are made by local ONNX models run through an external runner
+//! (`SYNNIA_MAP_RUNNER`, models in `SYNNIA_DEPTH_MODEL` and
+//! `SYNNIA_SEGMENT_MODEL`, or else the installed depth and segmentation
+//! models) on the local worker pool. Each map becomes a new
+//! image asset beside the source, with `config.extra.provenance` linking back.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::error::AppError;
+use crate::models::{Asset, Position, SynniaNode, SynniaNodeData};
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::model_manager::ModelKind;
+use crate::services::{database, imaging, io_sqlite, model_manager};
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "image-maps";
+
+const NODE_GAP: f64 = 40.0;
+
+/// Kind of map to derive from an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum MapKind {
+    /// Grayscale depth, near is light
+    Depth,
+    /// Per-object color mask
+    Segmentation,
+}
+
+impl MapKind {
+    fn label(self) -> &'static str {
+        match self {
+            MapKind::Depth => "depth",
+            MapKind::Segmentation => "segmentation",
+        }
+    }
+
+    fn model_env(self) -> &'static str {
+        match self {
+            MapKind::Depth => "SYNNIA_DEPTH_MODEL",
+            MapKind::Segmentation => "SYNNIA_SEGMENT_MODEL",
+        }
+    }
+
+    fn model_kind(self) -> ModelKind {
+        match self {
+            MapKind::Depth => ModelKind::Depth,
+            MapKind::Segmentation => ModelKind::Segmentation,
+        }
+    }
+}
+
+/// Parameters of one map task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapTask {
+    pub kind: MapKind,
+    /// Image file relative to the project root
+    pub src: String,
+}
+
+/// Job payload for a map run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapsPayload {
+    pub asset_id: String,
+    pub node_id: Option<String>,
+}
+
+/// Assets and nodes produced by a finished map job
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapsResult {
+    pub assets: Vec<Asset>,
+    pub nodes: Vec<SynniaNode>,
+}
+
+/// Runner and per-kind models of the local map models
+#[derive(Debug, Clone)]
+pub struct MapRunner {
+    runner: PathBuf,
+    models: Vec<(MapKind, PathBuf)>,
+}
+
+impl MapRunner {
+    /// The configured runner, if any, with the models of `kinds`. Errors
+    /// name the first missing piece.
+    pub fn find(kinds: &[MapKind]) -> Result<Self, AppError> {
+        let runner = std::env::var_os("SYNNIA_MAP_RUNNER")
+            .map(PathBuf::from)
+            .filter(|p| p.is_file())
+            .ok_or_else(|| AppError::NotFound("Map model runner not found; set SYNNIA_MAP_RUNNER".to_string()))?;
+        let models = kinds.iter()
+            .map(|kind| {
+                std::env::var_os(kind.model_env())
+                    .map(PathBuf::from)
+                    .or_else(|| model_manager::installed(kind.model_kind()))
+                    .filter(|p| p.is_file())
+                    .map(|model| (*kind, model))
+                    .ok_or_else(|| AppError::NotFound(format!(
+                        "No {} model found; download one or set {}", kind.label(), kind.model_env()
+                    )))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(MapRunner { runner, models })
+    }
+
+    /// Make one map; returns its relative path. The error is the message
+    /// shown for the task.
+    pub fn run(&self, project_root: &Path, task: &MapTask) -> Result<String, String> {
+        let model = self.models.iter()
+            .find(|(kind, _)| *kind == task.kind)
+            .map(|(_, model)| model)
+            .ok_or_else(|| format!("No {} model configured", task.kind.label()))?;
+        
+        let relative_path = format!("assets/{}.png", uuid::Uuid::new_v4());
+        let target = project_root.join(&relative_path);
+        let output = Command::new(&self.runner)
+            .arg("-i").arg(project_root.join(&task.src))
+            .arg("-o").arg(&target)
+            .arg("-m").arg(model)
+            .args(["-t", task.kind.label()])
+            .stdout(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run map model: {}", e))?;
+        if !output.status.success() || !target.is_file() {
+            let _ = std::fs::remove_file(&target);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Map model failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+        }
+        Ok(relative_path)
+    }
+}
+
+/// One task per requested kind (duplicates dropped), checking the asset is
+/// an image file in the project.
+pub fn build_tasks(conn: &rusqlite::Connection, project_root: &Path, asset_id: &str, kinds: &[MapKind]) -> Result<Vec<MapTask>, AppError> {
+    let asset = io_sqlite::get_asset(conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    let path = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())
+        .and_then(|src| imaging::resolve_image_source(project_root, src))
+        .filter(|p| p.is_file())
+        .ok_or_else(|| AppError::NotFound(format!("Image file of {} not found", asset_id)))?;
+    let src = path.strip_prefix(project_root)
+        .map_err(|e| AppError::Unknown(e.to_string()))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    
+    let mut tasks: Vec<MapTask> = Vec::new();
+    for kind in kinds {
+        if !tasks.iter().any(|t| t.kind == *kind) {
+            tasks.push(MapTask { kind: *kind, src: src.clone() });
+        }
+    }
+    if tasks.is_empty() {
+        return Err(AppError::Unknown("No map kinds requested".to_string()));
+    }
+    Ok(tasks)
+}
+
+/// Turn every map of a finished job into an image asset linked to the
+/// source, stacked beside the source node when one was given.
+pub fn finalize_maps(project_root: &Path, job: &Job) -> Result<MapsResult, AppError> {
+    let payload: MapsPayload = serde_json::from_value(job.payload.clone())?;
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let source = io_sqlite::get_asset(&conn, &payload.asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.asset_id)))?;
+    let source_node = match payload.node_id.as_deref() {
+        Some(node_id) => io_sqlite::get_node(&conn, node_id)?,
+        None => None,
+    };
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut assets = Vec::new();
+    let mut nodes = Vec::new();
+    
+    for task in &job.tasks {
+        if task.status != TaskStatus::Success {
+            continue;
+        }
+        let Ok(map_task) = serde_json::from_value::<MapTask>(task.params.clone()) else { continue };
+        let Some(relative_path) = task.result.as_ref().and_then(|v| v.as_str()) else { continue };
+        let data = std::fs::read(project_root.join(relative_path))?;
+        let (width, height) = imaging::get_image_dimensions(&data)?;
+        let file_id = Path::new(relative_path).file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let preview = imaging::generate_thumbnail(project_root, &file_id, &data).ok();
+        
+        let mut asset = source.clone();
+        asset.id = uuid::Uuid::new_v4().to_string();
+        asset.value = json!({ "src": relative_path, "width": width, "height": height });
+        asset.value_meta = Some(json!({ "width": width, "height": height, "preview": preview }));
+        asset.config = Some(json!({
+            "schema": [],
+            "extra": {
+                "provenance": {
+                    "kind": format!("{}-map", map_task.kind.label()),
+                    "sourceAssetId": source.id,
+                    "generatedAt": now,
+                }
+            }
+        }));
+        asset.sys.name = format!("{} ({})", source.sys.name, map_task.kind.label());
+        asset.sys.created_at = now;
+        asset.sys.updated_at = now;
+        asset.sys.source = "ai".to_string();
+        
+        if let Some(source_node) = &source_node {
+            let row = nodes.len() as f64;
+            let node = SynniaNode {
+                id: uuid::Uuid::new_v4().to_string(),
+                type_: source_node.type_.clone(),
+                position: Position {
+                    x: source_node.position.x + source_node.width.unwrap_or(300.0) + NODE_GAP,
+                    y: source_node.position.y + row * (source_node.height.unwrap_or(300.0) + NODE_GAP),
+                },
+                width: source_node.width,
+                height: source_node.height,
+                parent_id: source_node.parent_id.clone(),
+                extent: source_node.extent.clone(),
+                style: None,
+                data: SynniaNodeData {
+                    title: asset.sys.name.clone(),
+                    asset_id: Some(asset.id.clone()),
+                    ..Default::default()
+                },
+            };
+            io_sqlite::upsert_node(&conn, &node)?;
+            nodes.push(node);
+        }
+        
+        io_sqlite::save_asset_with_history(project_root, &asset)?;
+        assets.push(asset);
+    }
+    
+    Ok(MapsResult { assets, nodes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use tempfile::tempdir;
+
+    fn image_node(id: &str, asset_id: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: Some(200.0),
+            height: Some(100.0),
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: Some(asset_id.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_finalize_maps() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Scene").unwrap();
+        for file in ["room.png", "depth.png", "segments.png"] {
+            image::RgbImage::new(8, 4).save(root.join("assets").join(file)).unwrap();
+        }
+        project.assets.insert("room".to_string(), Asset {
+            id: "room".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/room.png" }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Room".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        });
+        project.graph.nodes.push(image_node("n1", "room"));
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let tasks = build_tasks(&conn, root, "room", &[MapKind::Depth, MapKind::Segmentation, MapKind::Depth]).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(build_tasks(&conn, root, "room", &[]).is_err());
+        
+        let payload = MapsPayload { asset_id: "room".to_string(), node_id: Some("n1".to_string()) };
+        let params = tasks.iter().map(|t| serde_json::to_value(t).unwrap()).collect();
+        let mut job = Job::new(JOB_KIND, serde_json::to_value(&payload).unwrap(), params, 1);
+        for (task, file) in job.tasks.iter_mut().zip(["assets/depth.png", "assets/segments.png"]) {
+            task.status = TaskStatus::Success;
+            task.result = Some(json!(file));
+        }
+        
+        let result = finalize_maps(root, &job).unwrap();
+        assert_eq!(result.assets.len(), 2);
+        assert_eq!(result.assets[0].config.as_ref().unwrap()["extra"]["provenance"]["kind"], "depth-map");
+        assert_eq!(result.assets[1].sys.name, "Room (segmentation)");
+        assert_eq!(result.assets[0].value["width"], 8);
+        assert_eq!(result.nodes[1].position.x, 240.0);
+        assert_eq!(result.nodes[1].position.y, 140.0);
+    }
+}
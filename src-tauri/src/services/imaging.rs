@@ -0,0 +1,439 @@
+//! Shared image helpers: decoding, dimension probing, format detection,
+//! thumbnail, size tier and animated preview generation and palette
+//! extraction for files in the project assets folder.
+//!
+//! Each imported image `assets/<stem>.<ext>` gets derivatives next to it:
+//! `thumb_<stem>.jpg` (preview tier) and, for large images,
+//! `medium_<stem>.jpg|png` (medium tier). Large videos may have a
+//! `proxy_<stem>.mp4` serving both lower tiers. The file server picks one
+//! via `?tier=`.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use base64::Engine;
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Delay, DynamicImage, Frame, Frames, ImageReader};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Project settings key holding the thumbnail settings
+pub const THUMBNAIL_SETTINGS_KEY: &str = "thumbnails";
+
+/// Longest side of the medium tier; smaller images get none
+pub const MEDIUM_TIER_SIZE: u32 = 1024;
+
+/// Size tier requested from the file server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageTier {
+    /// The thumbnail
+    Preview,
+    /// Screen-sized copy for the canvas
+    Medium,
+    /// The original
+    #[default]
+    Full,
+}
+
+/// One dominant color of an image
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteColor {
+    pub hex: String,
+    /// Fraction of pixels (0..1)
+    pub share: f64,
+}
+
+/// Size and JPEG quality of generated thumbnails, configured per project
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThumbnailSettings {
+    /// Longest side in pixels
+    pub size: u32,
+    /// JPEG quality, 1..=100
+    pub quality: u8,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self { size: 200, quality: 75 }
+    }
+}
+
+impl ThumbnailSettings {
+    /// Settings of the project at `project_root`, or the defaults if none
+    /// are stored (or there is no project database yet).
+    pub fn load(project_root: &Path) -> Self {
+        let db_path = io_sqlite::get_db_path(project_root);
+        if !db_path.exists() {
+            return Self::default();
+        }
+        database::open_db(&db_path).ok()
+            .and_then(|conn| io_sqlite::get_setting(&conn, THUMBNAIL_SETTINGS_KEY).ok().flatten())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+    
+    /// Clamp to usable values
+    pub fn normalized(self) -> Self {
+        Self { size: self.size.clamp(32, 2048), quality: self.quality.clamp(1, 100) }
+    }
+}
+
+/// Frame count and timing of an animated GIF/WebP, kept in the asset's
+/// `valueMeta.animation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageAnimation {
+    pub frame_count: u32,
+    /// Length of one loop
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// Short looping preview (e.g. "assets/preview_xxx.gif")
+    pub preview: Option<String>,
+}
+
+/// Decode base64 image data, handling data URI prefix
+pub fn decode_base64_image(data: &str) -> Result<Vec<u8>, AppError> {
+    let base64_str = if data.contains(",") {
+        // Data URI format: "data:image/png;base64,xxxxx"
+        data.split(",").nth(1).unwrap_or(data)
+    } else {
+        data
+    };
+    
+    base64::engine::general_purpose::STANDARD
+        .decode(base64_str)
+        .map_err(|e| AppError::Unknown(format!("Failed to decode base64: {}", e)))
+}
+
+/// Get image dimensions from raw bytes
+pub fn get_image_dimensions(data: &[u8]) -> Result<(u32, u32), AppError> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| AppError::Unknown(format!("Failed to read image: {}", e)))?;
+    
+    let dimensions = reader.into_dimensions()
+        .map_err(|e| AppError::Unknown(format!("Failed to get image dimensions: {}", e)))?;
+    
+    Ok(dimensions)
+}
+
+/// Detect image format from raw bytes
+pub fn detect_image_format(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+        Some("png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.starts_with(b"GIF") {
+        Some("gif")
+    } else if data.starts_with(b"RIFF") && data.len() > 12 && &data[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+/// Files to try for `filename` (relative to `assets/`) at `tier`, best
+/// match first; missing tiers fall back to the next larger one.
+pub fn tier_candidates(filename: &str, tier: ImageTier) -> Vec<String> {
+    let (dir, name) = match filename.rsplit_once('/') {
+        Some((dir, name)) => (format!("{}/", dir), name),
+        None => (String::new(), filename),
+    };
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let medium = [format!("{}medium_{}.jpg", dir, stem), format!("{}medium_{}.png", dir, stem)];
+    
+    let mut candidates = Vec::new();
+    if tier == ImageTier::Preview {
+        candidates.push(format!("{}thumb_{}.jpg", dir, stem));
+    }
+    if tier != ImageTier::Full {
+        candidates.extend(medium);
+        candidates.push(format!("{}proxy_{}.mp4", dir, stem));
+    }
+    candidates.push(filename.to_string());
+    candidates
+}
+
+/// Generate a thumbnail for an image, using the project's thumbnail settings
+pub fn generate_thumbnail(project_root: &Path, file_id: &str, image_data: &[u8]) -> Result<String, AppError> {
+    generate_thumbnail_with(project_root, file_id, image_data, &ThumbnailSettings::load(project_root))
+}
+
+/// Generate a thumbnail (`assets/thumb_<file_id>.jpg`) with explicit
+/// settings, plus the medium tier for images larger than it
+pub fn generate_thumbnail_with(
+    project_root: &Path,
+    file_id: &str,
+    image_data: &[u8],
+    settings: &ThumbnailSettings,
+) -> Result<String, AppError> {
+    let settings = settings.normalized();
+    let img = image::load_from_memory(image_data)
+        .map_err(|e| AppError::Unknown(format!("Failed to load image for thumbnail: {}", e)))?;
+    
+    // Medium tier first, so the thumbnail can be scaled from it
+    let source = if img.width().max(img.height()) > MEDIUM_TIER_SIZE {
+        let medium = img.resize(MEDIUM_TIER_SIZE, MEDIUM_TIER_SIZE, image::imageops::FilterType::Triangle);
+        save_medium_tier(project_root, file_id, &medium)?;
+        medium
+    } else {
+        img
+    };
+    
+    // Resize to thumbnail (preserving aspect ratio)
+    let thumbnail = source.thumbnail(settings.size, settings.size);
+    
+    // Save thumbnail as JPEG (smaller file size); JPEG has no alpha channel
+    let thumb_filename = format!("thumb_{}.jpg", file_id);
+    let thumb_relative = format!("assets/{}", thumb_filename);
+    let thumb_path = project_root.join(&thumb_relative);
+    
+    let file = std::fs::File::create(&thumb_path)
+        .map_err(|e| AppError::Io(format!("Failed to create thumbnail: {}", e)))?;
+    JpegEncoder::new_with_quality(std::io::BufWriter::new(file), settings.quality)
+        .encode_image(&DynamicImage::ImageRgb8(thumbnail.to_rgb8()))
+        .map_err(|e| AppError::Unknown(format!("Failed to save thumbnail: {}", e)))?;
+    
+    Ok(thumb_relative)
+}
+
+/// Probe an animated GIF/WebP and write a small looping GIF preview of its
+/// first seconds next to the thumbnail. Returns `None` for still images.
+/// (The regular thumbnail already shows the first frame.)
+pub fn generate_animation_preview(project_root: &Path, file_id: &str, image_data: &[u8]) -> Result<Option<ImageAnimation>, AppError> {
+    const PREVIEW_SIZE: u32 = 200;
+    const MAX_PREVIEW_FRAMES: usize = 60;
+    const MAX_PREVIEW_MS: u64 = 5000;
+    
+    let Some(frames) = animation_frames(image_data)? else { return Ok(None) };
+    
+    let mut frame_count = 0u32;
+    let mut duration_ms = 0u64;
+    let mut preview = Vec::new();
+    for frame in frames {
+        let frame = frame.map_err(|e| AppError::Unknown(format!("Failed to decode frame: {}", e)))?;
+        let delay = frame_delay_ms(frame.delay());
+        if preview.len() < MAX_PREVIEW_FRAMES && duration_ms < MAX_PREVIEW_MS {
+            let small = DynamicImage::ImageRgba8(frame.into_buffer()).thumbnail(PREVIEW_SIZE, PREVIEW_SIZE).to_rgba8();
+            preview.push(Frame::from_parts(small, 0, 0, Delay::from_numer_denom_ms(delay as u32, 1)));
+        }
+        frame_count += 1;
+        duration_ms += delay;
+    }
+    if frame_count < 2 {
+        return Ok(None);
+    }
+    
+    let preview_relative = format!("assets/preview_{}.gif", file_id);
+    let file = std::fs::File::create(project_root.join(&preview_relative))
+        .map_err(|e| AppError::Io(format!("Failed to create preview: {}", e)))?;
+    let mut encoder = GifEncoder::new_with_speed(std::io::BufWriter::new(file), 10);
+    encoder.set_repeat(Repeat::Infinite)
+        .and_then(|_| encoder.encode_frames(preview))
+        .map_err(|e| AppError::Unknown(format!("Failed to save preview: {}", e)))?;
+    
+    Ok(Some(ImageAnimation { frame_count, duration_ms, preview: Some(preview_relative) }))
+}
+
+/// Resolve an image asset `src` to a file in the project: relative paths
+/// ("assets/a.png") and URLs served by the local file server
+/// ("http://127.0.0.1:port/assets/a.png"). Remote URLs and paths escaping
+/// the project return `None`.
+pub fn resolve_image_source(project_root: &Path, src: &str) -> Option<PathBuf> {
+    let path = if let Some(rest) = src.strip_prefix("http://").or_else(|| src.strip_prefix("https://")) {
+        let (host, path) = rest.split_once('/')?;
+        let host = host.rsplit_once(':').map(|(h, _)| h).unwrap_or(host);
+        if host != "127.0.0.1" && host != "localhost" {
+            return None;
+        }
+        path
+    } else if src.contains(':') {
+        return None; // data:, blob:, other schemes
+    } else {
+        src
+    };
+    
+    let path = path.split(['?', '#']).next().unwrap_or(path).trim_start_matches('/');
+    let relative = Path::new(path);
+    if path.is_empty() || relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    
+    Some(project_root.join(relative))
+}
+
+/// Dominant colors, most common first. Colors are bucketed at 4 bits per
+/// channel on a downscaled copy; buckets under 1% are dropped.
+pub fn extract_palette(img: &DynamicImage, max_colors: usize) -> Vec<PaletteColor> {
+    const SAMPLE_SIZE: u32 = 64;
+    const MIN_SHARE: f64 = 0.01;
+    
+    let sample = if img.width() > SAMPLE_SIZE || img.height() > SAMPLE_SIZE {
+        img.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgba8()
+    } else {
+        img.to_rgba8()
+    };
+    let mut buckets: HashMap<u16, (u64, [u64; 3])> = HashMap::new();
+    let mut total = 0u64;
+    
+    for pixel in sample.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a < 128 {
+            continue; // Transparent pixels are not part of the palette
+        }
+        let key = ((r as u16 >> 4) << 8) | ((g as u16 >> 4) << 4) | (b as u16 >> 4);
+        let entry = buckets.entry(key).or_insert((0, [0; 3]));
+        entry.0 += 1;
+        entry.1[0] += r as u64;
+        entry.1[1] += g as u64;
+        entry.1[2] += b as u64;
+        total += 1;
+    }
+    if total == 0 {
+        return Vec::new();
+    }
+    
+    let mut colors: Vec<(u64, [u64; 3])> = buckets.into_values().collect();
+    colors.sort_by_key(|c| std::cmp::Reverse(c.0));
+    
+    colors.into_iter()
+        .map(|(count, sum)| PaletteColor {
+            hex: format!("#{:02x}{:02x}{:02x}", sum[0] / count, sum[1] / count, sum[2] / count),
+            share: count as f64 / total as f64,
+        })
+        .filter(|c| c.share >= MIN_SHARE)
+        .take(max_colors)
+        .collect()
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// JPEG unless the image has transparency to keep
+fn save_medium_tier(project_root: &Path, file_id: &str, medium: &DynamicImage) -> Result<(), AppError> {
+    let assets_dir = project_root.join("assets");
+    let (keep, stale) = if medium.color().has_alpha() {
+        (format!("medium_{}.png", file_id), format!("medium_{}.jpg", file_id))
+    } else {
+        (format!("medium_{}.jpg", file_id), format!("medium_{}.png", file_id))
+    };
+    let _ = std::fs::remove_file(assets_dir.join(stale));
+    
+    let result = if medium.color().has_alpha() {
+        medium.save(assets_dir.join(&keep))
+    } else {
+        let file = std::fs::File::create(assets_dir.join(&keep))
+            .map_err(|e| AppError::Io(format!("Failed to create medium tier: {}", e)))?;
+        JpegEncoder::new_with_quality(std::io::BufWriter::new(file), 85)
+            .encode_image(&DynamicImage::ImageRgb8(medium.to_rgb8()))
+    };
+    result.map_err(|e| AppError::Unknown(format!("Failed to save medium tier: {}", e)))
+}
+
+/// Frame iterator for GIF and animated WebP data; `None` for other formats
+fn animation_frames(data: &[u8]) -> Result<Option<Frames<'_>>, AppError> {
+    let decode_error = |e: image::ImageError| AppError::Unknown(format!("Failed to read animation: {}", e));
+    match detect_image_format(data) {
+        Some("gif") => Ok(Some(GifDecoder::new(Cursor::new(data)).map_err(decode_error)?.into_frames())),
+        Some("webp") => {
+            let decoder = WebPDecoder::new(Cursor::new(data)).map_err(decode_error)?;
+            Ok(decoder.has_animation().then(|| decoder.into_frames()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Browsers play GIF delays under 20ms at 100ms; match them
+fn frame_delay_ms(delay: Delay) -> u64 {
+    let (numer, denom) = delay.numer_denom_ms();
+    let ms = numer as u64 / denom.max(1) as u64;
+    if ms < 20 { 100 } else { ms }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_image_source() {
+        let root = Path::new("/project");
+        assert_eq!(resolve_image_source(root, "assets/a.png"), Some(root.join("assets/a.png")));
+        assert_eq!(resolve_image_source(root, "http://127.0.0.1:5173/assets/a.png?v=2"), Some(root.join("assets/a.png")));
+        assert_eq!(resolve_image_source(root, "https://example.com/a.png"), None);
+        assert_eq!(resolve_image_source(root, "data:image/png;base64,xx"), None);
+        assert_eq!(resolve_image_source(root, "../secret.png"), None);
+    }
+
+    #[test]
+    fn test_tier_candidates() {
+        assert_eq!(tier_candidates("a.png", ImageTier::Full), vec!["a.png"]);
+        assert_eq!(
+            tier_candidates("sub/a.b.png", ImageTier::Preview),
+            vec!["sub/thumb_a.b.jpg", "sub/medium_a.b.jpg", "sub/medium_a.b.png", "sub/proxy_a.b.mp4", "sub/a.b.png"],
+        );
+        
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(image::RgbImage::new(3000, 1500))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        generate_thumbnail_with(dir.path(), "big", &png, &ThumbnailSettings::default()).unwrap();
+        let medium = std::fs::read(dir.path().join("assets/medium_big.jpg")).unwrap();
+        assert_eq!(get_image_dimensions(&medium).unwrap(), (1024, 512));
+    }
+
+    #[test]
+    fn test_extract_palette() {
+        let img = RgbaImage::from_fn(10, 10, |x, _| {
+            if x < 7 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) }
+        });
+        let palette = extract_palette(&DynamicImage::ImageRgba8(img), 5);
+        
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette[0].hex, "#ff0000");
+        assert!((palette[0].share - 0.7).abs() < 0.05);
+        assert_eq!(palette[1].hex, "#0000ff");
+    }
+
+    #[test]
+    fn test_generate_animation_preview() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            let frames = (0..3u8).map(|i| {
+                let img = RgbaImage::from_pixel(400, 300, Rgba([i * 80, 0, 0, 255]));
+                Frame::from_parts(img, 0, 0, Delay::from_numer_denom_ms(250, 1))
+            });
+            encoder.encode_frames(frames).unwrap();
+        }
+        
+        let animation = generate_animation_preview(dir.path(), "anim", &gif).unwrap().unwrap();
+        assert_eq!((animation.frame_count, animation.duration_ms), (3, 750));
+        let preview = std::fs::read(dir.path().join(animation.preview.unwrap())).unwrap();
+        assert_eq!(get_image_dimensions(&preview).unwrap(), (200, 150));
+        
+        // Still images have no animation
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::new(4, 4))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        assert!(generate_animation_preview(dir.path(), "still", &png).unwrap().is_none());
+    }
+}
@@ -0,0 +1,227 @@
+//! Prompt-from-image (CLIP interrogation): describe an image asset as a
+//! reusable prompt and tag list.
+//!
+//! The "local" provider runs a CLIP interrogator through an external runner
+//! (`SYNNIA_INTERROGATOR`, model in `SYNNIA_CLIP_MODEL`) that prints JSON;
+//! any other provider is dispatched to the frontend. Either reports
+//! `{ prompt, tags }` (tags as strings or `{ tag, confidence }`) or a plain
+//! caption. The result lands in the asset's `valueMeta.prompt` and
+//! `valueMeta.tags` and in the tag index.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::AssetTag;
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{database, imaging, io_sqlite, tags};
+use crate::services::tags::ScoredTag;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "interrogate";
+
+/// Provider name of the local CLIP interrogator
+pub const LOCAL_PROVIDER: &str = "local";
+
+/// Tag source in the tag index
+pub const TAG_SOURCE: &str = "interrogate";
+
+/// Job payload for an interrogation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterrogatePayload {
+    pub asset_id: String,
+    pub provider: String,
+}
+
+/// Prompt and tags stored for an asset
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interrogation {
+    pub asset_id: String,
+    pub prompt: String,
+    pub tags: Vec<AssetTag>,
+}
+
+/// External runner and the CLIP model it loads
+#[derive(Debug, Clone)]
+pub struct LocalInterrogator {
+    runner: PathBuf,
+    model: PathBuf,
+}
+
+impl LocalInterrogator {
+    /// The local interrogator, if a runner and model are configured.
+    pub fn find() -> Option<Self> {
+        let runner = PathBuf::from(std::env::var_os("SYNNIA_INTERROGATOR")?);
+        let model = PathBuf::from(std::env::var_os("SYNNIA_CLIP_MODEL")?);
+        (runner.is_file() && model.is_file()).then_some(LocalInterrogator { runner, model })
+    }
+
+    /// Describe the task's image; returns the runner's JSON output. The
+    /// error is the message shown for the task.
+    pub fn run(&self, project_root: &Path, task: &Value) -> Result<Value, String> {
+        let src = task.get("src").and_then(|v| v.as_str()).ok_or("Task has no source image")?;
+        let output = Command::new(&self.runner)
+            .arg("-i").arg(project_root.join(src))
+            .arg("-m").arg(&self.model)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run interrogator: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Interrogator failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(serde_json::from_str(&stdout).unwrap_or_else(|_| Value::String(stdout.trim().to_string())))
+    }
+}
+
+/// Build the single task of an interrogation, checking the asset is an
+/// image file in the project.
+pub fn build_task(conn: &Connection, project_root: &Path, payload: &InterrogatePayload) -> Result<Value, AppError> {
+    let asset = io_sqlite::get_asset(conn, &payload.asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.asset_id)))?;
+    let path = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())
+        .and_then(|src| imaging::resolve_image_source(project_root, src))
+        .filter(|p| p.is_file())
+        .ok_or_else(|| AppError::NotFound(format!("Image file of {} not found", payload.asset_id)))?;
+    let src = path.strip_prefix(project_root)
+        .map_err(|e| AppError::Unknown(e.to_string()))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    
+    Ok(json!({
+        "assetId": payload.asset_id,
+        "src": src,
+        "provider": payload.provider,
+    }))
+}
+
+/// Store the prompt and tags of a finished interrogation.
+pub fn finalize_interrogation(project_root: &Path, job: &Job) -> Result<Option<Interrogation>, AppError> {
+    let payload: InterrogatePayload = serde_json::from_value(job.payload.clone())?;
+    
+    let result = match job.tasks.first() {
+        Some(task) if task.status == TaskStatus::Success => task.result.clone().unwrap_or(Value::Null),
+        _ => return Ok(None),
+    };
+    let Some((prompt, found)) = parse_result(&result) else {
+        return Err(AppError::Unknown("Interrogator returned no description".to_string()));
+    };
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let stored = tags::set_tags(&conn, &payload.asset_id, TAG_SOURCE, &found)?;
+    
+    let names: Vec<&str> = stored.iter()
+        .filter(|t| t.source == TAG_SOURCE)
+        .map(|t| t.tag.as_str())
+        .collect();
+    conn.execute(
+        "UPDATE assets SET value_meta_json = json_set(COALESCE(value_meta_json, '{}'), '$.prompt', ?1, '$.tags', json(?2))
+         WHERE id = ?3",
+        params![prompt, serde_json::to_string(&names)?, payload.asset_id],
+    ).map_err(|e| AppError::Io(format!("Failed to update asset tags: {}", e)))?;
+    
+    Ok(Some(Interrogation { asset_id: payload.asset_id, prompt, tags: stored }))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Prompt and tags from `{ prompt, tags }`, `{ caption }` or a plain string.
+/// Without explicit tags, the prompt's comma-separated phrases are used.
+pub(crate) fn parse_result(result: &Value) -> Option<(String, Vec<ScoredTag>)> {
+    let prompt = match result {
+        Value::String(s) => s.clone(),
+        Value::Object(obj) => obj.get("prompt")
+            .or_else(|| obj.get("caption"))
+            .or_else(|| obj.get("text"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => return None,
+    };
+    let prompt = prompt.trim().to_string();
+    
+    let mut found: Vec<ScoredTag> = result.get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| tags.iter().filter_map(|tag| match tag {
+            Value::String(s) => Some((s.clone(), None)),
+            Value::Object(obj) => Some((
+                obj.get("tag").or_else(|| obj.get("label"))?.as_str()?.to_string(),
+                obj.get("confidence").or_else(|| obj.get("score")).and_then(|v| v.as_f64()),
+            )),
+            _ => None,
+        }).collect())
+        .unwrap_or_default();
+    if found.is_empty() {
+        found = prompt.split(',').map(|p| (p.to_string(), None)).collect();
+    }
+    
+    let prompt = if prompt.is_empty() {
+        found.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>().join(", ")
+    } else {
+        prompt
+    };
+    (!prompt.is_empty()).then_some((prompt, found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_result() {
+        let (prompt, found) = parse_result(&json!("a red car at night, neon lights, 35mm")).unwrap();
+        assert_eq!(prompt, "a red car at night, neon lights, 35mm");
+        assert_eq!(found.len(), 3);
+        
+        let (prompt, found) = parse_result(&json!({ "tags": [{ "label": "cat", "score": 0.9 }, "sofa"] })).unwrap();
+        assert_eq!(prompt, "cat, sofa");
+        assert_eq!(found[0], ("cat".to_string(), Some(0.9)));
+        
+        assert!(parse_result(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_finalize_interrogation() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Refs").unwrap();
+        image::RgbImage::new(4, 4).save(root.join("assets/ref.png")).unwrap();
+        project.assets.insert("ref".to_string(), Asset {
+            id: "ref".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/ref.png" }),
+            value_meta: Some(json!({ "width": 4, "height": 4 })),
+            config: None,
+            sys: AssetSysMetadata { name: "Ref".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        });
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let payload = InterrogatePayload { asset_id: "ref".to_string(), provider: "openai".to_string() };
+        let task = build_task(&conn, root, &payload).unwrap();
+        assert_eq!(task["src"], "assets/ref.png");
+        
+        let mut job = Job::new(JOB_KIND, serde_json::to_value(&payload).unwrap(), vec![task], 1);
+        job.tasks[0].status = TaskStatus::Success;
+        job.tasks[0].result = Some(json!({ "prompt": "Portrait, Soft Light", "tags": ["portrait", "soft light"] }));
+        
+        let result = finalize_interrogation(root, &job).unwrap().unwrap();
+        assert_eq!(result.tags.len(), 2);
+        
+        let meta = io_sqlite::get_asset(&conn, "ref").unwrap().unwrap().value_meta.unwrap();
+        assert_eq!(meta["prompt"], "Portrait, Soft Light");
+        assert_eq!(meta["tags"], json!(["portrait", "soft light"]));
+        assert_eq!(meta["width"], 4);
+        assert_eq!(tags::search(&conn, "soft", &[], None, 10).unwrap(), vec!["ref"]);
+    }
+}
@@ -5,15 +5,17 @@
 
 use std::path::Path;
 use std::collections::HashMap;
-use rusqlite::{Connection, params, Result as SqliteResult};
+use std::time::{Duration, Instant};
+use rusqlite::{Connection, params, Result as SqliteResult, TransactionBehavior};
 use crate::models::{
     SynniaProject, ProjectMeta, Viewport, Graph, 
     SynniaNode, SynniaEdge, SynniaNodeData, Position, Asset, AssetSysMetadata, ValueType
 };
 use crate::error::AppError;
-use crate::services::database;
+use crate::services::{activity, canvases, database, file_history, references};
 use crate::services::hash::compute_content_hash;
 use crate::services::history;
+use crate::services::markdown;
 
 /// Database filename
 const DB_FILENAME: &str = "synnia.db";
@@ -80,39 +82,48 @@ pub fn init_project_sqlite(project_root: &Path, name: &str) -> Result<SynniaProj
         graph: Graph { nodes: vec![], edges: vec![] },
         assets: HashMap::new(),
         settings: None,
+        canvas_id: Some(canvases::DEFAULT_CANVAS_ID.to_string()),
     };
     
     Ok(project)
 }
 
-/// Load a project from SQLite storage.
+/// Load a project from SQLite storage, with the graph and viewport of the
+/// active (most recently opened) canvas.
 pub fn load_project_sqlite(project_root: &Path) -> Result<SynniaProject, AppError> {
+    load_project_staged(project_root, |_, _| {})
+}
+
+/// `load_project_sqlite`, calling `on_stage` with the time each stage took
+/// (`db_open`, `meta`, `nodes`, `edges`, `assets`, `settings`). Stages are
+/// also `tracing` spans.
+#[tracing::instrument(name = "load_project", skip_all, fields(root = %project_root.display()))]
+pub fn load_project_staged(
+    project_root: &Path,
+    mut on_stage: impl FnMut(&'static str, Duration),
+) -> Result<SynniaProject, AppError> {
     let db_path = get_db_path(project_root);
     
     if !db_path.exists() {
         return Err(AppError::NotFound("Project database not found".to_string()));
     }
     
-    let conn = database::open_db(&db_path)
-        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
-    
-    // Load project metadata
-    let meta = load_project_meta(&conn)?;
+    let conn = stage("db_open", &mut on_stage, || {
+        database::open_db(&db_path).map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+    })?;
     
-    // Load viewport
-    let viewport = load_viewport(&conn)?;
+    // Project metadata, active canvas and its viewport
+    let (meta, canvas_id, viewport) = stage("meta", &mut on_stage, || {
+        let meta = load_project_meta(&conn)?;
+        let canvas_id = canvases::active_canvas(&conn)?;
+        let viewport = load_viewport(&conn, &canvas_id)?;
+        Ok((meta, canvas_id, viewport))
+    })?;
     
-    // Load nodes
-    let nodes = load_nodes(&conn)?;
-    
-    // Load edges
-    let edges = load_edges(&conn)?;
-    
-    // Load assets
-    let assets = load_assets(&conn)?;
-    
-    // Load settings
-    let settings = load_settings(&conn)?;
+    let nodes = stage("nodes", &mut on_stage, || load_nodes(&conn, &canvas_id))?;
+    let edges = stage("edges", &mut on_stage, || load_edges(&conn, &canvas_id))?;
+    let assets = stage("assets", &mut on_stage, || load_assets(&conn))?;
+    let settings = stage("settings", &mut on_stage, || load_settings(&conn))?;
     
     let project = SynniaProject {
         version: "3.0.0".to_string(),
@@ -121,46 +132,59 @@ pub fn load_project_sqlite(project_root: &Path) -> Result<SynniaProject, AppErro
         graph: Graph { nodes, edges },
         assets,
         settings,
+        canvas_id: Some(canvas_id),
     };
     
     Ok(project)
 }
 
-/// Save a project to SQLite storage.
+/// Save a project to SQLite storage. The graph and viewport replace those of
+/// `project.canvas_id`; other canvases are kept. The canvas must be named:
+/// the active one may have changed since the graph was loaded.
+#[tracing::instrument(
+    name = "save_project",
+    skip_all,
+    fields(root = %project_root.display(), nodes = project.graph.nodes.len(), assets = project.assets.len()),
+)]
 pub fn save_project_sqlite(project_root: &Path, project: &SynniaProject) -> Result<(), AppError> {
     let db_path = get_db_path(project_root);
     
-    let conn = if db_path.exists() {
+    let mut conn = if db_path.exists() {
         database::open_db(&db_path)
     } else {
         database::init_db(&db_path)
     }.map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
     
-    // Use a transaction for atomicity
-    conn.execute("BEGIN TRANSACTION", [])
+    // Rolled back on error, and on panic when the guard is dropped. Taking
+    // the write lock up front means a save that reads before it writes can't
+    // fail with SQLITE_BUSY halfway through.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)
         .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
     
-    let result = (|| {
-        save_project_meta(&conn, &project.meta)?;
-        save_viewport(&conn, &project.viewport)?;
-        save_nodes(&conn, &project.graph.nodes)?;
-        save_edges(&conn, &project.graph.edges)?;
-        save_assets(&conn, &project.assets)?;
-        save_settings(&conn, &project.settings)?;
-        Ok::<(), AppError>(())
-    })();
-    
-    match result {
-        Ok(()) => {
-            conn.execute("COMMIT", [])
-                .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
-            Ok(())
-        }
-        Err(e) => {
-            let _ = conn.execute("ROLLBACK", []);
-            Err(e)
-        }
+    let canvas_id = require_canvas(project)?;
+    if canvases::get(&tx, canvas_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Canvas not found: {}", canvas_id)));
     }
+    let before = activity::GraphSnapshot::capture(&tx)?;
+    save_project_meta(&tx, &project.meta)?;
+    save_viewport(&tx, canvas_id, &project.viewport)?;
+    tracing::info_span!("nodes").in_scope(|| {
+        save_nodes(&tx, canvas_id, &project.graph.nodes)?;
+        references::normalize(&tx)
+    })?;
+    tracing::info_span!("edges").in_scope(|| save_edges(&tx, canvas_id, &project.graph.edges))?;
+    tracing::info_span!("assets").in_scope(|| save_assets(&tx, project_root, &project.assets))?;
+    save_settings(&tx, &project.settings)?;
+    tracing::info_span!("activity").in_scope(|| activity::record_project_changes(&tx, &before, project))?;
+    
+    tracing::info_span!("commit").in_scope(|| tx.commit())
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))
+}
+
+/// The canvas a project save targets
+pub fn require_canvas(project: &SynniaProject) -> Result<&str, AppError> {
+    project.canvas_id.as_deref()
+        .ok_or_else(|| AppError::InvalidInput("Project save has no canvasId".to_string()))
 }
 
 /// Save a single asset with version history.
@@ -169,42 +193,50 @@ pub fn save_asset_with_history(
     asset: &Asset,
 ) -> Result<bool, AppError> {
     let db_path = get_db_path(project_root);
-    let conn = database::open_db(&db_path)
+    let mut conn = database::open_db(&db_path)
         .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    // Snapshot, upsert and activity are applied together or not at all
+    let tx = conn.transaction()
+        .map_err(|e| AppError::Io(format!("Failed to begin transaction: {}", e)))?;
     
     let value_json = serde_json::to_string(&asset.value)?;
     let new_hash = compute_content_hash(&value_json);
     
     // Check if hash changed
-    let old_hash = history::get_current_hash(&conn, &asset.id)
+    let old_hash = history::get_current_hash(&tx, &asset.id)
         .map_err(|e| AppError::Io(format!("Failed to get current hash: {}", e)))?;
     
     let hash_changed = old_hash.as_ref() != Some(&new_hash);
+    let is_new = old_hash.is_none();
     
     // Create snapshot if hash changed
     if hash_changed {
         if let Some(old) = old_hash {
-            let old_value: Option<String> = conn.query_row(
+            let old_value: Option<String> = tx.query_row(
                 "SELECT value_json FROM assets WHERE id = ?1",
                 params![&asset.id],
                 |row| row.get(0),
             ).ok();
             
             if let Some(old_value) = old_value {
-                history::create_snapshot_if_changed(&conn, &asset.id, &old, &old_value)
+                history::create_snapshot_if_changed(&tx, &asset.id, &old, &old_value)
                     .map_err(|e| AppError::Io(format!("Failed to create snapshot: {}", e)))?;
+                // A swapped image file is kept apart from the value history
+                let old_value: serde_json::Value = serde_json::from_str(&old_value)?;
+                file_history::track_replacement(&tx, project_root, &asset.id, &old_value, &asset.value)?;
             }
         }
     }
     
     // Upsert asset
     let sys_json = serde_json::to_string(&asset.sys)?;
-    let value_meta_json = asset.value_meta.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
+    let value_meta = markdown::derive_value_meta(asset, Some(project_root));
+    let value_meta_json = value_meta.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
     let config_json = asset.config.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
     let value_type_str = serde_json::to_string(&asset.value_type)?;
     let now = chrono::Utc::now().timestamp_millis();
     
-    conn.execute(
+    tx.execute(
         "INSERT INTO assets (id, value_type, value_hash, value_json, value_meta_json, config_json, sys_json, updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
          ON CONFLICT(id) DO UPDATE SET
@@ -227,6 +259,13 @@ pub fn save_asset_with_history(
         ],
     ).map_err(|e| AppError::Io(format!("Failed to save asset: {}", e)))?;
     
+    if hash_changed {
+        activity::record(&tx, &activity::asset_change(asset, is_new))?;
+        references::notify_asset_updated(&tx, &asset.id)?;
+    }
+    
+    tx.commit()
+        .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
     Ok(hash_changed)
 }
 
@@ -234,7 +273,20 @@ pub fn save_asset_with_history(
 // Private helper functions
 // ============================================
 
-fn load_project_meta(conn: &Connection) -> Result<ProjectMeta, AppError> {
+/// Run one stage of a load inside a span and report how long it took
+fn stage<T>(
+    name: &'static str,
+    on_stage: &mut impl FnMut(&'static str, Duration),
+    run: impl FnOnce() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let _span = tracing::info_span!("stage", name).entered();
+    let start = Instant::now();
+    let result = run();
+    on_stage(name, start.elapsed());
+    result
+}
+
+pub fn load_project_meta(conn: &Connection) -> Result<ProjectMeta, AppError> {
     let mut stmt = conn.prepare(
         "SELECT id, name, description, author, thumbnail, created_at, updated_at 
          FROM project_meta LIMIT 1"
@@ -293,10 +345,11 @@ fn save_project_meta(conn: &Connection, meta: &ProjectMeta) -> Result<(), AppErr
     Ok(())
 }
 
-fn load_viewport(conn: &Connection) -> Result<Viewport, AppError> {
+/// Load the saved viewport of a canvas.
+pub fn load_viewport(conn: &Connection, canvas_id: &str) -> Result<Viewport, AppError> {
     conn.query_row(
-        "SELECT x, y, zoom FROM viewport WHERE id = 1",
-        [],
+        "SELECT x, y, zoom FROM canvases WHERE id = ?1",
+        params![canvas_id],
         |row| Ok(Viewport {
             x: row.get(0)?,
             y: row.get(1)?,
@@ -305,94 +358,170 @@ fn load_viewport(conn: &Connection) -> Result<Viewport, AppError> {
     ).map_err(|e| AppError::Io(format!("Failed to load viewport: {}", e)))
 }
 
-fn save_viewport(conn: &Connection, viewport: &Viewport) -> Result<(), AppError> {
+fn save_viewport(conn: &Connection, canvas_id: &str, viewport: &Viewport) -> Result<(), AppError> {
     conn.execute(
-        "UPDATE viewport SET x = ?1, y = ?2, zoom = ?3 WHERE id = 1",
-        params![viewport.x, viewport.y, viewport.zoom],
+        "UPDATE canvases SET x = ?1, y = ?2, zoom = ?3 WHERE id = ?4",
+        params![viewport.x, viewport.y, viewport.zoom, canvas_id],
     ).map_err(|e| AppError::Io(format!("Failed to save viewport: {}", e)))?;
     
     Ok(())
 }
 
-fn load_nodes(conn: &Connection) -> Result<Vec<SynniaNode>, AppError> {
+/// Load all nodes of a canvas.
+pub fn load_nodes(conn: &Connection, canvas_id: &str) -> Result<Vec<SynniaNode>, AppError> {
     let mut stmt = conn.prepare(
-        "SELECT id, type, x, y, width, height, parent_id, extent, style_json, data_json FROM nodes"
+        "SELECT id, type, x, y, width, height, parent_id, extent, style_json, data_json FROM nodes WHERE canvas_id = ?1"
     ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
     
-    let nodes = stmt.query_map([], |row| {
-        let style_json: Option<String> = row.get(8)?;
-        let data_json: String = row.get(9)?;
-        
-        let style = style_json
-            .and_then(|s| serde_json::from_str(&s).ok());
-        let data: SynniaNodeData = serde_json::from_str(&data_json)
-            .unwrap_or_else(|_| SynniaNodeData {
-                title: "Untitled".to_string(),
-                asset_id: None,
-                is_reference: None,
-                collapsed: None,
-                layout_mode: None,
-                docked_to: None,
-                state: None,
-                recipe_id: None,
-                has_product_handle: None,
-            });
-        
-        Ok(SynniaNode {
-            id: row.get(0)?,
-            type_: row.get(1)?,
-            position: Position { x: row.get(2)?, y: row.get(3)? },
-            width: row.get(4)?,
-            height: row.get(5)?,
-            parent_id: row.get(6)?,
-            extent: row.get(7)?,
-            style,
-            data,
-        })
-    }).map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    let nodes = stmt.query_map(params![canvas_id], row_to_node)
+        .map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    
+    nodes.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))
+}
+
+/// Load a single node by ID.
+pub fn get_node(conn: &Connection, node_id: &str) -> Result<Option<SynniaNode>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, type, x, y, width, height, parent_id, extent, style_json, data_json FROM nodes WHERE id = ?1"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let mut rows = stmt.query_map(params![node_id], row_to_node)
+        .map_err(|e| AppError::Io(format!("Failed to query node: {}", e)))?;
+    
+    rows.next()
+        .transpose()
+        .map_err(|e| AppError::Io(format!("Failed to load node: {}", e)))
+}
+
+/// Load all nodes showing the given asset (originals and references), in board order.
+pub fn get_nodes_by_asset(conn: &Connection, asset_id: &str) -> Result<Vec<SynniaNode>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, type, x, y, width, height, parent_id, extent, style_json, data_json FROM nodes
+         WHERE json_extract(data_json, '$.assetId') = ?1 ORDER BY rowid"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let nodes = stmt.query_map(params![asset_id], row_to_node)
+        .map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
     
     nodes.collect::<Result<Vec<_>, _>>()
         .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))
 }
 
-fn save_nodes(conn: &Connection, nodes: &[SynniaNode]) -> Result<(), AppError> {
-    // Clear existing nodes
-    conn.execute("DELETE FROM nodes", [])
+fn row_to_node(row: &rusqlite::Row) -> SqliteResult<SynniaNode> {
+    let style_json: Option<String> = row.get(8)?;
+    let data_json: String = row.get(9)?;
+    
+    let style = style_json
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let data: SynniaNodeData = serde_json::from_str(&data_json)
+        .unwrap_or_else(|_| SynniaNodeData {
+            title: "Untitled".to_string(),
+            ..Default::default()
+        });
+    
+    Ok(SynniaNode {
+        id: row.get(0)?,
+        type_: row.get(1)?,
+        position: Position { x: row.get(2)?, y: row.get(3)? },
+        width: row.get(4)?,
+        height: row.get(5)?,
+        parent_id: row.get(6)?,
+        extent: row.get(7)?,
+        style,
+        data,
+    })
+}
+
+fn save_nodes(conn: &Connection, canvas_id: &str, nodes: &[SynniaNode]) -> Result<(), AppError> {
+    let flags = stored_lock_flags(conn, nodes)?;
+    
+    // Clear existing nodes of the canvas
+    conn.execute("DELETE FROM nodes WHERE canvas_id = ?1", params![canvas_id])
         .map_err(|e| AppError::Io(format!("Failed to clear nodes: {}", e)))?;
     
-    // Insert new nodes
+    // Insert new nodes. Lock flags only change through `set_node_flags`, so a
+    // graph sent without them (or with stale ones) keeps what is stored.
     for node in nodes {
-        let style_json = node.style.as_ref()
-            .and_then(|s| serde_json::to_string(s).ok());
-        let data_json = serde_json::to_string(&node.data)?;
-        
-        conn.execute(
-            "INSERT INTO nodes (id, type, x, y, width, height, parent_id, extent, style_json, data_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                &node.id,
-                &node.type_,
-                node.position.x,
-                node.position.y,
-                node.width,
-                node.height,
-                &node.parent_id,
-                &node.extent,
-                &style_json,
-                &data_json
-            ],
-        ).map_err(|e| AppError::Io(format!("Failed to insert node: {}", e)))?;
+        match flags.get(&node.id) {
+            Some(&(locked, pinned)) if (node.data.locked, node.data.pinned) != (locked, pinned) => {
+                let mut node = node.clone();
+                node.data.locked = locked;
+                node.data.pinned = pinned;
+                insert_node(conn, &node, Some(canvas_id))?;
+            }
+            _ => insert_node(conn, node, Some(canvas_id))?,
+        }
     }
     
     Ok(())
 }
 
-fn load_edges(conn: &Connection) -> Result<Vec<SynniaEdge>, AppError> {
+/// `(locked, pinned)` as stored for one node
+type LockFlags = (Option<bool>, Option<bool>);
+
+/// Stored lock flags for the nodes about to be saved
+fn stored_lock_flags(conn: &Connection, nodes: &[SynniaNode]) -> Result<HashMap<String, LockFlags>, AppError> {
+    let ids = serde_json::to_string(&nodes.iter().map(|n| &n.id).collect::<Vec<_>>())?;
     let mut stmt = conn.prepare(
-        "SELECT id, source, target, source_handle, target_handle, type, label, animated FROM edges"
+        "SELECT id, json_extract(data_json, '$.locked'), json_extract(data_json, '$.pinned')
+         FROM nodes WHERE id IN (SELECT value FROM json_each(?1))"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let flags = stmt.query_map(params![ids], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+        .map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))?;
+    Ok(flags)
+}
+
+/// Insert or replace a single node without touching the rest of the graph.
+/// The node stays on its canvas; new nodes join their parent's canvas, or
+/// the active one.
+pub fn upsert_node(conn: &Connection, node: &SynniaNode) -> Result<(), AppError> {
+    insert_node(conn, node, None)
+}
+
+fn insert_node(conn: &Connection, node: &SynniaNode, canvas_id: Option<&str>) -> Result<(), AppError> {
+    let style_json = node.style.as_ref()
+        .and_then(|s| serde_json::to_string(s).ok());
+    let data_json = serde_json::to_string(&node.data)?;
+    
+    // Cached, so full saves don't re-prepare the statement per node
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO nodes (id, type, x, y, width, height, parent_id, extent, style_json, data_json, canvas_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, COALESCE(
+             ?11,
+             (SELECT canvas_id FROM nodes WHERE id = ?1),
+             (SELECT canvas_id FROM nodes WHERE id = ?7),
+             (SELECT id FROM canvases ORDER BY opened_at DESC, sort_order LIMIT 1),
+             'main'
+         ))"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare insert: {}", e)))?;
+    stmt.execute(
+        params![
+            &node.id,
+            &node.type_,
+            node.position.x,
+            node.position.y,
+            node.width,
+            node.height,
+            &node.parent_id,
+            &node.extent,
+            &style_json,
+            &data_json,
+            canvas_id
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to insert node: {}", e)))?;
+    
+    Ok(())
+}
+
+pub fn load_edges(conn: &Connection, canvas_id: &str) -> Result<Vec<SynniaEdge>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source, target, source_handle, target_handle, type, label, animated FROM edges WHERE canvas_id = ?1"
     ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
     
-    let edges = stmt.query_map([], |row| {
+    let edges = stmt.query_map(params![canvas_id], |row| {
         let animated: Option<i32> = row.get(7)?;
         
         Ok(SynniaEdge {
@@ -411,65 +540,62 @@ fn load_edges(conn: &Connection) -> Result<Vec<SynniaEdge>, AppError> {
         .map_err(|e| AppError::Io(format!("Failed to load edges: {}", e)))
 }
 
-fn save_edges(conn: &Connection, edges: &[SynniaEdge]) -> Result<(), AppError> {
-    conn.execute("DELETE FROM edges", [])
+fn save_edges(conn: &Connection, canvas_id: &str, edges: &[SynniaEdge]) -> Result<(), AppError> {
+    conn.execute("DELETE FROM edges WHERE canvas_id = ?1", params![canvas_id])
         .map_err(|e| AppError::Io(format!("Failed to clear edges: {}", e)))?;
     
     for edge in edges {
-        let animated = edge.animated.map(|a| if a { 1 } else { 0 });
-        
-        conn.execute(
-            "INSERT INTO edges (id, source, target, source_handle, target_handle, type, label, animated)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                &edge.id,
-                &edge.source,
-                &edge.target,
-                &edge.source_handle,
-                &edge.target_handle,
-                &edge.type_,
-                &edge.label,
-                animated
-            ],
-        ).map_err(|e| AppError::Io(format!("Failed to insert edge: {}", e)))?;
+        insert_edge(conn, edge, Some(canvas_id))?;
     }
     
     Ok(())
 }
 
-fn load_assets(conn: &Connection) -> Result<HashMap<String, Asset>, AppError> {
+/// Insert or replace a single edge without touching the rest of the graph.
+/// New edges join the canvas of their source node.
+pub fn upsert_edge(conn: &Connection, edge: &SynniaEdge) -> Result<(), AppError> {
+    insert_edge(conn, edge, None)
+}
+
+fn insert_edge(conn: &Connection, edge: &SynniaEdge, canvas_id: Option<&str>) -> Result<(), AppError> {
+    let animated = edge.animated.map(|a| if a { 1 } else { 0 });
+    
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO edges (id, source, target, source_handle, target_handle, type, label, animated, canvas_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, COALESCE(
+             ?9,
+             (SELECT canvas_id FROM edges WHERE id = ?1),
+             (SELECT canvas_id FROM nodes WHERE id = ?2),
+             'main'
+         ))"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare insert: {}", e)))?;
+    stmt.execute(
+        params![
+            &edge.id,
+            &edge.source,
+            &edge.target,
+            &edge.source_handle,
+            &edge.target_handle,
+            &edge.type_,
+            &edge.label,
+            animated,
+            canvas_id
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to insert edge: {}", e)))?;
+    
+    Ok(())
+}
+
+/// Load all assets of the project.
+pub fn load_assets(conn: &Connection) -> Result<HashMap<String, Asset>, AppError> {
     let mut stmt = conn.prepare(
         "SELECT id, value_type, value_json, value_meta_json, config_json, sys_json FROM assets"
     ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
     
     let mut assets = HashMap::new();
     
-    let rows = stmt.query_map([], |row| {
-        let id: String = row.get(0)?;
-        let value_type_str: String = row.get(1)?;
-        let value_json: String = row.get(2)?;
-        let value_meta_json: Option<String> = row.get(3)?;
-        let config_json: Option<String> = row.get(4)?;
-        let sys_json: String = row.get(5)?;
-        
-        let value_type: ValueType = serde_json::from_str(&value_type_str)
-            .unwrap_or(ValueType::Record);
-        let value: serde_json::Value = serde_json::from_str(&value_json)
-            .unwrap_or(serde_json::Value::Null);
-        let value_meta: Option<serde_json::Value> = value_meta_json
-            .and_then(|s| serde_json::from_str(&s).ok());
-        let config: Option<serde_json::Value> = config_json
-            .and_then(|s| serde_json::from_str(&s).ok());
-        let sys: AssetSysMetadata = serde_json::from_str(&sys_json)
-            .unwrap_or_else(|_| AssetSysMetadata {
-                name: "Unknown".to_string(),
-                created_at: 0,
-                updated_at: 0,
-                source: "user".to_string(),
-            });
-        
-        Ok(Asset { id, value_type, value, value_meta, config, sys })
-    }).map_err(|e| AppError::Io(format!("Failed to query assets: {}", e)))?;
+    let rows = stmt.query_map([], row_to_asset)
+        .map_err(|e| AppError::Io(format!("Failed to query assets: {}", e)))?;
     
     for asset_result in rows {
         let asset = asset_result.map_err(|e| AppError::Io(format!("Failed to load asset: {}", e)))?;
@@ -479,55 +605,117 @@ fn load_assets(conn: &Connection) -> Result<HashMap<String, Asset>, AppError> {
     Ok(assets)
 }
 
-fn save_assets(conn: &Connection, assets: &HashMap<String, Asset>) -> Result<(), AppError> {
+/// Load a single asset by ID.
+pub fn get_asset(conn: &Connection, asset_id: &str) -> Result<Option<Asset>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, value_type, value_json, value_meta_json, config_json, sys_json FROM assets WHERE id = ?1"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let mut rows = stmt.query_map(params![asset_id], row_to_asset)
+        .map_err(|e| AppError::Io(format!("Failed to query asset: {}", e)))?;
+    
+    rows.next()
+        .transpose()
+        .map_err(|e| AppError::Io(format!("Failed to load asset: {}", e)))
+}
+
+fn row_to_asset(row: &rusqlite::Row) -> SqliteResult<Asset> {
+    let id: String = row.get(0)?;
+    let value_type_str: String = row.get(1)?;
+    let value_json: String = row.get(2)?;
+    let value_meta_json: Option<String> = row.get(3)?;
+    let config_json: Option<String> = row.get(4)?;
+    let sys_json: String = row.get(5)?;
+    
+    let value_type: ValueType = serde_json::from_str(&value_type_str)
+        .unwrap_or(ValueType::Record);
+    let value: serde_json::Value = serde_json::from_str(&value_json)
+        .unwrap_or(serde_json::Value::Null);
+    let value_meta: Option<serde_json::Value> = value_meta_json
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let config: Option<serde_json::Value> = config_json
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let sys: AssetSysMetadata = serde_json::from_str(&sys_json)
+        .unwrap_or_else(|_| AssetSysMetadata {
+            name: "Unknown".to_string(),
+            created_at: 0,
+            updated_at: 0,
+            source: "user".to_string(),
+        });
+    
+    Ok(Asset { id, value_type, value, value_meta, config, sys })
+}
+
+fn save_assets(conn: &Connection, project_root: &Path, assets: &HashMap<String, Asset>) -> Result<(), AppError> {
     // Note: We don't clear assets here to preserve history.
     // Instead, we upsert each asset.
+    let mut upsert = conn.prepare_cached(
+        "INSERT INTO assets (id, value_type, value_hash, value_json, value_meta_json, config_json, sys_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET
+             value_type = excluded.value_type,
+             value_hash = excluded.value_hash,
+             value_json = excluded.value_json,
+             value_meta_json = excluded.value_meta_json,
+             config_json = excluded.config_json,
+             sys_json = excluded.sys_json,
+             updated_at = excluded.updated_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare upsert: {}", e)))?;
+    let now = chrono::Utc::now().timestamp_millis();
     
     for (id, asset) in assets {
         let value_json = serde_json::to_string(&asset.value)?;
-        let value_meta_json = asset.value_meta.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
+        let value_meta = markdown::derive_value_meta(asset, Some(project_root));
+        let value_meta_json = value_meta.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
         let config_json = asset.config.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
         let sys_json = serde_json::to_string(&asset.sys)?;
         let value_type_str = serde_json::to_string(&asset.value_type)?;
         let value_hash = compute_content_hash(&value_json);
-        let now = chrono::Utc::now().timestamp_millis();
         
-        conn.execute(
-            "INSERT INTO assets (id, value_type, value_hash, value_json, value_meta_json, config_json, sys_json, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(id) DO UPDATE SET
-                 value_type = excluded.value_type,
-                 value_hash = excluded.value_hash,
-                 value_json = excluded.value_json,
-                 value_meta_json = excluded.value_meta_json,
-                 config_json = excluded.config_json,
-                 sys_json = excluded.sys_json,
-                 updated_at = excluded.updated_at",
+        upsert.execute(
             params![id, &value_type_str, &value_hash, &value_json, &value_meta_json, &config_json, &sys_json, now],
         ).map_err(|e| AppError::Io(format!("Failed to save asset: {}", e)))?;
     }
     
-    // Remove assets that are no longer in the project
-    let ids: Vec<String> = assets.keys().cloned().collect();
-    if !ids.is_empty() {
-        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let sql = format!("DELETE FROM assets WHERE id NOT IN ({})", placeholders);
-        
-        let mut stmt = conn.prepare(&sql)
-            .map_err(|e| AppError::Io(format!("Failed to prepare delete: {}", e)))?;
-        
-        for (i, id) in ids.iter().enumerate() {
-            stmt.raw_bind_parameter(i + 1, id)
-                .map_err(|e| AppError::Io(format!("Failed to bind: {}", e)))?;
-        }
-        
-        stmt.raw_execute()
-            .map_err(|e| AppError::Io(format!("Failed to delete orphaned assets: {}", e)))?;
+    // Remove assets that are no longer in the project. The IDs go in as one
+    // JSON array, which keeps large boards under SQLite's parameter limit.
+    if !assets.is_empty() {
+        let ids = serde_json::to_string(&assets.keys().collect::<Vec<_>>())?;
+        conn.execute(
+            "DELETE FROM assets WHERE id NOT IN (SELECT value FROM json_each(?1))",
+            params![ids],
+        ).map_err(|e| AppError::Io(format!("Failed to delete orphaned assets: {}", e)))?;
     }
     
     Ok(())
 }
 
+/// Load a single project setting by key.
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<serde_json::Value>, AppError> {
+    let mut stmt = conn.prepare("SELECT value_json FROM settings WHERE key = ?1")
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+
+    let mut rows = stmt.query_map(params![key], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Io(format!("Failed to query setting: {}", e)))?;
+
+    match rows.next() {
+        Some(row) => {
+            let value_json = row.map_err(|e| AppError::Io(format!("Failed to load setting: {}", e)))?;
+            Ok(serde_json::from_str(&value_json).ok())
+        }
+        None => Ok(None),
+    }
+}
+
+/// Insert or replace a single project setting.
+pub fn set_setting(conn: &Connection, key: &str, value: &serde_json::Value) -> Result<(), AppError> {
+    let value_json = serde_json::to_string(value)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value_json) VALUES (?1, ?2)",
+        params![key, &value_json],
+    ).map_err(|e| AppError::Io(format!("Failed to save setting: {}", e)))?;
+    Ok(())
+}
 
 fn load_settings(conn: &Connection) -> Result<Option<HashMap<String, serde_json::Value>>, AppError> {
     let mut stmt = conn.prepare("SELECT key, value_json FROM settings")
@@ -554,20 +742,14 @@ fn load_settings(conn: &Connection) -> Result<Option<HashMap<String, serde_json:
     }
 }
 
+/// Upsert the settings the caller sent. Keys it left out are kept: the
+/// frontend saves with an empty map, and services such as the brand kit,
+/// permissions and budget write their keys through `set_setting`.
 fn save_settings(conn: &Connection, settings: &Option<HashMap<String, serde_json::Value>>) -> Result<(), AppError> {
-    conn.execute("DELETE FROM settings", [])
-        .map_err(|e| AppError::Io(format!("Failed to clear settings: {}", e)))?;
-    
-    if let Some(settings) = settings {
-        for (key, value) in settings {
-            let value_json = serde_json::to_string(value)?;
-            conn.execute(
-                "INSERT INTO settings (key, value_json) VALUES (?1, ?2)",
-                params![key, &value_json],
-            ).map_err(|e| AppError::Io(format!("Failed to save setting: {}", e)))?;
-        }
+    let Some(settings) = settings.as_ref().filter(|s| !s.is_empty()) else { return Ok(()) };
+    for (key, value) in settings {
+        set_setting(conn, key, value)?;
     }
-    
     Ok(())
 }
 
@@ -618,6 +800,11 @@ mod tests {
                 state: None,
                 recipe_id: None,
                 has_product_handle: None,
+                start_date: None,
+                due_date: None,
+                status: None,
+                locked: None,
+                pinned: None,
             },
         });
         
@@ -645,4 +832,91 @@ mod tests {
         assert_eq!(loaded.assets.len(), 1);
         assert!(loaded.assets.contains_key("asset-1"));
     }
+
+    #[test]
+    fn test_save_large_board() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        let mut project = init_project_sqlite(project_root, "Large").unwrap();
+        
+        for i in 0..2000 {
+            let id = format!("asset-{}", i);
+            project.assets.insert(id.clone(), Asset {
+                id,
+                value_type: ValueType::Record,
+                value: serde_json::json!(i),
+                value_meta: None,
+                config: None,
+                sys: AssetSysMetadata { name: format!("Asset {}", i), created_at: 0, updated_at: 0, source: "user".to_string() },
+            });
+            project.graph.nodes.push(SynniaNode {
+                id: format!("node-{}", i),
+                type_: "text".to_string(),
+                position: Position { x: i as f64, y: 0.0 },
+                width: None,
+                height: None,
+                parent_id: None,
+                extent: None,
+                style: None,
+                data: SynniaNodeData {
+                    title: "Node".to_string(),
+                    asset_id: Some(format!("asset-{}", i)),
+                    ..Default::default()
+                },
+            });
+            if i > 0 {
+                project.graph.edges.push(SynniaEdge {
+                    id: format!("edge-{}", i),
+                    source: format!("node-{}", i - 1),
+                    target: format!("node-{}", i),
+                    source_handle: None,
+                    target_handle: None,
+                    type_: None,
+                    label: None,
+                    animated: None,
+                });
+            }
+        }
+        save_project_sqlite(project_root, &project).unwrap();
+        
+        let mut loaded = load_project_sqlite(project_root).unwrap();
+        assert_eq!(loaded.graph.nodes.len(), 2000);
+        assert_eq!(loaded.graph.edges.len(), 1999);
+        assert_eq!(loaded.assets.len(), 2000);
+        
+        // Assets dropped from the project are removed on the next save
+        loaded.assets.retain(|id, _| id != "asset-7");
+        save_project_sqlite(project_root, &loaded).unwrap();
+        let reloaded = load_project_sqlite(project_root).unwrap();
+        assert_eq!(reloaded.assets.len(), 1999);
+        assert!(!reloaded.assets.contains_key("asset-7"));
+    }
+
+    #[test]
+    fn test_save_keeps_settings_it_does_not_send() {
+        let dir = tempdir().unwrap();
+        let project_root = dir.path();
+        let mut project = init_project_sqlite(project_root, "Settings").unwrap();
+        
+        let conn = database::open_db(&get_db_path(project_root)).unwrap();
+        set_setting(&conn, "brandKit", &serde_json::json!({ "colors": ["#ff0000"] })).unwrap();
+        drop(conn);
+        
+        // Autosave sends an empty map
+        project.settings = Some(HashMap::new());
+        save_project_sqlite(project_root, &project).unwrap();
+        project.settings = None;
+        save_project_sqlite(project_root, &project).unwrap();
+        
+        let conn = database::open_db(&get_db_path(project_root)).unwrap();
+        assert_eq!(get_setting(&conn, "brandKit").unwrap(), Some(serde_json::json!({ "colors": ["#ff0000"] })));
+        drop(conn);
+        
+        // Keys that are sent are written
+        project.settings = Some(HashMap::from([("theme".to_string(), serde_json::json!("dark"))]));
+        save_project_sqlite(project_root, &project).unwrap();
+        let settings = load_project_sqlite(project_root).unwrap().settings.unwrap();
+        assert_eq!(settings["theme"], "dark");
+        assert!(settings.contains_key("brandKit"));
+    }
 }
@@ -0,0 +1,394 @@
+//! Backend job queue for work executed by the frontend recipe executors.
+//!
+//! The backend owns scheduling: a job is a list of tasks plus a concurrency
+//! limit. Tasks are handed out as `TaskDispatch`es (emitted to the frontend
+//! as `job:task` events); the frontend reports each result back, which frees
+//! a slot for the next pending task. When every task has settled the job is
+//! complete and its kind-specific finalizer runs.
+//!
+//! A job can be submitted on hold (e.g. when it would exceed the project's
+//! budget): it is tracked like any other but hands out no tasks until it is
+//! released.
+//!
+//! CPU/IO-bound kinds (e.g. batch import) don't need the frontend: they run
+//! on a local worker pool via `JobQueue::run_local`, with the same
+//! concurrency limit, status tracking and cancellation.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use ts_rs::TS;
+
+/// Upper bound for per-job concurrency
+pub const MAX_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Success,
+    Error,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JobTask {
+    pub index: usize,
+    #[ts(type = "any")]
+    pub params: serde_json::Value,
+    pub status: TaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(type = "any")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    /// e.g. "generation-grid"
+    pub kind: String,
+    /// Kind-specific data needed by the finalizer
+    #[ts(type = "any")]
+    pub payload: serde_json::Value,
+    pub concurrency: usize,
+    pub tasks: Vec<JobTask>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// Waiting for confirmation; no tasks are dispatched meanwhile
+    #[serde(default)]
+    pub held: bool,
+    /// Project the job was submitted in; it is finalized there even if
+    /// another project has been opened since
+    #[serde(skip)]
+    #[ts(skip)]
+    pub project_root: Option<PathBuf>,
+}
+
+/// A task handed to the frontend for execution (`job:task` event payload)
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDispatch {
+    pub job_id: String,
+    pub kind: String,
+    pub task_index: usize,
+    #[ts(type = "any")]
+    pub params: serde_json::Value,
+}
+
+impl Job {
+    pub fn new(kind: &str, payload: serde_json::Value, params: Vec<serde_json::Value>, concurrency: usize) -> Self {
+        let tasks = params
+            .into_iter()
+            .enumerate()
+            .map(|(index, params)| JobTask {
+                index,
+                params,
+                status: TaskStatus::Pending,
+                result: None,
+                error: None,
+            })
+            .collect();
+        
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            payload,
+            concurrency: concurrency.clamp(1, MAX_CONCURRENCY),
+            tasks,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            held: false,
+            project_root: None,
+        }
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.tasks.iter().all(|t| !matches!(t.status, TaskStatus::Pending | TaskStatus::Running))
+    }
+    
+    /// Mark pending tasks as running up to the concurrency limit.
+    fn next_dispatches(&mut self) -> Vec<TaskDispatch> {
+        if self.held {
+            return Vec::new();
+        }
+        let running = self.tasks.iter().filter(|t| t.status == TaskStatus::Running).count();
+        let free = self.concurrency.saturating_sub(running);
+        
+        let mut dispatches = Vec::new();
+        for task in self.tasks.iter_mut().filter(|t| t.status == TaskStatus::Pending).take(free) {
+            task.status = TaskStatus::Running;
+            dispatches.push(TaskDispatch {
+                job_id: self.id.clone(),
+                kind: self.kind.clone(),
+                task_index: task.index,
+                params: task.params.clone(),
+            });
+        }
+        dispatches
+    }
+}
+
+/// Outcome of reporting a task result
+#[derive(Debug)]
+pub struct TaskUpdate {
+    /// Tasks that may start now
+    pub dispatches: Vec<TaskDispatch>,
+    /// Set once every task has settled; the job is removed from the queue
+    pub finished: Option<Job>,
+}
+
+/// In-memory registry of running jobs
+#[derive(Default)]
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Register a job and return the first batch of tasks to run.
+    pub fn submit(&self, mut job: Job) -> Result<Vec<TaskDispatch>, AppError> {
+        let dispatches = job.next_dispatches();
+        let mut jobs = self.lock()?;
+        jobs.insert(job.id.clone(), job);
+        Ok(dispatches)
+    }
+    
+    /// Take a held job off hold and return the first batch of tasks to run.
+    pub fn release(&self, job_id: &str) -> Result<Vec<TaskDispatch>, AppError> {
+        let mut jobs = self.lock()?;
+        let job = jobs.get_mut(job_id)
+            .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))?;
+        job.held = false;
+        Ok(job.next_dispatches())
+    }
+    
+    /// Record the result of a task and schedule the next ones.
+    pub fn complete_task(
+        &self,
+        job_id: &str,
+        task_index: usize,
+        result: Result<serde_json::Value, String>,
+    ) -> Result<TaskUpdate, AppError> {
+        let mut jobs = self.lock()?;
+        let job = jobs.get_mut(job_id)
+            .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))?;
+        
+        let task = job.tasks.get_mut(task_index)
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found in job {}", task_index, job_id)))?;
+        if task.status != TaskStatus::Running {
+            return Err(AppError::Unknown(format!("Task {} is not running", task_index)));
+        }
+        
+        match result {
+            Ok(value) => {
+                task.status = TaskStatus::Success;
+                task.result = Some(value);
+            }
+            Err(error) => {
+                task.status = TaskStatus::Error;
+                task.error = Some(error);
+            }
+        }
+        
+        let dispatches = job.next_dispatches();
+        let finished = if job.is_finished() { jobs.remove(job_id) } else { None };
+        
+        Ok(TaskUpdate { dispatches, finished })
+    }
+    
+    /// Cancel all pending and running tasks. Returns the settled job.
+    pub fn cancel(&self, job_id: &str) -> Result<Job, AppError> {
+        let mut jobs = self.lock()?;
+        let mut job = jobs.remove(job_id)
+            .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))?;
+        
+        for task in job.tasks.iter_mut() {
+            if matches!(task.status, TaskStatus::Pending | TaskStatus::Running) {
+                task.status = TaskStatus::Cancelled;
+            }
+        }
+        Ok(job)
+    }
+    
+    /// Run a job on a pool of `job.concurrency` worker threads, calling
+    /// `on_settled` as each task finishes. Blocks until the job has settled
+    /// and returns it; fails if the job is cancelled meanwhile.
+    pub fn run_local<W, P>(&self, job: Job, worker: W, mut on_settled: P) -> Result<Job, AppError>
+    where
+        W: Fn(&serde_json::Value) -> Result<serde_json::Value, String> + Sync,
+        P: FnMut(&Job, &JobTask),
+    {
+        let job_id = job.id.clone();
+        let workers = job.concurrency;
+        let first = self.submit(job)?;
+        if first.is_empty() {
+            return self.lock()?.remove(&job_id)
+                .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)));
+        }
+        
+        let (work_tx, work_rx) = mpsc::channel::<TaskDispatch>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let work_rx = Mutex::new(work_rx);
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                let (work_rx, done_tx, worker) = (&work_rx, done_tx.clone(), &worker);
+                scope.spawn(move || loop {
+                    // Hold the lock only while waiting, not while working
+                    let next = work_rx.lock().map(|rx| rx.recv());
+                    let Ok(Ok(dispatch)) = next else { break };
+                    let result = worker(&dispatch.params);
+                    if done_tx.send((dispatch.task_index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(done_tx);
+            
+            for dispatch in first {
+                let _ = work_tx.send(dispatch);
+            }
+            
+            let result = (|| {
+                for (task_index, result) in &done_rx {
+                    let update = self.complete_task(&job_id, task_index, result)
+                        .map_err(|_| AppError::Unknown(format!("Job cancelled: {}", job_id)))?;
+                    if let Some(job) = update.finished {
+                        on_settled(&job, &job.tasks[task_index]);
+                        return Ok(job);
+                    }
+                    
+                    let job = self.get(&job_id)?
+                        .ok_or_else(|| AppError::Unknown(format!("Job cancelled: {}", job_id)))?;
+                    on_settled(&job, &job.tasks[task_index]);
+                    for dispatch in update.dispatches {
+                        let _ = work_tx.send(dispatch);
+                    }
+                }
+                Err(AppError::Unknown(format!("Job workers stopped: {}", job_id)))
+            })();
+            
+            // Closing the work channel lets the workers exit
+            drop(work_tx);
+            result
+        })
+    }
+    
+    /// Snapshot of a running job
+    pub fn get(&self, job_id: &str) -> Result<Option<Job>, AppError> {
+        Ok(self.lock()?.get(job_id).cloned())
+    }
+    
+    /// Snapshot of all running jobs
+    pub fn list(&self) -> Result<Vec<Job>, AppError> {
+        Ok(self.lock()?.values().cloned().collect())
+    }
+    
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, Job>>, AppError> {
+        self.jobs.lock().map_err(|_| AppError::Unknown("Job queue lock poisoned".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_concurrency_limit() {
+        let queue = JobQueue::new();
+        let job = Job::new("test", json!({}), vec![json!(1), json!(2), json!(3)], 2);
+        let job_id = job.id.clone();
+        
+        let first = queue.submit(job).unwrap();
+        assert_eq!(first.len(), 2, "only two tasks should start");
+        
+        let update = queue.complete_task(&job_id, 0, Ok(json!("a"))).unwrap();
+        assert_eq!(update.dispatches.len(), 1);
+        assert_eq!(update.dispatches[0].task_index, 2);
+        assert!(update.finished.is_none());
+        
+        queue.complete_task(&job_id, 1, Err("boom".to_string())).unwrap();
+        let update = queue.complete_task(&job_id, 2, Ok(json!("c"))).unwrap();
+        let finished = update.finished.expect("job should be finished");
+        assert_eq!(finished.tasks[1].status, TaskStatus::Error);
+        assert!(queue.get(&job_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_run_local() {
+        let queue = JobQueue::new();
+        let params = (0..20).map(|i| json!(i)).collect();
+        let job = Job::new("test", json!({}), params, 4);
+        
+        let mut settled = Vec::new();
+        let job = queue.run_local(
+            job,
+            |p| match p.as_i64() {
+                Some(7) => Err("unlucky".to_string()),
+                Some(n) => Ok(json!(n * 2)),
+                None => Err("not a number".to_string()),
+            },
+            |job, task| settled.push((job.tasks.iter().filter(|t| t.status != TaskStatus::Running && t.status != TaskStatus::Pending).count(), task.index)),
+        ).unwrap();
+        
+        assert_eq!(settled.len(), 20);
+        assert!(settled.iter().enumerate().all(|(i, (done, _))| *done == i + 1));
+        assert_eq!(job.tasks[3].result, Some(json!(6)));
+        assert_eq!(job.tasks[7].status, TaskStatus::Error);
+        assert!(queue.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_held_job() {
+        let queue = JobQueue::new();
+        let mut job = Job::new("test", json!({}), vec![json!(1), json!(2), json!(3)], 2);
+        job.held = true;
+        let job_id = job.id.clone();
+        
+        assert!(queue.submit(job).unwrap().is_empty(), "held jobs dispatch nothing");
+        assert!(queue.get(&job_id).unwrap().unwrap().held);
+        
+        let first = queue.release(&job_id).unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(!queue.get(&job_id).unwrap().unwrap().held);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let queue = JobQueue::new();
+        let job = Job::new("test", json!({}), vec![json!(1), json!(2)], 1);
+        let job_id = job.id.clone();
+        queue.submit(job).unwrap();
+        
+        let cancelled = queue.cancel(&job_id).unwrap();
+        assert!(cancelled.tasks.iter().all(|t| t.status == TaskStatus::Cancelled));
+        assert!(queue.complete_task(&job_id, 0, Ok(json!(null))).is_err());
+    }
+
+    #[test]
+    fn test_finished_job_keeps_its_project() {
+        let queue = JobQueue::new();
+        let mut job = Job::new("generation-grid", json!({}), vec![json!(1)], 1);
+        job.project_root = Some(PathBuf::from("/projects/film"));
+        let job_id = job.id.clone();
+        queue.submit(job).unwrap();
+        
+        let finished = queue.complete_task(&job_id, 0, Ok(json!("a"))).unwrap().finished.unwrap();
+        assert_eq!(finished.project_root, Some(PathBuf::from("/projects/film")));
+        // Not part of what the frontend sees
+        assert!(serde_json::to_value(&finished).unwrap().get("projectRoot").is_none());
+    }
+}
@@ -0,0 +1,312 @@
+//! Usage rights of assets: license type, attribution, restrictions and
+//! expiry, so a studio knows what it may ship.
+//!
+//! Licenses are kept per asset in their own table (like tags), where
+//! project saves from the frontend don't touch them. Searches can filter on
+//! them (see `LicenseFilter`), and `report` lists every asset that came from
+//! outside the app with the state of its rights: unknown, expired, expiring
+//! soon or fine.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::io_sqlite;
+use ts_rs::TS;
+
+/// Rights ending within this many days count as expiring
+pub const DEFAULT_EXPIRING_DAYS: u32 = 30;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const MAX_FIELD_LEN: usize = 2000;
+
+/// Asset sources made inside the app; everything else came from outside
+const INTERNAL_SOURCES: &[&str] = &["user", "ai"];
+
+/// License of an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetLicense {
+    /// e.g. "CC-BY-4.0", "royalty-free", "editorial", "owned"
+    pub license_type: String,
+    /// Credit line required when the asset is used
+    #[serde(default)]
+    pub attribution: Option<String>,
+    /// What the license doesn't allow, e.g. "no print", "web only"
+    #[serde(default)]
+    pub restrictions: Option<String>,
+    /// When the rights end (Unix ms); none for perpetual rights
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub expires_at: Option<i64>,
+    /// Where the license or asset was obtained
+    #[serde(default)]
+    pub source_url: Option<String>,
+}
+
+/// State of an asset's rights at a given time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseStatus {
+    /// No license recorded
+    Unknown,
+    Expired,
+    /// Ends within the expiring window
+    Expiring,
+    Valid,
+}
+
+/// License conditions for asset searches
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LicenseFilter {
+    /// License types to match (case-insensitive); empty for any
+    pub types: Vec<String>,
+    /// Rights in one of these states; empty for any
+    pub statuses: Vec<LicenseStatus>,
+    /// Window for `expiring`, in days
+    pub expiring_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseReportItem {
+    pub asset_id: String,
+    pub name: String,
+    /// Where the asset came from (`sys.source`)
+    pub source: String,
+    pub license: Option<AssetLicense>,
+    pub status: LicenseStatus,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseReport {
+    #[ts(type = "number")]
+    pub generated_at: i64,
+    pub expiring_days: u32,
+    /// Unknown, expired and expiring rights first, soonest expiry first
+    pub items: Vec<LicenseReportItem>,
+    pub unknown: usize,
+    pub expired: usize,
+    pub expiring: usize,
+}
+
+/// Record (or with `None`, clear) an asset's license.
+pub fn set_license(conn: &Connection, asset_id: &str, license: Option<&AssetLicense>) -> Result<Option<AssetLicense>, AppError> {
+    let Some(license) = license else {
+        conn.execute("DELETE FROM asset_licenses WHERE asset_id = ?1", params![asset_id])
+            .map_err(|e| AppError::Io(format!("Failed to clear license: {}", e)))?;
+        return Ok(None);
+    };
+
+    let license = normalize(license)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO asset_licenses
+         (asset_id, license_type, attribution, restrictions, expires_at, source_url, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            asset_id,
+            license.license_type,
+            license.attribution,
+            license.restrictions,
+            license.expires_at,
+            license.source_url,
+            chrono::Utc::now().timestamp_millis(),
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to save license: {}", e)))?;
+    Ok(Some(license))
+}
+
+pub fn get_license(conn: &Connection, asset_id: &str) -> Result<Option<AssetLicense>, AppError> {
+    conn.query_row(
+        "SELECT license_type, attribution, restrictions, expires_at, source_url FROM asset_licenses WHERE asset_id = ?1",
+        params![asset_id],
+        row_to_license,
+    ).optional().map_err(|e| AppError::Io(format!("Failed to load license: {}", e)))
+}
+
+pub fn status(license: Option<&AssetLicense>, now: i64, expiring_days: u32) -> LicenseStatus {
+    match license.map(|l| l.expires_at) {
+        None => LicenseStatus::Unknown,
+        Some(Some(expires_at)) if expires_at <= now => LicenseStatus::Expired,
+        Some(Some(expires_at)) if expires_at <= now + expiring_days as i64 * DAY_MS => LicenseStatus::Expiring,
+        Some(_) => LicenseStatus::Valid,
+    }
+}
+
+/// SQL condition matching `filter` on the asset whose ID is `id_column`,
+/// with its parameters appended to `params`. `None` if the filter is empty.
+pub fn filter_condition(filter: &LicenseFilter, id_column: &str, params: &mut Vec<SqlValue>) -> Option<String> {
+    let licensed = |condition: String| format!(
+        "EXISTS (SELECT 1 FROM asset_licenses l WHERE l.asset_id = {} AND {})", id_column, condition
+    );
+    let mut conditions = Vec::new();
+    if !filter.types.is_empty() {
+        let placeholders: Vec<String> = filter.types.iter()
+            .map(|t| {
+                params.push(SqlValue::Text(t.trim().to_lowercase()));
+                format!("?{}", params.len())
+            })
+            .collect();
+        conditions.push(licensed(format!("LOWER(l.license_type) IN ({})", placeholders.join(", "))));
+    }
+
+    if !filter.statuses.is_empty() {
+        let now = chrono::Utc::now().timestamp_millis();
+        params.push(SqlValue::Integer(now));
+        let now_param = params.len();
+        params.push(SqlValue::Integer(now + filter.expiring_days.unwrap_or(DEFAULT_EXPIRING_DAYS) as i64 * DAY_MS));
+        let soon_param = params.len();
+        let states: Vec<String> = filter.statuses.iter()
+            .map(|status| match status {
+                LicenseStatus::Unknown => format!("NOT EXISTS (SELECT 1 FROM asset_licenses l WHERE l.asset_id = {})", id_column),
+                LicenseStatus::Expired => licensed(format!("l.expires_at <= ?{}", now_param)),
+                LicenseStatus::Expiring => licensed(format!("l.expires_at > ?{} AND l.expires_at <= ?{}", now_param, soon_param)),
+                LicenseStatus::Valid => licensed(format!("(l.expires_at IS NULL OR l.expires_at > ?{})", soon_param)),
+            })
+            .collect();
+        conditions.push(format!("({})", states.join(" OR ")));
+    }
+
+    (!conditions.is_empty()).then(|| conditions.join(" AND "))
+}
+
+/// Rights of every asset from outside the app (and every licensed asset).
+pub fn report(conn: &Connection, expiring_days: u32) -> Result<LicenseReport, AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let assets = io_sqlite::load_assets(conn)?;
+
+    let mut items = Vec::new();
+    for asset in assets.into_values() {
+        let license = get_license(conn, &asset.id)?;
+        if license.is_none() && INTERNAL_SOURCES.contains(&asset.sys.source.as_str()) {
+            continue;
+        }
+        items.push(LicenseReportItem {
+            status: status(license.as_ref(), now, expiring_days),
+            asset_id: asset.id,
+            name: asset.sys.name,
+            source: asset.sys.source,
+            license,
+        });
+    }
+
+    let rank = |status: LicenseStatus| match status {
+        LicenseStatus::Unknown => 0,
+        LicenseStatus::Expired => 1,
+        LicenseStatus::Expiring => 2,
+        LicenseStatus::Valid => 3,
+    };
+    items.sort_by(|a, b| {
+        let expiry = |item: &LicenseReportItem| item.license.as_ref().and_then(|l| l.expires_at).unwrap_or(i64::MAX);
+        (rank(a.status), expiry(a), &a.name).cmp(&(rank(b.status), expiry(b), &b.name))
+    });
+
+    let count = |status: LicenseStatus| items.iter().filter(|i| i.status == status).count();
+    Ok(LicenseReport {
+        generated_at: now,
+        expiring_days,
+        unknown: count(LicenseStatus::Unknown),
+        expired: count(LicenseStatus::Expired),
+        expiring: count(LicenseStatus::Expiring),
+        items,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Trim the fields, dropping empty optional ones
+fn normalize(license: &AssetLicense) -> Result<AssetLicense, AppError> {
+    let optional = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(String::from);
+    let license = AssetLicense {
+        license_type: license.license_type.trim().to_string(),
+        attribution: optional(&license.attribution),
+        restrictions: optional(&license.restrictions),
+        expires_at: license.expires_at,
+        source_url: optional(&license.source_url),
+    };
+    if license.license_type.is_empty() {
+        return Err(AppError::InvalidInput("A license type is required".to_string()));
+    }
+    let fields = [Some(&license.license_type), license.attribution.as_ref(), license.restrictions.as_ref(), license.source_url.as_ref()];
+    if fields.into_iter().flatten().any(|f| f.chars().count() > MAX_FIELD_LEN) {
+        return Err(AppError::InvalidInput(format!("License fields are at most {} characters", MAX_FIELD_LEN)));
+    }
+    Ok(license)
+}
+
+fn row_to_license(row: &rusqlite::Row) -> rusqlite::Result<AssetLicense> {
+    Ok(AssetLicense {
+        license_type: row.get(0)?,
+        attribution: row.get(1)?,
+        restrictions: row.get(2)?,
+        expires_at: row.get(3)?,
+        source_url: row.get(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use rusqlite::params_from_iter;
+    use tempfile::tempdir;
+
+    fn insert_asset(conn: &Connection, id: &str, source: &str) {
+        let sys = serde_json::json!({ "name": id, "createdAt": 0, "updatedAt": 0, "source": source });
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at) VALUES (?1, 'record', 'h', '{}', ?2, 0)",
+            params![id, sys.to_string()],
+        ).unwrap();
+    }
+
+    fn license(license_type: &str, expires_at: Option<i64>) -> AssetLicense {
+        AssetLicense { license_type: license_type.to_string(), attribution: None, restrictions: None, expires_at, source_url: None }
+    }
+
+    fn search(conn: &Connection, filter: &LicenseFilter) -> Vec<String> {
+        let mut params = Vec::new();
+        let condition = filter_condition(filter, "a.id", &mut params).unwrap();
+        let sql = format!("SELECT a.id FROM assets a WHERE {} ORDER BY a.id", condition);
+        let mut stmt = conn.prepare(&sql).unwrap();
+        let ids = stmt.query_map(params_from_iter(params.iter()), |row| row.get(0)).unwrap();
+        ids.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_report_and_filters() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+        for (id, source) in [("drawn", "user"), ("photo", "import"), ("stock", "import"), ("old", "import"), ("clip", "clip")] {
+            insert_asset(&conn, id, source);
+        }
+        set_license(&conn, "stock", Some(&license(" CC-BY-4.0 ", None))).unwrap();
+        set_license(&conn, "old", Some(&license("editorial", Some(now - DAY_MS)))).unwrap();
+        set_license(&conn, "clip", Some(&license("royalty-free", Some(now + 3 * DAY_MS)))).unwrap();
+        assert_eq!(get_license(&conn, "stock").unwrap().unwrap().license_type, "CC-BY-4.0");
+
+        let report = report(&conn, DEFAULT_EXPIRING_DAYS).unwrap();
+        let ids: Vec<&str> = report.items.iter().map(|i| i.asset_id.as_str()).collect();
+        assert_eq!(ids, ["photo", "old", "clip", "stock"], "internal assets without a license are left out");
+        assert_eq!((report.unknown, report.expired, report.expiring), (1, 1, 1));
+
+        let flagged = LicenseFilter { statuses: vec![LicenseStatus::Unknown, LicenseStatus::Expiring], ..Default::default() };
+        assert_eq!(search(&conn, &flagged), ["clip", "drawn", "photo"]);
+        let by_type = LicenseFilter { types: vec!["cc-by-4.0".to_string()], ..Default::default() };
+        assert_eq!(search(&conn, &by_type), ["stock"]);
+        assert!(filter_condition(&LicenseFilter::default(), "a.id", &mut Vec::new()).is_none());
+
+        set_license(&conn, "stock", None).unwrap();
+        assert!(get_license(&conn, "stock").unwrap().is_none());
+        assert!(set_license(&conn, "stock", Some(&license("  ", None))).is_err());
+    }
+}
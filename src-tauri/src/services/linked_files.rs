@@ -0,0 +1,191 @@
+//! Files imported by reference instead of copied.
+//!
+//! Footage on a NAS or external drive shouldn't be duplicated into the
+//! project. A linked import puts a symlink at `assets/<id>.<ext>` pointing
+//! at the original, so everything that reads assets works unchanged, and
+//! records it in `linked_files`. That table is also what lets the file
+//! server follow these links out of the project folder, which
+//! `validation::resolve_in_project` otherwise refuses.
+//!
+//! When a drive is remounted or footage moves, links break; they're listed
+//! on project load and repointed with `relink`.
+
+use std::fs;
+use std::path::Path;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::services::{database, fs_paths, io_sqlite};
+use ts_rs::TS;
+
+/// A linked asset file whose original can't be reached
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    /// The link, relative to the project root ("assets/<id>.<ext>")
+    pub src: String,
+    /// Where the original was when it was linked
+    pub source_path: String,
+}
+
+/// Outcome of `relink`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkReport {
+    /// Links now pointing at the new location
+    pub relinked: usize,
+    /// Links under the old prefix whose file isn't at the new location;
+    /// these are left unchanged
+    pub missing: Vec<BrokenLink>,
+}
+
+/// Link `source` into `assets/` and record it. Returns the file ID and the
+/// link's path relative to the project root.
+pub fn link_file(conn: &Connection, project_root: &Path, source: &Path) -> Result<(String, String), AppError> {
+    if !source.is_absolute() {
+        return Err(AppError::InvalidInput(format!("Linked files need an absolute path: {}", source.display())));
+    }
+    let target = fs_paths::long_path(source);
+    if !target.exists() {
+        return Err(AppError::NotFound(format!("File not found: {}", source.display())));
+    }
+    if !target.is_file() {
+        return Err(AppError::InvalidInput(format!("Not a file: {}", source.display())));
+    }
+
+    let assets_dir = project_root.join("assets");
+    if !assets_dir.exists() {
+        fs::create_dir_all(&assets_dir)?;
+    }
+    let file_id = uuid::Uuid::new_v4().to_string();
+    let ext = fs_paths::extension(source).unwrap_or_else(|| "bin".to_string());
+    let src = format!("assets/{}.{}", file_id, ext);
+
+    create_link(&target, &project_root.join(&src))?;
+    conn.execute(
+        "INSERT INTO linked_files (src, source_path, created_at) VALUES (?1, ?2, ?3)",
+        params![src, source.to_string_lossy(), chrono::Utc::now().timestamp_millis()],
+    ).map_err(|e| AppError::Io(format!("Failed to record link: {}", e)))?;
+
+    Ok((file_id, src))
+}
+
+/// Whether `src` is a link made by `link_file`. Opens the project database.
+pub fn is_linked(project_root: &Path, src: &str) -> bool {
+    let Ok(conn) = database::open_db(&io_sqlite::get_db_path(project_root)) else { return false };
+    conn.query_row("SELECT 1 FROM linked_files WHERE src = ?1", params![src], |_| Ok(()))
+        .optional()
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Links whose original is gone (or whose link was removed)
+pub fn broken_links(conn: &Connection, project_root: &Path) -> Result<Vec<BrokenLink>, AppError> {
+    Ok(load_links(conn)?
+        .into_iter()
+        .filter(|link| !fs_paths::long_path(&project_root.join(&link.src)).exists())
+        .collect())
+}
+
+/// Point every link whose original sits under `old_prefix` at the same
+/// relative spot under `new_prefix`, e.g. after a NAS share is mounted at
+/// another drive letter.
+pub fn relink(conn: &Connection, project_root: &Path, old_prefix: &str, new_prefix: &str) -> Result<RelinkReport, AppError> {
+    let mut report = RelinkReport { relinked: 0, missing: Vec::new() };
+    for link in load_links(conn)? {
+        let Ok(rest) = Path::new(&link.source_path).strip_prefix(old_prefix) else { continue };
+        let source = Path::new(new_prefix).join(rest);
+        let target = fs_paths::long_path(&source);
+        if !target.is_file() {
+            report.missing.push(link);
+            continue;
+        }
+
+        let path = project_root.join(&link.src);
+        if path.symlink_metadata().is_ok() {
+            fs::remove_file(&path)?;
+        }
+        create_link(&target, &path)?;
+        conn.execute(
+            "UPDATE linked_files SET source_path = ?1 WHERE src = ?2",
+            params![source.to_string_lossy(), link.src],
+        ).map_err(|e| AppError::Io(format!("Failed to update link: {}", e)))?;
+        report.relinked += 1;
+    }
+    Ok(report)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn load_links(conn: &Connection) -> Result<Vec<BrokenLink>, AppError> {
+    let mut stmt = conn.prepare("SELECT src, source_path FROM linked_files ORDER BY created_at")
+        .map_err(|e| AppError::Io(format!("Failed to query links: {}", e)))?;
+    let links = stmt.query_map([], |row| Ok(BrokenLink { src: row.get(0)?, source_path: row.get(1)? }))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| AppError::Io(format!("Failed to read links: {}", e)))?;
+    Ok(links)
+}
+
+#[cfg(unix)]
+pub(crate) fn create_link(target: &Path, link: &Path) -> Result<(), AppError> {
+    std::os::unix::fs::symlink(target, link)
+        .map_err(|e| AppError::Io(format!("Failed to link {}: {}", target.display(), e)))
+}
+
+#[cfg(windows)]
+pub(crate) fn create_link(target: &Path, link: &Path) -> Result<(), AppError> {
+    std::os::windows::fs::symlink_file(target, link).map_err(|e| AppError::Io(format!(
+        "Failed to link {}: {} (creating links needs Developer Mode or administrator rights)",
+        target.display(), e
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_break_and_relink() {
+        let project = tempdir().unwrap();
+        let root = project.path();
+        io_sqlite::init_project_sqlite(root, "Footage").unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+
+        let nas = tempdir().unwrap();
+        let old_mount = nas.path().join("old");
+        fs::create_dir_all(old_mount.join("day 1")).unwrap();
+        fs::write(old_mount.join("day 1/take.MOV"), b"footage").unwrap();
+
+        let (_, src) = link_file(&conn, root, &old_mount.join("day 1/take.MOV")).unwrap();
+        assert!(src.starts_with("assets/") && src.ends_with(".mov"));
+        assert_eq!(fs::read(root.join(&src)).unwrap(), b"footage");
+        assert!(root.join(&src).symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(is_linked(root, &src));
+        assert!(!is_linked(root, "assets/other.png"));
+        assert!(broken_links(&conn, root).unwrap().is_empty());
+
+        // The share moves: the link breaks until it's repointed
+        let new_mount = nas.path().join("new");
+        fs::rename(&old_mount, &new_mount).unwrap();
+        let broken = broken_links(&conn, root).unwrap();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].src, src);
+
+        let report = relink(&conn, root, "/elsewhere", &new_mount.to_string_lossy()).unwrap();
+        assert_eq!((report.relinked, report.missing.len()), (0, 0));
+        let report = relink(&conn, root, &old_mount.to_string_lossy(), &nas.path().join("wrong").to_string_lossy()).unwrap();
+        assert_eq!((report.relinked, report.missing.len()), (0, 1));
+
+        let report = relink(&conn, root, &old_mount.to_string_lossy(), &new_mount.to_string_lossy()).unwrap();
+        assert_eq!((report.relinked, report.missing.len()), (1, 0));
+        assert!(broken_links(&conn, root).unwrap().is_empty());
+        assert_eq!(fs::read(root.join(&src)).unwrap(), b"footage");
+    }
+}
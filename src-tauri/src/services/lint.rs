@@ -0,0 +1,335 @@
+//! Board linting: configurable rules that flag common issues before export.
+//!
+//! Rules:
+//! - `empty-node`: node pointing at a missing asset or an asset with no content
+//! - `missing-alt-text`: image without alt text
+//! - `low-resolution`: image smaller than the configured minimum
+//! - `text-too-long`: text longer than its platform's character limit
+//!
+//! The config is read from project settings (`lint`) unless one is passed in.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::models::{Asset, SynniaNode, SynniaProject, ValueType};
+use crate::services::text_asset;
+use ts_rs::TS;
+
+/// Project settings key holding the lint config
+pub const SETTINGS_KEY: &str = "lint";
+
+/// Character limits for common post formats
+const PLATFORM_LIMITS: &[(&str, usize)] = &[
+    ("x", 280),
+    ("twitter", 280),
+    ("threads", 500),
+    ("instagram", 2200),
+    ("tiktok", 2200),
+    ("linkedin", 3000),
+    ("facebook", 63206),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LintConfig {
+    #[serde(default = "default_true")]
+    pub empty_nodes: bool,
+    #[serde(default = "default_true")]
+    pub missing_alt_text: bool,
+    /// Minimum image size in pixels (shorter side); `None` disables the rule
+    #[serde(default = "default_min_resolution")]
+    pub min_image_size: Option<u32>,
+    /// Platform whose character limit applies to all text, unless a text
+    /// asset sets its own `config.extra.platform`
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Fallback limit when no known platform applies
+    #[serde(default)]
+    pub max_text_length: Option<usize>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            empty_nodes: true,
+            missing_alt_text: true,
+            min_image_size: default_min_resolution(),
+            platform: None,
+            max_text_length: None,
+        }
+    }
+}
+
+impl LintConfig {
+    /// Config stored in project settings, or the defaults.
+    pub fn from_project(project: &SynniaProject) -> Self {
+        project.settings.as_ref()
+            .and_then(|s| s.get(SETTINGS_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+}
+
+/// Run all enabled rules over the board. Findings are ordered by severity
+/// (errors first), then by node.
+pub fn lint_project(project: &SynniaProject, config: &LintConfig) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    
+    for node in &project.graph.nodes {
+        let Some(asset_id) = &node.data.asset_id else { continue };
+        
+        let Some(asset) = project.assets.get(asset_id) else {
+            if config.empty_nodes {
+                findings.push(finding("empty-node", Severity::Error, node, asset_id,
+                    format!("\"{}\" points to a missing asset", node.data.title)));
+            }
+            continue;
+        };
+        
+        if config.empty_nodes && is_empty(asset) {
+            findings.push(finding("empty-node", Severity::Warning, node, asset_id,
+                format!("\"{}\" has no content", node.data.title)));
+        }
+        
+        if is_image(node, asset) {
+            lint_image(node, asset, config, &mut findings);
+        } else if let Some(text) = text_asset::text_content(asset) {
+            lint_text(node, asset, text, config, &mut findings);
+        }
+    }
+    
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.node_id.cmp(&b.node_id)));
+    findings
+}
+
+/// Character limit for a platform name, if known
+pub fn platform_limit(platform: &str) -> Option<usize> {
+    let platform = platform.to_lowercase();
+    PLATFORM_LIMITS.iter()
+        .find(|(name, _)| *name == platform)
+        .map(|(_, limit)| *limit)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_min_resolution() -> Option<u32> {
+    Some(512)
+}
+
+fn finding(rule: &str, severity: Severity, node: &SynniaNode, asset_id: &str, message: String) -> LintFinding {
+    LintFinding {
+        rule: rule.to_string(),
+        severity,
+        message,
+        node_id: Some(node.id.clone()),
+        asset_id: Some(asset_id.to_string()),
+    }
+}
+
+fn is_image(node: &SynniaNode, asset: &Asset) -> bool {
+    node.type_ == "image-node" || asset.value.get("src").is_some()
+}
+
+fn is_empty(asset: &Asset) -> bool {
+    if let Some(text) = text_asset::text_content(asset) {
+        return text.trim().is_empty();
+    }
+    match (&asset.value_type, &asset.value) {
+        (_, Value::Null) => true,
+        (ValueType::Array, Value::Array(items)) => items.is_empty(),
+        (ValueType::Record, Value::Object(obj)) => obj.get("src")
+            .map(|src| src.as_str().map_or(true, |s| s.trim().is_empty()))
+            .unwrap_or_else(|| obj.values().all(|v| v.is_null() || v.as_str() == Some(""))),
+        _ => false,
+    }
+}
+
+fn lint_image(node: &SynniaNode, asset: &Asset, config: &LintConfig, findings: &mut Vec<LintFinding>) {
+    if config.missing_alt_text {
+        let alt = asset.value.get("alt").and_then(|v| v.as_str()).unwrap_or("");
+        if alt.trim().is_empty() {
+            findings.push(finding("missing-alt-text", Severity::Warning, node, &asset.id,
+                format!("\"{}\" has no alt text", node.data.title)));
+        }
+    }
+    
+    if let (Some(min), Some((width, height))) = (config.min_image_size, image_dimensions(asset)) {
+        if width.min(height) < min {
+            findings.push(finding("low-resolution", Severity::Warning, node, &asset.id,
+                format!("\"{}\" is {}×{}, below the {}px minimum", node.data.title, width, height, min)));
+        }
+    }
+}
+
+fn lint_text(node: &SynniaNode, asset: &Asset, text: &str, config: &LintConfig, findings: &mut Vec<LintFinding>) {
+    let asset_platform = asset.config.as_ref()
+        .and_then(|c| c.get("extra"))
+        .and_then(|e| e.get("platform"))
+        .and_then(|p| p.as_str());
+    
+    let (platform, limit) = match asset_platform.or(config.platform.as_deref()) {
+        Some(platform) => (Some(platform), platform_limit(platform).or(config.max_text_length)),
+        None => (None, config.max_text_length),
+    };
+    let Some(limit) = limit else { return };
+    
+    let length = text.chars().count();
+    if length > limit {
+        let target = platform.map(|p| format!(" for {}", p)).unwrap_or_default();
+        findings.push(finding("text-too-long", Severity::Error, node, &asset.id,
+            format!("\"{}\" has {} characters, the limit{} is {}", node.data.title, length, target, limit)));
+    }
+}
+
+/// Image size from the value (ImageNode schema), `valueMeta` or `config.meta`
+fn image_dimensions(asset: &Asset) -> Option<(u32, u32)> {
+    let read = |v: Option<&Value>| -> Option<(u32, u32)> {
+        let v = v?;
+        let width = v.get("width")?.as_u64()?;
+        let height = v.get("height")?.as_u64()?;
+        Some((width as u32, height as u32))
+    };
+    read(Some(&asset.value))
+        .or_else(|| read(asset.value_meta.as_ref()))
+        .or_else(|| read(asset.config.as_ref().and_then(|c| c.get("meta"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, Graph, Position, ProjectMeta, SynniaNodeData, Viewport};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn asset(id: &str, value: Value, config: Option<Value>) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value,
+            value_meta: None,
+            config,
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    fn node(id: &str, type_: &str, asset_id: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: type_.to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: Some(asset_id.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn project() -> SynniaProject {
+        let assets = vec![
+            asset("hero", json!({ "src": "assets/hero.png", "width": 1920, "height": 1080, "alt": "Hero" }), None),
+            asset("thumb", json!({ "src": "assets/t.png", "width": 200, "height": 200 }), None),
+            asset("blank", json!({ "content": "  ", "format": "plain" }), None),
+            asset("tweet", json!({ "content": "x".repeat(300), "format": "plain" }), Some(json!({ "extra": { "platform": "twitter" } }))),
+            asset("caption", json!({ "content": "x".repeat(600), "format": "plain" }), None),
+        ];
+        SynniaProject {
+            version: "2".to_string(),
+            meta: ProjectMeta {
+                id: "p".to_string(),
+                name: "Board".to_string(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                thumbnail: None,
+                description: None,
+                author: None,
+            },
+            viewport: Viewport { x: 0.0, y: 0.0, zoom: 1.0 },
+            graph: Graph {
+                nodes: vec![
+                    node("n-hero", "image-node", "hero"),
+                    node("n-thumb", "image-node", "thumb"),
+                    node("n-blank", "text-node", "blank"),
+                    node("n-tweet", "text-node", "tweet"),
+                    node("n-caption", "text-node", "caption"),
+                    node("n-dangling", "text-node", "gone"),
+                ],
+                edges: vec![],
+            },
+            assets: assets.into_iter().map(|a| (a.id.clone(), a)).collect::<HashMap<_, _>>(),
+            settings: None,
+            canvas_id: None,
+        }
+    }
+
+    #[test]
+    fn test_lint_project_defaults() {
+        let findings = lint_project(&project(), &LintConfig::default());
+        let rules: Vec<(&str, &str)> = findings.iter()
+            .map(|f| (f.rule.as_str(), f.node_id.as_deref().unwrap()))
+            .collect();
+        
+        assert_eq!(rules, vec![
+            ("empty-node", "n-dangling"),
+            ("text-too-long", "n-tweet"),
+            ("empty-node", "n-blank"),
+            ("missing-alt-text", "n-thumb"),
+            ("low-resolution", "n-thumb"),
+        ]);
+    }
+
+    #[test]
+    fn test_lint_project_config() {
+        let mut project = project();
+        project.settings = Some(HashMap::from([(
+            SETTINGS_KEY.to_string(),
+            json!({ "missingAltText": false, "minImageSize": null, "platform": "threads", "emptyNodes": false }),
+        )]));
+        
+        let config = LintConfig::from_project(&project);
+        let findings = lint_project(&project, &config);
+        let nodes: Vec<&str> = findings.iter().map(|f| f.node_id.as_deref().unwrap()).collect();
+        
+        // The tweet keeps its own platform limit; the caption now gets the threads limit
+        assert_eq!(nodes, vec!["n-caption", "n-tweet"]);
+        assert_eq!(platform_limit("X"), Some(280));
+    }
+}
@@ -0,0 +1,91 @@
+//! Timing breakdown of opening a project, for diagnosing slow-project
+//! reports.
+//!
+//! Runs the same load as opening the project (`io_sqlite::load_project_staged`)
+//! plus the thumbnail check the canvas depends on, and reports how long each
+//! stage took alongside the project's size, so a report says whether the
+//! database, the node or asset count, or missing thumbnails are to blame.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::services::{compaction, database, io_sqlite, thumbnails};
+use ts_rs::TS;
+
+/// How long one stage of the load took
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTiming {
+    /// `db_open`, `meta`, `nodes`, `edges`, `assets`, `settings` or `thumbnails`
+    pub stage: String,
+    pub ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadProfile {
+    /// Stages in the order they ran
+    pub stages: Vec<StageTiming>,
+    pub total_ms: f64,
+    /// Database file size, WAL included
+    #[ts(type = "number")]
+    pub db_bytes: u64,
+    pub nodes: usize,
+    pub edges: usize,
+    pub assets: usize,
+    /// Image assets whose thumbnail file is missing and would be regenerated
+    pub missing_thumbnails: usize,
+}
+
+/// Load the project at `project_root` the way opening it does and time it.
+/// Nothing is written.
+pub fn profile_load(project_root: &Path) -> Result<LoadProfile, AppError> {
+    let started = Instant::now();
+    let mut stages = Vec::new();
+    let mut record = |stage: &str, elapsed: Duration| stages.push(StageTiming {
+        stage: stage.to_string(),
+        ms: elapsed.as_secs_f64() * 1000.0,
+    });
+
+    let project = io_sqlite::load_project_staged(project_root, &mut record)?;
+
+    let thumbnails_started = Instant::now();
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let missing_thumbnails = thumbnails::pending_tasks(&conn, project_root, false)?.len();
+    record("thumbnails", thumbnails_started.elapsed());
+
+    Ok(LoadProfile {
+        stages,
+        total_ms: started.elapsed().as_secs_f64() * 1000.0,
+        db_bytes: compaction::db_size(&io_sqlite::get_db_path(project_root)),
+        nodes: project.graph.nodes.len(),
+        edges: project.graph.edges.len(),
+        assets: project.assets.len(),
+        missing_thumbnails,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_profile_load() {
+        let dir = tempdir().unwrap();
+        io_sqlite::init_project_sqlite(dir.path(), "Slow board").unwrap();
+
+        let profile = profile_load(dir.path()).unwrap();
+        let stages: Vec<&str> = profile.stages.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(stages, ["db_open", "meta", "nodes", "edges", "assets", "settings", "thumbnails"]);
+        assert!(profile.total_ms >= profile.stages.iter().map(|s| s.ms).sum::<f64>());
+        assert!(profile.db_bytes > 0);
+        assert_eq!((profile.nodes, profile.missing_thumbnails), (0, 0));
+
+        assert!(matches!(profile_load(&dir.path().join("missing")), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,343 @@
+//! Markdown metadata for text assets with `format: "markdown"`.
+//!
+//! Extracted on save and stored in `valueMeta.markdown`:
+//! - outline (headings with level, line and anchor slug)
+//! - word count and reading time
+//! - image and link references, with local paths and `#anchors` checked
+//!   so broken references can be flagged
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::models::Asset;
+use crate::services::text_asset;
+
+/// Average silent reading speed used for reading time
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownMeta {
+    pub outline: Vec<Heading>,
+    pub word_count: usize,
+    pub reading_minutes: usize,
+    pub images: Vec<Reference>,
+    pub links: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// 1-based line number
+    pub line: usize,
+    pub slug: String,
+}
+
+/// An image or link target found in the text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    /// Alt text for images, link text for links
+    pub text: String,
+    pub target: String,
+    /// 1-based line number
+    pub line: usize,
+    /// Local file or in-document anchor that could not be resolved
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub broken: bool,
+}
+
+/// Parse markdown into outline, counts and references. Local references are
+/// resolved against `project_root` when given.
+pub fn extract(content: &str, project_root: Option<&Path>) -> MarkdownMeta {
+    let mut meta = MarkdownMeta::default();
+    let mut fence: Option<&str> = None;
+    let mut prev_paragraph: Option<(usize, &str)> = None;
+    
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw.trim();
+        
+        // Fenced code blocks are skipped entirely
+        if let Some(marker) = fence {
+            if line.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if line.starts_with("```") || line.starts_with("~~~") {
+            fence = Some(&line[..3]);
+            prev_paragraph = None;
+            continue;
+        }
+        
+        // Setext headings: a paragraph line underlined with === or ---
+        if let Some((prev_no, prev_text)) = prev_paragraph {
+            let level = if is_underline(line, '=') {
+                Some(1)
+            } else if is_underline(line, '-') {
+                Some(2)
+            } else {
+                None
+            };
+            if let Some(level) = level {
+                meta.outline.push(heading(level, prev_text, prev_no));
+                prev_paragraph = None;
+                continue;
+            }
+        }
+        
+        if let Some((level, text)) = parse_atx_heading(line) {
+            meta.outline.push(heading(level, text, line_no));
+            meta.word_count += count_words(text);
+            prev_paragraph = None;
+            continue;
+        }
+        
+        let (images, links, plain) = scan_inline(line, line_no);
+        meta.images.extend(images);
+        meta.links.extend(links);
+        meta.word_count += count_words(&plain);
+        
+        prev_paragraph = if line.is_empty() || line.starts_with(['-', '*', '+', '>', '|']) {
+            None
+        } else {
+            Some((line_no, line))
+        };
+    }
+    
+    meta.reading_minutes = meta.word_count.div_ceil(WORDS_PER_MINUTE);
+    
+    let slugs: Vec<String> = meta.outline.iter().map(|h| h.slug.clone()).collect();
+    for reference in meta.images.iter_mut().chain(meta.links.iter_mut()) {
+        reference.broken = is_broken(&reference.target, &slugs, project_root);
+    }
+    
+    meta
+}
+
+/// `valueMeta` for an asset about to be saved: markdown text assets get a
+/// fresh `markdown` entry; other assets have any stale entry removed.
+pub fn derive_value_meta(asset: &Asset, project_root: Option<&Path>) -> Option<Value> {
+    let markdown = text_asset::text_content(asset)
+        .filter(|_| text_asset::text_format(asset) == "markdown")
+        .map(|content| extract(content, project_root));
+    
+    let mut value_meta = asset.value_meta.clone();
+    match (markdown, value_meta.as_mut().and_then(|v| v.as_object_mut())) {
+        (Some(markdown), Some(obj)) => {
+            obj.insert("markdown".to_string(), serde_json::to_value(markdown).ok()?);
+        }
+        (Some(markdown), None) => {
+            value_meta = Some(serde_json::json!({ "markdown": markdown }));
+        }
+        (None, Some(obj)) => {
+            obj.remove("markdown");
+        }
+        (None, None) => {}
+    }
+    value_meta
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn heading(level: u8, text: &str, line: usize) -> Heading {
+    Heading {
+        level,
+        text: text.to_string(),
+        line,
+        slug: slugify(text),
+    }
+}
+
+fn parse_atx_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level as u8, text))
+}
+
+fn is_underline(line: &str, marker: char) -> bool {
+    !line.is_empty() && line.chars().all(|c| c == marker)
+}
+
+/// GitHub-style anchor slug
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+fn count_words(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Extract `![alt](src)` and `[text](href)` from a line. Also returns the
+/// line with those constructs replaced by their text, for word counting.
+fn scan_inline(line: &str, line_no: usize) -> (Vec<Reference>, Vec<Reference>, String) {
+    let mut images = Vec::new();
+    let mut links = Vec::new();
+    let mut plain = String::with_capacity(line.len());
+    let mut rest = line;
+    
+    while let Some(open) = rest.find('[') {
+        let is_image = rest[..open].ends_with('!');
+        let Some((text, target, consumed)) = parse_link(&rest[open..]) else {
+            plain.push_str(&rest[..=open]);
+            rest = &rest[open + 1..];
+            continue;
+        };
+        
+        let prefix_end = if is_image { open - 1 } else { open };
+        plain.push_str(&rest[..prefix_end]);
+        plain.push(' ');
+        plain.push_str(text);
+        plain.push(' ');
+        
+        let reference = Reference {
+            text: text.to_string(),
+            target: target.to_string(),
+            line: line_no,
+            broken: false,
+        };
+        if is_image {
+            images.push(reference);
+        } else {
+            links.push(reference);
+        }
+        rest = &rest[open + consumed..];
+    }
+    plain.push_str(rest);
+    
+    (images, links, plain)
+}
+
+/// Parse `[text](target "title")` at the start of `s`; returns the text,
+/// the target and the number of bytes consumed.
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let close = s.find(']')?;
+    let after = &s[close + 1..];
+    if !after.starts_with('(') {
+        return None;
+    }
+    let end = after.find(')')?;
+    let inner = after[1..end].trim();
+    let target = inner.split_whitespace().next().unwrap_or("");
+    let target = target.trim_start_matches('<').trim_end_matches('>');
+    Some((&s[1..close], target, close + 1 + end + 1))
+}
+
+fn is_broken(target: &str, slugs: &[String], project_root: Option<&Path>) -> bool {
+    if target.is_empty() {
+        return true;
+    }
+    if let Some(anchor) = target.strip_prefix('#') {
+        return !slugs.iter().any(|s| s == anchor);
+    }
+    if target.contains("://") || target.starts_with("//") || target.contains(':') {
+        return false; // remote URL, data: or mailto:
+    }
+    match project_root {
+        Some(root) => {
+            let path = target.split(['#', '?']).next().unwrap_or(target);
+            !root.join(path.trim_start_matches('/')).exists()
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use serde_json::json;
+    use tempfile::tempdir;
+    
+    const DOC: &str = "# Campaign Brief\n\
+        Intro paragraph with [a link](#goals) and [dead](#nowhere).\n\
+        \n\
+        Goals\n\
+        =====\n\
+        ![Hero shot](assets/hero.png \"Hero\")\n\
+        ![Missing](assets/missing.png)\n\
+        \n\
+        ```\n\
+        # not a heading\n\
+        ```\n\
+        ## Timeline ##\n\
+        See [site](https://example.com).\n";
+
+    #[test]
+    fn test_extract_outline_and_counts() {
+        let meta = extract(DOC, None);
+        
+        let outline: Vec<(u8, &str, usize)> = meta.outline.iter().map(|h| (h.level, h.text.as_str(), h.line)).collect();
+        assert_eq!(outline, vec![(1, "Campaign Brief", 1), (1, "Goals", 4), (2, "Timeline", 12)]);
+        assert_eq!(meta.outline[0].slug, "campaign-brief");
+        
+        // Campaign Brief (2) + intro (7) + Goals (1) + alts (3) + Timeline (1) + See site (2)
+        assert_eq!(meta.word_count, 16);
+        assert_eq!(meta.reading_minutes, 1);
+        
+        assert_eq!(meta.images.len(), 2);
+        assert_eq!(meta.images[0].target, "assets/hero.png");
+        assert_eq!(meta.links.len(), 3);
+    }
+
+    #[test]
+    fn test_reference_integrity() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("assets/hero.png"), b"png").unwrap();
+        
+        let meta = extract(DOC, Some(dir.path()));
+        let broken: Vec<&str> = meta.images.iter().chain(meta.links.iter())
+            .filter(|r| r.broken)
+            .map(|r| r.target.as_str())
+            .collect();
+        assert_eq!(broken, vec!["assets/missing.png", "#nowhere"]);
+    }
+
+    #[test]
+    fn test_derive_value_meta() {
+        let mut asset = Asset {
+            id: "doc".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "content": "# Title\nBody", "format": "markdown" }),
+            value_meta: Some(json!({ "length": 12 })),
+            config: None,
+            sys: AssetSysMetadata {
+                name: "Doc".to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        };
+        
+        let meta = derive_value_meta(&asset, None).unwrap();
+        assert_eq!(meta["length"], 12);
+        assert_eq!(meta["markdown"]["wordCount"], 2);
+        
+        asset.value_meta = Some(meta);
+        asset.value["format"] = json!("plain");
+        let meta = derive_value_meta(&asset, None).unwrap();
+        assert!(meta.get("markdown").is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Server-side minimap.
+//!
+//! Rasterizes the board (node rectangles, plus thumbnails where an image node
+//! is big enough to show one) into a small PNG, so the frontend minimap on
+//! huge projects is a single image instead of thousands of SVG rects. The
+//! result is cached per project and size until the graph changes.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use base64::Engine;
+use image::{imageops, imageops::FilterType, DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::{SynniaNode, SynniaProject};
+use crate::services::{hash, imaging};
+use ts_rs::TS;
+
+/// Largest minimap side accepted (px)
+const MAX_SIZE: u32 = 2048;
+/// Size assumed for nodes the frontend hasn't measured yet
+const DEFAULT_NODE_SIZE: (f64, f64) = (200.0, 150.0);
+/// Image nodes smaller than this on the minimap are drawn as plain rects (px)
+const MIN_THUMBNAIL_SIZE: u32 = 12;
+/// Padding around the board bounds (px on the minimap)
+const PADDING: f64 = 4.0;
+
+const BACKGROUND: Rgba<u8> = Rgba([24, 24, 27, 255]);
+const GROUP_FILL: Rgba<u8> = Rgba([39, 39, 42, 255]);
+const GROUP_BORDER: Rgba<u8> = Rgba([82, 82, 91, 255]);
+
+/// (project root, width, height) -> (graph fingerprint, minimap)
+type Cache = HashMap<(PathBuf, u32, u32), (String, Minimap)>;
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Minimap {
+    /// PNG as a data URL
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    /// Board area shown, in board coordinates
+    pub bounds: Bounds,
+    /// Minimap pixels per board unit
+    pub scale: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Minimap of `project`, from the cache when the graph hasn't changed.
+pub fn render_minimap(project_root: &Path, project: &SynniaProject, width: u32, height: u32) -> Result<Minimap, AppError> {
+    if width == 0 || height == 0 || width > MAX_SIZE || height > MAX_SIZE {
+        return Err(AppError::Unknown(format!("Minimap size must be between 1 and {} px", MAX_SIZE)));
+    }
+    
+    let key = (project_root.to_path_buf(), width, height);
+    let fingerprint = fingerprint(project);
+    let mut cache = CACHE.lock().map_err(|_| AppError::Unknown("Minimap cache poisoned".to_string()))?;
+    if let Some((cached, minimap)) = cache.as_ref().and_then(|c| c.get(&key)) {
+        if *cached == fingerprint {
+            return Ok(minimap.clone());
+        }
+    }
+    
+    let minimap = rasterize(project_root, project, width, height)?;
+    cache.get_or_insert_with(HashMap::new).insert(key, (fingerprint, minimap.clone()));
+    Ok(minimap)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn rasterize(project_root: &Path, project: &SynniaProject, width: u32, height: u32) -> Result<Minimap, AppError> {
+    let rects = absolute_rects(&project.graph.nodes);
+    let bounds = board_bounds(&rects);
+    let scale = ((width as f64 - 2.0 * PADDING) / bounds.width)
+        .min((height as f64 - 2.0 * PADDING) / bounds.height)
+        .max(f64::MIN_POSITIVE);
+    // Center the board in the image
+    let offset_x = (width as f64 - bounds.width * scale) / 2.0;
+    let offset_y = (height as f64 - bounds.height * scale) / 2.0;
+    let to_pixels = |r: &Bounds| -> (i64, i64, u32, u32) {
+        let x = (offset_x + (r.x - bounds.x) * scale).floor() as i64;
+        let y = (offset_y + (r.y - bounds.y) * scale).floor() as i64;
+        let w = (r.width * scale).round().max(1.0) as u32;
+        let h = (r.height * scale).round().max(1.0) as u32;
+        (x, y, w, h)
+    };
+    
+    let parents: HashSet<&str> = project.graph.nodes.iter()
+        .filter_map(|n| n.parent_id.as_deref())
+        .collect();
+    let mut canvas = RgbaImage::from_pixel(width, height, BACKGROUND);
+    
+    // Containers first so their children are drawn on top
+    for (node, rect) in project.graph.nodes.iter().zip(&rects) {
+        if parents.contains(node.id.as_str()) {
+            let (x, y, w, h) = to_pixels(rect);
+            fill_rect(&mut canvas, x, y, w, h, GROUP_FILL);
+            outline_rect(&mut canvas, x, y, w, h, GROUP_BORDER);
+        }
+    }
+    for (node, rect) in project.graph.nodes.iter().zip(&rects) {
+        if parents.contains(node.id.as_str()) {
+            continue;
+        }
+        let (x, y, w, h) = to_pixels(rect);
+        let thumbnail = (w >= MIN_THUMBNAIL_SIZE && h >= MIN_THUMBNAIL_SIZE)
+            .then(|| load_thumbnail(project_root, project, node))
+            .flatten();
+        match thumbnail {
+            Some(image) => {
+                let image = image.resize_to_fill(w, h, FilterType::Triangle).to_rgba8();
+                imageops::overlay(&mut canvas, &image, x, y);
+            }
+            None => fill_rect(&mut canvas, x, y, w, h, node_color(node)),
+        }
+    }
+    
+    let mut png = Vec::new();
+    canvas.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::Unknown(format!("Failed to encode minimap: {}", e)))?;
+    
+    Ok(Minimap {
+        image: format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png)),
+        width,
+        height,
+        bounds,
+        scale,
+    })
+}
+
+/// Everything the picture depends on
+fn fingerprint(project: &SynniaProject) -> String {
+    let mut text = String::new();
+    for node in &project.graph.nodes {
+        let src = node.data.asset_id.as_ref()
+            .and_then(|id| project.assets.get(id))
+            .and_then(|a| a.value.get("src"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("");
+        text.push_str(&format!(
+            "{}|{}|{}|{}|{:?}|{:?}|{:?}|{}\n",
+            node.id, node.type_, node.position.x, node.position.y, node.width, node.height, node.parent_id, src,
+        ));
+    }
+    hash::compute_content_hash(&text)
+}
+
+/// Board rectangles; child positions are relative to their parent
+fn absolute_rects(nodes: &[SynniaNode]) -> Vec<Bounds> {
+    let by_id: HashMap<&str, &SynniaNode> = nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    nodes.iter().map(|node| {
+        let (mut x, mut y) = (node.position.x, node.position.y);
+        let mut parent = node.parent_id.as_deref();
+        let mut depth = 0;
+        while let Some(p) = parent.and_then(|id| by_id.get(id)) {
+            x += p.position.x;
+            y += p.position.y;
+            parent = p.parent_id.as_deref();
+            depth += 1;
+            // Guard against parent cycles in corrupt data
+            if depth > nodes.len() {
+                break;
+            }
+        }
+        Bounds {
+            x,
+            y,
+            width: node.width.unwrap_or(DEFAULT_NODE_SIZE.0),
+            height: node.height.unwrap_or(DEFAULT_NODE_SIZE.1),
+        }
+    }).collect()
+}
+
+fn board_bounds(rects: &[Bounds]) -> Bounds {
+    if rects.is_empty() {
+        return Bounds { x: 0.0, y: 0.0, width: 1.0, height: 1.0 };
+    }
+    let min_x = rects.iter().map(|r| r.x).fold(f64::INFINITY, f64::min);
+    let min_y = rects.iter().map(|r| r.y).fold(f64::INFINITY, f64::min);
+    let max_x = rects.iter().map(|r| r.x + r.width).fold(f64::NEG_INFINITY, f64::max);
+    let max_y = rects.iter().map(|r| r.y + r.height).fold(f64::NEG_INFINITY, f64::max);
+    Bounds {
+        x: min_x,
+        y: min_y,
+        width: (max_x - min_x).max(1.0),
+        height: (max_y - min_y).max(1.0),
+    }
+}
+
+/// The asset's `thumb_<id>.jpg` if present, otherwise the image itself
+fn load_thumbnail(project_root: &Path, project: &SynniaProject, node: &SynniaNode) -> Option<DynamicImage> {
+    let asset = project.assets.get(node.data.asset_id.as_ref()?)?;
+    let src = asset.value.get("src")?.as_str()?;
+    let path = imaging::resolve_image_source(project_root, src)?;
+    let thumb = path.file_stem()
+        .map(|stem| path.with_file_name(format!("thumb_{}.jpg", stem.to_string_lossy())))
+        .filter(|p| p.exists());
+    image::open(thumb.unwrap_or(path)).ok()
+}
+
+fn node_color(node: &SynniaNode) -> Rgba<u8> {
+    match node.type_.as_str() {
+        "image-node" | "gallery-node" => Rgba([96, 165, 250, 255]),
+        "text-node" => Rgba([161, 161, 170, 255]),
+        "recipe-node" => Rgba([192, 132, 252, 255]),
+        "form-node" | "selector-node" => Rgba([52, 211, 153, 255]),
+        "table-node" | "queue-node" => Rgba([251, 191, 36, 255]),
+        _ => Rgba([113, 113, 122, 255]),
+    }
+}
+
+fn fill_rect(canvas: &mut RgbaImage, x: i64, y: i64, w: u32, h: u32, color: Rgba<u8>) {
+    let (cw, ch) = (canvas.width() as i64, canvas.height() as i64);
+    for py in y.max(0)..(y + h as i64).min(ch) {
+        for px in x.max(0)..(x + w as i64).min(cw) {
+            canvas.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
+
+fn outline_rect(canvas: &mut RgbaImage, x: i64, y: i64, w: u32, h: u32, color: Rgba<u8>) {
+    fill_rect(canvas, x, y, w, 1, color);
+    fill_rect(canvas, x, y + h as i64 - 1, w, 1, color);
+    fill_rect(canvas, x, y, 1, h, color);
+    fill_rect(canvas, x + w as i64 - 1, y, 1, h, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNodeData};
+    use crate::services::io_sqlite;
+    use tempfile::tempdir;
+
+    fn node(id: &str, type_: &str, x: f64, y: f64, parent: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: type_.to_string(),
+            position: Position { x, y },
+            width: Some(100.0),
+            height: Some(100.0),
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_minimap() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Board").unwrap();
+        project.graph.nodes = vec![
+            node("group", "group", 0.0, 0.0, None),
+            node("child", "text-node", 900.0, 400.0, Some("group")),
+            node("recipe", "recipe-node", -1000.0, 0.0, None),
+        ];
+        
+        let minimap = render_minimap(dir.path(), &project, 200, 100).unwrap();
+        assert_eq!(minimap.bounds, Bounds { x: -1000.0, y: 0.0, width: 2000.0, height: 500.0 });
+        assert!(minimap.image.starts_with("data:image/png;base64,"));
+        
+        let cached = render_minimap(dir.path(), &project, 200, 100).unwrap();
+        assert_eq!(cached.image, minimap.image);
+        
+        project.graph.nodes.pop();
+        let moved = render_minimap(dir.path(), &project, 200, 100).unwrap();
+        assert_eq!(moved.bounds.x, 0.0, "cache invalidated when the graph changes");
+        
+        assert!(render_minimap(dir.path(), &project, 0, 100).is_err());
+    }
+}
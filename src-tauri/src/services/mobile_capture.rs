@@ -0,0 +1,324 @@
+//! Capture from a phone: photograph sketches or jot notes straight into the
+//! project inbox.
+//!
+//! `get_pairing_qr` starts a small second server on the machine's LAN
+//! address and returns a QR code of `http://<lan-ip>:<port>/mobile-capture?token=…`.
+//! Scanning it opens an upload page in the phone's browser; photos and text
+//! it sends land in the "Inbox" group like browser clippings. The main local
+//! server stays on loopback: this one only serves the capture page and its
+//! upload route, and takes a pairing token of its own that is dropped when
+//! capture stops or the app quits.
+
+use std::net::{IpAddr, SocketAddr, TcpListener, UdpSocket};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use actix_web::{get, post, web, App, Error, HttpRequest, HttpResponse, HttpServer};
+use actix_web::dev::ServerHandle;
+use actix_web::http::header;
+use qrcode::QrCode;
+use qrcode::render::svg;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use ts_rs::TS;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::services::clip;
+use crate::services::permissions::{Actor, Capability, PermissionGate};
+use super::file_server;
+
+/// What the desktop shows to pair a phone
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingQr {
+    /// Address of the capture page, token included
+    pub url: String,
+    /// QR code of `url` as an SVG document
+    pub svg: String,
+}
+
+/// Running capture server, if any
+#[derive(Default)]
+pub struct MobileCapture {
+    session: Mutex<Option<Session>>,
+}
+
+struct Session {
+    addr: SocketAddr,
+    token: String,
+    handle: ServerHandle,
+}
+
+/// State of the capture server's routes
+struct CaptureState {
+    token: String,
+    current_project_path: Arc<Mutex<Option<String>>>,
+    permissions: Arc<PermissionGate>,
+    app: AppHandle,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+impl MobileCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the capture server on the current LAN address (or keep the
+    /// running one) and return its pairing QR code.
+    pub fn pair(
+        &self,
+        current_project_path: Arc<Mutex<Option<String>>>,
+        permissions: Arc<PermissionGate>,
+        app: AppHandle,
+    ) -> Result<PairingQr, AppError> {
+        let ip = lan_address()?;
+        let mut session = self.session.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+
+        // Moved to another network since pairing: the old address is gone
+        if session.as_ref().is_some_and(|s| s.addr.ip() != ip) {
+            if let Some(old) = session.take() {
+                tauri::async_runtime::spawn(old.handle.stop(true));
+            }
+        }
+        if session.is_none() {
+            *session = Some(start(ip, current_project_path, permissions, app)?);
+        }
+        let Some(session) = session.as_ref() else {
+            return Err(AppError::Unknown("Capture server failed to start".to_string()));
+        };
+
+        let url = capture_url(session.addr, &session.token);
+        Ok(PairingQr { svg: render_qr(&url)?, url })
+    }
+
+    /// Stop the capture server. Paired phones need a new QR code afterwards.
+    pub fn stop(&self) -> Result<(), AppError> {
+        let session = self.session.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?
+            .take();
+        if let Some(session) = session {
+            tauri::async_runtime::spawn(session.handle.stop(true));
+        }
+        Ok(())
+    }
+}
+
+/// The upload page, for phone browsers
+#[get("/mobile-capture")]
+async fn capture_page(query: web::Query<TokenQuery>, data: web::Data<CaptureState>) -> Result<HttpResponse, Error> {
+    authorize(&data, query.token.as_deref())?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .body(CAPTURE_PAGE))
+}
+
+/// Import the photos and text sent by the page into the inbox group
+#[post("/mobile-capture")]
+async fn receive_capture(
+    req: HttpRequest,
+    body: web::Bytes,
+    query: web::Query<TokenQuery>,
+    data: web::Data<CaptureState>,
+) -> Result<HttpResponse, Error> {
+    authorize(&data, file_server::bearer_token(&req).or(query.token.as_deref()))?;
+
+    let project_root = project_root(&data)
+        .ok_or_else(|| actix_web::error::ErrorConflict("No project is open on the desktop"))?;
+
+    let content_type = req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !content_type.starts_with("multipart/form-data") {
+        return Err(actix_web::error::ErrorBadRequest("Expected multipart/form-data"));
+    }
+    let items = clip::parse_multipart(content_type, &body)
+        .map_err(|e| actix_web::error::ErrorBadRequest(e.to_string()))?;
+    if items.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("Nothing to capture"));
+    }
+
+    let summary = format!("Import {} capture{} from a phone", items.len(), if items.len() == 1 { "" } else { "s" });
+    data.permissions
+        .authorize(&data.app, &project_root, Actor::Clip, Capability::ImportAssets, summary, Vec::new())
+        .await
+        .map_err(|e| actix_web::error::ErrorForbidden(e.to_string()))?;
+
+    let imported = web::block(move || clip::import_clips(&project_root, items))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+    // Let the open canvas merge the new nodes before its next autosave
+    let _ = events::emit(&data.app, AppEvent::ClipImported(imported.clone()));
+
+    Ok(HttpResponse::Ok().json(imported))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn start(
+    ip: IpAddr,
+    current_project_path: Arc<Mutex<Option<String>>>,
+    permissions: Arc<PermissionGate>,
+    app: AppHandle,
+) -> Result<Session, AppError> {
+    let listener = TcpListener::bind((ip, 0))
+        .map_err(|e| AppError::Network(format!("Failed to listen on {}: {}", ip, e)))?;
+    let addr = listener.local_addr()?;
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
+    let state = web::Data::new(CaptureState {
+        token: token.clone(),
+        current_project_path,
+        permissions,
+        app,
+    });
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .app_data(web::PayloadConfig::new(clip::MAX_CLIP_SIZE))
+            .service(capture_page)
+            .service(receive_capture)
+    })
+    .workers(1)
+    .listen(listener)
+    .map_err(|e| AppError::Network(format!("Failed to start capture server: {}", e)))?
+    .run();
+
+    let handle = server.handle();
+    tauri::async_runtime::spawn(server);
+    println!("[MobileCapture] Listening on http://{}/mobile-capture", addr);
+
+    Ok(Session { addr, token, handle })
+}
+
+/// Address other devices on the network reach this machine at: the one of
+/// the interface that routes to the internet. Connecting a UDP socket sends
+/// nothing.
+fn lan_address() -> Result<IpAddr, AppError> {
+    let no_network = || AppError::Network("Not connected to a network a phone could reach".to_string());
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket.connect(("192.0.2.1", 80)).map_err(|_| no_network())?;
+    let ip = socket.local_addr()?.ip();
+    if ip.is_loopback() || ip.is_unspecified() {
+        return Err(no_network());
+    }
+    Ok(ip)
+}
+
+fn capture_url(addr: SocketAddr, token: &str) -> String {
+    format!("http://{}/mobile-capture?token={}", addr, token)
+}
+
+fn render_qr(text: &str) -> Result<String, AppError> {
+    let code = QrCode::new(text.as_bytes())
+        .map_err(|e| AppError::Unknown(format!("Failed to encode QR code: {}", e)))?;
+    Ok(code.render::<svg::Color>().min_dimensions(240, 240).build())
+}
+
+fn authorize(data: &CaptureState, provided: Option<&str>) -> Result<(), Error> {
+    if provided == Some(data.token.as_str()) {
+        Ok(())
+    } else {
+        Err(actix_web::error::ErrorUnauthorized("Scan the pairing code again"))
+    }
+}
+
+fn project_root(data: &CaptureState) -> Option<PathBuf> {
+    let path = data.current_project_path.lock().ok()?.clone()?;
+    Some(file_server::project_root_of(PathBuf::from(path)))
+}
+
+/// Self-contained so it works offline on the phone; the token stays in the
+/// page's own URL and goes along with each upload.
+const CAPTURE_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Synnia capture</title>
+<style>
+  body { font-family: -apple-system, system-ui, sans-serif; margin: 0; padding: 20px; background: #111; color: #eee; }
+  h1 { font-size: 20px; margin: 0 0 16px; }
+  label.button { display: block; padding: 18px; margin-bottom: 12px; border-radius: 12px; background: #2d6cdf; color: #fff; text-align: center; font-size: 17px; }
+  label.secondary { background: #333; }
+  input[type=file] { display: none; }
+  textarea { box-sizing: border-box; width: 100%; min-height: 90px; padding: 12px; border-radius: 12px; border: 1px solid #444; background: #1b1b1b; color: #eee; font: inherit; }
+  button { width: 100%; padding: 16px; margin-top: 12px; border: 0; border-radius: 12px; background: #eee; color: #111; font-size: 17px; }
+  button:disabled { opacity: 0.5; }
+  #picked, #status { margin: 12px 0; color: #aaa; }
+</style>
+</head>
+<body>
+<h1>Send to Synnia inbox</h1>
+<label class="button">Take photo<input id="camera" type="file" accept="image/*" capture="environment"></label>
+<label class="button secondary">Choose from library<input id="library" type="file" accept="image/*" multiple></label>
+<div id="picked"></div>
+<textarea id="text" placeholder="Note (optional)"></textarea>
+<button id="send" disabled>Send</button>
+<div id="status"></div>
+<script>
+  const token = new URLSearchParams(location.search).get("token") || "";
+  let files = [];
+  const picked = document.getElementById("picked");
+  const status = document.getElementById("status");
+  const send = document.getElementById("send");
+  const text = document.getElementById("text");
+  const refresh = () => {
+    picked.textContent = files.length ? files.length + " photo" + (files.length === 1 ? "" : "s") + " ready" : "";
+    send.disabled = !files.length && !text.value.trim();
+  };
+  for (const id of ["camera", "library"]) {
+    document.getElementById(id).addEventListener("change", (e) => {
+      files = files.concat(Array.from(e.target.files));
+      e.target.value = "";
+      refresh();
+    });
+  }
+  text.addEventListener("input", refresh);
+  send.addEventListener("click", async () => {
+    const form = new FormData();
+    files.forEach((file, i) => form.append("file" + i, file, file.name || "capture-" + i + ".jpg"));
+    if (text.value.trim()) form.append("text", text.value.trim());
+    send.disabled = true;
+    status.textContent = "Sending…";
+    try {
+      const res = await fetch("/mobile-capture", { method: "POST", headers: { "Authorization": "Bearer " + token }, body: form });
+      if (!res.ok) throw new Error(await res.text() || res.statusText);
+      const items = await res.json();
+      status.textContent = "Sent " + items.length + " item" + (items.length === 1 ? "" : "s") + " to the inbox.";
+      files = [];
+      text.value = "";
+    } catch (e) {
+      status.textContent = "Failed: " + e.message;
+    }
+    refresh();
+  });
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairing_url() {
+        let addr: SocketAddr = "192.168.1.20:51234".parse().unwrap();
+        let url = capture_url(addr, "abc");
+        assert_eq!(url, "http://192.168.1.20:51234/mobile-capture?token=abc");
+
+        let svg = render_qr(&url).unwrap();
+        assert!(svg.starts_with("<?xml") && svg.contains("<svg"));
+    }
+}
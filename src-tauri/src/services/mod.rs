@@ -1,7 +1,97 @@
+pub mod activity;
 pub mod agent_service;
+pub mod asset_rename;
+pub mod asset_stream;
+pub mod asset_usage;
+pub mod audio_trim;
+pub mod audit;
+pub mod auto_tag;
+pub mod backup;
+pub mod batch_import;
+pub mod blocking;
+pub mod brand;
+pub mod budget;
+pub mod bulk_edit;
+pub mod cancellation;
+pub mod canvases;
+pub mod clip;
+pub mod cloud_sync;
+pub mod file_history;
 pub mod file_server;
+pub mod folder_import;
+pub mod fs_paths;
+pub mod comparison;
+pub mod command_script;
+pub mod compaction;
+pub mod components;
+pub mod connectors;
+pub mod content_type;
+pub mod custom_fields;
 pub mod database;
+pub mod disk_space;
+pub mod download;
+pub mod export;
+pub mod faces;
 pub mod hash;
+pub mod generation;
+pub mod generation_grid;
+pub mod graph_analysis;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod history;
+pub mod image_edit;
+pub mod image_maps;
+pub mod ignore_rules;
+pub mod imaging;
+pub mod interrogate;
+pub mod markdown;
 pub mod metadata;
-pub mod io_sqlite;
\ No newline at end of file
+pub mod minimap;
+pub mod mobile_capture;
+pub mod model_manager;
+pub mod net_guard;
+pub mod node_locks;
+pub mod org_config;
+pub mod pdf_export;
+pub mod permissions;
+pub mod pipelines;
+pub mod planning;
+pub mod prompt_guard;
+pub mod provider_health;
+pub mod recipes;
+pub mod references;
+pub mod reminders;
+pub mod rest_api;
+pub mod run_comparison;
+pub mod safety;
+pub mod settings_bundle;
+pub mod share_bundle;
+pub mod smart_collections;
+pub mod spellcheck;
+pub mod subgraph;
+pub mod tags;
+pub mod tasklist;
+pub mod template_sync;
+pub mod text_asset;
+pub mod thumbnails;
+pub mod time_tracking;
+pub mod timeline;
+pub mod tracker_export;
+pub mod translation;
+pub mod upscale;
+pub mod url_scheme;
+pub mod usages;
+pub mod validation;
+pub mod video_proxy;
+pub mod viewport_bookmarks;
+pub mod io_sqlite;
+pub mod job_queue;
+pub mod licenses;
+pub mod lint;
+pub mod linked_files;
+pub mod load_profile;
+pub mod stock;
+#[cfg(debug_assertions)]
+pub mod stress_fixtures;
+pub mod watermark;
+pub mod webhook_share;
\ No newline at end of file
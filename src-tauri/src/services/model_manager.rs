@@ -0,0 +1,385 @@
+//! Local models (ONNX and GGUF) for the features that run on this machine:
+//! background removal, embeddings, speech-to-text (whisper), upscaling,
+//! face detection and depth/segmentation maps.
+//!
+//! Models are shared by all projects and live in `<app data>/models/<kind>/`,
+//! listed in `models/models.json`. A download names the SHA-256 it must
+//! match; anything else is deleted instead of installed. Downloads go through
+//! the download manager, so they resume, report `download:progress` and can
+//! be cancelled with `cancel_download`.
+//!
+//! Features use the newest installed model of their kind unless their
+//! `SYNNIA_*_MODEL` variable points somewhere else.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+use crate::error::AppError;
+use crate::services::{disk_space, hash};
+
+/// Folder of the models under the app data directory
+pub const MODELS_DIR: &str = "models";
+
+/// Largest accepted model file (32 GiB)
+pub const MAX_MODEL_BYTES: u64 = 32 * 1024 * 1024 * 1024;
+
+const MANIFEST_FILE: &str = "models.json";
+
+static MODELS_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Serializes read-modify-write of the manifest
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// What a model is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModelKind {
+    BackgroundRemoval,
+    Embeddings,
+    /// Speech-to-text
+    Whisper,
+    Upscale,
+    FaceDetection,
+    Depth,
+    Segmentation,
+}
+
+impl ModelKind {
+    pub const ALL: [ModelKind; 7] = [
+        ModelKind::BackgroundRemoval,
+        ModelKind::Embeddings,
+        ModelKind::Whisper,
+        ModelKind::Upscale,
+        ModelKind::FaceDetection,
+        ModelKind::Depth,
+        ModelKind::Segmentation,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelKind::BackgroundRemoval => "background-removal",
+            ModelKind::Embeddings => "embeddings",
+            ModelKind::Whisper => "whisper",
+            ModelKind::Upscale => "upscale",
+            ModelKind::FaceDetection => "face-detection",
+            ModelKind::Depth => "depth",
+            ModelKind::Segmentation => "segmentation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFormat {
+    Onnx,
+    Gguf,
+}
+
+impl ModelFormat {
+    /// Format of a file name (or URL path), by its extension
+    pub fn detect(name: &str) -> Option<Self> {
+        let (_, ext) = name.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "onnx" => Some(ModelFormat::Onnx),
+            "gguf" => Some(ModelFormat::Gguf),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ModelFormat::Onnx => "onnx",
+            ModelFormat::Gguf => "gguf",
+        }
+    }
+}
+
+/// A model to download
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownload {
+    pub kind: ModelKind,
+    /// Shown in the model list; defaults to the file name
+    pub name: Option<String>,
+    /// `.onnx` or `.gguf` file over http(s)
+    pub url: String,
+    /// Expected SHA-256 of the file, hex
+    pub sha256: String,
+}
+
+/// An installed model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledModel {
+    pub id: String,
+    pub name: String,
+    pub kind: ModelKind,
+    pub format: ModelFormat,
+    /// File, relative to the models folder
+    pub file: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    pub sha256: String,
+    pub source_url: String,
+    #[ts(type = "number")]
+    pub installed_at: i64,
+}
+
+/// Disk used by the models of one kind
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelKindUsage {
+    pub kind: ModelKind,
+    pub count: usize,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+/// Disk used by installed models
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiskUsage {
+    /// Models folder
+    pub path: String,
+    #[ts(type = "number")]
+    pub total: u64,
+    /// Kinds with at least one model
+    pub kinds: Vec<ModelKindUsage>,
+    /// Free space on the models' disk, if known
+    #[ts(type = "number | null")]
+    pub available: Option<u64>,
+}
+
+/// A download checked and ready to start
+#[derive(Debug, Clone)]
+pub struct PreparedDownload {
+    pub id: String,
+    pub format: ModelFormat,
+    /// Where the download manager writes the file
+    pub dest: PathBuf,
+}
+
+/// Remember the models folder for `installed` (called once at startup).
+pub fn init(app: &AppHandle) {
+    if let Ok(root) = models_dir(app) {
+        let _ = MODELS_ROOT.set(root);
+    }
+}
+
+pub fn models_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path().app_data_dir()
+        .map(|dir| dir.join(MODELS_DIR))
+        .map_err(|e| AppError::Io(format!("Failed to resolve app data dir: {}", e)))
+}
+
+/// Installed models, newest first. Entries whose file is gone are dropped.
+pub fn list(root: &Path) -> Result<Vec<InstalledModel>, AppError> {
+    let mut models: Vec<_> = read_manifest(root)?
+        .into_iter()
+        .filter(|m| root.join(&m.file).is_file())
+        .collect();
+    models.sort_by(|a, b| b.installed_at.cmp(&a.installed_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(models)
+}
+
+/// File of the newest installed model of `kind`, for features to fall back on.
+pub fn installed(kind: ModelKind) -> Option<PathBuf> {
+    installed_in(MODELS_ROOT.get()?, kind)
+}
+
+pub fn installed_in(root: &Path, kind: ModelKind) -> Option<PathBuf> {
+    list(root).ok()?
+        .into_iter()
+        .find(|m| m.kind == kind)
+        .map(|m| root.join(m.file))
+}
+
+/// The installed model with the checksum of `request`, if any
+pub fn find_existing(root: &Path, request: &ModelDownload) -> Result<Option<InstalledModel>, AppError> {
+    let sha256 = request.sha256.trim().to_ascii_lowercase();
+    Ok(list(root)?.into_iter().find(|m| m.kind == request.kind && m.sha256 == sha256))
+}
+
+/// Check `request` and pick the file it downloads to.
+pub fn prepare(root: &Path, request: &ModelDownload) -> Result<PreparedDownload, AppError> {
+    let sha256 = request.sha256.trim();
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput("sha256 must be 64 hex characters".to_string()));
+    }
+    let url = reqwest::Url::parse(&request.url)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| AppError::InvalidInput(format!("Not an http(s) URL: {}", request.url)))?;
+    let format = ModelFormat::detect(url.path())
+        .ok_or_else(|| AppError::InvalidInput("Models must be .onnx or .gguf files".to_string()))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let dir = root.join(request.kind.as_str());
+    std::fs::create_dir_all(&dir)?;
+    let dest = dir.join(format!("{}.{}", id, format.extension()));
+    Ok(PreparedDownload { id, format, dest })
+}
+
+/// Verify the downloaded file against the request's checksum and add it to
+/// the manifest. A mismatching file is deleted.
+pub fn install(root: &Path, request: &ModelDownload, prepared: &PreparedDownload, source_name: &str) -> Result<InstalledModel, AppError> {
+    let expected = request.sha256.trim().to_ascii_lowercase();
+    let actual = hash::compute_file_hash(&prepared.dest)?;
+    if actual != expected {
+        let _ = std::fs::remove_file(&prepared.dest);
+        return Err(AppError::InvalidInput(format!(
+            "Checksum mismatch: expected {}, got {}", expected, actual
+        )));
+    }
+
+    let file = prepared.dest.strip_prefix(root)
+        .map_err(|_| AppError::Unknown("Model downloaded outside the models folder".to_string()))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let name = request.name.as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(source_name)
+        .to_string();
+    let model = InstalledModel {
+        id: prepared.id.clone(),
+        name,
+        kind: request.kind,
+        format: prepared.format,
+        file,
+        size: std::fs::metadata(&prepared.dest)?.len(),
+        sha256: expected,
+        source_url: request.url.clone(),
+        installed_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let _guard = MANIFEST_LOCK.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    let mut manifest = read_manifest(root)?;
+    manifest.push(model.clone());
+    write_manifest(root, &manifest)?;
+    Ok(model)
+}
+
+/// Delete a model and its file. Returns false if it wasn't installed.
+pub fn delete(root: &Path, model_id: &str) -> Result<bool, AppError> {
+    let _guard = MANIFEST_LOCK.lock().map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?;
+    let mut manifest = read_manifest(root)?;
+    let Some(index) = manifest.iter().position(|m| m.id == model_id) else {
+        return Ok(false);
+    };
+    let model = manifest.remove(index);
+    match std::fs::remove_file(root.join(&model.file)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    write_manifest(root, &manifest)?;
+    Ok(true)
+}
+
+pub fn disk_usage(root: &Path) -> Result<ModelDiskUsage, AppError> {
+    let models = list(root)?;
+    let kinds: Vec<_> = ModelKind::ALL.iter()
+        .map(|kind| {
+            let of_kind = models.iter().filter(|m| m.kind == *kind);
+            ModelKindUsage { kind: *kind, count: of_kind.clone().count(), size: of_kind.map(|m| m.size).sum() }
+        })
+        .filter(|usage| usage.count > 0)
+        .collect();
+    Ok(ModelDiskUsage {
+        path: root.to_string_lossy().to_string(),
+        total: kinds.iter().map(|k| k.size).sum(),
+        kinds,
+        available: disk_space::available_space(root),
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn read_manifest(root: &Path) -> Result<Vec<InstalledModel>, AppError> {
+    match std::fs::read(root.join(MANIFEST_FILE)) {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_manifest(root: &Path, models: &[InstalledModel]) -> Result<(), AppError> {
+    std::fs::create_dir_all(root)?;
+    let tmp = root.join(format!("{}.tmp", MANIFEST_FILE));
+    std::fs::write(&tmp, serde_json::to_vec_pretty(models)?)?;
+    std::fs::rename(tmp, root.join(MANIFEST_FILE))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn request(sha256: &str) -> ModelDownload {
+        ModelDownload {
+            kind: ModelKind::Upscale,
+            name: None,
+            url: "https://example.com/models/esrgan-x4.onnx".to_string(),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_rejects_bad_requests() {
+        let dir = tempdir().unwrap();
+        let sha = "a".repeat(64);
+        assert!(prepare(dir.path(), &request("abc")).is_err());
+        assert!(prepare(dir.path(), &ModelDownload { url: "https://example.com/model.bin".to_string(), ..request(&sha) }).is_err());
+        assert!(prepare(dir.path(), &ModelDownload { url: "file:///tmp/model.onnx".to_string(), ..request(&sha) }).is_err());
+
+        let prepared = prepare(dir.path(), &request(&sha)).unwrap();
+        assert_eq!(prepared.format, ModelFormat::Onnx);
+        assert!(prepared.dest.starts_with(dir.path().join("upscale")));
+    }
+
+    #[test]
+    fn test_install_list_delete() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let data = b"not really a model";
+
+        // Wrong checksum: nothing is installed and the file is gone
+        let bad = request(&"0".repeat(64));
+        let prepared = prepare(root, &bad).unwrap();
+        std::fs::write(&prepared.dest, data).unwrap();
+        assert!(matches!(install(root, &bad, &prepared, "esrgan-x4.onnx"), Err(AppError::InvalidInput(_))));
+        assert!(!prepared.dest.exists());
+        assert!(list(root).unwrap().is_empty());
+
+        let good = request(&hash::compute_binary_hash(data).to_uppercase());
+        let prepared = prepare(root, &good).unwrap();
+        std::fs::write(&prepared.dest, data).unwrap();
+        let model = install(root, &good, &prepared, "esrgan-x4.onnx").unwrap();
+        assert_eq!(model.name, "esrgan-x4.onnx");
+        assert_eq!(model.size, data.len() as u64);
+        assert_eq!(find_existing(root, &good).unwrap(), Some(model.clone()));
+        assert_eq!(installed_in(root, ModelKind::Upscale), Some(prepared.dest.clone()));
+        assert_eq!(installed_in(root, ModelKind::Whisper), None);
+
+        let usage = disk_usage(root).unwrap();
+        assert_eq!(usage.total, data.len() as u64);
+        assert_eq!(usage.kinds, vec![ModelKindUsage { kind: ModelKind::Upscale, count: 1, size: data.len() as u64 }]);
+
+        assert!(delete(root, &model.id).unwrap());
+        assert!(!delete(root, &model.id).unwrap());
+        assert!(!prepared.dest.exists());
+        assert!(list(root).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,367 @@
+//! Outbound request policy (SSRF protection).
+//!
+//! Every backend-issued HTTP request driven by frontend or agent input goes
+//! through a `NetworkPolicy`:
+//! - Link-local, cloud metadata, multicast and unspecified addresses are always rejected
+//! - Loopback (local AI services such as Ollama/ComfyUI) is always allowed
+//! - Private network addresses require the host to be on the user allowlist
+//! - In strict mode, public hosts also require the allowlist
+//! - Hosts the organization config blocks are rejected, allowlisted or not
+//!
+//! Guarded clients also resolve host names through the policy, so they only
+//! ever connect to addresses it allows, whatever DNS answers between the
+//! `check_url` lookup and the connection or for a redirect target.
+//!
+//! It also carries the user's timeouts: guarded clients always get the
+//! connect timeout; callers add the total or idle timeout that fits the call.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use ts_rs::TS;
+
+/// Maximum number of redirects followed by guarded clients
+const MAX_REDIRECTS: usize = 10;
+
+/// Hostnames that are never allowed, regardless of resolution
+const BLOCKED_HOSTS: &[&str] = &["metadata.google.internal", "metadata"];
+
+/// Timeouts for outbound requests, in seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkTimeouts {
+    /// Establishing a connection
+    #[ts(type = "number")]
+    pub connect_secs: u64,
+    /// A whole request with a bounded response (agent runs, proxy calls)
+    #[ts(type = "number")]
+    pub request_secs: u64,
+    /// Silence between chunks of a streamed body (downloads, streams)
+    #[ts(type = "number")]
+    pub idle_secs: u64,
+}
+
+impl Default for NetworkTimeouts {
+    fn default() -> Self {
+        Self {
+            connect_secs: 10,
+            request_secs: 300,
+            idle_secs: 60,
+        }
+    }
+}
+
+impl NetworkTimeouts {
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs.max(1))
+    }
+
+    pub fn request(&self) -> Duration {
+        Duration::from_secs(self.request_secs.max(1))
+    }
+
+    pub fn idle(&self) -> Duration {
+        Duration::from_secs(self.idle_secs.max(1))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkPolicy {
+    /// User-approved hosts. `*.example.com` matches subdomains.
+    pub allowed_hosts: Vec<String>,
+    /// Only loopback and allowlisted hosts are reachable
+    pub strict: bool,
+    pub timeouts: NetworkTimeouts,
+    /// Hosts blocked by the organization config, same patterns as the allowlist
+    pub blocked_hosts: Vec<String>,
+}
+
+impl NetworkPolicy {
+    pub fn from_config(config: &GlobalConfig) -> Self {
+        Self {
+            allowed_hosts: config.network_allowlist.clone().unwrap_or_default(),
+            strict: config.network_strict.unwrap_or(false),
+            timeouts: config.network_timeouts.clone().unwrap_or_default(),
+            blocked_hosts: config.org.blocked_domains.clone(),
+        }
+    }
+
+    /// Validate a URL before issuing a request, resolving its host so that
+    /// DNS names pointing at internal addresses are caught too.
+    pub async fn check_url(&self, url: &str) -> Result<Url, AppError> {
+        let parsed = self.check_url_static(url)?;
+        
+        let host = parsed.host_str().unwrap_or_default().to_string();
+        if host.parse::<IpAddr>().is_ok() || is_localhost(&host) {
+            return Ok(parsed);
+        }
+        
+        let port = parsed.port_or_known_default().unwrap_or(80);
+        let addrs = lookup(host, port).await?;
+        
+        let allowlisted = self.is_allowlisted(parsed.host_str().unwrap_or_default());
+        for addr in addrs {
+            self.check_ip(addr.ip(), allowlisted)?;
+        }
+        
+        Ok(parsed)
+    }
+
+    /// Validate a URL without DNS resolution (scheme, host name, IP literals).
+    pub fn check_url_static(&self, url: &str) -> Result<Url, AppError> {
+        let parsed = Url::parse(url)
+            .map_err(|e| AppError::Forbidden(format!("Invalid URL '{}': {}", url, e)))?;
+        
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AppError::Forbidden(format!("Scheme not allowed: {}", parsed.scheme())));
+        }
+        
+        let host = parsed.host_str()
+            .ok_or_else(|| AppError::Forbidden(format!("URL has no host: {}", url)))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase();
+        
+        if BLOCKED_HOSTS.contains(&host.as_str()) {
+            return Err(AppError::Forbidden(format!("Host not allowed: {}", host)));
+        }
+        if matches_host(&self.blocked_hosts, &host) {
+            return Err(AppError::Forbidden(format!("Host is blocked by your organization: {}", host)));
+        }
+        
+        let allowlisted = self.is_allowlisted(&host);
+        
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            self.check_ip(ip, allowlisted)?;
+        } else if self.strict && !allowlisted && !is_localhost(&host) {
+            return Err(AppError::Forbidden(format!("Host is not on the allowlist: {}", host)));
+        }
+        
+        Ok(parsed)
+    }
+
+    /// Build a reqwest client whose redirects are re-validated at every hop
+    /// and whose host names only resolve to addresses the policy allows.
+    pub fn client_builder(&self) -> reqwest::ClientBuilder {
+        let policy = self.clone();
+        reqwest::Client::builder()
+            .connect_timeout(self.timeouts.connect())
+            .dns_resolver(Arc::new(GuardedResolver { policy: self.clone() }))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("Too many redirects")
+            } else if let Err(e) = policy.check_url_static(attempt.url().as_str()) {
+                attempt.error(e.to_string())
+            } else {
+                attempt.follow()
+            }
+        }))
+    }
+
+    /// The addresses of `host` a connection may use. Errors when none are left.
+    fn allowed_addrs(&self, host: &str, addrs: Vec<SocketAddr>) -> Result<Vec<SocketAddr>, AppError> {
+        let allowlisted = self.is_allowlisted(host);
+        let mut rejected = None;
+        let allowed: Vec<SocketAddr> = addrs
+            .into_iter()
+            .filter(|addr| match self.check_ip(addr.ip(), allowlisted) {
+                Ok(()) => true,
+                Err(e) => {
+                    rejected.get_or_insert(e);
+                    false
+                }
+            })
+            .collect();
+        
+        match rejected {
+            Some(e) if allowed.is_empty() => Err(e),
+            _ if allowed.is_empty() => Err(AppError::Network(format!("No addresses found for {}", host))),
+            _ => Ok(allowed),
+        }
+    }
+
+    fn is_allowlisted(&self, host: &str) -> bool {
+        matches_host(&self.allowed_hosts, host)
+    }
+
+    fn check_ip(&self, ip: IpAddr, allowlisted: bool) -> Result<(), AppError> {
+        if is_forbidden_ip(&ip) {
+            return Err(AppError::Forbidden(format!("Address not allowed: {}", ip)));
+        }
+        if ip.is_loopback() || allowlisted {
+            return Ok(());
+        }
+        if is_private_ip(&ip) {
+            return Err(AppError::Forbidden(format!(
+                "Private network address requires allowlisting: {}", ip
+            )));
+        }
+        if self.strict {
+            return Err(AppError::Forbidden(format!("Host is not on the allowlist: {}", ip)));
+        }
+        Ok(())
+    }
+}
+
+/// DNS resolver of guarded clients, dropping the addresses the policy rejects
+struct GuardedResolver {
+    policy: NetworkPolicy,
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            // reqwest sets the port of the request on the returned addresses
+            let addrs = lookup(host.clone(), 0).await?;
+            let allowed = policy.allowed_addrs(&host, addrs)?;
+            Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(allowed.into_iter()))
+        })
+    }
+}
+
+async fn lookup(host: String, port: u16) -> Result<Vec<SocketAddr>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        (host.as_str(), port).to_socket_addrs().map(|a| a.collect::<Vec<_>>())
+    })
+    .await
+    .map_err(|e| AppError::Network(e.to_string()))?
+    .map_err(|e| AppError::Network(format!("Failed to resolve host: {}", e)))
+}
+
+/// Whether `host` matches one of `patterns` (`*.example.com` matches subdomains)
+fn matches_host(patterns: &[String], host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim().to_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host == suffix || host.ends_with(&format!(".{}", suffix)),
+            None => host == pattern,
+        }
+    })
+}
+
+/// Addresses that are never reachable: link-local (incl. cloud metadata),
+/// unspecified, multicast and broadcast.
+pub fn is_forbidden_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_link_local() || v4.is_unspecified() || v4.is_multicast() || v4.is_broadcast()
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_forbidden_ip(&IpAddr::V4(v4));
+            }
+            v6.is_unspecified()
+                || v6.is_multicast()
+                || (v6.segments()[0] & 0xffc0) == 0xfe80 // fe80::/10 link-local
+                || *v6 == Ipv6Addr::new(0xfd00, 0xec2, 0, 0, 0, 0, 0, 0x254) // AWS IMDS over IPv6
+        }
+    }
+}
+
+/// RFC 1918 / unique-local / CGNAT ranges.
+pub fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || is_cgnat(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_ip(&IpAddr::V4(v4));
+            }
+            (v6.segments()[0] & 0xfe00) == 0xfc00 // fc00::/7 unique local
+        }
+    }
+}
+
+fn is_cgnat(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    octets[0] == 100 && (octets[1] & 0xc0) == 64
+}
+
+fn is_localhost(host: &str) -> bool {
+    host == "localhost" || host.ends_with(".localhost")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_metadata_and_link_local() {
+        let policy = NetworkPolicy::default();
+        assert!(policy.check_url_static("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(policy.check_url_static("http://[fe80::1]/").is_err());
+        assert!(policy.check_url_static("http://metadata.google.internal/").is_err());
+        assert!(policy.check_url_static("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_loopback_always_allowed() {
+        let policy = NetworkPolicy { allowed_hosts: vec![], strict: true, ..Default::default() };
+        assert!(policy.check_url_static("http://127.0.0.1:11434/api/generate").is_ok());
+        assert!(policy.check_url_static("http://localhost:8188/prompt").is_ok());
+        assert!(policy.check_url_static("http://[::1]:8188/").is_ok());
+    }
+
+    #[test]
+    fn test_private_ranges_require_allowlist() {
+        let policy = NetworkPolicy::default();
+        assert!(policy.check_url_static("http://192.168.1.20:8188/").is_err());
+        assert!(policy.check_url_static("http://10.0.0.5/").is_err());
+        
+        let policy = NetworkPolicy { allowed_hosts: vec!["192.168.1.20".to_string()], strict: false, ..Default::default() };
+        assert!(policy.check_url_static("http://192.168.1.20:8188/").is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_wildcards() {
+        let policy = NetworkPolicy { allowed_hosts: vec!["*.example.com".to_string()], strict: true, ..Default::default() };
+        assert!(policy.check_url_static("https://cdn.example.com/a.png").is_ok());
+        assert!(policy.check_url_static("https://example.com/a.png").is_ok());
+        assert!(policy.check_url_static("https://evil.com/a.png").is_err());
+        
+        let relaxed = NetworkPolicy::default();
+        assert!(relaxed.check_url_static("https://evil.com/a.png").is_ok());
+    }
+
+    #[test]
+    fn test_resolved_addresses_filtered() {
+        let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+        let policy = NetworkPolicy::default();
+        
+        // A public name rebound to internal addresses keeps only its public ones
+        let allowed = policy
+            .allowed_addrs("example.com", vec![addr("93.184.216.34:0"), addr("169.254.169.254:0"), addr("10.0.0.5:0")])
+            .unwrap();
+        assert_eq!(allowed, vec![addr("93.184.216.34:0")]);
+        
+        assert!(matches!(
+            policy.allowed_addrs("example.com", vec![addr("169.254.169.254:0")]),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(policy.allowed_addrs("example.com", vec![]).is_err());
+        
+        let allowlisted = NetworkPolicy { allowed_hosts: vec!["nas.lan".to_string()], ..Default::default() };
+        assert!(allowlisted.allowed_addrs("nas.lan", vec![addr("192.168.1.20:0")]).is_ok());
+        assert!(allowlisted.allowed_addrs("nas.lan", vec![addr("[fe80::1]:0")]).is_err());
+    }
+
+    #[test]
+    fn test_org_blocked_hosts_win_over_allowlist() {
+        let policy = NetworkPolicy {
+            allowed_hosts: vec!["*.example.com".to_string()],
+            blocked_hosts: vec!["files.example.com".to_string(), "*.pastebin.com".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.check_url_static("https://cdn.example.com/a.png").is_ok());
+        assert!(matches!(policy.check_url_static("https://FILES.example.com/a"), Err(AppError::Forbidden(_))));
+        assert!(policy.check_url_static("https://pastebin.com/raw/x").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Node locking and pinning.
+//!
+//! A locked node (`data.locked`) can't be moved, resized, reparented or
+//! deleted; a pinned node (`data.pinned`) can't be moved or reparented but
+//! may still be resized or deleted. The flags stored in the database are
+//! authoritative: graph saves that would break them are rejected unless
+//! forced, so arranged reference areas survive a stray drag, and saves keep
+//! the stored flags whatever the graph says.
+
+use rusqlite::Connection;
+use crate::error::AppError;
+use crate::models::{Position, SynniaNode};
+use crate::services::io_sqlite;
+
+/// Geometry differences below this (in px) are measurement noise, not edits
+const TOLERANCE: f64 = 0.5;
+
+/// Reject a save of a canvas graph that moves, resizes or deletes locked
+/// nodes, or moves pinned ones. The graph's own flags don't count.
+pub fn check_graph_save(conn: &Connection, canvas_id: &str, nodes: &[SynniaNode]) -> Result<(), AppError> {
+    let mut violations = Vec::new();
+    for stored in io_sqlite::load_nodes(conn, canvas_id)? {
+        let locked = stored.data.locked == Some(true);
+        let pinned = stored.data.pinned == Some(true);
+        if !locked && !pinned {
+            continue;
+        }
+        
+        let Some(incoming) = nodes.iter().find(|n| n.id == stored.id) else {
+            if locked {
+                violations.push(format!("{} ({}) would be deleted", stored.data.title, stored.id));
+            }
+            continue;
+        };
+        if moved(&stored.position, &incoming.position) || stored.parent_id != incoming.parent_id {
+            violations.push(format!("{} ({}) would be moved", stored.data.title, stored.id));
+        } else if locked && (size_changed(stored.width, incoming.width) || size_changed(stored.height, incoming.height)) {
+            violations.push(format!("{} ({}) would be resized", stored.data.title, stored.id));
+        }
+    }
+    
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!("Locked nodes changed: {}", violations.join("; "))))
+    }
+}
+
+/// Reject moving nodes to another canvas if any of them is locked or pinned.
+pub fn check_canvas_move<'a>(conn: &Connection, node_ids: impl IntoIterator<Item = &'a String>) -> Result<(), AppError> {
+    let mut violations = Vec::new();
+    for id in node_ids {
+        let Some(stored) = io_sqlite::get_node(conn, id)? else { continue };
+        if stored.data.locked == Some(true) || stored.data.pinned == Some(true) {
+            violations.push(format!("{} ({})", stored.data.title, stored.id));
+        }
+    }
+    
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!("Locked nodes can't change canvas: {}", violations.join("; "))))
+    }
+}
+
+/// Set or clear the `locked` / `pinned` flags of a node; `None` leaves a flag as is.
+pub fn set_node_flags(conn: &Connection, node_id: &str, locked: Option<bool>, pinned: Option<bool>) -> Result<SynniaNode, AppError> {
+    let mut node = io_sqlite::get_node(conn, node_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", node_id)))?;
+    if let Some(locked) = locked {
+        node.data.locked = locked.then_some(true);
+    }
+    if let Some(pinned) = pinned {
+        node.data.pinned = pinned.then_some(true);
+    }
+    io_sqlite::upsert_node(conn, &node)?;
+    Ok(node)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn moved(a: &Position, b: &Position) -> bool {
+    (a.x - b.x).abs() > TOLERANCE || (a.y - b.y).abs() > TOLERANCE
+}
+
+fn size_changed(a: Option<f64>, b: Option<f64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() > TOLERANCE,
+        // Unmeasured nodes pick up a size on first render
+        (None, _) => false,
+        (Some(_), None) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SynniaNodeData;
+    use crate::services::{canvases::DEFAULT_CANVAS_ID, database};
+    use tempfile::tempdir;
+
+    fn node(id: &str, x: f64) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x, y: 0.0 },
+            width: Some(200.0),
+            height: Some(150.0),
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_locked_and_pinned_nodes() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for id in ["moodboard", "sticky", "free"] {
+            io_sqlite::upsert_node(&conn, &node(id, 0.0)).unwrap();
+        }
+        set_node_flags(&conn, "moodboard", Some(true), None).unwrap();
+        set_node_flags(&conn, "sticky", None, Some(true)).unwrap();
+        
+        let graph = io_sqlite::load_nodes(&conn, DEFAULT_CANVAS_ID).unwrap();
+        assert!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &graph).is_ok());
+        
+        let mut edited = graph.clone();
+        edited.iter_mut().find(|n| n.id == "free").unwrap().position.x = 300.0;
+        edited.iter_mut().find(|n| n.id == "sticky").unwrap().width = Some(400.0);
+        assert!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &edited).is_ok(), "pinned nodes may be resized");
+        
+        edited.iter_mut().find(|n| n.id == "sticky").unwrap().position.x = 0.2;
+        assert!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &edited).is_ok(), "sub-pixel jitter is not a move");
+        
+        let mut moved = graph.clone();
+        moved.iter_mut().find(|n| n.id == "sticky").unwrap().position.x = 50.0;
+        assert!(matches!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &moved), Err(AppError::Forbidden(_))));
+        
+        let deleted: Vec<_> = graph.iter().filter(|n| n.id != "moodboard").cloned().collect();
+        assert!(matches!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &deleted), Err(AppError::Forbidden(_))));
+        
+        set_node_flags(&conn, "moodboard", Some(false), None).unwrap();
+        assert!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &deleted).is_ok());
+    }
+
+    #[test]
+    fn test_save_keeps_stored_flags() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Refs").unwrap();
+        project.graph.nodes = vec![node("moodboard", 0.0), node("sticky", 300.0)];
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        set_node_flags(&conn, "moodboard", Some(true), None).unwrap();
+        
+        // Autosave of a graph loaded before the lock, and one trying to set flags
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        project.graph.nodes[1].data.pinned = Some(true);
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let moodboard = io_sqlite::get_node(&conn, "moodboard").unwrap().unwrap();
+        assert_eq!((moodboard.data.locked, moodboard.data.pinned), (Some(true), None));
+        let sticky = io_sqlite::get_node(&conn, "sticky").unwrap().unwrap();
+        assert_eq!(sticky.data.pinned, None);
+        assert!(check_graph_save(&conn, DEFAULT_CANVAS_ID, &[node("sticky", 300.0)]).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Organization config: a read-only settings layer for studio deployments.
+//!
+//! The layer comes from the path or URL in `SYNNIA_ORG_CONFIG`, or else
+//! from a file at the system-wide location device management tools write
+//! to (`/Library/Application Support/Synnia`, `%ProgramData%\Synnia`,
+//! `/etc/synnia`). A URL is fetched at startup and cached in the app config
+//! directory, so the last copy still applies offline.
+//!
+//! It sits under the user's config: its default workspace only applies
+//! when the user hasn't picked one. The restrictions — providers the studio
+//! approves and domains it blocks — always apply and can't be changed from
+//! the app. `GlobalConfig` carries the layer but never saves it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use ts_rs::TS;
+
+/// Environment variable holding the layer's path or URL
+pub const ENV_VAR: &str = "SYNNIA_ORG_CONFIG";
+
+/// Copy of a fetched layer, in the app config directory
+pub const CACHE_FILE: &str = "org-config.json";
+
+/// Largest org config accepted from a URL
+const MAX_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OrgConfig {
+    /// Where the layer was read from; none when there is no layer
+    #[serde(skip_deserializing)]
+    pub source: Option<String>,
+    /// Provider keys (as in the app settings) the studio allows; none allows all
+    pub approved_providers: Option<Vec<String>>,
+    /// Hosts no request may reach, even when allowlisted. `*.example.com`
+    /// matches subdomains.
+    pub blocked_domains: Vec<String>,
+    /// Workspace for users who haven't chosen one
+    pub default_workspace: Option<String>,
+}
+
+impl OrgConfig {
+    /// Fail unless the studio allows `provider`
+    pub fn check_provider(&self, provider: &str) -> Result<(), AppError> {
+        match &self.approved_providers {
+            Some(approved) if !approved.iter().any(|p| p.eq_ignore_ascii_case(provider)) => Err(AppError::Forbidden(
+                format!("Provider '{}' is not approved by your organization", provider),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where the layer comes from: `SYNNIA_ORG_CONFIG`, or the system-wide file
+/// if it exists.
+pub fn source() -> Option<String> {
+    if let Some(value) = std::env::var_os(ENV_VAR) {
+        let value = value.to_string_lossy().trim().to_string();
+        return (!value.is_empty()).then_some(value);
+    }
+    let system = system_path()?;
+    system.is_file().then(|| system.to_string_lossy().to_string())
+}
+
+/// Read the layer: the file at a path source, or the cached copy of a URL
+/// source. A missing or unreadable layer is treated as none.
+pub fn load(config_dir: &Path) -> OrgConfig {
+    let Some(source) = source() else {
+        return OrgConfig::default();
+    };
+    let path = if is_url(&source) { config_dir.join(CACHE_FILE) } else { PathBuf::from(&source) };
+    let mut org = fs::read(&path).ok()
+        .and_then(|data| parse(&data).ok())
+        .unwrap_or_default();
+    org.source = Some(source);
+    org
+}
+
+/// Fetch a URL source and replace the cached copy. Does nothing for a path
+/// source. The fetch itself can't reach blocked domains.
+pub async fn refresh(config_dir: &Path, policy: &NetworkPolicy) -> Result<(), AppError> {
+    let Some(url) = source().filter(|s| is_url(s)) else {
+        return Ok(());
+    };
+    let url = policy.check_url(&url).await?;
+    let client = policy.client_builder()
+        .timeout(policy.timeouts.request())
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let response = client.get(url.clone()).send().await
+        .map_err(|e| AppError::Network(format!("Failed to fetch org config: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!("Failed to fetch org config: HTTP {}", response.status().as_u16())));
+    }
+    let data = response.bytes().await
+        .map_err(|e| AppError::Network(format!("Failed to fetch org config: {}", e)))?;
+    if data.len() > MAX_BYTES {
+        return Err(AppError::InvalidInput(format!("Org config at {} is too large", url)));
+    }
+    // Keep the last good copy if the new one doesn't parse
+    parse(&data)?;
+    fs::create_dir_all(config_dir)?;
+    fs::write(config_dir.join(CACHE_FILE), &data)?;
+    Ok(())
+}
+
+pub fn parse(data: &[u8]) -> Result<OrgConfig, AppError> {
+    serde_json::from_slice(data).map_err(|e| AppError::InvalidInput(format!("Invalid org config: {}", e)))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+fn system_path() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        Some(PathBuf::from("/Library/Application Support/Synnia").join(CACHE_FILE))
+    } else if cfg!(target_os = "windows") {
+        std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join("Synnia").join(CACHE_FILE))
+    } else {
+        Some(PathBuf::from("/etc/synnia").join(CACHE_FILE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check_provider() {
+        let org = parse(br#"{ "approvedProviders": ["openai", "Ollama"], "blockedDomains": ["*.pastebin.com"], "source": "ignored" }"#).unwrap();
+        assert_eq!(org.source, None);
+        assert_eq!(org.blocked_domains, ["*.pastebin.com"]);
+        assert!(org.check_provider("openai").is_ok());
+        assert!(org.check_provider("ollama").is_ok());
+        assert!(matches!(org.check_provider("replicate"), Err(AppError::Forbidden(_))));
+
+        assert!(OrgConfig::default().check_provider("replicate").is_ok(), "no list allows everything");
+        assert!(parse(b"not json").is_err());
+    }
+}
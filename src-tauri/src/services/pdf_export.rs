@@ -0,0 +1,681 @@
+//! Contact sheet / board PDF export for printable review decks.
+//!
+//! Selected nodes are laid out in a grid: images with their node title as
+//! caption (followed by any requested custom fields, e.g. "Hero — Status:
+//! approved"), text assets as note cards. Each group (or rack) becomes its own
+//! section with a heading, nested groups are titled "Parent / Child", and
+//! items follow the board's reading order (top to bottom, then left to
+//! right), which is also the sequence order of racks.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::Path;
+use image::{codecs::jpeg::JpegEncoder, DynamicImage, GenericImageView, Rgb, RgbImage};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{SynniaNode, SynniaProject};
+use crate::services::cancellation::CancellationToken;
+use crate::services::export::{sanitize, unique_filename, EXPORT_DIR};
+use crate::services::watermark::Watermark;
+use crate::services::{custom_fields, database, disk_space, imaging, io_sqlite, text_asset};
+use ts_rs::TS;
+
+const MARGIN: f32 = 36.0;
+const GAP: f32 = 12.0;
+const TITLE_SIZE: f32 = 18.0;
+const HEADING_SIZE: f32 = 13.0;
+const CAPTION_SIZE: f32 = 9.0;
+const NOTE_SIZE: f32 = 8.5;
+const LINE_HEIGHT: f32 = 1.3;
+/// Image cells are 4:3
+const CELL_ASPECT: f32 = 0.75;
+/// Long side images are downscaled to before embedding
+const MAX_IMAGE_SIZE: u32 = 1600;
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfExportOptions {
+    /// Nodes to include (with everything nested inside); empty for the whole board
+    #[serde(default)]
+    pub node_ids: Vec<String>,
+    /// Document title, defaults to the project name
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub page_size: PageSize,
+    #[serde(default)]
+    pub landscape: bool,
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+    /// Include text assets as note cards
+    #[serde(default = "default_true")]
+    pub include_notes: bool,
+    /// Custom field IDs to add to image captions, node values before asset ones
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+impl Default for PdfExportOptions {
+    fn default() -> Self {
+        Self {
+            node_ids: Vec::new(),
+            title: None,
+            page_size: PageSize::default(),
+            landscape: false,
+            columns: default_columns(),
+            include_notes: true,
+            fields: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfExport {
+    /// Path relative to the project root
+    pub path: String,
+    pub pages: usize,
+    pub items: usize,
+}
+
+/// A titled run of items; the untitled section holds ungrouped nodes
+#[derive(Debug)]
+struct Section<'a> {
+    title: Option<String>,
+    items: Vec<&'a SynniaNode>,
+}
+
+enum Cell {
+    Image { image: DynamicImage, caption: String },
+    Note { title: String, text: String },
+}
+
+/// Lay out the selected nodes into a paginated PDF in the export folder.
+/// Stops between images when cancelled.
+pub fn export_pdf(
+    project_root: &Path,
+    project: &SynniaProject,
+    options: &PdfExportOptions,
+    watermark: Option<&Watermark>,
+    cancel: &CancellationToken,
+) -> Result<PdfExport, AppError> {
+    let labels = if options.fields.is_empty() {
+        HashMap::new()
+    } else {
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        custom_fields::labels(&conn, &options.fields)?
+    };
+    
+    let mut sections: Vec<(Option<String>, Vec<Cell>)> = Vec::new();
+    for section in collect_sections(project, &options.node_ids)? {
+        let mut cells = Vec::with_capacity(section.items.len());
+        for node in &section.items {
+            cancel.check()?;
+            cells.extend(load_cell(project_root, project, node, options.include_notes, &labels, watermark));
+        }
+        if !cells.is_empty() {
+            sections.push((section.title, cells));
+        }
+    }
+    let items = sections.iter().map(|(_, cells)| cells.len()).sum();
+    if items == 0 {
+        return Err(AppError::Unknown("Nothing to export: no images or notes in the selection".to_string()));
+    }
+    
+    let title = options.title.clone().unwrap_or_else(|| project.meta.name.clone());
+    let mut writer = PdfWriter::new(options.page_size, options.landscape);
+    writer.layout(&title, &sections, options.columns.clamp(1, 8))?;
+    let pages = writer.pages.len();
+    cancel.check()?;
+    
+    let export_dir = project_root.join(EXPORT_DIR);
+    std::fs::create_dir_all(&export_dir)?;
+    let filename = unique_filename(&export_dir, &sanitize(&title), "pdf");
+    writer.save(&export_dir.join(&filename))?;
+    
+    Ok(PdfExport {
+        path: format!("{}/{}", EXPORT_DIR, filename),
+        pages,
+        items,
+    })
+}
+
+/// Rough size of the PDF `export_pdf` would write: the selected images as
+/// stored in the project (they're embedded downscaled, so usually less)
+pub fn estimated_size(project_root: &Path, project: &SynniaProject, options: &PdfExportOptions) -> Result<u64, AppError> {
+    let paths: Vec<_> = collect_sections(project, &options.node_ids)?
+        .iter()
+        .flat_map(|section| section.items.iter())
+        .filter_map(|node| project.assets.get(node.data.asset_id.as_ref()?))
+        .filter_map(|asset| asset.value.get("src").and_then(|s| s.as_str()))
+        .filter_map(|src| imaging::resolve_image_source(project_root, src))
+        .collect();
+    Ok(disk_space::files_size(&paths))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_columns() -> u32 {
+    3
+}
+
+/// Group the selection into sections, depth-first in reading order.
+fn collect_sections<'a>(project: &'a SynniaProject, node_ids: &[String]) -> Result<Vec<Section<'a>>, AppError> {
+    let mut children: HashMap<&str, Vec<&SynniaNode>> = HashMap::new();
+    for node in &project.graph.nodes {
+        if let Some(parent) = &node.parent_id {
+            children.entry(parent.as_str()).or_default().push(node);
+        }
+    }
+    for siblings in children.values_mut() {
+        sort_reading_order(siblings);
+    }
+    
+    let mut roots: Vec<&SynniaNode> = if node_ids.is_empty() {
+        project.graph.nodes.iter().filter(|n| n.parent_id.is_none()).collect()
+    } else {
+        let selected: HashSet<&str> = node_ids.iter().map(String::as_str).collect();
+        let mut roots = Vec::new();
+        for id in node_ids {
+            let node = project.graph.nodes.iter().find(|n| &n.id == id)
+                .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", id)))?;
+            // Skip nodes already covered by a selected ancestor
+            let mut parent = node.parent_id.as_deref();
+            let mut covered = false;
+            while let Some(p) = parent {
+                if selected.contains(p) {
+                    covered = true;
+                    break;
+                }
+                parent = project.graph.nodes.iter().find(|n| n.id == p).and_then(|n| n.parent_id.as_deref());
+            }
+            if !covered {
+                roots.push(node);
+            }
+        }
+        roots
+    };
+    sort_reading_order(&mut roots);
+    
+    let mut sections = vec![Section { title: None, items: Vec::new() }];
+    for root in roots {
+        if children.contains_key(root.id.as_str()) {
+            push_group(root, None, &children, &mut sections);
+        } else {
+            sections[0].items.push(root);
+        }
+    }
+    Ok(sections)
+}
+
+fn push_group<'a>(
+    group: &'a SynniaNode,
+    parent_title: Option<&str>,
+    children: &HashMap<&str, Vec<&'a SynniaNode>>,
+    sections: &mut Vec<Section<'a>>,
+) {
+    let title = match parent_title {
+        Some(parent) => format!("{} / {}", parent, group.data.title),
+        None => group.data.title.clone(),
+    };
+    let members = children.get(group.id.as_str()).map(Vec::as_slice).unwrap_or(&[]);
+    
+    sections.push(Section {
+        title: Some(title.clone()),
+        items: members.iter().filter(|n| !children.contains_key(n.id.as_str())).copied().collect(),
+    });
+    for nested in members.iter().filter(|n| children.contains_key(n.id.as_str())) {
+        push_group(nested, Some(&title), children, sections);
+    }
+}
+
+fn sort_reading_order(nodes: &mut [&SynniaNode]) {
+    nodes.sort_by(|a, b| {
+        a.position.y.total_cmp(&b.position.y).then_with(|| a.position.x.total_cmp(&b.position.x))
+    });
+}
+
+fn load_cell(
+    project_root: &Path,
+    project: &SynniaProject,
+    node: &SynniaNode,
+    include_notes: bool,
+    labels: &HashMap<String, Vec<String>>,
+    watermark: Option<&Watermark>,
+) -> Option<Cell> {
+    let asset = project.assets.get(node.data.asset_id.as_ref()?)?;
+    
+    if let Some(src) = asset.value.get("src").and_then(|s| s.as_str()) {
+        let path = imaging::resolve_image_source(project_root, src)?;
+        let image = match image::open(&path) {
+            Ok(image) => image,
+            Err(e) => {
+                println!("[PdfExport] Skipping unreadable image {:?}: {}", path, e);
+                return None;
+            }
+        };
+        let image = if image.width() > MAX_IMAGE_SIZE || image.height() > MAX_IMAGE_SIZE {
+            image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE)
+        } else {
+            image
+        };
+        let image = match watermark {
+            Some(watermark) => watermark.apply(image),
+            None => image,
+        };
+        let fields: Vec<&str> = [&node.id, &asset.id].into_iter()
+            .filter_map(|id| labels.get(id))
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let caption = if fields.is_empty() {
+            node.data.title.clone()
+        } else {
+            format!("{} — {}", node.data.title, fields.join(", "))
+        };
+        return Some(Cell::Image { image, caption });
+    }
+    
+    if include_notes {
+        let text = text_asset::text_content(asset)?;
+        if !text.trim().is_empty() {
+            return Some(Cell::Note { title: node.data.title.clone(), text: text.to_string() });
+        }
+    }
+    None
+}
+
+/// Minimal page writer over lopdf: Helvetica text and JPEG images
+struct PdfWriter {
+    doc: Document,
+    pages_id: ObjectId,
+    font_id: ObjectId,
+    width: f32,
+    height: f32,
+    pages: Vec<ObjectId>,
+    ops: Vec<Operation>,
+    images: Vec<(String, ObjectId)>,
+    /// Top of the free area on the current page (PDF y grows upwards)
+    cursor: f32,
+}
+
+impl PdfWriter {
+    fn new(size: PageSize, landscape: bool) -> Self {
+        let (width, height) = match size {
+            PageSize::A4 => (595.0, 842.0),
+            PageSize::Letter => (612.0, 792.0),
+        };
+        let (width, height) = if landscape { (height, width) } else { (width, height) };
+        
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        
+        Self {
+            doc,
+            pages_id,
+            font_id,
+            width,
+            height,
+            pages: Vec::new(),
+            ops: Vec::new(),
+            images: Vec::new(),
+            cursor: height - MARGIN,
+        }
+    }
+
+    fn layout(&mut self, title: &str, sections: &[(Option<String>, Vec<Cell>)], columns: u32) -> Result<(), AppError> {
+        let content_width = self.width - 2.0 * MARGIN;
+        let cell_width = (content_width - GAP * (columns - 1) as f32) / columns as f32;
+        let image_height = cell_width * CELL_ASPECT;
+        let row_height = image_height + CAPTION_SIZE * LINE_HEIGHT * 2.0 + GAP;
+        
+        self.text(MARGIN, self.cursor - TITLE_SIZE, TITLE_SIZE, title);
+        self.cursor -= TITLE_SIZE * LINE_HEIGHT + GAP;
+        
+        for (heading, cells) in sections {
+            if let Some(heading) = heading {
+                let heading_height = HEADING_SIZE * LINE_HEIGHT + GAP / 2.0;
+                if self.cursor - heading_height - row_height < MARGIN {
+                    self.finish_page()?;
+                }
+                self.text(MARGIN, self.cursor - HEADING_SIZE, HEADING_SIZE, heading);
+                self.cursor -= heading_height;
+            }
+            
+            for row in cells.chunks(columns as usize) {
+                if self.cursor - row_height < MARGIN {
+                    self.finish_page()?;
+                }
+                for (i, cell) in row.iter().enumerate() {
+                    let x = MARGIN + i as f32 * (cell_width + GAP);
+                    self.cell(cell, x, self.cursor, cell_width, image_height)?;
+                }
+                self.cursor -= row_height;
+            }
+            self.cursor -= GAP;
+        }
+        
+        self.finish_page()
+    }
+
+    /// Draw a cell with its top-left corner at (`x`, `top`)
+    fn cell(&mut self, cell: &Cell, x: f32, top: f32, width: f32, height: f32) -> Result<(), AppError> {
+        match cell {
+            Cell::Image { image, caption } => {
+                let (img_w, img_h) = image.dimensions();
+                let scale = (width / img_w as f32).min(height / img_h as f32);
+                let (draw_w, draw_h) = (img_w as f32 * scale, img_h as f32 * scale);
+                let name = self.embed_image(image)?;
+                self.ops.push(Operation::new("q", vec![]));
+                self.ops.push(Operation::new("cm", vec![
+                    draw_w.into(), 0.into(), 0.into(), draw_h.into(),
+                    (x + (width - draw_w) / 2.0).into(), (top - height + (height - draw_h) / 2.0).into(),
+                ]));
+                self.ops.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+                self.ops.push(Operation::new("Q", vec![]));
+                for (i, line) in wrap(caption, width, CAPTION_SIZE).iter().take(2).enumerate() {
+                    let y = top - height - CAPTION_SIZE * LINE_HEIGHT * (i + 1) as f32;
+                    self.text(x, y, CAPTION_SIZE, line);
+                }
+            }
+            Cell::Note { title, text } => {
+                // Light frame around the note
+                self.ops.push(Operation::new("G", vec![0.8.into()]));
+                self.ops.push(Operation::new("re", vec![x.into(), (top - height).into(), width.into(), height.into()]));
+                self.ops.push(Operation::new("S", vec![]));
+                self.ops.push(Operation::new("G", vec![0.into()]));
+                
+                let inner = width - 12.0;
+                let max_lines = ((height - 12.0) / (NOTE_SIZE * LINE_HEIGHT)) as usize;
+                let lines: Vec<String> = text.lines().flat_map(|l| wrap(l, inner, NOTE_SIZE)).collect();
+                let truncated = lines.len() > max_lines;
+                for (i, line) in lines.iter().take(max_lines).enumerate() {
+                    let line = if truncated && i + 1 == max_lines { format!("{}…", line) } else { line.clone() };
+                    self.text(x + 6.0, top - 6.0 - NOTE_SIZE * LINE_HEIGHT * (i + 1) as f32 + 2.0, NOTE_SIZE, &line);
+                }
+                if let Some(line) = wrap(title, width, CAPTION_SIZE).first() {
+                    self.text(x, top - height - CAPTION_SIZE * LINE_HEIGHT, CAPTION_SIZE, line);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.ops.push(Operation::new("BT", vec![]));
+        self.ops.push(Operation::new("Tf", vec!["F1".into(), size.into()]));
+        self.ops.push(Operation::new("Td", vec![x.into(), y.into()]));
+        self.ops.push(Operation::new("Tj", vec![Object::string_literal(win_ansi(text))]));
+        self.ops.push(Operation::new("ET", vec![]));
+    }
+
+    fn embed_image(&mut self, image: &DynamicImage) -> Result<String, AppError> {
+        // Flatten transparency onto white; JPEG has no alpha
+        let rgba = image.to_rgba8();
+        let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+            Rgb([blend(r), blend(g), blend(b)])
+        });
+        
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(Cursor::new(&mut jpeg), JPEG_QUALITY)
+            .encode_image(&rgb)
+            .map_err(|e| AppError::Unknown(format!("Failed to encode image: {}", e)))?;
+        
+        let stream = Stream::new(dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => rgb.width() as i64,
+            "Height" => rgb.height() as i64,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+            "Filter" => "DCTDecode",
+        }, jpeg).with_compression(false);
+        
+        let name = format!("Im{}", self.images.len() + 1);
+        let id = self.doc.add_object(stream);
+        self.images.push((name.clone(), id));
+        Ok(name)
+    }
+
+    fn finish_page(&mut self) -> Result<(), AppError> {
+        let content = Content { operations: std::mem::take(&mut self.ops) };
+        let encoded = content.encode()
+            .map_err(|e| AppError::Unknown(format!("Failed to encode PDF page: {}", e)))?;
+        let content_id = self.doc.add_object(Stream::new(dictionary! {}, encoded));
+        
+        let mut xobjects = lopdf::Dictionary::new();
+        for (name, id) in self.images.drain(..) {
+            xobjects.set(name, id);
+        }
+        let page_id = self.doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => self.pages_id,
+            "Contents" => content_id,
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => self.font_id },
+                "XObject" => xobjects,
+            },
+        });
+        self.pages.push(page_id);
+        self.cursor = self.height - MARGIN;
+        Ok(())
+    }
+
+    fn save(mut self, path: &Path) -> Result<(), AppError> {
+        let kids: Vec<Object> = self.pages.iter().map(|id| (*id).into()).collect();
+        let pages = dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => self.pages.len() as i64,
+            "MediaBox" => vec![0.into(), 0.into(), self.width.into(), self.height.into()],
+        };
+        self.doc.objects.insert(self.pages_id, Object::Dictionary(pages));
+        let catalog_id = self.doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => self.pages_id,
+        });
+        self.doc.trailer.set("Root", catalog_id);
+        self.doc.compress();
+        self.doc.save(path)
+            .map_err(|e| AppError::Io(format!("Failed to write PDF: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Greedy word wrap using an average Helvetica glyph width
+fn wrap(text: &str, width: f32, size: f32) -> Vec<String> {
+    let max_chars = ((width / (size * 0.5)) as usize).max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    
+    for word in text.split_whitespace() {
+        let needed = line.chars().count() + usize::from(!line.is_empty()) + word.chars().count();
+        if needed > max_chars && !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Encode for the WinAnsi base font: Latin-1 passes through, a few common
+/// typographic characters are mapped, anything else becomes '?'
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '…' => 0x85,
+            '–' => 0x96,
+            '—' => 0x97,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '€' => 0x80,
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u32 as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, Graph, Position, ProjectMeta, SynniaNodeData, ValueType, Viewport};
+    use image::{Rgba, RgbaImage};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn node(id: &str, x: f64, y: f64, parent: Option<&str>, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "asset-node".to_string(),
+            position: Position { x, y },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn asset(id: &str, value: serde_json::Value) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value,
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    fn project(nodes: Vec<SynniaNode>, assets: Vec<Asset>) -> SynniaProject {
+        SynniaProject {
+            version: "2".to_string(),
+            meta: ProjectMeta {
+                id: "p".to_string(),
+                name: "Review Deck".to_string(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                thumbnail: None,
+                description: None,
+                author: None,
+            },
+            viewport: Viewport { x: 0.0, y: 0.0, zoom: 1.0 },
+            graph: Graph { nodes, edges: vec![] },
+            assets: assets.into_iter().map(|a| (a.id.clone(), a)).collect(),
+            settings: None,
+            canvas_id: None,
+        }
+    }
+
+    #[test]
+    fn test_sections_follow_groups_and_order() {
+        let board = project(vec![
+            node("loose", 0.0, 0.0, None, None),
+            node("shots", 0.0, 100.0, None, None),
+            node("shot-2", 0.0, 50.0, Some("shots"), None),
+            node("shot-1", 0.0, 10.0, Some("shots"), None),
+            node("alts", 0.0, 90.0, Some("shots"), None),
+            node("alt-1", 0.0, 0.0, Some("alts"), None),
+        ], vec![]);
+        
+        let sections = collect_sections(&board, &[]).unwrap();
+        let layout: Vec<(Option<&str>, Vec<&str>)> = sections.iter()
+            .map(|s| (s.title.as_deref(), s.items.iter().map(|n| n.id.as_str()).collect()))
+            .collect();
+        assert_eq!(layout, vec![
+            (None, vec!["loose"]),
+            (Some("shots"), vec!["shot-1", "shot-2"]),
+            (Some("shots / alts"), vec!["alt-1"]),
+        ]);
+        
+        let selected = collect_sections(&board, &["alts".to_string(), "alt-1".to_string()]).unwrap();
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[1].items.len(), 1, "selected child of a selected group is not duplicated");
+    }
+
+    #[test]
+    fn test_export_pdf() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        RgbaImage::from_pixel(40, 30, Rgba([200, 10, 10, 255]))
+            .save(dir.path().join("assets/shot.png"))
+            .unwrap();
+        
+        let mut nodes = Vec::new();
+        let mut assets = vec![
+            asset("img", json!({ "src": "assets/shot.png" })),
+            asset("note", json!({ "content": "Warmer grade – “approved”", "format": "plain" })),
+        ];
+        for i in 0..14 {
+            nodes.push(node(&format!("shot-{}", i), i as f64, 0.0, None, Some("img")));
+        }
+        nodes.push(node("notes", 0.0, 10.0, None, Some("note")));
+        assets.push(asset("table", json!([])));
+        nodes.push(node("table", 0.0, 20.0, None, Some("table")));
+        
+        let board = project(nodes, assets);
+        let shot_len = std::fs::metadata(dir.path().join("assets/shot.png")).unwrap().len();
+        assert_eq!(estimated_size(dir.path(), &board, &PdfExportOptions::default()).unwrap(), 14 * shot_len);
+        let result = export_pdf(dir.path(), &board, &PdfExportOptions::default(), None, &CancellationToken::new()).unwrap();
+        assert_eq!(result.path, "exports/Review_Deck.pdf");
+        assert_eq!(result.items, 15, "unsupported assets are skipped");
+        assert_eq!(result.pages, 2);
+        
+        let doc = Document::load(dir.path().join(&result.path)).unwrap();
+        assert_eq!(doc.get_pages().len(), 2);
+    }
+
+    #[test]
+    fn test_wrap_and_encoding() {
+        assert_eq!(wrap("one two three four", 40.0, 10.0), vec!["one two", "three", "four"]);
+        assert_eq!(win_ansi("é…中"), vec![0xE9, 0x85, b'?']);
+    }
+}
@@ -0,0 +1,305 @@
+//! Capability checks for actions that don't come from the user's own hands:
+//! agent actions, browser clippings and other automation.
+//!
+//! Each project has a policy mapping (actor, capability) to allow, prompt or
+//! deny. A prompt is emitted as `permission:request` and waits for
+//! `resolve_permission_request`; nobody answering within `PROMPT_TIMEOUT`
+//! counts as a denial. Every decision other than a silent allow lands in the
+//! activity feed.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::services::{database, io_sqlite};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use ts_rs::TS;
+
+/// Settings key of the permission policy
+pub const SETTINGS_KEY: &str = "permissions";
+
+/// How long a prompt waits for an answer
+pub const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Who is asking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum Actor {
+    /// Actions proposed by an agent run
+    Agent,
+    /// The browser extension's clip endpoint and phone capture
+    Clip,
+    /// Webhooks, tool servers, `synnia://` links and other external triggers
+    Automation,
+}
+
+impl Actor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Actor::Agent => "agent",
+            Actor::Clip => "clip",
+            Actor::Automation => "automation",
+        }
+    }
+}
+
+/// What they want to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    CreateNodes,
+    ModifyNodes,
+    DeleteNodes,
+    ImportAssets,
+    /// Run a recipe node, spending provider credits
+    RunRecipes,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::CreateNodes => "create-nodes",
+            Capability::ModifyNodes => "modify-nodes",
+            Capability::DeleteNodes => "delete-nodes",
+            Capability::ImportAssets => "import-assets",
+            Capability::RunRecipes => "run-recipes",
+        }
+    }
+
+    /// Additive actions are allowed unless configured otherwise; changing or
+    /// removing existing work, or spending on providers, asks first.
+    pub fn default_decision(&self) -> Decision {
+        match self {
+            Capability::CreateNodes | Capability::ImportAssets => Decision::Allow,
+            Capability::ModifyNodes | Capability::DeleteNodes | Capability::RunRecipes => Decision::Prompt,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Allow,
+    Prompt,
+    Deny,
+}
+
+/// One policy entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRule {
+    pub actor: Actor,
+    pub capability: Capability,
+    pub decision: Decision,
+}
+
+/// Per-project permission policy; pairs without a rule use the capability's default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PermissionPolicy {
+    pub rules: Vec<PermissionRule>,
+}
+
+impl PermissionPolicy {
+    pub fn decision(&self, actor: Actor, capability: Capability) -> Decision {
+        self.rules.iter()
+            .find(|r| r.actor == actor && r.capability == capability)
+            .map(|r| r.decision)
+            .unwrap_or_else(|| capability.default_decision())
+    }
+
+    /// Add or replace the rule for the rule's actor and capability.
+    pub fn set(&mut self, rule: PermissionRule) {
+        self.rules.retain(|r| !(r.actor == rule.actor && r.capability == rule.capability));
+        self.rules.push(rule);
+    }
+}
+
+/// A prompt shown to the user
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRequest {
+    pub id: String,
+    pub actor: Actor,
+    pub capability: Capability,
+    /// What will happen, e.g. "Delete 3 nodes"
+    pub summary: String,
+    pub target_ids: Vec<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+pub fn get_policy(conn: &Connection) -> Result<PermissionPolicy, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Store the policy, one rule per actor and capability (the last one wins).
+pub fn save_policy(conn: &Connection, policy: &PermissionPolicy) -> Result<PermissionPolicy, AppError> {
+    let mut normalized = PermissionPolicy::default();
+    for rule in &policy.rules {
+        normalized.set(rule.clone());
+    }
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(&normalized)?)?;
+    Ok(normalized)
+}
+
+/// Open prompts by ID, with the channel their answer goes to
+type PendingRequests = HashMap<String, (PermissionRequest, oneshot::Sender<bool>)>;
+
+/// Prompts waiting for the user's answer
+#[derive(Default)]
+pub struct PermissionGate {
+    pending: Mutex<PendingRequests>,
+}
+
+impl PermissionGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check an action against the project's policy, asking the user when
+    /// the policy says so. Errors with `Forbidden` when it may not proceed.
+    pub async fn authorize(
+        &self,
+        app: &AppHandle,
+        project_root: &Path,
+        actor: Actor,
+        capability: Capability,
+        summary: impl Into<String>,
+        target_ids: Vec<String>,
+    ) -> Result<(), AppError> {
+        let summary = summary.into();
+        let decision = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))
+            .and_then(|conn| get_policy(&conn))?
+            .decision(actor, capability);
+
+        let (allowed, verdict) = match decision {
+            Decision::Allow => return Ok(()),
+            Decision::Deny => (false, "Denied by policy"),
+            Decision::Prompt => {
+                let request = PermissionRequest {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    actor,
+                    capability,
+                    summary: summary.clone(),
+                    target_ids,
+                    created_at: chrono::Utc::now().timestamp_millis(),
+                };
+                let id = request.id.clone();
+                let (tx, rx) = oneshot::channel();
+                self.lock()?.insert(id.clone(), (request.clone(), tx));
+                events::emit(app, AppEvent::PermissionRequest(request))?;
+
+                let answer = tokio::time::timeout(PROMPT_TIMEOUT, rx).await;
+                self.lock()?.remove(&id);
+                match answer {
+                    Ok(Ok(true)) => (true, "Allowed by user"),
+                    Ok(Ok(false)) => (false, "Denied by user"),
+                    _ => (false, "Denied (no answer)"),
+                }
+            }
+        };
+
+        activity::log(project_root, NewActivity::new(
+            ActivityKind::PermissionDecided,
+            actor.as_str(),
+            format!("{}: {}", verdict, summary),
+        ).source(capability.as_str()));
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(format!("{} is not allowed to {}", actor.as_str(), capability.as_str())))
+        }
+    }
+
+    /// Answer a prompt. Returns the request that was answered.
+    pub fn resolve(&self, request_id: &str, allow: bool) -> Result<PermissionRequest, AppError> {
+        let (request, tx) = self.lock()?.remove(request_id)
+            .ok_or_else(|| AppError::NotFound(format!("Permission request not found: {}", request_id)))?;
+        // The waiting side may have timed out in the meantime
+        let _ = tx.send(allow);
+        Ok(request)
+    }
+
+    /// Prompts still waiting, oldest first
+    pub fn pending(&self) -> Result<Vec<PermissionRequest>, AppError> {
+        let mut requests: Vec<PermissionRequest> = self.lock()?.values().map(|(r, _)| r.clone()).collect();
+        requests.sort_by_key(|r| r.created_at);
+        Ok(requests)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, PendingRequests>, AppError> {
+        self.pending.lock().map_err(|_| AppError::Unknown("Permission lock poisoned".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_policy() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+
+        let policy = get_policy(&conn).unwrap();
+        assert_eq!(policy.decision(Actor::Agent, Capability::CreateNodes), Decision::Allow);
+        assert_eq!(policy.decision(Actor::Automation, Capability::DeleteNodes), Decision::Prompt);
+        assert_eq!(policy.decision(Actor::Automation, Capability::RunRecipes), Decision::Prompt);
+
+        let rule = |actor, capability, decision| PermissionRule { actor, capability, decision };
+        let saved = save_policy(&conn, &PermissionPolicy { rules: vec![
+            rule(Actor::Automation, Capability::DeleteNodes, Decision::Allow),
+            rule(Actor::Automation, Capability::DeleteNodes, Decision::Deny),
+            rule(Actor::Clip, Capability::ImportAssets, Decision::Prompt),
+        ] }).unwrap();
+        assert_eq!(saved.rules.len(), 2);
+
+        let policy = get_policy(&conn).unwrap();
+        assert_eq!(policy.decision(Actor::Automation, Capability::DeleteNodes), Decision::Deny);
+        assert_eq!(policy.decision(Actor::Clip, Capability::ImportAssets), Decision::Prompt);
+        assert_eq!(policy.decision(Actor::Agent, Capability::DeleteNodes), Decision::Prompt);
+    }
+
+    #[test]
+    fn test_policy_survives_project_save() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Policy").unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        save_policy(&conn, &PermissionPolicy { rules: vec![
+            PermissionRule { actor: Actor::Agent, capability: Capability::DeleteNodes, decision: Decision::Deny },
+        ] }).unwrap();
+
+        // The frontend's autosave sends no settings
+        project.settings = Some(HashMap::new());
+        io_sqlite::save_project_sqlite(dir.path(), &project).unwrap();
+
+        let policy = get_policy(&conn).unwrap();
+        assert_eq!(policy.decision(Actor::Agent, Capability::DeleteNodes), Decision::Deny);
+    }
+
+    #[test]
+    fn test_resolve_unknown_request() {
+        let gate = PermissionGate::new();
+        assert!(gate.pending().unwrap().is_empty());
+        assert!(matches!(gate.resolve("missing", true), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,560 @@
+//! Checkpointed pipeline runs over graphs of recipe nodes.
+//!
+//! Recipes still execute in the frontend; the backend owns the run: the
+//! nodes in dependency order (edges between them), each node's state and
+//! output asset saved in the project database as the frontend reports them,
+//! and the scheduling. Every node whose inputs are done is handed out as
+//! ready, so independent branches run side by side up to the run's
+//! concurrency and each provider's limit. A rate-limited node goes back to
+//! pending and pauses its provider for the time the provider asked for.
+//!
+//! A failed node only blocks the nodes downstream of it; a cancelled one
+//! stops the whole run. Resuming keeps every usable checkpoint, so upstream
+//! generations aren't paid for twice. A checkpoint whose output asset is
+//! gone no longer counts, and neither does anything downstream of it.
+//!
+//! Finished nodes also snapshot their output value, so runs can be compared
+//! after later runs have overwritten the assets (see `run_comparison`).
+
+use std::collections::{HashMap, HashSet};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::io_sqlite;
+use crate::services::job_queue::{TaskStatus, MAX_CONCURRENCY};
+use ts_rs::TS;
+
+/// Upper bound of nodes per run
+pub const MAX_STEPS: usize = 200;
+
+/// How a run is scheduled
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineOptions {
+    /// Nodes running at once, 1 (the default) to 8
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Provider of each node by node ID, e.g. "gemini"
+    #[serde(default)]
+    pub providers: HashMap<String, String>,
+    /// Nodes running at once per provider
+    #[serde(default)]
+    pub provider_limits: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStep {
+    pub node_id: String,
+    pub status: TaskStatus,
+    /// Nodes of the run feeding this one
+    pub depends_on: Vec<String>,
+    pub provider: Option<String>,
+    /// Checkpoint: the asset the node produced
+    pub output_asset_id: Option<String>,
+    pub error: Option<String>,
+    #[ts(type = "number | null")]
+    pub started_at: Option<i64>,
+    #[ts(type = "number | null")]
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRun {
+    pub id: String,
+    /// Derived from the steps: running while anything runs or can start
+    pub status: TaskStatus,
+    /// In execution order
+    pub steps: Vec<PipelineStep>,
+    pub concurrency: usize,
+    pub provider_limits: HashMap<String, usize>,
+    /// Rate-limited providers and when they may be used again
+    #[ts(type = "Record<string, number>")]
+    pub paused_until: HashMap<String, i64>,
+    /// Nodes to start now; report them as running before starting them
+    pub ready_node_ids: Vec<String>,
+    /// When paused nodes may start, if that's all that's left
+    #[ts(type = "number | null")]
+    pub resume_at: Option<i64>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// Plan a run over `node_ids`, ordered so every node comes after the nodes
+/// feeding it. Ties keep the given order.
+pub fn start(conn: &Connection, node_ids: &[String], options: &PipelineOptions) -> Result<PipelineRun, AppError> {
+    let mut unique: Vec<&String> = Vec::new();
+    for node_id in node_ids {
+        if !unique.contains(&node_id) {
+            unique.push(node_id);
+        }
+    }
+    if unique.is_empty() || unique.len() > MAX_STEPS {
+        return Err(AppError::InvalidInput(format!("Pipelines need 1 to {} nodes", MAX_STEPS)));
+    }
+    for node_id in &unique {
+        if io_sqlite::get_node(conn, node_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Node not found: {}", node_id)));
+        }
+    }
+    if let Some((provider, _)) = options.provider_limits.iter().find(|(_, &limit)| limit == 0) {
+        return Err(AppError::InvalidInput(format!("The limit of {} must be at least 1", provider)));
+    }
+    let concurrency = options.concurrency.unwrap_or(1).clamp(1, MAX_CONCURRENCY);
+    let (order, dependencies) = execution_order(conn, &unique)?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let run_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO pipeline_runs (id, concurrency, provider_limits_json, provider_pauses_json, created_at, updated_at)
+         VALUES (?1, ?2, ?3, '{}', ?4, ?4)",
+        params![run_id, concurrency as i64, serde_json::to_string(&options.provider_limits)?, now],
+    ).map_err(|e| AppError::Io(format!("Failed to save pipeline: {}", e)))?;
+    for (position, node_id) in order.iter().enumerate() {
+        let depends_on = dependencies.get(node_id.as_str()).cloned().unwrap_or_default();
+        conn.execute(
+            "INSERT INTO pipeline_steps (run_id, node_id, position, status, depends_on_json, provider)
+             VALUES (?1, ?2, ?3, 'pending', ?4, ?5)",
+            params![run_id, node_id, position as i64, serde_json::to_string(&depends_on)?, options.providers.get(*node_id)],
+        ).map_err(|e| AppError::Io(format!("Failed to save pipeline step: {}", e)))?;
+    }
+    get_run(conn, &run_id)?
+        .ok_or_else(|| AppError::Unknown("Pipeline vanished after saving".to_string()))
+}
+
+/// Save what happened to one node. Finished nodes can't be changed; resume
+/// the run to retry failed ones. A node reported back as pending with
+/// `retry_after_ms` was rate limited: it waits, and so does its provider.
+pub fn record_step(
+    conn: &Connection,
+    run_id: &str,
+    node_id: &str,
+    status: TaskStatus,
+    output_asset_id: Option<&str>,
+    error: Option<&str>,
+    retry_after_ms: Option<u64>,
+) -> Result<PipelineRun, AppError> {
+    let run = get_run(conn, run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))?;
+    let step = run.steps.iter().find(|s| s.node_id == node_id)
+        .ok_or_else(|| AppError::NotFound(format!("Node {} isn't part of pipeline {}", node_id, run_id)))?;
+    if step.status == TaskStatus::Success {
+        return Err(AppError::InvalidInput(format!("Node {} already finished", node_id)));
+    }
+    if status == TaskStatus::Pending && retry_after_ms.is_none() {
+        return Err(AppError::InvalidInput("Resume the pipeline to run nodes again".to_string()));
+    }
+    if status == TaskStatus::Success && output_asset_id.is_none() {
+        return Err(AppError::InvalidInput("Finished nodes need their output asset".to_string()));
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let (started_at, finished_at) = match status {
+        TaskStatus::Pending => (None, None),
+        TaskStatus::Running => (Some(now), None),
+        _ => (step.started_at.or(Some(now)), Some(now)),
+    };
+    // Snapshot first, so a missing asset leaves the step as it was
+    if let (TaskStatus::Success, Some(asset_id)) = (status, output_asset_id) {
+        let saved = conn.execute(
+            "INSERT OR REPLACE INTO pipeline_outputs (run_id, node_id, asset_id, value_hash, value_json, created_at)
+             SELECT ?1, ?2, id, value_hash, value_json, ?3 FROM assets WHERE id = ?4",
+            params![run_id, node_id, now, asset_id],
+        ).map_err(|e| AppError::Io(format!("Failed to save pipeline output: {}", e)))?;
+        if saved == 0 {
+            return Err(AppError::NotFound(format!("Asset not found: {}", asset_id)));
+        }
+    }
+
+    conn.execute(
+        "UPDATE pipeline_steps SET status = ?1, output_asset_id = ?2, error = ?3, started_at = ?4, finished_at = ?5
+         WHERE run_id = ?6 AND node_id = ?7",
+        params![status_name(status), output_asset_id, error, started_at, finished_at, run_id, node_id],
+    ).map_err(|e| AppError::Io(format!("Failed to save pipeline step: {}", e)))?;
+
+    if let (TaskStatus::Pending, Some(retry_after_ms)) = (status, retry_after_ms) {
+        let until = now.saturating_add(i64::try_from(retry_after_ms).unwrap_or(i64::MAX));
+        let mut pauses = run.paused_until.clone();
+        let paused = pauses.entry(pause_key(step)).or_insert(until);
+        *paused = (*paused).max(until);
+        conn.execute(
+            "UPDATE pipeline_runs SET provider_pauses_json = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&pauses)?, run_id],
+        ).map_err(|e| AppError::Io(format!("Failed to pause provider: {}", e)))?;
+    }
+    touch(conn, run_id, now)?;
+    get_run(conn, run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))
+}
+
+/// Make a run resumable: every node without a usable checkpoint, and every
+/// node downstream of one, goes back to pending. Finished runs can't be
+/// resumed.
+pub fn resume(conn: &Connection, run_id: &str) -> Result<PipelineRun, AppError> {
+    let run = get_run(conn, run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))?;
+    if run.status == TaskStatus::Success {
+        return Err(AppError::InvalidInput("Pipeline already finished".to_string()));
+    }
+
+    // Steps are in dependency order, so upstream resets are known in time
+    let mut reset: HashSet<&str> = HashSet::new();
+    for step in &run.steps {
+        let usable = match (&step.status, &step.output_asset_id) {
+            (TaskStatus::Success, Some(asset_id)) => io_sqlite::get_asset(conn, asset_id)?.is_some(),
+            _ => false,
+        };
+        if !usable || step.depends_on.iter().any(|d| reset.contains(d.as_str())) {
+            reset.insert(&step.node_id);
+        }
+    }
+    for node_id in reset {
+        conn.execute(
+            "UPDATE pipeline_steps SET status = 'pending', output_asset_id = NULL, error = NULL,
+                 started_at = NULL, finished_at = NULL
+             WHERE run_id = ?1 AND node_id = ?2",
+            params![run_id, node_id],
+        ).map_err(|e| AppError::Io(format!("Failed to reset pipeline steps: {}", e)))?;
+        conn.execute(
+            "DELETE FROM pipeline_outputs WHERE run_id = ?1 AND node_id = ?2",
+            params![run_id, node_id],
+        ).map_err(|e| AppError::Io(format!("Failed to reset pipeline outputs: {}", e)))?;
+    }
+    touch(conn, run_id, chrono::Utc::now().timestamp_millis())?;
+    get_run(conn, run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))
+}
+
+pub fn get_run(conn: &Connection, run_id: &str) -> Result<Option<PipelineRun>, AppError> {
+    let run: Option<(i64, String, String, i64, i64)> = conn.query_row(
+        "SELECT concurrency, provider_limits_json, provider_pauses_json, created_at, updated_at
+         FROM pipeline_runs WHERE id = ?1",
+        params![run_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    ).optional().map_err(|e| AppError::Io(format!("Failed to load pipeline: {}", e)))?;
+    let Some((concurrency, limits, pauses, created_at, updated_at)) = run else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id, status, depends_on_json, provider, output_asset_id, error, started_at, finished_at
+         FROM pipeline_steps WHERE run_id = ?1 ORDER BY position"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let steps = stmt.query_map(params![run_id], |row| {
+        let status: String = row.get(1)?;
+        let depends_on: String = row.get(2)?;
+        Ok(PipelineStep {
+            node_id: row.get(0)?,
+            status: parse_status(&status),
+            depends_on: serde_json::from_str(&depends_on).unwrap_or_default(),
+            provider: row.get(3)?,
+            output_asset_id: row.get(4)?,
+            error: row.get(5)?,
+            started_at: row.get(6)?,
+            finished_at: row.get(7)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query pipeline steps: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load pipeline steps: {}", e)))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut run = PipelineRun {
+        id: run_id.to_string(),
+        status: TaskStatus::Pending,
+        steps,
+        concurrency: (concurrency.max(1) as usize).min(MAX_CONCURRENCY),
+        provider_limits: serde_json::from_str(&limits).unwrap_or_default(),
+        paused_until: serde_json::from_str::<HashMap<String, i64>>(&pauses).unwrap_or_default()
+            .into_iter()
+            .filter(|(_, until)| *until > now)
+            .collect(),
+        ready_node_ids: Vec::new(),
+        resume_at: None,
+        created_at,
+        updated_at,
+    };
+    schedule(&mut run);
+    Ok(Some(run))
+}
+
+/// Most recently updated runs first.
+pub fn list_runs(conn: &Connection, limit: usize) -> Result<Vec<PipelineRun>, AppError> {
+    let mut stmt = conn.prepare("SELECT id FROM pipeline_runs ORDER BY updated_at DESC LIMIT ?1")
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let ids = stmt.query_map(params![limit as i64], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Io(format!("Failed to query pipelines: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load pipelines: {}", e)))?;
+
+    let mut runs = Vec::with_capacity(ids.len());
+    for id in ids {
+        runs.extend(get_run(conn, &id)?);
+    }
+    Ok(runs)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+type Plan<'a> = (Vec<&'a String>, HashMap<&'a str, Vec<String>>);
+
+/// Kahn's algorithm over the edges between the nodes, giving the order and
+/// each node's upstream nodes; fails on cycles
+fn execution_order<'a>(conn: &Connection, node_ids: &[&'a String]) -> Result<Plan<'a>, AppError> {
+    let ids_json = serde_json::to_string(node_ids)?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT source, target FROM edges
+         WHERE source IN (SELECT value FROM json_each(?1)) AND target IN (SELECT value FROM json_each(?1))
+           AND source <> target"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let edges = stmt.query_map(params![ids_json], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| AppError::Io(format!("Failed to query edges: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load edges: {}", e)))?;
+
+    let mut dependencies: HashMap<&str, Vec<String>> = HashMap::new();
+    for id in node_ids {
+        let sources = edges.iter().filter(|(_, target)| target == *id).map(|(source, _)| source.clone()).collect();
+        dependencies.insert(id.as_str(), sources);
+    }
+    let mut order: Vec<&String> = Vec::with_capacity(node_ids.len());
+    let mut placed: HashSet<&str> = HashSet::new();
+    while order.len() < node_ids.len() {
+        let Some(next) = node_ids.iter().find(|id| {
+            !placed.contains(id.as_str()) && dependencies[id.as_str()].iter().all(|d| placed.contains(d.as_str()))
+        }) else {
+            return Err(AppError::InvalidInput("The pipeline's nodes form a cycle".to_string()));
+        };
+        placed.insert(next.as_str());
+        order.push(next);
+    }
+    Ok((order, dependencies))
+}
+
+/// Fill in the ready nodes, when paused ones may start, and the status.
+/// Expired pauses have already been dropped.
+fn schedule(run: &mut PipelineRun) {
+    let succeeded: HashSet<&str> = run.steps.iter()
+        .filter(|s| s.status == TaskStatus::Success)
+        .map(|s| s.node_id.as_str())
+        .collect();
+    let mut running = run.steps.iter().filter(|s| s.status == TaskStatus::Running).count();
+    let mut per_provider: HashMap<&str, usize> = HashMap::new();
+    for step in run.steps.iter().filter(|s| s.status == TaskStatus::Running) {
+        *per_provider.entry(step.provider.as_deref().unwrap_or_default()).or_default() += 1;
+    }
+
+    let cancelled = run.steps.iter().any(|s| s.status == TaskStatus::Cancelled);
+    for step in run.steps.iter().filter(|s| !cancelled && s.status == TaskStatus::Pending) {
+        if !step.depends_on.iter().all(|d| succeeded.contains(d.as_str())) {
+            continue;
+        }
+        if let Some(&until) = run.paused_until.get(&pause_key(step)) {
+            run.resume_at = Some(run.resume_at.map_or(until, |at| at.min(until)));
+            continue;
+        }
+        if running >= run.concurrency {
+            break;
+        }
+        let provider = step.provider.as_deref().unwrap_or_default();
+        let limit = run.provider_limits.get(provider).copied().unwrap_or(usize::MAX);
+        let busy = per_provider.entry(provider).or_default();
+        if *busy >= limit {
+            continue;
+        }
+        *busy += 1;
+        running += 1;
+        run.ready_node_ids.push(step.node_id.clone());
+    }
+
+    let any = |status: TaskStatus| run.steps.iter().any(|s| s.status == status);
+    run.status = if run.steps.iter().all(|s| s.status == TaskStatus::Pending) {
+        TaskStatus::Pending
+    } else if any(TaskStatus::Running) || !run.ready_node_ids.is_empty() || run.resume_at.is_some() {
+        TaskStatus::Running
+    } else if any(TaskStatus::Cancelled) {
+        TaskStatus::Cancelled
+    } else if any(TaskStatus::Error) {
+        TaskStatus::Error
+    } else if run.steps.iter().all(|s| s.status == TaskStatus::Success) {
+        TaskStatus::Success
+    } else {
+        TaskStatus::Running
+    };
+}
+
+/// Nodes without a provider are paused together
+fn pause_key(step: &PipelineStep) -> String {
+    step.provider.clone().unwrap_or_default()
+}
+
+fn status_name(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::Running => "running",
+        TaskStatus::Success => "success",
+        TaskStatus::Error => "error",
+        TaskStatus::Cancelled => "cancelled",
+    }
+}
+
+fn parse_status(name: &str) -> TaskStatus {
+    match name {
+        "running" => TaskStatus::Running,
+        "success" => TaskStatus::Success,
+        "error" => TaskStatus::Error,
+        "cancelled" => TaskStatus::Cancelled,
+        _ => TaskStatus::Pending,
+    }
+}
+
+fn touch(conn: &Connection, run_id: &str, now: i64) -> Result<(), AppError> {
+    conn.execute("UPDATE pipeline_runs SET updated_at = ?1 WHERE id = ?2", params![now, run_id])
+        .map_err(|e| AppError::Io(format!("Failed to update pipeline: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaEdge, SynniaNode, SynniaNodeData};
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn node(id: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: format!("recipe:{}", id),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData::default(),
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> SynniaEdge {
+        SynniaEdge {
+            id: format!("{}-{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }
+    }
+
+    fn insert_asset(conn: &Connection, id: &str) {
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+             VALUES (?1, 'record', '', '{}', '{\"name\":\"out\",\"createdAt\":0,\"updatedAt\":0,\"source\":\"ai\"}', 0)",
+            params![id],
+        ).unwrap();
+    }
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> (tempfile::TempDir, Connection) {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for id in nodes {
+            io_sqlite::upsert_node(&conn, &node(id)).unwrap();
+        }
+        for (source, target) in edges {
+            io_sqlite::upsert_edge(&conn, &edge(source, target)).unwrap();
+        }
+        (dir, conn)
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pipeline_checkpoints() {
+        let (_dir, conn) = graph(&["script", "storyboard", "render"], &[("script", "storyboard"), ("storyboard", "render")]);
+
+        let nodes = ids(&["render", "script", "storyboard"]);
+        let run = start(&conn, &nodes, &PipelineOptions::default()).unwrap();
+        let order: Vec<&str> = run.steps.iter().map(|s| s.node_id.as_str()).collect();
+        assert_eq!(order, ["script", "storyboard", "render"]);
+        assert_eq!(run.status, TaskStatus::Pending);
+
+        insert_asset(&conn, "script-out");
+        insert_asset(&conn, "board-out");
+        record_step(&conn, &run.id, "script", TaskStatus::Success, Some("script-out"), None, None).unwrap();
+        record_step(&conn, &run.id, "storyboard", TaskStatus::Success, Some("board-out"), None, None).unwrap();
+        let failed = record_step(&conn, &run.id, "render", TaskStatus::Error, None, Some("Quota exceeded"), None).unwrap();
+        assert_eq!(failed.status, TaskStatus::Error);
+        assert!(failed.ready_node_ids.is_empty());
+        assert!(matches!(
+            record_step(&conn, &run.id, "script", TaskStatus::Error, None, None, None),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        // Upstream checkpoints survive; only the failed node runs again
+        let resumed = resume(&conn, &run.id).unwrap();
+        assert_eq!(resumed.status, TaskStatus::Running);
+        assert_eq!(resumed.ready_node_ids, ["render"]);
+        assert_eq!(resumed.steps[1].output_asset_id.as_deref(), Some("board-out"));
+
+        // A checkpoint whose asset is gone reruns from there
+        conn.execute("DELETE FROM assets WHERE id = 'board-out'", []).unwrap();
+        record_step(&conn, &run.id, "render", TaskStatus::Cancelled, None, None, None).unwrap();
+        let resumed = resume(&conn, &run.id).unwrap();
+        assert_eq!(resumed.ready_node_ids, ["storyboard"]);
+        assert_eq!(resumed.steps[0].status, TaskStatus::Success);
+
+        assert_eq!(list_runs(&conn, 10).unwrap().len(), 1);
+        io_sqlite::upsert_edge(&conn, &edge("render", "script")).unwrap();
+        assert!(matches!(start(&conn, &nodes, &PipelineOptions::default()), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_parallel_scheduling() {
+        // brief feeds two image branches that meet in a layout; music is independent
+        let (_dir, conn) = graph(
+            &["brief", "hero", "banner", "layout", "music"],
+            &[("brief", "hero"), ("brief", "banner"), ("hero", "layout"), ("banner", "layout")],
+        );
+        let options = PipelineOptions {
+            concurrency: Some(2),
+            providers: HashMap::from([
+                ("hero".to_string(), "gemini".to_string()),
+                ("banner".to_string(), "gemini".to_string()),
+            ]),
+            provider_limits: HashMap::from([("gemini".to_string(), 1)]),
+        };
+        let run = start(&conn, &ids(&["brief", "hero", "banner", "layout", "music"]), &options).unwrap();
+        assert_eq!(run.ready_node_ids, ["brief", "music"]);
+        assert_eq!(run.steps[3].depends_on, ["hero", "banner"]);
+
+        record_step(&conn, &run.id, "brief", TaskStatus::Running, None, None, None).unwrap();
+        let run = record_step(&conn, &run.id, "music", TaskStatus::Running, None, None, None).unwrap();
+        assert!(run.ready_node_ids.is_empty(), "concurrency is full");
+
+        insert_asset(&conn, "brief-out");
+        let run = record_step(&conn, &run.id, "brief", TaskStatus::Success, Some("brief-out"), None, None).unwrap();
+        assert_eq!(run.ready_node_ids, ["hero"], "one gemini node at a time");
+
+        // Rate limited: the node waits and gemini is paused
+        record_step(&conn, &run.id, "hero", TaskStatus::Running, None, None, None).unwrap();
+        let run = record_step(&conn, &run.id, "hero", TaskStatus::Pending, None, Some("429"), Some(60_000)).unwrap();
+        assert!(run.ready_node_ids.is_empty());
+        assert!(run.resume_at.is_some());
+        assert_eq!(run.status, TaskStatus::Running);
+
+        // A failed branch doesn't stop independent ones
+        let run = record_step(&conn, &run.id, "music", TaskStatus::Error, None, Some("Timeout"), None).unwrap();
+        assert_eq!(run.status, TaskStatus::Running);
+    }
+}
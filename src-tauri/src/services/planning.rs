@@ -0,0 +1,245 @@
+//! Planning queries over node `startDate` / `dueDate` / `status` fields,
+//! backing timeline, calendar and kanban views.
+//!
+//! The fields live in `nodes.data_json`; the `start_date`, `due_date` and
+//! `status` generated columns expose them to indexed queries.
+
+use std::collections::HashMap;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use ts_rs::TS;
+
+/// Kanban column order for well-known statuses; others follow alphabetically
+const STATUS_ORDER: &[&str] = &["todo", "in-progress", "review", "done"];
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineRange {
+    /// Inclusive, ISO 8601; compared at the precision given ("2025-03" = whole month)
+    #[serde(default)]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum KanbanGroupBy {
+    #[default]
+    Status,
+    /// Containing group or rack
+    Parent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanningItem {
+    pub node_id: String,
+    pub node_type: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanColumn {
+    /// Status or parent node ID; `None` for items without one
+    pub key: Option<String>,
+    pub title: String,
+    pub items: Vec<PlanningItem>,
+}
+
+/// Dated nodes overlapping the range, ordered by start (or due) date.
+pub fn get_timeline(conn: &Connection, range: &TimelineRange) -> Result<Vec<PlanningItem>, AppError> {
+    let sql = format!(
+        "{} WHERE (n.start_date IS NOT NULL OR n.due_date IS NOT NULL)
+           AND (?1 IS NULL OR substr(COALESCE(n.due_date, n.start_date), 1, length(?1)) >= ?1)
+           AND (?2 IS NULL OR substr(COALESCE(n.start_date, n.due_date), 1, length(?2)) <= ?2)
+         ORDER BY COALESCE(n.start_date, n.due_date), n.id",
+        SELECT_ITEMS
+    );
+    query_items(conn, &sql, params![range.start, range.end])
+}
+
+/// Nodes with any planning field, grouped into columns.
+pub fn get_kanban(conn: &Connection, group_by: KanbanGroupBy) -> Result<Vec<KanbanColumn>, AppError> {
+    let sql = format!(
+        "{} WHERE n.status IS NOT NULL OR n.start_date IS NOT NULL OR n.due_date IS NOT NULL
+         ORDER BY n.due_date IS NULL, n.due_date, n.y, n.x",
+        SELECT_ITEMS
+    );
+    let items = query_items(conn, &sql, [])?;
+    
+    let mut columns: Vec<KanbanColumn> = Vec::new();
+    for item in items {
+        let key = match group_by {
+            KanbanGroupBy::Status => item.status.clone(),
+            KanbanGroupBy::Parent => item.parent_id.clone(),
+        };
+        match columns.iter_mut().find(|c| c.key == key) {
+            Some(column) => column.items.push(item),
+            None => columns.push(KanbanColumn { key, title: String::new(), items: vec![item] }),
+        }
+    }
+    
+    match group_by {
+        KanbanGroupBy::Status => {
+            for column in &mut columns {
+                column.title = column.key.clone().unwrap_or_else(|| "No status".to_string());
+            }
+            columns.sort_by_key(|c| status_rank(c.key.as_deref()));
+        }
+        KanbanGroupBy::Parent => {
+            let titles = parent_titles(conn)?;
+            for column in &mut columns {
+                column.title = match &column.key {
+                    Some(id) => titles.get(id).cloned().unwrap_or_else(|| id.clone()),
+                    None => "Ungrouped".to_string(),
+                };
+            }
+            columns.sort_by_key(|c| (c.key.is_none(), c.title.to_lowercase()));
+        }
+    }
+    
+    Ok(columns)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+const SELECT_ITEMS: &str = "SELECT n.id, n.type, json_extract(n.data_json, '$.title'), json_extract(n.data_json, '$.assetId'),
+        n.parent_id, n.start_date, n.due_date, n.status
+    FROM nodes n";
+
+fn query_items<P: rusqlite::Params>(conn: &Connection, sql: &str, params: P) -> Result<Vec<PlanningItem>, AppError> {
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params, |row| {
+        Ok(PlanningItem {
+            node_id: row.get(0)?,
+            node_type: row.get(1)?,
+            title: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            asset_id: row.get(3)?,
+            parent_id: row.get(4)?,
+            start_date: row.get(5)?,
+            due_date: row.get(6)?,
+            status: row.get(7)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query planning items: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load planning items: {}", e)))
+}
+
+fn parent_titles(conn: &Connection) -> Result<HashMap<String, String>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, json_extract(data_json, '$.title') FROM nodes
+         WHERE id IN (SELECT DISTINCT parent_id FROM nodes WHERE parent_id IS NOT NULL)"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default()))
+    }).map_err(|e| AppError::Io(format!("Failed to query groups: {}", e)))?;
+    
+    rows.collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load groups: {}", e)))
+}
+
+/// Sort key: known statuses in workflow order, then others, then no status
+fn status_rank(status: Option<&str>) -> (usize, String) {
+    match status {
+        Some(s) => {
+            let lower = s.to_lowercase();
+            let rank = STATUS_ORDER.iter().position(|known| *known == lower).unwrap_or(STATUS_ORDER.len());
+            (rank, lower)
+        }
+        None => (STATUS_ORDER.len() + 1, String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::{database, io_sqlite};
+    use tempfile::tempdir;
+
+    fn node(id: &str, parent: Option<&str>, start: Option<&str>, due: Option<&str>, status: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "text-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_uppercase(),
+                start_date: start.map(String::from),
+                due_date: due.map(String::from),
+                status: status.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn board() -> (tempfile::TempDir, Connection) {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for node in [
+            node("sprint", None, None, None, None),
+            node("draft", Some("sprint"), Some("2025-03-01"), Some("2025-03-05"), Some("done")),
+            node("shoot", Some("sprint"), Some("2025-03-10T09:00"), None, Some("todo")),
+            node("launch", None, None, Some("2025-04-01"), Some("blocked")),
+            node("idea", None, None, None, Some("in-progress")),
+        ] {
+            io_sqlite::upsert_node(&conn, &node).unwrap();
+        }
+        (dir, conn)
+    }
+
+    #[test]
+    fn test_timeline_range() {
+        let (_dir, conn) = board();
+        let ids = |range: TimelineRange| -> Vec<String> {
+            get_timeline(&conn, &range).unwrap().into_iter().map(|i| i.node_id).collect()
+        };
+        
+        assert_eq!(ids(TimelineRange::default()), vec!["draft", "shoot", "launch"]);
+        let march = TimelineRange { start: Some("2025-03".to_string()), end: Some("2025-03".to_string()) };
+        assert_eq!(ids(march), vec!["draft", "shoot"]);
+        let overlap = TimelineRange { start: Some("2025-03-04".to_string()), end: Some("2025-03-10".to_string()) };
+        assert_eq!(ids(overlap), vec!["draft", "shoot"]);
+    }
+
+    #[test]
+    fn test_kanban_columns() {
+        let (_dir, conn) = board();
+        
+        let by_status = get_kanban(&conn, KanbanGroupBy::Status).unwrap();
+        let titles: Vec<&str> = by_status.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["todo", "in-progress", "done", "blocked"]);
+        
+        let by_parent = get_kanban(&conn, KanbanGroupBy::Parent).unwrap();
+        assert_eq!(by_parent[0].title, "SPRINT");
+        assert_eq!(by_parent[0].items.len(), 2);
+        assert_eq!(by_parent[1].title, "Ungrouped");
+    }
+}
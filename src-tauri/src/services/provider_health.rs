@@ -0,0 +1,316 @@
+//! Provider health checks and model listing for the settings screen.
+//!
+//! Credentials come from the unified app settings (`providers[key]` with
+//! `apiKey`/`baseUrl`), the same ones the frontend's executors use. A check
+//! asks the provider's model list endpoint, which needs a valid key on every
+//! cloud provider, so one request proves reachability, the key and the
+//! available models.
+
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::services::net_guard::NetworkPolicy;
+use ts_rs::TS;
+
+/// Give up on a provider after this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Key and base URL of a provider, as stored in the app settings
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderCredentials {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl ProviderCredentials {
+    /// Credentials of `provider` in the app settings; empty if not configured.
+    pub fn from_config(config: &GlobalConfig, provider: &str) -> Self {
+        config.app_settings.as_deref()
+            .and_then(|s| serde_json::from_str::<Value>(s).ok())
+            .and_then(|settings| settings.get("providers")?.get(provider).cloned())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// A model offered by a provider
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Outcome of `test_ai_connection`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionReport {
+    pub provider: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[ts(type = "number")]
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response shape of a model list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListStyle {
+    /// `{ data: [{ id }] }` (OpenAI and compatible servers, Anthropic)
+    Data,
+    /// `{ models: [{ name: "models/x", displayName }] }`
+    Google,
+    /// `{ models: [{ name }] }`
+    Ollama,
+    /// `{ results: [{ owner, name }] }`
+    Replicate,
+    /// Checkpoint names of `CheckpointLoaderSimple`
+    ComfyUi,
+}
+
+/// How to ask a provider for its models
+#[derive(Debug, Clone, PartialEq)]
+struct ModelsRequest {
+    url: String,
+    headers: Vec<(String, String)>,
+    style: ListStyle,
+}
+
+/// Ask the provider for its models, timing the round trip.
+pub async fn fetch_models(
+    policy: &NetworkPolicy,
+    provider: &str,
+    credentials: &ProviderCredentials,
+) -> Result<(Vec<ModelInfo>, Duration), AppError> {
+    let request = models_request(provider, credentials)?;
+    let url = policy.check_url(&request.url).await?;
+    let client = policy.client_builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    let mut builder = client.get(url);
+    for (key, value) in &request.headers {
+        builder = builder.header(key, value);
+    }
+    let started = Instant::now();
+    let response = builder.send().await.map_err(|e| {
+        if e.is_timeout() {
+            AppError::Network(format!("{} did not answer within {}s", provider, REQUEST_TIMEOUT.as_secs()))
+        } else if e.is_connect() {
+            AppError::Network(format!("Could not connect to {}: {}", provider, e))
+        } else {
+            AppError::Network(e.to_string())
+        }
+    })?;
+    let latency = started.elapsed();
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(status_error(status.as_u16()));
+    }
+    let body: Value = response.json().await
+        .map_err(|e| AppError::Network(format!("Unexpected response from {}: {}", provider, e)))?;
+    Ok((parse_models(request.style, &body), latency))
+}
+
+/// Check a provider and report instead of failing, for the settings screen.
+pub async fn test_connection(policy: &NetworkPolicy, provider: &str, credentials: &ProviderCredentials) -> ConnectionReport {
+    let started = Instant::now();
+    let result = fetch_models(policy, provider, credentials).await;
+    let mut report = ConnectionReport {
+        provider: provider.to_string(),
+        ok: false,
+        status: None,
+        latency_ms: started.elapsed().as_millis() as u64,
+        model_count: None,
+        error: None,
+    };
+    match result {
+        Ok((models, latency)) => {
+            report.ok = true;
+            report.status = Some(200);
+            report.latency_ms = latency.as_millis() as u64;
+            report.model_count = Some(models.len());
+        }
+        Err(e) => {
+            let message = match e {
+                AppError::Network(m) | AppError::Forbidden(m) | AppError::NotFound(m) | AppError::Unknown(m) => m,
+                other => other.to_string(),
+            };
+            report.status = http_status_of(&message);
+            report.error = Some(message);
+        }
+    }
+    report
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn models_request(provider: &str, credentials: &ProviderCredentials) -> Result<ModelsRequest, AppError> {
+    let key = credentials.api_key.as_deref().map(str::trim).filter(|k| !k.is_empty());
+    let base = |default: &str| {
+        credentials.base_url.as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    };
+    let require_key = || key.map(str::to_string).ok_or_else(|| AppError::NotFound(format!("No API key configured for {}", provider)));
+    let bearer = |key: String| vec![("Authorization".to_string(), format!("Bearer {}", key))];
+
+    let (url, headers, style) = match provider {
+        "openai" => (openai_models_url(&base("https://api.openai.com/v1")), bearer(require_key()?), ListStyle::Data),
+        "deepseek" => (openai_models_url(&base("https://api.deepseek.com")), bearer(require_key()?), ListStyle::Data),
+        "ppio" => (openai_models_url(&base("https://api.ppinfra.com/v3/openai")), bearer(require_key()?), ListStyle::Data),
+        "anthropic" => (
+            format!("{}/models", base("https://api.anthropic.com/v1")),
+            vec![
+                ("x-api-key".to_string(), require_key()?),
+                ("anthropic-version".to_string(), ANTHROPIC_VERSION.to_string()),
+            ],
+            ListStyle::Data,
+        ),
+        "google" => (
+            format!("{}/v1beta/models", base("https://generativelanguage.googleapis.com")),
+            vec![("x-goog-api-key".to_string(), require_key()?)],
+            ListStyle::Google,
+        ),
+        "replicate" => (format!("{}/v1/models", base("https://api.replicate.com")), bearer(require_key()?), ListStyle::Replicate),
+        "ollama" => (format!("{}/api/tags", base("http://localhost:11434")), Vec::new(), ListStyle::Ollama),
+        "lmstudio" => (
+            openai_models_url(&base("http://localhost:1234")),
+            key.map(|k| bearer(k.to_string())).unwrap_or_default(),
+            ListStyle::Data,
+        ),
+        "comfyui" => (
+            format!("{}/object_info/CheckpointLoaderSimple", base("http://localhost:8188")),
+            Vec::new(),
+            ListStyle::ComfyUi,
+        ),
+        "fal" => return Err(AppError::Unknown("FAL.ai has no model list to check the key against".to_string())),
+        other => return Err(AppError::NotFound(format!("Unknown provider: {}", other))),
+    };
+    Ok(ModelsRequest { url, headers, style })
+}
+
+/// `/models` under a versioned base (`.../v1`, `.../v3/openai`), else `/v1/models`
+fn openai_models_url(base: &str) -> String {
+    let path = base.split_once("://").map(|(_, rest)| rest).unwrap_or(base);
+    let versioned = path.split('/').skip(1).any(|segment| {
+        segment.len() > 1 && segment.starts_with('v') && segment[1..].chars().all(|c| c.is_ascii_digit())
+    });
+    if versioned {
+        format!("{}/models", base)
+    } else {
+        format!("{}/v1/models", base)
+    }
+}
+
+fn parse_models(style: ListStyle, body: &Value) -> Vec<ModelInfo> {
+    let str_of = |v: &Value, key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string);
+    let list = |key: &str| body.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+    let mut models: Vec<ModelInfo> = match style {
+        ListStyle::Data => list("data").iter()
+            .filter_map(|m| Some(ModelInfo { id: str_of(m, "id")?, name: str_of(m, "display_name") }))
+            .collect(),
+        ListStyle::Google => list("models").iter()
+            .filter_map(|m| {
+                let id = str_of(m, "name")?;
+                Some(ModelInfo { id: id.trim_start_matches("models/").to_string(), name: str_of(m, "displayName") })
+            })
+            .collect(),
+        ListStyle::Ollama => list("models").iter()
+            .filter_map(|m| Some(ModelInfo { id: str_of(m, "name").or_else(|| str_of(m, "model"))?, name: None }))
+            .collect(),
+        ListStyle::Replicate => list("results").iter()
+            .filter_map(|m| Some(ModelInfo { id: format!("{}/{}", str_of(m, "owner")?, str_of(m, "name")?), name: None }))
+            .collect(),
+        ListStyle::ComfyUi => body.pointer("/CheckpointLoaderSimple/input/required/ckpt_name/0")
+            .and_then(|v| v.as_array())
+            .map(|names| names.iter()
+                .filter_map(|n| Some(ModelInfo { id: n.as_str()?.to_string(), name: None }))
+                .collect())
+            .unwrap_or_default(),
+    };
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models.dedup_by(|a, b| a.id == b.id);
+    models
+}
+
+fn status_error(status: u16) -> AppError {
+    let message = match status {
+        401 | 403 => "the API key was rejected".to_string(),
+        404 => "endpoint not found; check the base URL".to_string(),
+        429 => "rate limited; try again later".to_string(),
+        _ => "unexpected response".to_string(),
+    };
+    AppError::Network(format!("HTTP {}: {}", status, message))
+}
+
+/// Status code of a `status_error` message
+fn http_status_of(message: &str) -> Option<u16> {
+    message.strip_prefix("HTTP ")?.split(':').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn creds(api_key: Option<&str>, base_url: Option<&str>) -> ProviderCredentials {
+        ProviderCredentials { api_key: api_key.map(str::to_string), base_url: base_url.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_models_request() {
+        let openai = models_request("openai", &creds(Some("sk-1"), None)).unwrap();
+        assert_eq!(openai.url, "https://api.openai.com/v1/models");
+        assert_eq!(openai.headers[0].1, "Bearer sk-1");
+
+        assert_eq!(models_request("deepseek", &creds(Some("k"), None)).unwrap().url, "https://api.deepseek.com/v1/models");
+        assert_eq!(models_request("ppio", &creds(Some("k"), None)).unwrap().url, "https://api.ppinfra.com/v3/openai/models");
+        assert_eq!(models_request("lmstudio", &creds(None, Some("http://127.0.0.1:1234/"))).unwrap().url, "http://127.0.0.1:1234/v1/models");
+        assert_eq!(models_request("ollama", &creds(None, None)).unwrap().url, "http://localhost:11434/api/tags");
+
+        assert!(matches!(models_request("anthropic", &creds(Some("  "), None)), Err(AppError::NotFound(_))));
+        assert!(models_request("fal", &creds(Some("k"), None)).is_err());
+        assert!(models_request("nope", &creds(None, None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_models() {
+        let data = json!({ "data": [{ "id": "gpt-4o" }, { "id": "claude-x", "display_name": "Claude X" }, { "id": "gpt-4o" }] });
+        let models = parse_models(ListStyle::Data, &data);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name.as_deref(), Some("Claude X"));
+
+        let google = json!({ "models": [{ "name": "models/gemini-2.0-flash", "displayName": "Gemini 2.0 Flash" }] });
+        assert_eq!(parse_models(ListStyle::Google, &google)[0].id, "gemini-2.0-flash");
+
+        let replicate = json!({ "results": [{ "owner": "black-forest-labs", "name": "flux-schnell" }] });
+        assert_eq!(parse_models(ListStyle::Replicate, &replicate)[0].id, "black-forest-labs/flux-schnell");
+
+        let comfy = json!({ "CheckpointLoaderSimple": { "input": { "required": { "ckpt_name": [["sdxl.safetensors", "sd15.ckpt"]] } } } });
+        assert_eq!(parse_models(ListStyle::ComfyUi, &comfy).len(), 2);
+
+        assert!(parse_models(ListStyle::Ollama, &json!({})).is_empty());
+        assert_eq!(http_status_of("HTTP 401: the API key was rejected"), Some(401));
+    }
+}
@@ -0,0 +1,489 @@
+//! Recipe definitions stored in the project database, so the `recipeId` on
+//! nodes and assets points at durable project data.
+//!
+//! A recipe binds an agent, maps canvas connections (source node and handle,
+//! into a target handle) onto its input fields, and says where its output
+//! goes. Recipe IDs may be chosen by the caller, which keeps the IDs of
+//! recipes loaded from packages (and already used in `recipe:<id>` node
+//! types) stable when they are saved into a project.
+//!
+//! Previews resolve a recipe node's inputs and render the prompt its agent
+//! would be sent, without calling the provider: text outputs use the
+//! streaming instruction, everything else the graph-actions one.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::error::AppError;
+use crate::models::{AgentDefinition, Asset};
+use crate::services::{agent_service, asset_stream, io_sqlite, text_asset};
+use ts_rs::TS;
+
+const MAX_ID_LEN: usize = 64;
+const MAX_NAME_LEN: usize = 100;
+const MAX_INPUTS: usize = 64;
+/// Output node kinds, as in recipe manifests
+const OUTPUT_NODES: &[&str] = &["form", "selector", "gallery", "table", "text"];
+
+/// One connection feeding a recipe input field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct InputMapping {
+    /// Input field key
+    pub field: String,
+    pub source_node_id: String,
+    #[serde(default)]
+    pub source_handle: Option<String>,
+    /// Handle on the recipe node the edge connects to
+    #[serde(default)]
+    pub target_handle: Option<String>,
+}
+
+/// Where the recipe's output goes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputTarget {
+    /// "form", "selector", "gallery", "table" or "text"
+    pub node: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Existing node to write into instead of creating one
+    #[serde(default)]
+    pub target_node_id: Option<String>,
+    /// Node-specific config, copied to the output asset's `config.extra`
+    #[serde(default)]
+    #[ts(type = "Record<string, any>")]
+    pub extra: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    pub id: String,
+    pub name: String,
+    pub agent_id: Option<String>,
+    pub inputs: Vec<InputMapping>,
+    pub output: OutputTarget,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// A recipe to save; without `id` a new one is created
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub inputs: Vec<InputMapping>,
+    pub output: OutputTarget,
+}
+
+/// The prompt a recipe node would send, with rough token counts
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipePreview {
+    pub recipe_id: String,
+    pub agent_name: String,
+    /// Resolved inputs by field; fields fed by several sources get a list
+    #[ts(type = "Record<string, any>")]
+    pub inputs: Value,
+    /// Fields whose source node or asset is gone
+    pub missing_inputs: Vec<String>,
+    pub system_instruction: String,
+    pub user_message: String,
+    pub system_tokens: usize,
+    pub user_tokens: usize,
+    pub total_tokens: usize,
+}
+
+/// Recipes by name.
+pub fn list(conn: &Connection) -> Result<Vec<Recipe>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, agent_id, inputs_json, output_json, created_at, updated_at
+         FROM recipes ORDER BY name COLLATE NOCASE, id"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+
+    let rows = stmt.query_map([], row_to_recipe)
+        .map_err(|e| AppError::Io(format!("Failed to query recipes: {}", e)))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load recipes: {}", e)))
+}
+
+pub fn get(conn: &Connection, recipe_id: &str) -> Result<Option<Recipe>, AppError> {
+    conn.query_row(
+        "SELECT id, name, agent_id, inputs_json, output_json, created_at, updated_at
+         FROM recipes WHERE id = ?1",
+        params![recipe_id],
+        row_to_recipe,
+    ).optional().map_err(|e| AppError::Io(format!("Failed to load recipe: {}", e)))
+}
+
+/// Create a recipe, or replace the definition of the one with `input.id`.
+/// An unknown `id` creates the recipe under that ID.
+pub fn save(conn: &Connection, input: &RecipeInput) -> Result<Recipe, AppError> {
+    let name = input.name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::InvalidInput(format!("Recipe names must be 1 to {} characters", MAX_NAME_LEN)));
+    }
+    if let Some(id) = &input.id {
+        validate_id(id)?;
+    }
+    let agent_id = input.agent_id.as_deref().map(str::trim).filter(|a| !a.is_empty()).map(str::to_string);
+    let inputs = clean_inputs(&input.inputs)?;
+    let output = clean_output(&input.output)?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let existing = match &input.id {
+        Some(id) => get(conn, id)?,
+        None => None,
+    };
+    let recipe = Recipe {
+        id: input.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name,
+        agent_id,
+        inputs,
+        output,
+        created_at: existing.map_or(now, |r| r.created_at),
+        updated_at: now,
+    };
+    conn.execute(
+        "INSERT INTO recipes (id, name, agent_id, inputs_json, output_json, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET name = excluded.name, agent_id = excluded.agent_id,
+             inputs_json = excluded.inputs_json, output_json = excluded.output_json,
+             updated_at = excluded.updated_at",
+        params![
+            recipe.id,
+            recipe.name,
+            recipe.agent_id,
+            serde_json::to_string(&recipe.inputs)?,
+            serde_json::to_string(&recipe.output)?,
+            recipe.created_at,
+            recipe.updated_at,
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to save recipe: {}", e)))?;
+    Ok(recipe)
+}
+
+/// Delete a recipe. Nodes using it keep their `recipeId`. Returns false if
+/// it didn't exist.
+pub fn delete(conn: &Connection, recipe_id: &str) -> Result<bool, AppError> {
+    let deleted = conn.execute("DELETE FROM recipes WHERE id = ?1", params![recipe_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete recipe: {}", e)))?;
+    Ok(deleted > 0)
+}
+
+/// The recipe a node runs: its `recipe:<id>` type, its `recipeId`, or its
+/// asset's `config.extra.recipeId`.
+pub fn for_node(conn: &Connection, node_id: &str) -> Result<Recipe, AppError> {
+    let node = io_sqlite::get_node(conn, node_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", node_id)))?;
+    let mut recipe_id = node.type_.strip_prefix("recipe:").map(str::to_string).or(node.data.recipe_id.clone());
+    if let (None, Some(asset_id)) = (&recipe_id, &node.data.asset_id) {
+        recipe_id = io_sqlite::get_asset(conn, asset_id)?
+            .and_then(|a| a.config)
+            .and_then(|c| c.pointer("/extra/recipeId").and_then(Value::as_str).map(str::to_string));
+    }
+    let recipe_id = recipe_id
+        .ok_or_else(|| AppError::InvalidInput(format!("Node {} doesn't run a recipe", node_id)))?;
+    get(conn, &recipe_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Recipe not found: {}", recipe_id)))
+}
+
+/// The recipe's input values, read from its mapped source nodes, and the
+/// fields that couldn't be resolved.
+pub fn resolve_inputs(conn: &Connection, recipe: &Recipe) -> Result<(Map<String, Value>, Vec<String>), AppError> {
+    let mut inputs = Map::new();
+    let mut missing = Vec::new();
+    for mapping in &recipe.inputs {
+        let asset = match io_sqlite::get_node(conn, &mapping.source_node_id)?.and_then(|n| n.data.asset_id) {
+            Some(asset_id) => io_sqlite::get_asset(conn, &asset_id)?,
+            None => None,
+        };
+        let Some(asset) = asset else {
+            if !missing.contains(&mapping.field) {
+                missing.push(mapping.field.clone());
+            }
+            continue;
+        };
+        let value = source_value(&asset, mapping.source_handle.as_deref());
+        match inputs.get_mut(&mapping.field) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(first) => *first = Value::Array(vec![first.take(), value]),
+            None => {
+                inputs.insert(mapping.field.clone(), value);
+            }
+        }
+    }
+    Ok((inputs, missing))
+}
+
+/// Render what running `recipe` with `agent` would send, given the agent
+/// context built for the recipe node.
+pub fn preview(conn: &Connection, recipe: &Recipe, agent: &AgentDefinition, context: &str) -> Result<RecipePreview, AppError> {
+    let (inputs, missing_inputs) = resolve_inputs(conn, recipe)?;
+    let inputs = Value::Object(inputs);
+    let system_instruction = if recipe.output.node == "text" {
+        agent_service::text_instruction(&agent.system_prompt, &inputs)
+    } else {
+        agent_service::actions_instruction(&agent.system_prompt, &inputs)
+    };
+    let user_message = agent_service::user_message(context);
+    let system_tokens = asset_stream::estimate_tokens(&system_instruction);
+    let user_tokens = asset_stream::estimate_tokens(&user_message);
+    Ok(RecipePreview {
+        recipe_id: recipe.id.clone(),
+        agent_name: agent.name.clone(),
+        inputs,
+        missing_inputs,
+        system_instruction,
+        user_message,
+        system_tokens,
+        user_tokens,
+        total_tokens: system_tokens + user_tokens,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// IDs end up in node types (`recipe:<id>`), so keep them plain
+fn validate_id(id: &str) -> Result<(), AppError> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::InvalidInput(format!(
+            "Recipe IDs must be 1 to {} letters, digits, '-', '_' or '.'",
+            MAX_ID_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn clean_inputs(inputs: &[InputMapping]) -> Result<Vec<InputMapping>, AppError> {
+    if inputs.len() > MAX_INPUTS {
+        return Err(AppError::InvalidInput(format!("At most {} input mappings per recipe", MAX_INPUTS)));
+    }
+    let mut cleaned: Vec<InputMapping> = Vec::with_capacity(inputs.len());
+    for mapping in inputs {
+        let field = mapping.field.trim();
+        let source_node_id = mapping.source_node_id.trim();
+        if field.is_empty() || source_node_id.is_empty() {
+            return Err(AppError::InvalidInput("Input mappings need a field and a source node".to_string()));
+        }
+        let mapping = InputMapping {
+            field: field.to_string(),
+            source_node_id: source_node_id.to_string(),
+            source_handle: non_empty(&mapping.source_handle),
+            target_handle: non_empty(&mapping.target_handle),
+        };
+        if !cleaned.contains(&mapping) {
+            cleaned.push(mapping);
+        }
+    }
+    Ok(cleaned)
+}
+
+fn clean_output(output: &OutputTarget) -> Result<OutputTarget, AppError> {
+    if !OUTPUT_NODES.contains(&output.node.as_str()) {
+        return Err(AppError::InvalidInput(format!("Unknown output node: {}", output.node)));
+    }
+    let extra = match &output.extra {
+        Value::Null => serde_json::json!({}),
+        extra @ Value::Object(_) => extra.clone(),
+        _ => return Err(AppError::InvalidInput("Output extra config must be an object".to_string())),
+    };
+    Ok(OutputTarget {
+        node: output.node.clone(),
+        title: non_empty(&output.title),
+        target_node_id: non_empty(&output.target_node_id),
+        extra,
+    })
+}
+
+/// The field named by the source handle, the text of text assets, or the
+/// whole value
+fn source_value(asset: &Asset, handle: Option<&str>) -> Value {
+    if let Some(value) = handle.and_then(|h| asset.value.get(h)) {
+        return value.clone();
+    }
+    match text_asset::text_content(asset) {
+        Some(text) => Value::String(text.to_string()),
+        None => asset.value.clone(),
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+fn row_to_recipe(row: &rusqlite::Row) -> rusqlite::Result<Recipe> {
+    let inputs: String = row.get(3)?;
+    let output: String = row.get(4)?;
+    let output = serde_json::from_str(&output).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(Recipe {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        agent_id: row.get(2)?,
+        inputs: serde_json::from_str(&inputs).unwrap_or_default(),
+        output,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn output(node: &str) -> OutputTarget {
+        OutputTarget { node: node.to_string(), title: None, target_node_id: None, extra: Value::Null }
+    }
+
+    #[test]
+    fn test_recipe_crud() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+
+        let mapping = InputMapping {
+            field: "prompt".to_string(),
+            source_node_id: "brief".to_string(),
+            source_handle: Some("output".to_string()),
+            target_handle: Some(" ".to_string()),
+        };
+        let storyteller = save(&conn, &RecipeInput {
+            id: Some("storyteller".to_string()),
+            name: "  Story   teller ".to_string(),
+            agent_id: Some("writer".to_string()),
+            inputs: vec![mapping.clone(), mapping.clone()],
+            output: output("text"),
+        }).unwrap();
+        assert_eq!(storyteller.name, "Story teller");
+        assert_eq!(storyteller.inputs.len(), 1, "duplicate mappings collapse");
+        assert_eq!(storyteller.inputs[0].target_handle, None);
+        assert_eq!(storyteller.output.extra, serde_json::json!({}));
+        assert_eq!(get(&conn, "storyteller").unwrap().unwrap(), storyteller);
+
+        // Saving again replaces the definition but keeps its creation time
+        let mut update = RecipeInput {
+            id: Some("storyteller".to_string()),
+            name: "Storyteller".to_string(),
+            agent_id: None,
+            inputs: Vec::new(),
+            output: OutputTarget { target_node_id: Some("board".to_string()), ..output("gallery") },
+        };
+        let updated = save(&conn, &update).unwrap();
+        assert_eq!(updated.created_at, storyteller.created_at);
+        assert_eq!((updated.agent_id, updated.inputs.len()), (None, 0));
+
+        update.id = None;
+        update.name = "Captioner".to_string();
+        let captioner = save(&conn, &update).unwrap();
+        let names: Vec<String> = list(&conn).unwrap().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["Captioner", "Storyteller"]);
+
+        update.output = output("video");
+        assert!(matches!(save(&conn, &update), Err(AppError::InvalidInput(_))));
+        update.output = output("text");
+        update.id = Some("recipe:bad id".to_string());
+        assert!(matches!(save(&conn, &update), Err(AppError::InvalidInput(_))));
+
+        assert!(delete(&conn, &captioner.id).unwrap());
+        assert!(!delete(&conn, &captioner.id).unwrap());
+        assert!(get(&conn, &captioner.id).unwrap().is_none());
+    }
+
+    fn node(id: &str, node_type: &str, asset_id: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: node_type.to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData { asset_id: asset_id.map(str::to_string), ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn test_preview_recipe() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for (id, value) in [
+            ("brief-asset", serde_json::json!({ "content": "A fox at dusk", "format": "markdown" })),
+            ("style-asset", serde_json::json!({ "style": "noir", "palette": "cold" })),
+        ] {
+            conn.execute(
+                "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+                 VALUES (?1, 'record', '', ?2, '{\"name\":\"a\",\"createdAt\":0,\"updatedAt\":0,\"source\":\"user\"}', 0)",
+                params![id, value.to_string()],
+            ).unwrap();
+        }
+        io_sqlite::upsert_node(&conn, &node("brief", "text-node", Some("brief-asset"))).unwrap();
+        io_sqlite::upsert_node(&conn, &node("style", "form-node", Some("style-asset"))).unwrap();
+        io_sqlite::upsert_node(&conn, &node("run", "recipe:storyteller", None)).unwrap();
+        io_sqlite::upsert_node(&conn, &node("plain", "text-node", Some("brief-asset"))).unwrap();
+
+        let mapping = |field: &str, source: &str, handle: Option<&str>| InputMapping {
+            field: field.to_string(),
+            source_node_id: source.to_string(),
+            source_handle: handle.map(str::to_string),
+            target_handle: None,
+        };
+        save(&conn, &RecipeInput {
+            id: Some("storyteller".to_string()),
+            name: "Storyteller".to_string(),
+            agent_id: Some("writer".to_string()),
+            inputs: vec![
+                mapping("prompt", "brief", None),
+                mapping("style", "style", Some("style")),
+                mapping("style", "brief", None),
+                mapping("mood", "deleted", None),
+            ],
+            output: output("text"),
+        }).unwrap();
+
+        let recipe = for_node(&conn, "run").unwrap();
+        assert_eq!(recipe.id, "storyteller");
+        assert!(matches!(for_node(&conn, "plain"), Err(AppError::InvalidInput(_))));
+
+        let agent = AgentDefinition {
+            id: "writer".to_string(),
+            name: "Writer".to_string(),
+            description: None,
+            system_prompt: "Write about {{prompt}}.".to_string(),
+            input_schema: "{}".to_string(),
+            output_config: None,
+            is_system: false,
+        };
+        let preview = preview(&conn, &recipe, &agent, "No specific node selected.").unwrap();
+        assert_eq!(preview.inputs["prompt"], "A fox at dusk");
+        assert_eq!(preview.inputs["style"], serde_json::json!(["noir", "A fox at dusk"]));
+        assert_eq!(preview.missing_inputs, ["mood"]);
+        assert!(preview.system_instruction.contains("Write about A fox at dusk."));
+        assert!(preview.system_instruction.contains("Respond with the content only"));
+        assert_eq!(preview.user_message, agent_service::user_message("No specific node selected."));
+        assert_eq!(preview.total_tokens, preview.system_tokens + preview.user_tokens);
+    }
+}
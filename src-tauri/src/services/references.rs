@@ -0,0 +1,165 @@
+//! Reference ("alias") nodes: several nodes showing the same asset.
+//!
+//! The first node of an asset in board order is its original; every other
+//! node is a reference (`data.isReference = true`). `normalize` keeps those
+//! flags consistent after saves (e.g. promoting a reference when the original
+//! is deleted), and asset changes are broadcast as `asset:updated` to every
+//! node showing the asset.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::AppHandle;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::models::{Position, SynniaNode};
+use crate::services::io_sqlite;
+use ts_rs::TS;
+
+/// Offset of a new reference from its source node
+const REFERENCE_OFFSET: f64 = 40.0;
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Payload of the `asset:updated` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetUpdated {
+    pub asset_id: String,
+    /// Every node showing the asset, original first
+    pub node_ids: Vec<String>,
+}
+
+/// Enable `asset:updated` broadcasts (called once at startup).
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Create a reference node next to `source_node_id`, showing the same asset.
+pub fn create_reference(conn: &Connection, source_node_id: &str, position: Option<Position>) -> Result<SynniaNode, AppError> {
+    let source = io_sqlite::get_node(conn, source_node_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", source_node_id)))?;
+    if source.data.asset_id.is_none() {
+        return Err(AppError::Unknown(format!("Node {} has no asset to reference", source_node_id)));
+    }
+    
+    let mut node = source.clone();
+    node.id = uuid::Uuid::new_v4().to_string();
+    node.position = position.unwrap_or(Position {
+        x: source.position.x + REFERENCE_OFFSET,
+        y: source.position.y + REFERENCE_OFFSET,
+    });
+    node.data.is_reference = Some(true);
+    node.data.docked_to = None;
+    
+    io_sqlite::upsert_node(conn, &node)?;
+    Ok(node)
+}
+
+/// References of an asset (excluding its original node).
+pub fn list_references(conn: &Connection, asset_id: &str) -> Result<Vec<SynniaNode>, AppError> {
+    Ok(io_sqlite::get_nodes_by_asset(conn, asset_id)?
+        .into_iter()
+        .filter(|n| n.data.is_reference == Some(true))
+        .collect())
+}
+
+/// Make `isReference` consistent: exactly one original per asset. Returns the
+/// number of nodes whose flag changed.
+pub fn normalize(conn: &Connection) -> Result<usize, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, json_extract(data_json, '$.assetId'), COALESCE(json_extract(data_json, '$.isReference'), 0)
+         FROM nodes WHERE json_extract(data_json, '$.assetId') IS NOT NULL
+         ORDER BY rowid"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?))
+    }).map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    let nodes = rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))?;
+    
+    // The first non-reference node is the original; if there is none, the first node
+    let mut originals: HashMap<&str, &str> = HashMap::new();
+    for (id, asset_id, is_reference) in &nodes {
+        if !is_reference {
+            originals.entry(asset_id.as_str()).or_insert(id.as_str());
+        }
+    }
+    for (id, asset_id, _) in &nodes {
+        originals.entry(asset_id.as_str()).or_insert(id.as_str());
+    }
+    
+    let mut changed = 0;
+    for (id, asset_id, is_reference) in &nodes {
+        let should_be_reference = originals.get(asset_id.as_str()) != Some(&id.as_str());
+        if should_be_reference != *is_reference {
+            conn.execute(
+                "UPDATE nodes SET data_json = json_set(data_json, '$.isReference', json(?1)) WHERE id = ?2",
+                params![should_be_reference.to_string(), id],
+            ).map_err(|e| AppError::Io(format!("Failed to update node: {}", e)))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Emit `asset:updated` to every node showing the asset.
+pub fn notify_asset_updated(conn: &Connection, asset_id: &str) -> Result<(), AppError> {
+    let Some(app) = APP_HANDLE.get() else { return Ok(()) };
+    let node_ids = io_sqlite::get_nodes_by_asset(conn, asset_id)?
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    let _ = events::emit(app, AppEvent::AssetUpdated(AssetUpdated { asset_id: asset_id.to_string(), node_ids }));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SynniaNodeData;
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn image_node(id: &str, asset_id: &str) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: Some(asset_id.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_references_and_normalize() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        io_sqlite::upsert_node(&conn, &image_node("original", "hero")).unwrap();
+        
+        let alias = create_reference(&conn, "original", None).unwrap();
+        assert_eq!(alias.position.x, REFERENCE_OFFSET);
+        assert_eq!(list_references(&conn, "hero").unwrap().len(), 1);
+        assert_eq!(normalize(&conn).unwrap(), 0);
+        
+        // Deleting the original promotes the reference
+        conn.execute("DELETE FROM nodes WHERE id = 'original'", []).unwrap();
+        assert_eq!(normalize(&conn).unwrap(), 1);
+        assert!(list_references(&conn, "hero").unwrap().is_empty());
+        
+        // A copy pasted without the flag becomes a reference
+        io_sqlite::upsert_node(&conn, &image_node("pasted", "hero")).unwrap();
+        assert_eq!(normalize(&conn).unwrap(), 1);
+        assert_eq!(list_references(&conn, "hero").unwrap()[0].id, "pasted");
+    }
+}
@@ -0,0 +1,199 @@
+//! Per-project reminders for nodes (e.g. due storyboard frames).
+//!
+//! Reminders live in the project's `reminders` table and are fired by a
+//! background scheduler while Synnia runs: each due reminder is marked fired,
+//! shown as an OS notification and emitted as `reminder:fired`, and the main
+//! window requests the user's attention.
+//!
+//! Only the open project is watched. Reminders of other projects wait until
+//! that project is opened again and then fire straight away, overdue.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, UserAttentionType};
+use tauri_plugin_notification::NotificationExt;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::models::Reminder;
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// How often the scheduler checks for due reminders
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Payload of the `reminder:fired` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FiredReminder {
+    pub reminder: Reminder,
+    /// Notification title: the node's title, if it still exists
+    pub title: String,
+}
+
+/// Persist a new reminder for a node.
+pub fn schedule(conn: &Connection, node_id: &str, fire_at: i64, message: &str) -> Result<Reminder, AppError> {
+    let reminder = Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        node_id: node_id.to_string(),
+        fire_at,
+        message: message.to_string(),
+        fired: false,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    
+    conn.execute(
+        "INSERT INTO reminders (id, node_id, fire_at, message, fired, created_at) VALUES (?1, ?2, ?3, ?4, 0, ?5)",
+        params![reminder.id, reminder.node_id, reminder.fire_at, reminder.message, reminder.created_at],
+    ).map_err(|e| AppError::Io(format!("Failed to save reminder: {}", e)))?;
+    
+    Ok(reminder)
+}
+
+/// Reminders ordered by fire time; fired ones only when asked for.
+pub fn list(conn: &Connection, include_fired: bool) -> Result<Vec<Reminder>, AppError> {
+    query_reminders(
+        conn,
+        "SELECT id, node_id, fire_at, message, fired, created_at FROM reminders
+         WHERE ?1 OR fired = 0 ORDER BY fire_at, created_at",
+        params![include_fired],
+    )
+}
+
+/// Delete a reminder. Returns false if it didn't exist.
+pub fn cancel(conn: &Connection, reminder_id: &str) -> Result<bool, AppError> {
+    let deleted = conn.execute("DELETE FROM reminders WHERE id = ?1", params![reminder_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete reminder: {}", e)))?;
+    Ok(deleted > 0)
+}
+
+/// Mark every pending reminder due at `now` as fired and return them.
+pub fn take_due(conn: &Connection, now: i64) -> Result<Vec<Reminder>, AppError> {
+    let due = query_reminders(
+        conn,
+        "SELECT id, node_id, fire_at, message, fired, created_at FROM reminders
+         WHERE fired = 0 AND fire_at <= ?1 ORDER BY fire_at, created_at",
+        params![now],
+    )?;
+    
+    for reminder in &due {
+        conn.execute("UPDATE reminders SET fired = 1 WHERE id = ?1", params![reminder.id])
+            .map_err(|e| AppError::Io(format!("Failed to update reminder: {}", e)))?;
+    }
+    
+    Ok(due.into_iter().map(|r| Reminder { fired: true, ..r }).collect())
+}
+
+/// Fire due reminders of the project at `project_root`.
+pub fn fire_due(app: &AppHandle, project_root: &Path) -> Result<usize, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let due = take_due(&conn, chrono::Utc::now().timestamp_millis())?;
+    for reminder in &due {
+        let title = node_title(&conn, &reminder.node_id)?
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| "Synnia reminder".to_string());
+        // Failing to notify (e.g. no notification service) still leaves the event
+        let _ = app.notification().builder().title(&title).body(&reminder.message).show();
+        let _ = events::emit(app, AppEvent::ReminderFired(FiredReminder { reminder: reminder.clone(), title }));
+    }
+    
+    if !due.is_empty() {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.request_user_attention(Some(UserAttentionType::Informational));
+        }
+    }
+    
+    Ok(due.len())
+}
+
+/// Start the background scheduler. It follows whichever project is open;
+/// see the module docs for reminders of projects that aren't.
+pub fn start_scheduler(app: AppHandle, current_project_path: Arc<Mutex<Option<String>>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            
+            let project_path = match current_project_path.lock() {
+                Ok(guard) => guard.clone(),
+                Err(_) => continue,
+            };
+            let Some(project_path) = project_path else { continue };
+            
+            let app = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                fire_due(&app, Path::new(&project_path))
+            }).await;
+            if let Ok(Err(e)) = result {
+                println!("Failed to fire reminders: {}", e);
+            }
+        }
+    });
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn query_reminders<P: rusqlite::Params>(conn: &Connection, sql: &str, params: P) -> Result<Vec<Reminder>, AppError> {
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params, |row| {
+        Ok(Reminder {
+            id: row.get(0)?,
+            node_id: row.get(1)?,
+            fire_at: row.get(2)?,
+            message: row.get(3)?,
+            fired: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query reminders: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load reminders: {}", e)))
+}
+
+fn node_title(conn: &Connection, node_id: &str) -> Result<Option<String>, AppError> {
+    conn.query_row(
+        "SELECT json_extract(data_json, '$.title') FROM nodes WHERE id = ?1",
+        params![node_id],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| AppError::Io(format!("Failed to query node: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_take_due_fires_once() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        
+        let early = schedule(&conn, "frame-1", 1_000, "Storyboard due").unwrap();
+        let late = schedule(&conn, "frame-2", 5_000, "Shoot").unwrap();
+        
+        let due = take_due(&conn, 2_000).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, early.id);
+        assert!(due[0].fired);
+        assert!(take_due(&conn, 2_000).unwrap().is_empty(), "fired reminders are not fired again");
+        
+        assert_eq!(list(&conn, false).unwrap().len(), 1);
+        assert_eq!(list(&conn, true).unwrap().len(), 2);
+        
+        assert!(cancel(&conn, &late.id).unwrap());
+        assert!(!cancel(&conn, &late.id).unwrap());
+        assert!(take_due(&conn, 10_000).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+//! Versioned REST API on the local server, for scripts and tools that work
+//! with a running Synnia instance without Tauri IPC.
+//!
+//! Routes live under `/api/v1` and take the same bearer token as `/clip` and
+//! `/events` (see `get_clip_token`). They mirror the read side of the
+//! project, node, asset and search commands and answer in the same JSON
+//! shapes. Changes still go through the app (or `/clip`): the open canvas
+//! autosaves over database edits it didn't make itself.
+//!
+//! The exception is the job queue, which has no window to run its tasks in
+//! `--server` mode: jobs are submitted under `/jobs`, workers take their
+//! `job:task` events from `/events` (or the task list of `GET /jobs`) and
+//! report each outcome to `POST /jobs/{id}/tasks/{index}`.
+//!
+//! Errors come back as `{ "code", "message" }`, like `AppError` over IPC.
+//! Breaking changes to the routes or their shapes get a new version prefix.
+
+use std::path::PathBuf;
+use actix_web::{get, post, web, Error, HttpRequest, HttpResponse};
+use actix_web::http::StatusCode;
+use rusqlite::Connection;
+use serde::Deserialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use crate::commands::jobs;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::services::generation_grid::GridAxis;
+use crate::services::{canvases, database, io_sqlite, tags};
+use crate::AppState;
+use super::file_server::{self, ServerState};
+
+pub const API_VERSION: u32 = 1;
+
+/// Most items per page of a listing
+const MAX_LIMIT: usize = 500;
+const DEFAULT_LIMIT: usize = 100;
+
+/// Mount the API on the local server.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1")
+            .service(status)
+            .service(projects)
+            .service(project)
+            .service(nodes)
+            .service(node)
+            .service(assets)
+            .service(asset)
+            .service(search)
+            .service(list_jobs)
+            .service(submit_grid)
+            .service(job)
+            .service(report_task),
+    );
+}
+
+/// Query of listings: `?limit=&offset=`
+#[derive(Debug, Deserialize)]
+struct Page {
+    limit: Option<usize>,
+    #[serde(default)]
+    offset: usize,
+}
+
+impl Page {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NodesQuery {
+    /// Defaults to the most recently opened canvas
+    canvas: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    /// Comma-separated tags, as in `search_assets_by_tags`
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Body of `POST /jobs/grid`, as the arguments of `run_generation_grid`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GridRequest {
+    recipe_node_id: String,
+    variations: Vec<GridAxis>,
+    concurrency: Option<usize>,
+    output_node_type: Option<String>,
+}
+
+/// Body of `POST /jobs/{id}/tasks/{index}`: `error` marks the task failed
+#[derive(Debug, Default, Deserialize)]
+struct TaskReport {
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+impl TaskReport {
+    fn into_outcome(self) -> Result<serde_json::Value, String> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.result.unwrap_or(serde_json::Value::Null)),
+        }
+    }
+}
+
+/// API version and the open project, if any
+#[get("")]
+async fn status(req: HttpRequest, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    authorize(&req, &data)?;
+    let open_project = match file_server::resolve_project_root(&data) {
+        Some(root) => {
+            let meta = with_db(root.clone(), io_sqlite::load_project_meta).await?;
+            json!({ "name": meta.name, "path": root.to_string_lossy() })
+        }
+        None => serde_json::Value::Null,
+    };
+    Ok(HttpResponse::Ok().json(json!({
+        "apiVersion": API_VERSION,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "project": open_project,
+    })))
+}
+
+/// Recently opened projects, as in `get_recent_projects`
+#[get("/projects")]
+async fn projects(req: HttpRequest, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    authorize(&req, &data)?;
+    Ok(HttpResponse::Ok().json(GlobalConfig::load(&data.app).recent_projects))
+}
+
+/// Metadata and canvases of the open project
+#[get("/project")]
+async fn project(req: HttpRequest, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let (meta, canvases) = with_db(root, |conn| {
+        Ok((io_sqlite::load_project_meta(conn)?, canvases::list(conn)?))
+    }).await?;
+    Ok(HttpResponse::Ok().json(json!({ "meta": meta, "canvases": canvases })))
+}
+
+#[get("/nodes")]
+async fn nodes(req: HttpRequest, query: web::Query<NodesQuery>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let canvas = query.into_inner().canvas;
+    let nodes = with_db(root, move |conn| {
+        let canvas = match canvas {
+            Some(canvas) => canvas,
+            None => canvases::active_canvas(conn)?,
+        };
+        io_sqlite::load_nodes(conn, &canvas)
+    }).await?;
+    Ok(HttpResponse::Ok().json(nodes))
+}
+
+#[get("/nodes/{id}")]
+async fn node(req: HttpRequest, id: web::Path<String>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let id = id.into_inner();
+    let node = with_db(root, move |conn| {
+        io_sqlite::get_node(conn, &id)?.ok_or_else(|| AppError::NotFound(format!("Node not found: {}", id)))
+    }).await?;
+    Ok(HttpResponse::Ok().json(node))
+}
+
+/// Assets, most recently updated first: `{ items, total }`
+#[get("/assets")]
+async fn assets(req: HttpRequest, page: web::Query<Page>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let (limit, offset) = (page.limit(), page.offset);
+    let (items, total) = with_db(root, move |conn| {
+        let mut assets: Vec<_> = io_sqlite::load_assets(conn)?.into_values().collect();
+        assets.sort_by(|a, b| b.sys.updated_at.cmp(&a.sys.updated_at).then_with(|| a.id.cmp(&b.id)));
+        let total = assets.len();
+        Ok((assets.into_iter().skip(offset).take(limit).collect::<Vec<_>>(), total))
+    }).await?;
+    Ok(HttpResponse::Ok().json(json!({ "items": items, "total": total })))
+}
+
+#[get("/assets/{id}")]
+async fn asset(req: HttpRequest, id: web::Path<String>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let id = id.into_inner();
+    let asset = with_db(root, move |conn| {
+        io_sqlite::get_asset(conn, &id)?.ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", id)))
+    }).await?;
+    Ok(HttpResponse::Ok().json(asset))
+}
+
+/// Assets matching every tag of `q`, most recently updated first
+#[get("/search")]
+async fn search(req: HttpRequest, query: web::Query<SearchQuery>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    let root = authorize_project(&req, &data)?;
+    let SearchQuery { q, limit } = query.into_inner();
+    if q.trim().is_empty() {
+        return Err(AppError::InvalidInput("Missing query `q`".to_string()).into());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let matches = with_db(root, move |conn| {
+        tags::search(conn, &q, &[], None, limit)?.iter()
+            .filter_map(|id| io_sqlite::get_asset(conn, id).transpose())
+            .collect::<Result<Vec<_>, _>>()
+    }).await?;
+    Ok(HttpResponse::Ok().json(matches))
+}
+
+/// Jobs with per-task status, as in `get_jobs`
+#[get("/jobs")]
+async fn list_jobs(req: HttpRequest, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    authorize(&req, &data)?;
+    let jobs = with_app(&data, |app| app.state::<AppState>().jobs.list()).await?;
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+#[get("/jobs/{id}")]
+async fn job(req: HttpRequest, id: web::Path<String>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    authorize(&req, &data)?;
+    let id = id.into_inner();
+    let job = with_app(&data, move |app| {
+        app.state::<AppState>().jobs.get(&id)?.ok_or_else(|| AppError::NotFound(format!("Job not found: {}", id)))
+    }).await?;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// Submit a generation grid, as `run_generation_grid`: answers the `JobInfo`
+#[post("/jobs/grid")]
+async fn submit_grid(req: HttpRequest, body: web::Json<GridRequest>, data: web::Data<ServerState>) -> Result<HttpResponse, Error> {
+    authorize_project(&req, &data)?;
+    let GridRequest { recipe_node_id, variations, concurrency, output_node_type } = body.into_inner();
+    let info = with_app(&data, move |app| {
+        jobs::submit_generation_grid(&app, &app.state::<AppState>(), recipe_node_id, variations, concurrency, output_node_type)
+    }).await?;
+    Ok(HttpResponse::Ok().json(info))
+}
+
+/// Report a task's outcome, as `complete_job_task`
+#[post("/jobs/{id}/tasks/{index}")]
+async fn report_task(
+    req: HttpRequest,
+    path: web::Path<(String, usize)>,
+    body: web::Json<TaskReport>,
+    data: web::Data<ServerState>,
+) -> Result<HttpResponse, Error> {
+    authorize(&req, &data)?;
+    let (job_id, index) = path.into_inner();
+    let outcome = body.into_inner().into_outcome();
+    with_app(&data, move |app| jobs::finish_task(&app, &app.state::<AppState>(), &job_id, index, outcome)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+impl actix_web::ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            AppError::ProjectNotLoaded => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn authorize(req: &HttpRequest, data: &ServerState) -> Result<(), Error> {
+    if file_server::is_authorized(data, file_server::bearer_token(req).unwrap_or("")) {
+        Ok(())
+    } else {
+        Err(actix_web::error::ErrorUnauthorized("Invalid clip token"))
+    }
+}
+
+/// Check the token and return the open project's root
+fn authorize_project(req: &HttpRequest, data: &ServerState) -> Result<PathBuf, Error> {
+    authorize(req, data)?;
+    Ok(file_server::resolve_project_root(data).ok_or(AppError::ProjectNotLoaded)?)
+}
+
+/// Run `work` on the project database off the server's event loop
+async fn with_db<T, F>(project_root: PathBuf, work: F) -> Result<T, AppError>
+where
+    F: FnOnce(&Connection) -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    web::block(move || {
+        let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        work(&conn)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
+
+/// Run `work` against the app's state off the server's event loop; job
+/// finalizers write to the project database and assets
+async fn with_app<T, F>(data: &ServerState, work: F) -> Result<T, AppError>
+where
+    F: FnOnce(AppHandle) -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    let app = data.app.clone();
+    web::block(move || work(app))
+        .await
+        .map_err(|e| AppError::Unknown(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_status() {
+        use actix_web::ResponseError;
+        assert_eq!(AppError::NotFound("x".to_string()).status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(AppError::ProjectNotLoaded.status_code(), StatusCode::CONFLICT);
+        assert_eq!(AppError::Io("x".to_string()).status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(Page { limit: Some(10_000), offset: 0 }.limit(), MAX_LIMIT);
+        assert_eq!(Page { limit: None, offset: 0 }.limit(), DEFAULT_LIMIT);
+    }
+
+    #[test]
+    fn test_task_report_drives_the_queue() {
+        use crate::services::job_queue::{Job, JobQueue, TaskStatus};
+        
+        let report: TaskReport = serde_json::from_str(r#"{ "result": { "assetId": "a1" } }"#).unwrap();
+        assert_eq!(report.into_outcome(), Ok(json!({ "assetId": "a1" })));
+        let report: TaskReport = serde_json::from_str(r#"{ "error": "rate limited" }"#).unwrap();
+        assert_eq!(report.into_outcome(), Err("rate limited".to_string()));
+        assert_eq!(TaskReport::default().into_outcome(), Ok(serde_json::Value::Null));
+        
+        // A worker's reports free the slot for the next task, then finish the job
+        let queue = JobQueue::new();
+        let grid = Job::new("generation-grid", json!({}), vec![json!({ "n": 0 }), json!({ "n": 1 })], 1);
+        let job_id = grid.id.clone();
+        assert_eq!(queue.submit(grid).unwrap().len(), 1);
+        
+        let report: TaskReport = serde_json::from_str(r#"{ "result": "first" }"#).unwrap();
+        let update = queue.complete_task(&job_id, 0, report.into_outcome()).unwrap();
+        assert_eq!(update.dispatches.len(), 1);
+        assert_eq!(update.dispatches[0].task_index, 1);
+        assert!(update.finished.is_none());
+        
+        let report: TaskReport = serde_json::from_str(r#"{ "error": "timeout" }"#).unwrap();
+        let finished = queue.complete_task(&job_id, 1, report.into_outcome()).unwrap().finished.unwrap();
+        assert_eq!(finished.tasks[0].status, TaskStatus::Success);
+        assert_eq!(finished.tasks[1].status, TaskStatus::Error);
+        assert_eq!(finished.tasks[1].error.as_deref(), Some("timeout"));
+    }
+}
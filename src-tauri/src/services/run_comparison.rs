@@ -0,0 +1,292 @@
+//! Compare what two pipeline runs produced, node by node, to see whether a
+//! prompt tweak improved the results across the board.
+//!
+//! Outputs come from the per-run snapshots saved as nodes finish, not from
+//! the assets, which later runs overwrite. Text outputs get a line diff,
+//! record outputs the fields that changed; anything else (images) is only
+//! marked changed or not by its value hash and shown side by side.
+
+use std::collections::BTreeSet;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::AppError;
+use crate::services::pipelines;
+use ts_rs::TS;
+
+/// Larger texts are shown as fully replaced instead of diffed line by line
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOutput {
+    pub asset_id: String,
+    pub value_hash: String,
+    #[ts(type = "any")]
+    pub value: Value,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    #[ts(type = "any")]
+    pub before: Value,
+    #[ts(type = "any")]
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputPair {
+    pub node_id: String,
+    /// Missing if the node didn't finish in that run
+    pub a: Option<RunOutput>,
+    pub b: Option<RunOutput>,
+    pub changed: bool,
+    /// For text outputs
+    pub text_diff: Vec<DiffLine>,
+    /// For record outputs, top-level fields
+    pub field_changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RunComparison {
+    pub run_a: String,
+    pub run_b: String,
+    /// In run B's execution order, then nodes only run A has
+    pub pairs: Vec<OutputPair>,
+    /// Pairs that differ
+    pub changed: usize,
+}
+
+/// Pair the outputs of `run_a` and `run_b`, for one node or all of them.
+pub fn compare_runs(conn: &Connection, node_id: Option<&str>, run_a: &str, run_b: &str) -> Result<RunComparison, AppError> {
+    let mut node_ids: Vec<String> = Vec::new();
+    for run_id in [run_b, run_a] {
+        let run = pipelines::get_run(conn, run_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Pipeline not found: {}", run_id)))?;
+        for step in run.steps {
+            if !node_ids.contains(&step.node_id) {
+                node_ids.push(step.node_id);
+            }
+        }
+    }
+    if let Some(node_id) = node_id {
+        if !node_ids.iter().any(|id| id == node_id) {
+            return Err(AppError::NotFound(format!("Node {} isn't part of either run", node_id)));
+        }
+        node_ids = vec![node_id.to_string()];
+    }
+
+    let mut pairs = Vec::with_capacity(node_ids.len());
+    for node_id in node_ids {
+        let a = get_output(conn, run_a, &node_id)?;
+        let b = get_output(conn, run_b, &node_id)?;
+        let changed = match (&a, &b) {
+            (Some(a), Some(b)) => a.value_hash != b.value_hash,
+            (None, None) => false,
+            _ => true,
+        };
+        let (mut text_diff, mut field_changes) = (Vec::new(), Vec::new());
+        if changed {
+            let before = a.as_ref().map(|o| &o.value).unwrap_or(&Value::Null);
+            let after = b.as_ref().map(|o| &o.value).unwrap_or(&Value::Null);
+            match (text_of(before), text_of(after)) {
+                (Some(before), Some(after)) => text_diff = line_diff(before, after),
+                _ => field_changes = changed_fields(before, after),
+            }
+        }
+        pairs.push(OutputPair { node_id, a, b, changed, text_diff, field_changes });
+    }
+    Ok(RunComparison {
+        run_a: run_a.to_string(),
+        run_b: run_b.to_string(),
+        changed: pairs.iter().filter(|p| p.changed).count(),
+        pairs,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn get_output(conn: &Connection, run_id: &str, node_id: &str) -> Result<Option<RunOutput>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT asset_id, value_hash, value_json, created_at FROM pipeline_outputs WHERE run_id = ?1 AND node_id = ?2"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let mut rows = stmt.query_map(params![run_id, node_id], |row| {
+        let value: String = row.get(2)?;
+        Ok(RunOutput {
+            asset_id: row.get(0)?,
+            value_hash: row.get(1)?,
+            value: serde_json::from_str(&value).unwrap_or(Value::Null),
+            created_at: row.get(3)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query pipeline outputs: {}", e)))?;
+    rows.next().transpose()
+        .map_err(|e| AppError::Io(format!("Failed to load pipeline output: {}", e)))
+}
+
+/// Plain strings and text records (`content`)
+fn text_of(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(text) => Some(text),
+        value => value.get("content").and_then(Value::as_str),
+    }
+}
+
+/// Top-level fields that differ; non-objects count as one unnamed field
+fn changed_fields(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let (Value::Object(a), Value::Object(b)) = (before, after) else {
+        return vec![FieldChange { field: String::new(), before: before.clone(), after: after.clone() }];
+    };
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .filter(|key| a.get(*key) != b.get(*key))
+        .map(|key| FieldChange {
+            field: key.clone(),
+            before: a.get(key).cloned().unwrap_or(Value::Null),
+            after: b.get(key).cloned().unwrap_or(Value::Null),
+        })
+        .collect()
+}
+
+/// Longest-common-subsequence diff of the lines
+fn line_diff(before: &str, after: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    let line = |kind, text: &str| DiffLine { kind, text: text.to_string() };
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return a.iter().map(|l| line(DiffKind::Removed, l))
+            .chain(b.iter().map(|l| line(DiffKind::Added, l)))
+            .collect();
+    }
+
+    // lcs[i][j]: common lines of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            diff.push(line(DiffKind::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(line(DiffKind::Removed, a[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffKind::Added, b[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::job_queue::TaskStatus;
+    use crate::services::pipelines::PipelineOptions;
+    use crate::services::{database, io_sqlite};
+    use tempfile::tempdir;
+
+    fn set_asset(conn: &Connection, id: &str, value: Value) {
+        conn.execute(
+            "INSERT OR REPLACE INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+             VALUES (?1, 'record', ?2, ?3, '{\"name\":\"out\",\"createdAt\":0,\"updatedAt\":0,\"source\":\"ai\"}', 0)",
+            params![id, crate::services::hash::compute_content_hash(&value.to_string()), value.to_string()],
+        ).unwrap();
+    }
+
+    fn run(conn: &Connection, outputs: &[(&str, Value)]) -> String {
+        let ids: Vec<String> = outputs.iter().map(|(id, _)| id.to_string()).collect();
+        let run = pipelines::start(conn, &ids, &PipelineOptions::default()).unwrap();
+        for (node_id, value) in outputs {
+            let asset_id = format!("{}-out", node_id);
+            set_asset(conn, &asset_id, value.clone());
+            pipelines::record_step(conn, &run.id, node_id, TaskStatus::Success, Some(&asset_id), None, None).unwrap();
+        }
+        run.id
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        for id in ["copy", "specs", "hero"] {
+            io_sqlite::upsert_node(&conn, &SynniaNode {
+                id: id.to_string(),
+                type_: "recipe-node".to_string(),
+                position: Position { x: 0.0, y: 0.0 },
+                width: None,
+                height: None,
+                parent_id: None,
+                extent: None,
+                style: None,
+                data: SynniaNodeData::default(),
+            }).unwrap();
+        }
+
+        let before = run(&conn, &[
+            ("copy", serde_json::json!({ "content": "Meet Ada.\nShe builds engines.\nThe end.", "format": "markdown" })),
+            ("specs", serde_json::json!({ "tone": "dry", "length": 3 })),
+            ("hero", serde_json::json!({ "src": "assets/hero.png" })),
+        ]);
+        // The second run overwrites the same assets
+        let after = run(&conn, &[
+            ("copy", serde_json::json!({ "content": "Meet Ada.\nShe designs engines.\nThe end.", "format": "markdown" })),
+            ("specs", serde_json::json!({ "tone": "warm", "length": 3 })),
+        ]);
+
+        let comparison = compare_runs(&conn, None, &before, &after).unwrap();
+        let nodes: Vec<&str> = comparison.pairs.iter().map(|p| p.node_id.as_str()).collect();
+        assert_eq!(nodes, ["copy", "specs", "hero"]);
+        assert_eq!(comparison.changed, 3);
+
+        let copy = &comparison.pairs[0];
+        assert_eq!(copy.a.as_ref().unwrap().value["content"], "Meet Ada.\nShe builds engines.\nThe end.");
+        let kinds: Vec<DiffKind> = copy.text_diff.iter().map(|l| l.kind).collect();
+        assert_eq!(kinds, [DiffKind::Same, DiffKind::Removed, DiffKind::Added, DiffKind::Same]);
+        assert_eq!(comparison.pairs[1].field_changes, [FieldChange {
+            field: "tone".to_string(),
+            before: serde_json::json!("dry"),
+            after: serde_json::json!("warm"),
+        }]);
+        assert!(comparison.pairs[2].b.is_none());
+
+        let same = compare_runs(&conn, Some("specs"), &after, &after).unwrap();
+        assert_eq!((same.pairs.len(), same.changed), (1, 0));
+        assert!(matches!(compare_runs(&conn, Some("nope"), &before, &after), Err(AppError::NotFound(_))));
+    }
+}
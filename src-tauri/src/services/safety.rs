@@ -0,0 +1,395 @@
+//! Content safety review of imported and AI-generated images.
+//!
+//! A local classifier runner (`SYNNIA_SAFETY_RUNNER`, model in
+//! `SYNNIA_SAFETY_MODEL`) prints `{ "score": 0.93, "label": "explicit" }` (or
+//! just the score) for an image. Per the project's policy, images scoring at
+//! or above the threshold are flagged for review or quarantined (hidden from
+//! the media library) until someone approves them. Reviews are stored per
+//! image file (`image_reviews.src`), so imports are checked before the
+//! frontend creates their assets.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::AppError;
+use crate::services::{database, imaging, io_sqlite};
+use crate::services::batch_import::IMAGE_EXTENSIONS;
+use ts_rs::TS;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "safety-scan";
+
+/// Settings key of the safety settings
+pub const SETTINGS_KEY: &str = "safety";
+
+/// SQL condition matching an asset `a` to the image review `r` of its file
+/// (the asset value is a path or an object with `src`).
+pub(crate) const ASSET_SRC_MATCH: &str =
+    "r.src = COALESCE(json_extract(a.value_json, '$.src'), json_extract(a.value_json, '$'))";
+
+/// What happens to an image the classifier flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SafetyPolicy {
+    /// Images are not checked automatically
+    #[default]
+    Off,
+    /// Mark for review; the asset stays visible
+    Flag,
+    /// Hide from the media library until approved
+    Quarantine,
+}
+
+/// Review state of a checked image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Clear,
+    Flagged,
+    Quarantined,
+    Approved,
+    Rejected,
+}
+
+impl ReviewStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReviewStatus::Clear => "clear",
+            ReviewStatus::Flagged => "flagged",
+            ReviewStatus::Quarantined => "quarantined",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::Rejected => "rejected",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "flagged" => ReviewStatus::Flagged,
+            "quarantined" => ReviewStatus::Quarantined,
+            "approved" => ReviewStatus::Approved,
+            "rejected" => ReviewStatus::Rejected,
+            _ => ReviewStatus::Clear,
+        }
+    }
+}
+
+/// A reviewer's decision on flagged assets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewDecision {
+    Approve,
+    Reject,
+}
+
+/// Per-project safety settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafetySettings {
+    pub policy: SafetyPolicy,
+    /// Classifier scores (0..1) at or above this are flagged
+    pub threshold: f64,
+}
+
+impl Default for SafetySettings {
+    fn default() -> Self {
+        Self { policy: SafetyPolicy::Off, threshold: 0.7 }
+    }
+}
+
+impl SafetySettings {
+    /// Clamp to usable values
+    pub fn normalized(self) -> Self {
+        let threshold = if self.threshold.is_finite() { self.threshold.clamp(0.0, 1.0) } else { 0.7 };
+        Self { policy: self.policy, threshold }
+    }
+
+    /// Status of an image with this classifier score. Manual scans flag
+    /// even when automatic checks are off.
+    pub fn status_for(&self, score: f64) -> ReviewStatus {
+        match self.policy {
+            _ if score < self.threshold => ReviewStatus::Clear,
+            SafetyPolicy::Quarantine => ReviewStatus::Quarantined,
+            SafetyPolicy::Flag | SafetyPolicy::Off => ReviewStatus::Flagged,
+        }
+    }
+}
+
+/// An asset waiting for (or given) a review
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedAsset {
+    pub asset_id: String,
+    pub name: String,
+    pub src: String,
+    pub score: f64,
+    pub label: Option<String>,
+    pub status: ReviewStatus,
+}
+
+/// Parameters of one scan task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetyTask {
+    /// Image file relative to the project root
+    pub src: String,
+}
+
+/// Runner and model of the local safety classifier
+#[derive(Debug, Clone)]
+pub struct SafetyClassifier {
+    runner: PathBuf,
+    model: PathBuf,
+}
+
+impl SafetyClassifier {
+    /// The local classifier, if a runner and model are configured.
+    pub fn find() -> Option<Self> {
+        let runner = PathBuf::from(std::env::var_os("SYNNIA_SAFETY_RUNNER")?);
+        let model = PathBuf::from(std::env::var_os("SYNNIA_SAFETY_MODEL")?);
+        (runner.is_file() && model.is_file()).then_some(SafetyClassifier { runner, model })
+    }
+
+    /// Score and label of the image at `path`. The error is the message
+    /// shown for it.
+    pub fn classify(&self, path: &Path) -> Result<(f64, Option<String>), String> {
+        let output = Command::new(&self.runner)
+            .arg("-i").arg(path)
+            .arg("-m").arg(&self.model)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run safety classifier: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Safety classifier failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let result: Value = serde_json::from_str(stdout.trim())
+            .map_err(|e| format!("Unreadable safety classifier output: {}", e))?;
+        parse_result(&result).ok_or_else(|| "Safety classifier returned no score".to_string())
+    }
+
+    /// Classify `src` (relative to the project root) and store its review
+    /// under the project's settings.
+    pub fn check(&self, project_root: &Path, src: &str) -> Result<ReviewStatus, String> {
+        let (score, label) = self.classify(&project_root.join(src))?;
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        let settings = get_settings(&conn).map_err(|e| e.to_string())?;
+        let status = settings.status_for(score);
+        save_review(&conn, src, score, label.as_deref(), status).map_err(|e| e.to_string())?;
+        Ok(status)
+    }
+}
+
+pub fn get_settings(conn: &Connection) -> Result<SafetySettings, AppError> {
+    Ok(io_sqlite::get_setting(conn, SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Store safety settings (clamped to usable values). Returns what was stored.
+pub fn save_settings(conn: &Connection, settings: &SafetySettings) -> Result<SafetySettings, AppError> {
+    let settings = settings.normalized();
+    io_sqlite::set_setting(conn, SETTINGS_KEY, &serde_json::to_value(settings)?)?;
+    Ok(settings)
+}
+
+/// The classifier to run automatically, if the project has a policy and
+/// one is installed.
+pub fn auto_classifier(project_root: &Path) -> Option<SafetyClassifier> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root)).ok()?;
+    let settings = get_settings(&conn).ok()?;
+    (settings.policy != SafetyPolicy::Off).then(SafetyClassifier::find).flatten()
+}
+
+/// Record the classifier's verdict on an image file.
+pub fn save_review(
+    conn: &Connection,
+    src: &str,
+    score: f64,
+    label: Option<&str>,
+    status: ReviewStatus,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO image_reviews (src, score, label, status, created_at, reviewed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, NULL)",
+        params![src, score, label, status.as_str(), chrono::Utc::now().timestamp_millis()],
+    ).map_err(|e| AppError::Io(format!("Failed to save review: {}", e)))?;
+    Ok(())
+}
+
+/// Image files of the project's assets that were not checked yet (all of
+/// them with `force`). `asset_ids` limits the search.
+pub fn pending_tasks(
+    conn: &Connection,
+    project_root: &Path,
+    asset_ids: Option<&[String]>,
+    force: bool,
+) -> Result<Vec<SafetyTask>, AppError> {
+    let mut sources: Vec<String> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .filter(|asset| asset_ids.map_or(true, |ids| ids.contains(&asset.id)))
+        .filter_map(|asset| {
+            let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+            let path = imaging::resolve_image_source(project_root, src).filter(|p| p.is_file())?;
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            IMAGE_EXTENSIONS.contains(&ext.as_str()).then_some(())?;
+            Some(path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    if !force {
+        let mut stmt = conn.prepare("SELECT 1 FROM image_reviews WHERE src = ?1")
+            .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+        sources.retain(|src| !matches!(stmt.query_row(params![src], |_| Ok(())).optional(), Ok(Some(()))));
+    }
+    sources.sort();
+    sources.dedup();
+    Ok(sources.into_iter().map(|src| SafetyTask { src }).collect())
+}
+
+/// Assets whose image is flagged or quarantined (with `include_reviewed`,
+/// also approved and rejected ones), highest score first.
+pub fn flagged_assets(conn: &Connection, include_reviewed: bool) -> Result<Vec<FlaggedAsset>, AppError> {
+    let statuses = if include_reviewed {
+        "'flagged', 'quarantined', 'approved', 'rejected'"
+    } else {
+        "'flagged', 'quarantined'"
+    };
+    let sql = format!(
+        "SELECT a.id, COALESCE(json_extract(a.sys_json, '$.name'), ''), r.src, r.score, r.label, r.status
+         FROM image_reviews r JOIN assets a ON {}
+         WHERE r.status IN ({}) ORDER BY r.score DESC, a.id",
+        ASSET_SRC_MATCH, statuses
+    );
+    let mut stmt = conn.prepare(&sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(FlaggedAsset {
+            asset_id: row.get(0)?,
+            name: row.get(1)?,
+            src: row.get(2)?,
+            score: row.get(3)?,
+            label: row.get(4)?,
+            status: ReviewStatus::parse(&row.get::<_, String>(5)?),
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query reviews: {}", e)))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load reviews: {}", e)))
+}
+
+/// Approve or reject the flagged images of these assets. Rejected images
+/// stay hidden from the media library. Returns the number of images updated.
+pub fn review(conn: &Connection, asset_ids: &[String], decision: ReviewDecision) -> Result<usize, AppError> {
+    let status = match decision {
+        ReviewDecision::Approve => ReviewStatus::Approved,
+        ReviewDecision::Reject => ReviewStatus::Rejected,
+    };
+    let sql = format!(
+        "UPDATE image_reviews SET status = ?1, reviewed_at = ?2
+         WHERE status != 'clear' AND EXISTS (SELECT 1 FROM assets a WHERE a.id = ?3 AND {})",
+        ASSET_SRC_MATCH.replace("r.src", "image_reviews.src")
+    );
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut updated = 0;
+    for asset_id in asset_ids {
+        updated += conn.execute(&sql, params![status.as_str(), now, asset_id])
+            .map_err(|e| AppError::Io(format!("Failed to save review: {}", e)))?;
+    }
+    Ok(updated)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Score and label from `{ score, label }`, `{ nsfw }` or a bare number.
+fn parse_result(result: &Value) -> Option<(f64, Option<String>)> {
+    let score = match result {
+        Value::Number(n) => n.as_f64(),
+        Value::Object(obj) => obj.get("score")
+            .or_else(|| obj.get("nsfw"))
+            .or_else(|| obj.get("unsafe"))
+            .and_then(|v| v.as_f64()),
+        _ => None,
+    }?;
+    let label = result.get("label").and_then(|v| v.as_str()).map(str::to_string);
+    score.is_finite().then_some((score.clamp(0.0, 1.0), label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_result() {
+        assert_eq!(parse_result(&json!(0.4)), Some((0.4, None)));
+        assert_eq!(parse_result(&json!({ "score": 0.9, "label": "explicit" })), Some((0.9, Some("explicit".to_string()))));
+        assert_eq!(parse_result(&json!({ "nsfw": 1.5 })), Some((1.0, None)));
+        assert!(parse_result(&json!({ "safe": 0.1 })).is_none());
+    }
+
+    #[test]
+    fn test_review_flow() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Shoot").unwrap();
+        for (id, value) in [("a", json!({ "src": "assets/a.png" })), ("b", json!("assets/b.png")), ("c", json!({ "src": "assets/c.png" }))] {
+            image::RgbImage::new(4, 4).save(root.join(format!("assets/{}.png", id))).unwrap();
+            project.assets.insert(id.to_string(), Asset {
+                id: id.to_string(),
+                value_type: ValueType::Record,
+                value,
+                value_meta: None,
+                config: None,
+                sys: AssetSysMetadata { name: id.to_uppercase(), created_at: 0, updated_at: 0, source: "import".to_string() },
+            });
+        }
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        
+        let settings = save_settings(&conn, &SafetySettings { policy: SafetyPolicy::Quarantine, threshold: 2.0 }).unwrap();
+        assert_eq!(settings.threshold, 1.0);
+        let settings = SafetySettings { policy: SafetyPolicy::Quarantine, threshold: 0.7 };
+        assert_eq!(settings.status_for(0.2), ReviewStatus::Clear);
+        
+        save_review(&conn, "assets/a.png", 0.95, Some("explicit"), settings.status_for(0.95)).unwrap();
+        save_review(&conn, "assets/b.png", 0.8, None, ReviewStatus::Flagged).unwrap();
+        save_review(&conn, "assets/c.png", 0.1, None, settings.status_for(0.1)).unwrap();
+        assert!(pending_tasks(&conn, root, None, false).unwrap().is_empty());
+        assert_eq!(pending_tasks(&conn, root, Some(&["a".to_string()]), true).unwrap().len(), 1);
+        
+        let flagged = flagged_assets(&conn, false).unwrap();
+        assert_eq!(flagged.iter().map(|f| f.asset_id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!((flagged[0].status, flagged[0].name.as_str()), (ReviewStatus::Quarantined, "A"));
+        
+        assert_eq!(review(&conn, &["a".to_string(), "c".to_string()], ReviewDecision::Approve).unwrap(), 1);
+        let flagged = flagged_assets(&conn, false).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged_assets(&conn, true).unwrap()[0].status, ReviewStatus::Approved);
+    }
+
+    #[test]
+    fn test_settings_survive_project_save() {
+        let dir = tempdir().unwrap();
+        let mut project = io_sqlite::init_project_sqlite(dir.path(), "Safety").unwrap();
+        let conn = database::open_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let saved = save_settings(&conn, &SafetySettings { policy: SafetyPolicy::Quarantine, threshold: 0.4 }).unwrap();
+
+        // The frontend's autosave sends no settings
+        project.settings = Some(std::collections::HashMap::new());
+        io_sqlite::save_project_sqlite(dir.path(), &project).unwrap();
+
+        assert_eq!(get_settings(&conn).unwrap(), saved);
+        assert_ne!(saved, Default::default());
+    }
+}
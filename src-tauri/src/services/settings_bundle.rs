@@ -0,0 +1,334 @@
+//! Settings bundles: a user's setup in one file, to move it to another
+//! machine.
+//!
+//! A bundle holds the portable part of `GlobalConfig` (provider settings,
+//! default models, theme, network and backup preferences), every agent and
+//! every component. Secrets (API keys, tokens) and machine-specific entries
+//! (recent projects, workspace and backup folders) are left out. Importing
+//! merges the bundle over the current setup: settings present in the bundle
+//! win, secrets already on this machine are kept, and agents and components
+//! with the same ID are replaced.
+
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::GlobalConfig;
+use crate::error::AppError;
+use crate::models::{AgentDefinition, ComponentDefinition};
+use crate::services::backup::BackupSettings;
+use crate::services::components;
+use crate::services::net_guard::NetworkTimeouts;
+use ts_rs::TS;
+
+/// `format` of every bundle file
+pub const BUNDLE_FORMAT: &str = "synnia-settings";
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Object keys dropped from settings JSON wherever they appear
+const SECRET_KEYS: &[&str] = &["apiKey", "api_key", "token", "secret", "clientSecret", "password"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub settings: PortableSettings,
+    #[serde(default)]
+    pub agents: Vec<AgentDefinition>,
+    #[serde(default)]
+    pub components: Vec<ComponentDefinition>,
+}
+
+/// The parts of `GlobalConfig` that make sense on another machine. The JSON
+/// string settings are stored parsed, with secrets removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PortableSettings {
+    pub theme: Option<String>,
+    pub language: Option<String>,
+    pub gemini_base_url: Option<String>,
+    pub gemini_model_name: Option<String>,
+    pub ai_config: Option<Value>,
+    pub media_config: Option<Value>,
+    pub app_settings: Option<Value>,
+    pub network_allowlist: Option<Vec<String>>,
+    pub network_strict: Option<bool>,
+    pub network_timeouts: Option<NetworkTimeouts>,
+    pub backup: Option<BackupSettings>,
+}
+
+/// What a bundle exported or imported
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleReport {
+    /// The bundle file
+    pub path: String,
+    /// Names of the settings included
+    pub settings: Vec<String>,
+    pub agents: usize,
+    pub components: usize,
+}
+
+/// Collect the current setup into a bundle.
+pub fn collect(config: &GlobalConfig, agents_dir: &Path, components_dir: &Path) -> SettingsBundle {
+    SettingsBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        settings: PortableSettings {
+            theme: config.theme.clone(),
+            language: config.language.clone(),
+            gemini_base_url: config.gemini_base_url.clone(),
+            gemini_model_name: config.gemini_model_name.clone(),
+            ai_config: config.ai_config.as_deref().and_then(portable_json),
+            media_config: config.media_config.as_deref().and_then(portable_json),
+            app_settings: config.app_settings.as_deref().and_then(portable_json),
+            network_allowlist: config.network_allowlist.clone(),
+            network_strict: config.network_strict,
+            network_timeouts: config.network_timeouts.clone(),
+            backup: config.backup.clone().map(|backup| BackupSettings { directory: None, ..backup }),
+        },
+        agents: list_agents(agents_dir),
+        components: components::list_components(components_dir),
+    }
+}
+
+/// Write the current setup to `dest` as a bundle file.
+pub fn export_bundle(config: &GlobalConfig, agents_dir: &Path, components_dir: &Path, dest: &Path) -> Result<BundleReport, AppError> {
+    let bundle = collect(config, agents_dir, components_dir);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(dest, serde_json::to_string_pretty(&bundle)?)?;
+    Ok(report(&bundle, dest))
+}
+
+/// Read a bundle file, rejecting files that aren't bundles or come from a
+/// newer version.
+pub fn read_bundle(path: &Path) -> Result<SettingsBundle, AppError> {
+    let content = fs::read_to_string(path)?;
+    let bundle: SettingsBundle = serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidInput(format!("Not a settings bundle: {}", e)))?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(AppError::InvalidInput(format!("Not a settings bundle: {}", path.display())));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "Settings bundle version {} is newer than this version of Synnia supports", bundle.version
+        )));
+    }
+    Ok(bundle)
+}
+
+/// Merge `bundle` into `config` and write its agents and components. The
+/// caller saves `config`.
+pub fn apply_bundle(
+    bundle: &SettingsBundle,
+    config: &mut GlobalConfig,
+    agents_dir: &Path,
+    components_dir: &Path,
+) -> Result<(), AppError> {
+    let settings = &bundle.settings;
+    merge_option(&mut config.theme, &settings.theme);
+    merge_option(&mut config.language, &settings.language);
+    merge_option(&mut config.gemini_base_url, &settings.gemini_base_url);
+    merge_option(&mut config.gemini_model_name, &settings.gemini_model_name);
+    merge_json_setting(&mut config.ai_config, &settings.ai_config)?;
+    merge_json_setting(&mut config.media_config, &settings.media_config)?;
+    merge_json_setting(&mut config.app_settings, &settings.app_settings)?;
+    merge_option(&mut config.network_allowlist, &settings.network_allowlist);
+    merge_option(&mut config.network_strict, &settings.network_strict);
+    merge_option(&mut config.network_timeouts, &settings.network_timeouts);
+    if let Some(backup) = &settings.backup {
+        let directory = config.backup.as_ref().and_then(|b| b.directory.clone());
+        config.backup = Some(BackupSettings { directory, ..backup.clone() });
+    }
+
+    fs::create_dir_all(agents_dir)?;
+    for agent in &bundle.agents {
+        fs::write(agent_path(agents_dir, &agent.id), serde_json::to_string_pretty(agent)?)?;
+    }
+    fs::create_dir_all(components_dir)?;
+    for component in &bundle.components {
+        components::save_component(components_dir, component)?;
+    }
+    Ok(())
+}
+
+/// Summary of `bundle`, as written to or read from `path`
+pub fn report(bundle: &SettingsBundle, path: &Path) -> BundleReport {
+    let settings = &bundle.settings;
+    let included = [
+        ("theme", settings.theme.is_some()),
+        ("language", settings.language.is_some()),
+        ("geminiBaseUrl", settings.gemini_base_url.is_some()),
+        ("geminiModelName", settings.gemini_model_name.is_some()),
+        ("aiConfig", settings.ai_config.is_some()),
+        ("mediaConfig", settings.media_config.is_some()),
+        ("appSettings", settings.app_settings.is_some()),
+        ("networkAllowlist", settings.network_allowlist.is_some()),
+        ("networkStrict", settings.network_strict.is_some()),
+        ("networkTimeouts", settings.network_timeouts.is_some()),
+        ("backup", settings.backup.is_some()),
+    ];
+    BundleReport {
+        path: path.to_string_lossy().to_string(),
+        settings: included.iter().filter(|(_, set)| *set).map(|(name, _)| name.to_string()).collect(),
+        agents: bundle.agents.len(),
+        components: bundle.components.len(),
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// A JSON string setting, parsed and without secrets. Unparseable settings
+/// are left out, since secrets can't be removed from them.
+fn portable_json(raw: &str) -> Option<Value> {
+    let mut value = serde_json::from_str::<Value>(raw).ok()?;
+    strip_secrets(&mut value);
+    Some(value)
+}
+
+fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !SECRET_KEYS.contains(&key.as_str()));
+            map.values_mut().for_each(strip_secrets);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+fn merge_option<T: Clone>(current: &mut Option<T>, imported: &Option<T>) {
+    if imported.is_some() {
+        current.clone_from(imported);
+    }
+}
+
+/// Merge an imported JSON setting over the current JSON string, keeping
+/// current values (secrets among them) the bundle doesn't have.
+fn merge_json_setting(current: &mut Option<String>, imported: &Option<Value>) -> Result<(), AppError> {
+    let Some(imported) = imported else { return Ok(()) };
+    let mut merged = current.as_deref()
+        .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+        .unwrap_or(Value::Null);
+    merge_value(&mut merged, imported);
+    *current = Some(serde_json::to_string(&merged)?);
+    Ok(())
+}
+
+fn merge_value(current: &mut Value, imported: &Value) {
+    match (current, imported) {
+        (Value::Object(current), Value::Object(imported)) => {
+            for (key, value) in imported {
+                merge_value(current.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (current, imported) => *current = imported.clone(),
+    }
+}
+
+fn list_agents(dir: &Path) -> Vec<AgentDefinition> {
+    let mut agents: Vec<AgentDefinition> = fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+                .filter_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    agents.sort_by(|a, b| a.id.cmp(&b.id));
+    agents
+}
+
+/// Same file name `save_agent` uses
+fn agent_path(dir: &Path, id: &str) -> std::path::PathBuf {
+    let safe_id: String = id.chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-').collect();
+    dir.join(format!("{}.json", safe_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn agent(id: &str) -> AgentDefinition {
+        AgentDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            system_prompt: "You are {{role}}".to_string(),
+            input_schema: "{}".to_string(),
+            output_config: None,
+            is_system: false,
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_keeps_secrets_local() {
+        let source = tempdir().unwrap();
+        let (agents_dir, components_dir) = (source.path().join("agents"), source.path().join("components"));
+        fs::create_dir_all(&agents_dir).unwrap();
+        fs::write(agent_path(&agents_dir, "critic"), serde_json::to_string(&agent("critic")).unwrap()).unwrap();
+
+        let config = GlobalConfig {
+            theme: Some("dark".to_string()),
+            gemini_api_key: Some("old-key".to_string()),
+            clip_token: Some("clip-secret".to_string()),
+            default_workspace: Some("/Users/me/Projects".to_string()),
+            app_settings: Some(json!({
+                "providers": { "openai": { "apiKey": "sk-live", "baseUrl": "https://api.openai.com" } },
+                "defaultModels": { "llm-chat": "gpt-4o" },
+            }).to_string()),
+            backup: Some(BackupSettings { directory: Some("/Volumes/Backup".to_string()), ..Default::default() }),
+            ..Default::default()
+        };
+
+        let file = source.path().join("setup.synnia-settings");
+        let exported = export_bundle(&config, &agents_dir, &components_dir, &file).unwrap();
+        assert_eq!((exported.agents, exported.components), (1, 0));
+        assert_eq!(exported.settings, ["theme", "appSettings", "backup"]);
+        let raw = fs::read_to_string(&file).unwrap();
+        for leaked in ["sk-live", "old-key", "clip-secret", "/Users/me", "/Volumes/Backup"] {
+            assert!(!raw.contains(leaked), "{} must not be exported", leaked);
+        }
+
+        // A machine that already has its own key for the provider
+        let target = tempdir().unwrap();
+        let mut local = GlobalConfig {
+            theme: Some("light".to_string()),
+            language: Some("de".to_string()),
+            app_settings: Some(json!({ "providers": { "openai": { "apiKey": "sk-local" } } }).to_string()),
+            ..Default::default()
+        };
+        let bundle = read_bundle(&file).unwrap();
+        apply_bundle(&bundle, &mut local, &target.path().join("agents"), &target.path().join("components")).unwrap();
+
+        assert_eq!(local.theme.as_deref(), Some("dark"));
+        assert_eq!(local.language.as_deref(), Some("de"), "settings missing from the bundle are kept");
+        let settings: Value = serde_json::from_str(local.app_settings.as_deref().unwrap()).unwrap();
+        assert_eq!(settings["providers"]["openai"], json!({ "apiKey": "sk-local", "baseUrl": "https://api.openai.com" }));
+        assert_eq!(settings["defaultModels"]["llm-chat"], "gpt-4o");
+        assert_eq!(local.backup.unwrap().directory, None);
+        assert_eq!(list_agents(&target.path().join("agents")).len(), 1);
+    }
+
+    #[test]
+    fn test_read_bundle_rejects_other_files() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("other.json");
+        fs::write(&file, json!({ "format": "something-else", "version": 1, "exportedAt": "", "settings": {} }).to_string()).unwrap();
+        assert!(matches!(read_bundle(&file), Err(AppError::InvalidInput(_))));
+
+        fs::write(&file, json!({ "format": BUNDLE_FORMAT, "version": BUNDLE_VERSION + 1, "exportedAt": "", "settings": {} }).to_string()).unwrap();
+        assert!(matches!(read_bundle(&file), Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,403 @@
+//! Share bundles: a read-only HTML snapshot of selected nodes, with their
+//! files, for people who don't have Synnia.
+//!
+//! A bundle is a folder under `exports/share/` holding `index.html` and a
+//! `files/` folder, plus a zip of the same next to it for attaching to an
+//! email. Bundles are tracked in the project database with their expiry:
+//! the page itself says it has expired once the date passes, and expired
+//! bundles are deleted from disk the next time bundles are listed or made.
+//!
+//! Bundles stay on this machine; handing the zip (or folder) over is up to
+//! the user.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{backup, content_type, imaging, io_sqlite, text_asset};
+use crate::services::export::{sanitize, EXPORT_DIR};
+use ts_rs::TS;
+
+/// Folder (under the export folder) receiving bundles
+pub const SHARE_DIR: &str = "share";
+
+/// Most nodes per bundle
+pub const MAX_NODES: usize = 500;
+
+/// Longest expiry, in days
+pub const MAX_EXPIRY_DAYS: u32 = 365;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const FILES_DIR: &str = "files";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareBundle {
+    pub id: String,
+    pub title: String,
+    /// Bundle folder, relative to the project root
+    pub path: String,
+    /// Zip of the folder, relative to the project root
+    pub archive: String,
+    /// Items on the page
+    pub items: usize,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// When the bundle expires (Unix ms); none if it doesn't
+    #[ts(type = "number | null")]
+    pub expires_at: Option<i64>,
+}
+
+/// A node as it appears on the page
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Image { caption: String, file: PathBuf },
+    Video { caption: String, file: PathBuf },
+    Audio { caption: String, file: PathBuf },
+    File { caption: String, file: PathBuf },
+    Text { caption: String, text: String },
+}
+
+/// Write a bundle of `node_ids` that expires after `expiry_days` (never,
+/// with `None`) and record it.
+pub fn create(project_root: &Path, conn: &Connection, node_ids: &[String], expiry_days: Option<u32>) -> Result<ShareBundle, AppError> {
+    if node_ids.is_empty() {
+        return Err(AppError::InvalidInput("No nodes to share".to_string()));
+    }
+    if node_ids.len() > MAX_NODES {
+        return Err(AppError::InvalidInput(format!("At most {} nodes per bundle", MAX_NODES)));
+    }
+    if expiry_days.is_some_and(|days| days == 0 || days > MAX_EXPIRY_DAYS) {
+        return Err(AppError::InvalidInput(format!("Expiry has to be between 1 and {} days", MAX_EXPIRY_DAYS)));
+    }
+    prune_expired(project_root, conn)?;
+
+    let items = collect(project_root, conn, node_ids)?;
+    if items.is_empty() {
+        return Err(AppError::InvalidInput("Nothing to share: no images, media or text in the selection".to_string()));
+    }
+
+    let title: String = conn.query_row("SELECT name FROM project_meta LIMIT 1", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| AppError::Io(format!("Failed to load project: {}", e)))?
+        .unwrap_or_else(|| "Synnia share".to_string());
+    let id = uuid::Uuid::new_v4().to_string();
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let expires_at = expiry_days.map(|days| created_at + days as i64 * DAY_MS);
+
+    let share_dir = project_root.join(EXPORT_DIR).join(SHARE_DIR);
+    let folder = format!("{}_{}", sanitize(&title), &id[..8]);
+    let bundle_dir = share_dir.join(&folder);
+    fs::create_dir_all(bundle_dir.join(FILES_DIR))?;
+
+    let written = write_bundle(&bundle_dir, &title, &items, created_at, expires_at)
+        .and_then(|files| write_archive(&share_dir.join(format!("{}.zip", folder)), &files));
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&bundle_dir);
+        let _ = fs::remove_file(share_dir.join(format!("{}.zip", folder)));
+        return Err(e);
+    }
+
+    let bundle = ShareBundle {
+        id,
+        title,
+        path: format!("{}/{}/{}", EXPORT_DIR, SHARE_DIR, folder),
+        archive: format!("{}/{}/{}.zip", EXPORT_DIR, SHARE_DIR, folder),
+        items: items.len(),
+        created_at,
+        expires_at,
+    };
+    conn.execute(
+        "INSERT INTO share_bundles (id, title, path, archive, items, created_at, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![bundle.id, bundle.title, bundle.path, bundle.archive, bundle.items as i64, bundle.created_at, bundle.expires_at],
+    ).map_err(|e| AppError::Io(format!("Failed to save share bundle: {}", e)))?;
+    Ok(bundle)
+}
+
+/// Bundles still on disk, newest first. Expired ones are deleted first.
+pub fn list(project_root: &Path, conn: &Connection) -> Result<Vec<ShareBundle>, AppError> {
+    prune_expired(project_root, conn)?;
+    load_bundles(conn, None)
+}
+
+/// Delete a bundle's folder and zip and stop tracking it.
+pub fn delete(project_root: &Path, conn: &Connection, bundle_id: &str) -> Result<(), AppError> {
+    let bundle = load_bundles(conn, Some(bundle_id))?.pop()
+        .ok_or_else(|| AppError::NotFound(format!("Share bundle not found: {}", bundle_id)))?;
+    remove_files(project_root, &bundle)?;
+    conn.execute("DELETE FROM share_bundles WHERE id = ?1", params![bundle_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete share bundle: {}", e)))?;
+    Ok(())
+}
+
+/// Delete every bundle whose expiry has passed; how many went.
+pub fn prune_expired(project_root: &Path, conn: &Connection) -> Result<usize, AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let expired: Vec<ShareBundle> = load_bundles(conn, None)?.into_iter()
+        .filter(|b| b.expires_at.is_some_and(|at| at <= now))
+        .collect();
+    for bundle in &expired {
+        remove_files(project_root, bundle)?;
+        conn.execute("DELETE FROM share_bundles WHERE id = ?1", params![bundle.id])
+            .map_err(|e| AppError::Io(format!("Failed to delete share bundle: {}", e)))?;
+    }
+    Ok(expired.len())
+}
+
+/// Rough size of a bundle of `node_ids`: their files, twice (folder and zip)
+pub fn estimated_size(project_root: &Path, conn: &Connection, node_ids: &[String]) -> Result<u64, AppError> {
+    let size: u64 = collect(project_root, conn, node_ids)?.iter()
+        .filter_map(|item| match item {
+            Item::Image { file, .. } | Item::Video { file, .. } | Item::Audio { file, .. } | Item::File { file, .. } => {
+                fs::metadata(file).ok().map(|m| m.len())
+            }
+            Item::Text { text, .. } => Some(text.len() as u64),
+        })
+        .sum();
+    Ok(size * 2)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn collect(project_root: &Path, conn: &Connection, node_ids: &[String]) -> Result<Vec<Item>, AppError> {
+    let mut items = Vec::new();
+    for node_id in node_ids {
+        let node = io_sqlite::get_node(conn, node_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", node_id)))?;
+        let Some(asset) = node.data.asset_id.as_deref().map(|id| io_sqlite::get_asset(conn, id)).transpose()?.flatten() else {
+            continue;
+        };
+        let caption = Some(node.data.title.trim())
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| asset.sys.name.clone());
+
+        if let Some(text) = text_asset::text_content(&asset) {
+            items.push(Item::Text { caption, text: text.to_string() });
+            continue;
+        }
+        let file = asset.value.get("src").and_then(|s| s.as_str())
+            .and_then(|src| imaging::resolve_image_source(project_root, src))
+            .filter(|path| path.is_file());
+        if let Some(file) = file {
+            let mime = content_type::detect_file_mime(&file);
+            items.push(match mime.split('/').next() {
+                Some("image") => Item::Image { caption, file },
+                Some("video") => Item::Video { caption, file },
+                Some("audio") => Item::Audio { caption, file },
+                _ => Item::File { caption, file },
+            });
+        }
+    }
+    Ok(items)
+}
+
+/// Copy the files and write `index.html`; every file written, relative to
+/// the bundle folder
+fn write_bundle(bundle_dir: &Path, title: &str, items: &[Item], created_at: i64, expires_at: Option<i64>) -> Result<Vec<(String, PathBuf)>, AppError> {
+    let mut written = Vec::new();
+    let mut cards = String::new();
+    for (i, item) in items.iter().enumerate() {
+        let (caption, body) = match item {
+            Item::Text { caption, text } => (caption, format!("<div class=\"text\">{}</div>", escape(text))),
+            Item::Image { caption, file } | Item::Video { caption, file } | Item::Audio { caption, file } | Item::File { caption, file } => {
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let relative = format!("{}/{}_{}", FILES_DIR, i + 1, sanitize_file_name(&name));
+                let dest = bundle_dir.join(&relative);
+                fs::copy(file, &dest)?;
+                written.push((relative.clone(), dest));
+                let src = escape(&relative);
+                let body = match item {
+                    Item::Image { .. } => format!("<a href=\"{0}\"><img src=\"{0}\" alt=\"{1}\" loading=\"lazy\"></a>", src, escape(caption)),
+                    Item::Video { .. } => format!("<video src=\"{}\" controls preload=\"metadata\"></video>", src),
+                    Item::Audio { .. } => format!("<audio src=\"{}\" controls preload=\"metadata\"></audio>", src),
+                    _ => format!("<a class=\"file\" href=\"{}\">{}</a>", src, escape(&name)),
+                };
+                (caption, body)
+            }
+        };
+        cards.push_str(&format!("<figure>{}<figcaption>{}</figcaption></figure>\n", body, escape(caption)));
+    }
+
+    let index = bundle_dir.join("index.html");
+    fs::write(&index, render_page(title, &cards, created_at, expires_at))?;
+    written.push(("index.html".to_string(), index));
+    Ok(written)
+}
+
+fn write_archive(archive: &Path, files: &[(String, PathBuf)]) -> Result<(), AppError> {
+    let mut zip = backup::ZipWriter::new(fs::File::create(archive)?);
+    for (name, path) in files {
+        zip.add(name, &fs::read(path)?)?;
+    }
+    zip.finish()
+}
+
+fn render_page(title: &str, cards: &str, created_at: i64, expires_at: Option<i64>) -> String {
+    let date = |ms: i64| chrono::DateTime::from_timestamp_millis(ms)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let mut note = format!("Shared {}", date(created_at));
+    if let Some(expires_at) = expires_at {
+        note.push_str(&format!(" · expires {}", date(expires_at)));
+    }
+    // The page can't be taken back once sent, but it can stop showing itself
+    let expiry_check = expires_at
+        .map(|at| format!(
+            "<script>if (Date.now() > {}) {{ document.body.innerHTML = '<p class=\"expired\">This share has expired.</p>'; }}</script>",
+            at,
+        ))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="generator" content="Synnia">
+<title>{title}</title>
+<style>
+body {{ margin: 0; padding: 32px; font: 15px/1.5 system-ui, sans-serif; background: #f6f6f6; color: #222; }}
+header {{ margin-bottom: 24px; }}
+h1 {{ margin: 0; font-size: 24px; }}
+.note {{ color: #777; font-size: 13px; }}
+main {{ display: grid; grid-template-columns: repeat(auto-fill, minmax(280px, 1fr)); gap: 20px; }}
+figure {{ margin: 0; padding: 12px; background: #fff; border-radius: 8px; box-shadow: 0 1px 3px rgba(0, 0, 0, .1); }}
+img, video, audio {{ display: block; width: 100%; border-radius: 4px; }}
+figcaption {{ margin-top: 8px; font-weight: 600; }}
+.text {{ white-space: pre-wrap; max-height: 360px; overflow: auto; }}
+.expired {{ text-align: center; color: #777; margin-top: 20vh; }}
+</style>
+</head>
+<body>
+<header><h1>{title}</h1><div class="note">{note}</div></header>
+<main>
+{cards}</main>
+{expiry_check}
+</body>
+</html>
+"#,
+        title = escape(title),
+        note = escape(&note),
+        cards = cards,
+        expiry_check = expiry_check,
+    )
+}
+
+fn load_bundles(conn: &Connection, bundle_id: Option<&str>) -> Result<Vec<ShareBundle>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, path, archive, items, created_at, expires_at FROM share_bundles
+         WHERE ?1 IS NULL OR id = ?1 ORDER BY created_at DESC"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![bundle_id], |row| {
+        Ok(ShareBundle {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            path: row.get(2)?,
+            archive: row.get(3)?,
+            items: row.get::<_, i64>(4)? as usize,
+            created_at: row.get(5)?,
+            expires_at: row.get(6)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to load share bundles: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load share bundles: {}", e)))
+}
+
+/// Remove a bundle's folder and zip, if they're still where they belong
+fn remove_files(project_root: &Path, bundle: &ShareBundle) -> Result<(), AppError> {
+    let share_dir = project_root.join(EXPORT_DIR).join(SHARE_DIR);
+    for relative in [&bundle.path, &bundle.archive] {
+        let path = project_root.join(relative);
+        if path.parent() != Some(share_dir.as_path()) {
+            continue;
+        }
+        if path.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else if path.is_file() {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the extension, unlike `export::sanitize`
+fn sanitize_file_name(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.{}", sanitize(stem), sanitize(ext)),
+        None => sanitize(name),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn add_image_node(root: &Path, conn: &Connection, id: &str, file: &str) {
+        let mut png = Vec::new();
+        image::RgbImage::from_pixel(4, 4, image::Rgb([9, 9, 9]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        fs::create_dir_all(root.join("assets")).unwrap();
+        fs::write(root.join("assets").join(file), png).unwrap();
+
+        let sys = json!({ "name": file, "createdAt": 0, "updatedAt": 0, "source": "user" });
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at) VALUES (?1, 'record', 'h', ?2, ?3, 0)",
+            params![format!("a-{}", id), json!({ "src": format!("assets/{}", file) }).to_string(), sys.to_string()],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO nodes (id, type, x, y, data_json) VALUES (?1, 'asset-node', 0, 0, ?2)",
+            params![id, json!({ "title": format!("Title {}", id), "assetId": format!("a-{}", id) }).to_string()],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_create_list_and_prune() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let conn = init_db(&io_sqlite::get_db_path(root)).unwrap();
+        add_image_node(root, &conn, "n1", "shot 1.png");
+
+        let bundle = create(root, &conn, &["n1".to_string()], Some(7)).unwrap();
+        assert_eq!(bundle.items, 1);
+        assert!(bundle.expires_at.unwrap() > bundle.created_at);
+        let html = fs::read_to_string(root.join(&bundle.path).join("index.html")).unwrap();
+        assert!(html.contains("Title n1"));
+        assert!(html.contains("<img src=\"files/1_"));
+        assert!(html.contains("Date.now() >"));
+        assert!(root.join(&bundle.archive).is_file());
+        assert_eq!(list(root, &conn).unwrap(), vec![bundle.clone()]);
+
+        assert!(create(root, &conn, &["n1".to_string()], Some(0)).is_err());
+        assert!(create(root, &conn, &["missing".to_string()], None).is_err());
+
+        // Once past its expiry the bundle is removed on the next listing
+        conn.execute("UPDATE share_bundles SET expires_at = 1 WHERE id = ?1", params![bundle.id]).unwrap();
+        assert!(list(root, &conn).unwrap().is_empty());
+        assert!(!root.join(&bundle.path).exists());
+        assert!(!root.join(&bundle.archive).exists());
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("shot <1>.png"), "shot__1_.png");
+        assert_eq!(sanitize_file_name("README"), "README");
+    }
+}
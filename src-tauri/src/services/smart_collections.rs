@@ -0,0 +1,288 @@
+//! Smart collections: saved asset queries the library shows as living
+//! collections ("all approved hero images").
+//!
+//! A query combines asset kinds, tag prefixes (as in tag search), custom
+//! field filters, created/updated date ranges and a free-text query; every
+//! part that is set has to match. Tags, fields and dates are evaluated in
+//! SQL, kinds and text against the loaded assets. Collections store the
+//! query, not their members, so they follow the project as it changes.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::custom_fields::{self, FieldFilter};
+use crate::services::licenses::{self, LicenseFilter};
+use crate::services::{asset_usage, io_sqlite, tags, text_asset};
+use ts_rs::TS;
+
+const MAX_NAME_LEN: usize = 64;
+const KINDS: &[&str] = &["image", "text", "record"];
+
+/// Inclusive range of Unix timestamps in milliseconds; either end may be open
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub from: Option<i64>,
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartQuery {
+    /// "image", "text" or "record"; empty for any
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    /// Comma-separated tag prefixes, e.g. "hero, night"
+    #[serde(default)]
+    pub tags: String,
+    #[serde(default)]
+    pub fields: Vec<FieldFilter>,
+    #[serde(default)]
+    pub created: Option<DateRange>,
+    #[serde(default)]
+    pub updated: Option<DateRange>,
+    #[serde(default)]
+    pub license: Option<LicenseFilter>,
+    /// Words that all have to appear in the asset name or text, ignoring case
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCollection {
+    pub id: String,
+    pub name: String,
+    pub query: SmartQuery,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// Save a new collection. Referenced custom fields have to exist.
+pub fn create(conn: &Connection, name: &str, query: &SmartQuery) -> Result<SmartCollection, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::InvalidInput(format!("Collection names need 1 to {} characters", MAX_NAME_LEN)));
+    }
+    validate(conn, query)?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let collection = SmartCollection {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        query: query.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+    conn.execute(
+        "INSERT INTO smart_collections (id, name, query_json, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![collection.id, collection.name, serde_json::to_string(&collection.query)?, now, now],
+    ).map_err(|e| AppError::Io(format!("Failed to save collection: {}", e)))?;
+    Ok(collection)
+}
+
+/// Collections in creation order.
+pub fn list(conn: &Connection) -> Result<Vec<SmartCollection>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, query_json, created_at, updated_at FROM smart_collections ORDER BY created_at, name"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+
+    let rows = stmt.query_map([], row_to_collection)
+        .map_err(|e| AppError::Io(format!("Failed to query collections: {}", e)))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load collections: {}", e)))
+}
+
+pub fn get(conn: &Connection, collection_id: &str) -> Result<Option<SmartCollection>, AppError> {
+    conn.query_row(
+        "SELECT id, name, query_json, created_at, updated_at FROM smart_collections WHERE id = ?1",
+        params![collection_id],
+        row_to_collection,
+    ).optional().map_err(|e| AppError::Io(format!("Failed to load collection: {}", e)))
+}
+
+/// Delete a collection. Returns false if it didn't exist.
+pub fn delete(conn: &Connection, collection_id: &str) -> Result<bool, AppError> {
+    let deleted = conn.execute("DELETE FROM smart_collections WHERE id = ?1", params![collection_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete collection: {}", e)))?;
+    Ok(deleted > 0)
+}
+
+/// IDs of the assets currently matching the collection, most recently
+/// updated first.
+pub fn evaluate(conn: &Connection, collection_id: &str, limit: usize) -> Result<Vec<String>, AppError> {
+    let collection = get(conn, collection_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Collection not found: {}", collection_id)))?;
+    run_query(conn, &collection.query, limit)
+}
+
+/// IDs of the assets matching `query`, most recently updated first.
+pub fn run_query(conn: &Connection, query: &SmartQuery, limit: usize) -> Result<Vec<String>, AppError> {
+    let mut params = Vec::new();
+    let mut conditions = tags::query_conditions(&query.tags, "a.id", &mut params);
+    for filter in &query.fields {
+        conditions.push(custom_fields::filter_condition(conn, filter, "a.id", &mut params)?);
+    }
+    conditions.extend(query.license.as_ref().and_then(|l| licenses::filter_condition(l, "a.id", &mut params)));
+    for (range, column) in [
+        (&query.created, "json_extract(a.sys_json, '$.createdAt')"),
+        (&query.updated, "json_extract(a.sys_json, '$.updatedAt')"),
+    ] {
+        let Some(range) = range else { continue };
+        for (bound, operator) in [(range.from, ">="), (range.to, "<=")] {
+            if let Some(bound) = bound {
+                params.push(SqlValue::Integer(bound));
+                conditions.push(format!("{} {} ?{}", column, operator, params.len()));
+            }
+        }
+    }
+
+    let words: Vec<String> = query.text.split_whitespace().map(str::to_lowercase).collect();
+    let post_filter = !query.kinds.is_empty() || !words.is_empty();
+    let sql = format!(
+        "SELECT a.id FROM assets a WHERE {} ORDER BY a.updated_at DESC{}",
+        if conditions.is_empty() { "1".to_string() } else { conditions.join(" AND ") },
+        // Kinds and text are checked afterwards, so the limit has to wait
+        if post_filter { String::new() } else { format!(" LIMIT {}", limit) },
+    );
+
+    let mut stmt = conn.prepare(&sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let ids = stmt.query_map(params_from_iter(params.iter()), |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Io(format!("Failed to run collection: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load collection results: {}", e)))?;
+    if !post_filter {
+        return Ok(ids);
+    }
+
+    let mut matches = Vec::new();
+    for id in ids {
+        if matches.len() >= limit {
+            break;
+        }
+        let Some(asset) = io_sqlite::get_asset(conn, &id)? else { continue };
+        if !query.kinds.is_empty() && !query.kinds.iter().any(|k| k == asset_usage::kind(&asset)) {
+            continue;
+        }
+        if !words.is_empty() {
+            let haystack = format!(
+                "{}\n{}",
+                asset.sys.name,
+                text_asset::text_content(&asset).unwrap_or_default()
+            ).to_lowercase();
+            if !words.iter().all(|w| haystack.contains(w.as_str())) {
+                continue;
+            }
+        }
+        matches.push(id);
+    }
+    Ok(matches)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn validate(conn: &Connection, query: &SmartQuery) -> Result<(), AppError> {
+    if let Some(kind) = query.kinds.iter().find(|k| !KINDS.contains(&k.as_str())) {
+        return Err(AppError::InvalidInput(format!("Unknown asset kind: {}", kind)));
+    }
+    for range in [&query.created, &query.updated].into_iter().flatten() {
+        if let (Some(from), Some(to)) = (range.from, range.to) {
+            if from > to {
+                return Err(AppError::InvalidInput("Date range ends before it starts".to_string()));
+            }
+        }
+    }
+    // Builds every field condition once, which checks fields and values
+    custom_fields::search(conn, custom_fields::FieldTarget::Asset, &query.fields, 1)?;
+    Ok(())
+}
+
+fn row_to_collection(row: &rusqlite::Row) -> rusqlite::Result<SmartCollection> {
+    let query: String = row.get(2)?;
+    Ok(SmartCollection {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        query: serde_json::from_str(&query).unwrap_or_default(),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::custom_fields::{CustomFieldInput, FieldKind, FieldTarget, FilterOp};
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    fn insert_asset(conn: &Connection, id: &str, name: &str, value: serde_json::Value, created_at: i64) {
+        let sys = serde_json::json!({ "name": name, "createdAt": created_at, "updatedAt": created_at, "source": "user" });
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+             VALUES (?1, 'record', '', ?2, ?3, ?4)",
+            params![id, value.to_string(), sys.to_string(), created_at],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_smart_collections() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        insert_asset(&conn, "hero", "Hero shot", serde_json::json!({ "src": "assets/hero.png" }), 1_000);
+        insert_asset(&conn, "alt", "Hero alternate", serde_json::json!({ "src": "assets/alt.png" }), 2_000);
+        insert_asset(&conn, "brief", "Brief", serde_json::json!({ "content": "The hero walks at dusk", "format": "markdown" }), 3_000);
+        for id in ["hero", "alt"] {
+            tags::set_tags(&conn, id, "user", &[("hero".to_string(), None)]).unwrap();
+        }
+        let status = custom_fields::save_field(&conn, &CustomFieldInput {
+            id: None,
+            name: "Status".to_string(),
+            kind: FieldKind::Select,
+            options: vec!["draft".to_string(), "approved".to_string()],
+        }).unwrap();
+        custom_fields::set_value(&conn, FieldTarget::Asset, "hero", &status.id, &serde_json::json!("approved")).unwrap();
+
+        let approved_heroes = create(&conn, " Approved heroes ", &SmartQuery {
+            kinds: vec!["image".to_string()],
+            tags: "her".to_string(),
+            fields: vec![FieldFilter { field_id: status.id.clone(), op: FilterOp::Equals, value: serde_json::json!("approved") }],
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(approved_heroes.name, "Approved heroes");
+        assert_eq!(evaluate(&conn, &approved_heroes.id, 10).unwrap(), vec!["hero"]);
+
+        // Collections follow the project as values change
+        custom_fields::set_value(&conn, FieldTarget::Asset, "alt", &status.id, &serde_json::json!("approved")).unwrap();
+        let mut found = evaluate(&conn, &approved_heroes.id, 10).unwrap();
+        found.sort();
+        assert_eq!(found, vec!["alt", "hero"]);
+
+        let text = run_query(&conn, &SmartQuery { text: "HERO dusk".to_string(), ..Default::default() }, 10).unwrap();
+        assert_eq!(text, vec!["brief"]);
+        let recent = SmartQuery { created: Some(DateRange { from: Some(1_500), to: None }), ..Default::default() };
+        assert_eq!(run_query(&conn, &recent, 10).unwrap().len(), 2);
+        assert_eq!(run_query(&conn, &SmartQuery::default(), 2).unwrap().len(), 2);
+
+        assert_eq!(list(&conn).unwrap().len(), 1);
+        assert!(matches!(
+            create(&conn, "Bad", &SmartQuery { kinds: vec!["video".to_string()], ..Default::default() }),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(delete(&conn, &approved_heroes.id).unwrap());
+        assert!(matches!(evaluate(&conn, &approved_heroes.id, 10), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,437 @@
+//! Spelling and grammar checks for text assets.
+//!
+//! Spelling uses word lists from the global dictionaries directory:
+//! - `<lang>.dic`: Hunspell dictionaries (affix flags are ignored, common
+//!   suffixes are stripped instead)
+//! - `<lang>.txt`: plain word lists, one word per line
+//!
+//! This is a word-list checker, not a Hunspell engine: `.aff` files are not
+//! read, so compounds and irregular inflections may be flagged.
+//!
+//! Grammar checks are simple rules: repeated words, runs of spaces and
+//! (for English) "a"/"an" agreement.
+//!
+//! All offsets are UTF-16 code units so the frontend can use them directly
+//! with JavaScript string indices.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use ts_rs::TS;
+
+/// Max suggestions returned per misspelled word
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Max edit distance for suggestions
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Suffixes tried when a word is not in the dictionary as-is
+const SUFFIXES: &[&str] = &["'s", "s", "es", "ed", "d", "ing", "ly", "er", "est"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueKind {
+    Spelling,
+    Grammar,
+}
+
+/// A problem found in the text, as a UTF-16 span
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TextIssue {
+    pub kind: IssueKind,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub message: String,
+    pub suggestions: Vec<String>,
+}
+
+/// A replacement for a UTF-16 span
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFix {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// Set of known words for one language
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    /// Parse a Hunspell `.dic` file: optional word count on the first line,
+    /// then one `word[/FLAGS]` per line.
+    pub fn from_dic(content: &str) -> Self {
+        let words = content
+            .lines()
+            .enumerate()
+            .filter(|(i, line)| !(*i == 0 && line.trim().parse::<usize>().is_ok()))
+            .filter_map(|(_, line)| line.split('/').next())
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+        Dictionary { words }
+    }
+
+    /// Parse a plain word list, one word per line.
+    pub fn from_word_list(content: &str) -> Self {
+        let words = content
+            .lines()
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty() && !w.starts_with('#'))
+            .collect();
+        Dictionary { words }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        if self.words.contains(&lower) {
+            return true;
+        }
+        SUFFIXES.iter().any(|suffix| {
+            lower.strip_suffix(suffix)
+                .filter(|stem| stem.chars().count() > 1)
+                .is_some_and(|stem| self.words.contains(stem) || self.words.contains(&format!("{}e", stem)))
+        })
+    }
+
+    /// Closest dictionary words by edit distance, best first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let len = lower.chars().count();
+        
+        let mut candidates: Vec<(usize, &String)> = self.words.iter()
+            .filter(|w| w.chars().count().abs_diff(len) <= MAX_SUGGESTION_DISTANCE)
+            .map(|w| (strsim::damerau_levenshtein(&lower, w), w))
+            .filter(|(d, _)| *d <= MAX_SUGGESTION_DISTANCE)
+            .collect();
+        candidates.sort();
+        
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        candidates.into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, w)| if capitalized { capitalize(w) } else { w.clone() })
+            .collect()
+    }
+}
+
+/// Load the dictionary for `lang` from `dir`, if one is installed.
+pub fn load_dictionary(dir: &Path, lang: &str) -> Option<Dictionary> {
+    let safe_lang = sanitize_lang(lang);
+    
+    let dic_path = dir.join(format!("{}.dic", safe_lang));
+    if let Ok(content) = fs::read_to_string(&dic_path) {
+        return Some(Dictionary::from_dic(&content));
+    }
+    
+    let txt_path = dir.join(format!("{}.txt", safe_lang));
+    fs::read_to_string(&txt_path).ok().map(|c| Dictionary::from_word_list(&c))
+}
+
+/// Languages with an installed dictionary, sorted.
+pub fn available_languages(dir: &Path) -> Vec<String> {
+    let mut langs: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|e| e.path())
+                .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("dic") | Some("txt")))
+                .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    langs.sort();
+    langs.dedup();
+    langs
+}
+
+/// Check `text` for spelling (when a dictionary is given) and grammar issues,
+/// ordered by position.
+pub fn check_text(text: &str, lang: &str, dictionary: Option<&Dictionary>) -> Vec<TextIssue> {
+    let words = tokenize(text);
+    let mut issues = Vec::new();
+    
+    if let Some(dictionary) = dictionary {
+        for word in &words {
+            if should_skip(word.text) || dictionary.contains(word.text) {
+                continue;
+            }
+            issues.push(TextIssue {
+                kind: IssueKind::Spelling,
+                start: word.start,
+                end: word.end,
+                text: word.text.to_string(),
+                message: format!("Unknown word \"{}\"", word.text),
+                suggestions: dictionary.suggest(word.text),
+            });
+        }
+    }
+    
+    check_repeated_words(text, &words, &mut issues);
+    check_spaces(text, &mut issues);
+    if lang.to_lowercase().starts_with("en") {
+        check_articles(&words, &mut issues);
+    }
+    
+    issues.sort_by_key(|i| (i.start, i.end));
+    issues
+}
+
+/// Apply non-overlapping fixes to `text`.
+pub fn apply_fixes(text: &str, fixes: &[TextFix]) -> Result<String, AppError> {
+    let offsets = utf16_to_byte_offsets(text);
+    
+    let mut sorted: Vec<&TextFix> = fixes.iter().collect();
+    sorted.sort_by_key(|f| std::cmp::Reverse(f.start));
+    
+    let mut result = text.to_string();
+    let mut limit = usize::MAX;
+    for fix in sorted {
+        if fix.start > fix.end || fix.end > limit {
+            return Err(AppError::Unknown(format!("Overlapping or invalid fix at {}..{}", fix.start, fix.end)));
+        }
+        let (start, end) = match (offsets.get(fix.start).copied().flatten(), offsets.get(fix.end).copied().flatten()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(AppError::Unknown(format!("Fix {}..{} is outside the text", fix.start, fix.end))),
+        };
+        result.replace_range(start..end, &fix.replacement);
+        limit = fix.start;
+    }
+    
+    Ok(result)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+struct Word<'a> {
+    text: &'a str,
+    /// UTF-16 span
+    start: usize,
+    end: usize,
+    /// Byte span
+    byte_start: usize,
+    byte_end: usize,
+}
+
+/// Split text into words (letters, digits and inner apostrophes).
+fn tokenize(text: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    let mut current: Option<(usize, usize)> = None; // (byte_start, utf16_start)
+    let mut utf16 = 0;
+    
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    for (i, &(byte, c)) in chars.iter().enumerate() {
+        let inner_apostrophe = (c == '\'' || c == '’')
+            && current.is_some()
+            && chars.get(i + 1).is_some_and(|(_, n)| n.is_alphabetic());
+        
+        if c.is_alphanumeric() || inner_apostrophe {
+            if current.is_none() {
+                current = Some((byte, utf16));
+            }
+        } else if let Some((byte_start, start)) = current.take() {
+            words.push(Word { text: &text[byte_start..byte], start, end: utf16, byte_start, byte_end: byte });
+        }
+        utf16 += c.len_utf16();
+    }
+    if let Some((byte_start, start)) = current {
+        words.push(Word { text: &text[byte_start..], start, end: utf16, byte_start, byte_end: text.len() });
+    }
+    
+    words
+}
+
+/// Words the spellchecker should ignore: numbers, acronyms, single letters
+fn should_skip(word: &str) -> bool {
+    word.chars().count() < 2
+        || word.chars().any(|c| c.is_numeric())
+        || word.chars().all(|c| !c.is_lowercase())
+}
+
+fn check_repeated_words(text: &str, words: &[Word], issues: &mut Vec<TextIssue>) {
+    for pair in words.windows(2) {
+        let (first, second) = (&pair[0], &pair[1]);
+        let between = &text[first.byte_end..second.byte_start];
+        if !between.is_empty()
+            && between.chars().all(|c| c == ' ' || c == '\t')
+            && first.text.to_lowercase() == second.text.to_lowercase()
+            && !first.text.chars().any(|c| c.is_numeric())
+        {
+            issues.push(TextIssue {
+                kind: IssueKind::Grammar,
+                start: first.end,
+                end: second.end,
+                text: text[first.byte_end..second.byte_end].to_string(),
+                message: format!("Repeated word \"{}\"", second.text),
+                suggestions: vec![String::new()],
+            });
+        }
+    }
+}
+
+fn check_spaces(text: &str, issues: &mut Vec<TextIssue>) {
+    let mut utf16 = 0;
+    let mut run: Option<(usize, usize)> = None; // (utf16_start, count)
+    let mut line_start = true;
+    
+    for c in text.chars() {
+        if c == ' ' {
+            if !line_start {
+                run = Some(run.map_or((utf16, 1), |(start, count)| (start, count + 1)));
+            }
+        } else {
+            if let Some((start, count)) = run.take() {
+                if count > 1 && c != '\n' && c != '\r' {
+                    issues.push(TextIssue {
+                        kind: IssueKind::Grammar,
+                        start,
+                        end: start + count,
+                        text: " ".repeat(count),
+                        message: "Multiple spaces".to_string(),
+                        suggestions: vec![" ".to_string()],
+                    });
+                }
+            }
+            line_start = c == '\n';
+        }
+        utf16 += c.len_utf16();
+    }
+}
+
+fn check_articles(words: &[Word], issues: &mut Vec<TextIssue>) {
+    for pair in words.windows(2) {
+        let (article, next) = (&pair[0], &pair[1]);
+        let lower = article.text.to_lowercase();
+        let starts_with_vowel = next.text.chars().next()
+            .is_some_and(|c| "aeiouAEIOU".contains(c));
+        let starts_with_consonant = next.text.chars().next()
+            .is_some_and(|c| c.is_ascii_alphabetic() && !"aeiouhAEIOUH".contains(c));
+        
+        let suggestion = match lower.as_str() {
+            "a" if starts_with_vowel => "an",
+            "an" if starts_with_consonant => "a",
+            _ => continue,
+        };
+        let suggestion = if article.text.starts_with('A') { capitalize(suggestion) } else { suggestion.to_string() };
+        
+        issues.push(TextIssue {
+            kind: IssueKind::Grammar,
+            start: article.start,
+            end: article.end,
+            text: article.text.to_string(),
+            message: format!("Use \"{}\" before \"{}\"", suggestion, next.text),
+            suggestions: vec![suggestion],
+        });
+    }
+}
+
+/// Map each UTF-16 offset (0..=len) to a byte offset; `None` inside a surrogate pair.
+fn utf16_to_byte_offsets(text: &str) -> Vec<Option<usize>> {
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    for (byte, c) in text.char_indices() {
+        offsets.push(Some(byte));
+        for _ in 1..c.len_utf16() {
+            offsets.push(None);
+        }
+    }
+    offsets.push(Some(text.len()));
+    offsets
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn sanitize_lang(lang: &str) -> String {
+    lang.chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn dictionary() -> Dictionary {
+        Dictionary::from_dic("6\nthe\nquick/S\nbrown\nfox/M\njump/SDG\nover\n")
+    }
+
+    #[test]
+    fn test_dictionary_parsing_and_suffixes() {
+        let dict = dictionary();
+        assert!(dict.contains("The"));
+        assert!(dict.contains("jumped"));
+        assert!(dict.contains("foxes"));
+        assert!(!dict.contains("6"));
+        assert!(!dict.contains("quack"));
+        assert_eq!(dict.suggest("Quikc").first().map(String::as_str), Some("Quick"));
+    }
+
+    #[test]
+    fn test_check_text() {
+        let text = "The quikc brown fox jumped over  the the fox, NASA 2024";
+        let issues = check_text(text, "en_US", Some(&dictionary()));
+        
+        let kinds: Vec<(&str, IssueKind)> = issues.iter().map(|i| (i.message.as_str(), i.kind)).collect();
+        assert_eq!(issues.len(), 3, "{:?}", kinds);
+        assert_eq!(issues[0].text, "quikc");
+        assert_eq!(issues[1].message, "Multiple spaces");
+        assert_eq!(issues[2].message, "Repeated word \"the\"");
+    }
+
+    #[test]
+    fn test_articles() {
+        let issues = check_text("A apple and an banana, an hour", "en", None);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].suggestions, vec!["An".to_string()]);
+        assert_eq!(issues[1].suggestions, vec!["a".to_string()]);
+        
+        assert!(check_text("a apple", "de", None).is_empty());
+    }
+
+    #[test]
+    fn test_apply_fixes_utf16() {
+        // The emoji is two UTF-16 code units
+        let text = "😀 teh cat";
+        let issues = check_text(text, "en", Some(&Dictionary::from_word_list("the\ncat")));
+        assert_eq!((issues[0].start, issues[0].end), (3, 6));
+        
+        let fixed = apply_fixes(text, &[
+            TextFix { start: 3, end: 6, replacement: "the".to_string() },
+            TextFix { start: 7, end: 10, replacement: "dog".to_string() },
+        ]).unwrap();
+        assert_eq!(fixed, "😀 the dog");
+        
+        let overlapping = [
+            TextFix { start: 3, end: 6, replacement: String::new() },
+            TextFix { start: 5, end: 7, replacement: String::new() },
+        ];
+        assert!(apply_fixes(text, &overlapping).is_err());
+        assert!(apply_fixes(text, &[TextFix { start: 1, end: 2, replacement: String::new() }]).is_err());
+    }
+
+    #[test]
+    fn test_load_dictionary() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("en_US.dic"), "1\nhello/S\n").unwrap();
+        fs::write(dir.path().join("fr.txt"), "bonjour\n").unwrap();
+        
+        assert!(load_dictionary(dir.path(), "en_US").unwrap().contains("hellos"));
+        assert!(load_dictionary(dir.path(), "../fr").unwrap().contains("bonjour"));
+        assert!(load_dictionary(dir.path(), "de").is_none());
+        assert_eq!(available_languages(dir.path()), vec!["en_US".to_string(), "fr".to_string()]);
+    }
+}
@@ -0,0 +1,439 @@
+//! Stock photo search: Unsplash and Pexels.
+//!
+//! Keys come from the app settings (`providers.unsplash` /
+//! `providers.pexels`, as for AI providers). Results carry a
+//! `<provider>:<photo id>` ID; importing one looks the photo up again, so
+//! nothing has to be kept between the search and the import, downloads the
+//! chosen size and records the provider's license with the photographer's
+//! credit on the new asset.
+
+use std::path::Path;
+use std::time::Duration;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::AppError;
+use crate::models::{Asset, AssetSysMetadata, SynniaNode, SynniaNodeData, ValueType};
+use crate::services::{clip, database, folder_import, io_sqlite, licenses};
+use crate::services::activity::{self, ActivityKind, NewActivity};
+use crate::services::licenses::AssetLicense;
+use crate::services::net_guard::NetworkPolicy;
+use crate::services::provider_health::ProviderCredentials;
+use ts_rs::TS;
+
+/// Give up on a provider after this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Results per search page
+pub const PAGE_SIZE: u32 = 30;
+
+const MAX_QUERY_LEN: usize = 200;
+const NODE_WIDTH: f64 = 300.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum StockProvider {
+    Unsplash,
+    Pexels,
+}
+
+impl StockProvider {
+    /// Key of the provider in the app settings
+    pub fn key(&self) -> &'static str {
+        match self {
+            StockProvider::Unsplash => "unsplash",
+            StockProvider::Pexels => "pexels",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            StockProvider::Unsplash => "Unsplash",
+            StockProvider::Pexels => "Pexels",
+        }
+    }
+
+    fn base_url(&self) -> &'static str {
+        match self {
+            StockProvider::Unsplash => "https://api.unsplash.com",
+            StockProvider::Pexels => "https://api.pexels.com/v1",
+        }
+    }
+}
+
+/// Size to download
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum StockSize {
+    /// About 400px wide
+    Small,
+    /// About 1000px wide
+    #[default]
+    Medium,
+    /// About 2000px wide
+    Large,
+    /// The file as uploaded
+    Original,
+}
+
+/// A photo found by `search`
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StockResult {
+    /// `<provider>:<photo id>`, for `import_stock_result`
+    pub id: String,
+    pub provider: StockProvider,
+    pub description: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail_url: String,
+    pub preview_url: String,
+    /// Photographer
+    pub author: String,
+    pub author_url: Option<String>,
+    /// The photo's page on the provider's site
+    pub page_url: String,
+}
+
+/// Asset and node made by `import`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StockImport {
+    pub asset: Asset,
+    pub node: SynniaNode,
+    pub license: AssetLicense,
+}
+
+/// A photo with its download links
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockPhoto {
+    pub result: StockResult,
+    /// Small, medium, large and original
+    urls: [String; 4],
+    /// Endpoint to report a download to (Unsplash asks for it)
+    download_location: Option<String>,
+}
+
+impl StockPhoto {
+    pub fn url(&self, size: StockSize) -> &str {
+        &self.urls[size as usize]
+    }
+
+    /// License and credit line the provider asks for
+    pub fn license(&self) -> AssetLicense {
+        let result = &self.result;
+        let provider = result.provider.label();
+        AssetLicense {
+            license_type: format!("{} License", provider),
+            attribution: Some(format!("Photo by {} on {}", result.author, provider)),
+            restrictions: Some("May not be sold unaltered or used to build a competing stock service".to_string()),
+            expires_at: None,
+            source_url: Some(result.page_url.clone()),
+        }
+    }
+}
+
+/// One page (from 1) of photos matching `query`.
+pub async fn search(
+    policy: &NetworkPolicy,
+    provider: StockProvider,
+    credentials: &ProviderCredentials,
+    query: &str,
+    page: u32,
+) -> Result<Vec<StockResult>, AppError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::InvalidInput("Search query is empty".to_string()));
+    }
+    if query.chars().count() > MAX_QUERY_LEN {
+        return Err(AppError::InvalidInput(format!("Search queries are at most {} characters", MAX_QUERY_LEN)));
+    }
+    let path = match provider {
+        StockProvider::Unsplash => "search/photos",
+        StockProvider::Pexels => "search",
+    };
+    let params = [
+        ("query", query.to_string()),
+        ("page", page.max(1).to_string()),
+        ("per_page", PAGE_SIZE.to_string()),
+    ];
+    let body = get_json(policy, provider, credentials, path, &params).await?;
+
+    let photos = match provider {
+        StockProvider::Unsplash => body.get("results"),
+        StockProvider::Pexels => body.get("photos"),
+    };
+    Ok(photos.and_then(Value::as_array).into_iter().flatten()
+        .filter_map(|photo| parse_photo(provider, photo))
+        .map(|photo| photo.result)
+        .collect())
+}
+
+/// Look up a photo by the ID of a search result.
+pub async fn fetch_photo(
+    policy: &NetworkPolicy,
+    credentials: &ProviderCredentials,
+    id: &str,
+) -> Result<StockPhoto, AppError> {
+    let (provider, photo_id) = parse_id(id)?;
+    let body = get_json(policy, provider, credentials, &format!("photos/{}", photo_id), &[]).await?;
+    parse_photo(provider, &body)
+        .ok_or_else(|| AppError::Network(format!("Unexpected response from {}", provider.label())))
+}
+
+/// Tell the provider the photo was downloaded, where its guidelines ask for it.
+pub async fn track_download(policy: &NetworkPolicy, credentials: &ProviderCredentials, photo: &StockPhoto) -> Result<(), AppError> {
+    let Some(location) = &photo.download_location else {
+        return Ok(());
+    };
+    let url = policy.check_url(location).await?;
+    let client = client(policy)?;
+    authorize(client.get(url), photo.result.provider, credentials)?
+        .send().await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    Ok(())
+}
+
+/// Add a downloaded photo to the project: an image asset from source
+/// "stock", a node next to everything on the canvas, and the license.
+pub fn import(project_root: &Path, photo: &StockPhoto, data: &[u8]) -> Result<StockImport, AppError> {
+    let result = &photo.result;
+    let name = result.description.clone()
+        .unwrap_or_else(|| format!("Photo by {}", result.author));
+    let (value, value_meta, node_type) = clip::store_file(project_root, &format!("{}.jpg", result.id.replace(':', "-")), data)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let asset = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Record,
+        value,
+        value_meta,
+        config: Some(serde_json::json!({ "schema": [] })),
+        sys: AssetSysMetadata {
+            name: name.clone(),
+            created_at: now,
+            updated_at: now,
+            source: "stock".to_string(),
+        },
+    };
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let node = SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: node_type.to_string(),
+        position: folder_import::free_position(&conn)?,
+        width: Some(NODE_WIDTH),
+        height: None,
+        parent_id: None,
+        extent: None,
+        style: None,
+        data: SynniaNodeData {
+            title: name,
+            asset_id: Some(asset.id.clone()),
+            ..Default::default()
+        },
+    };
+    io_sqlite::upsert_node(&conn, &node)?;
+    let license = licenses::set_license(&conn, &asset.id, Some(&photo.license()))?
+        .expect("a license was given");
+
+    let summary = format!("Imported a {} photo by {}", result.provider.label(), result.author);
+    activity::record(&conn, &NewActivity::new(ActivityKind::Imported, "user", summary).source(result.provider.key()).target(&asset.id))?;
+    Ok(StockImport { asset, node, license })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn parse_id(id: &str) -> Result<(StockProvider, &str), AppError> {
+    let invalid = || AppError::InvalidInput(format!("Not a stock result ID: {}", id));
+    let (provider, photo_id) = id.split_once(':').ok_or_else(invalid)?;
+    let provider = match provider {
+        "unsplash" => StockProvider::Unsplash,
+        "pexels" => StockProvider::Pexels,
+        _ => return Err(invalid()),
+    };
+    if photo_id.is_empty() || !photo_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(invalid());
+    }
+    Ok((provider, photo_id))
+}
+
+fn parse_photo(provider: StockProvider, photo: &Value) -> Option<StockPhoto> {
+    let text = |value: Option<&Value>| value.and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+    let size = |key: &str| photo.get(key).and_then(Value::as_u64).and_then(|n| u32::try_from(n).ok());
+
+    match provider {
+        StockProvider::Unsplash => {
+            let urls = photo.get("urls")?;
+            let url = |key: &str| text(urls.get(key));
+            Some(StockPhoto {
+                result: StockResult {
+                    id: format!("unsplash:{}", text(photo.get("id"))?),
+                    provider,
+                    description: text(photo.get("description")).or_else(|| text(photo.get("alt_description"))),
+                    width: size("width")?,
+                    height: size("height")?,
+                    thumbnail_url: url("thumb")?,
+                    preview_url: url("small")?,
+                    author: text(photo.pointer("/user/name"))?,
+                    author_url: text(photo.pointer("/user/links/html")),
+                    page_url: text(photo.pointer("/links/html"))?,
+                },
+                urls: [url("small")?, url("regular")?, url("full")?, url("raw")?],
+                download_location: text(photo.pointer("/links/download_location")),
+            })
+        }
+        StockProvider::Pexels => {
+            let src = photo.get("src")?;
+            let url = |key: &str| text(src.get(key));
+            Some(StockPhoto {
+                result: StockResult {
+                    id: format!("pexels:{}", photo.get("id")?.as_u64()?),
+                    provider,
+                    description: text(photo.get("alt")),
+                    width: size("width")?,
+                    height: size("height")?,
+                    thumbnail_url: url("tiny")?,
+                    preview_url: url("medium")?,
+                    author: text(photo.get("photographer"))?,
+                    author_url: text(photo.get("photographer_url")),
+                    page_url: text(photo.get("url"))?,
+                },
+                urls: [url("medium")?, url("large")?, url("large2x")?, url("original")?],
+                download_location: None,
+            })
+        }
+    }
+}
+
+async fn get_json(
+    policy: &NetworkPolicy,
+    provider: StockProvider,
+    credentials: &ProviderCredentials,
+    path: &str,
+    params: &[(&str, String)],
+) -> Result<Value, AppError> {
+    let base = credentials.base_url.as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .unwrap_or(provider.base_url())
+        .trim_end_matches('/');
+    let url = Url::parse_with_params(&format!("{}/{}", base, path), params)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid {} URL: {}", provider.label(), e)))?;
+    let url = policy.check_url(url.as_str()).await?;
+
+    let response = authorize(client(policy)?.get(url), provider, credentials)?
+        .send().await
+        .map_err(|e| {
+            if e.is_timeout() {
+                AppError::Network(format!("{} did not answer within {}s", provider.label(), REQUEST_TIMEOUT.as_secs()))
+            } else {
+                AppError::Network(format!("Could not reach {}: {}", provider.label(), e))
+            }
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        let message = match status.as_u16() {
+            401 | 403 => "the API key was rejected",
+            404 => "photo not found",
+            429 => "rate limited; try again later",
+            _ => "unexpected response",
+        };
+        return Err(AppError::Network(format!("HTTP {}: {}", status.as_u16(), message)));
+    }
+    response.json().await
+        .map_err(|e| AppError::Network(format!("Unexpected response from {}: {}", provider.label(), e)))
+}
+
+fn client(policy: &NetworkPolicy) -> Result<reqwest::Client, AppError> {
+    policy.client_builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))
+}
+
+fn authorize(
+    request: reqwest::RequestBuilder,
+    provider: StockProvider,
+    credentials: &ProviderCredentials,
+) -> Result<reqwest::RequestBuilder, AppError> {
+    let key = credentials.api_key.as_deref().map(str::trim).filter(|k| !k.is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("Add a {} API key in Settings", provider.label())))?;
+    Ok(match provider {
+        StockProvider::Unsplash => request.header("Authorization", format!("Client-ID {}", key)).header("Accept-Version", "v1"),
+        StockProvider::Pexels => request.header("Authorization", key),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn unsplash_photo() -> Value {
+        json!({
+            "id": "Ab-12",
+            "width": 4000,
+            "height": 3000,
+            "description": null,
+            "alt_description": "a red car at night",
+            "urls": { "raw": "https://u/raw", "full": "https://u/full", "regular": "https://u/regular", "small": "https://u/small", "thumb": "https://u/thumb" },
+            "links": { "html": "https://unsplash.com/photos/Ab-12", "download_location": "https://api.unsplash.com/photos/Ab-12/download" },
+            "user": { "name": "Jane Doe", "links": { "html": "https://unsplash.com/@jane" } },
+        })
+    }
+
+    #[test]
+    fn test_parse_photos() {
+        let photo = parse_photo(StockProvider::Unsplash, &unsplash_photo()).unwrap();
+        assert_eq!(photo.result.id, "unsplash:Ab-12");
+        assert_eq!(photo.result.description.as_deref(), Some("a red car at night"));
+        assert_eq!(photo.url(StockSize::Medium), "https://u/regular");
+        assert_eq!(photo.url(StockSize::Original), "https://u/raw");
+        assert_eq!(photo.license().attribution.as_deref(), Some("Photo by Jane Doe on Unsplash"));
+
+        let pexels = json!({
+            "id": 2014422,
+            "width": 3024,
+            "height": 3024,
+            "url": "https://www.pexels.com/photo/2014422/",
+            "photographer": "Joey Farina",
+            "photographer_url": "https://www.pexels.com/@joey",
+            "alt": "",
+            "src": { "original": "https://p/original", "large2x": "https://p/large2x", "large": "https://p/large", "medium": "https://p/medium", "tiny": "https://p/tiny" },
+        });
+        let photo = parse_photo(StockProvider::Pexels, &pexels).unwrap();
+        assert_eq!(photo.result.id, "pexels:2014422");
+        assert_eq!(photo.result.description, None);
+        assert_eq!(photo.url(StockSize::Small), "https://p/medium");
+        assert!(photo.download_location.is_none());
+
+        assert!(parse_photo(StockProvider::Pexels, &json!({ "id": 1 })).is_none());
+        assert!(parse_id("unsplash:Ab-12").is_ok());
+        assert!(parse_id("pexels:../../x").is_err());
+        assert!(parse_id("flickr:1").is_err());
+    }
+
+    #[test]
+    fn test_import_records_license() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let photo = parse_photo(StockProvider::Unsplash, &unsplash_photo()).unwrap();
+
+        let import = import(dir.path(), &photo, b"not an image").unwrap();
+        assert_eq!(import.asset.sys.source, "stock");
+        assert_eq!(import.node.data.asset_id.as_deref(), Some(import.asset.id.as_str()));
+        let license = licenses::get_license(&conn, &import.asset.id).unwrap().unwrap();
+        assert_eq!(license.license_type, "Unsplash License");
+        assert_eq!(license.source_url.as_deref(), Some("https://unsplash.com/photos/Ab-12"));
+    }
+}
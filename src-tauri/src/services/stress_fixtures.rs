@@ -0,0 +1,307 @@
+//! Synthetic large projects for performance work (debug builds only).
+//!
+//! `generate` writes a project shaped like a real busy board: image and text
+//! assets stored the way imports store them, nodes in grid groups with some
+//! of them referencing already-used assets, and edges chaining nodes within
+//! and across groups. It is saved through `io_sqlite::save_project_sqlite`,
+//! so the result exercises the same load, save, search and layout paths as a
+//! user's project. Content is seeded, so equal arguments give equal boards.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::{
+    Asset, AssetSysMetadata, Graph, Position, SynniaEdge, SynniaNode, SynniaNodeData, ValueType,
+};
+use crate::services::{clip, compaction, io_sqlite};
+use ts_rs::TS;
+
+/// Upper bounds, so a typo can't fill the disk
+pub const MAX_NODES: usize = 100_000;
+pub const MAX_ASSETS: usize = 20_000;
+pub const MIN_IMAGE_SIZE: u32 = 8;
+pub const MAX_IMAGE_SIZE: u32 = 4096;
+
+/// Content nodes per group
+const GROUP_SIZE: usize = 24;
+const GROUP_COLUMNS: usize = 6;
+/// Groups per row of the board
+const BOARD_COLUMNS: usize = 8;
+const CELL_WIDTH: f64 = 240.0;
+const CELL_HEIGHT: f64 = 260.0;
+const GROUP_PADDING: f64 = 40.0;
+const GROUP_GAP: f64 = 200.0;
+/// Every this many assets is a text asset; the rest are images
+const TEXT_ASSET_EVERY: usize = 4;
+
+const WORDS: &[&str] = &[
+    "moodboard", "storyboard", "neon", "harbor", "portrait", "dusk", "palette", "texture",
+    "concept", "forest", "chrome", "sketch", "lantern", "poster", "desert", "velvet",
+    "camera", "studio", "glacier", "market", "orbit", "brutalist", "pastel", "ember",
+];
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedProject {
+    pub path: String,
+    /// Content nodes, groups not included
+    pub nodes: usize,
+    pub groups: usize,
+    pub edges: usize,
+    pub assets: usize,
+    /// Database and asset files on disk
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+/// Generate a project at `project_root` with `nodes` content nodes showing
+/// `assets` assets; images are `size` pixels square. `project_root` must not
+/// hold a project already.
+pub fn generate(project_root: &Path, nodes: usize, assets: usize, size: u32) -> Result<GeneratedProject, AppError> {
+    if nodes > MAX_NODES || assets > MAX_ASSETS {
+        return Err(AppError::InvalidInput(format!(
+            "At most {} nodes and {} assets", MAX_NODES, MAX_ASSETS
+        )));
+    }
+    if !(MIN_IMAGE_SIZE..=MAX_IMAGE_SIZE).contains(&size) {
+        return Err(AppError::InvalidInput(format!(
+            "Image size must be between {} and {}", MIN_IMAGE_SIZE, MAX_IMAGE_SIZE
+        )));
+    }
+    if io_sqlite::is_sqlite_project(project_root) {
+        return Err(AppError::InvalidInput(format!(
+            "A project already exists at {}", project_root.display()
+        )));
+    }
+
+    let name = format!("Stress test ({} nodes, {} assets)", nodes, assets);
+    let mut project = io_sqlite::init_project_sqlite(project_root, &name)?;
+    let mut rng = Rng(0x5eed_0000 ^ ((nodes as u64) << 20) ^ assets as u64);
+
+    let mut generated = Vec::with_capacity(assets);
+    for i in 0..assets {
+        generated.push(make_asset(project_root, i, size, &mut rng)?);
+    }
+
+    let mut graph = Graph { nodes: Vec::new(), edges: Vec::new() };
+    let groups = nodes.div_ceil(GROUP_SIZE);
+    let mut previous_group_last: Option<String> = None;
+    for g in 0..groups {
+        let group = group_node(g);
+        let members = GROUP_SIZE.min(nodes - g * GROUP_SIZE);
+        graph.nodes.push(group.clone());
+
+        let mut previous: Option<String> = previous_group_last.take();
+        for m in 0..members {
+            let index = g * GROUP_SIZE + m;
+            let node = content_node(index, m, &group.id, generated.get(index % assets.max(1)), index >= assets);
+            if let Some(source) = &previous {
+                // Chain roughly a third of the nodes, and always across groups
+                if m == 0 || rng.below(3) == 0 {
+                    graph.edges.push(edge(source, &node.id));
+                }
+            }
+            previous = Some(node.id.clone());
+            graph.nodes.push(node);
+        }
+        previous_group_last = previous;
+    }
+
+    let edges = graph.edges.len();
+    project.graph = graph;
+    project.assets = generated.into_iter()
+        .map(|(asset, _)| (asset.id.clone(), asset))
+        .collect::<HashMap<_, _>>();
+    io_sqlite::save_project_sqlite(project_root, &project)?;
+
+    Ok(GeneratedProject {
+        path: project_root.to_string_lossy().to_string(),
+        nodes,
+        groups,
+        edges,
+        assets,
+        bytes: compaction::db_size(&io_sqlite::get_db_path(project_root)) + dir_size(&project_root.join("assets")),
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Deterministic xorshift generator; fixtures don't need real randomness
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| WORDS[self.below(WORDS.len() as u64) as usize])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn make_asset(project_root: &Path, index: usize, size: u32, rng: &mut Rng) -> Result<(Asset, &'static str), AppError> {
+    let title = format!("{} {}", rng.words(2), index + 1);
+    let (value, value_meta, node_type) = if index % TEXT_ASSET_EVERY == TEXT_ASSET_EVERY - 1 {
+        let sentences = 3 + rng.below(12) as usize;
+        let text = (0..sentences)
+            .map(|_| {
+                let count = 6 + rng.below(10) as usize;
+                format!("{}.", rng.words(count))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        (
+            serde_json::json!({ "content": format!("# {}\n\n{}", title, text), "format": "markdown" }),
+            Some(serde_json::json!({ "length": text.chars().count() })),
+            "text-node",
+        )
+    } else {
+        clip::store_file(project_root, &format!("{}.png", title), &gradient_png(size, rng.next())?)?
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let asset = Asset {
+        id: uuid::Uuid::new_v4().to_string(),
+        value_type: ValueType::Record,
+        value,
+        value_meta,
+        config: Some(serde_json::json!({ "schema": [] })),
+        sys: AssetSysMetadata {
+            name: title,
+            created_at: now,
+            updated_at: now,
+            source: "fixture".to_string(),
+        },
+    };
+    Ok((asset, node_type))
+}
+
+/// A diagonal two-colour gradient, so images differ and don't compress away
+fn gradient_png(size: u32, seed: u64) -> Result<Vec<u8>, AppError> {
+    let from = [(seed >> 8) as u8, (seed >> 16) as u8, (seed >> 24) as u8];
+    let to = [(seed >> 32) as u8, (seed >> 40) as u8, (seed >> 48) as u8];
+    let image = image::RgbImage::from_fn(size, size, |x, y| {
+        let t = (x + y) as f32 / (2 * size) as f32;
+        image::Rgb(std::array::from_fn(|c| {
+            (from[c] as f32 * (1.0 - t) + to[c] as f32 * t) as u8 ^ ((x * y) as u8 & 0x0f)
+        }))
+    });
+    let mut data = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)
+        .map_err(|e| AppError::Unknown(format!("Failed to encode image: {}", e)))?;
+    Ok(data)
+}
+
+fn group_node(index: usize) -> SynniaNode {
+    let rows = GROUP_SIZE.div_ceil(GROUP_COLUMNS);
+    let width = GROUP_COLUMNS as f64 * CELL_WIDTH + 2.0 * GROUP_PADDING;
+    let height = rows as f64 * CELL_HEIGHT + 2.0 * GROUP_PADDING;
+    SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: "group".to_string(),
+        position: Position {
+            x: (index % BOARD_COLUMNS) as f64 * (width + GROUP_GAP),
+            y: (index / BOARD_COLUMNS) as f64 * (height + GROUP_GAP),
+        },
+        width: Some(width),
+        height: Some(height),
+        parent_id: None,
+        extent: None,
+        style: None,
+        data: SynniaNodeData {
+            title: format!("Group {}", index + 1),
+            layout_mode: Some("grid".to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+fn content_node(
+    index: usize,
+    slot: usize,
+    group_id: &str,
+    asset: Option<&(Asset, &'static str)>,
+    is_reference: bool,
+) -> SynniaNode {
+    SynniaNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        type_: asset.map_or("text-node", |(_, node_type)| node_type).to_string(),
+        position: Position {
+            x: GROUP_PADDING + (slot % GROUP_COLUMNS) as f64 * CELL_WIDTH,
+            y: GROUP_PADDING + (slot / GROUP_COLUMNS) as f64 * CELL_HEIGHT,
+        },
+        width: Some(CELL_WIDTH - GROUP_PADDING),
+        height: None,
+        parent_id: Some(group_id.to_string()),
+        extent: Some("parent".to_string()),
+        style: None,
+        data: SynniaNodeData {
+            title: asset.map_or_else(|| format!("Node {}", index + 1), |(a, _)| a.sys.name.clone()),
+            asset_id: asset.map(|(a, _)| a.id.clone()),
+            is_reference: (asset.is_some() && is_reference).then_some(true),
+            ..Default::default()
+        },
+    }
+}
+
+fn edge(source: &str, target: &str) -> SynniaEdge {
+    SynniaEdge {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: source.to_string(),
+        target: target.to_string(),
+        source_handle: None,
+        target_handle: None,
+        type_: None,
+        label: None,
+        animated: None,
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().filter_map(|e| e.metadata().ok()).map(|m| m.len()).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generate_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("stress");
+
+        let report = generate(&root, 60, 20, 16).unwrap();
+        assert_eq!((report.nodes, report.groups, report.assets), (60, 3, 20));
+        assert!(report.edges >= 2, "groups are always chained");
+        assert!(report.bytes > 0);
+
+        let project = io_sqlite::load_project_sqlite(&root).unwrap();
+        assert_eq!(project.graph.nodes.len(), 63);
+        assert_eq!(project.graph.edges.len(), report.edges);
+        assert_eq!(project.assets.len(), 20);
+        let references = project.graph.nodes.iter().filter(|n| n.data.is_reference == Some(true)).count();
+        assert_eq!(references, 40);
+        let texts = project.assets.values().filter(|a| a.value.get("format").is_some()).count();
+        assert_eq!(texts, 5);
+
+        assert!(matches!(generate(&root, 1, 1, 16), Err(AppError::InvalidInput(_))));
+        assert!(matches!(generate(&dir.path().join("big"), 1, 1, 1), Err(AppError::InvalidInput(_))));
+    }
+}
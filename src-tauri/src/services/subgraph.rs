@@ -0,0 +1,336 @@
+//! Spin a subgraph out into a new standalone project.
+//!
+//! The chosen nodes (plus everything nested inside chosen groups), the assets
+//! they use, the edges between them and the files those assets reference are
+//! copied to a fresh project. Nodes whose parent was left behind are detached
+//! and keep their absolute board position. Custom field definitions and the
+//! copied items' values always come along; asset history and generation
+//! records when asked.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use rusqlite::params;
+use serde::Serialize;
+use crate::error::AppError;
+use crate::models::{Position, SynniaNode, SynniaProject};
+use crate::services::{compaction, database, io_sqlite};
+use crate::services::cancellation::CancellationToken;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphExport {
+    /// Root of the new project
+    pub path: String,
+    pub nodes: usize,
+    pub edges: usize,
+    pub assets: usize,
+    pub files: usize,
+}
+
+/// Copy `node_ids` (and their descendants) from `project` at `source_root`
+/// into a new project at `dest`, which must not exist or be empty. A
+/// cancelled export leaves `dest` empty again.
+pub fn export_subgraph(
+    source_root: &Path,
+    project: &SynniaProject,
+    node_ids: &[String],
+    dest: &Path,
+    include_history: bool,
+    cancel: &CancellationToken,
+) -> Result<SubgraphExport, AppError> {
+    if node_ids.is_empty() {
+        return Err(AppError::Unknown("No nodes selected".to_string()));
+    }
+    if let Some(missing) = node_ids.iter().find(|id| !project.graph.nodes.iter().any(|n| &n.id == *id)) {
+        return Err(AppError::NotFound(format!("Node not found: {}", missing)));
+    }
+    if dest.exists() && fs::read_dir(dest)?.next().is_some() {
+        return Err(AppError::Unknown(format!("Destination is not empty: {}", dest.display())));
+    }
+    
+    let selected = with_descendants(project, node_ids);
+    let by_id: HashMap<&str, &SynniaNode> = project.graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    
+    // Keep the source's node order so parents still precede their children
+    let nodes: Vec<SynniaNode> = project.graph.nodes.iter()
+        .filter(|n| selected.contains(n.id.as_str()))
+        .map(|node| {
+            let mut node = node.clone();
+            if node.parent_id.as_deref().is_some_and(|p| !selected.contains(p)) {
+                node.position = absolute_position(&node, &by_id);
+                node.parent_id = None;
+                node.extent = None;
+            }
+            node
+        })
+        .collect();
+    let edges: Vec<_> = project.graph.edges.iter()
+        .filter(|e| selected.contains(e.source.as_str()) && selected.contains(e.target.as_str()))
+        .cloned()
+        .collect();
+    let assets: HashMap<_, _> = nodes.iter()
+        .filter_map(|n| n.data.asset_id.as_ref())
+        .filter_map(|id| project.assets.get(id).map(|a| (id.clone(), a.clone())))
+        .collect();
+    
+    let name = dest.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Untitled Project".to_string());
+    cancel.check()?;
+    let existed = dest.exists();
+    let written = (|| {
+        let mut spun_out = io_sqlite::init_project_sqlite(dest, &name)?;
+        spun_out.viewport = project.viewport.clone();
+        spun_out.settings = project.settings.clone();
+        spun_out.graph.nodes = nodes;
+        spun_out.graph.edges = edges;
+        spun_out.assets = assets;
+        io_sqlite::save_project_sqlite(dest, &spun_out)?;
+        
+        let asset_ids: Vec<&String> = spun_out.assets.keys().collect();
+        let target_ids: Vec<&String> = spun_out.graph.nodes.iter().map(|n| &n.id).chain(asset_ids.iter().copied()).collect();
+        copy_custom_fields(source_root, dest, &target_ids)?;
+        if include_history {
+            cancel.check()?;
+            copy_history(source_root, dest, &asset_ids)?;
+        }
+        let files = copy_files(source_root, dest, &spun_out, include_history, cancel)?;
+        Ok((spun_out, files))
+    })();
+    let (spun_out, files) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            if cancel.is_cancelled() {
+                let _ = fs::remove_dir_all(dest);
+                if existed {
+                    let _ = fs::create_dir(dest);
+                }
+            }
+            return Err(e);
+        }
+    };
+    
+    Ok(SubgraphExport {
+        path: dest.to_string_lossy().into_owned(),
+        nodes: spun_out.graph.nodes.len(),
+        edges: spun_out.graph.edges.len(),
+        assets: spun_out.assets.len(),
+        files,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn with_descendants<'a>(project: &'a SynniaProject, node_ids: &'a [String]) -> HashSet<&'a str> {
+    let mut selected: HashSet<&str> = node_ids.iter().map(String::as_str).collect();
+    loop {
+        let before = selected.len();
+        for node in &project.graph.nodes {
+            if node.parent_id.as_deref().is_some_and(|p| selected.contains(p)) {
+                selected.insert(node.id.as_str());
+            }
+        }
+        if selected.len() == before {
+            return selected;
+        }
+    }
+}
+
+/// Child positions are relative to their parent; sum up the chain
+fn absolute_position(node: &SynniaNode, by_id: &HashMap<&str, &SynniaNode>) -> Position {
+    let mut position = node.position.clone();
+    let mut parent = node.parent_id.as_deref();
+    let mut depth = 0;
+    while let Some(parent_node) = parent.and_then(|id| by_id.get(id)) {
+        position.x += parent_node.position.x;
+        position.y += parent_node.position.y;
+        parent = parent_node.parent_id.as_deref();
+        depth += 1;
+        // Guard against parent cycles in corrupt data
+        if depth > by_id.len() {
+            break;
+        }
+    }
+    position
+}
+
+fn copy_history(source_root: &Path, dest: &Path, asset_ids: &[&String]) -> Result<(), AppError> {
+    let mut conn = database::open_db(&io_sqlite::get_db_path(dest))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let source_db = io_sqlite::get_db_path(source_root);
+    conn.execute("ATTACH DATABASE ?1 AS source", params![source_db.to_string_lossy()])
+        .map_err(|e| AppError::Io(format!("Failed to attach source project: {}", e)))?;
+    
+    let ids = serde_json::to_string(asset_ids)?;
+    let result = conn.transaction().and_then(|tx| {
+        tx.execute(
+            "INSERT OR IGNORE INTO main.asset_history (asset_id, content_hash, content_json, created_at)
+             SELECT asset_id, content_hash, content_json, created_at FROM source.asset_history
+             WHERE asset_id IN (SELECT value FROM json_each(?1))",
+            params![ids],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO main.generation_records
+             SELECT * FROM source.generation_records WHERE asset_id IN (SELECT value FROM json_each(?1))",
+            params![ids],
+        )?;
+        tx.commit()
+    });
+    let _ = conn.execute_batch("DETACH DATABASE source;");
+    result.map_err(|e| AppError::Io(format!("Failed to copy history: {}", e)))
+}
+
+fn copy_custom_fields(source_root: &Path, dest: &Path, target_ids: &[&String]) -> Result<(), AppError> {
+    let mut conn = database::open_db(&io_sqlite::get_db_path(dest))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let source_db = io_sqlite::get_db_path(source_root);
+    conn.execute("ATTACH DATABASE ?1 AS source", params![source_db.to_string_lossy()])
+        .map_err(|e| AppError::Io(format!("Failed to attach source project: {}", e)))?;
+    
+    let ids = serde_json::to_string(target_ids)?;
+    let result = conn.transaction().and_then(|tx| {
+        tx.execute("INSERT OR REPLACE INTO main.custom_fields SELECT * FROM source.custom_fields", [])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO main.custom_field_values
+             SELECT * FROM source.custom_field_values WHERE target_id IN (SELECT value FROM json_each(?1))",
+            params![ids],
+        )?;
+        tx.commit()
+    });
+    let _ = conn.execute_batch("DETACH DATABASE source;");
+    result.map_err(|e| AppError::Io(format!("Failed to copy custom fields: {}", e)))
+}
+
+/// Copy files under `assets/` referenced by the exported assets (and their
+/// history, if copied). Returns the number of files copied.
+fn copy_files(
+    source_root: &Path,
+    dest: &Path,
+    project: &SynniaProject,
+    include_history: bool,
+    cancel: &CancellationToken,
+) -> Result<usize, AppError> {
+    let mut referenced = HashSet::new();
+    for asset in project.assets.values() {
+        compaction::collect_asset_paths(&asset.value.to_string(), &mut referenced);
+        if let Some(meta) = &asset.value_meta {
+            compaction::collect_asset_paths(&meta.to_string(), &mut referenced);
+        }
+    }
+    if include_history {
+        let conn = database::open_db(&io_sqlite::get_db_path(dest))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        let mut stmt = conn.prepare("SELECT content_json FROM asset_history")
+            .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| AppError::Io(format!("Failed to query history: {}", e)))?;
+        for content in rows.flatten() {
+            compaction::collect_asset_paths(&content, &mut referenced);
+        }
+    }
+    
+    let source_assets = source_root.join("assets");
+    let dest_assets = dest.join("assets");
+    fs::create_dir_all(&dest_assets)?;
+    
+    let mut copied = 0;
+    if source_assets.is_dir() {
+        for entry in fs::read_dir(&source_assets)?.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_file() && compaction::is_referenced(&name, &referenced) {
+                cancel.check()?;
+                fs::copy(entry.path(), dest_assets.join(&name))?;
+                copied += 1;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, SynniaEdge, SynniaNodeData, ValueType};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn node(id: &str, parent: Option<&str>, x: f64, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "image-node".to_string(),
+            position: Position { x, y: 10.0 },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: parent.map(|_| "parent".to_string()),
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> SynniaEdge {
+        SynniaEdge {
+            id: format!("{}-{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            type_: None,
+            label: None,
+            animated: None,
+        }
+    }
+
+    #[test]
+    fn test_export_subgraph() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("Brainstorm");
+        let mut project = io_sqlite::init_project_sqlite(&source, "Brainstorm").unwrap();
+        fs::write(source.join("assets/hero.png"), b"png").unwrap();
+        fs::write(source.join("assets/other.png"), b"png").unwrap();
+        project.assets.insert("hero".to_string(), Asset {
+            id: "hero".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/hero.png" }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Hero".to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        });
+        project.graph.nodes = vec![
+            node("board", None, 100.0, None),
+            node("concept", Some("board"), 20.0, None),
+            node("hero", Some("concept"), 5.0, Some("hero")),
+            node("elsewhere", None, 0.0, None),
+        ];
+        project.graph.edges = vec![edge("concept", "hero"), edge("hero", "elsewhere")];
+        io_sqlite::save_project_sqlite(&source, &project).unwrap();
+        
+        let dest = dir.path().join("Concept");
+        let result = export_subgraph(&source, &project, &["concept".to_string()], &dest, true, &CancellationToken::new()).unwrap();
+        assert_eq!((result.nodes, result.edges, result.assets, result.files), (2, 1, 1, 1));
+        
+        let spun_out = io_sqlite::load_project_sqlite(&dest).unwrap();
+        let concept = spun_out.graph.nodes.iter().find(|n| n.id == "concept").unwrap();
+        assert_eq!(concept.parent_id, None);
+        assert_eq!(concept.position.x, 120.0, "detached node keeps its absolute position");
+        assert!(dest.join("assets/hero.png").exists());
+        assert!(!dest.join("assets/other.png").exists());
+        
+        assert!(export_subgraph(&source, &project, &["concept".to_string()], &dest, false, &CancellationToken::new()).is_err(), "destination must be empty");
+        
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let skipped = dir.path().join("Skipped");
+        assert!(export_subgraph(&source, &project, &["concept".to_string()], &skipped, false, &cancelled).is_err());
+        assert!(!skipped.exists());
+    }
+}
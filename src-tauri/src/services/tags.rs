@@ -0,0 +1,189 @@
+//! Asset tags: a searchable index of user-added and machine-generated tags.
+//!
+//! Tags are normalized (lowercase, single spaces) and unique per asset; each
+//! remembers its source so a tagger can replace its own tags without
+//! touching the user's.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
+use crate::error::AppError;
+use crate::models::AssetTag;
+use crate::services::custom_fields::{self, FieldFilter};
+use crate::services::licenses::{self, LicenseFilter};
+
+/// Source of tags added by hand
+pub const USER_SOURCE: &str = "user";
+
+const MAX_TAG_LEN: usize = 64;
+
+/// A tag and the confidence of the model that produced it
+pub type ScoredTag = (String, Option<f64>);
+
+/// Lowercase, trim and collapse whitespace. `None` for empty or overlong tags.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let tag = tag.trim_matches(|c: char| c == ',' || c == '.' || c == ';').trim().to_string();
+    (!tag.is_empty() && tag.chars().count() <= MAX_TAG_LEN).then_some(tag)
+}
+
+/// Replace the tags `source` put on an asset. A tag already present from
+/// another source keeps that source. Returns all of the asset's tags.
+pub fn set_tags(
+    conn: &Connection,
+    asset_id: &str,
+    source: &str,
+    tags: &[ScoredTag],
+) -> Result<Vec<AssetTag>, AppError> {
+    conn.execute(
+        "DELETE FROM asset_tags WHERE asset_id = ?1 AND source = ?2",
+        params![asset_id, source],
+    ).map_err(|e| AppError::Io(format!("Failed to clear tags: {}", e)))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    for (tag, confidence) in tags {
+        let Some(tag) = normalize_tag(tag) else { continue };
+        conn.execute(
+            "INSERT OR IGNORE INTO asset_tags (asset_id, tag, source, confidence, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![asset_id, tag, source, confidence, now],
+        ).map_err(|e| AppError::Io(format!("Failed to save tag: {}", e)))?;
+    }
+    
+    get_tags(conn, asset_id)
+}
+
+/// Tags of an asset, most confident first (user tags count as certain).
+pub fn get_tags(conn: &Connection, asset_id: &str) -> Result<Vec<AssetTag>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT asset_id, tag, source, confidence, created_at FROM asset_tags
+         WHERE asset_id = ?1 ORDER BY COALESCE(confidence, 1.0) DESC, tag"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params![asset_id], row_to_tag)
+        .map_err(|e| AppError::Io(format!("Failed to query tags: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load tags: {}", e)))
+}
+
+/// IDs of assets with a tag starting with every word group of `query`
+/// (comma-separated, e.g. "red car, night") and matching every custom field
+/// filter and the license filter, most recently updated first. With
+/// filters, an empty `query` matches any tags.
+pub fn search(
+    conn: &Connection,
+    query: &str,
+    fields: &[FieldFilter],
+    license: Option<&LicenseFilter>,
+    limit: usize,
+) -> Result<Vec<String>, AppError> {
+    let mut params = Vec::new();
+    let mut conditions = query_conditions(query, "a.id", &mut params);
+    for filter in fields {
+        conditions.push(custom_fields::filter_condition(conn, filter, "a.id", &mut params)?);
+    }
+    conditions.extend(license.and_then(|l| licenses::filter_condition(l, "a.id", &mut params)));
+    if conditions.is_empty() {
+        return Ok(Vec::new());
+    }
+    let conditions = conditions.join(" AND ");
+    let sql = format!(
+        "SELECT a.id FROM assets a WHERE {} ORDER BY a.updated_at DESC LIMIT {}",
+        conditions, limit
+    );
+    
+    let mut stmt = conn.prepare(&sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params_from_iter(params.iter()), |row| row.get(0))
+        .map_err(|e| AppError::Io(format!("Failed to search tags: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load search results: {}", e)))
+}
+
+/// SQL conditions requiring a tag starting with each word group of `query`
+/// on the asset whose ID is `id_column`, with their parameters appended to
+/// `params` (numbered after the ones already there).
+pub fn query_conditions(query: &str, id_column: &str, params: &mut Vec<SqlValue>) -> Vec<String> {
+    query.split(',')
+        .filter_map(normalize_tag)
+        .map(|t| {
+            params.push(SqlValue::Text(format!("{}%", t.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))));
+            format!(
+                "EXISTS (SELECT 1 FROM asset_tags t WHERE t.asset_id = {} AND t.tag LIKE ?{} ESCAPE '\\')",
+                id_column, params.len()
+            )
+        })
+        .collect()
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn row_to_tag(row: &rusqlite::Row) -> rusqlite::Result<AssetTag> {
+    Ok(AssetTag {
+        asset_id: row.get(0)?,
+        tag: row.get(1)?,
+        source: row.get(2)?,
+        confidence: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    fn tags(list: &[&str]) -> Vec<ScoredTag> {
+        list.iter().map(|t| (t.to_string(), Some(0.8))).collect()
+    }
+
+    #[test]
+    fn test_tags_and_search() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        for (id, updated) in [("a", 1), ("b", 2)] {
+            conn.execute(
+                "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+                 VALUES (?1, 'record', '', '{}', '{}', ?2)",
+                params![id, updated],
+            ).unwrap();
+        }
+        
+        assert_eq!(normalize_tag("  Red   Car, "), Some("red car".to_string()));
+        assert_eq!(normalize_tag(" , "), None);
+        
+        set_tags(&conn, "a", USER_SOURCE, &[("Favorite".to_string(), None)]).unwrap();
+        set_tags(&conn, "a", "interrogate", &tags(&["red car", "night", "favorite"])).unwrap();
+        set_tags(&conn, "b", "interrogate", &tags(&["red dress", "studio_light"])).unwrap();
+        
+        let a = get_tags(&conn, "a").unwrap();
+        assert_eq!(a.len(), 3);
+        assert_eq!((a[0].tag.as_str(), a[0].source.as_str()), ("favorite", "user"));
+        
+        assert_eq!(search(&conn, "red", &[], None, 10).unwrap(), vec!["b", "a"]);
+        assert_eq!(search(&conn, "red, nig", &[], None, 10).unwrap(), vec!["a"]);
+        assert!(search(&conn, "studio%", &[], None, 10).unwrap().is_empty(), "wildcards are literal");
+        assert_eq!(search(&conn, "studio_", &[], None, 10).unwrap(), vec!["b"]);
+        
+        // Custom field filters narrow the tag search, or stand in for it
+        let client = custom_fields::save_field(&conn, &custom_fields::CustomFieldInput {
+            id: None,
+            name: "Client".to_string(),
+            kind: custom_fields::FieldKind::Text,
+            options: Vec::new(),
+        }).unwrap();
+        custom_fields::set_value(&conn, custom_fields::FieldTarget::Asset, "a", &client.id, &serde_json::json!("Acme")).unwrap();
+        let acme = [FieldFilter { field_id: client.id, op: custom_fields::FilterOp::Equals, value: serde_json::json!("Acme") }];
+        assert_eq!(search(&conn, "red", &acme, None, 10).unwrap(), vec!["a"]);
+        assert_eq!(search(&conn, "", &acme, None, 10).unwrap(), vec!["a"]);
+        
+        // Re-tagging replaces only the tagger's own tags
+        set_tags(&conn, "a", "interrogate", &tags(&["sunset"])).unwrap();
+        let a: Vec<String> = get_tags(&conn, "a").unwrap().into_iter().map(|t| t.tag).collect();
+        assert_eq!(a, vec!["favorite", "sunset"]);
+    }
+}
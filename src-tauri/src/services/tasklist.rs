@@ -0,0 +1,249 @@
+//! Task/checklist assets with progress rollup for production tracking.
+//!
+//! A task list is an array asset with `config.mode = "tasklist"` whose items
+//! are `{ text, done }` records (other item fields are kept as-is). The
+//! completion count is stored in `valueMeta` as `{ length, done }` and rolled
+//! up across everything nested inside a group by `group_progress`.
+
+use std::collections::HashSet;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::{Asset, SynniaProject, ValueType};
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+/// Array asset mode for task lists
+pub const TASKLIST_MODE: &str = "tasklist";
+
+/// Completion of one task list on the board
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskListProgress {
+    pub node_id: String,
+    pub asset_id: String,
+    pub title: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupProgress {
+    pub group_node_id: String,
+    pub done: usize,
+    pub total: usize,
+    /// 0..100, `None` when the group has no tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f64>,
+    pub lists: Vec<TaskListProgress>,
+}
+
+/// Whether an asset is an array asset in tasklist mode
+pub fn is_tasklist(asset: &Asset) -> bool {
+    asset.value_type == ValueType::Array
+        && asset.config.as_ref()
+            .and_then(|c| c.get("mode"))
+            .and_then(|m| m.as_str())
+            == Some(TASKLIST_MODE)
+}
+
+/// `(done, total)` for a task list value
+pub fn count_tasks(value: &Value) -> (usize, usize) {
+    let items = value.as_array().map(Vec::as_slice).unwrap_or(&[]);
+    let done = items.iter().filter(|item| is_done(item)).count();
+    (done, items.len())
+}
+
+/// Flip the `done` flag of one task and save the asset.
+pub fn toggle_task(project_root: &Path, asset_id: &str, index: usize) -> Result<Asset, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let mut asset = io_sqlite::get_asset(&conn, asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+    if !is_tasklist(&asset) {
+        return Err(AppError::Unknown(format!("Asset {} is not a task list", asset_id)));
+    }
+    
+    let item = asset.value.as_array_mut()
+        .and_then(|items| items.get_mut(index))
+        .ok_or_else(|| AppError::NotFound(format!("Task {} not found in {}", index, asset_id)))?;
+    let done = !is_done(item);
+    match item {
+        Value::Object(obj) => {
+            obj.insert("done".to_string(), Value::Bool(done));
+        }
+        // Bare strings are upgraded to task records
+        other => {
+            let text = other.as_str().unwrap_or_default().to_string();
+            *other = json!({ "text": text, "done": done });
+        }
+    }
+    
+    asset.value_meta = Some(summarize(&asset));
+    asset.sys.updated_at = chrono::Utc::now().timestamp_millis();
+    
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    Ok(asset)
+}
+
+/// Aggregate task completion across a group and everything nested in it.
+pub fn group_progress(project: &SynniaProject, group_node_id: &str) -> Result<GroupProgress, AppError> {
+    if !project.graph.nodes.iter().any(|n| n.id == group_node_id) {
+        return Err(AppError::NotFound(format!("Node not found: {}", group_node_id)));
+    }
+    
+    let mut members: HashSet<&str> = HashSet::from([group_node_id]);
+    loop {
+        let before = members.len();
+        for node in &project.graph.nodes {
+            if node.parent_id.as_deref().is_some_and(|p| members.contains(p)) {
+                members.insert(node.id.as_str());
+            }
+        }
+        if members.len() == before {
+            break;
+        }
+    }
+    
+    let mut lists = Vec::new();
+    let mut seen_assets = HashSet::new();
+    for node in project.graph.nodes.iter().filter(|n| members.contains(n.id.as_str())) {
+        let Some(asset) = node.data.asset_id.as_ref().and_then(|id| project.assets.get(id)) else { continue };
+        // Shared assets (references) count once
+        if !is_tasklist(asset) || !seen_assets.insert(asset.id.as_str()) {
+            continue;
+        }
+        let (done, total) = count_tasks(&asset.value);
+        lists.push(TaskListProgress {
+            node_id: node.id.clone(),
+            asset_id: asset.id.clone(),
+            title: node.data.title.clone(),
+            done,
+            total,
+        });
+    }
+    
+    let done: usize = lists.iter().map(|l| l.done).sum();
+    let total: usize = lists.iter().map(|l| l.total).sum();
+    Ok(GroupProgress {
+        group_node_id: group_node_id.to_string(),
+        done,
+        total,
+        percent: (total > 0).then(|| done as f64 * 100.0 / total as f64),
+        lists,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn is_done(item: &Value) -> bool {
+    item.get("done").and_then(|d| d.as_bool()).unwrap_or(false)
+}
+
+fn summarize(asset: &Asset) -> Value {
+    let (done, total) = count_tasks(&asset.value);
+    let mut meta = asset.value_meta.clone()
+        .filter(|m| m.is_object())
+        .unwrap_or_else(|| json!({}));
+    meta["length"] = json!(total);
+    meta["done"] = json!(done);
+    meta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, Position, SynniaNode, SynniaNodeData};
+    use crate::services::io_sqlite::init_project_sqlite;
+    use tempfile::tempdir;
+
+    fn tasklist(id: &str, items: Value) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Array,
+            value: items,
+            value_meta: None,
+            config: Some(json!({ "mode": TASKLIST_MODE })),
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    fn node(id: &str, parent: Option<&str>, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "table-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_toggle_task() {
+        let dir = tempdir().unwrap();
+        init_project_sqlite(dir.path(), "Tasks").unwrap();
+        let list = tasklist("shots", json!([
+            { "text": "Storyboard", "done": true },
+            "Shoot",
+        ]));
+        io_sqlite::save_asset_with_history(dir.path(), &list).unwrap();
+        
+        let updated = toggle_task(dir.path(), "shots", 1).unwrap();
+        assert_eq!(updated.value[1], json!({ "text": "Shoot", "done": true }));
+        assert_eq!(updated.value_meta.as_ref().unwrap()["done"], 2);
+        
+        let updated = toggle_task(dir.path(), "shots", 0).unwrap();
+        assert_eq!(updated.value[0]["done"], false);
+        assert!(toggle_task(dir.path(), "shots", 5).is_err());
+    }
+
+    #[test]
+    fn test_group_progress() {
+        let dir = tempdir().unwrap();
+        let mut project = init_project_sqlite(dir.path(), "Tasks").unwrap();
+        project.graph.nodes = vec![
+            node("episode", None, None),
+            node("prep", Some("episode"), Some("a")),
+            node("scene", Some("episode"), None),
+            node("scene-tasks", Some("scene"), Some("b")),
+            node("scene-tasks-ref", Some("scene"), Some("b")),
+            node("outside", None, Some("c")),
+        ];
+        for asset in [
+            tasklist("a", json!([{ "text": "1", "done": true }, { "text": "2" }])),
+            tasklist("b", json!([{ "text": "3", "done": true }, { "text": "4", "done": true }])),
+            tasklist("c", json!([{ "text": "5" }])),
+        ] {
+            project.assets.insert(asset.id.clone(), asset);
+        }
+        
+        let progress = group_progress(&project, "episode").unwrap();
+        assert_eq!((progress.done, progress.total), (3, 4));
+        assert_eq!(progress.percent, Some(75.0));
+        assert_eq!(progress.lists.len(), 2);
+        
+        let prep = group_progress(&project, "prep").unwrap();
+        assert_eq!((prep.done, prep.total), (1, 2), "a node with a task list counts as its own group");
+        assert!(group_progress(&project, "missing").is_err());
+    }
+}
@@ -0,0 +1,378 @@
+//! Template gallery sync: pull a team's starting kit (project templates,
+//! components and agents) from a remote index into the local stores.
+//!
+//! The index is a JSON file listing each entry's kind, ID, version, URL
+//! (absolute or relative to the index) and SHA-256. Every download is
+//! checked against its checksum and parsed before it is written. What was
+//! installed is recorded in a manifest next to the stores, so a later sync
+//! only downloads entries whose checksum changed, and leaves alone files
+//! the user has edited since they were synced.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::models::{AgentDefinition, ComponentDefinition, SynniaProject};
+use crate::services::hash::compute_binary_hash;
+use crate::services::net_guard::NetworkPolicy;
+use ts_rs::TS;
+
+/// `format` of every index file
+pub const INDEX_FORMAT: &str = "synnia-templates";
+
+/// Largest index and entry files accepted
+const MAX_INDEX_BYTES: usize = 1024 * 1024;
+const MAX_ENTRY_BYTES: usize = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateKind {
+    /// A starter project (`SynniaProject` JSON)
+    Template,
+    Component,
+    Agent,
+}
+
+impl TemplateKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TemplateKind::Template => "template",
+            TemplateKind::Component => "component",
+            TemplateKind::Agent => "agent",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateIndex {
+    pub format: String,
+    #[serde(default)]
+    pub entries: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub kind: TemplateKind,
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Absolute, or relative to the index URL
+    pub url: String,
+    pub sha256: String,
+}
+
+/// An installed entry, as recorded in the manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedEntry {
+    pub version: Option<String>,
+    pub sha256: String,
+    /// Index the entry came from
+    pub source: String,
+    pub synced_at: String,
+}
+
+/// What a sync did (or, for a dry run, would do) with an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSyncStatus {
+    Added,
+    Updated,
+    Unchanged,
+    /// Edited locally since it was synced (or never synced); kept as is
+    Modified,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSyncItem {
+    pub kind: TemplateKind,
+    pub id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub status: TemplateSyncStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSyncReport {
+    pub source: String,
+    /// Nothing was downloaded or written
+    pub dry_run: bool,
+    pub items: Vec<TemplateSyncItem>,
+}
+
+/// The local stores entries are installed into
+#[derive(Debug, Clone)]
+pub struct TemplateStores {
+    pub templates: PathBuf,
+    pub components: PathBuf,
+    pub agents: PathBuf,
+    /// The sync manifest
+    pub manifest: PathBuf,
+}
+
+impl TemplateStores {
+    /// File an entry of `kind` with `id` is installed as
+    pub fn path(&self, kind: TemplateKind, id: &str) -> PathBuf {
+        let dir = match kind {
+            TemplateKind::Template => &self.templates,
+            TemplateKind::Component => &self.components,
+            TemplateKind::Agent => &self.agents,
+        };
+        let safe_id: String = id.chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-').collect();
+        dir.join(format!("{}.json", safe_id))
+    }
+}
+
+/// Installed entries by `kind:id`
+pub type Manifest = HashMap<String, SyncedEntry>;
+
+pub fn load_manifest(path: &Path) -> Manifest {
+    fs::read_to_string(path).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn parse_index(data: &[u8]) -> Result<TemplateIndex, AppError> {
+    let index: TemplateIndex = serde_json::from_slice(data)
+        .map_err(|e| AppError::InvalidInput(format!("Not a template index: {}", e)))?;
+    if index.format != INDEX_FORMAT {
+        return Err(AppError::InvalidInput(format!("Not a template index: format is '{}'", index.format)));
+    }
+    for entry in &index.entries {
+        if entry.id.trim().is_empty() {
+            return Err(AppError::InvalidInput("Index entry without an ID".to_string()));
+        }
+        if entry.sha256.len() != 64 || !entry.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AppError::InvalidInput(format!("Index entry {} has no valid SHA-256", entry.id)));
+        }
+    }
+    Ok(index)
+}
+
+/// Compare an entry with what is installed: `Added` and `Updated` need a
+/// download, everything else is left alone.
+pub fn plan(stores: &TemplateStores, manifest: &Manifest, entry: &IndexEntry) -> TemplateSyncStatus {
+    let wanted = entry.sha256.to_lowercase();
+    let local = fs::read(stores.path(entry.kind, &entry.id)).ok().map(|data| compute_binary_hash(&data));
+    let synced = manifest.get(&manifest_key(entry.kind, &entry.id)).map(|s| s.sha256.as_str());
+
+    match (local, synced) {
+        (None, _) => TemplateSyncStatus::Added,
+        (Some(local), _) if local == wanted => TemplateSyncStatus::Unchanged,
+        (Some(local), Some(synced)) if local == synced => TemplateSyncStatus::Updated,
+        (Some(_), _) => TemplateSyncStatus::Modified,
+    }
+}
+
+/// Verify a downloaded entry against its checksum and kind, then write it
+/// to its store and record it in `manifest`.
+pub fn install(
+    stores: &TemplateStores,
+    manifest: &mut Manifest,
+    entry: &IndexEntry,
+    source: &str,
+    data: &[u8],
+) -> Result<(), AppError> {
+    let actual = compute_binary_hash(data);
+    if actual != entry.sha256.to_lowercase() {
+        return Err(AppError::InvalidInput(format!(
+            "Checksum mismatch for {}: expected {}, got {}", entry.id, entry.sha256, actual
+        )));
+    }
+
+    let parse_error = |e: serde_json::Error| AppError::InvalidInput(format!("Not a valid {}: {}", entry.kind.as_str(), e));
+    let id = match entry.kind {
+        TemplateKind::Template => serde_json::from_slice::<SynniaProject>(data).map_err(parse_error)?.meta.id,
+        TemplateKind::Component => serde_json::from_slice::<ComponentDefinition>(data).map_err(parse_error)?.id,
+        TemplateKind::Agent => serde_json::from_slice::<AgentDefinition>(data).map_err(parse_error)?.id,
+    };
+    if entry.kind != TemplateKind::Template && id != entry.id {
+        return Err(AppError::InvalidInput(format!("Index entry {} holds {} {}", entry.id, entry.kind.as_str(), id)));
+    }
+
+    let path = stores.path(entry.kind, &entry.id);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, data)?;
+    manifest.insert(manifest_key(entry.kind, &entry.id), SyncedEntry {
+        version: entry.version.clone(),
+        sha256: actual,
+        source: source.to_string(),
+        synced_at: chrono::Utc::now().to_rfc3339(),
+    });
+    Ok(())
+}
+
+/// Fetch the index at `url` and bring the stores up to date with it. With
+/// `dry_run` only the index is downloaded, to report what would change.
+pub async fn sync(policy: &NetworkPolicy, url: &str, stores: &TemplateStores, dry_run: bool) -> Result<TemplateSyncReport, AppError> {
+    let client = policy.client_builder()
+        .timeout(policy.timeouts.request())
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let index_url = policy.check_url(url).await?;
+    let index = parse_index(&fetch(&client, policy, index_url.as_str(), MAX_INDEX_BYTES).await?)?;
+
+    let mut manifest = load_manifest(&stores.manifest);
+    let mut items = Vec::new();
+    for entry in &index.entries {
+        let mut status = plan(stores, &manifest, entry);
+        let mut error = None;
+        if !dry_run && matches!(status, TemplateSyncStatus::Added | TemplateSyncStatus::Updated) {
+            let result = match index_url.join(&entry.url) {
+                Ok(entry_url) => match fetch(&client, policy, entry_url.as_str(), MAX_ENTRY_BYTES).await {
+                    Ok(data) => install(stores, &mut manifest, entry, index_url.as_str(), &data),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(AppError::InvalidInput(format!("Invalid URL '{}': {}", entry.url, e))),
+            };
+            if let Err(e) = result {
+                status = TemplateSyncStatus::Failed;
+                error = Some(e.to_string());
+            }
+        }
+        items.push(TemplateSyncItem {
+            kind: entry.kind,
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            status,
+            error,
+        });
+    }
+
+    if !dry_run {
+        if let Some(dir) = stores.manifest.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&stores.manifest, serde_json::to_string_pretty(&manifest)?)?;
+    }
+    Ok(TemplateSyncReport { source: index_url.to_string(), dry_run, items })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn manifest_key(kind: TemplateKind, id: &str) -> String {
+    format!("{}:{}", kind.as_str(), id)
+}
+
+async fn fetch(client: &reqwest::Client, policy: &NetworkPolicy, url: &str, max_bytes: usize) -> Result<Vec<u8>, AppError> {
+    let url = policy.check_url(url).await?;
+    let response = client.get(url.clone()).send().await
+        .map_err(|e| AppError::Network(format!("Failed to fetch {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!("Failed to fetch {}: HTTP {}", url, response.status().as_u16())));
+    }
+    if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+        return Err(AppError::InvalidInput(format!("{} is larger than {} bytes", url, max_bytes)));
+    }
+    let data = response.bytes().await
+        .map_err(|e| AppError::Network(format!("Failed to fetch {}: {}", url, e)))?;
+    if data.len() > max_bytes {
+        return Err(AppError::InvalidInput(format!("{} is larger than {} bytes", url, max_bytes)));
+    }
+    Ok(data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn stores(root: &Path) -> TemplateStores {
+        TemplateStores {
+            templates: root.join("Templates"),
+            components: root.join("Components"),
+            agents: root.join("Agents"),
+            manifest: root.join("template-sync.json"),
+        }
+    }
+
+    fn agent(prompt: &str) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "id": "writer",
+            "name": "Writer",
+            "systemPrompt": prompt,
+            "inputSchema": "{}",
+            "isSystem": false,
+        })).unwrap()
+    }
+
+    fn entry(data: &[u8]) -> IndexEntry {
+        IndexEntry {
+            kind: TemplateKind::Agent,
+            id: "writer".to_string(),
+            name: None,
+            version: None,
+            url: "agents/writer.json".to_string(),
+            sha256: compute_binary_hash(data),
+        }
+    }
+
+    #[test]
+    fn test_install_and_update_detection() {
+        let dir = tempdir().unwrap();
+        let stores = stores(dir.path());
+        let mut manifest = Manifest::new();
+
+        let v1 = agent("Write.");
+        let e1 = entry(&v1);
+        assert_eq!(plan(&stores, &manifest, &e1), TemplateSyncStatus::Added);
+        install(&stores, &mut manifest, &e1, "https://example.com/index.json", &v1).unwrap();
+        assert_eq!(plan(&stores, &manifest, &e1), TemplateSyncStatus::Unchanged);
+
+        let v2 = agent("Write well.");
+        let e2 = entry(&v2);
+        assert_eq!(plan(&stores, &manifest, &e2), TemplateSyncStatus::Updated);
+
+        // Local edits are kept
+        fs::write(stores.path(TemplateKind::Agent, "writer"), agent("Mine.")).unwrap();
+        assert_eq!(plan(&stores, &manifest, &e2), TemplateSyncStatus::Modified);
+    }
+
+    #[test]
+    fn test_install_rejects_bad_downloads() {
+        let dir = tempdir().unwrap();
+        let stores = stores(dir.path());
+        let mut manifest = Manifest::new();
+
+        let data = agent("Write.");
+        let mut tampered = entry(&data);
+        tampered.sha256 = compute_binary_hash(b"something else");
+        assert!(install(&stores, &mut manifest, &tampered, "src", &data).is_err());
+
+        let not_a_component = IndexEntry { kind: TemplateKind::Component, ..entry(&data) };
+        assert!(install(&stores, &mut manifest, &not_a_component, "src", &data).is_err());
+        assert!(manifest.is_empty());
+        assert!(!stores.path(TemplateKind::Agent, "writer").exists());
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let index = json!({
+            "format": INDEX_FORMAT,
+            "entries": [{ "kind": "agent", "id": "writer", "url": "a.json", "sha256": "0".repeat(64) }],
+        });
+        assert_eq!(parse_index(&serde_json::to_vec(&index).unwrap()).unwrap().entries.len(), 1);
+        assert!(parse_index(br#"{ "format": "other", "entries": [] }"#).is_err());
+        let bad = json!({ "format": INDEX_FORMAT, "entries": [{ "kind": "agent", "id": "x", "url": "a", "sha256": "abc" }] });
+        assert!(parse_index(&serde_json::to_vec(&bad).unwrap()).is_err());
+    }
+}
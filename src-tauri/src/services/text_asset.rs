@@ -0,0 +1,40 @@
+//! Helpers for text assets: record assets whose value is
+//! `{ content: string, format: "plain" | "markdown" }`.
+//!
+//! Image and clip assets share the `content` field but use
+//! `format: "file" | "url"`, so those are not treated as text.
+
+use crate::models::{Asset, ValueType};
+
+/// Formats whose `content` is a path or URL rather than prose
+const NON_TEXT_FORMATS: &[&str] = &["file", "url"];
+
+/// Text of a text asset, or `None` for any other kind of asset.
+pub fn text_content(asset: &Asset) -> Option<&str> {
+    if asset.value_type != ValueType::Record {
+        return None;
+    }
+    let format = text_format(asset);
+    if NON_TEXT_FORMATS.contains(&format) {
+        return None;
+    }
+    asset.value.get("content").and_then(|c| c.as_str())
+}
+
+/// `format` of a text asset, defaulting to "plain".
+pub fn text_format(asset: &Asset) -> &str {
+    asset.value.get("format").and_then(|f| f.as_str()).unwrap_or("plain")
+}
+
+/// Replace the text of a text asset, keeping its other value fields.
+pub fn set_text_content(asset: &mut Asset, content: String) {
+    match asset.value.as_object_mut() {
+        Some(value) => {
+            value.insert("content".to_string(), serde_json::Value::String(content));
+        }
+        None => {
+            asset.value = serde_json::json!({ "content": content, "format": "plain" });
+        }
+    }
+    asset.sys.updated_at = chrono::Utc::now().timestamp_millis();
+}
@@ -0,0 +1,151 @@
+//! Thumbnail regeneration, run as a local job after the project's thumbnail
+//! settings change or when thumbnail files went missing.
+//!
+//! Thumbnails are named after the image file (`thumb_<stem>.jpg`), the same
+//! name compaction and the minimap look for.
+
+use std::path::Path;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{imaging, io_sqlite};
+use crate::services::batch_import::IMAGE_EXTENSIONS;
+use crate::services::imaging::{ThumbnailSettings, THUMBNAIL_SETTINGS_KEY};
+
+pub const JOB_KIND: &str = "regenerate-thumbnails";
+
+/// Parameters of one regeneration task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailTask {
+    pub asset_id: String,
+    /// Image file relative to the project root
+    pub src: String,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<ThumbnailSettings, AppError> {
+    Ok(io_sqlite::get_setting(conn, THUMBNAIL_SETTINGS_KEY)?
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Store thumbnail settings (clamped to usable values). Returns what was stored.
+pub fn save_settings(conn: &Connection, settings: &ThumbnailSettings) -> Result<ThumbnailSettings, AppError> {
+    let settings = settings.normalized();
+    io_sqlite::set_setting(conn, THUMBNAIL_SETTINGS_KEY, &serde_json::to_value(settings)?)?;
+    Ok(settings)
+}
+
+/// Image assets whose thumbnail needs (re)generating: all of them with
+/// `force`, otherwise those whose thumbnail file is missing.
+pub fn pending_tasks(conn: &Connection, project_root: &Path, force: bool) -> Result<Vec<ThumbnailTask>, AppError> {
+    let mut tasks: Vec<ThumbnailTask> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .filter_map(|asset| {
+            let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+            let path = imaging::resolve_image_source(project_root, src)?;
+            if !has_image_extension(&path) || !path.is_file() {
+                return None;
+            }
+            if !force && thumbnail_path(&path).is_some_and(|t| t.exists()) {
+                return None;
+            }
+            let src = path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/");
+            Some(ThumbnailTask { asset_id: asset.id, src })
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.src.cmp(&b.src));
+    Ok(tasks)
+}
+
+/// Regenerate one thumbnail; returns its relative path. The error is the
+/// message shown for the asset.
+pub fn regenerate(project_root: &Path, task: &ThumbnailTask, settings: &ThumbnailSettings) -> Result<String, String> {
+    let path = project_root.join(&task.src);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Not an image file: {}", task.src))?;
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read image: {}", e))?;
+    imaging::generate_thumbnail_with(project_root, &stem, &data, settings).map_err(|e| e.to_string())
+}
+
+/// Point the assets' `valueMeta.preview` at their regenerated thumbnails.
+/// Returns the number of assets updated.
+pub fn record_previews(conn: &Connection, previews: &[(String, String)]) -> Result<usize, AppError> {
+    let mut updated = 0;
+    for (asset_id, preview) in previews {
+        updated += conn.execute(
+            "UPDATE assets SET value_meta_json = json_set(COALESCE(value_meta_json, '{}'), '$.preview', ?1)
+             WHERE id = ?2 AND json_extract(COALESCE(value_meta_json, '{}'), '$.preview') IS NOT ?1",
+            params![preview, asset_id],
+        ).map_err(|e| AppError::Io(format!("Failed to update asset preview: {}", e)))?;
+    }
+    Ok(updated)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn thumbnail_path(path: &Path) -> Option<std::path::PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    Some(path.with_file_name(format!("thumb_{}.jpg", stem)))
+}
+
+fn has_image_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use crate::services::database;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_regenerate_thumbnails() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Photos").unwrap();
+        let mut png = Vec::new();
+        image::RgbaImage::new(800, 400)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        for id in ["a", "b"] {
+            std::fs::write(root.join(format!("assets/{}.png", id)), &png).unwrap();
+            project.assets.insert(id.to_string(), Asset {
+                id: id.to_string(),
+                value_type: ValueType::Record,
+                value: json!({ "src": format!("assets/{}.png", id) }),
+                value_meta: None,
+                config: None,
+                sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+            });
+        }
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        std::fs::write(root.join("assets/thumb_a.jpg"), b"old").unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let settings = save_settings(&conn, &ThumbnailSettings { size: 5000, quality: 90 }).unwrap();
+        assert_eq!(settings.size, 2048);
+        assert_eq!(get_settings(&conn).unwrap(), settings);
+        
+        // Only the missing thumbnail unless forced
+        let missing = pending_tasks(&conn, root, false).unwrap();
+        assert_eq!(missing.iter().map(|t| t.asset_id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        let all = pending_tasks(&conn, root, true).unwrap();
+        assert_eq!(all.len(), 2);
+        
+        let small = ThumbnailSettings { size: 100, quality: 50 };
+        let thumb = regenerate(root, &all[0], &small).unwrap();
+        assert_eq!(thumb, "assets/thumb_a.jpg");
+        let data = std::fs::read(root.join(&thumb)).unwrap();
+        assert_eq!(imaging::get_image_dimensions(&data).unwrap(), (100, 50));
+        
+        assert_eq!(record_previews(&conn, &[("a".to_string(), thumb.clone())]).unwrap(), 1);
+        assert_eq!(record_previews(&conn, &[("a".to_string(), thumb)]).unwrap(), 0);
+    }
+}
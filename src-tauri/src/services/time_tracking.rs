@@ -0,0 +1,322 @@
+//! Time tracking per project: how long a project was open, and how long the
+//! Synnia window had focus while it was, for billing exploration time.
+//!
+//! Sessions are rows in the project's `sessions` table. `SessionTracker`
+//! starts and stops them on project load/close and window focus/blur;
+//! `get_time_report` aggregates them per day, week or month (local time).
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::{Datelike, Duration, NaiveDate, TimeZone};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+const KIND_OPEN: &str = "open";
+const KIND_FOCUS: &str = "focus";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum TimePeriod {
+    #[default]
+    Day,
+    /// ISO weeks, starting Monday
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeBucket {
+    /// "2025-03-14" for days and weeks (week start), "2025-03" for months
+    pub label: String,
+    /// Unix timestamp (ms) of the bucket start
+    #[ts(type = "number")]
+    pub start: i64,
+    #[ts(type = "number")]
+    pub open_ms: i64,
+    #[ts(type = "number")]
+    pub focus_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeReport {
+    pub period: TimePeriod,
+    pub buckets: Vec<TimeBucket>,
+    #[ts(type = "number")]
+    pub total_open_ms: i64,
+    #[ts(type = "number")]
+    pub total_focus_ms: i64,
+}
+
+/// Tracks the sessions of the currently open project
+#[derive(Default)]
+pub struct SessionTracker {
+    active: Mutex<Option<ActiveSessions>>,
+}
+
+struct ActiveSessions {
+    project_root: PathBuf,
+    open_id: i64,
+    focus_id: Option<i64>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a project (ends the previous project's sessions).
+    /// The window is assumed focused, since opening a project is user action.
+    pub fn project_opened(&self, project_root: &Path) {
+        self.stop();
+        let result = with_db(project_root, |conn| {
+            close_dangling(conn)?;
+            let now = now_ms();
+            Ok(ActiveSessions {
+                project_root: project_root.to_path_buf(),
+                open_id: start_session(conn, KIND_OPEN, now)?,
+                focus_id: Some(start_session(conn, KIND_FOCUS, now)?),
+            })
+        });
+        match result {
+            Ok(sessions) => {
+                if let Ok(mut active) = self.active.lock() {
+                    *active = Some(sessions);
+                }
+            }
+            Err(e) => println!("Failed to start session: {}", e),
+        }
+    }
+
+    /// Window focus changed
+    pub fn set_focused(&self, focused: bool) {
+        let Ok(mut active) = self.active.lock() else { return };
+        let Some(sessions) = active.as_mut() else { return };
+        if focused == sessions.focus_id.is_some() {
+            return;
+        }
+        let result = with_db(&sessions.project_root, |conn| {
+            match sessions.focus_id {
+                Some(id) => end_session(conn, id, now_ms()).map(|_| None),
+                None => start_session(conn, KIND_FOCUS, now_ms()).map(Some),
+            }
+        });
+        match result {
+            Ok(focus_id) => sessions.focus_id = focus_id,
+            Err(e) => println!("Failed to update focus session: {}", e),
+        }
+    }
+
+    /// End all sessions (project closed or app exiting)
+    pub fn stop(&self) {
+        let Ok(mut active) = self.active.lock() else { return };
+        let Some(sessions) = active.take() else { return };
+        let result = with_db(&sessions.project_root, |conn| {
+            let now = now_ms();
+            if let Some(id) = sessions.focus_id {
+                end_session(conn, id, now)?;
+            }
+            end_session(conn, sessions.open_id, now)
+        });
+        if let Err(e) = result {
+            println!("Failed to end session: {}", e);
+        }
+    }
+}
+
+/// Open and focus time per period, optionally limited to `since..until` (ms).
+pub fn get_time_report(conn: &Connection, period: TimePeriod, since: Option<i64>, until: Option<i64>) -> Result<TimeReport, AppError> {
+    build_report(conn, period, since, until, &chrono::Local)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn with_db<T>(project_root: &Path, f: impl FnOnce(&Connection) -> Result<T, AppError>) -> Result<T, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    f(&conn)
+}
+
+fn start_session(conn: &Connection, kind: &str, now: i64) -> Result<i64, AppError> {
+    conn.execute("INSERT INTO sessions (kind, started_at) VALUES (?1, ?2)", params![kind, now])
+        .map_err(|e| AppError::Io(format!("Failed to start session: {}", e)))?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn end_session(conn: &Connection, id: i64, now: i64) -> Result<(), AppError> {
+    conn.execute("UPDATE sessions SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL", params![now, id])
+        .map_err(|e| AppError::Io(format!("Failed to end session: {}", e)))?;
+    Ok(())
+}
+
+/// Sessions left open by a crash have no reliable end; count them as empty
+fn close_dangling(conn: &Connection) -> Result<(), AppError> {
+    conn.execute("UPDATE sessions SET ended_at = started_at WHERE ended_at IS NULL", [])
+        .map_err(|e| AppError::Io(format!("Failed to close sessions: {}", e)))?;
+    Ok(())
+}
+
+fn build_report<Tz: TimeZone>(
+    conn: &Connection,
+    period: TimePeriod,
+    since: Option<i64>,
+    until: Option<i64>,
+    tz: &Tz,
+) -> Result<TimeReport, AppError> {
+    let now = now_ms();
+    let mut stmt = conn.prepare(
+        "SELECT kind, started_at, COALESCE(ended_at, ?1) FROM sessions
+         WHERE (?2 IS NULL OR COALESCE(ended_at, ?1) > ?2) AND (?3 IS NULL OR started_at < ?3)
+         ORDER BY started_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map(params![now, since, until], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+    }).map_err(|e| AppError::Io(format!("Failed to query sessions: {}", e)))?;
+    let sessions = rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load sessions: {}", e)))?;
+    
+    let mut buckets: Vec<TimeBucket> = Vec::new();
+    for (kind, start, end) in sessions {
+        let start = since.map_or(start, |s| start.max(s));
+        let end = until.map_or(end, |u| end.min(u));
+        
+        // Split the session at bucket boundaries
+        let mut cursor = start;
+        while cursor < end {
+            let (bucket_start, label) = bucket_of(cursor, period, tz);
+            let bucket_end = next_bucket(bucket_start, period, tz);
+            let slice = end.min(bucket_end) - cursor;
+            
+            let bucket = match buckets.iter_mut().find(|b| b.start == bucket_start) {
+                Some(bucket) => bucket,
+                None => {
+                    buckets.push(TimeBucket { label, start: bucket_start, open_ms: 0, focus_ms: 0 });
+                    buckets.last_mut().unwrap()
+                }
+            };
+            if kind == KIND_FOCUS {
+                bucket.focus_ms += slice;
+            } else {
+                bucket.open_ms += slice;
+            }
+            cursor = bucket_end;
+        }
+    }
+    buckets.sort_by_key(|b| b.start);
+    
+    Ok(TimeReport {
+        period,
+        total_open_ms: buckets.iter().map(|b| b.open_ms).sum(),
+        total_focus_ms: buckets.iter().map(|b| b.focus_ms).sum(),
+        buckets,
+    })
+}
+
+/// Start (ms) and label of the bucket containing `ms`
+fn bucket_of<Tz: TimeZone>(ms: i64, period: TimePeriod, tz: &Tz) -> (i64, String) {
+    let date = tz.timestamp_millis_opt(ms).earliest()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default();
+    let (first_day, label) = match period {
+        TimePeriod::Day => (date, date.format("%Y-%m-%d").to_string()),
+        TimePeriod::Week => {
+            let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            (monday, monday.format("%Y-%m-%d").to_string())
+        }
+        TimePeriod::Month => {
+            let first = date.with_day(1).unwrap_or(date);
+            (first, first.format("%Y-%m").to_string())
+        }
+    };
+    (midnight(first_day, tz), label)
+}
+
+fn next_bucket<Tz: TimeZone>(bucket_start: i64, period: TimePeriod, tz: &Tz) -> i64 {
+    let date = tz.timestamp_millis_opt(bucket_start).earliest()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default();
+    let next = match period {
+        TimePeriod::Day => date + Duration::days(1),
+        TimePeriod::Week => date + Duration::days(7),
+        TimePeriod::Month => {
+            let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+            NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(date + Duration::days(31))
+        }
+    };
+    midnight(next, tz)
+}
+
+fn midnight<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> i64 {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    tz.from_local_datetime(&naive).earliest()
+        .map(|dt| dt.timestamp_millis())
+        // Midnight skipped by a DST jump: fall back to UTC midnight
+        .unwrap_or_else(|| naive.and_utc().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::tempdir;
+    
+    const HOUR: i64 = 3_600_000;
+
+    fn at(date: &str, hour: i64) -> i64 {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        midnight(day, &Utc) + hour * HOUR
+    }
+
+    #[test]
+    fn test_report_splits_sessions_across_days() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        
+        // 22:00 Sunday -> 02:00 Monday, focused for the first hour only
+        let open = start_session(&conn, KIND_OPEN, at("2025-03-09", 22)).unwrap();
+        end_session(&conn, open, at("2025-03-10", 2)).unwrap();
+        let focus = start_session(&conn, KIND_FOCUS, at("2025-03-09", 22)).unwrap();
+        end_session(&conn, focus, at("2025-03-09", 23)).unwrap();
+        
+        let days = build_report(&conn, TimePeriod::Day, None, None, &Utc).unwrap();
+        let summary: Vec<(&str, i64, i64)> = days.buckets.iter()
+            .map(|b| (b.label.as_str(), b.open_ms / HOUR, b.focus_ms / HOUR))
+            .collect();
+        assert_eq!(summary, vec![("2025-03-09", 2, 1), ("2025-03-10", 2, 0)]);
+        assert_eq!(days.total_open_ms, 4 * HOUR);
+        
+        let weeks = build_report(&conn, TimePeriod::Week, None, None, &Utc).unwrap();
+        let labels: Vec<&str> = weeks.buckets.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, vec!["2025-03-03", "2025-03-10"]);
+        
+        let months = build_report(&conn, TimePeriod::Month, Some(at("2025-03-10", 0)), None, &Utc).unwrap();
+        assert_eq!(months.buckets.len(), 1);
+        assert_eq!(months.buckets[0].label, "2025-03");
+        assert_eq!(months.total_open_ms, 2 * HOUR);
+    }
+
+    #[test]
+    fn test_dangling_sessions_are_closed_empty() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        start_session(&conn, KIND_OPEN, at("2025-03-09", 10)).unwrap();
+        close_dangling(&conn).unwrap();
+        
+        let report = build_report(&conn, TimePeriod::Day, None, None, &Utc).unwrap();
+        assert_eq!(report.total_open_ms, 0);
+    }
+}
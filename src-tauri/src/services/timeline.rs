@@ -0,0 +1,214 @@
+//! Project-wide history timeline.
+//!
+//! Merges the activity feed, asset value snapshots and kept image files into
+//! one chronological feed, grouped by the project's open sessions (see
+//! `time_tracking`). Events outside any recorded session are grouped by gaps
+//! of more than `SESSION_GAP_MS`.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use ts_rs::TS;
+
+/// Untracked events further apart than this start a new group
+pub const SESSION_GAP_MS: i64 = 30 * 60 * 1000;
+
+/// Default number of events returned
+const DEFAULT_LIMIT: usize = 500;
+
+/// Time window to load (Unix ms, both ends optional)
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, rename = "HistoryTimelineRange")]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimelineRange {
+    #[ts(type = "number | null")]
+    pub since: Option<i64>,
+    #[ts(type = "number | null")]
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// Where an event comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimelineSource {
+    /// An activity feed entry
+    Activity,
+    /// An asset value snapshot (`get_history_content` / `restore_asset_version`)
+    AssetVersion,
+    /// A kept image file (`restore_file_version`)
+    FileVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    pub source: TimelineSource,
+    /// Row ID within the source
+    #[ts(type = "number")]
+    pub id: i64,
+    /// Activity kind, or "asset-version" / "file-version"
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Node or asset the event is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    pub summary: String,
+    /// Unix timestamp (ms)
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// Events of one working session, newest first
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineSession {
+    /// The recorded session, if the events fall into one
+    #[ts(type = "number | null")]
+    pub session_id: Option<i64>,
+    #[ts(type = "number")]
+    pub started_at: i64,
+    #[ts(type = "number")]
+    pub ended_at: i64,
+    pub events: Vec<TimelineEvent>,
+}
+
+/// The merged feed within `range`, newest session first.
+pub fn get_timeline(conn: &Connection, range: &TimelineRange) -> Result<Vec<TimelineSession>, AppError> {
+    let limit = range.limit.unwrap_or(DEFAULT_LIMIT);
+    let mut events = Vec::new();
+    for (source, sql) in [
+        (TimelineSource::Activity,
+         "SELECT id, kind, actor, target_id, summary, created_at FROM activity
+          WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at <= ?2)
+          ORDER BY created_at DESC LIMIT ?3"),
+        (TimelineSource::AssetVersion,
+         "SELECT h.id, 'asset-version', NULL, h.asset_id,
+                 'Saved a version of ' || COALESCE(json_extract(a.sys_json, '$.name'), 'a deleted asset'), h.created_at
+          FROM asset_history h LEFT JOIN assets a ON a.id = h.asset_id
+          WHERE (?1 IS NULL OR h.created_at >= ?1) AND (?2 IS NULL OR h.created_at <= ?2)
+          ORDER BY h.created_at DESC LIMIT ?3"),
+        (TimelineSource::FileVersion,
+         "SELECT f.id, 'file-version', NULL, f.asset_id,
+                 'Replaced the image of ' || COALESCE(json_extract(a.sys_json, '$.name'), 'a deleted asset'), f.created_at
+          FROM file_history f LEFT JOIN assets a ON a.id = f.asset_id
+          WHERE (?1 IS NULL OR f.created_at >= ?1) AND (?2 IS NULL OR f.created_at <= ?2)
+          ORDER BY f.created_at DESC LIMIT ?3"),
+    ] {
+        let mut stmt = conn.prepare(sql)
+            .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+        let rows = stmt.query_map(params![range.since, range.until, limit as i64], |row| {
+            Ok(TimelineEvent {
+                source,
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                actor: row.get(2)?,
+                target_id: row.get(3)?,
+                summary: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        }).map_err(|e| AppError::Io(format!("Failed to query timeline: {}", e)))?;
+        for event in rows {
+            events.push(event.map_err(|e| AppError::Io(format!("Failed to load timeline: {}", e)))?);
+        }
+    }
+    events.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    events.truncate(limit);
+    
+    let sessions = open_sessions(conn)?;
+    Ok(group(events, &sessions))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Recorded open sessions as (id, start, end); a running one ends now
+fn open_sessions(conn: &Connection) -> Result<Vec<(i64, i64, i64)>, AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut stmt = conn.prepare(
+        "SELECT id, started_at, COALESCE(ended_at, ?1) FROM sessions WHERE kind = 'open' ORDER BY started_at"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![now], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| AppError::Io(format!("Failed to query sessions: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load sessions: {}", e)))
+}
+
+/// Group events (newest first) by session, or by gaps outside sessions
+fn group(events: Vec<TimelineEvent>, sessions: &[(i64, i64, i64)]) -> Vec<TimelineSession> {
+    let mut groups: Vec<TimelineSession> = Vec::new();
+    for event in events {
+        let session = sessions.iter()
+            .find(|(_, start, end)| (*start..=*end).contains(&event.created_at));
+        let session_id = session.map(|(id, _, _)| *id);
+        
+        let joins_last = groups.last().is_some_and(|last| {
+            last.session_id == session_id
+                && (session_id.is_some() || last.started_at - event.created_at <= SESSION_GAP_MS)
+        });
+        match groups.last_mut() {
+            Some(last) if joins_last => {
+                if session_id.is_none() {
+                    last.started_at = event.created_at;
+                }
+                last.events.push(event);
+            }
+            _ => {
+                let (started_at, ended_at) = match session {
+                    Some((_, start, end)) => (*start, *end),
+                    None => (event.created_at, event.created_at),
+                };
+                groups.push(TimelineSession { session_id, started_at, ended_at, events: vec![event] });
+            }
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_timeline_groups_by_session() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&dir.path().join("test.db")).unwrap();
+        let hour = 60 * 60 * 1000;
+        
+        conn.execute("INSERT INTO sessions (kind, started_at, ended_at) VALUES ('open', ?1, ?2)", params![10 * hour, 12 * hour]).unwrap();
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at)
+             VALUES ('a1', '\"Record\"', 'h2', '{}', '{\"name\":\"Poster\"}', 0)",
+            [],
+        ).unwrap();
+        conn.execute("INSERT INTO asset_history (asset_id, content_hash, content_json, created_at) VALUES ('a1', 'h1', '{}', ?1)", params![11 * hour]).unwrap();
+        for (summary, at) in [("Added node", 10 * hour + 1), ("Imported", 2 * hour), ("Imported more", 2 * hour + 1000), ("Old", 0)] {
+            conn.execute(
+                "INSERT INTO activity (kind, actor, summary, created_at) VALUES ('imported', 'user', ?1, ?2)",
+                params![summary, at],
+            ).unwrap();
+        }
+        
+        let timeline = get_timeline(&conn, &TimelineRange::default()).unwrap();
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline[0].session_id, Some(1));
+        assert_eq!(timeline[0].events.len(), 2);
+        assert_eq!(timeline[0].events[0].source, TimelineSource::AssetVersion);
+        assert_eq!(timeline[0].events[0].summary, "Saved a version of Poster");
+        assert_eq!((timeline[1].session_id, timeline[1].events.len()), (None, 2));
+        assert_eq!((timeline[1].started_at, timeline[1].ended_at), (2 * hour, 2 * hour + 1000));
+        assert_eq!(timeline[2].events[0].summary, "Old");
+        
+        let range = TimelineRange { since: Some(hour), until: Some(3 * hour), limit: None };
+        let timeline = get_timeline(&conn, &range).unwrap();
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(timeline[0].events.len(), 2);
+    }
+}
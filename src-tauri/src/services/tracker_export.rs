@@ -0,0 +1,486 @@
+//! Hand-off of curated assets to production trackers: Notion databases and
+//! Airtable tables.
+//!
+//! A mapping names the property (or column) that receives each part of an
+//! asset: its name, its text, its image and any custom fields. Notion
+//! properties are typed, so the database schema is read first and values are
+//! shaped to fit it; Airtable converts values itself (`typecast`). Images
+//! are uploaded as files, not linked, since asset files aren't reachable
+//! from outside. Tokens come from the app settings (`providers.notion` /
+//! `providers.airtable`).
+//!
+//! Assets are sent one record at a time and a failure doesn't stop the rest;
+//! the result lists what went where and what didn't.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use base64::Engine;
+use reqwest::Url;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use crate::error::AppError;
+use crate::services::{content_type, custom_fields, imaging, io_sqlite, text_asset};
+use crate::services::net_guard::NetworkPolicy;
+use ts_rs::TS;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+const AIRTABLE_API: &str = "https://api.airtable.com/v0";
+const AIRTABLE_CONTENT_API: &str = "https://content.airtable.com/v0";
+
+/// Most assets per export
+pub const MAX_ASSETS: usize = 200;
+
+/// Largest image each service accepts in a direct upload
+const NOTION_MAX_IMAGE: u64 = 20 * 1024 * 1024;
+const AIRTABLE_MAX_IMAGE: u64 = 5 * 1024 * 1024;
+
+/// Notion text objects hold 2000 characters, and a property 100 of them
+const NOTION_TEXT_CHUNK: usize = 2000;
+const NOTION_MAX_CHUNKS: usize = 100;
+
+/// Records Airtable creates per request
+const AIRTABLE_BATCH: usize = 10;
+
+/// Pause between requests, inside both services' rate limits
+const REQUEST_GAP: Duration = Duration::from_millis(350);
+
+/// Where each part of an asset goes, by property or column name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportMapping {
+    /// Receives the asset name (in Notion, usually the title property)
+    pub title: String,
+    /// Receives the text of text assets
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Receives the image of image assets (a files / attachment property)
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Custom field ID → property name
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+}
+
+/// An asset that was sent
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedRecord {
+    pub asset_id: String,
+    /// Notion page or Airtable record ID
+    pub record_id: String,
+    pub url: Option<String>,
+}
+
+/// An asset that couldn't be sent
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFailure {
+    pub asset_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerExport {
+    pub records: Vec<ExportedRecord>,
+    pub failed: Vec<ExportFailure>,
+}
+
+/// An asset's parts, ready to send
+#[derive(Debug, Clone)]
+pub struct ExportItem {
+    pub asset_id: String,
+    pub name: String,
+    pub text: Option<String>,
+    pub image: Option<ImageFile>,
+    /// Property name → custom field value
+    pub fields: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageFile {
+    pub name: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Gather the mapped parts of `asset_ids` from the project. Images are
+/// read only when the mapping has an image property.
+pub fn collect(project_root: &Path, conn: &Connection, asset_ids: &[String], mapping: &ExportMapping) -> Result<Vec<ExportItem>, AppError> {
+    if asset_ids.is_empty() {
+        return Err(AppError::InvalidInput("No assets to export".to_string()));
+    }
+    if asset_ids.len() > MAX_ASSETS {
+        return Err(AppError::InvalidInput(format!("At most {} assets per export", MAX_ASSETS)));
+    }
+    if mapping.title.trim().is_empty() {
+        return Err(AppError::InvalidInput("Choose the property that receives asset names".to_string()));
+    }
+
+    let mut items = Vec::with_capacity(asset_ids.len());
+    for asset_id in asset_ids {
+        let asset = io_sqlite::get_asset(conn, asset_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+
+        let image = match (&mapping.image, asset.value.get("src").and_then(Value::as_str)) {
+            (Some(_), Some(src)) => imaging::resolve_image_source(project_root, src)
+                .filter(|path| path.is_file())
+                .map(|path| -> Result<ImageFile, AppError> {
+                    Ok(ImageFile {
+                        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                        mime_type: content_type::detect_file_mime(&path),
+                        data: fs::read(&path)?,
+                    })
+                })
+                .transpose()?,
+            _ => None,
+        };
+
+        let fields = custom_fields::get_values(conn, asset_id)?.into_iter()
+            .filter_map(|value| Some((mapping.fields.get(&value.field_id)?.clone(), value.value)))
+            .filter(|(_, value)| !value.is_null())
+            .collect();
+
+        items.push(ExportItem {
+            asset_id: asset_id.clone(),
+            text: mapping.text.as_ref().and(text_asset::text_content(&asset)).map(String::from),
+            name: asset.sys.name,
+            image,
+            fields,
+        });
+    }
+    Ok(items)
+}
+
+/// Create a page per item in the Notion database `database_id`.
+pub async fn to_notion(
+    policy: &NetworkPolicy,
+    token: &str,
+    database_id: &str,
+    mapping: &ExportMapping,
+    items: Vec<ExportItem>,
+) -> Result<TrackerExport, AppError> {
+    let database_id = notion_id(database_id)?;
+    let client = client(policy)?;
+    let notion = |request: reqwest::RequestBuilder| request.bearer_auth(token).header("Notion-Version", NOTION_VERSION);
+
+    let url = policy.check_url(&format!("{}/databases/{}", NOTION_API, database_id)).await?;
+    let database = send_json(notion(client.get(url)), "Notion").await?;
+    let schema = database.get("properties").cloned().unwrap_or_default();
+
+    let mut export = TrackerExport::default();
+    for item in items {
+        let result = async {
+            let mut properties = notion_properties(&schema, mapping, &item)?;
+            if let (Some(property), Some(image)) = (&mapping.image, &item.image) {
+                check_property(&schema, property, &["files"])?;
+                let upload_id = notion_upload(policy, &client, token, image).await?;
+                properties.insert(property.clone(), json!({
+                    "files": [{ "type": "file_upload", "file_upload": { "id": upload_id }, "name": image.name }],
+                }));
+            }
+            let url = policy.check_url(&format!("{}/pages", NOTION_API)).await?;
+            let body = json!({ "parent": { "database_id": database_id }, "properties": properties });
+            send_json(notion(client.post(url).json(&body)), "Notion").await
+        }.await;
+
+        match result {
+            Ok(page) => export.records.push(ExportedRecord {
+                asset_id: item.asset_id,
+                record_id: page.get("id").and_then(Value::as_str).unwrap_or_default().to_string(),
+                url: page.get("url").and_then(Value::as_str).map(String::from),
+            }),
+            Err(e) => export.failed.push(ExportFailure { asset_id: item.asset_id, error: e.to_string() }),
+        }
+        tokio::time::sleep(REQUEST_GAP).await;
+    }
+    Ok(export)
+}
+
+/// Create a record per item in the Airtable table `table` of base `base_id`.
+pub async fn to_airtable(
+    policy: &NetworkPolicy,
+    token: &str,
+    base_id: &str,
+    table: &str,
+    mapping: &ExportMapping,
+    items: Vec<ExportItem>,
+) -> Result<TrackerExport, AppError> {
+    if !base_id.starts_with("app") || !base_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(AppError::InvalidInput(format!("Not an Airtable base ID: {}", base_id)));
+    }
+    if table.trim().is_empty() {
+        return Err(AppError::InvalidInput("Choose an Airtable table".to_string()));
+    }
+    let client = client(policy)?;
+    let mut table_url = Url::parse(AIRTABLE_API).map_err(|e| AppError::Unknown(e.to_string()))?;
+    table_url.path_segments_mut()
+        .map_err(|_| AppError::Unknown("Invalid Airtable URL".to_string()))?
+        .extend([base_id, table]);
+
+    let mut export = TrackerExport::default();
+    for batch in items.chunks(AIRTABLE_BATCH) {
+        let records: Vec<Value> = batch.iter().map(|item| json!({ "fields": airtable_fields(mapping, item) })).collect();
+        let result = async {
+            let url = policy.check_url(table_url.as_str()).await?;
+            send_json(client.post(url).bearer_auth(token).json(&json!({ "records": records, "typecast": true })), "Airtable").await
+        }.await;
+        let created = match result {
+            Ok(body) => body.get("records").and_then(Value::as_array).cloned().unwrap_or_default(),
+            Err(e) => {
+                export.failed.extend(batch.iter().map(|item| ExportFailure { asset_id: item.asset_id.clone(), error: e.to_string() }));
+                continue;
+            }
+        };
+
+        // Records come back in the order they were sent
+        for (item, record) in batch.iter().zip(created) {
+            let record_id = record.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+            if let (Some(field), Some(image)) = (&mapping.image, &item.image) {
+                tokio::time::sleep(REQUEST_GAP).await;
+                if let Err(e) = airtable_upload(policy, &client, token, base_id, &record_id, field, image).await {
+                    export.failed.push(ExportFailure { asset_id: item.asset_id.clone(), error: format!("Record created, image not attached: {}", e) });
+                }
+            }
+            export.records.push(ExportedRecord { asset_id: item.asset_id.clone(), record_id, url: None });
+        }
+        tokio::time::sleep(REQUEST_GAP).await;
+    }
+    Ok(export)
+}
+
+/// Notion property values for an item's name, text and fields, shaped to
+/// the property types in `schema`.
+pub fn notion_properties(schema: &Value, mapping: &ExportMapping, item: &ExportItem) -> Result<Map<String, Value>, AppError> {
+    let mut properties = Map::new();
+    let mut values: Vec<(&String, Value)> = vec![(&mapping.title, Value::String(item.name.clone()))];
+    if let (Some(property), Some(text)) = (&mapping.text, &item.text) {
+        values.push((property, Value::String(text.clone())));
+    }
+    values.extend(item.fields.iter().map(|(property, value)| (property, value.clone())));
+
+    for (property, value) in values {
+        let kind = check_property(schema, property, &[])?;
+        let text = match &value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let shaped = match kind {
+            "title" => json!({ "title": notion_text(&text) }),
+            "rich_text" => json!({ "rich_text": notion_text(&text) }),
+            "number" => json!({ "number": value.as_f64().or_else(|| text.trim().parse().ok()) }),
+            "select" => json!({ "select": { "name": text } }),
+            "multi_select" => json!({
+                "multi_select": text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|s| json!({ "name": s })).collect::<Vec<_>>(),
+            }),
+            "date" => json!({ "date": { "start": text } }),
+            "checkbox" => json!({ "checkbox": value.as_bool().unwrap_or(matches!(text.as_str(), "true" | "yes" | "1")) }),
+            "url" => json!({ "url": text }),
+            "email" => json!({ "email": text }),
+            "phone_number" => json!({ "phone_number": text }),
+            other => return Err(AppError::InvalidInput(format!("Property '{}' is of type {}, which can't be filled", property, other))),
+        };
+        properties.insert(property.clone(), shaped);
+    }
+    Ok(properties)
+}
+
+/// Airtable fields for an item's name, text and fields
+pub fn airtable_fields(mapping: &ExportMapping, item: &ExportItem) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert(mapping.title.clone(), Value::String(item.name.clone()));
+    if let (Some(field), Some(text)) = (&mapping.text, &item.text) {
+        fields.insert(field.clone(), Value::String(text.clone()));
+    }
+    for (field, value) in &item.fields {
+        fields.insert(field.clone(), value.clone());
+    }
+    fields
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// A Notion ID (32 hex digits, dashes optional) without dashes
+fn notion_id(id: &str) -> Result<String, AppError> {
+    let id: String = id.trim().chars().filter(|c| *c != '-').collect();
+    if id.len() != 32 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput("Not a Notion database ID".to_string()));
+    }
+    Ok(id)
+}
+
+/// Type of `property` in a Notion schema, which must be one of `allowed`
+/// (any when empty)
+fn check_property<'a>(schema: &'a Value, property: &str, allowed: &[&str]) -> Result<&'a str, AppError> {
+    let kind = schema.get(property)
+        .and_then(|p| p.get("type"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| AppError::InvalidInput(format!("The database has no property '{}'", property)))?;
+    if !allowed.is_empty() && !allowed.contains(&kind) {
+        return Err(AppError::InvalidInput(format!("Property '{}' has to be of type {}", property, allowed.join(" or "))));
+    }
+    Ok(kind)
+}
+
+fn notion_text(text: &str) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.chunks(NOTION_TEXT_CHUNK)
+        .take(NOTION_MAX_CHUNKS)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+/// Upload an image to Notion in one part; the upload's ID
+async fn notion_upload(policy: &NetworkPolicy, client: &reqwest::Client, token: &str, image: &ImageFile) -> Result<String, AppError> {
+    if image.data.len() as u64 > NOTION_MAX_IMAGE {
+        return Err(AppError::InvalidInput(format!("{} is over Notion's 20 MB upload limit", image.name)));
+    }
+    let url = policy.check_url(&format!("{}/file_uploads", NOTION_API)).await?;
+    let upload = send_json(
+        client.post(url).bearer_auth(token).header("Notion-Version", NOTION_VERSION)
+            .json(&json!({ "filename": image.name, "content_type": image.mime_type })),
+        "Notion",
+    ).await?;
+    let upload_id = upload.get("id").and_then(Value::as_str)
+        .ok_or_else(|| AppError::Network("Unexpected response from Notion".to_string()))?
+        .to_string();
+
+    let boundary = format!("synnia-{}", uuid::Uuid::new_v4().simple());
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        boundary, image.name.replace(['"', '\r', '\n'], "_"), image.mime_type,
+    ).into_bytes();
+    body.extend_from_slice(&image.data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    let url = policy.check_url(&format!("{}/file_uploads/{}/send", NOTION_API, upload_id)).await?;
+    send_json(
+        client.post(url).bearer_auth(token).header("Notion-Version", NOTION_VERSION)
+            .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+            .body(body),
+        "Notion",
+    ).await?;
+    Ok(upload_id)
+}
+
+async fn airtable_upload(
+    policy: &NetworkPolicy,
+    client: &reqwest::Client,
+    token: &str,
+    base_id: &str,
+    record_id: &str,
+    field: &str,
+    image: &ImageFile,
+) -> Result<(), AppError> {
+    if image.data.len() as u64 > AIRTABLE_MAX_IMAGE {
+        return Err(AppError::InvalidInput(format!("{} is over Airtable's 5 MB upload limit", image.name)));
+    }
+    let mut url = Url::parse(AIRTABLE_CONTENT_API).map_err(|e| AppError::Unknown(e.to_string()))?;
+    url.path_segments_mut()
+        .map_err(|_| AppError::Unknown("Invalid Airtable URL".to_string()))?
+        .extend([base_id, record_id, field, "uploadAttachment"]);
+    let url = policy.check_url(url.as_str()).await?;
+    let body = json!({
+        "contentType": image.mime_type,
+        "filename": image.name,
+        "file": base64::engine::general_purpose::STANDARD.encode(&image.data),
+    });
+    send_json(client.post(url).bearer_auth(token).json(&body), "Airtable").await?;
+    Ok(())
+}
+
+fn client(policy: &NetworkPolicy) -> Result<reqwest::Client, AppError> {
+    policy.client_builder()
+        .timeout(policy.timeouts.request())
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))
+}
+
+/// Send a request and read its JSON body, with the service's own error
+/// message on failure
+async fn send_json(request: reqwest::RequestBuilder, service: &str) -> Result<Value, AppError> {
+    let response = request.send().await
+        .map_err(|e| AppError::Network(format!("Could not reach {}: {}", service, e)))?;
+    let status = response.status().as_u16();
+    let body: Value = response.json().await.unwrap_or_default();
+    if (200..300).contains(&status) {
+        return Ok(body);
+    }
+    // Notion: `{ message }`; Airtable: `{ error: { message } }` or `{ error: "CODE" }`
+    let message = body.get("message")
+        .or_else(|| body.pointer("/error/message"))
+        .or_else(|| body.get("error"))
+        .and_then(Value::as_str)
+        .unwrap_or("unexpected response");
+    match status {
+        401 | 403 => Err(AppError::Forbidden(format!("{} refused the request: {}", service, message))),
+        404 => Err(AppError::NotFound(format!("{}: {}", service, message))),
+        _ => Err(AppError::Network(format!("HTTP {}: {}", status, message))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::custom_fields::{CustomFieldInput, FieldKind, FieldTarget};
+    use crate::services::database::init_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_and_shape() {
+        let dir = tempdir().unwrap();
+        let conn = init_db(&io_sqlite::get_db_path(dir.path())).unwrap();
+        let sys = json!({ "name": "Hero copy", "createdAt": 0, "updatedAt": 0, "source": "user" });
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, sys_json, updated_at) VALUES ('t1', 'record', 'h', ?1, ?2, 0)",
+            rusqlite::params![json!({ "content": "Fast. Red. Yours.", "format": "plain" }).to_string(), sys.to_string()],
+        ).unwrap();
+        let status = custom_fields::save_field(&conn, &CustomFieldInput {
+            id: None,
+            name: "Status".to_string(),
+            kind: FieldKind::Select,
+            options: vec!["draft".to_string(), "approved".to_string()],
+        }).unwrap();
+        custom_fields::set_value(&conn, FieldTarget::Asset, "t1", &status.id, &json!("approved")).unwrap();
+
+        let mapping = ExportMapping {
+            title: "Name".to_string(),
+            text: Some("Copy".to_string()),
+            image: None,
+            fields: HashMap::from([(status.id.clone(), "Stage".to_string())]),
+        };
+        let items = collect(dir.path(), &conn, &["t1".to_string()], &mapping).unwrap();
+        assert_eq!(items[0].text.as_deref(), Some("Fast. Red. Yours."));
+        assert_eq!(items[0].fields, [("Stage".to_string(), json!("approved"))]);
+        assert!(collect(dir.path(), &conn, &["missing".to_string()], &mapping).is_err());
+
+        let schema = json!({
+            "Name": { "type": "title" },
+            "Copy": { "type": "rich_text" },
+            "Stage": { "type": "select" },
+        });
+        let properties = notion_properties(&schema, &mapping, &items[0]).unwrap();
+        assert_eq!(properties["Name"]["title"][0]["text"]["content"], "Hero copy");
+        assert_eq!(properties["Stage"], json!({ "select": { "name": "approved" } }));
+        let missing = json!({ "Name": { "type": "title" } });
+        assert!(notion_properties(&missing, &mapping, &items[0]).is_err());
+
+        let fields = airtable_fields(&mapping, &items[0]);
+        assert_eq!(fields["Copy"], "Fast. Red. Yours.");
+        assert_eq!(fields["Stage"], "approved");
+
+        assert_eq!(notion_text(&"x".repeat(4500)).len(), 3);
+        assert_eq!(notion_id("1a2b3c4d-1a2b-1a2b-1a2b-1a2b3c4d5e6f").unwrap().len(), 32);
+        assert!(notion_id("../pages").is_err());
+    }
+}
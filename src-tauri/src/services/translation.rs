@@ -0,0 +1,325 @@
+//! Translation of text assets through the job queue.
+//!
+//! Each task carries one source text; the frontend translates it with the
+//! chosen provider and reports `{ text }` (or a plain string). When the job
+//! settles, every translation becomes a new text asset placed next to its
+//! source node, with `config.extra.provenance` linking back to the source.
+
+use std::collections::HashSet;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::{Asset, Position, SynniaNode, SynniaNodeData, SynniaProject};
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{database, io_sqlite, text_asset};
+
+/// Job kind used in the job queue
+pub const TRANSLATION_JOB_KIND: &str = "translation";
+
+/// Max texts translated in one batch
+pub const MAX_BATCH_SIZE: usize = 100;
+
+const NODE_GAP: f64 = 40.0;
+
+/// Job payload for a translation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationPayload {
+    pub target_lang: String,
+    pub provider: Option<String>,
+}
+
+/// Assets and nodes produced by a finished translation job
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+    pub job_id: String,
+    pub assets: Vec<Asset>,
+    pub nodes: Vec<SynniaNode>,
+}
+
+/// What to translate
+pub enum TranslationSource {
+    Asset(String),
+    Group(String),
+}
+
+/// Build one task per text asset. With a group, every text asset nested in
+/// it is included; otherwise only `asset_id`.
+pub fn build_tasks(
+    project: &SynniaProject,
+    source: TranslationSource,
+    payload: &TranslationPayload,
+) -> Result<Vec<Value>, AppError> {
+    let mut targets: Vec<(String, Option<String>)> = Vec::new();
+    
+    match source {
+        TranslationSource::Asset(asset_id) => {
+            let node_id = project.graph.nodes.iter()
+                .find(|n| n.data.asset_id.as_deref() == Some(asset_id.as_str()))
+                .map(|n| n.id.clone());
+            targets.push((asset_id, node_id));
+        }
+        TranslationSource::Group(group_id) => {
+            if !project.graph.nodes.iter().any(|n| n.id == group_id) {
+                return Err(AppError::NotFound(format!("Group not found: {}", group_id)));
+            }
+            let members = descendants(project, &group_id);
+            let mut seen = HashSet::new();
+            for node in project.graph.nodes.iter().filter(|n| members.contains(n.id.as_str())) {
+                let Some(asset_id) = &node.data.asset_id else { continue };
+                let is_text = project.assets.get(asset_id)
+                    .is_some_and(|a| text_asset::text_content(a).is_some());
+                if is_text && seen.insert(asset_id.clone()) {
+                    targets.push((asset_id.clone(), Some(node.id.clone())));
+                }
+            }
+        }
+    }
+    
+    if targets.is_empty() {
+        return Err(AppError::Unknown("No text assets to translate".to_string()));
+    }
+    if targets.len() > MAX_BATCH_SIZE {
+        return Err(AppError::Unknown(format!(
+            "Batch has {} texts, the maximum is {}", targets.len(), MAX_BATCH_SIZE
+        )));
+    }
+    
+    targets.into_iter().map(|(asset_id, node_id)| {
+        let asset = project.assets.get(&asset_id)
+            .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", asset_id)))?;
+        let text = text_asset::text_content(asset)
+            .ok_or_else(|| AppError::Unknown(format!("Asset {} is not a text asset", asset_id)))?;
+        Ok(json!({
+            "assetId": asset_id,
+            "nodeId": node_id,
+            "text": text,
+            "format": text_asset::text_format(asset),
+            "targetLang": payload.target_lang,
+            "provider": payload.provider,
+        }))
+    }).collect()
+}
+
+/// Persist every successful translation as a linked text asset.
+pub fn finalize_translation(project_root: &Path, job: &Job) -> Result<TranslationResult, AppError> {
+    let payload: TranslationPayload = serde_json::from_value(job.payload.clone())?;
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut assets = Vec::new();
+    let mut nodes = Vec::new();
+    
+    for task in &job.tasks {
+        if task.status != TaskStatus::Success {
+            continue;
+        }
+        let Some(text) = task.result.as_ref().and_then(translated_text) else { continue };
+        let Some(source_id) = task.params.get("assetId").and_then(|v| v.as_str()) else { continue };
+        let Some(source) = io_sqlite::get_asset(&conn, source_id)? else { continue };
+        
+        let mut asset = source.clone();
+        asset.id = uuid::Uuid::new_v4().to_string();
+        text_asset::set_text_content(&mut asset, text);
+        asset.value_meta = None;
+        asset.sys.name = format!("{} ({})", source.sys.name, payload.target_lang);
+        asset.sys.created_at = now;
+        asset.sys.updated_at = now;
+        asset.sys.source = "ai".to_string();
+        
+        let mut config = asset.config.take().unwrap_or_else(|| json!({ "schema": [] }));
+        if let Some(config) = config.as_object_mut() {
+            let extra = config.entry("extra").or_insert_with(|| json!({}));
+            if let Some(extra) = extra.as_object_mut() {
+                extra.insert("provenance".to_string(), json!({
+                    "kind": "translation",
+                    "sourceAssetId": source.id,
+                    "targetLang": payload.target_lang,
+                    "provider": payload.provider,
+                    "generatedAt": now,
+                }));
+            }
+        }
+        asset.config = Some(config);
+        
+        let source_node = match task.params.get("nodeId").and_then(|v| v.as_str()) {
+            Some(node_id) => io_sqlite::get_node(&conn, node_id)?,
+            None => None,
+        };
+        if let Some(source_node) = source_node {
+            let node = SynniaNode {
+                id: uuid::Uuid::new_v4().to_string(),
+                type_: source_node.type_.clone(),
+                position: Position {
+                    x: source_node.position.x + source_node.width.unwrap_or(300.0) + NODE_GAP,
+                    y: source_node.position.y,
+                },
+                width: source_node.width,
+                height: source_node.height,
+                parent_id: source_node.parent_id.clone(),
+                extent: source_node.extent.clone(),
+                style: None,
+                data: SynniaNodeData {
+                    title: asset.sys.name.clone(),
+                    asset_id: Some(asset.id.clone()),
+                    ..Default::default()
+                },
+            };
+            io_sqlite::upsert_node(&conn, &node)?;
+            nodes.push(node);
+        }
+        
+        io_sqlite::save_asset_with_history(project_root, &asset)?;
+        assets.push(asset);
+    }
+    
+    Ok(TranslationResult {
+        job_id: job.id.clone(),
+        assets,
+        nodes,
+    })
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Accept `"text"`, `{ text }` or `{ content }` from the executor
+fn translated_text(result: &Value) -> Option<String> {
+    match result {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(obj) => obj.get("text")
+            .or_else(|| obj.get("content"))
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        _ => None,
+    }
+}
+
+/// IDs of all nodes nested (at any depth) inside `group_id`
+fn descendants<'a>(project: &'a SynniaProject, group_id: &str) -> HashSet<&'a str> {
+    let mut members: HashSet<&str> = HashSet::new();
+    loop {
+        let before = members.len();
+        for node in &project.graph.nodes {
+            if let Some(parent) = node.parent_id.as_deref() {
+                if parent == group_id || members.contains(parent) {
+                    members.insert(node.id.as_str());
+                }
+            }
+        }
+        if members.len() == before {
+            break;
+        }
+    }
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use crate::services::io_sqlite::{init_project_sqlite, load_project_sqlite, save_project_sqlite};
+    use tempfile::tempdir;
+
+    fn text(id: &str, content: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "content": content, "format": "plain" }),
+            value_meta: None,
+            config: Some(json!({ "schema": [] })),
+            sys: AssetSysMetadata {
+                name: id.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                source: "user".to_string(),
+            },
+        }
+    }
+
+    fn node(id: &str, parent: Option<&str>, asset: Option<&str>) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: "text-node".to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: Some(200.0),
+            height: None,
+            parent_id: parent.map(String::from),
+            extent: None,
+            style: None,
+            data: SynniaNodeData {
+                title: id.to_string(),
+                asset_id: asset.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn setup(project_root: &Path) -> SynniaProject {
+        let mut project = init_project_sqlite(project_root, "Campaign").unwrap();
+        project.graph.nodes = vec![
+            node("board", None, None),
+            node("rack", Some("board"), None),
+            node("headline", Some("rack"), Some("t1")),
+            node("tagline", Some("board"), Some("t2")),
+            node("image", Some("board"), Some("img")),
+            node("loose", None, Some("t3")),
+        ];
+        project.assets.insert("t1".to_string(), text("t1", "Hello"));
+        project.assets.insert("t2".to_string(), text("t2", "Buy now"));
+        project.assets.insert("t3".to_string(), text("t3", "Elsewhere"));
+        let mut image = text("img", "assets/a.png");
+        image.value = json!({ "content": "assets/a.png", "format": "file" });
+        project.assets.insert("img".to_string(), image);
+        save_project_sqlite(project_root, &project).unwrap();
+        load_project_sqlite(project_root).unwrap()
+    }
+
+    fn payload() -> TranslationPayload {
+        TranslationPayload { target_lang: "fr".to_string(), provider: Some("openai".to_string()) }
+    }
+
+    #[test]
+    fn test_build_tasks_for_group() {
+        let dir = tempdir().unwrap();
+        let project = setup(dir.path());
+        
+        let tasks = build_tasks(&project, TranslationSource::Group("board".to_string()), &payload()).unwrap();
+        let ids: HashSet<&str> = tasks.iter().map(|t| t["assetId"].as_str().unwrap()).collect();
+        assert_eq!(ids, HashSet::from(["t1", "t2"]), "nested text only, images skipped");
+        
+        assert!(build_tasks(&project, TranslationSource::Group("missing".to_string()), &payload()).is_err());
+        assert!(build_tasks(&project, TranslationSource::Asset("img".to_string()), &payload()).is_err());
+    }
+
+    #[test]
+    fn test_finalize_translation() {
+        let dir = tempdir().unwrap();
+        let project = setup(dir.path());
+        
+        let tasks = build_tasks(&project, TranslationSource::Asset("t1".to_string()), &payload()).unwrap();
+        let mut job = Job::new(TRANSLATION_JOB_KIND, serde_json::to_value(payload()).unwrap(), tasks, 1);
+        job.tasks[0].status = TaskStatus::Success;
+        job.tasks[0].result = Some(json!({ "text": "Bonjour" }));
+        
+        let result = finalize_translation(dir.path(), &job).unwrap();
+        assert_eq!(result.assets.len(), 1);
+        assert_eq!(result.nodes.len(), 1);
+        
+        let asset = &result.assets[0];
+        assert_eq!(text_asset::text_content(asset), Some("Bonjour"));
+        assert_eq!(asset.config.as_ref().unwrap()["extra"]["provenance"]["sourceAssetId"], "t1");
+        
+        let node = &result.nodes[0];
+        assert_eq!(node.parent_id.as_deref(), Some("rack"));
+        assert_eq!(node.position.x, 240.0);
+        
+        let reloaded = load_project_sqlite(dir.path()).unwrap();
+        assert!(reloaded.assets.contains_key(&asset.id));
+    }
+}
@@ -0,0 +1,248 @@
+//! Image upscaling, saved as a new version of the same asset (the previous
+//! file is kept in the asset's file history).
+//!
+//! The "local" provider runs an ESRGAN ONNX model through an external runner
+//! (`SYNNIA_UPSCALER`, model in `SYNNIA_ESRGAN_MODEL` or else the installed
+//! upscale model, see `model_manager`) on the local worker
+//! pool. Any other provider is a remote API: the task is dispatched to the
+//! frontend, which reports `{ data }` (base64 or data URL) or `{ src }` (a
+//! file already saved into the project).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::models::Asset;
+use crate::services::job_queue::{Job, TaskStatus};
+use crate::services::{database, imaging, io_sqlite, model_manager};
+use crate::services::model_manager::ModelKind;
+
+/// Job kind used in the job queue
+pub const JOB_KIND: &str = "upscale";
+
+/// Provider name of the bundled-model upscaler
+pub const LOCAL_PROVIDER: &str = "local";
+
+/// ESRGAN models are trained for 2x to 4x
+pub const MIN_FACTOR: u32 = 2;
+pub const MAX_FACTOR: u32 = 4;
+
+/// Job payload for an upscale run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpscalePayload {
+    pub asset_id: String,
+    pub factor: u32,
+    pub provider: String,
+}
+
+/// External runner and the ONNX model it loads
+#[derive(Debug, Clone)]
+pub struct LocalUpscaler {
+    pub runner: PathBuf,
+    pub model: PathBuf,
+}
+
+/// The local upscaler, if a runner and model are configured.
+pub fn find_local_upscaler() -> Option<LocalUpscaler> {
+    let runner = PathBuf::from(std::env::var_os("SYNNIA_UPSCALER")?);
+    let model = std::env::var_os("SYNNIA_ESRGAN_MODEL")
+        .map(PathBuf::from)
+        .or_else(|| model_manager::installed(ModelKind::Upscale))?;
+    (runner.is_file() && model.is_file()).then_some(LocalUpscaler { runner, model })
+}
+
+/// Build the single task of an upscale job, checking the asset is an image
+/// file in the project.
+pub fn build_task(project_root: &Path, payload: &UpscalePayload) -> Result<Value, AppError> {
+    if !(MIN_FACTOR..=MAX_FACTOR).contains(&payload.factor) {
+        return Err(AppError::Unknown(format!(
+            "Upscale factor must be between {} and {}", MIN_FACTOR, MAX_FACTOR
+        )));
+    }
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let asset = io_sqlite::get_asset(&conn, &payload.asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.asset_id)))?;
+    let src = image_path(project_root, &asset)
+        .ok_or_else(|| AppError::NotFound(format!("Image file of {} not found", payload.asset_id)))?;
+    
+    Ok(json!({
+        "assetId": payload.asset_id,
+        "src": src,
+        "factor": payload.factor,
+        "provider": payload.provider,
+    }))
+}
+
+/// Upscale with the local runner; returns `{ src }` of the new file. The
+/// error is the message shown for the task.
+pub fn upscale_local(upscaler: &LocalUpscaler, project_root: &Path, task: &Value) -> Result<Value, String> {
+    let src = task.get("src").and_then(|v| v.as_str()).ok_or("Task has no source image")?;
+    let factor = task.get("factor").and_then(|v| v.as_u64()).ok_or("Task has no factor")?;
+    
+    let relative_path = format!("assets/{}.png", uuid::Uuid::new_v4());
+    let target = project_root.join(&relative_path);
+    let output = Command::new(&upscaler.runner)
+        .arg("-i").arg(project_root.join(src))
+        .arg("-o").arg(&target)
+        .arg("-m").arg(&upscaler.model)
+        .args(["-s", &factor.to_string()])
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run upscaler: {}", e))?;
+    if !output.status.success() || !target.is_file() {
+        let _ = std::fs::remove_file(&target);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Upscaler failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+    
+    Ok(json!({ "src": relative_path }))
+}
+
+/// Save the upscaled image of a finished job as a new version of its asset.
+pub fn finalize_upscale(project_root: &Path, job: &Job) -> Result<Option<Asset>, AppError> {
+    let payload: UpscalePayload = serde_json::from_value(job.payload.clone())?;
+    
+    let result = match job.tasks.first() {
+        Some(task) if task.status == TaskStatus::Success => task.result.clone().unwrap_or(Value::Null),
+        _ => return Ok(None),
+    };
+    
+    let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let mut asset = io_sqlite::get_asset(&conn, &payload.asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset not found: {}", payload.asset_id)))?;
+    let previous_src = image_path(project_root, &asset);
+    
+    let (relative_path, data) = store_result(project_root, &result)?;
+    let file_id = Path::new(&relative_path).file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (width, height) = imaging::get_image_dimensions(&data)?;
+    let preview = imaging::generate_thumbnail(project_root, &file_id, &data).ok();
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    match asset.value.as_object_mut() {
+        Some(value) => {
+            value.insert("src".to_string(), json!(relative_path));
+            value.insert("width".to_string(), json!(width));
+            value.insert("height".to_string(), json!(height));
+        }
+        None => asset.value = json!({ "src": relative_path, "width": width, "height": height }),
+    }
+    asset.value_meta = Some(json!({ "width": width, "height": height, "preview": preview }));
+    asset.sys.updated_at = now;
+    
+    let mut config = asset.config.take().unwrap_or_else(|| json!({ "schema": [] }));
+    if let Some(config) = config.as_object_mut() {
+        let extra = config.entry("extra").or_insert_with(|| json!({}));
+        if let Some(extra) = extra.as_object_mut() {
+            extra.insert("provenance".to_string(), json!({
+                "kind": "upscale",
+                "previousSrc": previous_src,
+                "factor": payload.factor,
+                "provider": payload.provider,
+                "generatedAt": now,
+            }));
+        }
+    }
+    asset.config = Some(config);
+    
+    io_sqlite::save_asset_with_history(project_root, &asset)?;
+    Ok(Some(asset))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+/// Relative path of the asset's image file, if it is in the project
+fn image_path(project_root: &Path, asset: &Asset) -> Option<String> {
+    let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+    let path = imaging::resolve_image_source(project_root, src).filter(|p| p.is_file())?;
+    Some(path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/"))
+}
+
+/// Accept `{ src }`, `{ data }` or a plain base64 string from the executor.
+/// Returns the file's relative path and its bytes.
+fn store_result(project_root: &Path, result: &Value) -> Result<(String, Vec<u8>), AppError> {
+    let src = result.get("src").and_then(|v| v.as_str());
+    if let Some(path) = src.and_then(|src| imaging::resolve_image_source(project_root, src)) {
+        let data = std::fs::read(&path)
+            .map_err(|e| AppError::Io(format!("Failed to read upscaled image: {}", e)))?;
+        let relative = path.strip_prefix(project_root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        return Ok((relative, data));
+    }
+    
+    let encoded = result.get("data").and_then(|v| v.as_str()).or(result.as_str())
+        .ok_or_else(|| AppError::Unknown("Upscaler returned no image".to_string()))?;
+    let data = imaging::decode_base64_image(encoded)?;
+    let ext = imaging::detect_image_format(&data)
+        .ok_or_else(|| AppError::Unknown("Upscaler returned an unsupported image".to_string()))?;
+    let relative_path = format!("assets/{}.{}", uuid::Uuid::new_v4(), ext);
+    std::fs::create_dir_all(project_root.join("assets"))?;
+    std::fs::write(project_root.join(&relative_path), &data)?;
+    Ok((relative_path, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AssetSysMetadata, ValueType};
+    use base64::Engine;
+    use tempfile::tempdir;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::RgbImage::new(width, height)
+            .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn payload(factor: u32) -> UpscalePayload {
+        UpscalePayload { asset_id: "draft".to_string(), factor, provider: "replicate".to_string() }
+    }
+
+    #[test]
+    fn test_upscale_new_version() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Drafts").unwrap();
+        std::fs::write(root.join("assets/draft.png"), png(16, 8)).unwrap();
+        project.assets.insert("draft".to_string(), Asset {
+            id: "draft".to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": "assets/draft.png", "width": 16, "height": 8 }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: "Draft".to_string(), created_at: 0, updated_at: 0, source: "ai".to_string() },
+        });
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        assert!(build_task(root, &payload(8)).is_err());
+        let task = build_task(root, &payload(4)).unwrap();
+        assert_eq!(task["src"], "assets/draft.png");
+        
+        let mut job = Job::new(JOB_KIND, serde_json::to_value(payload(4)).unwrap(), vec![task], 1);
+        job.tasks[0].status = TaskStatus::Success;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(png(64, 32));
+        job.tasks[0].result = Some(json!({ "data": format!("data:image/png;base64,{}", encoded) }));
+        
+        let asset = finalize_upscale(root, &job).unwrap().unwrap();
+        assert_eq!(asset.id, "draft");
+        assert_eq!((asset.value["width"].as_u64(), asset.value["height"].as_u64()), (Some(64), Some(32)));
+        assert_ne!(asset.value["src"], "assets/draft.png");
+        assert!(root.join(asset.value["src"].as_str().unwrap()).is_file());
+        
+        let provenance = &asset.config.as_ref().unwrap()["extra"]["provenance"];
+        assert_eq!(provenance["previousSrc"], "assets/draft.png");
+        assert_eq!(provenance["factor"], 4);
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        assert_eq!(crate::services::history::count_history(&conn, "draft").unwrap(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+//! `synnia://` links, so OS automations (Apple Shortcuts, scripts, launcher
+//! actions) can feed the open project.
+//!
+//! ```text
+//! synnia://import?url=<url>[&title=<title>]     link node in the inbox
+//! synnia://import?text=<text>[&title=<title>]   text node in the inbox
+//! synnia://run-recipe?node=<recipe node id>     run a recipe node
+//! ```
+//!
+//! Any app or web page can open a link, so links act as the `automation`
+//! actor under the project's permission policy: imports need
+//! `import-assets` (allowed unless configured otherwise) and running a
+//! recipe `run-recipes` (asks first). Imports land in the "Inbox" group like
+//! clippings and are announced as `clip:imported`; recipes run in the
+//! frontend, which is asked to with `automation:run-recipe`.
+//!
+//! A link that arrives before a project is open — e.g. the one that launched
+//! the app — waits up to `PROJECT_WAIT` for one. The installers register the
+//! scheme; on Windows and Linux it is registered again on start, which also
+//! covers dev builds and AppImages.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use reqwest::Url;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use ts_rs::TS;
+use crate::error::AppError;
+use crate::events::{self, AppEvent};
+use crate::AppState;
+use crate::services::{clip, database, file_server, io_sqlite, recipes};
+use crate::services::clip::ClipItem;
+use crate::services::permissions::{Actor, Capability};
+
+pub const SCHEME: &str = "synnia";
+
+/// How long a link waits for a project to be opened
+pub const PROJECT_WAIT: Duration = Duration::from_secs(120);
+
+/// Longest accepted `text` of an import
+const MAX_TEXT_LEN: usize = 100_000;
+
+/// Payload of `automation:run-recipe`
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeRunRequest {
+    pub recipe_node_id: String,
+    pub recipe_id: String,
+}
+
+/// What a link asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    ImportUrl { url: String, title: Option<String> },
+    ImportText { text: String, title: Option<String> },
+    RunRecipe { node_id: String },
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Result<Self, AppError> {
+        let invalid = |reason: &str| AppError::InvalidInput(format!("{}: {}", reason, link));
+        let url = Url::parse(link).map_err(|_| invalid("Not a link"))?;
+        if url.scheme() != SCHEME {
+            return Err(invalid("Not a synnia:// link"));
+        }
+        let param = |name: &str| url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let title = param("title");
+
+        match url.host_str().unwrap_or_default() {
+            "import" => match (param("url"), param("text")) {
+                (Some(target), _) => {
+                    // Nothing that would open or run locally once clicked on the canvas
+                    if !Url::parse(&target).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) {
+                        return Err(invalid("Only http(s) URLs can be imported"));
+                    }
+                    Ok(DeepLink::ImportUrl { url: target, title })
+                }
+                (None, Some(text)) if text.len() > MAX_TEXT_LEN => Err(invalid("Text too long")),
+                (None, Some(text)) => Ok(DeepLink::ImportText { text, title }),
+                (None, None) => Err(invalid("Import needs `url` or `text`")),
+            },
+            "run-recipe" => param("node")
+                .map(|node_id| DeepLink::RunRecipe { node_id })
+                .ok_or_else(|| invalid("Run-recipe needs `node`")),
+            _ => Err(invalid("Unknown action")),
+        }
+    }
+
+    fn capability(&self) -> Capability {
+        match self {
+            DeepLink::ImportUrl { .. } | DeepLink::ImportText { .. } => Capability::ImportAssets,
+            DeepLink::RunRecipe { .. } => Capability::RunRecipes,
+        }
+    }
+
+    /// What the permission prompt says
+    fn summary(&self) -> String {
+        match self {
+            DeepLink::ImportUrl { url, .. } => format!("Import {} from a synnia:// link", url),
+            DeepLink::ImportText { .. } => "Import text from a synnia:// link".to_string(),
+            DeepLink::RunRecipe { node_id } => format!("Run recipe node {} from a synnia:// link", node_id),
+        }
+    }
+}
+
+/// Handle links opened while the app runs, and the one it was launched with.
+pub fn init(app: &AppHandle) {
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        println!("[UrlScheme] Failed to register {}://: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            spawn_handle(handle.clone(), url.to_string());
+        }
+    });
+    // Windows and Linux pass the launching link as an argument
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            spawn_handle(app.clone(), url.to_string());
+        }
+    }
+}
+
+/// Carry out `link` on the open project, once the permission policy allows.
+pub async fn handle(app: &AppHandle, link: &str) -> Result<(), AppError> {
+    let link = DeepLink::parse(link)?;
+    let project_root = wait_for_project(app).await?;
+    let state = app.state::<AppState>();
+    state.permissions
+        .authorize(app, &project_root, Actor::Automation, link.capability(), link.summary(), Vec::new())
+        .await?;
+
+    match link {
+        DeepLink::ImportUrl { url, title } => import(app, project_root, ClipItem::Url { url, title }).await,
+        DeepLink::ImportText { text, title } => import(app, project_root, ClipItem::Text { text, title }).await,
+        DeepLink::RunRecipe { node_id } => {
+            let lookup_id = node_id.clone();
+            let recipe = state.blocking.run(move || {
+                let conn = database::open_db(&io_sqlite::get_db_path(&project_root))
+                    .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+                recipes::for_node(&conn, &lookup_id)
+            }).await?;
+            let request = RecipeRunRequest { recipe_node_id: node_id, recipe_id: recipe.id };
+            events::emit(app, AppEvent::RunRecipe(request))
+        }
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn spawn_handle(app: AppHandle, link: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = handle(&app, &link).await {
+            println!("[UrlScheme] {}: {}", link, e);
+        }
+    });
+}
+
+async fn import(app: &AppHandle, project_root: PathBuf, item: ClipItem) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let imported = state.blocking.run(move || clip::import_clips(&project_root, vec![item])).await?;
+    // Let the open canvas merge the new nodes before its next autosave
+    events::emit(app, AppEvent::ClipImported(imported))
+}
+
+async fn wait_for_project(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let state = app.state::<AppState>();
+    let deadline = tokio::time::Instant::now() + PROJECT_WAIT;
+    loop {
+        let path = state.current_project_path.lock()
+            .map_err(|_| AppError::Unknown("Lock poisoned".to_string()))?
+            .clone();
+        if let Some(path) = path {
+            return Ok(file_server::project_root_of(PathBuf::from(path)));
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(AppError::ProjectNotLoaded);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            DeepLink::parse("synnia://import?url=https%3A%2F%2Fexample.com%2Fa.png&title=Ref").unwrap(),
+            DeepLink::ImportUrl { url: "https://example.com/a.png".to_string(), title: Some("Ref".to_string()) },
+        );
+        assert_eq!(
+            DeepLink::parse("synnia://import?text=Warmer%20palette").unwrap(),
+            DeepLink::ImportText { text: "Warmer palette".to_string(), title: None },
+        );
+        assert_eq!(
+            DeepLink::parse("synnia://run-recipe?node=n1").unwrap(),
+            DeepLink::RunRecipe { node_id: "n1".to_string() },
+        );
+        assert_eq!(DeepLink::parse("synnia://run-recipe?node=n1").unwrap().capability(), Capability::RunRecipes);
+
+        for link in [
+            "https://import?url=https://example.com",
+            "synnia://import",
+            "synnia://import?url=file:///etc/passwd",
+            "synnia://import?url=javascript:alert(1)",
+            "synnia://run-recipe",
+            "synnia://delete?node=n1",
+        ] {
+            assert!(matches!(DeepLink::parse(link), Err(AppError::InvalidInput(_))), "{}", link);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Where-used reports for shared recipes (templates) and agents, so users can
+//! see what depends on one before editing it.
+//!
+//! Each project in the workspace (the recent projects list) is scanned:
+//! - a recipe is used by nodes of type `recipe:<id>`, nodes with that
+//!   `recipeId`, and nodes whose asset carries it in `config.extra.recipeId`;
+//!   its runs are the generation records made with it
+//! - agents are referenced by name only, in the activity feed: its runs are
+//!   the `agent-ran` entries, and the nodes are the ones it ran on or wrote to
+//!
+//! Projects that can't be opened are listed rather than failing the report.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use crate::error::AppError;
+use crate::services::{database, io_sqlite};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UsedNode {
+    pub id: String,
+    pub title: String,
+    pub node_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectUsage {
+    pub name: String,
+    pub path: String,
+    pub nodes: Vec<UsedNode>,
+    /// Generations made with the recipe, or runs of the agent
+    pub runs: usize,
+    #[ts(type = "number | null")]
+    pub last_used_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    /// Projects using it, most recently used first
+    pub projects: Vec<ProjectUsage>,
+    /// Projects looked at
+    pub scanned: usize,
+    /// Projects that couldn't be read
+    pub unreadable: Vec<String>,
+}
+
+/// What to look for
+#[derive(Debug, Clone, Copy)]
+pub enum Subject<'a> {
+    Recipe(&'a str),
+    /// By name, as the activity feed records agents
+    Agent(&'a str),
+}
+
+/// Scan `projects` (name and root) for uses of `subject`.
+pub fn scan(projects: &[(String, PathBuf)], subject: Subject) -> UsageReport {
+    let mut report = UsageReport { projects: Vec::new(), scanned: 0, unreadable: Vec::new() };
+    let mut seen = HashSet::new();
+    for (name, root) in projects {
+        if !seen.insert(root.clone()) || !io_sqlite::is_sqlite_project(root) {
+            continue;
+        }
+        report.scanned += 1;
+        match project_usage(name, root, subject) {
+            Ok(Some(usage)) => report.projects.push(usage),
+            Ok(None) => {}
+            Err(e) => {
+                println!("[Usages] Skipping {}: {}", root.display(), e);
+                report.unreadable.push(root.to_string_lossy().into_owned());
+            }
+        }
+    }
+    report.projects.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at).then_with(|| a.name.cmp(&b.name)));
+    report
+}
+
+/// Uses of `subject` in one project, `None` if there are none.
+pub fn project_usage(name: &str, root: &Path, subject: Subject) -> Result<Option<ProjectUsage>, AppError> {
+    let conn = database::open_db(&io_sqlite::get_db_path(root))
+        .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+    let (nodes, runs, last_used_at) = match subject {
+        Subject::Recipe(recipe_id) => recipe_usage(&conn, recipe_id)?,
+        Subject::Agent(agent_name) => agent_usage(&conn, agent_name)?,
+    };
+    if nodes.is_empty() && runs == 0 {
+        return Ok(None);
+    }
+    Ok(Some(ProjectUsage {
+        name: name.to_string(),
+        path: root.to_string_lossy().into_owned(),
+        nodes,
+        runs,
+        last_used_at,
+    }))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+type Usage = (Vec<UsedNode>, usize, Option<i64>);
+
+fn recipe_usage(conn: &Connection, recipe_id: &str) -> Result<Usage, AppError> {
+    let nodes = query_nodes(
+        conn,
+        "SELECT n.id, json_extract(n.data_json, '$.title'), n.type FROM nodes n
+         LEFT JOIN assets a ON a.id = json_extract(n.data_json, '$.assetId')
+         WHERE n.type = 'recipe:' || ?1
+            OR json_extract(n.data_json, '$.recipeId') = ?1
+            OR json_extract(a.config_json, '$.extra.recipeId') = ?1
+         ORDER BY n.rowid",
+        recipe_id,
+    )?;
+    let (runs, last_used_at) = conn.query_row(
+        "SELECT COUNT(*), MAX(created_at) FROM generation_records WHERE recipe_id = ?1",
+        params![recipe_id],
+        |row| Ok((row.get::<_, i64>(0)? as usize, row.get(1)?)),
+    ).map_err(|e| AppError::Io(format!("Failed to count generations: {}", e)))?;
+    Ok((nodes, runs, last_used_at))
+}
+
+fn agent_usage(conn: &Connection, agent_name: &str) -> Result<Usage, AppError> {
+    let (runs, last_used_at) = conn.query_row(
+        "SELECT COUNT(*), MAX(created_at) FROM activity WHERE kind = 'agent-ran' AND source = ?1",
+        params![agent_name],
+        |row| Ok((row.get::<_, i64>(0)? as usize, row.get(1)?)),
+    ).map_err(|e| AppError::Io(format!("Failed to count agent runs: {}", e)))?;
+    if runs == 0 {
+        return Ok((Vec::new(), 0, None));
+    }
+    // Runs target the focused node or the asset streamed into
+    let nodes = query_nodes(
+        conn,
+        "SELECT n.id, json_extract(n.data_json, '$.title'), n.type FROM nodes n
+         WHERE EXISTS (
+             SELECT 1 FROM activity t WHERE t.kind = 'agent-ran' AND t.source = ?1
+                AND (t.target_id = n.id OR t.target_id = json_extract(n.data_json, '$.assetId'))
+         )
+         ORDER BY n.rowid",
+        agent_name,
+    )?;
+    Ok((nodes, runs, last_used_at))
+}
+
+fn query_nodes(conn: &Connection, sql: &str, param: &str) -> Result<Vec<UsedNode>, AppError> {
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt.query_map(params![param], |row| {
+        Ok(UsedNode {
+            id: row.get(0)?,
+            title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            node_type: row.get(2)?,
+        })
+    }).map_err(|e| AppError::Io(format!("Failed to query nodes: {}", e)))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load nodes: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Position, SynniaNode, SynniaNodeData};
+    use crate::services::activity::{self, ActivityKind, NewActivity};
+    use tempfile::tempdir;
+
+    fn node(id: &str, node_type: &str, data: SynniaNodeData) -> SynniaNode {
+        SynniaNode {
+            id: id.to_string(),
+            type_: node_type.to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            width: None,
+            height: None,
+            parent_id: None,
+            extent: None,
+            style: None,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_scan_usages() {
+        let dir = tempdir().unwrap();
+        let storyboard = dir.path().join("Storyboard");
+        let moodboard = dir.path().join("Moodboard");
+        io_sqlite::init_project_sqlite(&storyboard, "Storyboard").unwrap();
+        io_sqlite::init_project_sqlite(&moodboard, "Moodboard").unwrap();
+
+        let conn = database::open_db(&io_sqlite::get_db_path(&storyboard)).unwrap();
+        io_sqlite::upsert_node(&conn, &node("typed", "recipe:storyteller", SynniaNodeData {
+            title: "Story".to_string(),
+            ..Default::default()
+        })).unwrap();
+        io_sqlite::upsert_node(&conn, &node("by-asset", "recipe-node", SynniaNodeData {
+            title: "Story 2".to_string(),
+            asset_id: Some("recipe-asset".to_string()),
+            ..Default::default()
+        })).unwrap();
+        io_sqlite::upsert_node(&conn, &node("other", "recipe:captioner", SynniaNodeData::default())).unwrap();
+        conn.execute(
+            "INSERT INTO assets (id, value_type, value_hash, value_json, config_json, sys_json, updated_at)
+             VALUES ('recipe-asset', '\"record\"', 'h', '{}', '{\"extra\":{\"recipeId\":\"storyteller\"}}', '{}', 0)",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO generation_records (asset_id, recipe_id, params_json, created_at) VALUES ('out', 'storyteller', '{}', 42)",
+            [],
+        ).unwrap();
+        activity::record(&conn, &NewActivity::new(ActivityKind::AgentRan, "agent", "Ran Critic")
+            .source("Critic")
+            .target("typed")).unwrap();
+
+        let projects = vec![
+            ("Storyboard".to_string(), storyboard.clone()),
+            ("Moodboard".to_string(), moodboard.clone()),
+            ("Storyboard".to_string(), storyboard.clone()),
+            ("Gone".to_string(), dir.path().join("Gone")),
+        ];
+        let recipe = scan(&projects, Subject::Recipe("storyteller"));
+        assert_eq!(recipe.scanned, 2);
+        assert_eq!(recipe.projects.len(), 1);
+        let usage = &recipe.projects[0];
+        assert_eq!(usage.nodes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), ["typed", "by-asset"]);
+        assert_eq!((usage.runs, usage.last_used_at), (1, Some(42)));
+
+        let agent = scan(&projects, Subject::Agent("Critic"));
+        assert_eq!(agent.projects[0].runs, 1);
+        assert_eq!(agent.projects[0].nodes[0].title, "Story");
+        assert!(scan(&projects, Subject::Agent("Nobody")).projects.is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+//! Argument checks for commands that take paths, URLs or large payloads.
+//!
+//! Commands call these before touching the disk or network, so malformed or
+//! oversized input is rejected with `AppError::InvalidInput` up front rather
+//! than failing halfway through, writing outside `assets/`, or buffering an
+//! unbounded amount of memory.
+//!
+//! Paths relative to the project go through `resolve_in_project`, which
+//! refuses anything that ends up outside the project folder (`AppError::Forbidden`).
+
+use std::path::{Component, Path, PathBuf};
+use reqwest::Url;
+use crate::error::AppError;
+
+pub const MAX_PATH_LEN: usize = 4096;
+pub const MAX_FILE_NAME_LEN: usize = 255;
+pub const MAX_URL_LEN: usize = 8 * 1024;
+
+/// Decoded size of an image sent as base64
+pub const MAX_IMAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Request body forwarded by the HTTP proxy
+pub const MAX_REQUEST_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// Response body the HTTP proxy will buffer for the frontend
+pub const MAX_RESPONSE_BODY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Characters Windows refuses in file names
+const RESERVED_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// A local file path from the frontend: non-empty, bounded, no control characters.
+pub fn check_path(path: &str) -> Result<PathBuf, AppError> {
+    if path.trim().is_empty() {
+        return Err(AppError::InvalidInput("Path is empty".to_string()));
+    }
+    if path.len() > MAX_PATH_LEN {
+        return Err(AppError::InvalidInput(format!("Path is longer than {} bytes", MAX_PATH_LEN)));
+    }
+    if path.chars().any(char::is_control) {
+        return Err(AppError::InvalidInput("Path contains control characters".to_string()));
+    }
+    Ok(PathBuf::from(path))
+}
+
+/// A single file name to create inside a project folder. Separators, `..`
+/// and characters invalid on Windows are rejected.
+pub fn check_file_name(name: &str) -> Result<&str, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput("File name is empty".to_string()));
+    }
+    if name.len() > MAX_FILE_NAME_LEN {
+        return Err(AppError::InvalidInput(format!("File name is longer than {} bytes", MAX_FILE_NAME_LEN)));
+    }
+    if name == "." || name == ".." || name.ends_with('.') || name.ends_with(' ') {
+        return Err(AppError::InvalidInput(format!("Invalid file name: {}", name)));
+    }
+    if name.chars().any(|c| c.is_control() || RESERVED_NAME_CHARS.contains(&c)) {
+        return Err(AppError::InvalidInput(format!("File name contains invalid characters: {}", name)));
+    }
+    Ok(name)
+}
+
+/// Resolve `relative` to a path inside `project_root`. Absolute paths, `..`
+/// climbing out of the folder and symlinks pointing elsewhere are rejected;
+/// the target itself doesn't have to exist yet.
+pub fn resolve_in_project(project_root: &Path, relative: &str) -> Result<PathBuf, AppError> {
+    check_path(relative)?;
+    let outside = || AppError::Forbidden(format!("Path is outside the project folder: {}", relative));
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(outside());
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return Err(outside()),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        return Err(AppError::InvalidInput(format!("Path does not name a file: {}", relative)));
+    }
+
+    // Where the deepest existing part really points decides, so a symlink
+    // (even a dangling one) can't lead out of the project
+    let root = project_root.canonicalize()?;
+    let path = project_root.join(&normalized);
+    let mut existing = path.clone();
+    while existing.symlink_metadata().is_err() {
+        if !existing.pop() {
+            return Err(outside());
+        }
+    }
+    let real = existing.canonicalize().map_err(|_| outside())?;
+    if !real.starts_with(&root) {
+        return Err(outside());
+    }
+    Ok(path)
+}
+
+/// Reject base64 data (plain or a data URI) that is empty or would decode to
+/// more than `max_bytes`, before decoding it.
+pub fn check_base64_size(data: &str, max_bytes: usize) -> Result<(), AppError> {
+    let payload = data.split_once(',').map(|(_, p)| p).unwrap_or(data);
+    if payload.trim().is_empty() {
+        return Err(AppError::InvalidInput("Base64 data is empty".to_string()));
+    }
+    let decoded = payload.len() / 4 * 3;
+    if decoded > max_bytes {
+        return Err(AppError::InvalidInput(format!(
+            "Data is too large ({} > {} bytes)", decoded, max_bytes
+        )));
+    }
+    Ok(())
+}
+
+/// An http(s) URL. Host rules are left to `NetworkPolicy`.
+pub fn check_url(url: &str) -> Result<Url, AppError> {
+    if url.len() > MAX_URL_LEN {
+        return Err(AppError::InvalidInput(format!("URL is longer than {} bytes", MAX_URL_LEN)));
+    }
+    let parsed = Url::parse(url)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid URL '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(AppError::InvalidInput(format!("Unsupported URL scheme: {}", parsed.scheme())));
+    }
+    Ok(parsed)
+}
+
+/// Reject a payload larger than `max_bytes`
+pub fn check_body_size(body: Option<&str>, max_bytes: usize) -> Result<(), AppError> {
+    match body {
+        Some(body) if body.len() > max_bytes => Err(AppError::InvalidInput(format!(
+            "Body is too large ({} > {} bytes)", body.len(), max_bytes
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_path() {
+        assert!(check_path("/home/me/photo.png").is_ok());
+        assert!(matches!(check_path(""), Err(AppError::InvalidInput(_))));
+        assert!(matches!(check_path("/tmp/a\0b"), Err(AppError::InvalidInput(_))));
+        assert!(matches!(check_path(&"a".repeat(MAX_PATH_LEN + 1)), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_check_file_name() {
+        assert_eq!(check_file_name("cat 01.png").unwrap(), "cat 01.png");
+        for name in ["", "..", "../escape.png", "a/b.png", "a\\b.png", "what?.png", "trailing."] {
+            assert!(matches!(check_file_name(name), Err(AppError::InvalidInput(_))), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_resolve_in_project() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("assets")).unwrap();
+
+        assert_eq!(resolve_in_project(root, "assets/a.png").unwrap(), root.join("assets/a.png"));
+        assert_eq!(resolve_in_project(root, "./assets/new/../b.png").unwrap(), root.join("assets/b.png"));
+        assert_eq!(resolve_in_project(root, "not/yet/created.png").unwrap(), root.join("not/yet/created.png"));
+
+        for path in ["../outside.png", "assets/../../outside.png", "/etc/passwd"] {
+            assert!(matches!(resolve_in_project(root, path), Err(AppError::Forbidden(_))), "{}", path);
+        }
+        assert!(matches!(resolve_in_project(root, "assets/.."), Err(AppError::InvalidInput(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_in_project_symlinks() {
+        let dir = tempdir().unwrap();
+        let outside = tempdir().unwrap();
+        let root = dir.path();
+        std::os::unix::fs::symlink(outside.path(), root.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("missing"), root.join("dangling")).unwrap();
+        std::os::unix::fs::symlink(root.join("assets"), root.join("inside")).unwrap();
+        std::fs::create_dir(root.join("assets")).unwrap();
+
+        assert!(matches!(resolve_in_project(root, "escape/a.png"), Err(AppError::Forbidden(_))));
+        assert!(matches!(resolve_in_project(root, "dangling"), Err(AppError::Forbidden(_))));
+        assert!(resolve_in_project(root, "inside/a.png").is_ok());
+    }
+
+    #[test]
+    fn test_check_base64_size() {
+        assert!(check_base64_size("data:image/png;base64,iVBORw0KGgo=", 1024).is_ok());
+        assert!(check_base64_size("data:image/png;base64,", 1024).is_err());
+        assert!(check_base64_size(&"A".repeat(4000), 1024).is_err());
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("http://localhost:11434/api/tags").is_ok());
+        assert!(matches!(check_url("file:///etc/passwd"), Err(AppError::InvalidInput(_))));
+        assert!(matches!(check_url("not a url"), Err(AppError::InvalidInput(_))));
+    }
+}
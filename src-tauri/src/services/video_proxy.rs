@@ -0,0 +1,172 @@
+//! Low-bitrate H.264 proxies of large videos for smooth canvas playback.
+//!
+//! Proxies are made with an external ffmpeg (`SYNNIA_FFMPEG` or `ffmpeg` on
+//! the PATH), stored next to the original as `proxy_<stem>.mp4` and recorded
+//! in the asset's `valueMeta.proxy`. The file server serves them for
+//! `?tier=medium` and `?tier=preview`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{imaging, io_sqlite};
+
+pub const JOB_KIND: &str = "video-proxy";
+
+/// Videos smaller than this play fine without a proxy
+pub const DEFAULT_MIN_BYTES: u64 = 20 * 1024 * 1024;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "m4v", "webm", "mkv", "avi"];
+
+/// Parameters of one proxy task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTask {
+    pub asset_id: String,
+    /// Video file relative to the project root
+    pub src: String,
+}
+
+/// The ffmpeg binary to use, if one is installed.
+pub fn find_ffmpeg() -> Option<PathBuf> {
+    let candidate = std::env::var_os("SYNNIA_FFMPEG")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("ffmpeg"));
+    let works = Command::new(&candidate)
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    works.then_some(candidate)
+}
+
+/// Video assets of at least `min_bytes` without a proxy (all of them with
+/// `force`). `asset_ids` limits the search.
+pub fn pending_tasks(
+    conn: &Connection,
+    project_root: &Path,
+    asset_ids: Option<&[String]>,
+    min_bytes: u64,
+    force: bool,
+) -> Result<Vec<ProxyTask>, AppError> {
+    let mut tasks: Vec<ProxyTask> = io_sqlite::load_assets(conn)?
+        .into_values()
+        .filter(|asset| asset_ids.map_or(true, |ids| ids.contains(&asset.id)))
+        .filter_map(|asset| {
+            let src = asset.value.get("src").or(Some(&asset.value)).and_then(|v| v.as_str())?;
+            let path = imaging::resolve_image_source(project_root, src)?;
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            if !VIDEO_EXTENSIONS.contains(&ext.as_str()) || path.metadata().ok()?.len() < min_bytes {
+                return None;
+            }
+            if !force && proxy_path(&path)?.exists() {
+                return None;
+            }
+            let src = path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/");
+            Some(ProxyTask { asset_id: asset.id, src })
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.src.cmp(&b.src));
+    Ok(tasks)
+}
+
+/// Transcode one proxy; returns its relative path. The error is the
+/// message shown for the asset.
+pub fn generate_proxy(ffmpeg: &Path, project_root: &Path, task: &ProxyTask) -> Result<String, String> {
+    let source = project_root.join(&task.src);
+    let target = proxy_path(&source).ok_or_else(|| format!("Not a video file: {}", task.src))?;
+    // Write under a temporary name so a failed run never leaves a broken proxy
+    let partial = target.with_extension("part.mp4");
+    
+    let output = Command::new(ffmpeg)
+        .args(["-y", "-v", "error", "-i"])
+        .arg(&source)
+        .args([
+            "-vf", "scale='min(1280,iw)':-2",
+            "-c:v", "libx264", "-preset", "veryfast", "-crf", "28",
+            "-maxrate", "2M", "-bufsize", "4M", "-pix_fmt", "yuv420p",
+            "-c:a", "aac", "-b:a", "96k",
+            "-movflags", "+faststart",
+        ])
+        .arg(&partial)
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&partial);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffmpeg failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+    std::fs::rename(&partial, &target).map_err(|e| format!("Failed to save proxy: {}", e))?;
+    
+    let relative = target.strip_prefix(project_root).map_err(|e| e.to_string())?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Point the assets' `valueMeta.proxy` at their proxies. Returns the number
+/// of assets updated.
+pub fn record_proxies(conn: &Connection, proxies: &[(String, String)]) -> Result<usize, AppError> {
+    let mut updated = 0;
+    for (asset_id, proxy) in proxies {
+        updated += conn.execute(
+            "UPDATE assets SET value_meta_json = json_set(COALESCE(value_meta_json, '{}'), '$.proxy', ?1)
+             WHERE id = ?2",
+            params![proxy, asset_id],
+        ).map_err(|e| AppError::Io(format!("Failed to update asset proxy: {}", e)))?;
+    }
+    Ok(updated)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn proxy_path(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    Some(path.with_file_name(format!("proxy_{}.mp4", stem)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Asset, AssetSysMetadata, ValueType};
+    use crate::services::database;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn video(id: &str, file: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            value_type: ValueType::Record,
+            value: json!({ "src": format!("assets/{}", file) }),
+            value_meta: None,
+            config: None,
+            sys: AssetSysMetadata { name: id.to_string(), created_at: 0, updated_at: 0, source: "user".to_string() },
+        }
+    }
+
+    #[test]
+    fn test_pending_proxies() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let mut project = io_sqlite::init_project_sqlite(root, "Edit").unwrap();
+        for (id, file, size) in [("big", "big.mp4", 2048), ("small", "small.mov", 10), ("done", "done.mp4", 4096), ("pic", "pic.png", 4096)] {
+            std::fs::write(root.join("assets").join(file), vec![0u8; size]).unwrap();
+            project.assets.insert(id.to_string(), video(id, file));
+        }
+        std::fs::write(root.join("assets/proxy_done.mp4"), b"proxy").unwrap();
+        io_sqlite::save_project_sqlite(root, &project).unwrap();
+        
+        let conn = database::open_db(&io_sqlite::get_db_path(root)).unwrap();
+        let ids = |tasks: Vec<ProxyTask>| tasks.into_iter().map(|t| t.asset_id).collect::<Vec<_>>();
+        assert_eq!(ids(pending_tasks(&conn, root, None, 1024, false).unwrap()), vec!["big"]);
+        assert_eq!(ids(pending_tasks(&conn, root, None, 1024, true).unwrap()), vec!["big", "done"]);
+        assert!(pending_tasks(&conn, root, Some(&["small".to_string()]), 1024, false).unwrap().is_empty());
+        
+        assert_eq!(record_proxies(&conn, &[("big".to_string(), "assets/proxy_big.mp4".to_string())]).unwrap(), 1);
+        let meta = io_sqlite::get_asset(&conn, "big").unwrap().unwrap().value_meta.unwrap();
+        assert_eq!(meta["proxy"], "assets/proxy_big.mp4");
+    }
+}
@@ -0,0 +1,97 @@
+//! Viewport bookmarks: named camera positions for jumping between regions of
+//! a large canvas. Names are unique per project; saving an existing name
+//! moves that bookmark.
+
+use rusqlite::{params, Connection};
+use crate::error::AppError;
+use crate::models::{Viewport, ViewportBookmark};
+
+/// Save `viewport` under `name`, replacing the position of a bookmark with
+/// the same name.
+pub fn save(conn: &Connection, name: &str, viewport: &Viewport) -> Result<ViewportBookmark, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Unknown("Bookmark name is empty".to_string()));
+    }
+    
+    conn.execute(
+        "INSERT INTO viewport_bookmarks (id, name, x, y, zoom, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(name) DO UPDATE SET x = excluded.x, y = excluded.y, zoom = excluded.zoom",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            name,
+            viewport.x,
+            viewport.y,
+            viewport.zoom,
+            chrono::Utc::now().timestamp_millis(),
+        ],
+    ).map_err(|e| AppError::Io(format!("Failed to save bookmark: {}", e)))?;
+    
+    conn.query_row(
+        "SELECT id, name, x, y, zoom, created_at FROM viewport_bookmarks WHERE name = ?1",
+        params![name],
+        row_to_bookmark,
+    ).map_err(|e| AppError::Io(format!("Failed to load bookmark: {}", e)))
+}
+
+/// Bookmarks in creation order.
+pub fn list(conn: &Connection) -> Result<Vec<ViewportBookmark>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, x, y, zoom, created_at FROM viewport_bookmarks ORDER BY created_at, name"
+    ).map_err(|e| AppError::Io(format!("Failed to prepare query: {}", e)))?;
+    
+    let rows = stmt.query_map([], row_to_bookmark)
+        .map_err(|e| AppError::Io(format!("Failed to query bookmarks: {}", e)))?;
+    
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Io(format!("Failed to load bookmarks: {}", e)))
+}
+
+/// Delete a bookmark. Returns false if it didn't exist.
+pub fn delete(conn: &Connection, bookmark_id: &str) -> Result<bool, AppError> {
+    let deleted = conn.execute("DELETE FROM viewport_bookmarks WHERE id = ?1", params![bookmark_id])
+        .map_err(|e| AppError::Io(format!("Failed to delete bookmark: {}", e)))?;
+    Ok(deleted > 0)
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn row_to_bookmark(row: &rusqlite::Row) -> rusqlite::Result<ViewportBookmark> {
+    Ok(ViewportBookmark {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        x: row.get(2)?,
+        y: row.get(3)?,
+        zoom: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_viewport_bookmarks() {
+        let dir = tempdir().unwrap();
+        let conn = database::init_db(&dir.path().join("test.db")).unwrap();
+        
+        let research = save(&conn, "Research", &Viewport { x: 0.0, y: 0.0, zoom: 1.0 }).unwrap();
+        save(&conn, "Storyboard", &Viewport { x: 5000.0, y: -200.0, zoom: 0.5 }).unwrap();
+        
+        // Same name moves the bookmark instead of adding one
+        let moved = save(&conn, " Research ", &Viewport { x: 100.0, y: 50.0, zoom: 2.0 }).unwrap();
+        assert_eq!(moved.id, research.id);
+        assert_eq!((moved.x, moved.zoom), (100.0, 2.0));
+        assert_eq!(list(&conn).unwrap().len(), 2);
+        
+        assert!(save(&conn, "  ", &Viewport { x: 0.0, y: 0.0, zoom: 1.0 }).is_err());
+        assert!(delete(&conn, &research.id).unwrap());
+        assert!(!delete(&conn, &research.id).unwrap());
+        assert_eq!(list(&conn).unwrap()[0].name, "Storyboard");
+    }
+}
@@ -0,0 +1,298 @@
+//! Watermarks for image exports, so drafts leaving the app are clearly
+//! marked.
+//!
+//! Configured per project in settings (`watermark`): a text label and/or a
+//! logo asset, stamped at a corner or the center with the given opacity.
+//! Text uses a built-in 5×7 pixel font (ASCII letters, digits and common
+//! punctuation; lowercase is drawn as uppercase) so no font files are needed.
+
+use std::path::Path;
+use image::{imageops, imageops::FilterType, DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::services::{database, imaging, io_sqlite};
+
+/// Project settings key holding the watermark config
+pub const SETTINGS_KEY: &str = "watermark";
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+/// Horizontal advance per character, including spacing
+const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatermarkConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Image asset stamped as a logo
+    #[serde(default)]
+    pub logo_asset_id: Option<String>,
+    #[serde(default)]
+    pub position: WatermarkPosition,
+    /// 0..1
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    /// Stamp width as a fraction of the image width
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+/// A watermark ready to apply: config plus the decoded logo
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    pub config: WatermarkConfig,
+    pub logo: Option<DynamicImage>,
+}
+
+impl Watermark {
+    /// Watermark configured for the project, if enabled and not empty.
+    pub fn load(project_root: &Path) -> Result<Option<Self>, AppError> {
+        let conn = database::open_db(&io_sqlite::get_db_path(project_root))
+            .map_err(|e| AppError::Io(format!("Failed to open database: {}", e)))?;
+        
+        let Some(config) = io_sqlite::get_setting(&conn, SETTINGS_KEY)?
+            .and_then(|v| serde_json::from_value::<WatermarkConfig>(v).ok())
+            .filter(|c| c.enabled)
+        else {
+            return Ok(None);
+        };
+        
+        let logo = match &config.logo_asset_id {
+            Some(id) => {
+                let asset = io_sqlite::get_asset(&conn, id)?
+                    .ok_or_else(|| AppError::NotFound(format!("Watermark logo not found: {}", id)))?;
+                let path = asset.value.get("src").and_then(|s| s.as_str())
+                    .and_then(|src| imaging::resolve_image_source(project_root, src))
+                    .ok_or_else(|| AppError::Unknown(format!("Watermark logo is not a project image: {}", id)))?;
+                Some(image::open(&path)
+                    .map_err(|e| AppError::Unknown(format!("Failed to load watermark logo: {}", e)))?)
+            }
+            None => None,
+        };
+        
+        let has_text = config.text.as_deref().is_some_and(|t| !t.trim().is_empty());
+        if !has_text && logo.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { config, logo }))
+    }
+
+    /// Stamp the watermark onto an image.
+    pub fn apply(&self, img: DynamicImage) -> DynamicImage {
+        let mut canvas = img.to_rgba8();
+        let (width, height) = canvas.dimensions();
+        let stamp_width = ((width as f32 * self.config.scale.clamp(0.01, 1.0)) as u32).max(1);
+        let opacity = self.config.opacity.clamp(0.0, 1.0);
+        
+        let Some(stamp) = self.render(stamp_width, opacity) else {
+            return DynamicImage::ImageRgba8(canvas);
+        };
+        
+        let margin = (width.min(height) / 40) as i64;
+        let (sw, sh) = (stamp.width() as i64, stamp.height() as i64);
+        let (w, h) = (width as i64, height as i64);
+        let (x, y) = match self.config.position {
+            WatermarkPosition::TopLeft => (margin, margin),
+            WatermarkPosition::TopRight => (w - sw - margin, margin),
+            WatermarkPosition::BottomLeft => (margin, h - sh - margin),
+            WatermarkPosition::BottomRight => (w - sw - margin, h - sh - margin),
+            WatermarkPosition::Center => ((w - sw) / 2, (h - sh) / 2),
+        };
+        imageops::overlay(&mut canvas, &stamp, x, y);
+        
+        DynamicImage::ImageRgba8(canvas)
+    }
+
+    /// Logo above text, both centered, at most `max_width` wide
+    fn render(&self, max_width: u32, opacity: f32) -> Option<RgbaImage> {
+        let logo = self.logo.as_ref().map(|logo| {
+            let logo_height = (logo.height() as f32 * max_width as f32 / logo.width().max(1) as f32).max(1.0) as u32;
+            let mut logo = logo.resize(max_width, logo_height, FilterType::Lanczos3).to_rgba8();
+            for pixel in logo.pixels_mut() {
+                pixel.0[3] = (pixel.0[3] as f32 * opacity) as u8;
+            }
+            logo
+        });
+        let text = self.config.text.as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| render_text(t.trim(), max_width, opacity));
+        
+        match (logo, text) {
+            (Some(logo), Some(text)) => {
+                let gap = text.height() / 2;
+                let width = logo.width().max(text.width());
+                let mut stamp = RgbaImage::new(width, logo.height() + gap + text.height());
+                imageops::overlay(&mut stamp, &logo, ((width - logo.width()) / 2) as i64, 0);
+                imageops::overlay(&mut stamp, &text, ((width - text.width()) / 2) as i64, (logo.height() + gap) as i64);
+                Some(stamp)
+            }
+            (logo, text) => logo.or(text),
+        }
+    }
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_opacity() -> f32 {
+    0.5
+}
+
+fn default_scale() -> f32 {
+    0.25
+}
+
+/// White text on a dark backdrop, scaled to fit `max_width`
+fn render_text(text: &str, max_width: u32, opacity: f32) -> RgbaImage {
+    let chars: Vec<char> = text.chars().collect();
+    let columns = (chars.len() as u32 * GLYPH_ADVANCE).saturating_sub(1).max(1);
+    let pad = 2;
+    let pixel = (max_width / (columns + pad * 2)).max(1);
+    
+    let mut stamp = RgbaImage::from_pixel(
+        (columns + pad * 2) * pixel,
+        (GLYPH_HEIGHT + pad * 2) * pixel,
+        Rgba([0, 0, 0, (255.0 * opacity * 0.6) as u8]),
+    );
+    let ink = Rgba([255, 255, 255, (255.0 * opacity) as u8]);
+    
+    for (i, c) in chars.iter().enumerate() {
+        let rows = glyph(*c);
+        let left = pad + i as u32 * GLYPH_ADVANCE;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                let (x0, y0) = ((left + col) * pixel, (pad + row as u32) * pixel);
+                for dy in 0..pixel {
+                    for dx in 0..pixel {
+                        stamp.put_pixel(x0 + dx, y0 + dy, ink);
+                    }
+                }
+            }
+        }
+    }
+    stamp
+}
+
+/// 5×7 bitmap rows, most significant of the low 5 bits is the leftmost pixel
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ' ' => [0x00; 7],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '\'' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn watermark(config: serde_json::Value, logo: Option<DynamicImage>) -> Watermark {
+        Watermark {
+            config: serde_json::from_value(config).unwrap(),
+            logo,
+        }
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: WatermarkConfig = serde_json::from_value(json!({ "text": "DRAFT" })).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.position, WatermarkPosition::BottomRight);
+        assert_eq!(config.opacity, 0.5);
+    }
+
+    #[test]
+    fn test_text_stamp_in_corner() {
+        let base = DynamicImage::ImageRgba8(RgbaImage::from_pixel(400, 200, Rgba([0, 0, 255, 255])));
+        let marked = watermark(json!({ "text": "Draft", "opacity": 1.0 }), None).apply(base).to_rgba8();
+        
+        // Top-left untouched, bottom-right stamped with white ink somewhere
+        assert_eq!(marked.get_pixel(10, 10), &Rgba([0, 0, 255, 255]));
+        let inked = (200..400).flat_map(|x| (100..200).map(move |y| (x, y)))
+            .any(|(x, y)| marked.get_pixel(x, y).0[..3] == [255, 255, 255]);
+        assert!(inked);
+    }
+
+    #[test]
+    fn test_logo_stamp_opacity() {
+        let base = DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 100, Rgba([0, 0, 0, 255])));
+        let logo = DynamicImage::ImageRgba8(RgbaImage::from_pixel(10, 10, Rgba([255, 255, 255, 255])));
+        let marked = watermark(json!({ "position": "center", "opacity": 0.5, "scale": 0.5 }), Some(logo))
+            .apply(base)
+            .to_rgba8();
+        
+        let center = marked.get_pixel(50, 50).0[0];
+        assert!((120..=135).contains(&center), "half-opaque white over black, got {}", center);
+        assert_eq!(marked.get_pixel(5, 5).0[0], 0);
+    }
+}
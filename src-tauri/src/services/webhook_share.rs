@@ -0,0 +1,371 @@
+//! Review posts to chat webhooks: selected images plus their captions sent to
+//! a Slack or Discord incoming webhook.
+//!
+//! Discord takes up to ten attachments per message; a selection with more
+//! images than that is composited into contact sheets (in the same order as the
+//! captions) and split across messages as needed. Slack incoming
+//! webhooks only carry text, so Slack posts list the captions and say how
+//! many images stayed behind.
+//!
+//! Images are scaled down and re-encoded as JPEG before sending, which keeps
+//! every message well under the services' upload limits.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops;
+use image::{Rgb, RgbImage};
+use reqwest::Url;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AppError;
+use crate::services::{imaging, io_sqlite};
+use crate::services::net_guard::NetworkPolicy;
+
+pub const JOB_KIND: &str = "webhook-share";
+
+/// Most nodes per share
+pub const MAX_NODES: usize = 200;
+
+/// Attachments Discord accepts per message
+const DISCORD_MAX_FILES: usize = 10;
+
+/// Discord message content limit, and a safe cap for Slack's
+const DISCORD_MAX_TEXT: usize = 2000;
+const SLACK_MAX_TEXT: usize = 3000;
+
+/// Longest edge of a single image once scaled down
+const MAX_EDGE: u32 = 2048;
+
+/// Contact sheet layout: cells per row and per sheet, and their size
+const SHEET_COLUMNS: u32 = 3;
+const SHEET_CELLS: usize = 9;
+const SHEET_CELL: u32 = 640;
+const SHEET_GAP: u32 = 16;
+
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+}
+
+impl WebhookKind {
+    /// The service behind a webhook URL; anything but a Slack or Discord
+    /// webhook is refused.
+    pub fn detect(webhook_url: &str) -> Result<Self, AppError> {
+        let url = Url::parse(webhook_url.trim())
+            .map_err(|_| AppError::InvalidInput("Invalid webhook URL".to_string()))?;
+        if url.scheme() != "https" {
+            return Err(AppError::InvalidInput("Webhook URLs have to use https".to_string()));
+        }
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+        if host == "hooks.slack.com" && path.starts_with("/services/") {
+            return Ok(Self::Slack);
+        }
+        let discord_host = ["discord.com", "discordapp.com"].iter()
+            .any(|d| host == *d || host.strip_suffix(d).is_some_and(|sub| sub.ends_with('.')));
+        if discord_host && path.starts_with("/api/webhooks/") {
+            return Ok(Self::Discord);
+        }
+        Err(AppError::InvalidInput("Only Slack and Discord webhook URLs are supported".to_string()))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Slack => "Slack",
+            Self::Discord => "Discord",
+        }
+    }
+}
+
+/// A selected node: its caption and image file, if it has one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShareItem {
+    pub caption: String,
+    pub image: Option<PathBuf>,
+}
+
+/// What goes out as one webhook message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharePost {
+    pub text: String,
+    /// Each entry is one attachment: a single image, or a contact sheet
+    /// of several
+    pub attachments: Vec<Vec<PathBuf>>,
+}
+
+/// Captions and images of `node_ids`, in the order given. Captions are the
+/// node titles, falling back to the asset names.
+pub fn collect(project_root: &Path, conn: &Connection, node_ids: &[String]) -> Result<Vec<ShareItem>, AppError> {
+    if node_ids.is_empty() {
+        return Err(AppError::InvalidInput("No nodes to share".to_string()));
+    }
+    if node_ids.len() > MAX_NODES {
+        return Err(AppError::InvalidInput(format!("At most {} nodes per share", MAX_NODES)));
+    }
+
+    let mut items = Vec::with_capacity(node_ids.len());
+    for node_id in node_ids {
+        let node = io_sqlite::get_node(conn, node_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Node not found: {}", node_id)))?;
+        let asset = match &node.data.asset_id {
+            Some(asset_id) => io_sqlite::get_asset(conn, asset_id)?,
+            None => None,
+        };
+        let image = asset.as_ref()
+            .and_then(|asset| asset.value.get("src")?.as_str())
+            .and_then(|src| imaging::resolve_image_source(project_root, src))
+            .filter(|path| path.is_file());
+        let caption = Some(node.data.title.trim())
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .or_else(|| asset.map(|a| a.sys.name))
+            .unwrap_or_default();
+        items.push(ShareItem { caption, image });
+    }
+    Ok(items)
+}
+
+/// Split `items` into the messages sent to a `kind` webhook.
+pub fn plan(kind: WebhookKind, items: &[ShareItem], message: Option<&str>) -> Vec<SharePost> {
+    let message = message.map(str::trim).filter(|m| !m.is_empty());
+    let images: Vec<&PathBuf> = items.iter().filter_map(|item| item.image.as_ref()).collect();
+
+    let mut lines: Vec<String> = message.map(String::from).into_iter().collect();
+    lines.extend(items.iter().enumerate()
+        .filter(|(_, item)| !item.caption.is_empty())
+        .map(|(i, item)| format!("{}. {}", i + 1, item.caption)));
+
+    match kind {
+        WebhookKind::Slack => {
+            if !images.is_empty() {
+                let s = if images.len() == 1 { "" } else { "s" };
+                lines.push(format!("_{} image{} not attached: Slack webhooks only carry text_", images.len(), s));
+            }
+            vec![SharePost { text: truncate(&lines.join("\n"), SLACK_MAX_TEXT), attachments: Vec::new() }]
+        }
+        WebhookKind::Discord => {
+            let attachments: Vec<Vec<PathBuf>> = if images.len() <= DISCORD_MAX_FILES {
+                images.iter().map(|path| vec![(*path).clone()]).collect()
+            } else {
+                images.chunks(SHEET_CELLS)
+                    .map(|sheet| sheet.iter().map(|path| (*path).clone()).collect())
+                    .collect()
+            };
+            let mut posts: Vec<SharePost> = attachments.chunks(DISCORD_MAX_FILES)
+                .map(|chunk| SharePost { text: String::new(), attachments: chunk.to_vec() })
+                .collect();
+            if posts.is_empty() {
+                posts.push(SharePost { text: String::new(), attachments: Vec::new() });
+            }
+            posts[0].text = truncate(&lines.join("\n"), DISCORD_MAX_TEXT);
+            posts
+        }
+    }
+}
+
+/// Encode each attachment of `post`: `(file name, JPEG data)`.
+pub fn render(post: &SharePost) -> Result<Vec<(String, Vec<u8>)>, AppError> {
+    post.attachments.iter().enumerate()
+        .map(|(i, images)| {
+            let (name, image) = match images.as_slice() {
+                [path] => {
+                    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+                    (format!("{}.jpg", stem), load(path)?.thumbnail(MAX_EDGE, MAX_EDGE).to_rgb8())
+                }
+                paths => (format!("sheet-{}.jpg", i + 1), contact_sheet(paths)?),
+            };
+            let mut data = Vec::new();
+            JpegEncoder::new_with_quality(Cursor::new(&mut data), JPEG_QUALITY)
+                .encode_image(&image)
+                .map_err(|e| AppError::Io(format!("Failed to encode {}: {}", name, e)))?;
+            Ok((name, data))
+        })
+        .collect()
+}
+
+/// Send one message to the webhook.
+pub async fn send(
+    policy: &NetworkPolicy,
+    webhook_url: &str,
+    kind: WebhookKind,
+    post: &SharePost,
+    files: Vec<(String, Vec<u8>)>,
+) -> Result<(), AppError> {
+    let url = policy.check_url(webhook_url.trim()).await?;
+    let client = policy.client_builder()
+        .timeout(policy.timeouts.request())
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    let request = match kind {
+        WebhookKind::Slack => client.post(url).json(&json!({ "text": post.text })),
+        WebhookKind::Discord => {
+            let payload = json!({
+                "content": post.text,
+                "attachments": files.iter().enumerate()
+                    .map(|(i, (name, _))| json!({ "id": i, "filename": name }))
+                    .collect::<Vec<_>>(),
+            });
+            let (content_type, body) = discord_body(&payload, &files);
+            client.post(url).header("Content-Type", content_type).body(body)
+        }
+    };
+
+    let response = request.send().await
+        .map_err(|e| AppError::Network(format!("Could not reach {}: {}", kind.label(), e)))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    // Slack answers in plain text (`invalid_token`), Discord with `{ message }`
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body).ok()
+        .and_then(|v| v.get("message")?.as_str().map(String::from))
+        .unwrap_or(body);
+    Err(AppError::Network(format!("{} refused the post ({}): {}", kind.label(), status.as_u16(), message.trim())))
+}
+
+// ============================================
+// Private helper functions
+// ============================================
+
+fn load(path: &Path) -> Result<image::DynamicImage, AppError> {
+    image::open(path).map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+/// Images laid out in a grid on white, each fitted into a square cell
+fn contact_sheet(paths: &[PathBuf]) -> Result<RgbImage, AppError> {
+    let (width, height) = sheet_size(paths.len());
+    let mut sheet = RgbImage::from_pixel(width, height, Rgb([255, 255, 255]));
+    for (i, path) in paths.iter().enumerate() {
+        let cell = load(path)?.thumbnail(SHEET_CELL, SHEET_CELL).to_rgb8();
+        let (col, row) = (i as u32 % SHEET_COLUMNS, i as u32 / SHEET_COLUMNS);
+        let x = SHEET_GAP + col * (SHEET_CELL + SHEET_GAP) + (SHEET_CELL - cell.width()) / 2;
+        let y = SHEET_GAP + row * (SHEET_CELL + SHEET_GAP) + (SHEET_CELL - cell.height()) / 2;
+        imageops::overlay(&mut sheet, &cell, x as i64, y as i64);
+    }
+    Ok(sheet)
+}
+
+fn sheet_size(count: usize) -> (u32, u32) {
+    let count = count.max(1) as u32;
+    let columns = count.min(SHEET_COLUMNS);
+    let rows = count.div_ceil(SHEET_COLUMNS);
+    (
+        SHEET_GAP + columns * (SHEET_CELL + SHEET_GAP),
+        SHEET_GAP + rows * (SHEET_CELL + SHEET_GAP),
+    )
+}
+
+/// Multipart body for Discord: the message as `payload_json`, then the
+/// files as `files[n]`
+fn discord_body(payload: &Value, files: &[(String, Vec<u8>)]) -> (String, Vec<u8>) {
+    let boundary = format!("synnia-{}", uuid::Uuid::new_v4().simple());
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+        boundary, payload,
+    ).into_bytes();
+    for (i, (name, data)) in files.iter().enumerate() {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"files[{}]\"; filename=\"{}\"\r\nContent-Type: image/jpeg\r\n\r\n",
+            boundary, i, name.replace(['"', '\r', '\n'], "_"),
+        ).as_bytes());
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut text: String = text.chars().take(max - 1).collect();
+    text.push('…');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(images: usize, captions: usize) -> Vec<ShareItem> {
+        (0..images.max(captions))
+            .map(|i| ShareItem {
+                caption: if i < captions { format!("Shot {}", i + 1) } else { String::new() },
+                image: (i < images).then(|| PathBuf::from(format!("/tmp/{}.png", i))),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(WebhookKind::detect("https://hooks.slack.com/services/T0/B0/xyz").unwrap(), WebhookKind::Slack);
+        assert_eq!(WebhookKind::detect("https://discord.com/api/webhooks/1/abc").unwrap(), WebhookKind::Discord);
+        assert_eq!(WebhookKind::detect("https://ptb.discord.com/api/webhooks/1/abc").unwrap(), WebhookKind::Discord);
+        assert!(WebhookKind::detect("http://hooks.slack.com/services/T0/B0/xyz").is_err());
+        assert!(WebhookKind::detect("https://evildiscord.com/api/webhooks/1/abc").is_err());
+        assert!(WebhookKind::detect("https://discord.com/channels/1").is_err());
+        assert!(WebhookKind::detect("https://example.com/hook").is_err());
+    }
+
+    #[test]
+    fn test_plan_discord() {
+        // Up to ten images go as they are, in one message
+        let posts = plan(WebhookKind::Discord, &items(4, 4), Some("Round 2"));
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].attachments.len(), 4);
+        assert!(posts[0].attachments.iter().all(|a| a.len() == 1));
+        assert_eq!(posts[0].text, "Round 2\n1. Shot 1\n2. Shot 2\n3. Shot 3\n4. Shot 4");
+
+        // More are composited, nine to a sheet, ten sheets to a message
+        let posts = plan(WebhookKind::Discord, &items(95, 0), None);
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].attachments.len(), 10);
+        assert_eq!(posts[1].attachments.len(), 1);
+        assert_eq!(posts[1].attachments[0].len(), 5);
+        assert!(posts[1].text.is_empty());
+    }
+
+    #[test]
+    fn test_plan_slack() {
+        let posts = plan(WebhookKind::Slack, &items(2, 3), None);
+        assert_eq!(posts.len(), 1);
+        assert!(posts[0].attachments.is_empty());
+        assert!(posts[0].text.starts_with("1. Shot 1\n"));
+        assert!(posts[0].text.ends_with("_2 images not attached: Slack webhooks only carry text_"));
+    }
+
+    #[test]
+    fn test_sheet_size() {
+        let cell = SHEET_CELL + SHEET_GAP;
+        assert_eq!(sheet_size(1), (SHEET_GAP + cell, SHEET_GAP + cell));
+        assert_eq!(sheet_size(4), (SHEET_GAP + 3 * cell, SHEET_GAP + 2 * cell));
+        assert_eq!(sheet_size(9), (SHEET_GAP + 3 * cell, SHEET_GAP + 3 * cell));
+    }
+
+    #[test]
+    fn test_render_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..2).map(|i| {
+            let path = dir.path().join(format!("{}.png", i));
+            RgbImage::from_pixel(40, 20, Rgb([200, 0, 0])).save(&path).unwrap();
+            path
+        }).collect();
+
+        let post = SharePost { text: String::new(), attachments: vec![vec![paths[0].clone()], paths.clone()] };
+        let files = render(&post).unwrap();
+        assert_eq!(files[0].0, "0.jpg");
+        assert_eq!(files[1].0, "sheet-2.jpg");
+        let sheet = image::load_from_memory(&files[1].1).unwrap();
+        assert_eq!((sheet.width(), sheet.height()), sheet_size(2));
+    }
+}
@@ -1,8 +1,31 @@
 use std::sync::{Arc, Mutex};
+use crate::services::blocking::BlockingPool;
+use crate::services::cancellation::CancellationRegistry;
+use crate::services::connectors::oauth::PendingLogins;
+use crate::services::download::DownloadManager;
+use crate::services::job_queue::JobQueue;
+use crate::services::mobile_capture::MobileCapture;
+use crate::services::permissions::PermissionGate;
+use crate::services::time_tracking::SessionTracker;
 
 // Simple state to hold the connection. 
 pub struct AppState {
     // Shared with Actix Server
     pub current_project_path: Arc<Mutex<Option<String>>>,
     pub server_port: u16,
+    // Shared with Actix: bearer token for POST /clip
+    pub clip_token: Arc<Mutex<String>>,
+    pub downloads: Arc<DownloadManager>,
+    pub jobs: Arc<JobQueue>,
+    pub sessions: Arc<SessionTracker>,
+    // Shared with Actix: open prompts of agent/automation permission checks
+    pub permissions: Arc<PermissionGate>,
+    // Shared with Actix: connector sign-ins awaiting their OAuth redirect
+    pub logins: Arc<PendingLogins>,
+    // In-flight agent runs, proxy requests and exports, cancellable by ID
+    pub operations: Arc<CancellationRegistry>,
+    // File and DB work of async commands, limited in concurrency
+    pub blocking: Arc<BlockingPool>,
+    // LAN upload page for phones, started on pairing
+    pub mobile_capture: Arc<MobileCapture>,
 }
\ No newline at end of file
@@ -50,10 +50,10 @@ export const AssetHistoryPanel = ({ assetId, nodeId }: AssetHistoryPanelProps) =
 
         setRestoring(entry.id);
         try {
-            const restoredContent = await apiClient.restoreAssetVersion(assetId, entry.id);
+            const { value } = await apiClient.restoreAssetVersion(assetId, entry.id);
 
             // Update local store
-            graphEngine.assets.update(assetId, restoredContent);
+            graphEngine.assets.update(assetId, value);
 
             toast.success('Version restored successfully');
 
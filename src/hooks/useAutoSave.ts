@@ -12,6 +12,7 @@ export function useAutoSave() {
   const assets = useWorkflowStore((state) => state.assets);
   
   const projectMeta = useWorkflowStore((state) => state.projectMeta);
+  const canvasId = useWorkflowStore((state) => state.canvasId);
   const viewport = useWorkflowStore((state) => state.viewport);
   
   const timeoutRef = useRef<NodeJS.Timeout | null>(null);
@@ -23,7 +24,7 @@ export function useAutoSave() {
     }
 
     timeoutRef.current = setTimeout(async () => {
-      if (projectMeta) {
+      if (projectMeta && canvasId) {
           // --- Real Project Auto-Save (Shadow File) ---
           // This writes to synnia.json.autosave, preserving the main file.
           const project: SynniaProject = {
@@ -35,7 +36,9 @@ export function useAutoSave() {
                   edges: edges as any 
               },
               assets,
-              settings: {}
+              settings: {},
+              // The canvas these nodes came from, even if another was opened since
+              canvasId
           };
           
           try {
@@ -53,5 +56,5 @@ export function useAutoSave() {
     return () => {
       if (timeoutRef.current) clearTimeout(timeoutRef.current);
     };
-  }, [nodes, edges, assets, projectMeta, viewport]);
+  }, [nodes, edges, assets, projectMeta, canvasId, viewport]);
 }
\ No newline at end of file
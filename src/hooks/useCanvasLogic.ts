@@ -96,7 +96,7 @@ export function useCanvasLogic() {
 
         graphEngine.mutator.addNode(NodeType.IMAGE, targetPos, {
           content: { src: result.relativePath, width: result.width, height: result.height },
-          assetName: filePath.split(/[/\\]/).pop(),
+          assetName: result.originalName ?? filePath.split(/[/\\]/).pop(),
           assetConfig: {
             meta: {
               width: result.width,
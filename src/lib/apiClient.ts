@@ -5,8 +5,10 @@
  * Includes mock implementations for browser-only development.
  */
 
-import { SynniaProject } from '@/bindings';
+import { SynniaProject, AppEvent, createCommands } from '@/bindings';
+import type { DeleteProjectReport } from '@/bindings/DeleteProjectReport';
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 // ============================================
 // Types
@@ -21,11 +23,31 @@ export interface AssetHistoryEntry {
     createdAt: number;
 }
 
+/** Result of restoring an asset history version */
+export interface RestoreResult {
+    value: unknown;
+    /** Hash of the replaced content, kept in history */
+    previousHash: string;
+    restoredHash: string;
+}
+
 /** Recent project entry */
 export interface RecentProject {
     name: string;
     path: string;
     last_opened: string;
+    project_id?: string;
+    /** Other paths the same project was opened through */
+    aliases?: string[];
+    /** Another entry with the same project ID that may be a copy */
+    ambiguous_with?: string;
+}
+
+/** Frame count, loop duration and looping preview of an animated GIF/WebP */
+export interface ImageAnimation {
+    frameCount: number;
+    durationMs: number;
+    preview: string | null;
 }
 
 /** Result from saving an image file */
@@ -34,6 +56,9 @@ export interface SaveImageResult {
     thumbnailPath: string | null;
     width: number;
     height: number;
+    animation: ImageAnimation | null;
+    /** Name of the source file as the user had it */
+    originalName: string | null;
 }
 
 /** Media asset info for asset library */
@@ -45,6 +70,7 @@ export interface MediaAssetInfo {
     thumbnailPath: string | null;
     width: number | null;
     height: number | null;
+    animation: ImageAnimation | null;
     createdAt: number;
     updatedAt: number;
 }
@@ -53,6 +79,7 @@ export interface MediaAssetInfo {
 export interface BatchImportResult {
     sourcePath: string;
     result: SaveImageResult | null;
+    contentHash: string | null;
     error: string | null;
 }
 
@@ -102,8 +129,8 @@ export const apiClient = {
     createProject: (name: string, parentPath: string): Promise<string> =>
         apiClient.invoke('create_project', { name, parentPath }),
 
-    deleteProject: (path: string): Promise<void> =>
-        apiClient.invoke('delete_project', { path }),
+    deleteProject: (path: string, dryRun?: boolean): Promise<DeleteProjectReport> =>
+        apiClient.invoke('delete_project', { path, dryRun }),
 
     renameProject: (oldPath: string, newName: string): Promise<string> =>
         apiClient.invoke('rename_project', { oldPath, newName }),
@@ -136,10 +163,10 @@ export const apiClient = {
         apiClient.invoke('get_history_content', { historyId }),
 
     /**
-     * Restore an asset to a previous version.
-     * @returns The restored content as JSON value
+     * Restore an asset to a previous version. The current content is
+     * saved to history first.
      */
-    restoreAssetVersion: (assetId: string, historyId: number): Promise<unknown> =>
+    restoreAssetVersion: (assetId: string, historyId: number): Promise<RestoreResult> =>
         apiClient.invoke('restore_asset_version', { assetId, historyId }),
 
     /**
@@ -153,8 +180,8 @@ export const apiClient = {
     // ========================================
 
     /** Result from saving an image file */
-    importFile: (filePath: string): Promise<SaveImageResult> =>
-        apiClient.invoke('import_file', { filePath }),
+    importFile: (filePath: string, link?: boolean): Promise<SaveImageResult> =>
+        apiClient.invoke('import_file', { filePath, link }),
 
     /**
      * Save a processed image from base64 data.
@@ -177,11 +204,12 @@ export const apiClient = {
         apiClient.invoke('download_and_save_image', { url, filename }),
 
     /**
-     * Batch import multiple image files from file system.
+     * Batch import multiple image files from file system, in parallel.
+     * Progress arrives as `import:progress` events.
      * Returns results for each file, including errors.
      */
-    batchImportImages: (filePaths: string[]): Promise<BatchImportResult[]> =>
-        apiClient.invoke('batch_import_images', { filePaths }),
+    batchImportImages: (filePaths: string[], concurrency?: number): Promise<BatchImportResult[]> =>
+        apiClient.invoke('batch_import_images', { filePaths, concurrency }),
 
     // ========================================
     // Utility Commands
@@ -194,6 +222,32 @@ export const apiClient = {
         apiClient.invoke('open_in_browser', { url }),
 };
 
+/**
+ * Generated invokers for every backend command, typed from the Rust signatures.
+ * Goes through `apiClient.invoke`, so errors and mock mode behave the same.
+ */
+export const commands = createCommands(apiClient.invoke);
+
+/** Name of a backend event */
+export type AppEventName = AppEvent['event'];
+
+/** Payload carried by the backend event `name` */
+export type AppEventPayload<E extends AppEventName> = Extract<AppEvent, { event: E }>['payload'];
+
+/**
+ * Subscribe to a backend event with its payload typed from `AppEvent`.
+ * Resolves to the unsubscribe function; in mock mode nothing is ever emitted.
+ */
+export async function onEvent<E extends AppEventName>(
+    name: E,
+    handler: (payload: AppEventPayload<E>) => void,
+): Promise<UnlistenFn> {
+    if (!isTauri) {
+        return () => {};
+    }
+    return listen<AppEventPayload<E>>(name, (event) => handler(event.payload));
+}
+
 // ============================================
 // Helpers
 // ============================================
@@ -118,9 +118,9 @@ function CanvasFlow() {
   } = useCanvasLogic();
 
   const handleSave = async () => {
-    const { nodes, edges, assets, projectMeta, viewport } = useWorkflowStore.getState();
+    const { nodes, edges, assets, projectMeta, canvasId, viewport } = useWorkflowStore.getState();
 
-    if (!projectMeta) {
+    if (!projectMeta || !canvasId) {
       toast.warning("No project open. Use File > New Project first.");
       return;
     }
@@ -132,7 +132,8 @@ function CanvasFlow() {
         viewport,
         graph: { nodes: nodes as any, edges: edges as any },
         assets,
-        settings: {}
+        settings: {},
+        canvasId
       };
       await apiClient.invoke('save_project', { project });
       toast.success("Project saved");
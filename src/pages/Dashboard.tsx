@@ -5,7 +5,7 @@ import { Card, CardContent, CardFooter, CardTitle } from "@/components/ui/card";
 import { Dialog, DialogContent, DialogHeader, DialogTitle, DialogDescription, DialogFooter } from "@/components/ui/dialog";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
-import { Plus, FolderOpen, Clock, ChevronRight, Trash2, Github, Pencil } from "lucide-react";
+import { Plus, FolderOpen, Clock, ChevronRight, Trash2, Github, Pencil, AlertTriangle } from "lucide-react";
 import { open } from '@tauri-apps/plugin-dialog';
 import { SynniaIcon } from "@/components/SynniaIcon";
 import { SynniaSticker } from "@/components/SynniaSticker";
@@ -17,6 +17,7 @@ interface RecentProject {
     name: string;
     path: string;
     last_opened: string;
+    ambiguous_with?: string;
 }
 
 function ProjectCard({ project, onClick, onDelete, onRename }: { project: RecentProject, onClick: () => void, onDelete: () => void, onRename: () => void }) {
@@ -85,6 +86,12 @@ function ProjectCard({ project, onClick, onDelete, onRename }: { project: Recent
             <CardContent className="p-5">
                 <CardTitle className="text-lg mb-1 truncate">{project.name}</CardTitle>
                 <p className="text-xs text-muted-foreground truncate font-mono">{project.path}</p>
+                {project.ambiguous_with && (
+                    <p className="text-xs text-amber-500 truncate flex items-center gap-1 mt-1" title={project.ambiguous_with}>
+                        <AlertTriangle className="w-3 h-3 shrink-0" />
+                        Same project also at {project.ambiguous_with}
+                    </p>
+                )}
             </CardContent>
             <CardFooter className="px-5 py-3 border-t border-border/50 text-xs text-muted-foreground flex justify-between bg-muted/20">
                 <span>{new Date(project.last_opened).toLocaleDateString()}</span>
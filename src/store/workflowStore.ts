@@ -18,6 +18,8 @@ export interface WorkflowState {
   // Project
   projectMeta: ProjectMeta | null;
   projectRoot: string | null;
+  // Canvas the graph was loaded from; saves go back to it
+  canvasId: string | null;
   serverPort: number | null;
   viewport: Viewport;
 
@@ -72,6 +74,7 @@ export const useWorkflowStore = create<WorkflowState & WorkflowActions>()(
         // Initial State
         projectMeta: null,
         projectRoot: null,
+        canvasId: null,
         serverPort: null,
         viewport: { x: 0, y: 0, zoom: 1 },
         nodes: [],
@@ -93,6 +96,7 @@ export const useWorkflowStore = create<WorkflowState & WorkflowActions>()(
             edges: project.graph.edges as unknown as SynniaEdge[],
             assets: project.assets as unknown as Record<string, Asset>,
             projectMeta: project.meta,
+            canvasId: project.canvasId,
             viewport: project.viewport,
           });
         },
@@ -104,6 +108,7 @@ export const useWorkflowStore = create<WorkflowState & WorkflowActions>()(
             edges,
             assets,
             projectMeta: null,
+            canvasId: null,
             viewport: { x: 0, y: 0, zoom: 1 }
           });
         },